@@ -619,7 +619,7 @@ class SolanaBlockchainService {
             description || '',
             instructions || '',
             model || 'gpt-4',
-            category || 'general'
+            [(category || 'general').toLowerCase()]
           )
           .accounts({
             factory: this.factoryPda,
@@ -767,12 +767,14 @@ class SolanaBlockchainService {
         .accounts({
           agent: agentPda,
           mint: mintPda,
-          buyerTokenAccount,
+          recipientTokenAccount: buyerTokenAccount,
           buyer,
+          recipient: buyer,
           creator: agentData.creator,
           platformTreasury: this.platformTreasury,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
+          factory: this.factoryPda,
         })
         .preInstructions(instructions)
         .rpc();
@@ -847,6 +849,7 @@ class SolanaBlockchainService {
           platformTreasury: this.platformTreasury,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
+          factory: this.factoryPda,
         })
         .rpc();
 
@@ -889,7 +892,8 @@ class SolanaBlockchainService {
         description: account.description,
         instructions: account.instructions,
         model: account.model,
-        category: account.category,
+        category: account.tags[0] || 'general',
+        tags: account.tags,
         createdAt: account.createdAt.toString(),
         isGraduated: account.isGraduated,
         bondingCurve: {
@@ -930,7 +934,8 @@ class SolanaBlockchainService {
         description: account.description,
         instructions: account.instructions,
         model: account.model,
-        category: account.category,
+        category: account.tags[0] || 'general',
+        tags: account.tags,
         createdAt: account.createdAt.toString(),
         isGraduated: account.isGraduated,
         bondingCurve: {
@@ -82,7 +82,7 @@ async function testBondingCurve() {
     console.log('🤖 Agent PDA:', agentPda.toBase58());
     console.log('🪙 Mint PDA:', mintPda.toBase58());
 
-    // Create agent (6 parameters: name, symbol, description, instructions, model, category)
+    // Create agent (6 parameters: name, symbol, description, instructions, model, tags)
     const createTx = await program.methods
       .createAgent(
         'Bonding Test',
@@ -90,7 +90,7 @@ async function testBondingCurve() {
         'Testing bonding curve functionality',
         'You are a helpful AI agent for testing bonding curves',
         'gpt-4',
-        'test'
+        ['test']
       )
       .accounts({
         factory: factoryPda,
@@ -152,8 +152,9 @@ async function testBondingCurve() {
       .accounts({
         agent: agentPda,
         mint: mintPda,
-        buyerTokenAccount: buyerTokenAccount,
+        recipientTokenAccount: buyerTokenAccount,
         buyer: walletKeypair.publicKey,
+        recipient: walletKeypair.publicKey,
         creator: agentData.creator,
         platformTreasury: PLATFORM_TREASURY,
         tokenProgram: TOKEN_PROGRAM_ID,
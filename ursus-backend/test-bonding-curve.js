@@ -176,8 +176,9 @@ async function main() {
       .accounts({
         agent: agentPda,
         mint: mintPda,
-        buyerTokenAccount: buyerTokenAccount,
+        recipientTokenAccount: buyerTokenAccount,
         buyer: walletKeypair.publicKey,
+        recipient: walletKeypair.publicKey,
         creator: agentData.creator,
         platformTreasury: PLATFORM_TREASURY,
         tokenProgram: TOKEN_PROGRAM_ID,
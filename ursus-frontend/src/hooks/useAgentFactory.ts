@@ -3,7 +3,7 @@ import { useWallet } from '@solana/wallet-adapter-react';
 import { useConnection } from '@solana/wallet-adapter-react';
 import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
 import { Program, AnchorProvider, web3, BN } from '@coral-xyz/anchor';
-import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
 import { apiService } from '../services/api';
 import idl from '../idl/agent_factory.json';
 
@@ -21,6 +21,10 @@ export interface AgentCreationParams {
 // Solana Testnet configuration - X402 Enabled Program
 const PROGRAM_ID = new PublicKey('4m6mpe2jdRiM24ui1Z3AGbCheu1DfQEjmEGtaGKD2ftU');
 const PLATFORM_TREASURY = new PublicKey('Hf37zaq9y5okWMxU3sW8Djhv3gd6iTy7mrSMfJcYxwQS');
+// Metaplex token metadata program, which owns the ownership NFT's metadata
+const METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+// Signs the program's event self-CPIs (emit_cpi!)
+const [EVENT_AUTHORITY] = PublicKey.findProgramAddressSync([Buffer.from('__event_authority')], PROGRAM_ID);
 
 /**
  * Solana Agent Factory Hook
@@ -116,6 +120,21 @@ export const useAgentFactory = () => {
         [Buffer.from('mint'), agentPda.toBuffer()],
         PROGRAM_ID
       );
+      const [mintMetadataPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), METADATA_PROGRAM_ID.toBuffer(), mintPda.toBuffer()],
+        METADATA_PROGRAM_ID
+      );
+
+      // Ownership NFT minted to the creator; it moves with the creator role
+      const [ownershipMintPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('ownership_mint'), agentPda.toBuffer()],
+        PROGRAM_ID
+      );
+      const creatorOwnershipAccount = getAssociatedTokenAddressSync(ownershipMintPda, publicKey);
+      const [ownershipMetadataPda] = await PublicKey.findProgramAddress(
+        [Buffer.from('metadata'), METADATA_PROGRAM_ID.toBuffer(), ownershipMintPda.toBuffer()],
+        METADATA_PROGRAM_ID
+      );
 
       console.log('📍 Agent PDA:', agentPda.toString());
       console.log('🪙 Mint PDA:', mintPda.toString());
@@ -130,17 +149,23 @@ export const useAgentFactory = () => {
           params.description || '',
           params.instructions || '',
           params.model || 'gpt-4',
-          params.category || 'general'
+          [(params.category || 'general').toLowerCase()]
         )
         .accounts({
           factory: factoryPda,
           agent: agentPda,
           mint: mintPda,
+          mintMetadata: mintMetadataPda,
+          ownershipMint: ownershipMintPda,
+          ownershipMetadata: ownershipMetadataPda,
+          creatorOwnershipAccount,
           creator: publicKey,
           platformTreasury: PLATFORM_TREASURY,
           tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: SYSVAR_RENT_PUBKEY,
+          metadataProgram: METADATA_PROGRAM_ID,
         })
         .transaction();
 
@@ -294,19 +319,29 @@ export const useAgentFactory = () => {
       const agentAccount = await program.account.agent.fetch(agentPda);
       const creatorPubkey = agentAccount.creator as PublicKey;
 
+      // First-buy marker, created by the program on the wallet's first buy
+      const [buyerRecordPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from('buyer_record'), agentPda.toBuffer(), publicKey.toBuffer()],
+        PROGRAM_ID
+      );
+
       // Build transaction
       const tx = await program.methods
         .buyTokens(solAmountLamports, minTokensOut)
         .accounts({
           agent: agentPda,
           mint: mintPda,
-          buyerTokenAccount: buyerTokenAccount,
+          recipientTokenAccount: buyerTokenAccount,
           buyer: publicKey,
+          recipient: publicKey,
+          buyerRecord: buyerRecordPda,
           creator: creatorPubkey,
           platformTreasury: PLATFORM_TREASURY,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: new PublicKey('ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL'),
           systemProgram: SystemProgram.programId,
+          eventAuthority: EVENT_AUTHORITY,
+          program: PROGRAM_ID,
         })
         .transaction();
 
@@ -440,6 +475,8 @@ export const useAgentFactory = () => {
           platformTreasury: PLATFORM_TREASURY,
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
+          eventAuthority: EVENT_AUTHORITY,
+          program: PROGRAM_ID,
         })
         .transaction();
 
@@ -178,7 +178,30 @@ class ErrorHandlingService {
     return retryablePatterns.some(pattern => pattern.test(error.message));
   }
 
-  private generateUserMessage(_error: Error, category: ErrorCategory): string {
+  /**
+   * Program errors that carry the compared values (actual vs expected),
+   * as exposed by `AnchorError.error.comparedValues`
+   */
+  private getProgramFailure(error: any): { code: string; actual: string; expected: string } | null {
+    const code = error?.error?.errorCode?.code;
+    const values = error?.error?.comparedValues;
+    if (typeof code !== 'string' || !Array.isArray(values) || values.length !== 2) {
+      return null;
+    }
+    return { code, actual: values[0].toString(), expected: values[1].toString() };
+  }
+
+  private generateUserMessage(error: Error, category: ErrorCategory): string {
+    const failure = this.getProgramFailure(error);
+    switch (failure?.code) {
+      case 'SlippageExceeded':
+        return `Price moved past your slippage tolerance: you would receive ${failure.actual}, below your minimum of ${failure.expected}.`;
+      case 'NonceMismatch':
+        return `Payment nonce ${failure.actual} is out of date (expected ${failure.expected}).`;
+      case 'PaymentTooLow':
+        return `Payment of ${failure.actual} is below this service's minimum of ${failure.expected}.`;
+    }
+
     switch (category) {
       case ErrorCategory.NETWORK:
         return 'Network connection issue. Please check your internet connection and try again.';
@@ -199,9 +222,18 @@ class ErrorHandlingService {
     }
   }
 
-  private generateSuggestedActions(_error: Error, category: ErrorCategory): string[] {
+  private generateSuggestedActions(error: Error, category: ErrorCategory): string[] {
     const commonActions = ['Refresh the page', 'Try again in a few moments'];
 
+    switch (this.getProgramFailure(error)?.code) {
+      case 'SlippageExceeded':
+        return ['Increase your slippage tolerance', 'Refresh the quote and try again'];
+      case 'NonceMismatch':
+        return ['Refresh the agent state and retry the payment'];
+      case 'PaymentTooLow':
+        return ['Pay at least the minimum amount', 'Check the service pricing'];
+    }
+
     switch (category) {
       case ErrorCategory.NETWORK:
         return [
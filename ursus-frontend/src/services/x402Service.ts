@@ -49,6 +49,20 @@ export class X402Service {
     );
   }
 
+  // Holder's account for the agent's ownership NFT, which the x402
+  // configuration instructions check
+  async getOwnershipAccount(agentAddress: PublicKey, holder: PublicKey): Promise<PublicKey> {
+    const [ownershipMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from('ownership_mint'), agentAddress.toBuffer()],
+      this.programId
+    );
+    return getAssociatedTokenAddress(ownershipMint, holder);
+  }
+
+  getEventAuthorityPDA(): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from('__event_authority')], this.programId)[0];
+  }
+
   async getPaymentRecordPDA(
     agentAddress: PublicKey,
     payerAddress: PublicKey,
@@ -98,6 +112,7 @@ export class X402Service {
     const program = this.getProgram(provider);
     const agentPubkey = new PublicKey(params.agentAddress);
     const [x402ConfigPDA] = await this.getX402ConfigPDA(agentPubkey);
+    const creatorOwnershipAccount = await this.getOwnershipAccount(agentPubkey, provider.wallet.publicKey);
 
     // Check if X402 config already exists
     try {
@@ -116,6 +131,7 @@ export class X402Service {
             agent: agentPubkey,
             x402Config: x402ConfigPDA,
             authority: provider.wallet.publicKey,
+            creatorOwnershipAccount,
           })
           .rpc();
       } else {
@@ -131,6 +147,7 @@ export class X402Service {
             agent: agentPubkey,
             x402Config: x402ConfigPDA,
             authority: provider.wallet.publicKey,
+            creatorOwnershipAccount,
             systemProgram: SystemProgram.programId,
           })
           .rpc();
@@ -148,6 +165,7 @@ export class X402Service {
           agent: agentPubkey,
           x402Config: x402ConfigPDA,
           authority: provider.wallet.publicKey,
+          creatorOwnershipAccount,
           systemProgram: SystemProgram.programId,
         })
         .rpc();
@@ -194,6 +212,8 @@ export class X402Service {
         recipientTokenAccount,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        eventAuthority: this.getEventAuthorityPDA(),
+        program: this.programId,
       })
       .rpc();
   }
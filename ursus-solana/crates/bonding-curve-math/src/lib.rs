@@ -0,0 +1,583 @@
+//! Bonding curve math shared by the on-chain program, the Rust client SDK
+//! and (with the `wasm` feature) the frontend, so quotes never diverge from
+//! what the chain executes.
+//!
+//! All amounts are raw units: lamports for SOL, base units (9 decimals) for
+//! tokens. Prices are lamports per token scaled by [`PRICE_SCALE`].
+
+#![cfg_attr(not(feature = "wasm"), no_std)]
+
+pub mod stepped;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Basis point denominator (100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Platform fee charged on every trade
+pub const PLATFORM_FEE_BPS: u64 = 100;
+
+/// Creator fee charged on every trade
+pub const CREATOR_FEE_BPS: u64 = 100;
+
+/// Fixed-point scale applied to spot prices
+pub const PRICE_SCALE: u64 = 1_000_000_000;
+
+/// SOL (lamports) sells can never take out of the curve reserves, so dust
+/// round trips can't drain them to zero (cf. Uniswap's `MINIMUM_LIQUIDITY`).
+/// Refund-guarantee redemptions still pay out in full.
+pub const MINIMUM_SOL_LIQUIDITY: u64 = 1_000_000;
+
+/// Tokens (base units) buys can never take off a constant-product curve, so
+/// its reserves never approach an extreme price before graduation
+pub const MINIMUM_TOKEN_LIQUIDITY: u64 = 1_000 * 1_000_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    Overflow,
+    InsufficientLiquidity,
+}
+
+pub type MathResult<T> = core::result::Result<T, MathError>;
+
+/// Tokens received for `sol_in` (constant product)
+///
+/// `tokens_out = vt - ceil((vs * vt) / (vs + sol_in))`, rounded against the
+/// buyer so `k` never shrinks
+pub fn tokens_out(virtual_sol: u64, virtual_token: u64, sol_in: u64) -> MathResult<u64> {
+    let new_sol_reserves = virtual_sol.checked_add(sol_in).ok_or(MathError::Overflow)?;
+    if new_sol_reserves == 0 {
+        return Err(MathError::Overflow);
+    }
+
+    let product = (virtual_sol as u128)
+        .checked_mul(virtual_token as u128)
+        .ok_or(MathError::Overflow)?;
+
+    let new_token_reserves = u64::try_from(product.div_ceil(new_sol_reserves as u128))
+        .map_err(|_| MathError::Overflow)?;
+
+    virtual_token
+        .checked_sub(new_token_reserves)
+        .ok_or(MathError::InsufficientLiquidity)
+}
+
+/// SOL received for `tokens_in` (constant product)
+///
+/// `sol_out = vs - ceil((vs * vt) / (vt + tokens_in))`, rounded against the
+/// seller so `k` never shrinks
+pub fn sol_out(virtual_sol: u64, virtual_token: u64, tokens_in: u64) -> MathResult<u64> {
+    let new_token_reserves = virtual_token
+        .checked_add(tokens_in)
+        .ok_or(MathError::Overflow)?;
+    if new_token_reserves == 0 {
+        return Err(MathError::Overflow);
+    }
+
+    let product = (virtual_sol as u128)
+        .checked_mul(virtual_token as u128)
+        .ok_or(MathError::Overflow)?;
+
+    let new_sol_reserves = u64::try_from(product.div_ceil(new_token_reserves as u128))
+        .map_err(|_| MathError::Overflow)?;
+
+    virtual_sol
+        .checked_sub(new_sol_reserves)
+        .ok_or(MathError::InsufficientLiquidity)
+}
+
+/// Smallest `sol_in` that buys at least `tokens` (inverse of [`tokens_out`])
+///
+/// `sol_in = ceil((vs * vt) / (vt - tokens)) - vs`
+pub fn sol_in_for_tokens(virtual_sol: u64, virtual_token: u64, tokens: u64) -> MathResult<u64> {
+    let remaining = virtual_token
+        .checked_sub(tokens)
+        .filter(|r| *r > 0)
+        .ok_or(MathError::InsufficientLiquidity)? as u128;
+
+    let product = (virtual_sol as u128)
+        .checked_mul(virtual_token as u128)
+        .ok_or(MathError::Overflow)?;
+
+    let new_sol_reserves = product
+        .checked_add(remaining - 1)
+        .ok_or(MathError::Overflow)?
+        / remaining;
+
+    u64::try_from(new_sol_reserves)
+        .map_err(|_| MathError::Overflow)?
+        .checked_sub(virtual_sol)
+        .ok_or(MathError::Overflow)
+}
+
+/// Tokens a constant-product buy may still take from `real_token_reserves`,
+/// leaving [`MINIMUM_TOKEN_LIQUIDITY`] on the curve
+pub fn buyable_tokens(real_token_reserves: u64) -> u64 {
+    real_token_reserves.saturating_sub(MINIMUM_TOKEN_LIQUIDITY)
+}
+
+/// SOL a sell may still take from `real_sol_reserves`, leaving
+/// [`MINIMUM_SOL_LIQUIDITY`] in the reserves
+pub fn sellable_sol(real_sol_reserves: u64) -> u64 {
+    real_sol_reserves.saturating_sub(MINIMUM_SOL_LIQUIDITY)
+}
+
+/// Virtual SOL reserves of a liquidity bootstrapping (Dutch auction) launch
+///
+/// `extra_virtual_sol` is added on top of `virtual_sol` at `start_ts` and
+/// decays linearly to zero over `duration_secs`, so the price starts high and
+/// falls back to the regular curve absent demand.
+pub fn lbp_virtual_sol(
+    virtual_sol: u64,
+    extra_virtual_sol: u64,
+    start_ts: i64,
+    duration_secs: i64,
+    now: i64,
+) -> u64 {
+    let elapsed = now.saturating_sub(start_ts).max(0);
+    if duration_secs <= 0 || elapsed >= duration_secs {
+        return virtual_sol;
+    }
+
+    let remaining = (duration_secs - elapsed) as u128;
+    let extra = extra_virtual_sol as u128 * remaining / duration_secs as u128;
+    virtual_sol.saturating_add(extra as u64)
+}
+
+/// Spot price (lamports per token, scaled by `PRICE_SCALE`)
+pub fn spot_price(virtual_sol: u64, virtual_token: u64) -> u64 {
+    if virtual_token == 0 {
+        return 0;
+    }
+
+    ((virtual_sol as u128)
+        .checked_mul(PRICE_SCALE as u128)
+        .unwrap_or(0)
+        / virtual_token as u128) as u64
+}
+
+/// Spot price in nano-lamports per token (lamports per token scaled by
+/// `PRICE_SCALE`, the unit of stepped tranche prices), precise enough to
+/// measure the price move of small trades
+pub fn spot_price_nano(virtual_sol: u64, virtual_token: u64) -> u128 {
+    if virtual_token == 0 {
+        return 0;
+    }
+
+    (virtual_sol as u128)
+        .checked_mul(PRICE_SCALE as u128 * PRICE_SCALE as u128)
+        .unwrap_or(0)
+        / virtual_token as u128
+}
+
+/// Relative move from `price_before` to `price_after` in basis points, in
+/// either direction (0 without a price before)
+pub fn price_impact_bps(price_before: u128, price_after: u128) -> u64 {
+    if price_before == 0 {
+        return 0;
+    }
+
+    let impact = price_before
+        .abs_diff(price_after)
+        .saturating_mul(BPS_DENOMINATOR as u128)
+        / price_before;
+    u64::try_from(impact).unwrap_or(u64::MAX)
+}
+
+/// Market cap of the circulating curve supply at `price`
+pub fn market_cap(price: u64, bonding_curve_supply: u64, real_token_reserves: u64) -> u64 {
+    let circulating_supply = bonding_curve_supply.saturating_sub(real_token_reserves);
+
+    ((circulating_supply as u128)
+        .checked_mul(price as u128)
+        .unwrap_or(0)
+        / PRICE_SCALE as u128) as u64
+}
+
+/// `amount * bps / 10_000`, rounded down
+pub fn fee(amount: u64, bps: u64) -> MathResult<u64> {
+    amount
+        .checked_mul(bps)
+        .ok_or(MathError::Overflow)?
+        .checked_div(BPS_DENOMINATOR)
+        .ok_or(MathError::Overflow)
+}
+
+/// Split `amount` into `(platform_fee, creator_fee, amount - fees)`
+pub fn split_fees(
+    amount: u64,
+    platform_fee_bps: u64,
+    creator_fee_bps: u64,
+) -> MathResult<(u64, u64, u64)> {
+    let platform_fee = fee(amount, platform_fee_bps)?;
+    let creator_fee = fee(amount, creator_fee_bps)?;
+    let net = amount
+        .checked_sub(platform_fee)
+        .ok_or(MathError::Overflow)?
+        .checked_sub(creator_fee)
+        .ok_or(MathError::Overflow)?;
+    Ok((platform_fee, creator_fee, net))
+}
+
+/// Largest gross amount whose net (after fees) stays within `max_net`,
+/// before rounding of the individual fees
+pub fn max_gross_for_net(
+    max_net: u64,
+    platform_fee_bps: u64,
+    creator_fee_bps: u64,
+) -> MathResult<u64> {
+    let net_bps = net_bps(platform_fee_bps, creator_fee_bps)?;
+    let max_gross = (max_net as u128 * BPS_DENOMINATOR as u128) / net_bps as u128;
+    Ok(u64::try_from(max_gross).unwrap_or(u64::MAX))
+}
+
+/// Smallest gross amount whose net (after fees) covers `net`
+pub fn gross_for_net(net: u64, platform_fee_bps: u64, creator_fee_bps: u64) -> MathResult<u64> {
+    let net_bps = net_bps(platform_fee_bps, creator_fee_bps)? as u128;
+    let gross = (net as u128 * BPS_DENOMINATOR as u128).div_ceil(net_bps);
+    let mut gross = u64::try_from(gross).map_err(|_| MathError::Overflow)?;
+
+    // Fees round down, so a few lamports less may still cover `net`
+    while gross > 0 && split_fees(gross - 1, platform_fee_bps, creator_fee_bps)?.2 >= net {
+        gross -= 1;
+    }
+    Ok(gross)
+}
+
+fn net_bps(platform_fee_bps: u64, creator_fee_bps: u64) -> MathResult<u64> {
+    BPS_DENOMINATOR
+        .checked_sub(platform_fee_bps)
+        .and_then(|v| v.checked_sub(creator_fee_bps))
+        .filter(|v| *v > 0)
+        .ok_or(MathError::Overflow)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuyQuote {
+    /// SOL actually spent, fees included (less than requested on a partial fill)
+    pub sol_amount: u64,
+    /// Tokens minted to the buyer
+    pub tokens_out: u64,
+    /// SOL added to the curve reserves
+    pub net_sol_amount: u64,
+    pub platform_fee: u64,
+    pub creator_fee: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SellQuote {
+    /// SOL removed from the curve reserves
+    pub gross_sol_out: u64,
+    /// SOL paid to the seller after fees
+    pub net_sol_out: u64,
+    pub platform_fee: u64,
+    pub creator_fee: u64,
+}
+
+/// Quote a buy of `sol_amount` exactly as `buy_tokens` executes it
+///
+/// Fees are carved out of `sol_amount` first and tokens are priced on the
+/// net amount, the SOL actually added to the reserves.
+pub fn quote_buy(
+    virtual_sol: u64,
+    virtual_token: u64,
+    sol_amount: u64,
+    platform_fee_bps: u64,
+    creator_fee_bps: u64,
+) -> MathResult<BuyQuote> {
+    quote_buy_capped(
+        virtual_sol,
+        virtual_token,
+        sol_amount,
+        u64::MAX,
+        u64::MAX,
+        platform_fee_bps,
+        creator_fee_bps,
+    )
+}
+
+/// Quote a buy that is partially filled against the remaining curve capacity
+///
+/// At most `max_tokens` are bought and at most `max_net_sol` (before rounding
+/// of fees) is added to the reserves; the unused part of `sol_amount` is left
+/// with the buyer. `BuyQuote::sol_amount` is what is actually spent.
+pub fn quote_buy_capped(
+    virtual_sol: u64,
+    virtual_token: u64,
+    sol_amount: u64,
+    max_tokens: u64,
+    max_net_sol: u64,
+    platform_fee_bps: u64,
+    creator_fee_bps: u64,
+) -> MathResult<BuyQuote> {
+    // Cap the SOL so the net amount stops at the graduation threshold
+    let mut sol_amount = sol_amount.min(max_gross_for_net(
+        max_net_sol,
+        platform_fee_bps,
+        creator_fee_bps,
+    )?);
+
+    // Cap the tokens at what is left on the curve, charging only enough to
+    // cover them
+    let (_, _, net) = split_fees(sol_amount, platform_fee_bps, creator_fee_bps)?;
+    let mut tokens = tokens_out(virtual_sol, virtual_token, net)?;
+    if tokens > max_tokens {
+        let net_needed = sol_in_for_tokens(virtual_sol, virtual_token, max_tokens)?;
+        sol_amount = gross_for_net(net_needed, platform_fee_bps, creator_fee_bps)?.min(sol_amount);
+    }
+
+    let (platform_fee, creator_fee, net_sol_amount) =
+        split_fees(sol_amount, platform_fee_bps, creator_fee_bps)?;
+    tokens = tokens_out(virtual_sol, virtual_token, net_sol_amount)?.min(max_tokens);
+
+    Ok(BuyQuote {
+        sol_amount,
+        tokens_out: tokens,
+        net_sol_amount,
+        platform_fee,
+        creator_fee,
+    })
+}
+
+/// Quote a sell of `token_amount` exactly as `sell_tokens` executes it
+pub fn quote_sell(
+    virtual_sol: u64,
+    virtual_token: u64,
+    token_amount: u64,
+    platform_fee_bps: u64,
+    creator_fee_bps: u64,
+) -> MathResult<SellQuote> {
+    let gross_sol_out = sol_out(virtual_sol, virtual_token, token_amount)?;
+    let (platform_fee, creator_fee, net_sol_out) =
+        split_fees(gross_sol_out, platform_fee_bps, creator_fee_bps)?;
+
+    Ok(SellQuote {
+        gross_sol_out,
+        net_sol_out,
+        platform_fee,
+        creator_fee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+    const TOKEN: u64 = 1_000_000_000;
+
+    /// Default curve: 30 SOL and 1.073B tokens of virtual reserves
+    const VS: u64 = 30 * LAMPORTS_PER_SOL;
+    const VT: u64 = 1_073_000_000 * TOKEN;
+
+    fn k(virtual_sol: u64, virtual_token: u64) -> u128 {
+        virtual_sol as u128 * virtual_token as u128
+    }
+
+    #[test]
+    fn tokens_out_rounds_against_the_buyer() {
+        // k = 100; 13 SOL leaves ceil(100 / 13) = 8 tokens, not 7
+        assert_eq!(tokens_out(10, 10, 3), Ok(2));
+        assert_eq!(tokens_out(VS, VT, 0), Ok(0));
+
+        let out = tokens_out(VS, VT, LAMPORTS_PER_SOL).unwrap();
+        assert!(k(VS + LAMPORTS_PER_SOL, VT - out) >= k(VS, VT));
+        assert!(k(VS + LAMPORTS_PER_SOL, VT - out - 1) < k(VS, VT));
+    }
+
+    #[test]
+    fn sol_out_rounds_against_the_seller() {
+        // k = 100; 13 tokens leave ceil(100 / 13) = 8 SOL, not 7
+        assert_eq!(sol_out(10, 10, 3), Ok(2));
+        assert_eq!(sol_out(VS, VT, 0), Ok(0));
+
+        let out = sol_out(VS, VT, 1_000_000 * TOKEN).unwrap();
+        assert!(k(VS - out, VT + 1_000_000 * TOKEN) >= k(VS, VT));
+        assert!(k(VS - out - 1, VT + 1_000_000 * TOKEN) < k(VS, VT));
+    }
+
+    #[test]
+    fn sol_in_for_tokens_is_the_smallest_input() {
+        for tokens in [1, 12_345 * TOKEN, 500_000_000 * TOKEN] {
+            let sol_in = sol_in_for_tokens(VS, VT, tokens).unwrap();
+            assert!(tokens_out(VS, VT, sol_in).unwrap() >= tokens);
+            assert!(tokens_out(VS, VT, sol_in - 1).unwrap() < tokens);
+        }
+        assert_eq!(
+            sol_in_for_tokens(VS, VT, VT),
+            Err(MathError::InsufficientLiquidity)
+        );
+    }
+
+    #[test]
+    fn fees_round_down_and_add_up() {
+        assert_eq!(
+            split_fees(1_000_000, 100, 100),
+            Ok((10_000, 10_000, 980_000))
+        );
+        assert_eq!(split_fees(199, 100, 100), Ok((1, 1, 197)));
+        assert_eq!(split_fees(99, 100, 100), Ok((0, 0, 99)));
+
+        for gross in [1, 99, 10_001, 123_456_789] {
+            let (platform_fee, creator_fee, net) = split_fees(gross, 100, 50).unwrap();
+            assert_eq!(platform_fee + creator_fee + net, gross);
+        }
+        assert_eq!(split_fees(100, 6_000, 5_000), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn gross_and_net_conversions_bound_each_other() {
+        for net in [1, 980_000, 29_999 * LAMPORTS_PER_SOL] {
+            let gross = gross_for_net(net, 100, 100).unwrap();
+            assert!(split_fees(gross, 100, 100).unwrap().2 >= net);
+            assert!(split_fees(gross - 1, 100, 100).unwrap().2 < net);
+
+            let max_gross = max_gross_for_net(net, 100, 100).unwrap();
+            assert!(max_gross * 9_800 / BPS_DENOMINATOR <= net);
+        }
+    }
+
+    #[test]
+    fn buys_are_priced_on_net_sol() {
+        let quote = quote_buy(VS, VT, LAMPORTS_PER_SOL, 100, 100).unwrap();
+        assert_eq!(quote.sol_amount, LAMPORTS_PER_SOL);
+        assert_eq!(quote.platform_fee, LAMPORTS_PER_SOL / 100);
+        assert_eq!(quote.creator_fee, LAMPORTS_PER_SOL / 100);
+        assert_eq!(
+            quote.net_sol_amount,
+            LAMPORTS_PER_SOL - 2 * LAMPORTS_PER_SOL / 100
+        );
+        assert_eq!(
+            quote.tokens_out,
+            tokens_out(VS, VT, quote.net_sol_amount).unwrap()
+        );
+        assert!(quote.tokens_out < tokens_out(VS, VT, quote.sol_amount).unwrap());
+    }
+
+    #[test]
+    fn sells_pay_gross_less_fees() {
+        let quote = quote_sell(VS, VT, 10_000_000 * TOKEN, 100, 100).unwrap();
+        assert_eq!(
+            quote.gross_sol_out,
+            sol_out(VS, VT, 10_000_000 * TOKEN).unwrap()
+        );
+        assert_eq!(quote.platform_fee, quote.gross_sol_out / 100);
+        assert_eq!(quote.creator_fee, quote.gross_sol_out / 100);
+        assert_eq!(
+            quote.net_sol_out + quote.platform_fee + quote.creator_fee,
+            quote.gross_sol_out
+        );
+    }
+
+    #[test]
+    fn capped_buys_stop_at_the_token_floor() {
+        // Near the end of the curve only the tokens above the floor are for sale
+        let real_token = 2_000 * TOKEN;
+        let max_tokens = buyable_tokens(real_token);
+        assert_eq!(max_tokens, real_token - MINIMUM_TOKEN_LIQUIDITY);
+
+        let requested = 100 * LAMPORTS_PER_SOL;
+        let quote = quote_buy_capped(VS, VT, requested, max_tokens, u64::MAX, 100, 100).unwrap();
+        assert_eq!(quote.tokens_out, max_tokens);
+        assert!(quote.sol_amount < requested);
+
+        // Charged just enough: one lamport less buys fewer tokens
+        let (_, _, net) = split_fees(quote.sol_amount - 1, 100, 100).unwrap();
+        assert!(tokens_out(VS, VT, net).unwrap() < max_tokens);
+        assert_eq!(
+            quote.net_sol_amount,
+            split_fees(quote.sol_amount, 100, 100).unwrap().2
+        );
+
+        assert_eq!(buyable_tokens(MINIMUM_TOKEN_LIQUIDITY - 1), 0);
+        let empty = quote_buy_capped(VS, VT, requested, 0, u64::MAX, 100, 100).unwrap();
+        assert_eq!(empty.tokens_out, 0);
+    }
+
+    #[test]
+    fn capped_buys_stop_at_the_graduation_threshold() {
+        let max_net_sol = LAMPORTS_PER_SOL;
+        let quote = quote_buy_capped(
+            VS,
+            VT,
+            5 * LAMPORTS_PER_SOL,
+            u64::MAX,
+            max_net_sol,
+            100,
+            100,
+        )
+        .unwrap();
+        assert!(quote.sol_amount < 5 * LAMPORTS_PER_SOL);
+        // Within fee rounding of the threshold
+        assert!(quote.net_sol_amount <= max_net_sol + 2);
+        assert!(quote.net_sol_amount + 2 >= max_net_sol);
+        assert_eq!(
+            quote.tokens_out,
+            tokens_out(VS, VT, quote.net_sol_amount).unwrap()
+        );
+    }
+
+    #[test]
+    fn sells_leave_the_sol_floor() {
+        assert_eq!(
+            sellable_sol(5 * MINIMUM_SOL_LIQUIDITY),
+            4 * MINIMUM_SOL_LIQUIDITY
+        );
+        assert_eq!(sellable_sol(MINIMUM_SOL_LIQUIDITY), 0);
+        assert_eq!(sellable_sol(0), 0);
+    }
+
+    #[test]
+    fn k_never_decreases_across_trades() {
+        let (mut vs, mut vt) = (VS, VT);
+        let mut real_token = 800_000_000 * TOKEN;
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+
+        for i in 0..500 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let k_before = k(vs, vt);
+
+            if i % 3 == 2 && real_token < 800_000_000 * TOKEN {
+                let sold = 800_000_000 * TOKEN - real_token;
+                let tokens = seed % sold + 1;
+                let quote = quote_sell(vs, vt, tokens, 100, 100).unwrap();
+                vs -= quote.gross_sol_out;
+                vt += tokens;
+                real_token += tokens;
+            } else {
+                let sol = seed % (3 * LAMPORTS_PER_SOL) + 1;
+                let quote =
+                    quote_buy_capped(vs, vt, sol, buyable_tokens(real_token), u64::MAX, 100, 100)
+                        .unwrap();
+                vs += quote.net_sol_amount;
+                vt -= quote.tokens_out;
+                real_token -= quote.tokens_out;
+            }
+
+            assert!(k(vs, vt) >= k_before, "k shrank on trade {}", i);
+            assert!(real_token >= MINIMUM_TOKEN_LIQUIDITY);
+        }
+    }
+
+    #[test]
+    fn stepped_round_trip_never_pays_out_more() {
+        let tranches = [
+            stepped::Tranche {
+                supply: 100_000 * TOKEN,
+                start_price: 1_000,
+                slope: 0,
+            },
+            stepped::Tranche {
+                supply: 100_000 * TOKEN,
+                start_price: 2_000,
+                slope: 10,
+            },
+        ];
+        let quote = stepped::quote_buy(&tranches, 0, LAMPORTS_PER_SOL, u64::MAX, 100, 100).unwrap();
+        let sell =
+            stepped::quote_sell(&tranches, quote.tokens_out, quote.tokens_out, 100, 100).unwrap();
+        assert!(sell.gross_sol_out <= quote.net_sol_amount);
+    }
+}
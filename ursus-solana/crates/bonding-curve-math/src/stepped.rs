@@ -0,0 +1,283 @@
+//! Stepped multi-phase curve: consecutive tranches, each selling a fixed
+//! token supply along its own linear price line.
+//!
+//! Within a tranche the price after `x` base units have been sold is
+//! `start_price + slope * x / 1e9`. Tranche prices are in nano-lamports per
+//! whole token (lamports scaled by [`PRICE_SCALE`]) so that slopes can be
+//! fine grained; [`spot_price`] converts back to the lamports per token
+//! reported by [`crate::spot_price`]. A zero slope gives a flat price (e.g. a
+//! community phase).
+//!
+//! As on the constant product curve, tokens are priced on the SOL that
+//! actually reaches the reserves (after fees), so the reserves always cover
+//! selling every outstanding token back. Buys round in favour of the curve
+//! and sells round against the seller.
+
+use crate::{
+    gross_for_net, max_gross_for_net, split_fees, BuyQuote, MathError, MathResult, SellQuote,
+    PRICE_SCALE,
+};
+
+/// One phase of a stepped curve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tranche {
+    /// Tokens sold in this tranche (base units)
+    pub supply: u64,
+    /// Price at the start of the tranche (nano-lamports per token)
+    pub start_price: u64,
+    /// Price increase (nano-lamports per token) per whole token sold
+    pub slope: u64,
+}
+
+/// Spot price (lamports per token) once `sold` tokens have left the curve
+pub fn spot_price(tranches: &[Tranche], sold: u64) -> u64 {
+    spot_price_nano(tranches, sold) / PRICE_SCALE
+}
+
+/// Spot price in nano-lamports per token once `sold` tokens have left the
+/// curve
+pub fn spot_price_nano(tranches: &[Tranche], sold: u64) -> u64 {
+    let mut offset = sold;
+    for tranche in tranches {
+        if offset < tranche.supply {
+            return price_at(tranche, offset).unwrap_or(u64::MAX);
+        }
+        offset -= tranche.supply;
+    }
+
+    // Curve sold out: report the closing price of the last tranche
+    tranches
+        .last()
+        .and_then(|t| price_at(t, t.supply).ok())
+        .unwrap_or(0)
+}
+
+/// SOL needed to move the curve from `from` to `to` tokens sold
+///
+/// Rounded up when `round_up` is set (buys), down otherwise (sells).
+pub fn cost(tranches: &[Tranche], from: u64, to: u64, round_up: bool) -> MathResult<u64> {
+    if to < from {
+        return Err(MathError::Overflow);
+    }
+
+    let mut total: u64 = 0;
+    let mut tranche_start: u64 = 0;
+    for tranche in tranches {
+        let tranche_end = tranche_start
+            .checked_add(tranche.supply)
+            .ok_or(MathError::Overflow)?;
+        let a = from.clamp(tranche_start, tranche_end) - tranche_start;
+        let b = to.clamp(tranche_start, tranche_end) - tranche_start;
+        if b > a {
+            total = total
+                .checked_add(segment_cost(tranche, a, b, round_up)?)
+                .ok_or(MathError::Overflow)?;
+        }
+        tranche_start = tranche_end;
+    }
+
+    if to > tranche_start {
+        return Err(MathError::InsufficientLiquidity);
+    }
+    Ok(total)
+}
+
+/// Tokens bought with `sol_in` once `sold` tokens have left the curve
+///
+/// Returns `(tokens_out, sol_used)`; `sol_used < sol_in` only when the curve
+/// sells out.
+pub fn tokens_out(tranches: &[Tranche], sold: u64, sol_in: u64) -> MathResult<(u64, u64)> {
+    let mut budget = sol_in;
+    let mut tokens: u64 = 0;
+    let mut offset = sold;
+
+    for tranche in tranches {
+        if budget == 0 {
+            break;
+        }
+        if offset >= tranche.supply {
+            offset -= tranche.supply;
+            continue;
+        }
+
+        let rest_cost = segment_cost(tranche, offset, tranche.supply, true)?;
+        if rest_cost <= budget {
+            // Take the rest of this tranche and move on
+            budget -= rest_cost;
+            tokens = tokens
+                .checked_add(tranche.supply - offset)
+                .ok_or(MathError::Overflow)?;
+        } else {
+            // Budget runs out inside this tranche; the remainder stays in reserves
+            let bought = tokens_for_budget(tranche, offset, budget)?.min(tranche.supply - offset);
+            budget = 0;
+            tokens = tokens.checked_add(bought).ok_or(MathError::Overflow)?;
+        }
+        offset = 0;
+    }
+
+    Ok((tokens, sol_in - budget))
+}
+
+/// SOL received for selling `tokens_in` once `sold` tokens have left the curve
+pub fn sol_out(tranches: &[Tranche], sold: u64, tokens_in: u64) -> MathResult<u64> {
+    let from = sold
+        .checked_sub(tokens_in)
+        .ok_or(MathError::InsufficientLiquidity)?;
+    cost(tranches, from, sold, false)
+}
+
+/// Quote a buy on a stepped curve, partially filled at the graduation
+/// threshold (`max_net_sol`) or when the tranches sell out
+pub fn quote_buy(
+    tranches: &[Tranche],
+    sold: u64,
+    sol_amount: u64,
+    max_net_sol: u64,
+    platform_fee_bps: u64,
+    creator_fee_bps: u64,
+) -> MathResult<BuyQuote> {
+    let mut sol_amount = sol_amount.min(max_gross_for_net(
+        max_net_sol,
+        platform_fee_bps,
+        creator_fee_bps,
+    )?);
+
+    let (_, _, net) = split_fees(sol_amount, platform_fee_bps, creator_fee_bps)?;
+    let (tokens_out, net_used) = tokens_out(tranches, sold, net)?;
+    if net_used < net {
+        // Sold out: only charge enough to cover the tokens
+        sol_amount = gross_for_net(net_used, platform_fee_bps, creator_fee_bps)?.min(sol_amount);
+    }
+
+    let (platform_fee, creator_fee, net_sol_amount) =
+        split_fees(sol_amount, platform_fee_bps, creator_fee_bps)?;
+
+    Ok(BuyQuote {
+        sol_amount,
+        tokens_out,
+        net_sol_amount,
+        platform_fee,
+        creator_fee,
+    })
+}
+
+/// Quote a sell on a stepped curve
+pub fn quote_sell(
+    tranches: &[Tranche],
+    sold: u64,
+    token_amount: u64,
+    platform_fee_bps: u64,
+    creator_fee_bps: u64,
+) -> MathResult<SellQuote> {
+    let gross_sol_out = sol_out(tranches, sold, token_amount)?;
+    let (platform_fee, creator_fee, net_sol_out) =
+        split_fees(gross_sol_out, platform_fee_bps, creator_fee_bps)?;
+
+    Ok(SellQuote {
+        gross_sol_out,
+        net_sol_out,
+        platform_fee,
+        creator_fee,
+    })
+}
+
+/// Price (nano-lamports per token) `x` base units into a tranche
+fn price_at(tranche: &Tranche, x: u64) -> MathResult<u64> {
+    let rise = (tranche.slope as u128)
+        .checked_mul(x as u128)
+        .ok_or(MathError::Overflow)?
+        / PRICE_SCALE as u128;
+
+    u64::try_from(rise)
+        .ok()
+        .and_then(|rise| tranche.start_price.checked_add(rise))
+        .ok_or(MathError::Overflow)
+}
+
+/// Area under the tranche's price line between offsets `a` and `b`
+///
+/// `cost = start * (b - a) / 1e18 + slope * (b - a) * (a + b) / 2e27`
+fn segment_cost(tranche: &Tranche, a: u64, b: u64, round_up: bool) -> MathResult<u64> {
+    let scale = PRICE_SCALE as u128;
+    let dx = (b - a) as u128;
+
+    let linear = div(
+        (tranche.start_price as u128)
+            .checked_mul(dx)
+            .ok_or(MathError::Overflow)?,
+        scale * scale,
+        round_up,
+    );
+
+    let area = dx
+        .checked_mul(a as u128 + b as u128)
+        .ok_or(MathError::Overflow)?;
+    let quadratic = div(
+        div(area, 2 * scale, round_up)
+            .checked_mul(tranche.slope as u128)
+            .ok_or(MathError::Overflow)?,
+        scale * scale,
+        round_up,
+    );
+
+    u64::try_from(linear + quadratic).map_err(|_| MathError::Overflow)
+}
+
+/// Tokens bought with `budget` lamports starting `offset` into a tranche
+/// (rounded down). Solves `budget = p * dx / 1e18 + slope * dx^2 / 2e27`:
+///
+/// `dx = (sqrt(p^2 + 2e9 * slope * budget) - p) * 1e9 / slope`
+fn tokens_for_budget(tranche: &Tranche, offset: u64, budget: u64) -> MathResult<u64> {
+    let price = price_at(tranche, offset)? as u128;
+    let scale = PRICE_SCALE as u128;
+
+    let tokens = if tranche.slope == 0 {
+        if price == 0 {
+            return Err(MathError::Overflow);
+        }
+        (budget as u128)
+            .checked_mul(scale * scale)
+            .ok_or(MathError::Overflow)?
+            / price
+    } else {
+        let discriminant = price
+            .checked_mul(price)
+            .and_then(|p2| {
+                (tranche.slope as u128)
+                    .checked_mul(budget as u128)
+                    .and_then(|v| v.checked_mul(2 * scale))
+                    .and_then(|v| p2.checked_add(v))
+            })
+            .ok_or(MathError::Overflow)?;
+        (isqrt(discriminant) - price)
+            .checked_mul(scale)
+            .ok_or(MathError::Overflow)?
+            / tranche.slope as u128
+    };
+
+    Ok(u64::try_from(tokens).unwrap_or(u64::MAX))
+}
+
+fn div(numerator: u128, denominator: u128, round_up: bool) -> u128 {
+    if round_up {
+        numerator.div_ceil(denominator)
+    } else {
+        numerator / denominator
+    }
+}
+
+/// Integer square root (floor)
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = 1u128 << ((128 - n.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
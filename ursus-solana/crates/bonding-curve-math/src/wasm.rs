@@ -0,0 +1,163 @@
+//! wasm-bindgen exports for the frontend. Build with:
+//!
+//! ```text
+//! cargo rustc -p bonding-curve-math --release --features wasm \
+//!     --target wasm32-unknown-unknown --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg \
+//!     target/wasm32-unknown-unknown/release/bonding_curve_math.wasm
+//! ```
+//!
+//! `u64` values cross the boundary as JS `BigInt`. Errors surface as thrown
+//! strings matching the program's error names.
+
+use wasm_bindgen::prelude::*;
+
+use crate::stepped::{self, Tranche};
+use crate::{MathError, CREATOR_FEE_BPS, PLATFORM_FEE_BPS};
+
+fn to_js(e: MathError) -> JsValue {
+    match e {
+        MathError::Overflow => JsValue::from_str("MathOverflow"),
+        MathError::InsufficientLiquidity => JsValue::from_str("InsufficientLiquidity"),
+    }
+}
+
+/// Tokens received for a buy of `sol_amount` lamports (after fees)
+#[wasm_bindgen(js_name = quoteBuyTokens)]
+pub fn quote_buy_tokens(
+    virtual_sol: u64,
+    virtual_token: u64,
+    sol_amount: u64,
+) -> Result<u64, JsValue> {
+    crate::quote_buy(
+        virtual_sol,
+        virtual_token,
+        sol_amount,
+        PLATFORM_FEE_BPS,
+        CREATOR_FEE_BPS,
+    )
+    .map(|q| q.tokens_out)
+    .map_err(to_js)
+}
+
+/// Partial-fill buy against the remaining curve capacity, returned as
+/// `[tokens_out, sol_spent]`; the rest of `sol_amount` stays with the buyer
+#[wasm_bindgen(js_name = quoteBuyFill)]
+pub fn quote_buy_fill(
+    virtual_sol: u64,
+    virtual_token: u64,
+    sol_amount: u64,
+    real_token_reserves: u64,
+    sol_to_graduation: u64,
+) -> Result<Vec<u64>, JsValue> {
+    crate::quote_buy_capped(
+        virtual_sol,
+        virtual_token,
+        sol_amount,
+        crate::buyable_tokens(real_token_reserves),
+        sol_to_graduation,
+        PLATFORM_FEE_BPS,
+        CREATOR_FEE_BPS,
+    )
+    .map(|q| vec![q.tokens_out, q.sol_amount])
+    .map_err(to_js)
+}
+
+/// Lamports paid to the seller for `token_amount` (after fees)
+#[wasm_bindgen(js_name = quoteSellSol)]
+pub fn quote_sell_sol(
+    virtual_sol: u64,
+    virtual_token: u64,
+    token_amount: u64,
+) -> Result<u64, JsValue> {
+    crate::quote_sell(
+        virtual_sol,
+        virtual_token,
+        token_amount,
+        PLATFORM_FEE_BPS,
+        CREATOR_FEE_BPS,
+    )
+    .map(|q| q.net_sol_out)
+    .map_err(to_js)
+}
+
+/// Current virtual SOL reserves of an LBP launch; pass the result as
+/// `virtual_sol` to the other quote functions
+#[wasm_bindgen(js_name = lbpVirtualSol)]
+pub fn lbp_virtual_sol(
+    virtual_sol: u64,
+    extra_virtual_sol: u64,
+    start_ts: i64,
+    duration_secs: i64,
+    now: i64,
+) -> u64 {
+    crate::lbp_virtual_sol(virtual_sol, extra_virtual_sol, start_ts, duration_secs, now)
+}
+
+#[wasm_bindgen(js_name = spotPrice)]
+pub fn spot_price(virtual_sol: u64, virtual_token: u64) -> u64 {
+    crate::spot_price(virtual_sol, virtual_token)
+}
+
+#[wasm_bindgen(js_name = marketCap)]
+pub fn market_cap(price: u64, bonding_curve_supply: u64, real_token_reserves: u64) -> u64 {
+    crate::market_cap(price, bonding_curve_supply, real_token_reserves)
+}
+
+fn tranches(flat: &[u64]) -> Result<Vec<Tranche>, JsValue> {
+    if !flat.len().is_multiple_of(3) {
+        return Err(JsValue::from_str("InvalidCurveConfig"));
+    }
+    Ok(flat
+        .chunks_exact(3)
+        .map(|t| Tranche {
+            supply: t[0],
+            start_price: t[1],
+            slope: t[2],
+        })
+        .collect())
+}
+
+/// Stepped curve buy, returned as `[tokens_out, sol_spent]`. Tranches are
+/// passed flattened as `supply, start_price, slope` triples.
+#[wasm_bindgen(js_name = steppedQuoteBuy)]
+pub fn stepped_quote_buy(
+    flat_tranches: &[u64],
+    tokens_sold: u64,
+    sol_amount: u64,
+    sol_to_graduation: u64,
+) -> Result<Vec<u64>, JsValue> {
+    stepped::quote_buy(
+        &tranches(flat_tranches)?,
+        tokens_sold,
+        sol_amount,
+        sol_to_graduation,
+        PLATFORM_FEE_BPS,
+        CREATOR_FEE_BPS,
+    )
+    .map(|q| vec![q.tokens_out, q.sol_amount])
+    .map_err(to_js)
+}
+
+/// Lamports paid to the seller on a stepped curve (after fees)
+#[wasm_bindgen(js_name = steppedQuoteSell)]
+pub fn stepped_quote_sell(
+    flat_tranches: &[u64],
+    tokens_sold: u64,
+    token_amount: u64,
+) -> Result<u64, JsValue> {
+    stepped::quote_sell(
+        &tranches(flat_tranches)?,
+        tokens_sold,
+        token_amount,
+        PLATFORM_FEE_BPS,
+        CREATOR_FEE_BPS,
+    )
+    .map(|q| q.net_sol_out)
+    .map_err(to_js)
+}
+
+#[wasm_bindgen(js_name = steppedSpotPrice)]
+pub fn stepped_spot_price(flat_tranches: &[u64], tokens_sold: u64) -> Result<u64, JsValue> {
+    Ok(stepped::spot_price(&tranches(flat_tranches)?, tokens_sold))
+}
@@ -0,0 +1,40 @@
+use anchor_lang::AccountDeserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{ClientError, ClientResult};
+
+pub use agent_factory::state::{
+    decay_by_half_life, decay_trending_score, service_id_hash, AdminAction, AdminActionStage,
+    Affiliate, Agent, AgentCommit, AgentFactory, AgentManifest, AgentReputation, ArbiterVote,
+    BasketConstituent, BondingCurve, BuyerRecord, Candle, ConsumerStats, CreatorFeeMode,
+    CreatorStats, CurveTranche, CurveType, DaoTreasury, DcaSchedule, Dispute, DisputeStatus,
+    EarlyBuyerClaim, EarlyBuyerRewards, FeeDiscountTier, Governance, HolderSnapshot, IndexBasket,
+    InsuranceFund, InsurancePolicy, Job, KeeperTask, KeeperTaskKind, LiquidityMining,
+    LiquidityMiningPosition, ListingReserve, MeterUnit, Milestone, MilestoneStatus,
+    NameReservation, PaymentStatus, PaymentStream, Presale, PresaleCommitment, PriceHistory,
+    PriceView, Proposal, ProposalAction, ProtocolLiquidity, ProviderBond, QueuedAdminAction,
+    ReputationAttestation, RevenuePool, RevenueShare, RevenueSplit, ServiceAgreement, ServiceQuote,
+    ServiceRating, StakePosition, StakingPool, TaskStatus, TokenLock, TokenMigration,
+    TrendingEntry, TrendingLeaderboard, TrialSession, TrialStatus, TwapAccumulator,
+    TwapObservation, VoteRecord, WorkQueue, WorkTask, X402Config, X402PaymentRecord,
+    BASKET_TOKEN_UNIT, CANDLE_INTERVAL_SECS, CAPABILITY_AFFILIATES, CAPABILITY_CHARGEBACKS,
+    CAPABILITY_ESCROW, CAPABILITY_GRADUATED, CAPABILITY_METERED, CAPABILITY_PAYS_AGENTS,
+    CAPABILITY_REVENUE_SPLIT, CAPABILITY_TRIALS, CAPABILITY_WEBHOOK, CAPABILITY_X402,
+    CREATION_DEMAND_UNIT, DEFAULT_ALLOWED_MODELS, DEFAULT_CREATOR_FEE_VESTING_SECS,
+    FEE_DISCOUNT_TIERS, INSURANCE_PREMIUM_BPS, MAX_AFFILIATE_BPS, MAX_AGENT_TAGS,
+    MAX_ALLOWED_MODELS, MAX_ARBITERS, MAX_BASKET_CONSTITUENTS, MAX_CREATION_SURGE_BPS,
+    MAX_CREATOR_FEE_VESTING_SECS, MAX_CREATOR_ROYALTY_BPS, MAX_JOB_DURATION_SECS,
+    MAX_JOB_MILESTONES, MAX_LISTING_RELEASE_DELAY_SECS, MAX_LISTING_RESERVE_BPS,
+    MAX_PROTOCOL_LIQUIDITY_BPS, MAX_QUEUE_WORKERS, MAX_QUOTE_TTL_SECS, MAX_RATING_STARS,
+    MAX_REPUTATION_SOURCES, MAX_REVENUE_SPLIT_BPS, MAX_REVENUE_SPLIT_RECIPIENTS,
+    MAX_STREAM_DURATION_SECS, MAX_TAG_LEN, MINIMUM_SOL_LIQUIDITY, MINIMUM_TOKEN_LIQUIDITY,
+    MIN_MAX_BUY_BPS, MODEL_ID_LEN, PRICE_HISTORY_CANDLES, RENAME_COOLDOWN_SECS,
+    REPUTATION_SOURCE_LEN, TRENDING_HALF_LIFE_SLOTS, TRENDING_LEADERBOARD_SIZE, TWAP_OBSERVATIONS,
+    TWAP_OBSERVATION_INTERVAL_SLOTS,
+};
+
+/// Decode a program account (discriminator checked) from raw account data
+pub fn decode<T: AccountDeserialize>(address: &Pubkey, data: &[u8]) -> ClientResult<T> {
+    let mut data = data;
+    T::try_deserialize(&mut data).map_err(|e| ClientError::AccountDecode(*address, e.to_string()))
+}
@@ -0,0 +1,3164 @@
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bonding_curve_math::BPS_DENOMINATOR;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::pubsub_client::{PubsubClient, PubsubClientSubscription};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig,
+    RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_response::{Response, RpcLogsResponse};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::UiTransactionEncoding;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+
+use crate::accounts::{
+    decode, AdminAction, Affiliate, Agent, AgentFactory, AgentManifest, AgentReputation,
+    BondingCurve, BuyerRecord, ConsumerStats, CreatorFeeMode, CreatorStats, CurveTranche,
+    DaoTreasury, DcaSchedule, Dispute, EarlyBuyerRewards, Governance, HolderSnapshot, IndexBasket,
+    InsuranceFund, InsurancePolicy, Job, KeeperTask, KeeperTaskKind, LiquidityMining,
+    LiquidityMiningPosition, MeterUnit, NameReservation, PaymentStatus, PaymentStream, Presale,
+    PresaleCommitment, PriceHistory, PriceView, Proposal, ProposalAction, ProtocolLiquidity,
+    ProviderBond, QueuedAdminAction, RevenuePool, RevenueSplit, ServiceAgreement, ServiceQuote,
+    ServiceRating, StakePosition, StakingPool, TokenLock, TokenMigration, TrendingLeaderboard,
+    TrialSession, WorkQueue, WorkTask, X402Config, X402PaymentRecord, REPUTATION_SOURCE_LEN,
+};
+use crate::error::{ClientError, ClientResult};
+use crate::events::{parse_logs, parse_transaction, ProgramEvent, TransactionEvents};
+use crate::instructions::{self, CreateAgentArgs, X402Settings};
+use crate::pda::{
+    find_admin_action_pda, find_affiliate_pda, find_agent_pda, find_basket_mint_pda,
+    find_buyer_record_pda, find_commitment_pda, find_consumer_stats_pda, find_creator_stats_pda,
+    find_dao_treasury_pda, find_dca_pda, find_dispute_pda, find_early_buyer_rewards_pda,
+    find_factory_pda, find_governance_pda, find_index_basket_pda, find_insurance_fund_pda,
+    find_insurance_policy_pda, find_job_pda, find_keeper_task_pda, find_liquidity_mining_pda,
+    find_lm_position_pda, find_lock_pda, find_migration_pda, find_mint_pda,
+    find_name_reservation_pda, find_ownership_mint_pda, find_payment_escrow_pda,
+    find_payment_record_pda, find_payment_stream_pda, find_presale_pda, find_price_history_pda,
+    find_proposal_pda, find_protocol_liquidity_pda, find_provider_bond_pda, find_rating_pda,
+    find_reputation_pda, find_revenue_split_pda, find_service_agreement_pda,
+    find_service_quote_pda, find_snapshot_pda, find_stake_position_pda, find_staking_pool_pda,
+    find_trending_pda, find_trial_session_pda, find_work_queue_pda, find_work_task_pda,
+    find_x402_config_pda,
+};
+use crate::quote::{
+    quote_buy, quote_buy_discounted, quote_redeem, quote_sell, quote_sell_discounted,
+    with_slippage, BuyQuote, SellQuote,
+};
+use crate::registry::LayoutInfo;
+use crate::PROGRAM_ID;
+
+/// Compute units requested for a launch bundle (the per-transaction maximum)
+const BUNDLE_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Live event stream; dropping it does not unsubscribe, call `shutdown`
+pub struct EventSubscription {
+    subscription: PubsubClientSubscription<Response<RpcLogsResponse>>,
+    receiver: crossbeam_channel::Receiver<Response<RpcLogsResponse>>,
+    /// When set, events are read from each transaction's inner instructions
+    /// rather than its (truncatable) logs
+    rpc: Option<RpcClient>,
+}
+
+impl EventSubscription {
+    /// Block until the next transaction with program events arrives
+    pub fn next_events(&self) -> Option<TransactionEvents> {
+        loop {
+            let response = self.receiver.recv().ok()?;
+            if response.value.err.is_some() {
+                continue;
+            }
+            // Fall back to the logs if the transaction can't be fetched
+            let events = self
+                .rpc
+                .as_ref()
+                .and_then(|rpc| {
+                    let signature = response.value.signature.parse().ok()?;
+                    fetch_transaction_events(rpc, &signature).ok()
+                })
+                .unwrap_or_else(|| parse_logs(&response.value.logs));
+            if !events.is_empty() {
+                return Some(TransactionEvents {
+                    signature: response.value.signature,
+                    slot: response.context.slot,
+                    events,
+                });
+            }
+        }
+    }
+
+    pub fn shutdown(mut self) -> ClientResult<()> {
+        self.subscription.send_unsubscribe()?;
+        let _ = self.subscription.shutdown();
+        Ok(())
+    }
+}
+
+/// Fetch a confirmed transaction and decode its `emit_cpi!` events
+fn fetch_transaction_events(
+    rpc: &RpcClient,
+    signature: &Signature,
+) -> ClientResult<Vec<ProgramEvent>> {
+    let tx = rpc.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    Ok(parse_transaction(&tx))
+}
+
+/// Serialized size of a legacy transaction signed by `payer` alone:
+/// signature count, signatures, then the message
+fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    1 + 64 * message.header.num_required_signatures as usize + message.serialize().len()
+}
+
+/// RPC-backed client for the agent factory program
+pub struct UrsusClient {
+    rpc: RpcClient,
+    payer: Keypair,
+}
+
+impl UrsusClient {
+    pub fn new(rpc_url: impl ToString, payer: Keypair) -> Self {
+        Self::with_commitment(rpc_url, payer, CommitmentConfig::confirmed())
+    }
+
+    pub fn with_commitment(
+        rpc_url: impl ToString,
+        payer: Keypair,
+        commitment: CommitmentConfig,
+    ) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(rpc_url.to_string(), commitment),
+            payer,
+        }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    // ========================================================================
+    // Account fetching
+    // ========================================================================
+
+    pub fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> ClientResult<T> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value
+            .ok_or(ClientError::AccountNotFound(*address))?;
+        decode(address, &account.data)
+    }
+
+    pub fn get_factory(&self) -> ClientResult<AgentFactory> {
+        self.fetch(&find_factory_pda().0)
+    }
+
+    pub fn get_admin_action(&self, action_id: u64) -> ClientResult<QueuedAdminAction> {
+        self.fetch(&find_admin_action_pda(action_id).0)
+    }
+
+    pub fn get_insurance_fund(&self) -> ClientResult<InsuranceFund> {
+        self.fetch(&find_insurance_fund_pda().0)
+    }
+
+    pub fn get_creator_stats(&self, creator: &Pubkey) -> ClientResult<CreatorStats> {
+        self.fetch(&find_creator_stats_pda(creator).0)
+    }
+
+    pub fn get_name_reservation(&self, name: &str) -> ClientResult<NameReservation> {
+        self.fetch(&find_name_reservation_pda(name).0)
+    }
+
+    pub fn get_agent(&self, agent: &Pubkey) -> ClientResult<Agent> {
+        self.fetch(agent)
+    }
+
+    pub fn get_agent_by_id(&self, agent_id: u64) -> ClientResult<Agent> {
+        self.fetch(&find_agent_pda(agent_id).0)
+    }
+
+    pub fn get_x402_config(&self, agent: &Pubkey) -> ClientResult<X402Config> {
+        self.fetch(&find_x402_config_pda(agent).0)
+    }
+
+    pub fn get_payment_record(&self, record: &Pubkey) -> ClientResult<X402PaymentRecord> {
+        self.fetch(record)
+    }
+
+    /// Payment records matching every given filter, with their addresses,
+    /// selected by the RPC node through memcmp filters on the record's
+    /// fixed-offset fields
+    pub fn get_payment_records(
+        &self,
+        agent: Option<&Pubkey>,
+        payer: Option<&Pubkey>,
+        status: Option<PaymentStatus>,
+    ) -> ClientResult<Vec<(Pubkey, X402PaymentRecord)>> {
+        let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &X402PaymentRecord::DISCRIMINATOR,
+        ))];
+        if let Some(agent) = agent {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                X402PaymentRecord::AGENT_OFFSET,
+                agent.as_ref(),
+            )));
+        }
+        if let Some(payer) = payer {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                X402PaymentRecord::PAYER_OFFSET,
+                payer.as_ref(),
+            )));
+        }
+        if let Some(status) = status {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                X402PaymentRecord::STATUS_OFFSET,
+                &[status as u8],
+            )));
+        }
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        self.rpc
+            .get_program_accounts_with_config(&PROGRAM_ID, config)?
+            .into_iter()
+            .map(|(address, account)| Ok((address, decode(&address, &account.data)?)))
+            .collect()
+    }
+
+    pub fn get_dca(&self, agent: &Pubkey, owner: &Pubkey) -> ClientResult<DcaSchedule> {
+        self.fetch(&find_dca_pda(agent, owner).0)
+    }
+
+    pub fn get_snapshot(&self, agent: &Pubkey, snapshot_id: u64) -> ClientResult<HolderSnapshot> {
+        self.fetch(&find_snapshot_pda(agent, snapshot_id).0)
+    }
+
+    /// `buyer`'s first-buy marker; `AccountNotFound` until their first buy
+    pub fn get_buyer_record(&self, agent: &Pubkey, buyer: &Pubkey) -> ClientResult<BuyerRecord> {
+        self.fetch(&find_buyer_record_pda(agent, buyer).0)
+    }
+
+    pub fn get_early_buyer_rewards(&self, agent: &Pubkey) -> ClientResult<EarlyBuyerRewards> {
+        self.fetch(&find_early_buyer_rewards_pda(agent).0)
+    }
+
+    pub fn get_liquidity_mining(&self, agent: &Pubkey) -> ClientResult<LiquidityMining> {
+        self.fetch(&find_liquidity_mining_pda(agent).0)
+    }
+
+    pub fn get_lp_position(
+        &self,
+        agent: &Pubkey,
+        owner: &Pubkey,
+    ) -> ClientResult<LiquidityMiningPosition> {
+        self.fetch(&find_lm_position_pda(&find_liquidity_mining_pda(agent).0, owner).0)
+    }
+
+    pub fn get_migration(&self, agent: &Pubkey) -> ClientResult<TokenMigration> {
+        self.fetch(&find_migration_pda(agent).0)
+    }
+
+    pub fn get_index_basket(&self, basket: &Pubkey) -> ClientResult<IndexBasket> {
+        self.fetch(basket)
+    }
+
+    pub fn get_keeper_task(&self, task: &Pubkey) -> ClientResult<KeeperTask> {
+        self.fetch(task)
+    }
+
+    /// Every registered keeper task, with its address
+    pub fn get_keeper_tasks(&self) -> ClientResult<Vec<(Pubkey, KeeperTask)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                0,
+                &KeeperTask::DISCRIMINATOR,
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        self.rpc
+            .get_program_accounts_with_config(&PROGRAM_ID, config)?
+            .into_iter()
+            .map(|(address, account)| Ok((address, decode(&address, &account.data)?)))
+            .collect()
+    }
+
+    pub fn get_provider_bond(&self, agent: &Pubkey) -> ClientResult<ProviderBond> {
+        self.fetch(&find_provider_bond_pda(agent).0)
+    }
+
+    pub fn get_dispute(&self, payment_record: &Pubkey) -> ClientResult<Dispute> {
+        self.fetch(&find_dispute_pda(payment_record).0)
+    }
+
+    pub fn get_affiliate(&self, wallet: &Pubkey) -> ClientResult<Affiliate> {
+        self.fetch(&find_affiliate_pda(wallet).0)
+    }
+
+    pub fn get_revenue_split(&self, agent: &Pubkey) -> ClientResult<RevenueSplit> {
+        self.fetch(&find_revenue_split_pda(agent).0)
+    }
+
+    pub fn get_payment_stream(&self, stream: &Pubkey) -> ClientResult<PaymentStream> {
+        self.fetch(stream)
+    }
+
+    /// Spending report of a paying wallet or agent
+    pub fn get_consumer_stats(&self, consumer: &Pubkey) -> ClientResult<ConsumerStats> {
+        self.fetch(&find_consumer_stats_pda(consumer).0)
+    }
+
+    pub fn get_service_agreement(&self, agreement: &Pubkey) -> ClientResult<ServiceAgreement> {
+        self.fetch(agreement)
+    }
+
+    pub fn get_service_quote(
+        &self,
+        agent: &Pubkey,
+        payer: &Pubkey,
+        service_id: &[u8; 32],
+    ) -> ClientResult<ServiceQuote> {
+        self.fetch(&find_service_quote_pda(agent, payer, service_id).0)
+    }
+
+    pub fn get_insurance_policy(&self, payment_record: &Pubkey) -> ClientResult<InsurancePolicy> {
+        self.fetch(&find_insurance_policy_pda(payment_record).0)
+    }
+
+    pub fn get_protocol_liquidity(&self) -> ClientResult<ProtocolLiquidity> {
+        self.fetch(&find_protocol_liquidity_pda().0)
+    }
+
+    pub fn get_price_history(&self, agent: &Pubkey) -> ClientResult<PriceHistory> {
+        self.fetch(&find_price_history_pda(agent).0)
+    }
+
+    pub fn get_trending(&self) -> ClientResult<TrendingLeaderboard> {
+        self.fetch(&find_trending_pda().0)
+    }
+
+    /// The agent's TWAP (nano-lamports per token) over at least
+    /// `window_slots` up to the current slot; `None` without enough history
+    pub fn get_twap(&self, agent: &Pubkey, window_slots: u64) -> ClientResult<Option<u64>> {
+        let agent = self.get_agent(agent)?;
+        let slot = self.rpc.get_slot()?;
+        Ok(agent.get_twap(window_slots, slot))
+    }
+
+    /// The agent's price, market cap and reserves as `view_price` reports
+    /// them to CPI callers, read by simulating the instruction
+    pub fn view_price(&self, agent: &Pubkey) -> ClientResult<PriceView> {
+        self.simulate_view(instructions::view_price(agent))
+    }
+
+    /// The agent's capabilities, service prices and operator keys as
+    /// `get_agent_manifest` reports them, read by simulating the
+    /// instruction. `mints` are the payment tokens to check the agent
+    /// accepts.
+    pub fn get_agent_manifest(
+        &self,
+        agent: &Pubkey,
+        mints: &[Pubkey],
+    ) -> ClientResult<AgentManifest> {
+        let ix = match self.get_x402_config(agent) {
+            Ok(config) => {
+                let token_accounts: Vec<Pubkey> = mints
+                    .iter()
+                    .map(|mint| get_associated_token_address(&config.payment_recipient, mint))
+                    .collect();
+                instructions::get_agent_manifest(agent, true, &token_accounts)
+            }
+            Err(ClientError::AccountNotFound(_)) => {
+                instructions::get_agent_manifest(agent, false, &[])
+            }
+            Err(e) => return Err(e),
+        };
+        self.simulate_view(ix)
+    }
+
+    /// Layout of the account type with `discriminator` as the deployed
+    /// program reports it, read by simulating `get_layout_info`. Compare
+    /// against `registry::ACCOUNT_LAYOUTS` to detect a program upgrade that
+    /// changed a layout.
+    pub fn get_layout_info(&self, discriminator: [u8; 8]) -> ClientResult<LayoutInfo> {
+        self.simulate_view(instructions::get_layout_info(discriminator))
+    }
+
+    /// Simulate a view instruction and decode its return data
+    fn simulate_view<T: AnchorDeserialize>(&self, ix: Instruction) -> ClientResult<T> {
+        let tx = Transaction::new_with_payer(&[ix], Some(&self.payer()));
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let result = self
+            .rpc
+            .simulate_transaction_with_config(&tx, config)?
+            .value;
+        if let Some(err) = result.err {
+            return Err(ClientError::Simulation(err.to_string()));
+        }
+        let (data, _) = result
+            .return_data
+            .ok_or_else(|| ClientError::Simulation("no return data".to_string()))?
+            .data;
+        let data = STANDARD
+            .decode(data)
+            .map_err(|e| ClientError::Simulation(e.to_string()))?;
+        T::try_from_slice(&data).map_err(|e| ClientError::Simulation(e.to_string()))
+    }
+
+    /// `address` if a `T` account has been initialized there
+    fn existing<T: AccountDeserialize>(&self, address: Pubkey) -> ClientResult<Option<Pubkey>> {
+        match self.fetch::<T>(&address) {
+            Ok(_) => Ok(Some(address)),
+            Err(ClientError::AccountNotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The agent's candle accumulator address if it has been initialized,
+    /// for passing to trades
+    pub fn price_history_address(&self, agent: &Pubkey) -> ClientResult<Option<Pubkey>> {
+        self.existing::<PriceHistory>(find_price_history_pda(agent).0)
+    }
+
+    /// The trending leaderboard address if it has been initialized, for
+    /// passing to trades
+    pub fn trending_address(&self) -> ClientResult<Option<Pubkey>> {
+        self.existing::<TrendingLeaderboard>(find_trending_pda().0)
+    }
+
+    pub fn get_lock(
+        &self,
+        agent: &Pubkey,
+        owner: &Pubkey,
+        lock_id: u64,
+    ) -> ClientResult<TokenLock> {
+        self.fetch(&find_lock_pda(agent, owner, lock_id).0)
+    }
+
+    pub fn get_staking_pool(&self, agent: &Pubkey) -> ClientResult<StakingPool> {
+        self.fetch(&find_staking_pool_pda(agent).0)
+    }
+
+    pub fn get_stake_position(
+        &self,
+        agent: &Pubkey,
+        owner: &Pubkey,
+    ) -> ClientResult<StakePosition> {
+        self.fetch(&find_stake_position_pda(&find_staking_pool_pda(agent).0, owner).0)
+    }
+
+    pub fn get_governance(&self, agent: &Pubkey) -> ClientResult<Governance> {
+        self.fetch(&find_governance_pda(agent).0)
+    }
+
+    pub fn get_proposal(&self, agent: &Pubkey, proposal_id: u64) -> ClientResult<Proposal> {
+        self.fetch(&find_proposal_pda(agent, proposal_id).0)
+    }
+
+    pub fn get_dao_treasury(&self, agent: &Pubkey) -> ClientResult<DaoTreasury> {
+        self.fetch(&find_dao_treasury_pda(agent).0)
+    }
+
+    pub fn get_presale(&self, agent: &Pubkey) -> ClientResult<Presale> {
+        self.fetch(&find_presale_pda(agent).0)
+    }
+
+    pub fn get_commitment(
+        &self,
+        agent: &Pubkey,
+        committer: &Pubkey,
+    ) -> ClientResult<PresaleCommitment> {
+        self.fetch(&find_commitment_pda(&find_presale_pda(agent).0, committer).0)
+    }
+
+    // ========================================================================
+    // Quotes
+    // ========================================================================
+
+    /// `owner`'s stake position in the platform token's staking pool and the
+    /// platform fee discount (bps) it earns; `None` without discounts or stake
+    pub fn platform_discount(
+        &self,
+        factory: &AgentFactory,
+        owner: &Pubkey,
+    ) -> ClientResult<Option<(Pubkey, u16)>> {
+        if factory.platform_mint == Pubkey::default() {
+            return Ok(None);
+        }
+        let position = find_stake_position_pda(&factory.platform_staking_pool, owner).0;
+        let stake = match self.fetch::<StakePosition>(&position) {
+            Ok(stake) => stake,
+            Err(ClientError::AccountNotFound(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let discount_bps = factory
+            .fee_discount_bps(&stake, owner)
+            .map_err(|e| ClientError::Quote(e.to_string()))?;
+        Ok(Some((position, discount_bps)))
+    }
+
+    pub fn quote_buy(&self, agent: &Pubkey, sol_amount: u64) -> ClientResult<BuyQuote> {
+        quote_buy(&self.get_agent(agent)?.bonding_curve, sol_amount)
+    }
+
+    pub fn quote_sell(&self, agent: &Pubkey, token_amount: u64) -> ClientResult<SellQuote> {
+        quote_sell(&self.get_agent(agent)?.bonding_curve, token_amount)
+    }
+
+    pub fn quote_redeem(&self, agent: &Pubkey, token_amount: u64) -> ClientResult<u64> {
+        quote_redeem(&self.get_agent(agent)?.bonding_curve, token_amount)
+    }
+
+    // ========================================================================
+    // Transactions
+    // ========================================================================
+
+    /// Sign with the payer (plus extra signers) and send
+    pub fn send(
+        &self,
+        instructions: &[Instruction],
+        extra_signers: &[&Keypair],
+    ) -> ClientResult<Signature> {
+        let mut signers: Vec<&Keypair> = vec![&self.payer];
+        signers.extend_from_slice(extra_signers);
+
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &signers,
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+
+    /// Queue a factory setting change; the payer must be the factory
+    /// authority. Returns the action id and the signature.
+    ///
+    /// Multisig authorities build the instruction with
+    /// `instructions::queue_admin_action` and propose it in the multisig.
+    pub fn queue_admin_action(&self, action: AdminAction) -> ClientResult<(u64, Signature)> {
+        let action_id = self.get_factory()?.admin_action_count;
+        let platform_agent = match action {
+            AdminAction::SetFeeDiscounts {
+                platform_mint,
+                staking_pool,
+                ..
+            } if platform_mint != Pubkey::default() => {
+                Some(self.fetch::<StakingPool>(&staking_pool)?.agent)
+            }
+            _ => None,
+        };
+        let ix = instructions::queue_admin_action(
+            &self.payer(),
+            &self.payer(),
+            action_id,
+            action,
+            platform_agent.as_ref(),
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((action_id, signature))
+    }
+
+    /// Apply a queued admin action whose timelock has expired
+    pub fn execute_admin_action(&self, action_id: u64) -> ClientResult<Signature> {
+        let rent_payer = self.get_admin_action(action_id)?.rent_payer;
+        self.send(
+            &[instructions::execute_admin_action(action_id, &rent_payer)],
+            &[],
+        )
+    }
+
+    pub fn cancel_admin_action(&self, action_id: u64) -> ClientResult<Signature> {
+        let rent_payer = self.get_admin_action(action_id)?.rent_payer;
+        self.send(
+            &[instructions::cancel_admin_action(
+                &self.payer(),
+                action_id,
+                &rent_payer,
+            )],
+            &[],
+        )
+    }
+
+    /// Open the insurance fund; the payer must be the factory authority
+    pub fn initialize_insurance_fund(&self) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::initialize_insurance_fund(
+                &self.payer(),
+                &self.payer(),
+            )],
+            &[],
+        )
+    }
+
+    pub fn collect_insurance_fees(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::collect_insurance_fees(agent)], &[])
+    }
+
+    /// Pay an agent's accrued public goods fees to the factory's public
+    /// goods recipient
+    pub fn collect_public_goods_fees(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        let recipient = self.get_factory()?.public_goods_recipient;
+        self.send(
+            &[instructions::collect_public_goods_fees(agent, &recipient)],
+            &[],
+        )
+    }
+
+    pub fn fund_insurance(&self, amount: u64) -> ClientResult<Signature> {
+        self.send(&[instructions::fund_insurance(&self.payer(), amount)], &[])
+    }
+
+    /// Pay an insurance claim; the payer must be the factory authority
+    pub fn pay_claim(
+        &self,
+        agent: &Pubkey,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::pay_claim(
+                &self.payer(),
+                agent,
+                recipient,
+                amount,
+            )],
+            &[],
+        )
+    }
+
+    /// Open the protocol-owned liquidity vault; the payer must be the factory
+    /// authority
+    pub fn initialize_protocol_liquidity(&self) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::initialize_protocol_liquidity(
+                &self.payer(),
+                &self.payer(),
+            )],
+            &[],
+        )
+    }
+
+    /// Withdraw protocol-owned SOL and/or `mint` tokens to `recipient`; the
+    /// payer must be the factory authority. The recipient's ATA is created if
+    /// missing.
+    pub fn withdraw_protocol_liquidity(
+        &self,
+        recipient: &Pubkey,
+        lamports: u64,
+        token_amount: u64,
+        mint: Option<&Pubkey>,
+    ) -> ClientResult<Signature> {
+        let mut ixs = Vec::new();
+        if let Some(mint) = mint {
+            ixs.push(create_associated_token_account_idempotent(
+                &self.payer(),
+                recipient,
+                mint,
+                &anchor_spl::token::ID,
+            ));
+        }
+        ixs.push(instructions::withdraw_protocol_liquidity(
+            &self.payer(),
+            recipient,
+            lamports,
+            token_amount,
+            mint,
+        ));
+        self.send(&ixs, &[])
+    }
+
+    /// Record a creator's rugged agents and blacklist status; the payer must
+    /// be the factory authority
+    pub fn set_creator_standing(
+        &self,
+        creator: &Pubkey,
+        rugged_agents: u16,
+        is_blacklisted: bool,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_creator_standing(
+                &self.payer(),
+                &self.payer(),
+                creator,
+                rugged_agents,
+                is_blacklisted,
+            )],
+            &[],
+        )
+    }
+
+    /// Reserve a name or symbol for `owner` (default = release it); the payer
+    /// must be the factory authority
+    pub fn reserve_name(&self, name: &str, owner: &Pubkey) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::reserve_name(
+                &self.payer(),
+                &self.payer(),
+                name,
+                owner,
+            )],
+            &[],
+        )
+    }
+
+    /// Lower the platform and creator fees on an agent's trades, effective
+    /// immediately; the payer must be the factory authority
+    pub fn set_agent_fee_override(
+        &self,
+        agent: &Pubkey,
+        platform_fee_bps: u16,
+        creator_fee_bps: u16,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_agent_fee_override(
+                &self.payer(),
+                agent,
+                platform_fee_bps,
+                creator_fee_bps,
+            )],
+            &[],
+        )
+    }
+
+    /// Hard-stop x402 payments to an agent, effective immediately; the payer
+    /// must be the factory authority
+    pub fn freeze_x402(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::freeze_x402(&self.payer(), agent)], &[])
+    }
+
+    /// Lift a freeze on an agent's x402 payments; the payer must be the
+    /// factory authority
+    pub fn thaw_x402(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::thaw_x402(&self.payer(), agent)], &[])
+    }
+
+    /// Cap agent creations per window (0 = no limit), effective immediately;
+    /// the payer must be the factory authority
+    pub fn set_creation_rate_limit(
+        &self,
+        max_creations_per_window: u32,
+        window_secs: i64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_creation_rate_limit(
+                &self.payer(),
+                max_creations_per_window,
+                window_secs,
+            )],
+            &[],
+        )
+    }
+
+    /// Value the quote mint at `lamports` per `QUOTE_RATE_UNIT` base units
+    /// for disputes and insurance, effective immediately; the payer must be
+    /// the factory authority
+    pub fn set_quote_lamport_rate(&self, lamports: u64) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_quote_lamport_rate(&self.payer(), lamports)],
+            &[],
+        )
+    }
+
+    /// Create the next agent; returns the new agent PDA and the signature
+    pub fn create_agent(&self, args: CreateAgentArgs) -> ClientResult<(Pubkey, Signature)> {
+        let factory = self.get_factory()?;
+        let ix = instructions::create_agent(
+            factory.total_agents,
+            &self.payer(),
+            &factory.platform_treasury,
+            args,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_agent_pda(factory.total_agents).0, signature))
+    }
+
+    /// Create the next agent through a commit-reveal launch: commit to the
+    /// hash of its fields, then reveal them in a later slot, so its name and
+    /// symbol never show up in a pending transaction that can be cloned
+    pub fn create_agent_sealed(&self, args: CreateAgentArgs) -> ClientResult<(Pubkey, Signature)> {
+        // A fresh keypair's secret is 32 random bytes
+        let salt = Keypair::new().secret().to_bytes();
+        self.send(
+            &[instructions::commit_agent(&self.payer(), &args, &salt)],
+            &[],
+        )?;
+
+        // The commitment's slot is complete once it's confirmed, so the
+        // reveal lands in a later one
+        let factory = self.get_factory()?;
+        let ix = instructions::reveal_agent(
+            factory.total_agents,
+            &self.payer(),
+            &factory.platform_treasury,
+            args,
+            salt,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_agent_pda(factory.total_agents).0, signature))
+    }
+
+    /// Create the next agent with `reserve_bps` of its supply set aside for
+    /// exchange listings until `release_delay_secs` after graduation
+    pub fn create_agent_with_listing_reserve(
+        &self,
+        args: CreateAgentArgs,
+        reserve_bps: u16,
+        release_delay_secs: i64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let factory = self.get_factory()?;
+        let ix = instructions::create_agent_with_listing_reserve(
+            factory.total_agents,
+            &self.payer(),
+            &factory.platform_treasury,
+            args,
+            reserve_bps,
+            release_delay_secs,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_agent_pda(factory.total_agents).0, signature))
+    }
+
+    /// Create an agent, configure x402 and make an optional dev buy in one
+    /// transaction; the dev buy's slippage is applied to a quote on a fresh
+    /// constant-product curve
+    pub fn create_agent_full(
+        &self,
+        args: CreateAgentArgs,
+        x402: X402Settings,
+        dev_buy_sol_amount: u64,
+        slippage_bps: u64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let factory = self.get_factory()?;
+        let min_tokens_out = if dev_buy_sol_amount > 0 {
+            with_slippage(
+                quote_buy(&BondingCurve::new(), dev_buy_sol_amount)?.tokens_out,
+                slippage_bps,
+            )
+        } else {
+            0
+        };
+        let ix = instructions::create_agent_full(
+            factory.total_agents,
+            &self.payer(),
+            &factory.platform_treasury,
+            args,
+            x402,
+            dev_buy_sol_amount,
+            min_tokens_out,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_agent_pda(factory.total_agents).0, signature))
+    }
+
+    /// Launch a bundle of agents whose names share `name_prefix`, packing
+    /// as many `create_agent`s into each transaction as fit in its
+    /// `PACKET_DATA_SIZE` (1232 bytes). With token metadata a `create_agent`
+    /// references 23 accounts and two no longer fit (about 1350 bytes), so
+    /// in practice each agent gets its own transaction. Each transaction lands atomically with consecutive ids; if
+    /// another agent is created first, that transaction fails and the agents
+    /// of earlier ones stay created. Returns each transaction's agents and
+    /// signature.
+    pub fn create_agent_batch(
+        &self,
+        name_prefix: &str,
+        agents: Vec<CreateAgentArgs>,
+    ) -> ClientResult<Vec<(Vec<Pubkey>, Signature)>> {
+        let payer = self.payer();
+        let mut remaining = agents;
+        let mut launched = Vec::new();
+        while !remaining.is_empty() {
+            let factory = self.get_factory()?;
+            let first_agent_id = factory.total_agents;
+            let build_chunk = |len: usize| {
+                let mut ixs = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+                    BUNDLE_COMPUTE_UNIT_LIMIT,
+                )];
+                ixs.extend(instructions::create_agent_batch(
+                    first_agent_id,
+                    &payer,
+                    &factory.platform_treasury,
+                    name_prefix,
+                    remaining[..len].to_vec(),
+                ));
+                ixs
+            };
+            // A single agent too large for a transaction is sent anyway and
+            // fails with the RPC's size error
+            let len = (1..=remaining.len())
+                .rev()
+                .find(|&len| transaction_size(&build_chunk(len), &payer) <= PACKET_DATA_SIZE)
+                .unwrap_or(1);
+            let signature = self.send(&build_chunk(len), &[])?;
+            let pdas = (first_agent_id..first_agent_id + len as u64)
+                .map(|agent_id| find_agent_pda(agent_id).0)
+                .collect();
+            launched.push((pdas, signature));
+            remaining.drain(..len);
+        }
+        Ok(launched)
+    }
+
+    /// Create an agent priced by a stepped curve
+    pub fn create_agent_with_curve(
+        &self,
+        args: CreateAgentArgs,
+        tranches: Vec<CurveTranche>,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let factory = self.get_factory()?;
+        let ix = instructions::create_agent_with_curve(
+            factory.total_agents,
+            &self.payer(),
+            &factory.platform_treasury,
+            args,
+            tranches,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_agent_pda(factory.total_agents).0, signature))
+    }
+
+    /// Create an agent whose creator fee is waived or goes to its stakers
+    pub fn create_agent_with_fee_mode(
+        &self,
+        args: CreateAgentArgs,
+        creator_fee_mode: CreatorFeeMode,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let factory = self.get_factory()?;
+        let ix = instructions::create_agent_with_fee_mode(
+            factory.total_agents,
+            &self.payer(),
+            &factory.platform_treasury,
+            args,
+            creator_fee_mode,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_agent_pda(factory.total_agents).0, signature))
+    }
+
+    /// Create an agent launched as a liquidity bootstrapping (Dutch auction) curve
+    pub fn create_agent_lbp(
+        &self,
+        args: CreateAgentArgs,
+        duration_secs: i64,
+        start_price_multiplier: u64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let factory = self.get_factory()?;
+        let ix = instructions::create_agent_lbp(
+            factory.total_agents,
+            &self.payer(),
+            &factory.platform_treasury,
+            args,
+            duration_secs,
+            start_price_multiplier,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_agent_pda(factory.total_agents).0, signature))
+    }
+
+    /// Buy with a slippage tolerance applied to the local quote
+    pub fn buy(
+        &self,
+        agent: &Pubkey,
+        sol_amount: u64,
+        slippage_bps: u64,
+    ) -> ClientResult<Signature> {
+        self.buy_for(agent, &self.payer(), sol_amount, slippage_bps)
+    }
+
+    /// Buy paid by the payer with tokens minted to `recipient`; the
+    /// recipient's ATA is created if missing
+    pub fn buy_for(
+        &self,
+        agent: &Pubkey,
+        recipient: &Pubkey,
+        sol_amount: u64,
+        slippage_bps: u64,
+    ) -> ClientResult<Signature> {
+        let agent_account = self.get_agent(agent)?;
+        let factory = self.get_factory()?;
+        let discount = self.platform_discount(&factory, &self.payer())?;
+        let price_history = self.price_history_address(agent)?;
+        let trending = self.trending_address()?;
+        let quote = quote_buy_discounted(
+            &agent_account.bonding_curve,
+            sol_amount,
+            discount.map_or(0, |(_, bps)| bps),
+        )?;
+
+        let mint = find_mint_pda(agent).0;
+        let create_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            recipient,
+            &mint,
+            &anchor_spl::token::ID,
+        );
+        let buy = instructions::buy_tokens(
+            agent,
+            &agent_account.creator,
+            &factory.platform_treasury,
+            &self.payer(),
+            recipient,
+            sol_amount,
+            with_slippage(quote.tokens_out, slippage_bps),
+            discount.as_ref().map(|(position, _)| position),
+            price_history.as_ref(),
+            trending.as_ref(),
+        );
+        self.send(&[create_ata, buy], &[])
+    }
+
+    /// Sell with a slippage tolerance applied to the local quote
+    pub fn sell(
+        &self,
+        agent: &Pubkey,
+        token_amount: u64,
+        slippage_bps: u64,
+    ) -> ClientResult<Signature> {
+        let agent_account = self.get_agent(agent)?;
+        let factory = self.get_factory()?;
+        let discount = self.platform_discount(&factory, &self.payer())?;
+        let price_history = self.price_history_address(agent)?;
+        let trending = self.trending_address()?;
+        let quote = quote_sell_discounted(
+            &agent_account.bonding_curve,
+            token_amount,
+            discount.map_or(0, |(_, bps)| bps),
+        )?;
+
+        let ix = instructions::sell_tokens(
+            agent,
+            &agent_account.creator,
+            &factory.platform_treasury,
+            &self.payer(),
+            token_amount,
+            with_slippage(quote.gross_sol_out, slippage_bps),
+            discount.as_ref().map(|(position, _)| position),
+            price_history.as_ref(),
+            trending.as_ref(),
+        );
+        self.send(&[ix], &[])
+    }
+
+    /// Opt one of the payer's agents into the refund guarantee
+    pub fn enable_refund_guarantee(&self, agent: &Pubkey, days: u16) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::enable_refund_guarantee(
+                agent,
+                &self.payer(),
+                days,
+            )],
+            &[],
+        )
+    }
+
+    /// Redeem tokens of an agent that missed its guaranteed graduation deadline
+    pub fn redeem(&self, agent: &Pubkey, token_amount: u64) -> ClientResult<Signature> {
+        let sol_out = self.quote_redeem(agent, token_amount)?;
+        self.send(
+            &[instructions::redeem_for_sol(
+                agent,
+                &self.payer(),
+                token_amount,
+                sol_out,
+            )],
+            &[],
+        )
+    }
+
+    pub fn graduate(&self, agent: &Pubkey, dex_program: &Pubkey) -> ClientResult<Signature> {
+        let creator = self.get_agent(agent)?.creator;
+        let protocol_liquidity = self.get_factory()?.protocol_liquidity_bps > 0;
+        let mut ixs = Vec::new();
+        if protocol_liquidity {
+            // The vault's token account receiving its share of the tokens
+            ixs.push(create_associated_token_account_idempotent(
+                &self.payer(),
+                &find_protocol_liquidity_pda().0,
+                &find_mint_pda(agent).0,
+                &anchor_spl::token::ID,
+            ));
+        }
+        ixs.push(instructions::graduate_agent(
+            agent,
+            &creator,
+            &self.payer(),
+            dex_program,
+            protocol_liquidity,
+        ));
+        self.send(&ixs, &[])
+    }
+
+    /// Graduate every eligible agent among `agents` (at most 8) in one
+    /// transaction; the rest are skipped
+    pub fn graduate_batch(&self, agents: &[Pubkey]) -> ClientResult<Signature> {
+        let protocol_liquidity = self.get_factory()?.protocol_liquidity_bps > 0;
+        let mut ixs = Vec::new();
+        let mut pairs = Vec::with_capacity(agents.len());
+        for agent in agents {
+            pairs.push((*agent, self.get_agent(agent)?.creator));
+            if protocol_liquidity {
+                ixs.push(create_associated_token_account_idempotent(
+                    &self.payer(),
+                    &find_protocol_liquidity_pda().0,
+                    &find_mint_pda(agent).0,
+                    &anchor_spl::token::ID,
+                ));
+            }
+        }
+        ixs.push(instructions::graduate_batch(
+            &self.payer(),
+            &pairs,
+            protocol_liquidity,
+        ));
+        self.send(&ixs, &[])
+    }
+
+    /// Take over the creator role of an agent whose ownership NFT the payer
+    /// holds
+    pub fn claim_agent_ownership(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::claim_agent_ownership(agent, &self.payer())],
+            &[],
+        )
+    }
+
+    /// Hand one of the payer's agents, with its ownership NFT, to
+    /// `new_creator`
+    pub fn transfer_agent_ownership(
+        &self,
+        agent: &Pubkey,
+        new_creator: &Pubkey,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::transfer_agent_ownership(
+                agent,
+                &self.payer(),
+                new_creator,
+            )],
+            &[],
+        )
+    }
+
+    /// Set the royalty the payer, as original creator, keeps on the agent's
+    /// creator fees after a transfer
+    pub fn set_creator_royalty(&self, agent: &Pubkey, royalty_bps: u16) -> ClientResult<Signature> {
+        let ownership_mint = find_ownership_mint_pda(agent).0;
+        let holds_ownership_nft = self
+            .fetch::<anchor_spl::token::TokenAccount>(&get_associated_token_address(
+                &self.payer(),
+                &ownership_mint,
+            ))
+            .is_ok_and(|account| account.amount == 1);
+        self.send(
+            &[instructions::set_creator_royalty(
+                agent,
+                &self.payer(),
+                holds_ownership_nft,
+                royalty_bps,
+            )],
+            &[],
+        )
+    }
+
+    pub fn claim_royalties(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::claim_royalties(agent, &self.payer())], &[])
+    }
+
+    /// Turn same-slot sell protection on or off for one of the payer's agents
+    pub fn set_same_slot_protection(
+        &self,
+        agent: &Pubkey,
+        enabled: bool,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_same_slot_protection(
+                agent,
+                &self.payer(),
+                enabled,
+            )],
+            &[],
+        )
+    }
+
+    /// Switch the model one of the payer's agents claims to run on; it must
+    /// be on the factory allowlist
+    pub fn set_agent_model(&self, agent: &Pubkey, model: &str) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_agent_model(agent, &self.payer(), model)],
+            &[],
+        )
+    }
+
+    /// Add a discovery tag to one of the payer's agents
+    pub fn add_agent_tag(&self, agent: &Pubkey, tag: &str) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::add_agent_tag(agent, &self.payer(), tag)],
+            &[],
+        )
+    }
+
+    /// Remove a discovery tag from one of the payer's agents
+    pub fn remove_agent_tag(&self, agent: &Pubkey, tag: &str) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::remove_agent_tag(agent, &self.payer(), tag)],
+            &[],
+        )
+    }
+
+    /// Rename one of the payer's agents, paying the factory's rename fee
+    pub fn rename_agent(
+        &self,
+        agent: &Pubkey,
+        new_name: &str,
+        new_symbol: &str,
+    ) -> ClientResult<Signature> {
+        let treasury = self.get_factory()?.platform_treasury;
+        let agent_account = self.get_agent(agent)?;
+        self.send(
+            &[instructions::rename_agent(
+                agent,
+                &agent_account.mint,
+                &agent_account.ownership_mint,
+                &self.payer(),
+                &treasury,
+                new_name,
+                new_symbol,
+            )],
+            &[],
+        )
+    }
+
+    /// Cap each buy of one of the payer's agents at a share (bps) of the
+    /// remaining curve tokens; 0 lifts the cap
+    pub fn set_max_buy(&self, agent: &Pubkey, max_buy_bps: u16) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_max_buy(agent, &self.payer(), max_buy_bps)],
+            &[],
+        )
+    }
+
+    /// Choose whether the unsold curve tokens of one of the payer's agents
+    /// are burned at graduation instead of going into the pool
+    pub fn set_graduation_burn(
+        &self,
+        agent: &Pubkey,
+        burn_remaining: bool,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_graduation_burn(
+                agent,
+                &self.payer(),
+                burn_remaining,
+            )],
+            &[],
+        )
+    }
+
+    /// Claim the creator fees one of the payer's agents has streamed out
+    pub fn claim_creator_fees(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::claim_creator_fees(agent, &self.payer())],
+            &[],
+        )
+    }
+
+    /// Record a holder snapshot for one of the payer's agents; returns the
+    /// snapshot PDA and the signature
+    pub fn create_snapshot(&self, agent: &Pubkey) -> ClientResult<(Pubkey, Signature)> {
+        let snapshot_id = self.get_agent(agent)?.snapshot_count;
+        let ix = instructions::create_snapshot(agent, &self.payer(), snapshot_id);
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_snapshot_pda(agent, snapshot_id).0, signature))
+    }
+
+    /// Create an agent's candle accumulator, paid by the payer; returns the
+    /// price history PDA and the signature
+    pub fn initialize_price_history(&self, agent: &Pubkey) -> ClientResult<(Pubkey, Signature)> {
+        let ix = instructions::initialize_price_history(agent, &self.payer());
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_price_history_pda(agent).0, signature))
+    }
+
+    /// Create the factory trending leaderboard, paid by the payer; returns
+    /// the leaderboard PDA and the signature
+    pub fn initialize_trending(&self) -> ClientResult<(Pubkey, Signature)> {
+        let signature = self.send(&[instructions::initialize_trending(&self.payer())], &[])?;
+        Ok((find_trending_pda().0, signature))
+    }
+
+    /// Lock the payer's tokens until `unlock_ts`; returns the lock PDA and the
+    /// signature
+    pub fn lock_tokens(
+        &self,
+        agent: &Pubkey,
+        lock_id: u64,
+        amount: u64,
+        unlock_ts: i64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let ix = instructions::lock_tokens(agent, &self.payer(), lock_id, amount, unlock_ts);
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_lock_pda(agent, &self.payer(), lock_id).0, signature))
+    }
+
+    pub fn unlock_tokens(&self, agent: &Pubkey, lock_id: u64) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::unlock_tokens(agent, &self.payer(), lock_id)],
+            &[],
+        )
+    }
+
+    /// Release listing reserve tokens of one of the payer's agents to
+    /// `destination`, a token account of the agent's mint
+    pub fn release_listing_reserve(
+        &self,
+        agent: &Pubkey,
+        destination: &Pubkey,
+        amount: u64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::release_listing_reserve(
+                agent,
+                &self.payer(),
+                destination,
+                amount,
+            )],
+            &[],
+        )
+    }
+
+    /// Fund early buyer bonuses for one of the payer's agents; returns the
+    /// rewards PDA and the signature
+    pub fn create_early_buyer_rewards(
+        &self,
+        agent: &Pubkey,
+        max_buyers: u64,
+        bonus_per_buyer: u64,
+        claim_deadline: i64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let ix = instructions::create_early_buyer_rewards(
+            agent,
+            &self.payer(),
+            max_buyers,
+            bonus_per_buyer,
+            claim_deadline,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_early_buyer_rewards_pda(agent).0, signature))
+    }
+
+    /// Claim the payer's early buyer bonus; the payer's ATA is created if
+    /// missing
+    pub fn claim_early_buyer_bonus(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        let create_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &self.payer(),
+            &find_mint_pda(agent).0,
+            &anchor_spl::token::ID,
+        );
+        let claim = instructions::claim_early_buyer_bonus(agent, &self.payer());
+        self.send(&[create_ata, claim], &[])
+    }
+
+    /// Return the unclaimed bonuses of one of the payer's agents after the
+    /// claim deadline
+    pub fn close_early_buyer_rewards(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::close_early_buyer_rewards(
+                agent,
+                &self.payer(),
+            )],
+            &[],
+        )
+    }
+
+    /// Open liquidity mining for the pool of one of the payer's graduated
+    /// agents; returns the mining PDA and the signature
+    pub fn create_liquidity_mining(
+        &self,
+        agent: &Pubkey,
+        lp_mint: &Pubkey,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let ix = instructions::create_liquidity_mining(agent, &self.payer(), lp_mint);
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_liquidity_mining_pda(agent).0, signature))
+    }
+
+    /// Emit `amount` agent tokens from the payer's ATA over `duration_secs`
+    pub fn fund_liquidity_mining(
+        &self,
+        agent: &Pubkey,
+        amount: u64,
+        duration_secs: i64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::fund_liquidity_mining(
+                agent,
+                &self.payer(),
+                amount,
+                duration_secs,
+            )],
+            &[],
+        )
+    }
+
+    pub fn stake_lp(&self, agent: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        let lp_mint = self.get_liquidity_mining(agent)?.lp_mint;
+        self.send(
+            &[instructions::stake_lp(
+                agent,
+                &self.payer(),
+                &lp_mint,
+                amount,
+            )],
+            &[],
+        )
+    }
+
+    pub fn withdraw_lp(&self, agent: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        let lp_mint = self.get_liquidity_mining(agent)?.lp_mint;
+        self.send(
+            &[instructions::withdraw_lp(
+                agent,
+                &self.payer(),
+                &lp_mint,
+                amount,
+            )],
+            &[],
+        )
+    }
+
+    /// Claim the payer's LP rewards; the payer's ATA is created if missing
+    pub fn claim_lp_rewards(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        let create_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &self.payer(),
+            &find_mint_pda(agent).0,
+            &anchor_spl::token::ID,
+        );
+        let claim = instructions::claim_lp_rewards(agent, &self.payer());
+        self.send(&[create_ata, claim], &[])
+    }
+
+    /// Register a swap from `old_mint` into one of the payer's agent tokens;
+    /// returns the migration PDA and the signature
+    pub fn create_migration(
+        &self,
+        agent: &Pubkey,
+        old_mint: &Pubkey,
+        ratio_numerator: u64,
+        ratio_denominator: u64,
+        deposit_amount: u64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let ix = instructions::create_migration(
+            agent,
+            &self.payer(),
+            old_mint,
+            ratio_numerator,
+            ratio_denominator,
+            deposit_amount,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_migration_pda(agent).0, signature))
+    }
+
+    /// Swap `amount` of the payer's old tokens for agent tokens; the payer's
+    /// agent token ATA is created if missing
+    pub fn migrate_tokens(&self, agent: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        let old_mint = self.get_migration(agent)?.old_mint;
+        let create_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &self.payer(),
+            &find_mint_pda(agent).0,
+            &anchor_spl::token::ID,
+        );
+        let migrate = instructions::migrate_tokens(agent, &self.payer(), &old_mint, amount);
+        self.send(&[create_ata, migrate], &[])
+    }
+
+    /// Define a basket holding `units[i]` base units of `agents[i]`'s token
+    /// per basket token; returns the basket PDA and the signature
+    pub fn create_index_basket(
+        &self,
+        basket_id: u64,
+        name: &str,
+        agents: &[Pubkey],
+        units: Vec<u64>,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let ix = instructions::create_index_basket(
+            &self.payer(),
+            basket_id,
+            name.to_string(),
+            agents,
+            units,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_index_basket_pda(&self.payer(), basket_id).0, signature))
+    }
+
+    /// Deposit the payer's constituents and mint `amount` basket tokens; the
+    /// basket's vaults and the payer's basket ATA are created if missing
+    pub fn mint_basket(&self, basket: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        let mints: Vec<Pubkey> = self
+            .get_index_basket(basket)?
+            .constituents
+            .iter()
+            .map(|constituent| constituent.mint)
+            .collect();
+        let mut ixs: Vec<Instruction> = mints
+            .iter()
+            .map(|mint| {
+                create_associated_token_account_idempotent(
+                    &self.payer(),
+                    basket,
+                    mint,
+                    &anchor_spl::token::ID,
+                )
+            })
+            .collect();
+        ixs.push(create_associated_token_account_idempotent(
+            &self.payer(),
+            &self.payer(),
+            &find_basket_mint_pda(basket).0,
+            &anchor_spl::token::ID,
+        ));
+        ixs.push(instructions::mint_basket(
+            basket,
+            &self.payer(),
+            &mints,
+            amount,
+        ));
+        self.send(&ixs, &[])
+    }
+
+    /// Burn `amount` of the payer's basket tokens for the constituents; the
+    /// payer's constituent ATAs are created if missing
+    pub fn redeem_basket(&self, basket: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        let mints: Vec<Pubkey> = self
+            .get_index_basket(basket)?
+            .constituents
+            .iter()
+            .map(|constituent| constituent.mint)
+            .collect();
+        let mut ixs: Vec<Instruction> = mints
+            .iter()
+            .map(|mint| {
+                create_associated_token_account_idempotent(
+                    &self.payer(),
+                    &self.payer(),
+                    mint,
+                    &anchor_spl::token::ID,
+                )
+            })
+            .collect();
+        ixs.push(instructions::redeem_basket(
+            basket,
+            &self.payer(),
+            &mints,
+            amount,
+        ));
+        self.send(&ixs, &[])
+    }
+
+    /// Register a keeper task tipping `tip_lamports` per unit of work on
+    /// `target`; returns the task PDA and the signature
+    pub fn register_keeper_task(
+        &self,
+        task_id: u64,
+        kind: KeeperTaskKind,
+        target: &Pubkey,
+        tip_lamports: u64,
+        deposit: u64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let ix = instructions::register_keeper_task(
+            &self.payer(),
+            task_id,
+            kind,
+            target,
+            tip_lamports,
+            deposit,
+        );
+        let signature = self.send(&[ix], &[])?;
+        Ok((find_keeper_task_pda(&self.payer(), task_id).0, signature))
+    }
+
+    pub fn fund_keeper_task(&self, task: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::fund_keeper_task(task, &self.payer(), amount)],
+            &[],
+        )
+    }
+
+    /// Run a keeper task's crank and collect its tip in one transaction.
+    /// Fails (in simulation, without paying fees) when no work is due.
+    pub fn crank_keeper_task(&self, task: &Pubkey) -> ClientResult<Signature> {
+        let task_account = self.get_keeper_task(task)?;
+        let target = task_account.target;
+        let mut ixs = Vec::new();
+        match task_account.kind {
+            KeeperTaskKind::Dca => {
+                let dca: DcaSchedule = self.fetch(&target)?;
+                let agent = self.get_agent(&dca.agent)?;
+                let factory = self.get_factory()?;
+                ixs.push(create_associated_token_account_idempotent(
+                    &self.payer(),
+                    &dca.owner,
+                    &agent.mint,
+                    &anchor_spl::token::ID,
+                ));
+                let price_history = self.price_history_address(&dca.agent)?;
+                let trending = self.trending_address()?;
+                ixs.push(instructions::execute_dca(
+                    &dca.agent,
+                    &dca.owner,
+                    &agent.creator,
+                    &factory.platform_treasury,
+                    &self.payer(),
+                    price_history.as_ref(),
+                    trending.as_ref(),
+                ));
+            }
+            KeeperTaskKind::Graduation => {
+                let creator = self.get_agent(&target)?.creator;
+                let protocol_liquidity = self.get_factory()?.protocol_liquidity_bps > 0;
+                if protocol_liquidity {
+                    ixs.push(create_associated_token_account_idempotent(
+                        &self.payer(),
+                        &find_protocol_liquidity_pda().0,
+                        &find_mint_pda(&target).0,
+                        &anchor_spl::token::ID,
+                    ));
+                }
+                ixs.push(instructions::graduate_agent(
+                    &target,
+                    &creator,
+                    &self.payer(),
+                    &Pubkey::default(),
+                    protocol_liquidity,
+                ));
+            }
+            KeeperTaskKind::AdminAction => {
+                let action: QueuedAdminAction = self.fetch(&target)?;
+                ixs.push(instructions::execute_admin_action(
+                    action.action_id,
+                    &action.rent_payer,
+                ));
+            }
+        }
+        ixs.push(instructions::crank_keeper_task(
+            task,
+            &target,
+            &self.payer(),
+        ));
+        self.send(&ixs, &[])
+    }
+
+    pub fn cancel_keeper_task(&self, task: &Pubkey) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::cancel_keeper_task(task, &self.payer())],
+            &[],
+        )
+    }
+
+    /// Open the agent's staking pool; the payer must be the agent creator
+    pub fn create_staking_pool(
+        &self,
+        agent: &Pubkey,
+        staking_fee_bps: u16,
+        emission_rate_cap: u64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::create_staking_pool(
+                agent,
+                &self.payer(),
+                staking_fee_bps,
+                emission_rate_cap,
+            )],
+            &[],
+        )
+    }
+
+    pub fn update_staking_pool(
+        &self,
+        agent: &Pubkey,
+        staking_fee_bps: u16,
+        emission_rate_cap: u64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::update_staking_pool(
+                agent,
+                &self.payer(),
+                staking_fee_bps,
+                emission_rate_cap,
+            )],
+            &[],
+        )
+    }
+
+    pub fn stake(&self, agent: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        self.send(&[instructions::stake(agent, &self.payer(), amount)], &[])
+    }
+
+    /// Lock the payer's stake until `unlock_ts` (rounded down to a whole epoch)
+    pub fn lock_stake(&self, agent: &Pubkey, unlock_ts: i64) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::lock_stake(agent, &self.payer(), unlock_ts)],
+            &[],
+        )
+    }
+
+    pub fn unstake(&self, agent: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        self.send(&[instructions::unstake(agent, &self.payer(), amount)], &[])
+    }
+
+    pub fn claim_rewards(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::claim_rewards(agent, &self.payer())], &[])
+    }
+
+    pub fn fund_staking_pool(&self, agent: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::fund_staking_pool(
+                agent,
+                &self.payer(),
+                amount,
+            )],
+            &[],
+        )
+    }
+
+    /// Deposit revenue earned outside x402 into an agent's pool, tagged with
+    /// its `source`. SOL unless `mint` is given, paid from the payer's ATA
+    /// into the DAO treasury's.
+    pub fn deposit_revenue(
+        &self,
+        agent: &Pubkey,
+        pool: RevenuePool,
+        amount: u64,
+        source: [u8; 32],
+        mint: Option<&Pubkey>,
+    ) -> ClientResult<Signature> {
+        let mut ixs = Vec::new();
+        let token_accounts = match mint {
+            Some(mint) => {
+                let treasury = find_dao_treasury_pda(agent).0;
+                ixs.push(create_associated_token_account_idempotent(
+                    &self.payer(),
+                    &treasury,
+                    mint,
+                    &anchor_spl::token::ID,
+                ));
+                Some((
+                    get_associated_token_address(&self.payer(), mint),
+                    get_associated_token_address(&treasury, mint),
+                ))
+            }
+            None => None,
+        };
+        ixs.push(instructions::deposit_revenue(
+            agent,
+            &self.payer(),
+            pool,
+            amount,
+            source,
+            token_accounts.as_ref().map(|(from, to)| (from, to)),
+        ));
+        self.send(&ixs, &[])
+    }
+
+    /// Set up governance for the agent; the payer must be the agent creator
+    pub fn create_governance(
+        &self,
+        agent: &Pubkey,
+        quorum_bps: u16,
+        threshold_bps: u16,
+        voting_period_secs: i64,
+        proposal_threshold: u64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::create_governance(
+                agent,
+                &self.payer(),
+                quorum_bps,
+                threshold_bps,
+                voting_period_secs,
+                proposal_threshold,
+            )],
+            &[],
+        )
+    }
+
+    /// Propose a parameter change; returns the proposal id and the signature
+    pub fn create_proposal(
+        &self,
+        agent: &Pubkey,
+        action: ProposalAction,
+    ) -> ClientResult<(u64, Signature)> {
+        let proposal_id = self.get_governance(agent)?.proposal_count;
+        let ix = instructions::create_proposal(agent, &self.payer(), proposal_id, action);
+        let signature = self.send(&[ix], &[])?;
+        Ok((proposal_id, signature))
+    }
+
+    pub fn cast_vote(
+        &self,
+        agent: &Pubkey,
+        proposal_id: u64,
+        support: bool,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::cast_vote(
+                agent,
+                &self.payer(),
+                proposal_id,
+                support,
+            )],
+            &[],
+        )
+    }
+
+    pub fn execute_proposal(&self, agent: &Pubkey, proposal_id: u64) -> ClientResult<Signature> {
+        let proposal = self.get_proposal(agent, proposal_id)?;
+        self.send(
+            &[instructions::execute_proposal(
+                agent,
+                proposal_id,
+                &proposal.action,
+            )],
+            &[],
+        )
+    }
+
+    /// Open the agent's DAO treasury; the payer must be the agent creator and
+    /// governance must already exist
+    pub fn create_dao_treasury(
+        &self,
+        agent: &Pubkey,
+        treasury_fee_bps: u16,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::create_dao_treasury(
+                agent,
+                &self.payer(),
+                treasury_fee_bps,
+            )],
+            &[],
+        )
+    }
+
+    pub fn collect_treasury_fees(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::collect_treasury_fees(agent)], &[])
+    }
+
+    /// Top up `x402_top_up_bps` of creator fees into the x402 treasury of
+    /// one of the payer's agents, opening the treasury if needed
+    pub fn set_x402_top_up(&self, agent: &Pubkey, x402_top_up_bps: u16) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_x402_top_up(
+                agent,
+                &self.payer(),
+                x402_top_up_bps,
+            )],
+            &[],
+        )
+    }
+
+    pub fn collect_x402_top_up(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::collect_x402_top_up(agent)], &[])
+    }
+
+    /// Create a DCA schedule owned by the payer; also creates the payer's ATA
+    /// so keepers can crank it without further setup
+    pub fn create_dca(
+        &self,
+        agent: &Pubkey,
+        amount_per_interval: u64,
+        interval_secs: i64,
+        total_intervals: u64,
+        min_tokens_per_interval: u64,
+    ) -> ClientResult<Signature> {
+        let create_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &self.payer(),
+            &find_mint_pda(agent).0,
+            &anchor_spl::token::ID,
+        );
+        let create = instructions::create_dca(
+            agent,
+            &self.payer(),
+            amount_per_interval,
+            interval_secs,
+            total_intervals,
+            min_tokens_per_interval,
+        );
+        self.send(&[create_ata, create], &[])
+    }
+
+    /// Crank the next due interval of `owner`'s schedule
+    pub fn execute_dca(&self, agent: &Pubkey, owner: &Pubkey) -> ClientResult<Signature> {
+        let agent_account = self.get_agent(agent)?;
+        let factory = self.get_factory()?;
+        let price_history = self.price_history_address(agent)?;
+        let trending = self.trending_address()?;
+        self.send(
+            &[instructions::execute_dca(
+                agent,
+                owner,
+                &agent_account.creator,
+                &factory.platform_treasury,
+                &self.payer(),
+                price_history.as_ref(),
+                trending.as_ref(),
+            )],
+            &[],
+        )
+    }
+
+    pub fn cancel_dca(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::cancel_dca(agent, &self.payer())], &[])
+    }
+
+    /// Open a presale for one of the payer's agents
+    pub fn create_presale(
+        &self,
+        agent: &Pubkey,
+        start_ts: i64,
+        end_ts: i64,
+        soft_cap: u64,
+        hard_cap: u64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::create_presale(
+                agent,
+                &self.payer(),
+                start_ts,
+                end_ts,
+                soft_cap,
+                hard_cap,
+            )],
+            &[],
+        )
+    }
+
+    pub fn commit_sol(&self, agent: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::commit_sol(agent, &self.payer(), amount)],
+            &[],
+        )
+    }
+
+    pub fn finalize_presale(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        let agent_account = self.get_agent(agent)?;
+        let factory = self.get_factory()?;
+        self.send(
+            &[instructions::finalize_presale(
+                agent,
+                &agent_account.creator,
+                &factory.platform_treasury,
+                &self.payer(),
+            )],
+            &[],
+        )
+    }
+
+    /// Claim the payer's presale allocation, creating their ATA if needed
+    pub fn claim_presale(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        let create_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &self.payer(),
+            &find_mint_pda(agent).0,
+            &anchor_spl::token::ID,
+        );
+        let claim = instructions::claim_presale(agent, &self.payer());
+        self.send(&[create_ata, claim], &[])
+    }
+
+    pub fn refund_commitment(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::refund_commitment(agent, &self.payer())],
+            &[],
+        )
+    }
+
+    pub fn configure_x402(
+        &self,
+        agent: &Pubkey,
+        settings: X402Settings,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::configure_x402(agent, &self.payer(), settings)],
+            &[],
+        )
+    }
+
+    pub fn update_x402(&self, agent: &Pubkey, settings: X402Settings) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::update_x402(agent, &self.payer(), settings)],
+            &[],
+        )
+    }
+
+    /// Pay for a service with the next nonce, optionally insuring it for
+    /// `coverage` lamports and crediting the referring `affiliate` wallet.
+    /// `request_id` is echoed in the payment's event and record, and
+    /// `priority_tip` is paid to the provider on top of `amount`; returns the
+    /// payment record PDA
+    #[allow(clippy::too_many_arguments)]
+    pub fn pay_for_service(
+        &self,
+        agent: &Pubkey,
+        payer_token_account: &Pubkey,
+        recipient_token_account: &Pubkey,
+        amount: u64,
+        service_id: [u8; 32],
+        request_id: [u8; 16],
+        priority_tip: u64,
+        coverage: Option<u64>,
+        affiliate: Option<&Pubkey>,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let discount = self.platform_discount(&self.get_factory()?, &self.payer())?;
+        self.pay_for_service_ix(
+            agent,
+            payer_token_account,
+            recipient_token_account,
+            amount,
+            service_id,
+            request_id,
+            priority_tip,
+            coverage,
+            affiliate,
+            discount.as_ref().map(|(position, _)| position),
+            false,
+        )
+    }
+
+    /// Pay the price the agent quoted the payer for `service_id` plus any
+    /// `priority_tip`, optionally insuring it and crediting an affiliate;
+    /// returns the payment record PDA
+    #[allow(clippy::too_many_arguments)]
+    pub fn pay_quoted_service(
+        &self,
+        agent: &Pubkey,
+        payer_token_account: &Pubkey,
+        recipient_token_account: &Pubkey,
+        service_id: [u8; 32],
+        request_id: [u8; 16],
+        priority_tip: u64,
+        coverage: Option<u64>,
+        affiliate: Option<&Pubkey>,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let quote = self.get_service_quote(agent, &self.payer(), &service_id)?;
+        self.pay_for_service_ix(
+            agent,
+            payer_token_account,
+            recipient_token_account,
+            quote.amount,
+            service_id,
+            request_id,
+            priority_tip,
+            coverage,
+            affiliate,
+            None,
+            true,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn pay_for_service_ix(
+        &self,
+        agent: &Pubkey,
+        payer_token_account: &Pubkey,
+        recipient_token_account: &Pubkey,
+        amount: u64,
+        service_id: [u8; 32],
+        request_id: [u8; 16],
+        priority_tip: u64,
+        coverage: Option<u64>,
+        affiliate: Option<&Pubkey>,
+        platform_stake: Option<&Pubkey>,
+        quoted: bool,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let nonce = self.next_x402_nonce(agent)?;
+        let mint = self
+            .fetch::<anchor_spl::token::TokenAccount>(payer_token_account)?
+            .mint;
+        let (mut ixs, revenue_split) = self.revenue_split_accounts(agent, &mint)?;
+        let affiliate_token_account = match affiliate {
+            Some(wallet) => {
+                ixs.push(create_associated_token_account_idempotent(
+                    &self.payer(),
+                    wallet,
+                    &mint,
+                    &anchor_spl::token::ID,
+                ));
+                Some(get_associated_token_address(wallet, &mint))
+            }
+            None => None,
+        };
+        ixs.push(instructions::pay_for_service(
+            agent,
+            &self.payer(),
+            payer_token_account,
+            recipient_token_account,
+            amount,
+            service_id,
+            nonce,
+            platform_stake,
+            quoted,
+            affiliate.zip(affiliate_token_account.as_ref()),
+            revenue_split.as_deref(),
+            request_id,
+            priority_tip,
+            self.has_consumer_stats(&self.payer())?,
+        ));
+        let record = find_payment_record_pda(agent, &self.payer(), nonce).0;
+        if let Some(coverage) = coverage {
+            ixs.push(instructions::insure_payment(
+                &record,
+                &self.payer(),
+                None,
+                coverage,
+            ));
+        }
+        Ok((record, self.send(&ixs, &[])?))
+    }
+
+    /// Worker treasury token accounts for `agent`'s revenue split in `mint`,
+    /// with instructions creating them; `None` when the agent doesn't route
+    /// revenue to other agents
+    fn revenue_split_accounts(
+        &self,
+        agent: &Pubkey,
+        mint: &Pubkey,
+    ) -> ClientResult<(Vec<Instruction>, Option<Vec<Pubkey>>)> {
+        if self.get_x402_config(agent)?.revenue_split_bps == 0 {
+            return Ok((Vec::new(), None));
+        }
+        let shares = self.get_revenue_split(agent)?.shares;
+        let ixs = shares
+            .iter()
+            .map(|share| {
+                create_associated_token_account_idempotent(
+                    &self.payer(),
+                    &share.treasury,
+                    mint,
+                    &anchor_spl::token::ID,
+                )
+            })
+            .collect();
+        let token_accounts = shares
+            .iter()
+            .map(|share| get_associated_token_address(&share.treasury, mint))
+            .collect();
+        Ok((ixs, Some(token_accounts)))
+    }
+
+    /// Route `bps` of every x402 payment to one of the payer's agents to each
+    /// worker agent's DAO treasury, given as `(worker_agent, bps)` pairs;
+    /// empty stops routing
+    pub fn set_revenue_split(
+        &self,
+        agent: &Pubkey,
+        workers: &[(Pubkey, u16)],
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_revenue_split(
+                agent,
+                &self.payer(),
+                workers,
+            )],
+            &[],
+        )
+    }
+
+    /// Set how long after settlement payments to one of the payer's agents
+    /// can be disputed; 0 = no limit
+    pub fn set_chargeback_window(
+        &self,
+        agent: &Pubkey,
+        chargeback_window_secs: u64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_chargeback_window(
+                agent,
+                &self.payer(),
+                chargeback_window_secs,
+            )],
+            &[],
+        )
+    }
+
+    /// Close a payment to disputes once its chargeback window has elapsed
+    pub fn finalize_payment(&self, payment_record: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::finalize_payment(payment_record)], &[])
+    }
+
+    /// Deprecate one of the payer's agents' services, rejecting new payments
+    /// for it from `sunset_ts`; 0 withdraws the deprecation
+    pub fn deprecate_service(
+        &self,
+        agent: &Pubkey,
+        service_id: [u8; 32],
+        sunset_ts: i64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::deprecate_service(
+                agent,
+                &self.payer(),
+                service_id,
+                sunset_ts,
+            )],
+            &[],
+        )
+    }
+
+    /// Open a spending report for the payer's wallet, or for `agent`, one of
+    /// the payer's agents; later payments by it keep the report up to date
+    pub fn create_consumer_stats(&self, agent: Option<&Pubkey>) -> ClientResult<Signature> {
+        let consumer = agent.copied().unwrap_or_else(|| self.payer());
+        self.send(
+            &[instructions::create_consumer_stats(
+                &self.payer(),
+                &consumer,
+                agent,
+            )],
+            &[],
+        )
+    }
+
+    /// Whether `consumer` has opened a spending report
+    fn has_consumer_stats(&self, consumer: &Pubkey) -> ClientResult<bool> {
+        Ok(self
+            .existing::<ConsumerStats>(find_consumer_stats_pda(consumer).0)?
+            .is_some())
+    }
+
+    /// Set the signer attesting escrowed payments to one of the payer's
+    /// agents; `Pubkey::default()` turns escrowed payments off
+    pub fn set_x402_verifier(&self, agent: &Pubkey, verifier: &Pubkey) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_x402_verifier(
+                agent,
+                &self.payer(),
+                verifier,
+            )],
+            &[],
+        )
+    }
+
+    /// Move one of the payer's agents' x402 payments to `new_recipient`,
+    /// keeping the old recipient valid for `grace_secs`
+    pub fn rotate_x402_recipient(
+        &self,
+        agent: &Pubkey,
+        new_recipient: &Pubkey,
+        grace_secs: i64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::rotate_x402_recipient(
+                agent,
+                &self.payer(),
+                new_recipient,
+                grace_secs,
+            )],
+            &[],
+        )
+    }
+
+    /// Bill one of the payer's agents by usage; `unit_price` 0 returns to
+    /// flat pricing
+    pub fn set_x402_metering(
+        &self,
+        agent: &Pubkey,
+        meter_unit: MeterUnit,
+        unit_price: u64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_x402_metering(
+                agent,
+                &self.payer(),
+                meter_unit,
+                unit_price,
+            )],
+            &[],
+        )
+    }
+
+    /// Require `price_notice_secs` of notice before minimum price increases
+    /// on one of the payer's agents take effect
+    pub fn set_x402_price_notice(
+        &self,
+        agent: &Pubkey,
+        price_notice_secs: i64,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_x402_price_notice(
+                agent,
+                &self.payer(),
+                price_notice_secs,
+            )],
+            &[],
+        )
+    }
+
+    /// Register the payer as an x402 affiliate asking `fee_bps` of referred
+    /// payments, or update its fee
+    pub fn register_affiliate(&self, fee_bps: u16) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::register_affiliate(&self.payer(), fee_bps)],
+            &[],
+        )
+    }
+
+    /// Cap the affiliate share of one of the payer's agents' payments
+    pub fn set_affiliate_cap(
+        &self,
+        agent: &Pubkey,
+        max_affiliate_bps: u16,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_affiliate_cap(
+                agent,
+                &self.payer(),
+                max_affiliate_bps,
+            )],
+            &[],
+        )
+    }
+
+    /// Register the callback URL (hashed with SHA-256) and response signing
+    /// key of one of the payer's agents; `None` clears the webhook
+    pub fn register_webhook(
+        &self,
+        agent: &Pubkey,
+        webhook: Option<(&str, &Pubkey)>,
+    ) -> ClientResult<Signature> {
+        let (url_hash, signer) = match webhook {
+            Some((url, signer)) => (solana_sdk::hash::hash(url.as_bytes()).to_bytes(), *signer),
+            None => ([0; 32], Pubkey::default()),
+        };
+        self.send(
+            &[instructions::register_webhook(
+                agent,
+                &self.payer(),
+                url_hash,
+                &signer,
+            )],
+            &[],
+        )
+    }
+
+    /// Quote `payer` a negotiated price for one of the payer's agents'
+    /// services; returns the quote PDA
+    pub fn post_quote(
+        &self,
+        agent: &Pubkey,
+        payer: &Pubkey,
+        service_id: [u8; 32],
+        amount: u64,
+        expires_at: i64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let quote = find_service_quote_pda(agent, payer, &service_id).0;
+        let signature = self.send(
+            &[instructions::post_quote(
+                agent,
+                &self.payer(),
+                payer,
+                service_id,
+                amount,
+                expires_at,
+            )],
+            &[],
+        )?;
+        Ok((quote, signature))
+    }
+
+    /// Propose paying `amount` for `target_agent`'s `service_id` from one
+    /// of the payer's agents until `expires_at`; returns the agreement PDA
+    /// and the signature
+    pub fn propose_terms(
+        &self,
+        caller_agent: &Pubkey,
+        target_agent: &Pubkey,
+        service_id: [u8; 32],
+        amount: u64,
+        expires_at: i64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let agreement = find_service_agreement_pda(caller_agent, target_agent, &service_id).0;
+        let signature = self.send(
+            &[instructions::propose_terms(
+                caller_agent,
+                target_agent,
+                &self.payer(),
+                service_id,
+                amount,
+                expires_at,
+            )],
+            &[],
+        )?;
+        Ok((agreement, signature))
+    }
+
+    /// Accept terms proposed for one of the payer's agents; `amount` must
+    /// match the proposed price
+    pub fn accept_terms(&self, agreement: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        let target_agent = self.fetch::<ServiceAgreement>(agreement)?.target_agent;
+        self.send(
+            &[instructions::accept_terms(
+                &target_agent,
+                agreement,
+                &self.payer(),
+                amount,
+            )],
+            &[],
+        )
+    }
+
+    /// Close terms the payer proposed, returning their rent
+    pub fn close_terms(&self, agreement: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::close_terms(agreement, &self.payer())], &[])
+    }
+
+    /// Close a quote of one of the payer's agents, returning its rent
+    pub fn close_quote(&self, quote: &Pubkey) -> ClientResult<Signature> {
+        let agent = self.fetch::<ServiceQuote>(quote)?.agent;
+        self.send(
+            &[instructions::close_quote(&agent, &self.payer(), quote)],
+            &[],
+        )
+    }
+
+    /// Pay `amount` of `mint` from the payer's ATA into escrow, optionally
+    /// insuring it for `coverage` lamports; returns the payment record PDA
+    /// and the signature
+    pub fn pay_for_service_escrowed(
+        &self,
+        agent: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        service_id: [u8; 32],
+        request_id: [u8; 16],
+        coverage: Option<u64>,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let nonce = self.next_x402_nonce(agent)?;
+        let discount = self.platform_discount(&self.get_factory()?, &self.payer())?;
+        let ix = instructions::pay_for_service_escrowed(
+            agent,
+            &self.payer(),
+            mint,
+            &get_associated_token_address(&self.payer(), mint),
+            amount,
+            service_id,
+            nonce,
+            discount.as_ref().map(|(position, _)| position),
+            request_id,
+            self.has_consumer_stats(&self.payer())?,
+        );
+        let record = find_payment_record_pda(agent, &self.payer(), nonce).0;
+        let mut ixs = vec![ix];
+        if let Some(coverage) = coverage {
+            ixs.push(instructions::insure_payment(
+                &record,
+                &self.payer(),
+                None,
+                coverage,
+            ));
+        }
+        Ok((record, self.send(&ixs, &[])?))
+    }
+
+    /// Attest an escrowed payment as the agent's verifier, paying the
+    /// recipient's ATA if the output `passed` and refunding the payer's
+    /// otherwise
+    pub fn settle_attested_payment(
+        &self,
+        payment_record: &Pubkey,
+        passed: bool,
+        attestation_hash: [u8; 32],
+    ) -> ClientResult<Signature> {
+        let refund_bps = if passed { 0 } else { BPS_DENOMINATOR as u16 };
+        self.settle_attested_payment_partial(payment_record, refund_bps, attestation_hash)
+    }
+
+    /// Attest an escrowed payment as the agent's verifier, refunding
+    /// `refund_bps` of it to the payer's ATA and paying the rest to the
+    /// recipient's
+    pub fn settle_attested_payment_partial(
+        &self,
+        payment_record: &Pubkey,
+        refund_bps: u16,
+        attestation_hash: [u8; 32],
+    ) -> ClientResult<Signature> {
+        let record = self.get_payment_record(payment_record)?;
+        let recipient = self.get_x402_config(&record.agent)?.payment_recipient;
+        let escrow: anchor_spl::token::TokenAccount =
+            self.fetch(&find_payment_escrow_pda(payment_record).0)?;
+        let create_recipient_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &recipient,
+            &escrow.mint,
+            &anchor_spl::token::ID,
+        );
+        let (mut ixs, revenue_split) = self.revenue_split_accounts(&record.agent, &escrow.mint)?;
+        let settle = instructions::settle_attested_payment_partial(
+            &record.agent,
+            payment_record,
+            &record.payer,
+            &get_associated_token_address(&recipient, &escrow.mint),
+            &get_associated_token_address(&record.payer, &escrow.mint),
+            &self.payer(),
+            refund_bps,
+            attestation_hash,
+            revenue_split.as_deref(),
+        );
+        ixs.extend([create_recipient_ata, settle]);
+        self.send(&ixs, &[])
+    }
+
+    /// Settle a metered payment to one of the payer's agents with its
+    /// reported usage, paying the charge to the recipient's ATA and
+    /// refunding the rest to the payer's
+    pub fn settle_metered_payment(
+        &self,
+        payment_record: &Pubkey,
+        units: u64,
+        usage_hash: [u8; 32],
+    ) -> ClientResult<Signature> {
+        let record = self.get_payment_record(payment_record)?;
+        let recipient = self.get_x402_config(&record.agent)?.payment_recipient;
+        let escrow: anchor_spl::token::TokenAccount =
+            self.fetch(&find_payment_escrow_pda(payment_record).0)?;
+        let create_recipient_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &recipient,
+            &escrow.mint,
+            &anchor_spl::token::ID,
+        );
+        let (mut ixs, revenue_split) = self.revenue_split_accounts(&record.agent, &escrow.mint)?;
+        let settle = instructions::settle_metered_payment(
+            &record.agent,
+            payment_record,
+            &record.payer,
+            &get_associated_token_address(&recipient, &escrow.mint),
+            &get_associated_token_address(&record.payer, &escrow.mint),
+            &self.payer(),
+            units,
+            usage_hash,
+            revenue_split.as_deref(),
+        );
+        ixs.extend([create_recipient_ata, settle]);
+        self.send(&ixs, &[])
+    }
+
+    /// Refund one of the payer's escrowed payments left unattested past the
+    /// service timeout
+    pub fn refund_expired_payment(&self, payment_record: &Pubkey) -> ClientResult<Signature> {
+        let record = self.get_payment_record(payment_record)?;
+        let escrow: anchor_spl::token::TokenAccount =
+            self.fetch(&find_payment_escrow_pda(payment_record).0)?;
+        self.send(
+            &[instructions::refund_expired_payment(
+                &record.agent,
+                payment_record,
+                &self.payer(),
+                &get_associated_token_address(&self.payer(), &escrow.mint),
+            )],
+            &[],
+        )
+    }
+
+    /// Stream `rate_per_sec` of `mint` from the payer's ATA to `agent` for up
+    /// to `max_duration` seconds, locking the whole amount now; returns the
+    /// stream PDA and the signature
+    pub fn start_stream(
+        &self,
+        agent: &Pubkey,
+        mint: &Pubkey,
+        rate_per_sec: u64,
+        max_duration: u64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let nonce = self.next_x402_nonce(agent)?;
+        let ix = instructions::start_stream(
+            agent,
+            &self.payer(),
+            mint,
+            &get_associated_token_address(&self.payer(), mint),
+            rate_per_sec,
+            max_duration,
+            nonce,
+        );
+        let stream = find_payment_stream_pda(agent, &self.payer(), nonce).0;
+        Ok((stream, self.send(&[ix], &[])?))
+    }
+
+    /// Pay a stream's provider what has streamed so far
+    pub fn withdraw_streamed(&self, stream: &Pubkey) -> ClientResult<Signature> {
+        let state = self.get_payment_stream(stream)?;
+        let recipient = self.get_x402_config(&state.agent)?.payment_recipient;
+        let create_recipient_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &recipient,
+            &state.mint,
+            &anchor_spl::token::ID,
+        );
+        let (mut ixs, revenue_split) = self.revenue_split_accounts(&state.agent, &state.mint)?;
+        let withdraw = instructions::withdraw_streamed(
+            &state.agent,
+            stream,
+            &state.payer,
+            &get_associated_token_address(&recipient, &state.mint),
+            revenue_split.as_deref(),
+        );
+        ixs.extend([create_recipient_ata, withdraw]);
+        self.send(&ixs, &[])
+    }
+
+    /// Cancel one of the payer's streams, reclaiming the unstreamed remainder
+    pub fn cancel_stream(&self, stream: &Pubkey) -> ClientResult<Signature> {
+        let state = self.get_payment_stream(stream)?;
+        self.send(
+            &[instructions::cancel_stream(
+                &state.agent,
+                stream,
+                &self.payer(),
+                &get_associated_token_address(&self.payer(), &state.mint),
+            )],
+            &[],
+        )
+    }
+
+    /// Nonce the program expects for the next payment to `agent`
+    pub fn next_x402_nonce(&self, agent: &Pubkey) -> ClientResult<u64> {
+        Ok(self.get_x402_config(agent)?.nonce + 1)
+    }
+
+    // ========================================================================
+    // Provider bonds and disputes
+    // ========================================================================
+
+    /// Post (or add to) the bond backing one of the payer's agents
+    pub fn deposit_provider_bond(&self, agent: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::deposit_provider_bond(
+                agent,
+                &self.payer(),
+                amount,
+            )],
+            &[],
+        )
+    }
+
+    pub fn withdraw_provider_bond(&self, agent: &Pubkey, amount: u64) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::withdraw_provider_bond(
+                agent,
+                &self.payer(),
+                amount,
+            )],
+            &[],
+        )
+    }
+
+    /// Dispute a payment made by the payer, or by one of the payer's agents,
+    /// claiming `claimed_amount` lamports
+    pub fn submit_evidence(
+        &self,
+        payment_record: &Pubkey,
+        evidence_hash: [u8; 32],
+        claimed_amount: u64,
+    ) -> ClientResult<Signature> {
+        let record = self.get_payment_record(payment_record)?;
+        let payer_agent = (record.payer != self.payer()).then_some(record.payer);
+        self.send(
+            &[instructions::submit_evidence(
+                &record.agent,
+                payment_record,
+                &self.payer(),
+                payer_agent.as_ref(),
+                evidence_hash,
+                claimed_amount,
+            )],
+            &[],
+        )
+    }
+
+    /// Vote on a dispute as an arbitration council member; `slash_amount` 0
+    /// votes to dismiss it
+    pub fn cast_arbiter_vote(
+        &self,
+        payment_record: &Pubkey,
+        slash_amount: u64,
+    ) -> ClientResult<Signature> {
+        let dispute = self.get_dispute(payment_record)?;
+        self.send(
+            &[instructions::cast_arbiter_vote(
+                &dispute.agent,
+                payment_record,
+                &dispute.claimant,
+                &self.payer(),
+                slash_amount,
+            )],
+            &[],
+        )
+    }
+
+    /// Pay an upheld dispute's insured shortfall to its claimant
+    pub fn pay_insured_claim(&self, payment_record: &Pubkey) -> ClientResult<Signature> {
+        let dispute = self.get_dispute(payment_record)?;
+        self.send(
+            &[instructions::pay_insured_claim(
+                payment_record,
+                &dispute.claimant,
+            )],
+            &[],
+        )
+    }
+
+    // ========================================================================
+    // Milestone jobs
+    // ========================================================================
+
+    pub fn get_job(&self, job: &Pubkey) -> ClientResult<Job> {
+        self.fetch(job)
+    }
+
+    /// Hire `agent` for a job paid in `milestone_amounts` of `mint` from the
+    /// payer's ATA, locking the total now; returns the job PDA and the
+    /// signature
+    pub fn create_job(
+        &self,
+        agent: &Pubkey,
+        mint: &Pubkey,
+        milestone_amounts: Vec<u64>,
+        service_id: [u8; 32],
+        deadline: i64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let nonce = self.next_x402_nonce(agent)?;
+        let ix = instructions::create_job(
+            agent,
+            &self.payer(),
+            mint,
+            &get_associated_token_address(&self.payer(), mint),
+            milestone_amounts,
+            service_id,
+            deadline,
+            nonce,
+        );
+        let job = find_job_pda(agent, &self.payer(), nonce).0;
+        Ok((job, self.send(&[ix], &[])?))
+    }
+
+    /// Deliver a milestone of a job for one of the payer's agents
+    pub fn submit_milestone(
+        &self,
+        job: &Pubkey,
+        index: u8,
+        deliverable_hash: [u8; 32],
+    ) -> ClientResult<Signature> {
+        let agent = self.get_job(job)?.agent;
+        self.send(
+            &[instructions::submit_milestone(
+                &agent,
+                job,
+                &self.payer(),
+                index,
+                deliverable_hash,
+            )],
+            &[],
+        )
+    }
+
+    /// Pay a submitted milestone to the provider, as the job's payer or
+    /// after its review period
+    pub fn release_milestone(&self, job: &Pubkey, index: u8) -> ClientResult<Signature> {
+        let state = self.get_job(job)?;
+        let recipient = self.get_x402_config(&state.agent)?.payment_recipient;
+        let create_recipient_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &recipient,
+            &state.mint,
+            &anchor_spl::token::ID,
+        );
+        let (mut ixs, revenue_split) = self.revenue_split_accounts(&state.agent, &state.mint)?;
+        let release = instructions::release_milestone(
+            &state.agent,
+            job,
+            &state.payer,
+            &get_associated_token_address(&recipient, &state.mint),
+            &self.payer(),
+            index,
+            revenue_split.as_deref(),
+        );
+        ixs.extend([create_recipient_ata, release]);
+        self.send(&ixs, &[])
+    }
+
+    /// Dispute a submitted milestone of one of the payer's jobs
+    pub fn dispute_milestone(
+        &self,
+        job: &Pubkey,
+        index: u8,
+        evidence_hash: [u8; 32],
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::dispute_milestone(
+                job,
+                &self.payer(),
+                index,
+                evidence_hash,
+            )],
+            &[],
+        )
+    }
+
+    /// Vote on the share (bps) of a disputed milestone refunded to the payer
+    /// as an arbitration council member; 0 votes to dismiss the dispute
+    pub fn vote_milestone_dispute(
+        &self,
+        job: &Pubkey,
+        index: u8,
+        refund_bps: u16,
+    ) -> ClientResult<Signature> {
+        let state = self.get_job(job)?;
+        let recipient = self.get_x402_config(&state.agent)?.payment_recipient;
+        let (mut ixs, revenue_split) = self.revenue_split_accounts(&state.agent, &state.mint)?;
+        ixs.push(instructions::vote_milestone_dispute(
+            &state.agent,
+            job,
+            &state.payer,
+            &get_associated_token_address(&recipient, &state.mint),
+            &get_associated_token_address(&state.payer, &state.mint),
+            &self.payer(),
+            index,
+            refund_bps,
+            revenue_split.as_deref(),
+        ));
+        self.send(&ixs, &[])
+    }
+
+    /// Reclaim the milestones of one of the payer's jobs left unsubmitted
+    /// past its deadline
+    pub fn cancel_job(&self, job: &Pubkey) -> ClientResult<Signature> {
+        let state = self.get_job(job)?;
+        self.send(
+            &[instructions::cancel_job(
+                &state.agent,
+                job,
+                &self.payer(),
+                &get_associated_token_address(&self.payer(), &state.mint),
+            )],
+            &[],
+        )
+    }
+
+    /// Close one of the payer's settled jobs, returning its rent
+    pub fn close_job(&self, job: &Pubkey) -> ClientResult<Signature> {
+        self.send(&[instructions::close_job(job, &self.payer())], &[])
+    }
+
+    // ========================================================================
+    // Work queues
+    // ========================================================================
+
+    pub fn get_work_queue(&self, agent: &Pubkey) -> ClientResult<WorkQueue> {
+        self.fetch(&find_work_queue_pda(agent).0)
+    }
+
+    pub fn get_work_task(&self, task: &Pubkey) -> ClientResult<WorkTask> {
+        self.fetch(task)
+    }
+
+    /// Set the workers serving the work queue of one of the payer's agents
+    pub fn set_queue_workers(
+        &self,
+        agent: &Pubkey,
+        workers: Vec<Pubkey>,
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_queue_workers(
+                agent,
+                &self.payer(),
+                workers,
+            )],
+            &[],
+        )
+    }
+
+    /// Queue a work request for `agent`, escrowing up to `max_price` of
+    /// `mint` from the payer's ATA; returns the task PDA and the signature
+    pub fn enqueue_task(
+        &self,
+        agent: &Pubkey,
+        mint: &Pubkey,
+        params_hash: [u8; 32],
+        max_price: u64,
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let task_id = self.get_work_queue(agent)?.next_task_id;
+        let ix = instructions::enqueue_task(
+            agent,
+            task_id,
+            &self.payer(),
+            mint,
+            &get_associated_token_address(&self.payer(), mint),
+            params_hash,
+            max_price,
+        );
+        let task = find_work_task_pda(agent, task_id).0;
+        Ok((task, self.send(&[ix], &[])?))
+    }
+
+    /// Claim a queued task as one of its agent's workers
+    pub fn claim_task(&self, task: &Pubkey) -> ClientResult<Signature> {
+        let agent = self.get_work_task(task)?.agent;
+        self.send(
+            &[instructions::claim_task(&agent, task, &self.payer())],
+            &[],
+        )
+    }
+
+    /// Deliver a task the payer claimed, charging `price` of its escrow
+    pub fn complete_task(
+        &self,
+        task: &Pubkey,
+        price: u64,
+        result_hash: [u8; 32],
+    ) -> ClientResult<Signature> {
+        let state = self.get_work_task(task)?;
+        let recipient = self.get_x402_config(&state.agent)?.payment_recipient;
+        let create_recipient_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &recipient,
+            &state.mint,
+            &anchor_spl::token::ID,
+        );
+        let (mut ixs, revenue_split) = self.revenue_split_accounts(&state.agent, &state.mint)?;
+        let complete = instructions::complete_task(
+            &state.agent,
+            task,
+            &state.requester,
+            &get_associated_token_address(&recipient, &state.mint),
+            &get_associated_token_address(&state.requester, &state.mint),
+            &self.payer(),
+            price,
+            result_hash,
+            revenue_split.as_deref(),
+        );
+        ixs.extend([create_recipient_ata, complete]);
+        self.send(&ixs, &[])
+    }
+
+    /// Withdraw one of the payer's tasks no worker is serving, refunding it
+    pub fn withdraw_task(&self, task: &Pubkey) -> ClientResult<Signature> {
+        let state = self.get_work_task(task)?;
+        self.send(
+            &[instructions::withdraw_task(
+                &state.agent,
+                task,
+                &self.payer(),
+                &get_associated_token_address(&self.payer(), &state.mint),
+            )],
+            &[],
+        )
+    }
+
+    // ========================================================================
+    // Trial sessions
+    // ========================================================================
+
+    pub fn get_trial_session(&self, agent: &Pubkey, caller: &Pubkey) -> ClientResult<TrialSession> {
+        self.fetch(&find_trial_session_pda(agent, caller).0)
+    }
+
+    /// Set the trial deposit of one of the payer's agents, 0 to stop trials
+    pub fn set_trial_deposit(&self, agent: &Pubkey, trial_deposit: u64) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::set_trial_deposit(
+                agent,
+                &self.payer(),
+                trial_deposit,
+            )],
+            &[],
+        )
+    }
+
+    /// Open a trial session of `agent`'s `service_id`, escrowing its trial
+    /// deposit in `mint` from the payer's ATA; returns the session PDA and
+    /// the signature
+    pub fn open_trial(
+        &self,
+        agent: &Pubkey,
+        mint: &Pubkey,
+        service_id: [u8; 32],
+    ) -> ClientResult<(Pubkey, Signature)> {
+        let ix = instructions::open_trial(
+            agent,
+            &self.payer(),
+            mint,
+            &get_associated_token_address(&self.payer(), mint),
+            service_id,
+        );
+        let session = find_trial_session_pda(agent, &self.payer()).0;
+        Ok((session, self.send(&[ix], &[])?))
+    }
+
+    /// End the payer's trial session with `agent`, refunding the deposit
+    pub fn end_trial(&self, agent: &Pubkey) -> ClientResult<Signature> {
+        let session = self.get_trial_session(agent, &self.payer())?;
+        self.send(
+            &[instructions::end_trial(
+                agent,
+                &self.payer(),
+                &get_associated_token_address(&self.payer(), &session.mint),
+            )],
+            &[],
+        )
+    }
+
+    /// Forfeit `caller`'s expired trial session with `agent`, paying the
+    /// deposit to the agent's payment recipient
+    pub fn forfeit_trial(&self, agent: &Pubkey, caller: &Pubkey) -> ClientResult<Signature> {
+        let session = self.get_trial_session(agent, caller)?;
+        let recipient = self.get_x402_config(agent)?.payment_recipient;
+        let create_recipient_ata = create_associated_token_account_idempotent(
+            &self.payer(),
+            &recipient,
+            &session.mint,
+            &anchor_spl::token::ID,
+        );
+        let forfeit = instructions::forfeit_trial(
+            agent,
+            caller,
+            &get_associated_token_address(&recipient, &session.mint),
+        );
+        self.send(&[create_recipient_ata, forfeit], &[])
+    }
+
+    // ========================================================================
+    // Reputation
+    // ========================================================================
+
+    pub fn get_agent_reputation(&self, agent: &Pubkey) -> ClientResult<AgentReputation> {
+        self.fetch(&find_reputation_pda(agent).0)
+    }
+
+    /// Post `agent`'s reputation from an external `source` as the factory
+    /// authority or its reputation attestor; a zero weight withdraws it
+    pub fn attest_reputation(
+        &self,
+        agent: &Pubkey,
+        source: [u8; REPUTATION_SOURCE_LEN],
+        score_bps: u16,
+        weight_bps: u16,
+        evidence_hash: [u8; 32],
+    ) -> ClientResult<Signature> {
+        self.send(
+            &[instructions::attest_reputation(
+                agent,
+                &self.payer(),
+                &self.payer(),
+                source,
+                score_bps,
+                weight_bps,
+                evidence_hash,
+            )],
+            &[],
+        )
+    }
+
+    pub fn get_service_rating(&self, payment_record: &Pubkey) -> ClientResult<ServiceRating> {
+        self.fetch(&find_rating_pda(payment_record).0)
+    }
+
+    /// Rate the service behind one of the payer's settled payments with 1 to
+    /// 5 stars, weighted by the amount paid
+    pub fn rate_service(&self, payment_record: &Pubkey, stars: u8) -> ClientResult<Signature> {
+        let record = self.get_payment_record(payment_record)?;
+        self.send(
+            &[instructions::rate_service(
+                &record.agent,
+                payment_record,
+                &self.payer(),
+                stars,
+            )],
+            &[],
+        )
+    }
+
+    // ========================================================================
+    // Events
+    // ========================================================================
+
+    /// Decode the program events of a confirmed transaction from its inner
+    /// instructions
+    pub fn get_transaction_events(&self, signature: &Signature) -> ClientResult<Vec<ProgramEvent>> {
+        fetch_transaction_events(&self.rpc, signature)
+    }
+
+    /// Subscribe to program events over the websocket endpoint, decoded from
+    /// transaction logs
+    pub fn subscribe_events(ws_url: &str) -> ClientResult<EventSubscription> {
+        Self::subscribe(ws_url, None)
+    }
+
+    /// Subscribe to program events, fetching each notified transaction from
+    /// `rpc_url` to decode its events from inner instructions. Busy
+    /// transactions can truncate logs, but not inner instructions.
+    pub fn subscribe_cpi_events(ws_url: &str, rpc_url: &str) -> ClientResult<EventSubscription> {
+        Self::subscribe(
+            ws_url,
+            Some(RpcClient::new_with_commitment(
+                rpc_url.to_string(),
+                CommitmentConfig::confirmed(),
+            )),
+        )
+    }
+
+    fn subscribe(ws_url: &str, rpc: Option<RpcClient>) -> ClientResult<EventSubscription> {
+        let (subscription, receiver) = PubsubClient::logs_subscribe(
+            ws_url,
+            RpcTransactionLogsFilter::Mentions(vec![PROGRAM_ID.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        Ok(EventSubscription {
+            subscription,
+            receiver,
+            rpc,
+        })
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt;
+
+use solana_client::client_error::{ClientError as RpcError, ClientErrorKind};
+use solana_client::pubsub_client::PubsubClientError;
+use solana_client::rpc_request::{RpcError as RpcRequestError, RpcResponseErrorData};
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+pub type ClientResult<T> = std::result::Result<T, ClientError>;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("RPC error: {0}")]
+    Rpc(Box<RpcError>),
+
+    #[error("Pubsub error: {0}")]
+    Pubsub(Box<PubsubClientError>),
+
+    #[error("Account not found: {0}")]
+    AccountNotFound(Pubkey),
+
+    #[error("Failed to decode account {0}: {1}")]
+    AccountDecode(Pubkey, String),
+
+    #[error("Quote error: {0}")]
+    Quote(String),
+
+    #[error("Simulation failed: {0}")]
+    Simulation(String),
+
+    #[error("Program error: {0}")]
+    Program(ProgramFailure),
+}
+
+/// An `AnchorError` recovered from a failed transaction's logs. Codes
+/// 6000-6999 are factory/curve errors, 7000-7999 x402 payment errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramFailure {
+    pub code: u32,
+    pub name: String,
+    pub message: String,
+    /// `(actual, expected)` for checks that compare values, e.g. the
+    /// tokens a buy would return vs. the caller's `min_tokens_out`.
+    pub values: Option<(String, String)>,
+}
+
+impl ProgramFailure {
+    /// Parses the first `AnchorError` from program logs, along with the
+    /// `Left:`/`Right:` lines Anchor emits for comparison checks.
+    pub fn from_logs(logs: &[String]) -> Option<Self> {
+        let idx = logs.iter().position(|l| l.contains("Error Code: "))?;
+        let line = &logs[idx];
+        let field = |key: &str, end: &str| {
+            let rest = &line[line.find(key)? + key.len()..];
+            Some(rest[..rest.find(end).unwrap_or(rest.len())].to_string())
+        };
+        let name = field("Error Code: ", ". Error Number")?;
+        let code = field("Error Number: ", ".")?.parse().ok()?;
+        let message = field("Error Message: ", "\n")?
+            .trim_end_matches('.')
+            .to_string();
+        let value = |i: usize, key: &str| {
+            logs.get(i)
+                .and_then(|l| l.split_once(key))
+                .map(|(_, v)| v.to_string())
+        };
+        let values = value(idx + 1, "Left: ").zip(value(idx + 2, "Right: "));
+        Some(Self {
+            code,
+            name,
+            message,
+            values,
+        })
+    }
+}
+
+impl fmt::Display for ProgramFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.name, self.code, self.message)?;
+        if let Some((actual, expected)) = &self.values {
+            write!(f, " (got {actual}, expected {expected})")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<RpcError> for ClientError {
+    fn from(e: RpcError) -> Self {
+        if let ClientErrorKind::RpcError(RpcRequestError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(sim),
+            ..
+        }) = e.kind()
+        {
+            if let Some(failure) = sim.logs.as_deref().and_then(ProgramFailure::from_logs) {
+                return Self::Program(failure);
+            }
+        }
+        Self::Rpc(Box::new(e))
+    }
+}
+
+impl From<PubsubClientError> for ClientError {
+    fn from(e: PubsubClientError) -> Self {
+        Self::Pubsub(Box::new(e))
+    }
+}
@@ -0,0 +1,251 @@
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInnerInstructions, UiInstruction,
+    UiLoadedAddresses,
+};
+
+use crate::PROGRAM_ID;
+
+pub use agent_factory::events::{
+    AdminActionEvent, AgentCreatedEvent, AgentFeeOverrideEvent, AgentGraduatedEvent,
+    AgentOwnershipTransferredEvent, AgentRenamedEvent, FeeClaimKind, FeesClaimedEvent,
+    InsuranceClaimEvent, PaymentEvent, PublicGoodsFeesCollectedEvent, ReputationAttestedEvent,
+    RevenueDepositedEvent, ServiceDeprecatedEvent, ServiceRatedEvent, ServiceTermsEvent,
+    SnapshotEvent, TradeEvent, TrialSessionEvent, WebhookEvent, WorkTaskEvent,
+    X402ConfigUpdatedEvent, X402FreezeEvent, X402RecipientRotatedEvent,
+};
+pub use agent_factory::instructions::call_agent_service::AgentServiceCallEvent;
+pub use agent_factory::state::CurveReserves;
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Any event emitted by the agent factory program
+pub enum ProgramEvent {
+    Trade(TradeEvent),
+    Payment(PaymentEvent),
+    AgentServiceCall(AgentServiceCallEvent),
+    Snapshot(SnapshotEvent),
+    AdminAction(AdminActionEvent),
+    InsuranceClaim(InsuranceClaimEvent),
+    Webhook(WebhookEvent),
+    AgentRenamed(AgentRenamedEvent),
+    AgentCreated(AgentCreatedEvent),
+    FeesClaimed(FeesClaimedEvent),
+    X402Freeze(X402FreezeEvent),
+    X402RecipientRotated(X402RecipientRotatedEvent),
+    AgentGraduated(AgentGraduatedEvent),
+    AgentFeeOverride(AgentFeeOverrideEvent),
+    ServiceDeprecated(ServiceDeprecatedEvent),
+    RevenueDeposited(RevenueDepositedEvent),
+    X402ConfigUpdated(X402ConfigUpdatedEvent),
+    ServiceTerms(ServiceTermsEvent),
+    WorkTask(WorkTaskEvent),
+    TrialSession(TrialSessionEvent),
+    ReputationAttested(ReputationAttestedEvent),
+    ServiceRated(ServiceRatedEvent),
+    PublicGoodsFeesCollected(PublicGoodsFeesCollectedEvent),
+    AgentOwnershipTransferred(AgentOwnershipTransferredEvent),
+}
+
+/// Events decoded from a single confirmed transaction
+pub struct TransactionEvents {
+    pub signature: String,
+    pub slot: u64,
+    pub events: Vec<ProgramEvent>,
+}
+
+impl ProgramEvent {
+    /// Decode an `emit_cpi!` self-invocation's instruction data (event
+    /// instruction tag + discriminator + borsh body)
+    pub fn decode_cpi(data: &[u8]) -> Option<Self> {
+        Self::decode(data.strip_prefix(EVENT_IX_TAG_LE.as_slice())?)
+    }
+
+    /// Decode a single event payload (discriminator + borsh body)
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < 8 {
+            return None;
+        }
+        let (disc, mut body) = data.split_at(8);
+
+        if disc == TradeEvent::DISCRIMINATOR {
+            return TradeEvent::deserialize(&mut body).ok().map(Self::Trade);
+        }
+        if disc == PaymentEvent::DISCRIMINATOR {
+            return PaymentEvent::deserialize(&mut body).ok().map(Self::Payment);
+        }
+        if disc == AgentServiceCallEvent::DISCRIMINATOR {
+            return AgentServiceCallEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::AgentServiceCall);
+        }
+        if disc == SnapshotEvent::DISCRIMINATOR {
+            return SnapshotEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::Snapshot);
+        }
+        if disc == AdminActionEvent::DISCRIMINATOR {
+            return AdminActionEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::AdminAction);
+        }
+        if disc == InsuranceClaimEvent::DISCRIMINATOR {
+            return InsuranceClaimEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::InsuranceClaim);
+        }
+        if disc == WebhookEvent::DISCRIMINATOR {
+            return WebhookEvent::deserialize(&mut body).ok().map(Self::Webhook);
+        }
+        if disc == AgentRenamedEvent::DISCRIMINATOR {
+            return AgentRenamedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::AgentRenamed);
+        }
+        if disc == AgentCreatedEvent::DISCRIMINATOR {
+            return AgentCreatedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::AgentCreated);
+        }
+        if disc == FeesClaimedEvent::DISCRIMINATOR {
+            return FeesClaimedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::FeesClaimed);
+        }
+        if disc == X402FreezeEvent::DISCRIMINATOR {
+            return X402FreezeEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::X402Freeze);
+        }
+        if disc == X402RecipientRotatedEvent::DISCRIMINATOR {
+            return X402RecipientRotatedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::X402RecipientRotated);
+        }
+        if disc == AgentGraduatedEvent::DISCRIMINATOR {
+            return AgentGraduatedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::AgentGraduated);
+        }
+        if disc == AgentFeeOverrideEvent::DISCRIMINATOR {
+            return AgentFeeOverrideEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::AgentFeeOverride);
+        }
+        if disc == ServiceDeprecatedEvent::DISCRIMINATOR {
+            return ServiceDeprecatedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::ServiceDeprecated);
+        }
+        if disc == RevenueDepositedEvent::DISCRIMINATOR {
+            return RevenueDepositedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::RevenueDeposited);
+        }
+        if disc == X402ConfigUpdatedEvent::DISCRIMINATOR {
+            return X402ConfigUpdatedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::X402ConfigUpdated);
+        }
+        if disc == ServiceTermsEvent::DISCRIMINATOR {
+            return ServiceTermsEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::ServiceTerms);
+        }
+        if disc == WorkTaskEvent::DISCRIMINATOR {
+            return WorkTaskEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::WorkTask);
+        }
+        if disc == TrialSessionEvent::DISCRIMINATOR {
+            return TrialSessionEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::TrialSession);
+        }
+        if disc == ReputationAttestedEvent::DISCRIMINATOR {
+            return ReputationAttestedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::ReputationAttested);
+        }
+        if disc == ServiceRatedEvent::DISCRIMINATOR {
+            return ServiceRatedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::ServiceRated);
+        }
+        if disc == PublicGoodsFeesCollectedEvent::DISCRIMINATOR {
+            return PublicGoodsFeesCollectedEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::PublicGoodsFeesCollected);
+        }
+        if disc == AgentOwnershipTransferredEvent::DISCRIMINATOR {
+            return AgentOwnershipTransferredEvent::deserialize(&mut body)
+                .ok()
+                .map(Self::AgentOwnershipTransferred);
+        }
+
+        None
+    }
+}
+
+/// Extract all program events from a transaction's log messages
+///
+/// Logs are not attributed to a program here, so callers should only pass
+/// logs from transactions that invoked the agent factory.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<ProgramEvent> {
+    logs.iter()
+        .filter_map(|line| line.as_ref().strip_prefix(PROGRAM_DATA_PREFIX))
+        .filter_map(|encoded| STANDARD.decode(encoded).ok())
+        .filter_map(|data| ProgramEvent::decode(&data))
+        .collect()
+}
+
+/// Extract all program events from a fetched transaction's inner
+/// instructions (`emit_cpi!` self-invocations)
+///
+/// Unlike logs, inner instructions are never truncated. Only the program can
+/// sign as its event authority, so every event instruction found here was
+/// emitted by the program. The transaction must be fetched with a binary
+/// encoding; failed transactions yield no events.
+pub fn parse_transaction(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<ProgramEvent> {
+    let Some(meta) = &tx.transaction.meta else {
+        return Vec::new();
+    };
+    if meta.err.is_some() {
+        return Vec::new();
+    }
+    let Some(transaction) = tx.transaction.transaction.decode() else {
+        return Vec::new();
+    };
+
+    // Static keys, then lookup table keys (writable before readonly)
+    let mut account_keys = transaction.message.static_account_keys().to_vec();
+    let loaded: Option<&UiLoadedAddresses> = meta.loaded_addresses.as_ref().into();
+    if let Some(loaded) = loaded {
+        account_keys.extend(
+            loaded
+                .writable
+                .iter()
+                .chain(&loaded.readonly)
+                .filter_map(|key| key.parse::<Pubkey>().ok()),
+        );
+    }
+
+    let inner_instructions: Option<&Vec<UiInnerInstructions>> =
+        meta.inner_instructions.as_ref().into();
+    inner_instructions
+        .into_iter()
+        .flatten()
+        .flat_map(|inner| &inner.instructions)
+        .filter_map(|instruction| match instruction {
+            UiInstruction::Compiled(ix) => Some(ix),
+            UiInstruction::Parsed(_) => None,
+        })
+        .filter(|ix| account_keys.get(ix.program_id_index as usize) == Some(&PROGRAM_ID))
+        .filter_map(|ix| bs58::decode(&ix.data).into_vec().ok())
+        .filter_map(|data| ProgramEvent::decode_cpi(&data))
+        .collect()
+}
@@ -0,0 +1,10 @@
+//! Bindings generated by `declare_program!` from `idls/agent_factory.json`
+//! (regenerate with `anchor idl build -o idls/agent_factory.json` after
+//! changing the program). Account, type, event and instruction definitions
+//! come straight from the IDL, so programs and tools that only need the
+//! interface can use them without depending on the program crate.
+
+// The generated CPI helpers take one argument per instruction argument
+#![allow(clippy::too_many_arguments)]
+
+anchor_lang::declare_program!(agent_factory);
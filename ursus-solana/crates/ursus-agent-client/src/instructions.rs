@@ -0,0 +1,3438 @@
+use agent_factory::{accounts, instruction};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{system_program, sysvar};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::accounts::{
+    AdminAction, AgentCommit, CreatorFeeMode, CurveTranche, KeeperTaskKind, MeterUnit,
+    NameReservation, ProposalAction, RevenuePool, REPUTATION_SOURCE_LEN,
+};
+use crate::pda::{
+    find_admin_action_pda, find_affiliate_pda, find_agent_commit_pda, find_agent_pda,
+    find_basket_mint_pda, find_buyer_record_pda, find_commitment_pda, find_consumer_stats_pda,
+    find_creator_fee_vault_pda, find_creator_stats_pda, find_dao_treasury_pda, find_dca_pda,
+    find_dca_vault_pda, find_dispute_pda, find_early_buyer_claim_pda, find_early_buyer_rewards_pda,
+    find_early_buyer_vault_pda, find_event_authority_pda, find_factory_pda, find_governance_pda,
+    find_index_basket_pda, find_insurance_fund_pda, find_insurance_policy_pda, find_job_escrow_pda,
+    find_job_pda, find_keeper_task_pda, find_liquidity_mining_pda, find_listing_reserve_pda,
+    find_listing_reserve_vault_pda, find_lm_lp_vault_pda, find_lm_position_pda,
+    find_lm_reward_vault_pda, find_lock_pda, find_lock_vault_pda, find_metadata_pda,
+    find_migration_old_vault_pda, find_migration_pda, find_migration_vault_pda, find_mint_pda,
+    find_name_reservation_pda, find_ownership_mint_pda, find_payment_escrow_pda,
+    find_payment_record_pda, find_payment_stream_pda, find_platform_fee_vault_pda,
+    find_presale_pda, find_price_history_pda, find_proposal_pda, find_protocol_liquidity_pda,
+    find_provider_bond_pda, find_rating_pda, find_reputation_pda, find_revenue_split_pda,
+    find_service_agreement_pda, find_service_quote_pda, find_snapshot_pda, find_sol_vault_pda,
+    find_stake_position_pda, find_stake_vault_pda, find_staking_pool_pda, find_stream_escrow_pda,
+    find_task_escrow_pda, find_trending_pda, find_trial_escrow_pda, find_trial_session_pda,
+    find_vote_pda, find_work_queue_pda, find_work_task_pda, find_x402_config_pda,
+    find_x402_treasury_pda,
+};
+use crate::PROGRAM_ID;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `holder`'s ATA for `agent`'s ownership NFT, which creator-gated
+/// instructions check
+fn ownership_account(agent: &Pubkey, holder: &Pubkey) -> Pubkey {
+    get_associated_token_address(holder, &find_ownership_mint_pda(agent).0)
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+pub fn initialize(
+    authority: &Pubkey,
+    platform_treasury: &Pubkey,
+    creation_fee: u64,
+) -> Instruction {
+    build(
+        accounts::Initialize {
+            factory: find_factory_pda().0,
+            authority: *authority,
+            platform_treasury: *platform_treasury,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize { creation_fee },
+    )
+}
+
+/// Build `queue_admin_action`; `action_id` must be the factory's current
+/// `admin_action_count`. With a multisig authority, `authority` is the vault
+/// PDA (signing via CPI) and `payer` a member wallet; otherwise both are the
+/// authority.
+///
+/// `platform_agent` is the platform token's agent, required by
+/// `SetFeeDiscounts` unless it turns discounts off.
+pub fn queue_admin_action(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    action_id: u64,
+    action: AdminAction,
+    platform_agent: Option<&Pubkey>,
+) -> Instruction {
+    let platform_staking_pool = match action {
+        AdminAction::SetFeeDiscounts { staking_pool, .. } if platform_agent.is_some() => {
+            Some(staking_pool)
+        }
+        _ => None,
+    };
+    let protocol_liquidity = match action {
+        AdminAction::SetProtocolLiquidity {
+            protocol_liquidity_bps,
+        } if protocol_liquidity_bps > 0 => Some(find_protocol_liquidity_pda().0),
+        _ => None,
+    };
+    build(
+        accounts::QueueAdminAction {
+            factory: find_factory_pda().0,
+            queued_action: find_admin_action_pda(action_id).0,
+            authority: *authority,
+            payer: *payer,
+            system_program: system_program::ID,
+            platform_staking_pool,
+            platform_agent: platform_agent.copied(),
+            protocol_liquidity,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::QueueAdminAction { action },
+    )
+}
+
+/// Build `execute_admin_action`; `rent_payer` is the queued action's
+/// `rent_payer`. Needs no signer besides the fee payer.
+pub fn execute_admin_action(action_id: u64, rent_payer: &Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteAdminAction {
+            factory: find_factory_pda().0,
+            queued_action: find_admin_action_pda(action_id).0,
+            rent_payer: *rent_payer,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::ExecuteAdminAction {},
+    )
+}
+
+pub fn cancel_admin_action(authority: &Pubkey, action_id: u64, rent_payer: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelAdminAction {
+            factory: find_factory_pda().0,
+            queued_action: find_admin_action_pda(action_id).0,
+            authority: *authority,
+            rent_payer: *rent_payer,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::CancelAdminAction {},
+    )
+}
+
+// ============================================================================
+// Insurance fund
+// ============================================================================
+
+/// Build `initialize_insurance_fund`; `payer` is the authority unless the
+/// authority is a multisig PDA
+pub fn initialize_insurance_fund(authority: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeInsuranceFund {
+            factory: find_factory_pda().0,
+            insurance_fund: find_insurance_fund_pda().0,
+            authority: *authority,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeInsuranceFund {},
+    )
+}
+
+pub fn collect_insurance_fees(agent: &Pubkey) -> Instruction {
+    build(
+        accounts::CollectInsuranceFees {
+            factory: find_factory_pda().0,
+            agent: *agent,
+            insurance_fund: find_insurance_fund_pda().0,
+            system_program: system_program::ID,
+            platform_fee_vault: find_platform_fee_vault_pda(agent).0,
+        },
+        instruction::CollectInsuranceFees {},
+    )
+}
+
+/// Build `collect_public_goods_fees`; `recipient` must be the factory's
+/// public goods recipient
+pub fn collect_public_goods_fees(agent: &Pubkey, recipient: &Pubkey) -> Instruction {
+    build(
+        accounts::CollectPublicGoodsFees {
+            factory: find_factory_pda().0,
+            agent: *agent,
+            public_goods_recipient: *recipient,
+            system_program: system_program::ID,
+            platform_fee_vault: find_platform_fee_vault_pda(agent).0,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::CollectPublicGoodsFees {},
+    )
+}
+
+pub fn fund_insurance(funder: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::FundInsurance {
+            insurance_fund: find_insurance_fund_pda().0,
+            funder: *funder,
+            system_program: system_program::ID,
+        },
+        instruction::FundInsurance { amount },
+    )
+}
+
+pub fn pay_claim(
+    authority: &Pubkey,
+    agent: &Pubkey,
+    recipient: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::PayClaim {
+            factory: find_factory_pda().0,
+            insurance_fund: find_insurance_fund_pda().0,
+            agent: *agent,
+            recipient: *recipient,
+            authority: *authority,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::PayClaim { amount },
+    )
+}
+
+/// Build `initialize_protocol_liquidity`; `payer` is the authority unless
+/// the authority is a multisig PDA
+pub fn initialize_protocol_liquidity(authority: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeProtocolLiquidity {
+            factory: find_factory_pda().0,
+            protocol_liquidity: find_protocol_liquidity_pda().0,
+            authority: *authority,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeProtocolLiquidity {},
+    )
+}
+
+/// Build `withdraw_protocol_liquidity`; `mint` selects the agent token
+/// withdrawn to `recipient`'s ATA and is needed when `token_amount` > 0
+pub fn withdraw_protocol_liquidity(
+    authority: &Pubkey,
+    recipient: &Pubkey,
+    lamports: u64,
+    token_amount: u64,
+    mint: Option<&Pubkey>,
+) -> Instruction {
+    let protocol_liquidity = find_protocol_liquidity_pda().0;
+    build(
+        accounts::WithdrawProtocolLiquidity {
+            factory: find_factory_pda().0,
+            protocol_liquidity,
+            recipient: *recipient,
+            protocol_liquidity_token_account: mint
+                .map(|mint| get_associated_token_address(&protocol_liquidity, mint)),
+            recipient_token_account: mint.map(|mint| get_associated_token_address(recipient, mint)),
+            authority: *authority,
+            token_program: spl_token_id(),
+        },
+        instruction::WithdrawProtocolLiquidity {
+            lamports,
+            token_amount,
+        },
+    )
+}
+
+/// Build `set_creator_standing`; `payer` is the authority unless the
+/// authority is a multisig PDA
+pub fn set_creator_standing(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    creator: &Pubkey,
+    rugged_agents: u16,
+    is_blacklisted: bool,
+) -> Instruction {
+    build(
+        accounts::SetCreatorStanding {
+            factory: find_factory_pda().0,
+            creator: *creator,
+            creator_stats: find_creator_stats_pda(creator).0,
+            authority: *authority,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::SetCreatorStanding {
+            rugged_agents,
+            is_blacklisted,
+        },
+    )
+}
+
+/// Build `set_creation_rate_limit` (0 = no limit)
+pub fn set_creation_rate_limit(
+    authority: &Pubkey,
+    max_creations_per_window: u32,
+    window_secs: i64,
+) -> Instruction {
+    build(
+        accounts::SetCreationRateLimit {
+            factory: find_factory_pda().0,
+            authority: *authority,
+        },
+        instruction::SetCreationRateLimit {
+            max_creations_per_window,
+            window_secs,
+        },
+    )
+}
+
+/// Build `set_quote_lamport_rate`: lamports per `QUOTE_RATE_UNIT` base
+/// units of the quote mint (0 = not valued)
+pub fn set_quote_lamport_rate(authority: &Pubkey, lamports: u64) -> Instruction {
+    build(
+        accounts::SetQuoteLamportRate {
+            factory: find_factory_pda().0,
+            authority: *authority,
+        },
+        instruction::SetQuoteLamportRate { lamports },
+    )
+}
+
+/// Build `set_agent_fee_override`; fees are in bps, at most the standard fees
+pub fn set_agent_fee_override(
+    authority: &Pubkey,
+    agent: &Pubkey,
+    platform_fee_bps: u16,
+    creator_fee_bps: u16,
+) -> Instruction {
+    build(
+        accounts::SetAgentFeeOverride {
+            factory: find_factory_pda().0,
+            agent: *agent,
+            authority: *authority,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::SetAgentFeeOverride {
+            platform_fee_bps,
+            creator_fee_bps,
+        },
+    )
+}
+
+/// Build `freeze_x402`, hard-stopping payments to `agent`
+pub fn freeze_x402(authority: &Pubkey, agent: &Pubkey) -> Instruction {
+    build(
+        freeze_x402_accounts(authority, agent),
+        instruction::FreezeX402 {},
+    )
+}
+
+/// Build `thaw_x402`, lifting a freeze on payments to `agent`
+pub fn thaw_x402(authority: &Pubkey, agent: &Pubkey) -> Instruction {
+    build(
+        freeze_x402_accounts(authority, agent),
+        instruction::ThawX402 {},
+    )
+}
+
+fn freeze_x402_accounts(authority: &Pubkey, agent: &Pubkey) -> accounts::FreezeX402 {
+    accounts::FreezeX402 {
+        factory: find_factory_pda().0,
+        x402_config: find_x402_config_pda(agent).0,
+        authority: *authority,
+        event_authority: find_event_authority_pda().0,
+        program: PROGRAM_ID,
+    }
+}
+
+/// Build `reserve_name` for a name or symbol; `payer` is the authority
+/// unless the authority is a multisig PDA, and a default `owner` releases
+/// the name
+pub fn reserve_name(authority: &Pubkey, payer: &Pubkey, name: &str, owner: &Pubkey) -> Instruction {
+    build(
+        accounts::ReserveName {
+            factory: find_factory_pda().0,
+            name_reservation: find_name_reservation_pda(name).0,
+            authority: *authority,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::ReserveName {
+            name_hash: NameReservation::name_hash(name),
+            owner: *owner,
+        },
+    )
+}
+
+// ============================================================================
+// Agent lifecycle
+// ============================================================================
+
+/// Metadata supplied at agent creation
+#[derive(Debug, Clone, Default)]
+pub struct CreateAgentArgs {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub instructions: String,
+    pub model: String,
+    /// Discovery tags (up to `MAX_AGENT_TAGS`, lowercase)
+    pub tags: Vec<String>,
+    /// Pay the creation fee in this quote mint (the factory's `quote_mint`)
+    /// instead of SOL, from the creator's ATA to the treasury's ATA
+    pub fee_mint: Option<Pubkey>,
+}
+
+fn create_agent_accounts(
+    agent_id: u64,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    args: &CreateAgentArgs,
+) -> accounts::CreateAgent {
+    let fee_mint = args.fee_mint;
+    let agent = find_agent_pda(agent_id).0;
+    let mint = find_mint_pda(&agent).0;
+    let ownership_mint = find_ownership_mint_pda(&agent).0;
+    accounts::CreateAgent {
+        factory: find_factory_pda().0,
+        agent,
+        mint,
+        mint_metadata: find_metadata_pda(&mint).0,
+        ownership_mint,
+        ownership_metadata: find_metadata_pda(&ownership_mint).0,
+        creator_ownership_account: get_associated_token_address(creator, &ownership_mint),
+        creator: *creator,
+        platform_treasury: *platform_treasury,
+        token_program: spl_token_id(),
+        associated_token_program: spl_associated_token_account::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+        metadata_program: anchor_spl::metadata::ID,
+        creator_stats: find_creator_stats_pda(creator).0,
+        creator_quote_account: fee_mint.map(|mint| get_associated_token_address(creator, &mint)),
+        treasury_quote_account: fee_mint
+            .map(|mint| get_associated_token_address(platform_treasury, &mint)),
+        name_reservation: find_name_reservation_pda(&args.name).0,
+        symbol_reservation: find_name_reservation_pda(&args.symbol).0,
+        event_authority: find_event_authority_pda().0,
+        program: PROGRAM_ID,
+        sol_vault: find_sol_vault_pda(&agent).0,
+        platform_fee_vault: find_platform_fee_vault_pda(&agent).0,
+        creator_fee_vault: find_creator_fee_vault_pda(&agent).0,
+    }
+}
+
+/// Build `create_agent`; `agent_id` must equal the factory's current `total_agents`
+pub fn create_agent(
+    agent_id: u64,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    args: CreateAgentArgs,
+) -> Instruction {
+    build(
+        create_agent_accounts(agent_id, creator, platform_treasury, &args),
+        instruction::CreateAgent {
+            name: args.name,
+            symbol: args.symbol,
+            description: args.description,
+            agent_instructions: args.instructions,
+            model: args.model,
+            tags: args.tags,
+        },
+    )
+}
+
+/// Build `create_agent_with_curve` for a stepped curve; the tranche supplies
+/// must add up to the bonding curve supply (800M tokens)
+pub fn create_agent_with_curve(
+    agent_id: u64,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    args: CreateAgentArgs,
+    tranches: Vec<CurveTranche>,
+) -> Instruction {
+    build(
+        create_agent_accounts(agent_id, creator, platform_treasury, &args),
+        instruction::CreateAgentWithCurve {
+            name: args.name,
+            symbol: args.symbol,
+            description: args.description,
+            agent_instructions: args.instructions,
+            model: args.model,
+            tags: args.tags,
+            tranches,
+        },
+    )
+}
+
+/// Build `create_agent_with_fee_mode` for a launch whose creator fee is
+/// waived or routed entirely to the staking pool
+pub fn create_agent_with_fee_mode(
+    agent_id: u64,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    args: CreateAgentArgs,
+    creator_fee_mode: CreatorFeeMode,
+) -> Instruction {
+    build(
+        create_agent_accounts(agent_id, creator, platform_treasury, &args),
+        instruction::CreateAgentWithFeeMode {
+            name: args.name,
+            symbol: args.symbol,
+            description: args.description,
+            agent_instructions: args.instructions,
+            model: args.model,
+            tags: args.tags,
+            creator_fee_mode,
+        },
+    )
+}
+
+/// Build `create_agent_lbp` for a liquidity bootstrapping launch opening at
+/// `start_price_multiplier` times the regular price (2-100x) and decaying over
+/// `duration_secs` (at most 30 days)
+pub fn create_agent_lbp(
+    agent_id: u64,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    args: CreateAgentArgs,
+    duration_secs: i64,
+    start_price_multiplier: u64,
+) -> Instruction {
+    build(
+        create_agent_accounts(agent_id, creator, platform_treasury, &args),
+        instruction::CreateAgentLbp {
+            name: args.name,
+            symbol: args.symbol,
+            description: args.description,
+            agent_instructions: args.instructions,
+            model: args.model,
+            tags: args.tags,
+            duration_secs,
+            start_price_multiplier,
+        },
+    )
+}
+
+/// Build `create_agent_full`: create the agent, configure x402 (paid to the
+/// creator) and, if `dev_buy_sol_amount` is non-zero, buy into the curve with
+/// tokens minted to the creator's ATA
+pub fn create_agent_full(
+    agent_id: u64,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    args: CreateAgentArgs,
+    x402: X402Settings,
+    dev_buy_sol_amount: u64,
+    min_tokens_out: u64,
+) -> Instruction {
+    let agent = find_agent_pda(agent_id).0;
+    build(
+        accounts::CreateAgentFull {
+            create: create_agent_accounts(agent_id, creator, platform_treasury, &args),
+            x402_config: find_x402_config_pda(&agent).0,
+            creator_token_account: get_associated_token_address(creator, &find_mint_pda(&agent).0),
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::CreateAgentFull {
+            name: args.name,
+            symbol: args.symbol,
+            description: args.description,
+            agent_instructions: args.instructions,
+            model: args.model,
+            tags: args.tags,
+            x402,
+            dev_buy_sol_amount,
+            min_tokens_out,
+        },
+    )
+}
+
+/// `AgentCommit::commit_hash` of a launch's fields and `salt`
+pub fn agent_commit_hash(args: &CreateAgentArgs, salt: &[u8; 32]) -> [u8; 32] {
+    AgentCommit::commit_hash(
+        &args.name,
+        &args.symbol,
+        &args.description,
+        &args.instructions,
+        &args.model,
+        &args.tags,
+        salt,
+    )
+}
+
+/// Build `commit_agent` for a sealed launch of `args`; keep `salt` secret
+/// until `reveal_agent`
+pub fn commit_agent(creator: &Pubkey, args: &CreateAgentArgs, salt: &[u8; 32]) -> Instruction {
+    let commit_hash = agent_commit_hash(args, salt);
+    build(
+        accounts::CommitAgent {
+            agent_commit: find_agent_commit_pda(creator, &commit_hash).0,
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        instruction::CommitAgent { commit_hash },
+    )
+}
+
+/// Build `reveal_agent` for a launch committed with `commit_agent`; send it
+/// in a later slot than the commitment. `agent_id` is the factory's
+/// `total_agents` at reveal time.
+pub fn reveal_agent(
+    agent_id: u64,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    args: CreateAgentArgs,
+    salt: [u8; 32],
+) -> Instruction {
+    let commit_hash = agent_commit_hash(&args, &salt);
+    build(
+        accounts::RevealAgent {
+            create: create_agent_accounts(agent_id, creator, platform_treasury, &args),
+            agent_commit: find_agent_commit_pda(creator, &commit_hash).0,
+        },
+        instruction::RevealAgent {
+            name: args.name,
+            symbol: args.symbol,
+            description: args.description,
+            agent_instructions: args.instructions,
+            model: args.model,
+            tags: args.tags,
+            salt,
+        },
+    )
+}
+
+/// Build `create_agent_with_listing_reserve`: create the agent and mint
+/// `reserve_bps` of its supply into a listing reserve releasable
+/// `release_delay_secs` after graduation
+pub fn create_agent_with_listing_reserve(
+    agent_id: u64,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    args: CreateAgentArgs,
+    reserve_bps: u16,
+    release_delay_secs: i64,
+) -> Instruction {
+    let agent = find_agent_pda(agent_id).0;
+    let listing_reserve = find_listing_reserve_pda(&agent).0;
+    build(
+        accounts::CreateAgentWithListingReserve {
+            create: create_agent_accounts(agent_id, creator, platform_treasury, &args),
+            listing_reserve,
+            listing_reserve_vault: find_listing_reserve_vault_pda(&listing_reserve).0,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+        },
+        instruction::CreateAgentWithListingReserve {
+            name: args.name,
+            symbol: args.symbol,
+            description: args.description,
+            agent_instructions: args.instructions,
+            model: args.model,
+            tags: args.tags,
+            reserve_bps,
+            release_delay_secs,
+        },
+    )
+}
+
+/// Build a launch bundle: one `create_agent` per entry, with ids assigned
+/// sequentially from `first_agent_id` (the factory's `total_agents`) and
+/// `name_prefix` prepended to every name
+pub fn create_agent_batch(
+    first_agent_id: u64,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    name_prefix: &str,
+    agents: Vec<CreateAgentArgs>,
+) -> Vec<Instruction> {
+    agents
+        .into_iter()
+        .zip(first_agent_id..)
+        .map(|(mut args, agent_id)| {
+            args.name = format!("{}{}", name_prefix, args.name);
+            create_agent(agent_id, creator, platform_treasury, args)
+        })
+        .collect()
+}
+
+/// Build `buy_tokens`; tokens are minted to `recipient`'s ATA (pass `buyer`
+/// to buy for yourself), `platform_stake` is the buyer's platform token
+/// stake position, passed to get the platform fee discount, and
+/// `price_history` (the agent's candle accumulator) and `trending` (the
+/// factory leaderboard) are passed when they exist
+#[allow(clippy::too_many_arguments)]
+pub fn buy_tokens(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    buyer: &Pubkey,
+    recipient: &Pubkey,
+    sol_amount: u64,
+    min_tokens_out: u64,
+    platform_stake: Option<&Pubkey>,
+    price_history: Option<&Pubkey>,
+    trending: Option<&Pubkey>,
+) -> Instruction {
+    let mint = find_mint_pda(agent).0;
+    build(
+        accounts::BuyTokens {
+            agent: *agent,
+            mint,
+            recipient_token_account: get_associated_token_address(recipient, &mint),
+            buyer: *buyer,
+            recipient: *recipient,
+            buyer_record: find_buyer_record_pda(agent, recipient).0,
+            creator: *creator,
+            platform_treasury: *platform_treasury,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            factory: find_factory_pda().0,
+            platform_stake: platform_stake.copied(),
+            price_history: price_history.copied(),
+            trending: trending.copied(),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+            sol_vault: find_sol_vault_pda(agent).0,
+            platform_fee_vault: find_platform_fee_vault_pda(agent).0,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::BuyTokens {
+            sol_amount,
+            min_tokens_out,
+        },
+    )
+}
+
+/// Build `sell_tokens`; `platform_stake` is the seller's platform token
+/// stake position, passed to get the platform fee discount, and
+/// `price_history` (the agent's candle accumulator) and `trending` (the
+/// factory leaderboard) are passed when they exist.
+#[allow(clippy::too_many_arguments)]
+pub fn sell_tokens(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    seller: &Pubkey,
+    token_amount: u64,
+    min_sol_out: u64,
+    platform_stake: Option<&Pubkey>,
+    price_history: Option<&Pubkey>,
+    trending: Option<&Pubkey>,
+) -> Instruction {
+    let mint = find_mint_pda(agent).0;
+    build(
+        accounts::SellTokens {
+            agent: *agent,
+            mint,
+            seller_token_account: get_associated_token_address(seller, &mint),
+            seller: *seller,
+            creator: *creator,
+            platform_treasury: *platform_treasury,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            factory: find_factory_pda().0,
+            platform_stake: platform_stake.copied(),
+            price_history: price_history.copied(),
+            trending: trending.copied(),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+            sol_vault: find_sol_vault_pda(agent).0,
+            platform_fee_vault: find_platform_fee_vault_pda(agent).0,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::SellTokens {
+            token_amount,
+            min_sol_out,
+        },
+    )
+}
+
+pub fn enable_refund_guarantee(agent: &Pubkey, creator: &Pubkey, days: u16) -> Instruction {
+    build(
+        accounts::EnableRefundGuarantee {
+            agent: *agent,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::EnableRefundGuarantee { days },
+    )
+}
+
+pub fn redeem_for_sol(
+    agent: &Pubkey,
+    holder: &Pubkey,
+    token_amount: u64,
+    min_sol_out: u64,
+) -> Instruction {
+    let mint = find_mint_pda(agent).0;
+    build(
+        accounts::RedeemForSol {
+            agent: *agent,
+            mint,
+            holder_token_account: get_associated_token_address(holder, &mint),
+            holder: *holder,
+            token_program: spl_token_id(),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+            system_program: system_program::ID,
+            sol_vault: find_sol_vault_pda(agent).0,
+            platform_fee_vault: find_platform_fee_vault_pda(agent).0,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::RedeemForSol {
+            token_amount,
+            min_sol_out,
+        },
+    )
+}
+
+/// Build `graduate_agent`; the unsold curve tokens are burned instead of
+/// going into the pool if the creator chose so with `set_graduation_burn`.
+/// `creator` is the agent's creator,
+/// whose stats are credited. Set `protocol_liquidity` while the factory keeps
+/// a protocol-owned liquidity share; the vault's ATA for the agent mint must
+/// exist.
+pub fn graduate_agent(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    authority: &Pubkey,
+    dex_program: &Pubkey,
+    protocol_liquidity: bool,
+) -> Instruction {
+    let pol = protocol_liquidity.then(|| find_protocol_liquidity_pda().0);
+    build(
+        accounts::GraduateAgent {
+            agent: *agent,
+            mint: find_mint_pda(agent).0,
+            authority: *authority,
+            dex_program: *dex_program,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            creator_stats: find_creator_stats_pda(creator).0,
+            factory: find_factory_pda().0,
+            protocol_liquidity: pol,
+            protocol_liquidity_token_account: pol
+                .map(|pol| get_associated_token_address(&pol, &find_mint_pda(agent).0)),
+            sol_vault: find_sol_vault_pda(agent).0,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::GraduateAgent {},
+    )
+}
+
+/// Build `graduate_batch` for `agents`, given as `(agent, creator)` pairs.
+/// Agents that can't graduate are skipped on-chain. Set `protocol_liquidity`
+/// as for `graduate_agent`.
+pub fn graduate_batch(
+    cranker: &Pubkey,
+    agents: &[(Pubkey, Pubkey)],
+    protocol_liquidity: bool,
+) -> Instruction {
+    let pol = protocol_liquidity.then(|| find_protocol_liquidity_pda().0);
+    let mut ix = build(
+        accounts::GraduateBatch {
+            factory: find_factory_pda().0,
+            protocol_liquidity: pol,
+            cranker: *cranker,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::GraduateBatch {},
+    );
+    ix.accounts
+        .extend(agents.iter().flat_map(|(agent, creator)| {
+            let mint = find_mint_pda(agent).0;
+            [
+                AccountMeta::new(*agent, false),
+                AccountMeta::new(mint, false),
+                AccountMeta::new(find_sol_vault_pda(agent).0, false),
+                AccountMeta::new(find_creator_stats_pda(creator).0, false),
+            ]
+            .into_iter()
+            .chain(
+                pol.map(|pol| AccountMeta::new(get_associated_token_address(&pol, &mint), false)),
+            )
+        }));
+    ix
+}
+
+/// Build `claim_agent_ownership`; `holder` must hold the ownership NFT in
+/// their ATA
+pub fn claim_agent_ownership(agent: &Pubkey, holder: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimAgentOwnership {
+            agent: *agent,
+            ownership_token_account: get_associated_token_address(
+                holder,
+                &find_ownership_mint_pda(agent).0,
+            ),
+            holder: *holder,
+        },
+        instruction::ClaimAgentOwnership {},
+    )
+}
+
+/// Build `transfer_agent_ownership`, handing `agent` and its ownership NFT
+/// from `creator` to `new_creator`
+pub fn transfer_agent_ownership(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    new_creator: &Pubkey,
+) -> Instruction {
+    let ownership_mint = find_ownership_mint_pda(agent).0;
+    build(
+        accounts::TransferAgentOwnership {
+            agent: *agent,
+            ownership_mint,
+            creator_ownership_account: get_associated_token_address(creator, &ownership_mint),
+            creator: *creator,
+            new_creator: *new_creator,
+            new_creator_ownership_account: get_associated_token_address(
+                new_creator,
+                &ownership_mint,
+            ),
+            token_program: spl_token_id(),
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::TransferAgentOwnership {},
+    )
+}
+
+/// Pass `holds_ownership_nft` when the original creator still holds the ownership NFT,
+/// which lets them raise the royalty as well as lower it.
+pub fn set_creator_royalty(
+    agent: &Pubkey,
+    original_creator: &Pubkey,
+    holds_ownership_nft: bool,
+    royalty_bps: u16,
+) -> Instruction {
+    build(
+        accounts::SetCreatorRoyalty {
+            agent: *agent,
+            original_creator: *original_creator,
+            original_creator_ownership_account: holds_ownership_nft
+                .then(|| ownership_account(agent, original_creator)),
+        },
+        instruction::SetCreatorRoyalty { royalty_bps },
+    )
+}
+
+pub fn claim_royalties(agent: &Pubkey, original_creator: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimRoyalties {
+            agent: *agent,
+            original_creator: *original_creator,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+            system_program: system_program::ID,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::ClaimRoyalties {},
+    )
+}
+
+pub fn set_same_slot_protection(agent: &Pubkey, creator: &Pubkey, enabled: bool) -> Instruction {
+    build(
+        accounts::SetSameSlotProtection {
+            agent: *agent,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetSameSlotProtection { enabled },
+    )
+}
+
+pub fn set_agent_model(agent: &Pubkey, creator: &Pubkey, model: &str) -> Instruction {
+    build(
+        accounts::SetAgentModel {
+            factory: find_factory_pda().0,
+            agent: *agent,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetAgentModel {
+            model: model.to_string(),
+        },
+    )
+}
+
+pub fn add_agent_tag(agent: &Pubkey, creator: &Pubkey, tag: &str) -> Instruction {
+    build(
+        accounts::AddAgentTag {
+            agent: *agent,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::AddAgentTag {
+            tag: tag.to_string(),
+        },
+    )
+}
+
+pub fn remove_agent_tag(agent: &Pubkey, creator: &Pubkey, tag: &str) -> Instruction {
+    build(
+        accounts::RemoveAgentTag {
+            agent: *agent,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::RemoveAgentTag {
+            tag: tag.to_string(),
+        },
+    )
+}
+
+/// `mint` and `ownership_mint` are the agent account's, whose Metaplex
+/// metadata is renamed too
+pub fn rename_agent(
+    agent: &Pubkey,
+    mint: &Pubkey,
+    ownership_mint: &Pubkey,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    new_name: &str,
+    new_symbol: &str,
+) -> Instruction {
+    build(
+        accounts::RenameAgent {
+            factory: find_factory_pda().0,
+            agent: *agent,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            platform_treasury: *platform_treasury,
+            system_program: system_program::ID,
+            name_reservation: find_name_reservation_pda(new_name).0,
+            symbol_reservation: find_name_reservation_pda(new_symbol).0,
+            mint_metadata: find_metadata_pda(mint).0,
+            ownership_metadata: find_metadata_pda(ownership_mint).0,
+            metadata_program: anchor_spl::metadata::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::RenameAgent {
+            new_name: new_name.to_string(),
+            new_symbol: new_symbol.to_string(),
+        },
+    )
+}
+
+pub fn set_max_buy(agent: &Pubkey, creator: &Pubkey, max_buy_bps_of_reserves: u16) -> Instruction {
+    build(
+        accounts::SetMaxBuy {
+            agent: *agent,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetMaxBuy {
+            max_buy_bps_of_reserves,
+        },
+    )
+}
+
+pub fn set_graduation_burn(agent: &Pubkey, creator: &Pubkey, burn_remaining: bool) -> Instruction {
+    build(
+        accounts::SetGraduationBurn {
+            agent: *agent,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetGraduationBurn { burn_remaining },
+    )
+}
+
+pub fn claim_creator_fees(agent: &Pubkey, creator: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimCreatorFees {
+            agent: *agent,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+            system_program: system_program::ID,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::ClaimCreatorFees {},
+    )
+}
+
+/// Build `create_snapshot`; `snapshot_id` must equal the agent's current
+/// `snapshot_count`
+pub fn create_snapshot(agent: &Pubkey, creator: &Pubkey, snapshot_id: u64) -> Instruction {
+    build(
+        accounts::CreateSnapshot {
+            agent: *agent,
+            mint: find_mint_pda(agent).0,
+            snapshot: find_snapshot_pda(agent, snapshot_id).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            system_program: system_program::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::CreateSnapshot {},
+    )
+}
+
+/// Build `initialize_price_history`; permissionless, `payer` funds the
+/// account
+pub fn initialize_price_history(agent: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializePriceHistory {
+            agent: *agent,
+            price_history: find_price_history_pda(agent).0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::InitializePriceHistory {},
+    )
+}
+
+/// Build `get_twap`; the TWAP comes back as the instruction's return data,
+/// for simulation or CPI
+pub fn get_twap(agent: &Pubkey, window_slots: u64) -> Instruction {
+    build(
+        accounts::GetTwap { agent: *agent },
+        instruction::GetTwap { window_slots },
+    )
+}
+
+/// Build `view_price`; the `PriceView` comes back as the instruction's
+/// return data, for simulation or CPI
+pub fn view_price(agent: &Pubkey) -> Instruction {
+    build(
+        accounts::ViewPrice { agent: *agent },
+        instruction::ViewPrice {},
+    )
+}
+
+/// Build `get_agent_manifest`; the `AgentManifest` comes back as the
+/// instruction's return data. `x402_config` is whether the agent has one;
+/// `token_accounts` are the payment recipient's token accounts for the
+/// mints to check it accepts.
+pub fn get_agent_manifest(
+    agent: &Pubkey,
+    x402_config: bool,
+    token_accounts: &[Pubkey],
+) -> Instruction {
+    let mut ix = build(
+        accounts::GetAgentManifest {
+            agent: *agent,
+            x402_config: x402_config.then(|| find_x402_config_pda(agent).0),
+        },
+        instruction::GetAgentManifest {},
+    );
+    ix.accounts.extend(
+        token_accounts
+            .iter()
+            .map(|account| AccountMeta::new_readonly(*account, false)),
+    );
+    ix
+}
+
+/// Build `get_layout_info`; the `LayoutInfo` of the account type with
+/// `discriminator` comes back as the instruction's return data
+pub fn get_layout_info(discriminator: [u8; 8]) -> Instruction {
+    build(
+        accounts::GetLayoutInfo {
+            factory: find_factory_pda().0,
+        },
+        instruction::GetLayoutInfo { discriminator },
+    )
+}
+
+/// Build `initialize_trending`; permissionless, `payer` funds the account
+pub fn initialize_trending(payer: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeTrending {
+            trending: find_trending_pda().0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeTrending {},
+    )
+}
+
+// ============================================================================
+// Token Locks
+// ============================================================================
+
+/// Build `lock_tokens`; tokens are taken from the owner's ATA
+pub fn lock_tokens(
+    agent: &Pubkey,
+    owner: &Pubkey,
+    lock_id: u64,
+    amount: u64,
+    unlock_ts: i64,
+) -> Instruction {
+    let mint = find_mint_pda(agent).0;
+    let lock = find_lock_pda(agent, owner, lock_id).0;
+    build(
+        accounts::LockTokens {
+            agent: *agent,
+            mint,
+            lock,
+            vault: find_lock_vault_pda(&lock).0,
+            owner_token_account: get_associated_token_address(owner, &mint),
+            owner: *owner,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::LockTokens {
+            lock_id,
+            amount,
+            unlock_ts,
+        },
+    )
+}
+
+/// Build `unlock_tokens`; tokens return to the owner's ATA
+pub fn unlock_tokens(agent: &Pubkey, owner: &Pubkey, lock_id: u64) -> Instruction {
+    let lock = find_lock_pda(agent, owner, lock_id).0;
+    build(
+        accounts::UnlockTokens {
+            lock,
+            vault: find_lock_vault_pda(&lock).0,
+            owner_token_account: get_associated_token_address(owner, &find_mint_pda(agent).0),
+            owner: *owner,
+            token_program: spl_token_id(),
+        },
+        instruction::UnlockTokens {},
+    )
+}
+
+/// Build `release_listing_reserve`, sending `amount` reserved tokens to
+/// `destination` (a token account of the agent's mint)
+pub fn release_listing_reserve(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let listing_reserve = find_listing_reserve_pda(agent).0;
+    build(
+        accounts::ReleaseListingReserve {
+            agent: *agent,
+            listing_reserve,
+            listing_reserve_vault: find_listing_reserve_vault_pda(&listing_reserve).0,
+            destination: *destination,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            token_program: spl_token_id(),
+        },
+        instruction::ReleaseListingReserve { amount },
+    )
+}
+
+// ============================================================================
+// Early buyer rewards
+// ============================================================================
+
+/// Build `create_early_buyer_rewards`; the bonuses are funded from the
+/// creator's ATA
+pub fn create_early_buyer_rewards(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    max_buyers: u64,
+    bonus_per_buyer: u64,
+    claim_deadline: i64,
+) -> Instruction {
+    let mint = find_mint_pda(agent).0;
+    let rewards = find_early_buyer_rewards_pda(agent).0;
+    build(
+        accounts::CreateEarlyBuyerRewards {
+            agent: *agent,
+            mint,
+            rewards,
+            vault: find_early_buyer_vault_pda(&rewards).0,
+            creator_token_account: get_associated_token_address(creator, &mint),
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateEarlyBuyerRewards {
+            max_buyers,
+            bonus_per_buyer,
+            claim_deadline,
+        },
+    )
+}
+
+/// Build `claim_early_buyer_bonus`; the bonus goes to the buyer's ATA
+pub fn claim_early_buyer_bonus(agent: &Pubkey, buyer: &Pubkey) -> Instruction {
+    let rewards = find_early_buyer_rewards_pda(agent).0;
+    build(
+        accounts::ClaimEarlyBuyerBonus {
+            rewards,
+            vault: find_early_buyer_vault_pda(&rewards).0,
+            buyer_record: find_buyer_record_pda(agent, buyer).0,
+            claim: find_early_buyer_claim_pda(&rewards, buyer).0,
+            buyer_token_account: get_associated_token_address(buyer, &find_mint_pda(agent).0),
+            buyer: *buyer,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+        },
+        instruction::ClaimEarlyBuyerBonus {},
+    )
+}
+
+/// Build `close_early_buyer_rewards`; unclaimed bonuses return to the
+/// creator's ATA
+pub fn close_early_buyer_rewards(agent: &Pubkey, creator: &Pubkey) -> Instruction {
+    let rewards = find_early_buyer_rewards_pda(agent).0;
+    build(
+        accounts::CloseEarlyBuyerRewards {
+            agent: *agent,
+            rewards,
+            vault: find_early_buyer_vault_pda(&rewards).0,
+            creator_token_account: get_associated_token_address(creator, &find_mint_pda(agent).0),
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            token_program: spl_token_id(),
+        },
+        instruction::CloseEarlyBuyerRewards {},
+    )
+}
+
+// ============================================================================
+// Liquidity mining
+// ============================================================================
+
+/// Build `create_liquidity_mining` for the pool whose LP token is `lp_mint`
+pub fn create_liquidity_mining(agent: &Pubkey, creator: &Pubkey, lp_mint: &Pubkey) -> Instruction {
+    let mining = find_liquidity_mining_pda(agent).0;
+    build(
+        accounts::CreateLiquidityMining {
+            agent: *agent,
+            mint: find_mint_pda(agent).0,
+            lp_mint: *lp_mint,
+            mining,
+            lp_vault: find_lm_lp_vault_pda(&mining).0,
+            reward_vault: find_lm_reward_vault_pda(&mining).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateLiquidityMining {},
+    )
+}
+
+/// Build `fund_liquidity_mining`; the rewards come from the creator's ATA
+pub fn fund_liquidity_mining(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    amount: u64,
+    duration_secs: i64,
+) -> Instruction {
+    let mining = find_liquidity_mining_pda(agent).0;
+    build(
+        accounts::FundLiquidityMining {
+            agent: *agent,
+            mining,
+            reward_vault: find_lm_reward_vault_pda(&mining).0,
+            creator_token_account: get_associated_token_address(creator, &find_mint_pda(agent).0),
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            token_program: spl_token_id(),
+        },
+        instruction::FundLiquidityMining {
+            amount,
+            duration_secs,
+        },
+    )
+}
+
+/// Build `stake_lp`; the LP tokens come from the owner's `lp_mint` ATA
+pub fn stake_lp(agent: &Pubkey, owner: &Pubkey, lp_mint: &Pubkey, amount: u64) -> Instruction {
+    let mining = find_liquidity_mining_pda(agent).0;
+    build(
+        accounts::StakeLp {
+            mining,
+            lp_vault: find_lm_lp_vault_pda(&mining).0,
+            position: find_lm_position_pda(&mining, owner).0,
+            owner_lp_account: get_associated_token_address(owner, lp_mint),
+            owner: *owner,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+        },
+        instruction::StakeLp { amount },
+    )
+}
+
+/// Build `withdraw_lp`; the LP tokens go to the owner's `lp_mint` ATA
+pub fn withdraw_lp(agent: &Pubkey, owner: &Pubkey, lp_mint: &Pubkey, amount: u64) -> Instruction {
+    let mining = find_liquidity_mining_pda(agent).0;
+    build(
+        accounts::WithdrawLp {
+            mining,
+            lp_vault: find_lm_lp_vault_pda(&mining).0,
+            position: find_lm_position_pda(&mining, owner).0,
+            owner_lp_account: get_associated_token_address(owner, lp_mint),
+            owner: *owner,
+            token_program: spl_token_id(),
+        },
+        instruction::WithdrawLp { amount },
+    )
+}
+
+/// Build `claim_lp_rewards`; the rewards go to the owner's agent token ATA
+pub fn claim_lp_rewards(agent: &Pubkey, owner: &Pubkey) -> Instruction {
+    let mining = find_liquidity_mining_pda(agent).0;
+    build(
+        accounts::ClaimLpRewards {
+            mining,
+            reward_vault: find_lm_reward_vault_pda(&mining).0,
+            position: find_lm_position_pda(&mining, owner).0,
+            owner_token_account: get_associated_token_address(owner, &find_mint_pda(agent).0),
+            owner: *owner,
+            token_program: spl_token_id(),
+        },
+        instruction::ClaimLpRewards {},
+    )
+}
+
+// ============================================================================
+// Token migration
+// ============================================================================
+
+/// Build `create_migration`; the deposit comes from the creator's ATA
+pub fn create_migration(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    old_mint: &Pubkey,
+    ratio_numerator: u64,
+    ratio_denominator: u64,
+    deposit_amount: u64,
+) -> Instruction {
+    let mint = find_mint_pda(agent).0;
+    let migration = find_migration_pda(agent).0;
+    build(
+        accounts::CreateMigration {
+            agent: *agent,
+            mint,
+            old_mint: *old_mint,
+            migration,
+            old_vault: find_migration_old_vault_pda(&migration).0,
+            vault: find_migration_vault_pda(&migration).0,
+            creator_token_account: get_associated_token_address(creator, &mint),
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateMigration {
+            ratio_numerator,
+            ratio_denominator,
+            deposit_amount,
+        },
+    )
+}
+
+/// Build `migrate_tokens`; old tokens come from and agent tokens go to the
+/// holder's ATAs
+pub fn migrate_tokens(
+    agent: &Pubkey,
+    holder: &Pubkey,
+    old_mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let migration = find_migration_pda(agent).0;
+    build(
+        accounts::MigrateTokens {
+            migration,
+            old_vault: find_migration_old_vault_pda(&migration).0,
+            vault: find_migration_vault_pda(&migration).0,
+            holder_old_account: get_associated_token_address(holder, old_mint),
+            holder_token_account: get_associated_token_address(holder, &find_mint_pda(agent).0),
+            holder: *holder,
+            token_program: spl_token_id(),
+        },
+        instruction::MigrateTokens { amount },
+    )
+}
+
+// ============================================================================
+// Index baskets
+// ============================================================================
+
+/// Build `create_index_basket`, holding `units[i]` base units of
+/// `agents[i]`'s token per whole basket token
+pub fn create_index_basket(
+    creator: &Pubkey,
+    basket_id: u64,
+    name: String,
+    agents: &[Pubkey],
+    units: Vec<u64>,
+) -> Instruction {
+    let basket = find_index_basket_pda(creator, basket_id).0;
+    let mut ix = build(
+        accounts::CreateIndexBasket {
+            basket,
+            basket_mint: find_basket_mint_pda(&basket).0,
+            creator: *creator,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateIndexBasket {
+            basket_id,
+            name,
+            units,
+        },
+    );
+    ix.accounts.extend(
+        agents
+            .iter()
+            .map(|agent| AccountMeta::new_readonly(*agent, false)),
+    );
+    ix
+}
+
+/// `[holder_account, vault]` remaining accounts for each constituent mint
+fn basket_constituent_metas(
+    basket: &Pubkey,
+    holder: &Pubkey,
+    mints: &[Pubkey],
+) -> Vec<AccountMeta> {
+    mints
+        .iter()
+        .flat_map(|mint| {
+            [
+                AccountMeta::new(get_associated_token_address(holder, mint), false),
+                AccountMeta::new(get_associated_token_address(basket, mint), false),
+            ]
+        })
+        .collect()
+}
+
+/// Build `mint_basket`; `mints` are the constituent mints in basket order.
+/// Constituents come from the holder's ATAs into the basket's ATAs.
+pub fn mint_basket(basket: &Pubkey, holder: &Pubkey, mints: &[Pubkey], amount: u64) -> Instruction {
+    let basket_mint = find_basket_mint_pda(basket).0;
+    let mut ix = build(
+        accounts::MintBasket {
+            basket: *basket,
+            basket_mint,
+            holder_basket_account: get_associated_token_address(holder, &basket_mint),
+            holder: *holder,
+            token_program: spl_token_id(),
+        },
+        instruction::MintBasket { amount },
+    );
+    ix.accounts
+        .extend(basket_constituent_metas(basket, holder, mints));
+    ix
+}
+
+/// Build `redeem_basket`; `mints` are the constituent mints in basket order.
+/// Constituents go to the holder's ATAs.
+pub fn redeem_basket(
+    basket: &Pubkey,
+    holder: &Pubkey,
+    mints: &[Pubkey],
+    amount: u64,
+) -> Instruction {
+    let basket_mint = find_basket_mint_pda(basket).0;
+    let mut ix = build(
+        accounts::RedeemBasket {
+            basket: *basket,
+            basket_mint,
+            holder_basket_account: get_associated_token_address(holder, &basket_mint),
+            holder: *holder,
+            token_program: spl_token_id(),
+        },
+        instruction::RedeemBasket { amount },
+    );
+    ix.accounts
+        .extend(basket_constituent_metas(basket, holder, mints));
+    ix
+}
+
+// ============================================================================
+// Keeper tasks
+// ============================================================================
+
+pub fn register_keeper_task(
+    owner: &Pubkey,
+    task_id: u64,
+    kind: KeeperTaskKind,
+    target: &Pubkey,
+    tip_lamports: u64,
+    deposit: u64,
+) -> Instruction {
+    build(
+        accounts::RegisterKeeperTask {
+            task: find_keeper_task_pda(owner, task_id).0,
+            target: *target,
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterKeeperTask {
+            task_id,
+            kind,
+            tip_lamports,
+            deposit,
+        },
+    )
+}
+
+pub fn fund_keeper_task(task: &Pubkey, funder: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::FundKeeperTask {
+            task: *task,
+            funder: *funder,
+            system_program: system_program::ID,
+        },
+        instruction::FundKeeperTask { amount },
+    )
+}
+
+/// Build `crank_keeper_task`; send it after the crank it tips for, in the
+/// same transaction
+pub fn crank_keeper_task(task: &Pubkey, target: &Pubkey, keeper: &Pubkey) -> Instruction {
+    build(
+        accounts::CrankKeeperTask {
+            task: *task,
+            target: *target,
+            keeper: *keeper,
+        },
+        instruction::CrankKeeperTask {},
+    )
+}
+
+pub fn cancel_keeper_task(task: &Pubkey, owner: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelKeeperTask {
+            task: *task,
+            owner: *owner,
+        },
+        instruction::CancelKeeperTask {},
+    )
+}
+
+// ============================================================================
+// Staking
+// ============================================================================
+
+/// Build `create_staking_pool`; also creates the pool's token vault
+pub fn create_staking_pool(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    staking_fee_bps: u16,
+    emission_rate_cap: u64,
+) -> Instruction {
+    let staking_pool = find_staking_pool_pda(agent).0;
+    build(
+        accounts::CreateStakingPool {
+            agent: *agent,
+            mint: find_mint_pda(agent).0,
+            staking_pool,
+            vault: find_stake_vault_pda(&staking_pool).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateStakingPool {
+            staking_fee_bps,
+            emission_rate_cap,
+        },
+    )
+}
+
+pub fn update_staking_pool(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    staking_fee_bps: u16,
+    emission_rate_cap: u64,
+) -> Instruction {
+    build(
+        accounts::UpdateStakingPool {
+            agent: *agent,
+            staking_pool: find_staking_pool_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::UpdateStakingPool {
+            staking_fee_bps,
+            emission_rate_cap,
+        },
+    )
+}
+
+/// Build `stake`; tokens are taken from the owner's ATA
+pub fn stake(agent: &Pubkey, owner: &Pubkey, amount: u64) -> Instruction {
+    let staking_pool = find_staking_pool_pda(agent).0;
+    build(
+        accounts::Stake {
+            agent: *agent,
+            staking_pool,
+            vault: find_stake_vault_pda(&staking_pool).0,
+            position: find_stake_position_pda(&staking_pool, owner).0,
+            owner_token_account: get_associated_token_address(owner, &find_mint_pda(agent).0),
+            owner: *owner,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::Stake { amount },
+    )
+}
+
+pub fn lock_stake(agent: &Pubkey, owner: &Pubkey, unlock_ts: i64) -> Instruction {
+    let staking_pool = find_staking_pool_pda(agent).0;
+    build(
+        accounts::LockStake {
+            staking_pool,
+            position: find_stake_position_pda(&staking_pool, owner).0,
+            owner: *owner,
+        },
+        instruction::LockStake { unlock_ts },
+    )
+}
+
+/// Build `unstake`; tokens return to the owner's ATA
+pub fn unstake(agent: &Pubkey, owner: &Pubkey, amount: u64) -> Instruction {
+    let staking_pool = find_staking_pool_pda(agent).0;
+    build(
+        accounts::Unstake {
+            agent: *agent,
+            staking_pool,
+            vault: find_stake_vault_pda(&staking_pool).0,
+            position: find_stake_position_pda(&staking_pool, owner).0,
+            owner_token_account: get_associated_token_address(owner, &find_mint_pda(agent).0),
+            owner: *owner,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::Unstake { amount },
+    )
+}
+
+pub fn claim_rewards(agent: &Pubkey, owner: &Pubkey) -> Instruction {
+    let staking_pool = find_staking_pool_pda(agent).0;
+    build(
+        accounts::ClaimRewards {
+            agent: *agent,
+            staking_pool,
+            position: find_stake_position_pda(&staking_pool, owner).0,
+            owner: *owner,
+            system_program: system_program::ID,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::ClaimRewards {},
+    )
+}
+
+pub fn fund_staking_pool(agent: &Pubkey, funder: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::FundStakingPool {
+            agent: *agent,
+            staking_pool: find_staking_pool_pda(agent).0,
+            funder: *funder,
+            system_program: system_program::ID,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::FundStakingPool { amount },
+    )
+}
+
+/// Build `deposit_revenue`; `token_accounts` are the depositor's and the DAO
+/// treasury's token accounts for a token deposit, `None` for SOL
+pub fn deposit_revenue(
+    agent: &Pubkey,
+    depositor: &Pubkey,
+    pool: RevenuePool,
+    amount: u64,
+    source: [u8; 32],
+    token_accounts: Option<(&Pubkey, &Pubkey)>,
+) -> Instruction {
+    build(
+        accounts::DepositRevenue {
+            agent: *agent,
+            depositor: *depositor,
+            system_program: system_program::ID,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+            staking_pool: (pool == RevenuePool::Stakers).then(|| find_staking_pool_pda(agent).0),
+            treasury: (pool == RevenuePool::Treasury).then(|| find_dao_treasury_pda(agent).0),
+            depositor_token_account: token_accounts.map(|(from, _)| *from),
+            treasury_token_account: token_accounts.map(|(_, to)| *to),
+            token_program: token_accounts.map(|_| spl_token_id()),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::DepositRevenue {
+            pool,
+            amount,
+            source,
+        },
+    )
+}
+
+// ============================================================================
+// Governance
+// ============================================================================
+
+pub fn create_governance(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    quorum_bps: u16,
+    threshold_bps: u16,
+    voting_period_secs: i64,
+    proposal_threshold: u64,
+) -> Instruction {
+    build(
+        accounts::CreateGovernance {
+            agent: *agent,
+            governance: find_governance_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            system_program: system_program::ID,
+        },
+        instruction::CreateGovernance {
+            quorum_bps,
+            threshold_bps,
+            voting_period_secs,
+            proposal_threshold,
+        },
+    )
+}
+
+/// Build `create_proposal`; `proposal_id` must be the governance's current
+/// `proposal_count`
+pub fn create_proposal(
+    agent: &Pubkey,
+    proposer: &Pubkey,
+    proposal_id: u64,
+    action: ProposalAction,
+) -> Instruction {
+    let staking_pool = find_staking_pool_pda(agent).0;
+    build(
+        accounts::CreateProposal {
+            governance: find_governance_pda(agent).0,
+            staking_pool,
+            position: find_stake_position_pda(&staking_pool, proposer).0,
+            proposal: find_proposal_pda(agent, proposal_id).0,
+            proposer: *proposer,
+            system_program: system_program::ID,
+        },
+        instruction::CreateProposal { action },
+    )
+}
+
+pub fn cast_vote(agent: &Pubkey, voter: &Pubkey, proposal_id: u64, support: bool) -> Instruction {
+    let staking_pool = find_staking_pool_pda(agent).0;
+    let proposal = find_proposal_pda(agent, proposal_id).0;
+    build(
+        accounts::CastVote {
+            proposal,
+            staking_pool,
+            position: find_stake_position_pda(&staking_pool, voter).0,
+            vote: find_vote_pda(&proposal, voter).0,
+            voter: *voter,
+            system_program: system_program::ID,
+        },
+        instruction::CastVote { support },
+    )
+}
+
+/// Build `execute_proposal` with the optional accounts `action` needs. Token
+/// transfers are paid from the treasury's ATA for the mint.
+pub fn execute_proposal(agent: &Pubkey, proposal_id: u64, action: &ProposalAction) -> Instruction {
+    let treasury = find_dao_treasury_pda(agent).0;
+    let mut accounts = accounts::ExecuteProposal {
+        agent: *agent,
+        governance: find_governance_pda(agent).0,
+        proposal: find_proposal_pda(agent, proposal_id).0,
+        x402_config: None,
+        treasury: None,
+        recipient: None,
+        treasury_token_account: None,
+        recipient_token_account: None,
+        token_program: None,
+        system_program: None,
+        creator_fee_vault: None,
+    };
+    match action {
+        ProposalAction::SetServicePrices { .. } => {
+            accounts.x402_config = Some(find_x402_config_pda(agent).0);
+        }
+        ProposalAction::SetTreasuryFee { .. } => {
+            accounts.treasury = Some(treasury);
+        }
+        ProposalAction::TransferSol { recipient, .. } => {
+            accounts.treasury = Some(treasury);
+            accounts.recipient = Some(*recipient);
+            accounts.system_program = Some(system_program::ID);
+            accounts.creator_fee_vault = Some(find_creator_fee_vault_pda(agent).0);
+        }
+        ProposalAction::TransferToken {
+            mint, recipient, ..
+        } => {
+            accounts.treasury = Some(treasury);
+            accounts.treasury_token_account = Some(get_associated_token_address(&treasury, mint));
+            accounts.recipient_token_account = Some(*recipient);
+            accounts.token_program = Some(spl_token_id());
+        }
+        _ => {}
+    }
+    build(accounts, instruction::ExecuteProposal {})
+}
+
+pub fn create_dao_treasury(agent: &Pubkey, creator: &Pubkey, treasury_fee_bps: u16) -> Instruction {
+    build(
+        accounts::CreateDaoTreasury {
+            agent: *agent,
+            governance: find_governance_pda(agent).0,
+            treasury: find_dao_treasury_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            system_program: system_program::ID,
+        },
+        instruction::CreateDaoTreasury { treasury_fee_bps },
+    )
+}
+
+pub fn collect_treasury_fees(agent: &Pubkey) -> Instruction {
+    build(
+        accounts::CollectTreasuryFees {
+            agent: *agent,
+            treasury: find_dao_treasury_pda(agent).0,
+            system_program: system_program::ID,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::CollectTreasuryFees {},
+    )
+}
+
+/// Build `set_x402_top_up`; also opens the agent's x402 treasury
+pub fn set_x402_top_up(agent: &Pubkey, creator: &Pubkey, x402_top_up_bps: u16) -> Instruction {
+    build(
+        accounts::SetX402TopUp {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            x402_treasury: find_x402_treasury_pda(agent).0,
+            native_mint: anchor_spl::token::spl_token::native_mint::ID,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::SetX402TopUp { x402_top_up_bps },
+    )
+}
+
+pub fn collect_x402_top_up(agent: &Pubkey) -> Instruction {
+    build(
+        accounts::CollectX402TopUp {
+            agent: *agent,
+            x402_treasury: find_x402_treasury_pda(agent).0,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+        },
+        instruction::CollectX402TopUp {},
+    )
+}
+
+// ============================================================================
+// DCA
+// ============================================================================
+
+pub fn create_dca(
+    agent: &Pubkey,
+    owner: &Pubkey,
+    amount_per_interval: u64,
+    interval_secs: i64,
+    total_intervals: u64,
+    min_tokens_per_interval: u64,
+) -> Instruction {
+    let dca = find_dca_pda(agent, owner).0;
+    build(
+        accounts::CreateDca {
+            agent: *agent,
+            dca,
+            dca_vault: find_dca_vault_pda(&dca).0,
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        instruction::CreateDca {
+            amount_per_interval,
+            interval_secs,
+            total_intervals,
+            min_tokens_per_interval,
+        },
+    )
+}
+
+/// Build `execute_dca`; the owner's token ATA must already exist.
+/// `price_history` (the agent's candle accumulator) and `trending` (the
+/// factory leaderboard) are passed when they exist.
+pub fn execute_dca(
+    agent: &Pubkey,
+    owner: &Pubkey,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    cranker: &Pubkey,
+    price_history: Option<&Pubkey>,
+    trending: Option<&Pubkey>,
+) -> Instruction {
+    let mint = find_mint_pda(agent).0;
+    let dca = find_dca_pda(agent, owner).0;
+    build(
+        accounts::ExecuteDca {
+            factory: find_factory_pda().0,
+            dca,
+            dca_vault: find_dca_vault_pda(&dca).0,
+            agent: *agent,
+            mint,
+            owner_token_account: get_associated_token_address(owner, &mint),
+            buyer_record: find_buyer_record_pda(agent, owner).0,
+            creator: *creator,
+            platform_treasury: *platform_treasury,
+            cranker: *cranker,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            price_history: price_history.copied(),
+            trending: trending.copied(),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+            sol_vault: find_sol_vault_pda(agent).0,
+            platform_fee_vault: find_platform_fee_vault_pda(agent).0,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::ExecuteDca {},
+    )
+}
+
+pub fn cancel_dca(agent: &Pubkey, owner: &Pubkey) -> Instruction {
+    let dca = find_dca_pda(agent, owner).0;
+    build(
+        accounts::CancelDca {
+            dca,
+            dca_vault: find_dca_vault_pda(&dca).0,
+            owner: *owner,
+            system_program: system_program::ID,
+        },
+        instruction::CancelDca {},
+    )
+}
+
+// ============================================================================
+// Presale
+// ============================================================================
+
+pub fn create_presale(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    start_ts: i64,
+    end_ts: i64,
+    soft_cap: u64,
+    hard_cap: u64,
+) -> Instruction {
+    build(
+        accounts::CreatePresale {
+            agent: *agent,
+            presale: find_presale_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            system_program: system_program::ID,
+        },
+        instruction::CreatePresale {
+            start_ts,
+            end_ts,
+            soft_cap,
+            hard_cap,
+        },
+    )
+}
+
+pub fn commit_sol(agent: &Pubkey, committer: &Pubkey, amount: u64) -> Instruction {
+    let presale = find_presale_pda(agent).0;
+    build(
+        accounts::CommitSol {
+            presale,
+            commitment: find_commitment_pda(&presale, committer).0,
+            committer: *committer,
+            system_program: system_program::ID,
+        },
+        instruction::CommitSol { amount },
+    )
+}
+
+pub fn finalize_presale(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    platform_treasury: &Pubkey,
+    cranker: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::FinalizePresale {
+            factory: find_factory_pda().0,
+            presale: find_presale_pda(agent).0,
+            agent: *agent,
+            creator: *creator,
+            platform_treasury: *platform_treasury,
+            cranker: *cranker,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+            sol_vault: find_sol_vault_pda(agent).0,
+            platform_fee_vault: find_platform_fee_vault_pda(agent).0,
+            creator_fee_vault: find_creator_fee_vault_pda(agent).0,
+        },
+        instruction::FinalizePresale {},
+    )
+}
+
+/// Build `claim_presale`; the committer's token ATA must already exist
+pub fn claim_presale(agent: &Pubkey, committer: &Pubkey) -> Instruction {
+    let presale = find_presale_pda(agent).0;
+    let mint = find_mint_pda(agent).0;
+    build(
+        accounts::ClaimPresale {
+            presale,
+            commitment: find_commitment_pda(&presale, committer).0,
+            agent: *agent,
+            mint,
+            committer_token_account: get_associated_token_address(committer, &mint),
+            committer: *committer,
+            token_program: spl_token_id(),
+        },
+        instruction::ClaimPresale {},
+    )
+}
+
+pub fn refund_commitment(agent: &Pubkey, committer: &Pubkey) -> Instruction {
+    let presale = find_presale_pda(agent).0;
+    build(
+        accounts::RefundCommitment {
+            presale,
+            commitment: find_commitment_pda(&presale, committer).0,
+            committer: *committer,
+        },
+        instruction::RefundCommitment {},
+    )
+}
+
+// ============================================================================
+// X402 Payment Protocol
+// ============================================================================
+
+/// X402 settings shared by `configure_x402`, `update_x402` and
+/// `create_agent_full`
+pub use agent_factory::state::X402Settings;
+
+pub fn configure_x402(agent: &Pubkey, authority: &Pubkey, settings: X402Settings) -> Instruction {
+    build(
+        accounts::ConfigureX402 {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            authority: *authority,
+            creator_ownership_account: ownership_account(agent, authority),
+            system_program: system_program::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::ConfigureX402 {
+            enabled: settings.enabled,
+            min_payment_amount: settings.min_payment_amount,
+            max_payment_amount: settings.max_payment_amount,
+            service_timeout_seconds: settings.service_timeout_seconds,
+        },
+    )
+}
+
+pub fn update_x402(agent: &Pubkey, authority: &Pubkey, settings: X402Settings) -> Instruction {
+    build(
+        accounts::UpdateX402 {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            authority: *authority,
+            creator_ownership_account: ownership_account(agent, authority),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::UpdateX402 {
+            enabled: settings.enabled,
+            min_payment_amount: settings.min_payment_amount,
+            max_payment_amount: settings.max_payment_amount,
+            service_timeout_seconds: settings.service_timeout_seconds,
+        },
+    )
+}
+
+/// Build `pay_for_service`; `nonce` must be the config's current nonce + 1
+/// Build `pay_for_service`; `platform_stake` is the payer's platform token
+/// stake position, passed to pay the discounted minimum price. `affiliate`
+/// is the referring affiliate's wallet and token account. `revenue_split`
+/// lists the worker treasuries' token accounts when the agent routes revenue
+/// to other agents. `consumer_stats` updates the payer's spending report,
+/// which must have been created.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_for_service(
+    agent: &Pubkey,
+    payer: &Pubkey,
+    payer_token_account: &Pubkey,
+    recipient_token_account: &Pubkey,
+    amount: u64,
+    service_id: [u8; 32],
+    nonce: u64,
+    platform_stake: Option<&Pubkey>,
+    quoted: bool,
+    affiliate: Option<(&Pubkey, &Pubkey)>,
+    revenue_split: Option<&[Pubkey]>,
+    request_id: [u8; 16],
+    priority_tip: u64,
+    consumer_stats: bool,
+) -> Instruction {
+    let quote = quoted.then(|| find_service_quote_pda(agent, payer, &service_id).0);
+    let mut ix = build(
+        accounts::PayForService {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            payment_record: find_payment_record_pda(agent, payer, nonce).0,
+            payer: *payer,
+            payer_token_account: *payer_token_account,
+            recipient_token_account: *recipient_token_account,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            factory: platform_stake.map(|_| find_factory_pda().0),
+            platform_stake: platform_stake.copied(),
+            quote,
+            affiliate: affiliate.map(|(wallet, _)| find_affiliate_pda(wallet).0),
+            affiliate_token_account: affiliate.map(|(_, token_account)| *token_account),
+            revenue_split: revenue_split.map(|_| find_revenue_split_pda(agent).0),
+            consumer_stats: consumer_stats.then(|| find_consumer_stats_pda(payer).0),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::PayForService {
+            amount,
+            service_id,
+            nonce,
+            request_id,
+            priority_tip,
+        },
+    );
+    ix.accounts.extend(revenue_split_metas(revenue_split));
+    ix
+}
+
+/// Build `set_revenue_split`; `workers` are `(worker_agent, bps)` pairs,
+/// empty to stop routing. Each worker agent needs a DAO treasury.
+pub fn set_revenue_split(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    workers: &[(Pubkey, u16)],
+) -> Instruction {
+    let mut ix = build(
+        accounts::SetRevenueSplit {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            revenue_split: find_revenue_split_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            system_program: system_program::ID,
+        },
+        instruction::SetRevenueSplit {
+            bps: workers.iter().map(|(_, bps)| *bps).collect(),
+        },
+    );
+    ix.accounts.extend(
+        workers
+            .iter()
+            .map(|(worker, _)| AccountMeta::new_readonly(find_dao_treasury_pda(worker).0, false)),
+    );
+    ix
+}
+
+pub fn set_chargeback_window(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    chargeback_window_secs: u64,
+) -> Instruction {
+    build(
+        accounts::SetChargebackWindow {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetChargebackWindow {
+            chargeback_window_secs,
+        },
+    )
+}
+
+/// Build `finalize_payment`, closing a payment to disputes after its
+/// chargeback window
+pub fn finalize_payment(payment_record: &Pubkey) -> Instruction {
+    build(
+        accounts::FinalizePayment {
+            payment_record: *payment_record,
+            dispute: find_dispute_pda(payment_record).0,
+        },
+        instruction::FinalizePayment {},
+    )
+}
+
+/// Build `deprecate_service`; `sunset_ts` 0 withdraws the deprecation
+pub fn deprecate_service(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    service_id: [u8; 32],
+    sunset_ts: i64,
+) -> Instruction {
+    build(
+        accounts::DeprecateService {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::DeprecateService {
+            service_id,
+            sunset_ts,
+        },
+    )
+}
+
+/// Build `create_consumer_stats` for `consumer`, the payer itself or, with
+/// `payer_agent`, one of the payer's agents
+pub fn create_consumer_stats(
+    payer: &Pubkey,
+    consumer: &Pubkey,
+    payer_agent: Option<&Pubkey>,
+) -> Instruction {
+    build(
+        accounts::CreateConsumerStats {
+            consumer_stats: find_consumer_stats_pda(consumer).0,
+            payer: *payer,
+            payer_agent: payer_agent.copied(),
+            system_program: system_program::ID,
+        },
+        instruction::CreateConsumerStats {
+            consumer: *consumer,
+        },
+    )
+}
+
+/// Worker treasury token accounts paid by an x402 payment, in revenue split
+/// share order. `revenue_split` is `None` when the agent doesn't route
+/// revenue; otherwise it's each worker treasury's token account for the
+/// payment mint.
+fn revenue_split_metas(revenue_split: Option<&[Pubkey]>) -> Vec<AccountMeta> {
+    revenue_split
+        .unwrap_or_default()
+        .iter()
+        .map(|token_account| AccountMeta::new(*token_account, false))
+        .collect()
+}
+
+pub fn set_x402_verifier(agent: &Pubkey, creator: &Pubkey, verifier: &Pubkey) -> Instruction {
+    build(
+        accounts::SetX402Verifier {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetX402Verifier {
+            verifier: *verifier,
+        },
+    )
+}
+
+/// Build `rotate_x402_recipient`; the old recipient stays valid for
+/// `grace_secs`
+pub fn rotate_x402_recipient(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    new_recipient: &Pubkey,
+    grace_secs: i64,
+) -> Instruction {
+    build(
+        accounts::RotateX402Recipient {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::RotateX402Recipient {
+            new_recipient: *new_recipient,
+            grace_secs,
+        },
+    )
+}
+
+pub fn set_x402_metering(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    meter_unit: MeterUnit,
+    unit_price: u64,
+) -> Instruction {
+    build(
+        accounts::SetX402Metering {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetX402Metering {
+            meter_unit,
+            unit_price,
+        },
+    )
+}
+
+pub fn set_x402_price_notice(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    price_notice_secs: i64,
+) -> Instruction {
+    build(
+        accounts::SetX402PriceNotice {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetX402PriceNotice { price_notice_secs },
+    )
+}
+
+pub fn register_affiliate(wallet: &Pubkey, fee_bps: u16) -> Instruction {
+    build(
+        accounts::RegisterAffiliate {
+            affiliate: find_affiliate_pda(wallet).0,
+            wallet: *wallet,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterAffiliate { fee_bps },
+    )
+}
+
+pub fn set_affiliate_cap(agent: &Pubkey, creator: &Pubkey, max_affiliate_bps: u16) -> Instruction {
+    build(
+        accounts::SetAffiliateCap {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetAffiliateCap { max_affiliate_bps },
+    )
+}
+
+pub fn register_webhook(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    url_hash: [u8; 32],
+    signer: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::RegisterWebhook {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::RegisterWebhook {
+            url_hash,
+            signer: *signer,
+        },
+    )
+}
+
+pub fn post_quote(
+    agent: &Pubkey,
+    creator: &Pubkey,
+    payer: &Pubkey,
+    service_id: [u8; 32],
+    amount: u64,
+    expires_at: i64,
+) -> Instruction {
+    build(
+        accounts::PostQuote {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            quote: find_service_quote_pda(agent, payer, &service_id).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            system_program: system_program::ID,
+        },
+        instruction::PostQuote {
+            payer: *payer,
+            service_id,
+            amount,
+            expires_at,
+        },
+    )
+}
+
+pub fn close_quote(agent: &Pubkey, creator: &Pubkey, quote: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseQuote {
+            agent: *agent,
+            quote: *quote,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::CloseQuote {},
+    )
+}
+
+/// Build `propose_terms`; `creator` must be the caller agent's creator
+pub fn propose_terms(
+    caller_agent: &Pubkey,
+    target_agent: &Pubkey,
+    creator: &Pubkey,
+    service_id: [u8; 32],
+    amount: u64,
+    expires_at: i64,
+) -> Instruction {
+    build(
+        accounts::ProposeTerms {
+            caller_agent: *caller_agent,
+            target_agent: *target_agent,
+            target_x402_config: find_x402_config_pda(target_agent).0,
+            agreement: find_service_agreement_pda(caller_agent, target_agent, &service_id).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(caller_agent, creator),
+            system_program: system_program::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::ProposeTerms {
+            service_id,
+            amount,
+            expires_at,
+        },
+    )
+}
+
+/// Build `accept_terms`; `creator` must be the target agent's creator
+pub fn accept_terms(
+    target_agent: &Pubkey,
+    agreement: &Pubkey,
+    creator: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::AcceptTerms {
+            target_agent: *target_agent,
+            agreement: *agreement,
+            creator: *creator,
+            creator_ownership_account: ownership_account(target_agent, creator),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::AcceptTerms { amount },
+    )
+}
+
+pub fn close_terms(agreement: &Pubkey, proposer: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseTerms {
+            agreement: *agreement,
+            proposer: *proposer,
+        },
+        instruction::CloseTerms {},
+    )
+}
+
+/// Build `pay_for_service_escrowed`; like `pay_for_service`, but the
+/// payment waits in escrow for the agent's attestation verifier
+#[allow(clippy::too_many_arguments)]
+pub fn pay_for_service_escrowed(
+    agent: &Pubkey,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    payer_token_account: &Pubkey,
+    amount: u64,
+    service_id: [u8; 32],
+    nonce: u64,
+    platform_stake: Option<&Pubkey>,
+    request_id: [u8; 16],
+    consumer_stats: bool,
+) -> Instruction {
+    let payment_record = find_payment_record_pda(agent, payer, nonce).0;
+    build(
+        accounts::PayForServiceEscrowed {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            payment_record,
+            escrow_token_account: find_payment_escrow_pda(&payment_record).0,
+            mint: *mint,
+            payer: *payer,
+            payer_token_account: *payer_token_account,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            factory: platform_stake.map(|_| find_factory_pda().0),
+            platform_stake: platform_stake.copied(),
+            consumer_stats: consumer_stats.then(|| find_consumer_stats_pda(payer).0),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::PayForServiceEscrowed {
+            amount,
+            service_id,
+            nonce,
+            request_id,
+        },
+    )
+}
+
+/// Build `settle_attested_payment`, signed by the agent's attestation
+/// verifier; pass `revenue_split` as for `pay_for_service`
+#[allow(clippy::too_many_arguments)]
+pub fn settle_attested_payment(
+    agent: &Pubkey,
+    payment_record: &Pubkey,
+    payer: &Pubkey,
+    recipient_token_account: &Pubkey,
+    payer_token_account: &Pubkey,
+    verifier: &Pubkey,
+    passed: bool,
+    attestation_hash: [u8; 32],
+    revenue_split: Option<&[Pubkey]>,
+) -> Instruction {
+    let mut ix = build(
+        settle_attested_accounts(
+            agent,
+            payment_record,
+            payer,
+            recipient_token_account,
+            payer_token_account,
+            verifier,
+            revenue_split.is_some(),
+        ),
+        instruction::SettleAttestedPayment {
+            passed,
+            attestation_hash,
+        },
+    );
+    ix.accounts.extend(revenue_split_metas(revenue_split));
+    ix
+}
+
+/// Build `settle_attested_payment_partial`, refunding `refund_bps` of the
+/// escrow to the payer; otherwise as `settle_attested_payment`
+#[allow(clippy::too_many_arguments)]
+pub fn settle_attested_payment_partial(
+    agent: &Pubkey,
+    payment_record: &Pubkey,
+    payer: &Pubkey,
+    recipient_token_account: &Pubkey,
+    payer_token_account: &Pubkey,
+    verifier: &Pubkey,
+    refund_bps: u16,
+    attestation_hash: [u8; 32],
+    revenue_split: Option<&[Pubkey]>,
+) -> Instruction {
+    let mut ix = build(
+        settle_attested_accounts(
+            agent,
+            payment_record,
+            payer,
+            recipient_token_account,
+            payer_token_account,
+            verifier,
+            revenue_split.is_some(),
+        ),
+        instruction::SettleAttestedPaymentPartial {
+            refund_bps,
+            attestation_hash,
+        },
+    );
+    ix.accounts.extend(revenue_split_metas(revenue_split));
+    ix
+}
+
+fn settle_attested_accounts(
+    agent: &Pubkey,
+    payment_record: &Pubkey,
+    payer: &Pubkey,
+    recipient_token_account: &Pubkey,
+    payer_token_account: &Pubkey,
+    verifier: &Pubkey,
+    revenue_split: bool,
+) -> accounts::SettleAttestedPayment {
+    accounts::SettleAttestedPayment {
+        x402_config: find_x402_config_pda(agent).0,
+        payment_record: *payment_record,
+        escrow_token_account: find_payment_escrow_pda(payment_record).0,
+        recipient_token_account: *recipient_token_account,
+        payer_token_account: *payer_token_account,
+        payer: *payer,
+        verifier: *verifier,
+        token_program: spl_token_id(),
+        revenue_split: revenue_split.then(|| find_revenue_split_pda(agent).0),
+    }
+}
+
+pub fn refund_expired_payment(
+    agent: &Pubkey,
+    payment_record: &Pubkey,
+    payer: &Pubkey,
+    payer_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::RefundExpiredPayment {
+            x402_config: find_x402_config_pda(agent).0,
+            payment_record: *payment_record,
+            escrow_token_account: find_payment_escrow_pda(payment_record).0,
+            payer_token_account: *payer_token_account,
+            payer: *payer,
+            token_program: spl_token_id(),
+        },
+        instruction::RefundExpiredPayment {},
+    )
+}
+
+/// Build `call_agent_service`; `caller_authority` must hold the caller agent's ownership NFT.
+/// Pass the target's `revenue_split` and the caller agent's `consumer_stats`
+/// as for `pay_for_service`. With `from_treasury`, `caller_token_account`
+/// must be the caller agent's x402 treasury. With `agreed`, `amount` is the
+/// price the target agent accepted through `accept_terms`.
+#[allow(clippy::too_many_arguments)]
+pub fn call_agent_service(
+    caller_agent: &Pubkey,
+    target_agent: &Pubkey,
+    caller_authority: &Pubkey,
+    caller_token_account: &Pubkey,
+    target_token_account: &Pubkey,
+    amount: u64,
+    service_id: [u8; 32],
+    nonce: u64,
+    service_params: Vec<u8>,
+    request_id: [u8; 16],
+    priority_tip: u64,
+    revenue_split: Option<&[Pubkey]>,
+    consumer_stats: bool,
+    from_treasury: bool,
+    agreed: bool,
+) -> Instruction {
+    let mut ix = build(
+        accounts::CallAgentService {
+            caller_agent: *caller_agent,
+            target_agent: *target_agent,
+            target_x402_config: find_x402_config_pda(target_agent).0,
+            payment_record: find_payment_record_pda(target_agent, caller_agent, nonce).0,
+            caller_authority: *caller_authority,
+            caller_ownership_account: ownership_account(caller_agent, caller_authority),
+            caller_token_account: *caller_token_account,
+            target_token_account: *target_token_account,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            revenue_split: revenue_split.map(|_| find_revenue_split_pda(target_agent).0),
+            consumer_stats: consumer_stats.then(|| find_consumer_stats_pda(caller_agent).0),
+            caller_x402_config: from_treasury.then(|| find_x402_config_pda(caller_agent).0),
+            agreement: agreed
+                .then(|| find_service_agreement_pda(caller_agent, target_agent, &service_id).0),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::CallAgentService {
+            amount,
+            service_id,
+            nonce,
+            service_params,
+            request_id,
+            priority_tip,
+        },
+    );
+    ix.accounts.extend(revenue_split_metas(revenue_split));
+    ix
+}
+
+// ============================================================================
+// Provider bonds and disputes
+// ============================================================================
+
+pub fn deposit_provider_bond(agent: &Pubkey, creator: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::DepositProviderBond {
+            agent: *agent,
+            provider_bond: find_provider_bond_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            system_program: system_program::ID,
+        },
+        instruction::DepositProviderBond { amount },
+    )
+}
+
+pub fn withdraw_provider_bond(agent: &Pubkey, creator: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::WithdrawProviderBond {
+            agent: *agent,
+            provider_bond: find_provider_bond_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::WithdrawProviderBond { amount },
+    )
+}
+
+/// Build `submit_evidence`; pass `payer_agent` when the payment was an
+/// agent-to-agent call made by the claimant's agent
+pub fn submit_evidence(
+    agent: &Pubkey,
+    payment_record: &Pubkey,
+    claimant: &Pubkey,
+    payer_agent: Option<&Pubkey>,
+    evidence_hash: [u8; 32],
+    claimed_amount: u64,
+) -> Instruction {
+    build(
+        accounts::SubmitEvidence {
+            factory: find_factory_pda().0,
+            payment_record: *payment_record,
+            provider_bond: find_provider_bond_pda(agent).0,
+            dispute: find_dispute_pda(payment_record).0,
+            claimant: *claimant,
+            payer_agent: payer_agent.copied(),
+            system_program: system_program::ID,
+        },
+        instruction::SubmitEvidence {
+            evidence_hash,
+            claimed_amount,
+        },
+    )
+}
+
+pub fn cast_arbiter_vote(
+    agent: &Pubkey,
+    payment_record: &Pubkey,
+    claimant: &Pubkey,
+    arbiter: &Pubkey,
+    slash_amount: u64,
+) -> Instruction {
+    build(
+        accounts::CastArbiterVote {
+            factory: find_factory_pda().0,
+            dispute: find_dispute_pda(payment_record).0,
+            provider_bond: find_provider_bond_pda(agent).0,
+            claimant: *claimant,
+            arbiter: *arbiter,
+        },
+        instruction::CastArbiterVote { slash_amount },
+    )
+}
+
+/// Build `insure_payment`; bundle it after the payment it insures
+pub fn insure_payment(
+    payment_record: &Pubkey,
+    holder: &Pubkey,
+    payer_agent: Option<&Pubkey>,
+    coverage: u64,
+) -> Instruction {
+    build(
+        accounts::InsurePayment {
+            factory: find_factory_pda().0,
+            payment_record: *payment_record,
+            insurance_fund: find_insurance_fund_pda().0,
+            insurance_policy: find_insurance_policy_pda(payment_record).0,
+            holder: *holder,
+            payer_agent: payer_agent.copied(),
+            system_program: system_program::ID,
+        },
+        instruction::InsurePayment { coverage },
+    )
+}
+
+pub fn pay_insured_claim(payment_record: &Pubkey, claimant: &Pubkey) -> Instruction {
+    build(
+        accounts::PayInsuredClaim {
+            insurance_fund: find_insurance_fund_pda().0,
+            dispute: find_dispute_pda(payment_record).0,
+            insurance_policy: find_insurance_policy_pda(payment_record).0,
+            claimant: *claimant,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::PayInsuredClaim {},
+    )
+}
+
+/// Build `settle_metered_payment`; pass `revenue_split` as for
+/// `pay_for_service`
+#[allow(clippy::too_many_arguments)]
+pub fn settle_metered_payment(
+    agent: &Pubkey,
+    payment_record: &Pubkey,
+    payer: &Pubkey,
+    recipient_token_account: &Pubkey,
+    payer_token_account: &Pubkey,
+    creator: &Pubkey,
+    units: u64,
+    usage_hash: [u8; 32],
+    revenue_split: Option<&[Pubkey]>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::SettleMeteredPayment {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            payment_record: *payment_record,
+            escrow_token_account: find_payment_escrow_pda(payment_record).0,
+            recipient_token_account: *recipient_token_account,
+            payer_token_account: *payer_token_account,
+            payer: *payer,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            token_program: spl_token_id(),
+            revenue_split: revenue_split.map(|_| find_revenue_split_pda(agent).0),
+        },
+        instruction::SettleMeteredPayment { units, usage_hash },
+    );
+    ix.accounts.extend(revenue_split_metas(revenue_split));
+    ix
+}
+
+// ============================================================================
+// Payment streams
+// ============================================================================
+
+/// Build `start_stream`; `nonce` must be the config's current nonce + 1
+#[allow(clippy::too_many_arguments)]
+pub fn start_stream(
+    agent: &Pubkey,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    payer_token_account: &Pubkey,
+    rate_per_sec: u64,
+    max_duration: u64,
+    nonce: u64,
+) -> Instruction {
+    let stream = find_payment_stream_pda(agent, payer, nonce).0;
+    build(
+        accounts::StartStream {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            stream,
+            escrow_token_account: find_stream_escrow_pda(&stream).0,
+            mint: *mint,
+            payer: *payer,
+            payer_token_account: *payer_token_account,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::StartStream {
+            rate_per_sec,
+            max_duration,
+            nonce,
+        },
+    )
+}
+
+/// Build `withdraw_streamed`; pass `revenue_split` as for `pay_for_service`
+pub fn withdraw_streamed(
+    agent: &Pubkey,
+    stream: &Pubkey,
+    payer: &Pubkey,
+    recipient_token_account: &Pubkey,
+    revenue_split: Option<&[Pubkey]>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::WithdrawStreamed {
+            x402_config: find_x402_config_pda(agent).0,
+            stream: *stream,
+            escrow_token_account: find_stream_escrow_pda(stream).0,
+            recipient_token_account: *recipient_token_account,
+            payer: *payer,
+            token_program: spl_token_id(),
+            revenue_split: revenue_split.map(|_| find_revenue_split_pda(agent).0),
+        },
+        instruction::WithdrawStreamed {},
+    );
+    ix.accounts.extend(revenue_split_metas(revenue_split));
+    ix
+}
+
+pub fn cancel_stream(
+    agent: &Pubkey,
+    stream: &Pubkey,
+    payer: &Pubkey,
+    payer_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::CancelStream {
+            x402_config: find_x402_config_pda(agent).0,
+            stream: *stream,
+            escrow_token_account: find_stream_escrow_pda(stream).0,
+            payer_token_account: *payer_token_account,
+            payer: *payer,
+            token_program: spl_token_id(),
+        },
+        instruction::CancelStream {},
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_job(
+    agent: &Pubkey,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    payer_token_account: &Pubkey,
+    milestone_amounts: Vec<u64>,
+    service_id: [u8; 32],
+    deadline: i64,
+    nonce: u64,
+) -> Instruction {
+    let job = find_job_pda(agent, payer, nonce).0;
+    build(
+        accounts::CreateJob {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            job,
+            escrow_token_account: find_job_escrow_pda(&job).0,
+            mint: *mint,
+            payer: *payer,
+            payer_token_account: *payer_token_account,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::CreateJob {
+            milestone_amounts,
+            service_id,
+            deadline,
+            nonce,
+        },
+    )
+}
+
+/// Build `submit_milestone`; `creator` must be the agent's creator
+pub fn submit_milestone(
+    agent: &Pubkey,
+    job: &Pubkey,
+    creator: &Pubkey,
+    index: u8,
+    deliverable_hash: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::SubmitMilestone {
+            agent: *agent,
+            job: *job,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SubmitMilestone {
+            index,
+            deliverable_hash,
+        },
+    )
+}
+
+/// Build `release_milestone`; pass `revenue_split` as for `pay_for_service`
+pub fn release_milestone(
+    agent: &Pubkey,
+    job: &Pubkey,
+    payer: &Pubkey,
+    recipient_token_account: &Pubkey,
+    authority: &Pubkey,
+    index: u8,
+    revenue_split: Option<&[Pubkey]>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::ReleaseMilestone {
+            x402_config: find_x402_config_pda(agent).0,
+            job: *job,
+            escrow_token_account: find_job_escrow_pda(job).0,
+            recipient_token_account: *recipient_token_account,
+            payer: *payer,
+            authority: *authority,
+            token_program: spl_token_id(),
+            revenue_split: revenue_split.map(|_| find_revenue_split_pda(agent).0),
+        },
+        instruction::ReleaseMilestone { index },
+    );
+    ix.accounts.extend(revenue_split_metas(revenue_split));
+    ix
+}
+
+pub fn dispute_milestone(
+    job: &Pubkey,
+    payer: &Pubkey,
+    index: u8,
+    evidence_hash: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::DisputeMilestone {
+            job: *job,
+            payer: *payer,
+        },
+        instruction::DisputeMilestone {
+            index,
+            evidence_hash,
+        },
+    )
+}
+
+/// Build `vote_milestone_dispute`; pass `revenue_split` as for
+/// `pay_for_service`
+#[allow(clippy::too_many_arguments)]
+pub fn vote_milestone_dispute(
+    agent: &Pubkey,
+    job: &Pubkey,
+    payer: &Pubkey,
+    recipient_token_account: &Pubkey,
+    payer_token_account: &Pubkey,
+    arbiter: &Pubkey,
+    index: u8,
+    refund_bps: u16,
+    revenue_split: Option<&[Pubkey]>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::VoteMilestoneDispute {
+            factory: find_factory_pda().0,
+            x402_config: find_x402_config_pda(agent).0,
+            job: *job,
+            escrow_token_account: find_job_escrow_pda(job).0,
+            recipient_token_account: *recipient_token_account,
+            payer_token_account: *payer_token_account,
+            payer: *payer,
+            arbiter: *arbiter,
+            token_program: spl_token_id(),
+            revenue_split: revenue_split.map(|_| find_revenue_split_pda(agent).0),
+        },
+        instruction::VoteMilestoneDispute { index, refund_bps },
+    );
+    ix.accounts.extend(revenue_split_metas(revenue_split));
+    ix
+}
+
+pub fn cancel_job(
+    agent: &Pubkey,
+    job: &Pubkey,
+    payer: &Pubkey,
+    payer_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::CancelJob {
+            x402_config: find_x402_config_pda(agent).0,
+            job: *job,
+            escrow_token_account: find_job_escrow_pda(job).0,
+            payer_token_account: *payer_token_account,
+            payer: *payer,
+            token_program: spl_token_id(),
+        },
+        instruction::CancelJob {},
+    )
+}
+
+pub fn close_job(job: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseJob {
+            job: *job,
+            payer: *payer,
+        },
+        instruction::CloseJob {},
+    )
+}
+
+/// Build `set_queue_workers`; `creator` must be the agent's creator
+pub fn set_queue_workers(agent: &Pubkey, creator: &Pubkey, workers: Vec<Pubkey>) -> Instruction {
+    build(
+        accounts::SetQueueWorkers {
+            agent: *agent,
+            queue: find_work_queue_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+            system_program: system_program::ID,
+        },
+        instruction::SetQueueWorkers { workers },
+    )
+}
+
+/// Build `enqueue_task`; `task_id` must be the queue's `next_task_id`
+#[allow(clippy::too_many_arguments)]
+pub fn enqueue_task(
+    agent: &Pubkey,
+    task_id: u64,
+    requester: &Pubkey,
+    mint: &Pubkey,
+    requester_token_account: &Pubkey,
+    params_hash: [u8; 32],
+    max_price: u64,
+) -> Instruction {
+    let task = find_work_task_pda(agent, task_id).0;
+    build(
+        accounts::EnqueueTask {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            queue: find_work_queue_pda(agent).0,
+            task,
+            escrow_token_account: find_task_escrow_pda(&task).0,
+            mint: *mint,
+            requester: *requester,
+            requester_token_account: *requester_token_account,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::EnqueueTask {
+            params_hash,
+            max_price,
+        },
+    )
+}
+
+pub fn claim_task(agent: &Pubkey, task: &Pubkey, worker: &Pubkey) -> Instruction {
+    build(
+        accounts::ClaimTask {
+            x402_config: find_x402_config_pda(agent).0,
+            queue: find_work_queue_pda(agent).0,
+            task: *task,
+            worker: *worker,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::ClaimTask {},
+    )
+}
+
+/// Build `complete_task`; pass `revenue_split` as for `pay_for_service`
+#[allow(clippy::too_many_arguments)]
+pub fn complete_task(
+    agent: &Pubkey,
+    task: &Pubkey,
+    requester: &Pubkey,
+    recipient_token_account: &Pubkey,
+    requester_token_account: &Pubkey,
+    worker: &Pubkey,
+    price: u64,
+    result_hash: [u8; 32],
+    revenue_split: Option<&[Pubkey]>,
+) -> Instruction {
+    let mut ix = build(
+        accounts::CompleteTask {
+            x402_config: find_x402_config_pda(agent).0,
+            queue: find_work_queue_pda(agent).0,
+            task: *task,
+            escrow_token_account: find_task_escrow_pda(task).0,
+            recipient_token_account: *recipient_token_account,
+            requester_token_account: *requester_token_account,
+            requester: *requester,
+            worker: *worker,
+            token_program: spl_token_id(),
+            revenue_split: revenue_split.map(|_| find_revenue_split_pda(agent).0),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::CompleteTask { price, result_hash },
+    );
+    ix.accounts.extend(revenue_split_metas(revenue_split));
+    ix
+}
+
+pub fn withdraw_task(
+    agent: &Pubkey,
+    task: &Pubkey,
+    requester: &Pubkey,
+    requester_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::WithdrawTask {
+            x402_config: find_x402_config_pda(agent).0,
+            queue: find_work_queue_pda(agent).0,
+            task: *task,
+            escrow_token_account: find_task_escrow_pda(task).0,
+            requester_token_account: *requester_token_account,
+            requester: *requester,
+            token_program: spl_token_id(),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::WithdrawTask {},
+    )
+}
+
+/// Build `set_trial_deposit`; `creator` must be the agent's creator
+pub fn set_trial_deposit(agent: &Pubkey, creator: &Pubkey, trial_deposit: u64) -> Instruction {
+    build(
+        accounts::SetTrialDeposit {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            creator: *creator,
+            creator_ownership_account: ownership_account(agent, creator),
+        },
+        instruction::SetTrialDeposit { trial_deposit },
+    )
+}
+
+/// Build `open_trial`, escrowing the agent's trial deposit
+pub fn open_trial(
+    agent: &Pubkey,
+    caller: &Pubkey,
+    mint: &Pubkey,
+    caller_token_account: &Pubkey,
+    service_id: [u8; 32],
+) -> Instruction {
+    let session = find_trial_session_pda(agent, caller).0;
+    build(
+        accounts::OpenTrial {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            session,
+            escrow_token_account: find_trial_escrow_pda(&session).0,
+            mint: *mint,
+            caller: *caller,
+            caller_token_account: *caller_token_account,
+            token_program: spl_token_id(),
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::OpenTrial { service_id },
+    )
+}
+
+pub fn end_trial(agent: &Pubkey, caller: &Pubkey, caller_token_account: &Pubkey) -> Instruction {
+    let session = find_trial_session_pda(agent, caller).0;
+    build(
+        accounts::EndTrial {
+            x402_config: find_x402_config_pda(agent).0,
+            session,
+            escrow_token_account: find_trial_escrow_pda(&session).0,
+            caller_token_account: *caller_token_account,
+            caller: *caller,
+            token_program: spl_token_id(),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::EndTrial {},
+    )
+}
+
+/// Build `forfeit_trial`; the deposit goes to `recipient_token_account`
+pub fn forfeit_trial(
+    agent: &Pubkey,
+    caller: &Pubkey,
+    recipient_token_account: &Pubkey,
+) -> Instruction {
+    let session = find_trial_session_pda(agent, caller).0;
+    build(
+        accounts::ForfeitTrial {
+            x402_config: find_x402_config_pda(agent).0,
+            session,
+            escrow_token_account: find_trial_escrow_pda(&session).0,
+            recipient_token_account: *recipient_token_account,
+            caller: *caller,
+            token_program: spl_token_id(),
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::ForfeitTrial {},
+    )
+}
+
+/// Build `attest_reputation`; `attestor` must be the factory authority or
+/// its reputation attestor
+pub fn attest_reputation(
+    agent: &Pubkey,
+    attestor: &Pubkey,
+    payer: &Pubkey,
+    source: [u8; REPUTATION_SOURCE_LEN],
+    score_bps: u16,
+    weight_bps: u16,
+    evidence_hash: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::AttestReputation {
+            factory: find_factory_pda().0,
+            agent: *agent,
+            reputation: find_reputation_pda(agent).0,
+            attestor: *attestor,
+            payer: *payer,
+            system_program: system_program::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::AttestReputation {
+            source,
+            score_bps,
+            weight_bps,
+            evidence_hash,
+        },
+    )
+}
+
+/// Build `rate_service`; `payer` must have made the payment, in the factory's
+/// quote mint
+pub fn rate_service(
+    agent: &Pubkey,
+    payment_record: &Pubkey,
+    payer: &Pubkey,
+    stars: u8,
+) -> Instruction {
+    build(
+        accounts::RateService {
+            agent: *agent,
+            x402_config: find_x402_config_pda(agent).0,
+            factory: find_factory_pda().0,
+            payment_record: *payment_record,
+            rating: find_rating_pda(payment_record).0,
+            reputation: find_reputation_pda(agent).0,
+            payer: *payer,
+            system_program: system_program::ID,
+            event_authority: find_event_authority_pda().0,
+            program: PROGRAM_ID,
+        },
+        instruction::RateService { stars },
+    )
+}
+
+fn spl_token_id() -> Pubkey {
+    anchor_spl::token::ID
+}
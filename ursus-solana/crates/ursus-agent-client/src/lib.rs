@@ -0,0 +1,20 @@
+//! Rust client SDK for the URSUS Agent Factory program.
+//!
+//! Wraps the RPC client with PDA derivation, instruction builders, account
+//! decoding, local quotes and event subscriptions so off-chain runtimes no
+//! longer have to hand-encode instructions.
+
+pub mod accounts;
+pub mod client;
+pub mod error;
+pub mod events;
+pub mod generated;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+pub mod registry;
+
+pub use agent_factory::ID as PROGRAM_ID;
+pub use client::UrsusClient;
+pub use error::{ClientError, ClientResult, ProgramFailure};
+pub use events::ProgramEvent;
@@ -0,0 +1,4 @@
+//! PDA derivation, re-exported from the program crate so the seeds live in
+//! one place.
+
+pub use agent_factory::pda::*;
@@ -0,0 +1,129 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use agent_factory::state::BondingCurve;
+pub use bonding_curve_math::{BPS_DENOMINATOR, CREATOR_FEE_BPS, PLATFORM_FEE_BPS};
+
+use crate::error::{ClientError, ClientResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuyQuote {
+    /// SOL actually spent, fees included (less than requested on a partial fill)
+    pub sol_amount: u64,
+    /// Tokens minted to the buyer
+    pub tokens_out: u64,
+    /// SOL added to the curve reserves
+    pub net_sol_amount: u64,
+    pub platform_fee: u64,
+    pub creator_fee: u64,
+    /// Spot price after the trade (lamports per token, scaled by 1e9)
+    pub price_after: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SellQuote {
+    /// SOL paid out to the seller after fees
+    pub net_sol_out: u64,
+    /// SOL removed from the curve reserves
+    pub gross_sol_out: u64,
+    pub platform_fee: u64,
+    pub creator_fee: u64,
+    /// Spot price after the trade (lamports per token, scaled by 1e9)
+    pub price_after: u64,
+}
+
+/// Quote a buy locally using the same math the program executes, including
+/// partial fills near graduation
+pub fn quote_buy(curve: &BondingCurve, sol_amount: u64) -> ClientResult<BuyQuote> {
+    quote_buy_discounted(curve, sol_amount, 0)
+}
+
+/// Quote a buy with the platform fee reduced by a platform token discount
+pub fn quote_buy_discounted(
+    curve: &BondingCurve,
+    sol_amount: u64,
+    discount_bps: u16,
+) -> ClientResult<BuyQuote> {
+    let now = unix_now();
+    let bonding_curve_math::BuyQuote {
+        sol_amount,
+        tokens_out,
+        net_sol_amount,
+        platform_fee,
+        creator_fee,
+    } = curve
+        .quote_buy_discounted_at(sol_amount, discount_bps, now)
+        .map_err(quote_error)?;
+
+    let mut after = *curve;
+    after
+        .update_after_buy(net_sol_amount, tokens_out)
+        .map_err(quote_error)?;
+
+    Ok(BuyQuote {
+        sol_amount,
+        tokens_out,
+        net_sol_amount,
+        platform_fee,
+        creator_fee,
+        price_after: after.get_price_at(now),
+    })
+}
+
+/// Quote a sell locally using the same math the program executes
+pub fn quote_sell(curve: &BondingCurve, token_amount: u64) -> ClientResult<SellQuote> {
+    quote_sell_discounted(curve, token_amount, 0)
+}
+
+/// Quote a sell with the platform fee reduced by a platform token discount
+pub fn quote_sell_discounted(
+    curve: &BondingCurve,
+    token_amount: u64,
+    discount_bps: u16,
+) -> ClientResult<SellQuote> {
+    let now = unix_now();
+    let bonding_curve_math::SellQuote {
+        gross_sol_out,
+        net_sol_out,
+        platform_fee,
+        creator_fee,
+    } = curve
+        .quote_sell_discounted_at(token_amount, discount_bps, now)
+        .map_err(quote_error)?;
+
+    let mut after = *curve;
+    after
+        .update_after_sell(token_amount, gross_sol_out)
+        .map_err(quote_error)?;
+
+    Ok(SellQuote {
+        net_sol_out,
+        gross_sol_out,
+        platform_fee,
+        creator_fee,
+        price_after: after.get_price_at(now),
+    })
+}
+
+/// SOL paid for redeeming `token_amount` once an agent's refund guarantee
+/// has kicked in (no fees)
+pub fn quote_redeem(curve: &BondingCurve, token_amount: u64) -> ClientResult<u64> {
+    curve.redemption_value(token_amount).map_err(quote_error)
+}
+
+/// Apply slippage tolerance (in bps) to a quoted output amount
+pub fn with_slippage(amount: u64, slippage_bps: u64) -> u64 {
+    let keep = BPS_DENOMINATOR.saturating_sub(slippage_bps);
+    ((amount as u128 * keep as u128) / BPS_DENOMINATOR as u128) as u64
+}
+
+/// Local clock, used to price LBP curves the way the program would right now
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(i64::MAX)
+}
+
+fn quote_error(e: anchor_lang::error::Error) -> ClientError {
+    ClientError::Quote(e.to_string())
+}
@@ -0,0 +1,4 @@
+//! Account discriminators, sizes and layout versions, re-exported from the
+//! program crate so the SDK and the on-chain `get_layout_info` view agree.
+
+pub use agent_factory::registry::*;
@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+
+/// Subset of the Solana CLI config file (`~/.config/solana/cli/config.yml`)
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    json_rpc_url: Option<String>,
+    keypair_path: Option<String>,
+}
+
+/// Resolved connection settings; command-line flags win over the config file
+#[derive(Debug)]
+pub struct Config {
+    pub rpc_url: String,
+    pub keypair_path: PathBuf,
+}
+
+impl Config {
+    pub fn load(
+        config_path: Option<&Path>,
+        rpc_url: Option<String>,
+        keypair_path: Option<PathBuf>,
+    ) -> Result<Self> {
+        let file = match config_path.map(PathBuf::from).or_else(default_config_path) {
+            Some(path) if path.exists() => {
+                let raw = std::fs::read_to_string(&path)
+                    .with_context(|| format!("reading {}", path.display()))?;
+                serde_yaml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?
+            }
+            Some(path) if config_path.is_some() => {
+                return Err(anyhow!("config file not found: {}", path.display()))
+            }
+            _ => FileConfig::default(),
+        };
+
+        let rpc_url = rpc_url
+            .or(file.json_rpc_url)
+            .unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+        let keypair_path = keypair_path
+            .or_else(|| file.keypair_path.map(PathBuf::from))
+            .or_else(|| dirs::home_dir().map(|home| home.join(".config/solana/id.json")))
+            .ok_or_else(|| anyhow!("no keypair configured"))?;
+
+        Ok(Self {
+            rpc_url,
+            keypair_path,
+        })
+    }
+
+    pub fn keypair(&self) -> Result<Keypair> {
+        read_keypair_file(&self.keypair_path)
+            .map_err(|e| anyhow!("reading keypair {}: {}", self.keypair_path.display(), e))
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config/solana/cli/config.yml"))
+}
@@ -0,0 +1,2837 @@
+//! `ursus` - operator CLI for the URSUS Agent Factory program.
+
+mod config;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand};
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+use ursus_agent_client::accounts::{
+    decay_trending_score, service_id_hash, AdminAction, AgentFactory, CreatorFeeMode, CurveTranche,
+    FeeDiscountTier, KeeperTaskKind, MeterUnit, PaymentStatus, ProposalAction,
+    ReputationAttestation, RevenuePool, CAPABILITY_AFFILIATES, CAPABILITY_CHARGEBACKS,
+    CAPABILITY_ESCROW, CAPABILITY_GRADUATED, CAPABILITY_METERED, CAPABILITY_PAYS_AGENTS,
+    CAPABILITY_REVENUE_SPLIT, CAPABILITY_TRIALS, CAPABILITY_WEBHOOK, CAPABILITY_X402,
+    FEE_DISCOUNT_TIERS, MAX_ALLOWED_MODELS, MAX_ARBITERS, MODEL_ID_LEN, REPUTATION_SOURCE_LEN,
+};
+use ursus_agent_client::instructions::{CreateAgentArgs, X402Settings};
+use ursus_agent_client::registry::ACCOUNT_LAYOUTS;
+use ursus_agent_client::UrsusClient;
+
+use crate::config::Config;
+
+#[derive(Parser)]
+#[command(name = "ursus", version, about = "URSUS Agent Factory operator CLI")]
+struct Cli {
+    /// Solana CLI config file (defaults to ~/.config/solana/cli/config.yml)
+    #[arg(long, global = true, env = "URSUS_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// RPC endpoint, overrides the config file
+    #[arg(long, short = 'u', global = true, env = "URSUS_RPC_URL")]
+    url: Option<String>,
+
+    /// Signer keypair, overrides the config file
+    #[arg(long, short = 'k', global = true, env = "URSUS_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Agent launch and trading
+    #[command(subcommand)]
+    Agent(AgentCommand),
+
+    /// X402 payment configuration and payments
+    #[command(subcommand)]
+    X402(X402Command),
+
+    /// Timelocked factory settings (factory authority)
+    #[command(subcommand)]
+    Admin(AdminCommand),
+
+    /// Platform insurance fund
+    #[command(subcommand)]
+    Insurance(InsuranceCommand),
+
+    /// Factory trending leaderboard
+    #[command(subcommand)]
+    Trending(TrendingCommand),
+
+    /// Protocol-owned liquidity kept from graduations
+    #[command(subcommand)]
+    Pol(PolCommand),
+
+    /// Weighted baskets of agent tokens
+    #[command(subcommand)]
+    Basket(BasketCommand),
+
+    /// Tipped cranks for keepers
+    #[command(subcommand)]
+    Keeper(KeeperCommand),
+
+    /// Provider bonds and payment disputes
+    #[command(subcommand)]
+    Dispute(DisputeCommand),
+
+    /// Escrowed jobs paid out per milestone
+    #[command(subcommand)]
+    Job(JobCommand),
+
+    /// Agent work queues served by operator workers
+    #[command(subcommand)]
+    Queue(QueueCommand),
+
+    /// Print the discriminator, size and layout version of every account
+    /// type, checked against the deployed program
+    Layouts,
+}
+
+#[derive(Subcommand)]
+enum AdminCommand {
+    /// Queue a new agent creation fee (lamports)
+    QueueCreationFee { lamports: u64 },
+
+    /// Queue a new agent rename fee (lamports)
+    QueueRenameFee { lamports: u64 },
+
+    /// Queue a new creator fee streaming period for new agents (0 = paid
+    /// out per trade)
+    QueueCreatorFeeVesting { secs: i64 },
+
+    /// Queue creation fee surge pricing: a surcharge (bps of the fee) per
+    /// recent creation, decaying by half every `half_life_secs` (0 bps = off)
+    QueueCreationSurge {
+        bps: u16,
+        #[arg(long, default_value_t = 3600)]
+        half_life_secs: i64,
+    },
+
+    /// Queue a creation fee payable in a quote mint such as USDC (base units)
+    QueueQuoteCreationFee { quote_mint: Pubkey, amount: u64 },
+
+    /// Queue a new platform treasury
+    QueuePlatformTreasury { treasury: Pubkey },
+
+    /// Queue a new timelock delay
+    QueueTimelockDelay { secs: i64 },
+
+    /// Queue a new factory authority
+    QueueAuthority {
+        authority: Pubkey,
+        /// The new authority is a program PDA, e.g. a Squads vault
+        #[arg(long)]
+        program: bool,
+    },
+
+    /// Queue a new insurance fund share of platform fees (bps)
+    QueueInsuranceFee { bps: u16 },
+
+    /// Queue a share of platform fees (bps) routed to a public goods
+    /// recipient such as an ecosystem grants program (0 = off)
+    QueuePublicGoodsFee { recipient: Pubkey, bps: u16 },
+
+    /// Pay an agent's accrued public goods fees to the factory's recipient
+    /// (permissionless)
+    CollectPublicGoods { agent: Pubkey },
+
+    /// Queue a new protocol-owned share of graduation liquidity (bps)
+    QueueProtocolLiquidity { bps: u16 },
+
+    /// Queue platform token fee discounts; without a mint, discounts are
+    /// turned off
+    QueueFeeDiscounts {
+        /// Platform token mint (an agent's mint)
+        #[arg(long, requires = "staking_pool")]
+        platform_mint: Option<Pubkey>,
+        /// The platform token agent's staking pool
+        #[arg(long)]
+        staking_pool: Option<Pubkey>,
+        /// Repeat per tier (up to 4, ascending) as `min_stake,discount_bps`
+        #[arg(long = "tier", value_parser = parse_discount_tier)]
+        tiers: Vec<FeeDiscountTier>,
+    },
+
+    /// Queue a new arbitration council resolving payment disputes
+    QueueArbitrationCouncil {
+        /// Repeat per council member (up to 7)
+        #[arg(long = "arbiter", required = true)]
+        arbiters: Vec<Pubkey>,
+        /// Matching votes needed to resolve a dispute
+        #[arg(long)]
+        threshold: u8,
+    },
+
+    /// Queue a new allowlist of models agents may claim to run on; without
+    /// models, any model is accepted
+    QueueAllowedModels {
+        /// Repeat per model (up to 16), e.g. `--model gpt-4o --model llama-3`
+        #[arg(long = "model")]
+        models: Vec<String>,
+    },
+
+    /// Queue a key allowed to post imported agent reputation besides the
+    /// authority; without one, only the authority can
+    QueueReputationAttestor { attestor: Option<Pubkey> },
+
+    /// Post an agent's reputation from another platform, as the authority
+    /// or reputation attestor (applies immediately)
+    AttestReputation {
+        agent: Pubkey,
+        /// Lowercase source tag, e.g. `olas`
+        #[arg(value_parser = parse_reputation_source)]
+        source: [u8; REPUTATION_SOURCE_LEN],
+        /// Score on the source, normalized to bps
+        score_bps: u16,
+        /// Weight of the source in the imported score (bps); 0 withdraws it
+        weight_bps: u16,
+        /// SHA-256 of the source's signed record, hex
+        #[arg(long, value_parser = parse_hex::<32>)]
+        evidence: [u8; 32],
+    },
+
+    /// Record a creator's rugged agents and blacklist status (applies immediately)
+    CreatorStanding {
+        creator: Pubkey,
+        /// Agents of this creator found rugged or exploited
+        #[arg(long, default_value_t = 0)]
+        rugged: u16,
+        #[arg(long)]
+        blacklisted: bool,
+    },
+
+    /// Reserve an agent name or symbol for a verified brand (applies immediately)
+    ReserveName {
+        /// Name or symbol, matched case-insensitively
+        name: String,
+        /// Only wallet allowed to launch an agent under it
+        owner: Pubkey,
+    },
+
+    /// Release a reserved name or symbol
+    ReleaseName { name: String },
+
+    /// Cap agent creations per window to throttle spam (applies immediately)
+    CreationRateLimit {
+        /// Most creations per window; 0 = no limit
+        max_creations: u32,
+        #[arg(long, default_value_t = 86400)]
+        window_secs: i64,
+    },
+
+    /// Value the quote mint for disputes and insurance (applies immediately)
+    QuoteLamportRate {
+        /// Lamports per 1_000_000 quote base units; 0 = quote payments are
+        /// not valued
+        lamports: u64,
+    },
+
+    /// Lower the platform and creator fees (bps) on an agent's trades,
+    /// effective immediately; the standard fees (100, 100) end the override
+    FeeOverride {
+        agent: Pubkey,
+        platform_fee_bps: u16,
+        creator_fee_bps: u16,
+    },
+
+    /// Hard-stop x402 payments to an agent, effective immediately
+    FreezeX402 { agent: Pubkey },
+
+    /// Lift a freeze on an agent's x402 payments
+    ThawX402 { agent: Pubkey },
+
+    /// Apply a queued action whose timelock has expired
+    Execute { action_id: u64 },
+
+    /// Cancel a queued action
+    Cancel { action_id: u64 },
+}
+
+#[derive(Subcommand)]
+enum InsuranceCommand {
+    /// Open the insurance fund (factory authority)
+    Init,
+
+    /// Sweep an agent's accrued insurance fees into the fund
+    Collect { agent: Pubkey },
+
+    /// Deposit SOL into the fund (lamports)
+    Fund { lamports: u64 },
+
+    /// Pay a claim to a user harmed by an agent (factory authority)
+    PayClaim {
+        agent: Pubkey,
+        recipient: Pubkey,
+        lamports: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum PolCommand {
+    /// Open the protocol-owned liquidity vault (factory authority)
+    Init,
+
+    /// Withdraw SOL (lamports) and/or agent tokens (factory authority)
+    Withdraw {
+        recipient: Pubkey,
+        #[arg(long, default_value_t = 0)]
+        lamports: u64,
+        /// Token amount (base units), requires `--mint`
+        #[arg(long, default_value_t = 0, requires = "mint")]
+        tokens: u64,
+        #[arg(long)]
+        mint: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BasketCommand {
+    /// Define a basket owned by the signer
+    Create {
+        #[arg(long)]
+        id: u64,
+        #[arg(long)]
+        name: String,
+        /// Repeat per agent (up to 8) as `agent,units`, with units the agent
+        /// token base units per whole basket token
+        #[arg(long = "constituent", value_parser = parse_constituent, required = true)]
+        constituents: Vec<(Pubkey, u64)>,
+    },
+
+    /// Deposit constituents and mint basket tokens (base units)
+    Mint { basket: Pubkey, amount: u64 },
+
+    /// Burn basket tokens (base units) for the constituents
+    Redeem { basket: Pubkey, amount: u64 },
+
+    /// Print a basket's constituents
+    Show { basket: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum DisputeCommand {
+    /// Post (or add to) the SOL bond (lamports) backing one of the signer's agents
+    Bond { agent: Pubkey, amount: u64 },
+
+    /// Withdraw bonded SOL (lamports) while no dispute is open
+    Unbond { agent: Pubkey, amount: u64 },
+
+    /// Dispute a payment, recording the hash of the evidence and the
+    /// compensation claimed (lamports)
+    Submit {
+        payment_record: Pubkey,
+        /// Hex hash of the evidence
+        #[arg(long, value_parser = parse_hex::<32>)]
+        evidence: [u8; 32],
+        #[arg(long)]
+        claim: u64,
+    },
+
+    /// Vote as a council member to slash the bond (lamports) to the
+    /// claimant; 0 votes to dismiss the dispute
+    Vote { payment_record: Pubkey, amount: u64 },
+
+    /// Pay the insured part of an award the provider bond fell short of
+    ClaimInsurance { payment_record: Pubkey },
+
+    /// Print a payment's dispute
+    Show { payment_record: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum JobCommand {
+    /// Hire an agent for a job, locking every milestone's amount now
+    Create {
+        agent: Pubkey,
+        mint: Pubkey,
+        /// Payment token units of each milestone, in order (repeatable)
+        #[arg(long = "milestone", required = true)]
+        milestones: Vec<u64>,
+        /// Service name, hashed into its service ID
+        #[arg(long, value_parser = parse_service_id)]
+        service_id: [u8; 32],
+        /// Seconds until unsubmitted milestones can be reclaimed
+        #[arg(long, default_value_t = 30 * 24 * 60 * 60)]
+        duration: i64,
+    },
+
+    /// Deliver a milestone of a job for one of your agents
+    Submit {
+        job: Pubkey,
+        index: u8,
+        /// Hex hash of the deliverable
+        #[arg(long, value_parser = parse_hex::<32>)]
+        deliverable: [u8; 32],
+    },
+
+    /// Pay a submitted milestone to the provider
+    Release { job: Pubkey, index: u8 },
+
+    /// Dispute a submitted milestone of one of your jobs
+    Dispute {
+        job: Pubkey,
+        index: u8,
+        /// Hex hash of the evidence
+        #[arg(long, value_parser = parse_hex::<32>)]
+        evidence: [u8; 32],
+    },
+
+    /// Vote as a council member on the share (bps) of a disputed milestone
+    /// refunded to the payer; 0 votes to dismiss the dispute
+    Vote {
+        job: Pubkey,
+        index: u8,
+        refund_bps: u16,
+    },
+
+    /// Reclaim the milestones left unsubmitted past the deadline
+    Cancel { job: Pubkey },
+
+    /// Close one of your settled jobs, returning its rent
+    Close { job: Pubkey },
+
+    /// Print a job and its milestones
+    Show { job: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum QueueCommand {
+    /// Set the workers serving one of your agents' work queue
+    Workers {
+        agent: Pubkey,
+        /// Worker key (repeatable; none removes every worker)
+        #[arg(long = "worker")]
+        workers: Vec<Pubkey>,
+    },
+
+    /// Queue a work request for an agent, escrowing up to max_price
+    Enqueue {
+        agent: Pubkey,
+        mint: Pubkey,
+        max_price: u64,
+        /// Hex hash of the request parameters
+        #[arg(long, value_parser = parse_hex::<32>)]
+        params: [u8; 32],
+    },
+
+    /// Claim a queued task as a worker
+    Claim { task: Pubkey },
+
+    /// Deliver a task you claimed, charging price of its escrow
+    Complete {
+        task: Pubkey,
+        price: u64,
+        /// Hex hash of the result
+        #[arg(long, value_parser = parse_hex::<32>)]
+        result: [u8; 32],
+    },
+
+    /// Withdraw one of your tasks no worker is serving, refunding it
+    Withdraw { task: Pubkey },
+
+    /// Print an agent's work queue
+    Show { agent: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum KeeperCommand {
+    /// Register a task tipping keepers per unit of work on `target`
+    Register {
+        #[arg(long)]
+        id: u64,
+        /// `dca`, `graduation` or `admin-action`
+        #[arg(long, value_parser = parse_keeper_kind)]
+        kind: KeeperTaskKind,
+        /// DCA schedule, agent or queued admin action
+        #[arg(long)]
+        target: Pubkey,
+        /// Tip per unit of work (lamports)
+        #[arg(long)]
+        tip: u64,
+        /// Tip deposit (lamports)
+        #[arg(long, default_value_t = 0)]
+        deposit: u64,
+    },
+
+    /// Top up a task's tip deposit (lamports)
+    Fund { task: Pubkey, amount: u64 },
+
+    /// Run a task's crank and collect its tip
+    Crank { task: Pubkey },
+
+    /// Close one of the signer's tasks, refunding the deposit
+    Cancel { task: Pubkey },
+
+    /// Print every registered task
+    List,
+
+    /// Crank every task with work due, forever
+    Run {
+        #[arg(long, default_value_t = 30)]
+        interval_secs: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrendingCommand {
+    /// Create the leaderboard, updated by later trades
+    Init,
+
+    /// Print the leaderboard with scores decayed to the current slot
+    Show,
+}
+
+#[derive(Subcommand)]
+enum AgentCommand {
+    /// Create a new agent with a bonding curve
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+        #[arg(long, default_value = "")]
+        description: String,
+        #[arg(long, default_value = "")]
+        instructions: String,
+        /// Model the agent runs on, from the factory allowlist (e.g. gpt-4o)
+        #[arg(long)]
+        model: String,
+        /// Discovery tag; repeat per tag (up to 5, e.g. `--tag trading --tag solana`)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Pay the creation fee in the factory's quote mint (e.g. USDC) instead of SOL
+        #[arg(long)]
+        fee_in_quote: bool,
+        /// Use a stepped curve; repeat per tranche as `supply,start_price,slope`
+        /// (supply in base units, prices in nano-lamports per token)
+        #[arg(long = "tranche", value_parser = parse_tranche)]
+        tranches: Vec<CurveTranche>,
+        /// Launch as a liquidity bootstrapping auction decaying over this many seconds
+        #[arg(long, conflicts_with = "tranches")]
+        lbp_duration_secs: Option<i64>,
+        /// Opening price of the LBP as a multiple of the regular curve price
+        #[arg(long, default_value_t = 10, requires = "lbp_duration_secs")]
+        lbp_multiplier: u64,
+        /// Configure x402 in the same transaction with this minimum payment
+        #[arg(long, conflicts_with_all = ["tranches", "lbp_duration_secs"])]
+        x402_min_payment: Option<u64>,
+        /// 0 = no limit
+        #[arg(long, default_value_t = 0, requires = "x402_min_payment")]
+        x402_max_payment: u64,
+        #[arg(long, default_value_t = 300, requires = "x402_min_payment")]
+        x402_timeout_secs: u64,
+        /// Buy this many lamports of the new agent's tokens in the same
+        /// transaction (configures x402 too, disabled unless --x402-min-payment)
+        #[arg(long, conflicts_with_all = ["tranches", "lbp_duration_secs"])]
+        dev_buy: Option<u64>,
+        #[arg(long, default_value_t = 100, requires = "dev_buy")]
+        slippage_bps: u64,
+        /// Commit to the agent first and reveal it in a later slot, so its
+        /// name and symbol can't be copied from the pending creation
+        #[arg(long, conflicts_with_all = ["tranches", "lbp_duration_secs", "x402_min_payment", "dev_buy"])]
+        sealed: bool,
+        /// Reserve this share of the supply (bps, up to 1000) for exchange
+        /// listings, locked until after graduation
+        #[arg(long, conflicts_with_all = ["tranches", "lbp_duration_secs", "x402_min_payment", "dev_buy", "sealed"])]
+        listing_reserve_bps: Option<u16>,
+        /// Seconds after graduation before the listing reserve unlocks
+        #[arg(long, default_value_t = 0, requires = "listing_reserve_bps")]
+        listing_release_delay_secs: i64,
+        /// Who the creator fee goes to: `creator`, `waived` (no creator fee)
+        /// or `holders` (all to the staking pool)
+        #[arg(
+            long,
+            value_parser = parse_creator_fee_mode,
+            default_value = "creator",
+            conflicts_with_all = ["tranches", "lbp_duration_secs", "x402_min_payment", "dev_buy", "sealed", "listing_reserve_bps"]
+        )]
+        creator_fee: CreatorFeeMode,
+    },
+
+    /// Launch a themed bundle of agents, as many per transaction as fit
+    CreateBundle {
+        /// Prepended to every agent name (e.g. `Alpha `)
+        #[arg(long, default_value = "")]
+        prefix: String,
+        /// Agent as `NAME:SYMBOL`; repeat per agent
+        #[arg(long = "agent", value_parser = parse_bundle_agent, required = true)]
+        agents: Vec<(String, String)>,
+        #[arg(long, default_value = "")]
+        description: String,
+        #[arg(long, default_value = "")]
+        instructions: String,
+        /// Model shared by every agent in the bundle
+        #[arg(long)]
+        model: String,
+        /// Discovery tag shared by every agent; repeat per tag
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+
+    /// Buy agent tokens (amount in lamports)
+    Buy {
+        agent: Pubkey,
+        lamports: u64,
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u64,
+        /// Mint the tokens to this wallet instead of the signer
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+    },
+
+    /// Sell agent tokens (amount in base units)
+    Sell {
+        agent: Pubkey,
+        tokens: u64,
+        #[arg(long, default_value_t = 100)]
+        slippage_bps: u64,
+    },
+
+    /// Opt an agent into the refund guarantee (before its first trade)
+    RefundGuarantee {
+        agent: Pubkey,
+        /// Days after creation the agent has to graduate
+        #[arg(long)]
+        days: u16,
+    },
+
+    /// Redeem tokens of an agent that missed its guaranteed graduation deadline
+    Redeem { agent: Pubkey, tokens: u64 },
+
+    /// Lock tokens (base units) until a unix timestamp
+    Lock {
+        agent: Pubkey,
+        tokens: u64,
+        #[arg(long)]
+        unlock_ts: i64,
+        /// Distinguishes several locks of the same agent token
+        #[arg(long, default_value_t = 0)]
+        id: u64,
+    },
+
+    /// Withdraw the tokens of an expired lock
+    Unlock {
+        agent: Pubkey,
+        #[arg(long, default_value_t = 0)]
+        id: u64,
+    },
+
+    /// Release listing reserve tokens (base units) to an exchange or market
+    /// maker token account once unlocked
+    ReleaseListingReserve {
+        agent: Pubkey,
+        tokens: u64,
+        #[arg(long)]
+        destination: Pubkey,
+    },
+
+    /// Record a holder snapshot for off-chain distributions
+    Snapshot { agent: Pubkey },
+
+    /// Create the agent's on-chain candle accumulator, updated by later trades
+    InitPriceHistory { agent: Pubkey },
+
+    /// Print the agent's TWAP (nano-lamports per token) over a slot window
+    Twap {
+        agent: Pubkey,
+        #[arg(long, default_value_t = 1500)]
+        window_slots: u64,
+    },
+
+    /// Print the price, market cap and reserves other programs read via CPI
+    Price { agent: Pubkey },
+
+    /// Print the capabilities, service prices and operator keys other agents
+    /// discover the agent by
+    Manifest {
+        agent: Pubkey,
+        /// Payment token to check the agent accepts (repeatable)
+        #[arg(long = "mint")]
+        mints: Vec<Pubkey>,
+    },
+
+    /// Print the agent's payment-weighted rating and the reputation imported
+    /// for it from other platforms
+    Reputation { agent: Pubkey },
+
+    /// Graduate an agent that reached its threshold
+    Graduate {
+        agent: Pubkey,
+        #[arg(long, default_value_t = Pubkey::default())]
+        dex_program: Pubkey,
+    },
+
+    /// Graduate every eligible agent among up to 8 in one transaction
+    GraduateBatch {
+        #[arg(required = true, num_args = 1..=8)]
+        agents: Vec<Pubkey>,
+    },
+
+    /// Take over the creator role with the agent's ownership NFT
+    ClaimOwnership { agent: Pubkey },
+
+    /// Hand the agent and its ownership NFT to a new creator
+    TransferOwnership { agent: Pubkey, new_creator: Pubkey },
+
+    /// Set the share of creator fees (bps) the signer, as original creator,
+    /// keeps after the agent changes hands
+    SetRoyalty { agent: Pubkey, royalty_bps: u16 },
+
+    /// Claim the royalties held for the signer as original creator
+    ClaimRoyalties { agent: Pubkey },
+
+    /// Reject sells from wallets that bought in the same slot
+    SameSlotProtection {
+        agent: Pubkey,
+        #[arg(long)]
+        disabled: bool,
+    },
+
+    /// Switch the model an agent claims to run on (factory allowlist)
+    SetModel { agent: Pubkey, model: String },
+
+    /// Claim the creator fees an agent has streamed out so far
+    ClaimFees { agent: Pubkey },
+
+    /// Cap each buy at a share (bps) of the remaining curve tokens; 0 lifts
+    /// the cap
+    MaxBuy { agent: Pubkey, max_buy_bps: u16 },
+
+    /// Burn the unsold curve tokens at graduation instead of adding them to
+    /// the pool
+    GraduationBurn {
+        agent: Pubkey,
+        #[arg(long)]
+        disabled: bool,
+    },
+
+    /// Rename an agent and its symbol (pays the factory rename fee; once
+    /// per 30 days)
+    Rename {
+        agent: Pubkey,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        symbol: String,
+    },
+
+    /// Add a discovery tag to an agent
+    AddTag { agent: Pubkey, tag: String },
+
+    /// Remove a discovery tag from an agent
+    RemoveTag { agent: Pubkey, tag: String },
+
+    /// Deposit revenue earned outside x402 into an agent's staking pool, or
+    /// with --treasury its DAO treasury
+    DepositRevenue {
+        agent: Pubkey,
+        /// Lamports, or base units of --mint
+        amount: u64,
+        /// Label attributing the revenue to its source (up to 32 bytes)
+        #[arg(long, value_parser = parse_source_tag)]
+        source: [u8; 32],
+        #[arg(long)]
+        treasury: bool,
+        /// Deposit this token instead of SOL (treasury only)
+        #[arg(long, requires = "treasury")]
+        mint: Option<Pubkey>,
+    },
+
+    /// Dollar-cost-average into an agent token
+    #[command(subcommand)]
+    Dca(DcaCommand),
+
+    /// Token bonuses for an agent's first buyers
+    #[command(subcommand)]
+    EarlyBuyers(EarlyBuyersCommand),
+
+    /// Agent token emissions for LP stakers of a graduated agent's pool
+    #[command(subcommand)]
+    Mining(MiningCommand),
+
+    /// Swap a project's previous token into its agent token
+    #[command(subcommand)]
+    Migration(MigrationCommand),
+
+    /// Presale commitments for an agent that has not started trading
+    #[command(subcommand)]
+    Presale(PresaleCommand),
+
+    /// Stake agent tokens for a share of the creator fees
+    #[command(subcommand)]
+    Staking(StakingCommand),
+
+    /// Staker votes on agent parameter changes
+    #[command(subcommand)]
+    Governance(GovernanceCommand),
+}
+
+#[derive(Subcommand)]
+enum DcaCommand {
+    /// Deposit lamports * intervals and schedule the buys
+    Create {
+        agent: Pubkey,
+        lamports: u64,
+        #[arg(long)]
+        interval_secs: i64,
+        #[arg(long)]
+        intervals: u64,
+        /// Fewest tokens a full interval may buy; executions priced worse fail
+        #[arg(long)]
+        min_tokens: u64,
+    },
+
+    /// Execute the next due interval of a schedule (defaults to the signer's)
+    Execute {
+        agent: Pubkey,
+        #[arg(long)]
+        owner: Option<Pubkey>,
+    },
+
+    /// Cancel the signer's schedule and refund the remaining deposit
+    Cancel { agent: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum EarlyBuyersCommand {
+    /// Fund a bonus (base units) for each of the first buyers, claimable
+    /// until a unix timestamp
+    Create {
+        agent: Pubkey,
+        bonus: u64,
+        #[arg(long)]
+        max_buyers: u64,
+        #[arg(long)]
+        deadline_ts: i64,
+    },
+
+    /// Claim the signer's bonus
+    Claim { agent: Pubkey },
+
+    /// Return the unclaimed bonuses after the deadline
+    Close { agent: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum MiningCommand {
+    /// Open liquidity mining for the pool whose LP token is `lp_mint`
+    Create { agent: Pubkey, lp_mint: Pubkey },
+
+    /// Emit an amount of agent tokens (base units) over a duration
+    Fund {
+        agent: Pubkey,
+        amount: u64,
+        #[arg(long)]
+        duration_secs: i64,
+    },
+
+    /// Stake LP tokens (base units)
+    Stake { agent: Pubkey, amount: u64 },
+
+    /// Withdraw staked LP tokens (base units)
+    Withdraw { agent: Pubkey, amount: u64 },
+
+    /// Claim the signer's earned rewards
+    Claim { agent: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum MigrationCommand {
+    /// Register a swap from `old_mint`, paying `numerator / denominator`
+    /// agent tokens per old token from a deposit (base units)
+    Create {
+        agent: Pubkey,
+        old_mint: Pubkey,
+        deposit: u64,
+        #[arg(long, default_value_t = 1)]
+        numerator: u64,
+        #[arg(long, default_value_t = 1)]
+        denominator: u64,
+    },
+
+    /// Swap old tokens (base units) for agent tokens
+    Migrate { agent: Pubkey, amount: u64 },
+}
+
+#[derive(Subcommand)]
+enum PresaleCommand {
+    /// Open a presale window (unix timestamps, caps in lamports)
+    Create {
+        agent: Pubkey,
+        #[arg(long)]
+        start_ts: i64,
+        #[arg(long)]
+        end_ts: i64,
+        #[arg(long)]
+        soft_cap: u64,
+        #[arg(long)]
+        hard_cap: u64,
+    },
+
+    /// Commit lamports to an open presale
+    Commit { agent: Pubkey, lamports: u64 },
+
+    /// Finalize an ended presale
+    Finalize { agent: Pubkey },
+
+    /// Claim the signer's tokens from a successful presale
+    Claim { agent: Pubkey },
+
+    /// Refund the signer's commitment to a failed presale
+    Refund { agent: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum StakingCommand {
+    /// Open (or with --update, change) the agent's staking pool
+    Create {
+        agent: Pubkey,
+        /// Share of the creator fee routed to stakers
+        #[arg(long)]
+        fee_bps: u16,
+        /// Max lamports streamed per second (0 = release immediately)
+        #[arg(long, default_value_t = 0)]
+        emission_rate_cap: u64,
+        #[arg(long)]
+        update: bool,
+    },
+
+    /// Stake tokens (base units)
+    Stake { agent: Pubkey, tokens: u64 },
+
+    /// Lock the signer's stake until a unix timestamp for boosted voting weight
+    Lock {
+        agent: Pubkey,
+        #[arg(long)]
+        unlock_ts: i64,
+    },
+
+    /// Withdraw staked tokens (base units) once the lock has ended
+    Unstake { agent: Pubkey, tokens: u64 },
+
+    /// Claim the signer's SOL rewards
+    Claim { agent: Pubkey },
+
+    /// Deposit lamports into the pool as rewards
+    Fund { agent: Pubkey, lamports: u64 },
+}
+
+#[derive(Args)]
+struct GovernanceArgs {
+    /// Share of the total voting weight that must vote
+    #[arg(long)]
+    quorum_bps: u16,
+    /// Share of the cast votes that must be in favor
+    #[arg(long, default_value_t = 5_000)]
+    threshold_bps: u16,
+    #[arg(long, default_value_t = 3 * 24 * 60 * 60)]
+    voting_period_secs: i64,
+    /// Voting weight needed to create a proposal
+    #[arg(long, default_value_t = 0)]
+    proposal_threshold: u64,
+}
+
+#[derive(Subcommand)]
+enum GovernanceCommand {
+    /// Set up governance for an agent (creator only)
+    Create {
+        agent: Pubkey,
+        #[command(flatten)]
+        settings: GovernanceArgs,
+    },
+
+    /// Propose a new creator fee share for stakers
+    ProposeStakingFee {
+        agent: Pubkey,
+        #[arg(long)]
+        fee_bps: u16,
+    },
+
+    /// Propose a new x402 service price range (lamports, max 0 = no limit)
+    ProposeServicePrices {
+        agent: Pubkey,
+        #[arg(long)]
+        min_payment: u64,
+        #[arg(long, default_value_t = 0)]
+        max_payment: u64,
+    },
+
+    /// Propose a new description and tags
+    ProposeMetadata {
+        agent: Pubkey,
+        #[arg(long)]
+        description: String,
+        /// Repeat per tag (up to 5)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+
+    /// Propose new governance settings
+    ProposeGovernance {
+        agent: Pubkey,
+        #[command(flatten)]
+        settings: GovernanceArgs,
+    },
+
+    /// Propose a new creator fee share for the DAO treasury
+    ProposeTreasuryFee {
+        agent: Pubkey,
+        #[arg(long)]
+        fee_bps: u16,
+    },
+
+    /// Propose paying lamports from the DAO treasury
+    ProposeTransferSol {
+        agent: Pubkey,
+        recipient: Pubkey,
+        lamports: u64,
+    },
+
+    /// Propose paying tokens (base units) from the DAO treasury's ATA for a mint
+    ProposeTransferToken {
+        agent: Pubkey,
+        mint: Pubkey,
+        /// Recipient token account
+        recipient: Pubkey,
+        amount: u64,
+    },
+
+    /// Open the agent's DAO treasury (creator only, after governance)
+    CreateTreasury {
+        agent: Pubkey,
+        #[arg(long)]
+        fee_bps: u16,
+    },
+
+    /// Sweep accrued treasury fees from the agent into the treasury
+    CollectTreasuryFees { agent: Pubkey },
+
+    /// Vote on an open proposal with the signer's stake
+    Vote {
+        agent: Pubkey,
+        proposal_id: u64,
+        #[arg(long)]
+        against: bool,
+    },
+
+    /// Apply a passed proposal after voting ends
+    Execute { agent: Pubkey, proposal_id: u64 },
+}
+
+#[derive(Args)]
+struct X402Args {
+    #[arg(long)]
+    disabled: bool,
+    #[arg(long)]
+    min_payment: u64,
+    /// 0 = no limit
+    #[arg(long, default_value_t = 0)]
+    max_payment: u64,
+    #[arg(long, default_value_t = 300)]
+    timeout_secs: u64,
+}
+
+impl From<&X402Args> for X402Settings {
+    fn from(args: &X402Args) -> Self {
+        Self {
+            enabled: !args.disabled,
+            min_payment_amount: args.min_payment,
+            max_payment_amount: args.max_payment,
+            service_timeout_seconds: args.timeout_secs,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum X402Command {
+    /// Create (or with --update, change) an agent's X402 config
+    Configure {
+        agent: Pubkey,
+        #[command(flatten)]
+        settings: X402Args,
+        #[arg(long)]
+        update: bool,
+    },
+
+    /// Pay for an agent service from the signer's associated token account
+    Pay {
+        agent: Pubkey,
+        amount: u64,
+        /// Service name, hashed into its service ID
+        #[arg(long, value_parser = parse_service_id)]
+        service_id: [u8; 32],
+        /// Payment token mint (e.g. USDC)
+        #[arg(long)]
+        mint: Pubkey,
+        /// Hold the payment in escrow until the agent's verifier attests it
+        #[arg(long)]
+        escrowed: bool,
+        /// Insure the payment for this many lamports, up to its lamport value
+        /// (5% premium to the insurance fund)
+        #[arg(long)]
+        insure: Option<u64>,
+        /// Wallet of the affiliate that referred the payment
+        #[arg(long, conflicts_with = "escrowed")]
+        affiliate: Option<Pubkey>,
+        /// Hex id (16 bytes) of the off-chain request the payment is for
+        #[arg(long, value_parser = parse_hex::<16>, default_value = "00000000000000000000000000000000")]
+        request_id: [u8; 16],
+        /// Tip paid to the provider on top of the price to expedite the
+        /// request (payment token units)
+        #[arg(long, default_value_t = 0, conflicts_with = "escrowed")]
+        priority_tip: u64,
+    },
+
+    /// Set the signer attesting escrowed payments (default key = off)
+    Verifier { agent: Pubkey, verifier: Pubkey },
+
+    /// Move payments to a new recipient, keeping the old one valid for a
+    /// grace window
+    RotateRecipient {
+        agent: Pubkey,
+        new_recipient: Pubkey,
+        /// Seconds the old recipient stays valid (max 7 days)
+        #[arg(long, default_value_t = 86400)]
+        grace_secs: i64,
+    },
+
+    /// Set how long after settlement payments can be disputed (0 = no limit)
+    ChargebackWindow { agent: Pubkey, secs: u64 },
+
+    /// Reject new payments for a service from a sunset timestamp (0 =
+    /// withdraw the deprecation)
+    Deprecate {
+        agent: Pubkey,
+        /// Service name, hashed into its service ID
+        #[arg(value_parser = parse_service_id)]
+        service_id: [u8; 32],
+        sunset_ts: i64,
+    },
+
+    /// Close a payment to disputes after its chargeback window
+    Finalize { payment_record: Pubkey },
+
+    /// Route a share of every payment to worker agents' DAO treasuries
+    RevenueSplit {
+        agent: Pubkey,
+        /// Repeat per worker agent (up to 4) as `agent,bps`; none stops
+        /// routing
+        #[arg(long = "worker", value_parser = parse_revenue_share)]
+        workers: Vec<(Pubkey, u16)>,
+    },
+
+    /// Attest an escrowed payment as the agent's verifier
+    Attest {
+        payment_record: Pubkey,
+        /// The output failed the check; refunds the payer
+        #[arg(long)]
+        failed: bool,
+        /// Refund this share (bps) to the payer and pay the rest
+        #[arg(long, conflicts_with = "failed")]
+        refund_bps: Option<u16>,
+        /// Hex hash of the attested service output
+        #[arg(long, value_parser = parse_hex::<32>)]
+        hash: [u8; 32],
+    },
+
+    /// Refund an escrowed payment left unattested past the service timeout
+    Refund { payment_record: Pubkey },
+
+    /// Bill an agent's services by usage (price 0 = flat pricing)
+    Metering {
+        agent: Pubkey,
+        /// Price per unit in payment token units
+        unit_price: u64,
+        /// Price per compute unit instead of per 1K LLM tokens
+        #[arg(long)]
+        compute_units: bool,
+    },
+
+    /// Quote a payer a negotiated price for one of your agents' services
+    Quote {
+        agent: Pubkey,
+        payer: Pubkey,
+        amount: u64,
+        /// Service name, hashed into its service ID
+        #[arg(long, value_parser = parse_service_id)]
+        service_id: [u8; 32],
+        /// Seconds until the quote expires (at most 24 hours)
+        #[arg(long, default_value_t = 3600)]
+        ttl: i64,
+    },
+
+    /// Pay the price an agent quoted you
+    PayQuote {
+        agent: Pubkey,
+        /// Service name, hashed into its service ID
+        #[arg(long, value_parser = parse_service_id)]
+        service_id: [u8; 32],
+        /// Payment token mint (e.g. USDC)
+        #[arg(long)]
+        mint: Pubkey,
+        /// Insure the payment for this many lamports
+        #[arg(long)]
+        insure: Option<u64>,
+        /// Wallet of the affiliate that referred the payment
+        #[arg(long)]
+        affiliate: Option<Pubkey>,
+        /// Hex id (16 bytes) of the off-chain request the payment is for
+        #[arg(long, value_parser = parse_hex::<16>, default_value = "00000000000000000000000000000000")]
+        request_id: [u8; 16],
+        /// Tip paid to the provider on top of the price to expedite the
+        /// request (payment token units)
+        #[arg(long, default_value_t = 0)]
+        priority_tip: u64,
+    },
+
+    /// Register as an affiliate asking this share (bps) of referred payments
+    Affiliate { fee_bps: u16 },
+
+    /// Cap the affiliate share (bps) of one of your agents' payments
+    AffiliateCap { agent: Pubkey, max_bps: u16 },
+
+    /// Register the callback endpoint and response signing key of one of
+    /// your agents; without a URL, clears the webhook
+    Webhook {
+        agent: Pubkey,
+        #[arg(long, requires = "signer")]
+        url: Option<String>,
+        /// Key the agent signs webhook responses with
+        #[arg(long)]
+        signer: Option<Pubkey>,
+    },
+
+    /// Close a used, expired or withdrawn quote, returning its rent
+    CloseQuote { quote: Pubkey },
+
+    /// Settle a metered payment with the service's reported usage
+    SettleUsage {
+        payment_record: Pubkey,
+        /// LLM tokens or compute units consumed
+        #[arg(long)]
+        units: u64,
+        /// Hex hash of the usage log
+        #[arg(long, value_parser = parse_hex::<32>)]
+        hash: [u8; 32],
+    },
+
+    /// Open a spending report for your wallet, or with --agent one of your
+    /// agents, updated by its later payments
+    OpenReport {
+        #[arg(long)]
+        agent: Option<Pubkey>,
+    },
+
+    /// Show the spending report of a paying wallet or agent
+    Report { consumer: Pubkey },
+
+    /// Stream payment to an agent per second, locking rate x duration now
+    Stream {
+        agent: Pubkey,
+        mint: Pubkey,
+        /// Payment token units per second
+        rate_per_sec: u64,
+        /// Longest the stream may run, in seconds
+        max_duration: u64,
+    },
+
+    /// Pay a stream's provider what has streamed so far
+    WithdrawStream { stream: Pubkey },
+
+    /// Cancel one of your streams, reclaiming the unstreamed remainder
+    CancelStream { stream: Pubkey },
+
+    /// Show a payment stream
+    ShowStream { stream: Pubkey },
+
+    /// Top up a share (bps) of creator fees into one of your agents' x402
+    /// treasury (0 = stop)
+    TopUp { agent: Pubkey, bps: u16 },
+
+    /// Sweep an agent's pending top-up into its x402 treasury
+    CollectTopUp { agent: Pubkey },
+
+    /// Give notice (seconds) before minimum price increases on one of your
+    /// agents take effect (0 = immediately)
+    PriceNotice { agent: Pubkey, secs: i64 },
+
+    /// Propose a price for a target agent's service, paid by one of your
+    /// agents once the target's creator accepts
+    Propose {
+        caller_agent: Pubkey,
+        target_agent: Pubkey,
+        amount: u64,
+        /// Service name, hashed into its service ID
+        #[arg(long, value_parser = parse_service_id)]
+        service_id: [u8; 32],
+        /// Seconds until the terms expire (at most 24 hours)
+        #[arg(long, default_value_t = 3600)]
+        ttl: i64,
+    },
+
+    /// Accept terms proposed to one of your agents at the proposed amount
+    AcceptTerms { agreement: Pubkey, amount: u64 },
+
+    /// Close terms you proposed, returning their rent
+    CloseTerms { agreement: Pubkey },
+
+    /// Set the refundable deposit for trial sessions with one of your
+    /// agents (0 = no trials)
+    TrialDeposit { agent: Pubkey, amount: u64 },
+
+    /// Open a trial session of an agent's free tier, escrowing its deposit
+    Trial {
+        agent: Pubkey,
+        /// Service name, hashed into its service ID
+        #[arg(long, value_parser = parse_service_id)]
+        service_id: [u8; 32],
+        /// Deposit token mint (e.g. USDC)
+        #[arg(long)]
+        mint: Pubkey,
+    },
+
+    /// End your trial session with an agent, refunding the deposit
+    EndTrial { agent: Pubkey },
+
+    /// Forfeit a caller's expired trial session to the agent's recipient
+    ForfeitTrial { agent: Pubkey, caller: Pubkey },
+
+    /// Rate the service behind one of your settled payments (1-5 stars),
+    /// weighted by the amount you paid
+    Rate { payment_record: Pubkey, stars: u8 },
+
+    /// List an agent's payment records, read straight from RPC
+    Payments {
+        agent: Pubkey,
+        /// Only payments made by this wallet
+        #[arg(long)]
+        payer: Option<Pubkey>,
+        /// Only payments in this status: `pending`, `verified`, `settled`
+        /// or `failed`
+        #[arg(long, value_parser = parse_payment_status)]
+        status: Option<PaymentStatus>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config = Config::load(cli.config.as_deref(), cli.url, cli.keypair)?;
+    let client = UrsusClient::new(&config.rpc_url, config.keypair()?);
+
+    match cli.command {
+        Command::Agent(cmd) => run_agent(&client, cmd),
+        Command::X402(cmd) => run_x402(&client, cmd),
+        Command::Admin(cmd) => run_admin(&client, cmd),
+        Command::Insurance(cmd) => run_insurance(&client, cmd),
+        Command::Trending(cmd) => run_trending(&client, cmd),
+        Command::Pol(cmd) => run_pol(&client, cmd),
+        Command::Basket(cmd) => run_basket(&client, cmd),
+        Command::Keeper(cmd) => run_keeper(&client, cmd),
+        Command::Dispute(cmd) => run_dispute(&client, cmd),
+        Command::Job(cmd) => run_job(&client, cmd),
+        Command::Queue(cmd) => run_queue(&client, cmd),
+        Command::Layouts => run_layouts(&client),
+    }
+}
+
+fn run_layouts(client: &UrsusClient) -> Result<()> {
+    for layout in ACCOUNT_LAYOUTS {
+        let deployed = client.get_layout_info(layout.discriminator)?;
+        let hex: String = layout
+            .discriminator
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let status = if deployed.size as usize == layout.size && deployed.version == layout.version
+        {
+            "ok"
+        } else {
+            "mismatch"
+        };
+        println!(
+            "{:<24} {} size {:>6} v{} ({}: size {} v{})",
+            layout.name, hex, layout.size, layout.version, status, deployed.size, deployed.version
+        );
+    }
+    Ok(())
+}
+
+fn run_agent(client: &UrsusClient, cmd: AgentCommand) -> Result<()> {
+    match cmd {
+        AgentCommand::Create {
+            name,
+            symbol,
+            description,
+            instructions,
+            model,
+            tags,
+            fee_in_quote,
+            tranches,
+            lbp_duration_secs,
+            lbp_multiplier,
+            x402_min_payment,
+            x402_max_payment,
+            x402_timeout_secs,
+            dev_buy,
+            slippage_bps,
+            sealed,
+            listing_reserve_bps,
+            listing_release_delay_secs,
+            creator_fee,
+        } => {
+            let fee_mint = if fee_in_quote {
+                Some(client.get_factory()?.quote_mint)
+            } else {
+                None
+            };
+            let args = CreateAgentArgs {
+                name,
+                symbol,
+                description,
+                instructions,
+                model,
+                tags,
+                fee_mint,
+            };
+            let (agent, signature) = if creator_fee != CreatorFeeMode::Creator {
+                client.create_agent_with_fee_mode(args, creator_fee)?
+            } else if sealed {
+                client.create_agent_sealed(args)?
+            } else if let Some(reserve_bps) = listing_reserve_bps {
+                client.create_agent_with_listing_reserve(
+                    args,
+                    reserve_bps,
+                    listing_release_delay_secs,
+                )?
+            } else if x402_min_payment.is_some() || dev_buy.is_some() {
+                let x402 = X402Settings {
+                    enabled: x402_min_payment.is_some(),
+                    min_payment_amount: x402_min_payment.unwrap_or_default(),
+                    max_payment_amount: x402_max_payment,
+                    service_timeout_seconds: x402_timeout_secs,
+                };
+                client.create_agent_full(args, x402, dev_buy.unwrap_or_default(), slippage_bps)?
+            } else if let Some(duration_secs) = lbp_duration_secs {
+                client.create_agent_lbp(args, duration_secs, lbp_multiplier)?
+            } else if tranches.is_empty() {
+                client.create_agent(args)?
+            } else {
+                client.create_agent_with_curve(args, tranches)?
+            };
+            println!("agent: {}", agent);
+            println!("signature: {}", signature);
+        }
+        AgentCommand::CreateBundle {
+            prefix,
+            agents,
+            description,
+            instructions,
+            model,
+            tags,
+        } => {
+            let agents = agents
+                .into_iter()
+                .map(|(name, symbol)| CreateAgentArgs {
+                    name,
+                    symbol,
+                    description: description.clone(),
+                    instructions: instructions.clone(),
+                    model: model.clone(),
+                    tags: tags.clone(),
+                    fee_mint: None,
+                })
+                .collect();
+            for (agents, signature) in client.create_agent_batch(&prefix, agents)? {
+                for agent in agents {
+                    println!("agent: {}", agent);
+                }
+                println!("signature: {}", signature);
+            }
+        }
+        AgentCommand::Buy {
+            agent,
+            lamports,
+            slippage_bps,
+            recipient,
+        } => {
+            let recipient = recipient.unwrap_or_else(|| client.payer());
+            let quote = client.quote_buy(&agent, lamports)?;
+            let signature = client.buy_for(&agent, &recipient, lamports, slippage_bps)?;
+            println!("tokens (quoted): {}", quote.tokens_out);
+            if quote.sol_amount < lamports {
+                println!("partial fill, lamports spent: {}", quote.sol_amount);
+            }
+            println!("signature: {}", signature);
+        }
+        AgentCommand::Sell {
+            agent,
+            tokens,
+            slippage_bps,
+        } => {
+            let quote = client.quote_sell(&agent, tokens)?;
+            let signature = client.sell(&agent, tokens, slippage_bps)?;
+            println!("lamports (quoted): {}", quote.net_sol_out);
+            println!("signature: {}", signature);
+        }
+        AgentCommand::RefundGuarantee { agent, days } => {
+            println!(
+                "signature: {}",
+                client.enable_refund_guarantee(&agent, days)?
+            );
+        }
+        AgentCommand::Redeem { agent, tokens } => {
+            let sol_out = client.quote_redeem(&agent, tokens)?;
+            let signature = client.redeem(&agent, tokens)?;
+            println!("lamports: {}", sol_out);
+            println!("signature: {}", signature);
+        }
+        AgentCommand::Lock {
+            agent,
+            tokens,
+            unlock_ts,
+            id,
+        } => {
+            let (lock, signature) = client.lock_tokens(&agent, id, tokens, unlock_ts)?;
+            println!("lock: {}", lock);
+            println!("signature: {}", signature);
+        }
+        AgentCommand::Unlock { agent, id } => {
+            println!("signature: {}", client.unlock_tokens(&agent, id)?);
+        }
+        AgentCommand::ReleaseListingReserve {
+            agent,
+            tokens,
+            destination,
+        } => {
+            let signature = client.release_listing_reserve(&agent, &destination, tokens)?;
+            println!("signature: {}", signature);
+        }
+        AgentCommand::Snapshot { agent } => {
+            let (snapshot, signature) = client.create_snapshot(&agent)?;
+            println!("snapshot: {}", snapshot);
+            println!("signature: {}", signature);
+        }
+        AgentCommand::InitPriceHistory { agent } => {
+            let (price_history, signature) = client.initialize_price_history(&agent)?;
+            println!("price history: {}", price_history);
+            println!("signature: {}", signature);
+        }
+        AgentCommand::Twap {
+            agent,
+            window_slots,
+        } => match client.get_twap(&agent, window_slots)? {
+            Some(twap) => println!("twap: {}", twap),
+            None => println!("not enough price history for {} slots", window_slots),
+        },
+        AgentCommand::Price { agent } => {
+            let view = client.view_price(&agent)?;
+            println!("price (nano-lamports/token): {}", view.price);
+            println!("market cap (lamports): {}", view.market_cap);
+            println!("virtual sol: {}", view.reserves.virtual_sol);
+            println!("virtual tokens: {}", view.reserves.virtual_token);
+            println!("real sol: {}", view.reserves.real_sol);
+            println!("real tokens: {}", view.reserves.real_token);
+            println!("graduated: {}", view.is_graduated);
+        }
+        AgentCommand::Manifest { agent, mints } => {
+            let manifest = client.get_agent_manifest(&agent, &mints)?;
+            let capabilities: Vec<&str> = [
+                (CAPABILITY_X402, "x402"),
+                (CAPABILITY_ESCROW, "escrow"),
+                (CAPABILITY_METERED, "metered"),
+                (CAPABILITY_WEBHOOK, "webhook"),
+                (CAPABILITY_AFFILIATES, "affiliates"),
+                (CAPABILITY_REVENUE_SPLIT, "revenue-split"),
+                (CAPABILITY_CHARGEBACKS, "chargebacks"),
+                (CAPABILITY_PAYS_AGENTS, "pays-agents"),
+                (CAPABILITY_GRADUATED, "graduated"),
+                (CAPABILITY_TRIALS, "trials"),
+            ]
+            .into_iter()
+            .filter(|(flag, _)| manifest.capabilities & flag != 0)
+            .map(|(_, name)| name)
+            .collect();
+            println!("mint: {}", manifest.mint);
+            println!("capabilities: {}", capabilities.join(", "));
+            println!("creator: {}", manifest.creator);
+            println!("payment recipient: {}", manifest.payment_recipient);
+            println!("attestation verifier: {}", manifest.attestation_verifier);
+            println!("webhook signer: {}", manifest.webhook_signer);
+            println!("min payment: {}", manifest.min_payment_amount);
+            println!("max payment: {}", manifest.max_payment_amount);
+            println!(
+                "unit price: {} ({:?})",
+                manifest.unit_price, manifest.meter_unit
+            );
+            println!("service timeout (s): {}", manifest.service_timeout_seconds);
+            if manifest.trial_deposit > 0 {
+                println!("trial deposit: {}", manifest.trial_deposit);
+            }
+            if manifest.pending_min_payment_at > 0 {
+                println!(
+                    "min payment from {}: {}",
+                    manifest.pending_min_payment_at, manifest.pending_min_payment_amount
+                );
+            }
+            for service in &manifest.deprecated_services {
+                let id: String = service
+                    .service_id
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+                println!("deprecated service {}: sunset {}", id, service.sunset_ts);
+            }
+            for mint in &manifest.accepted_mints {
+                println!("accepts: {}", mint);
+            }
+        }
+        AgentCommand::Reputation { agent } => {
+            let reputation = client.get_agent_reputation(&agent)?;
+            println!(
+                "rating (bps): {} over {} ratings, {} paid",
+                reputation.rating_bps(),
+                reputation.rating_count,
+                reputation.rated_amount
+            );
+            println!("imported reputation (bps): {}", reputation.score_bps());
+            for attestation in &reputation.attestations {
+                println!(
+                    "{}: score {} bps, weight {} bps, by {} at {}",
+                    String::from_utf8_lossy(&attestation.source).trim_end_matches('\0'),
+                    attestation.score_bps,
+                    attestation.weight_bps,
+                    attestation.attestor,
+                    attestation.attested_at
+                );
+            }
+        }
+        AgentCommand::Graduate { agent, dex_program } => {
+            let signature = client.graduate(&agent, &dex_program)?;
+            println!("signature: {}", signature);
+        }
+        AgentCommand::GraduateBatch { agents } => {
+            let signature = client.graduate_batch(&agents)?;
+            println!("signature: {}", signature);
+        }
+        AgentCommand::ClaimOwnership { agent } => {
+            println!("signature: {}", client.claim_agent_ownership(&agent)?);
+        }
+        AgentCommand::TransferOwnership { agent, new_creator } => {
+            println!(
+                "signature: {}",
+                client.transfer_agent_ownership(&agent, &new_creator)?
+            );
+        }
+        AgentCommand::SetRoyalty { agent, royalty_bps } => {
+            println!(
+                "signature: {}",
+                client.set_creator_royalty(&agent, royalty_bps)?
+            );
+        }
+        AgentCommand::ClaimRoyalties { agent } => {
+            println!("signature: {}", client.claim_royalties(&agent)?);
+        }
+        AgentCommand::SameSlotProtection { agent, disabled } => {
+            println!(
+                "signature: {}",
+                client.set_same_slot_protection(&agent, !disabled)?
+            );
+        }
+        AgentCommand::SetModel { agent, model } => {
+            println!("signature: {}", client.set_agent_model(&agent, &model)?);
+        }
+        AgentCommand::ClaimFees { agent } => {
+            println!("signature: {}", client.claim_creator_fees(&agent)?);
+        }
+        AgentCommand::MaxBuy { agent, max_buy_bps } => {
+            println!("signature: {}", client.set_max_buy(&agent, max_buy_bps)?);
+        }
+        AgentCommand::GraduationBurn { agent, disabled } => {
+            println!(
+                "signature: {}",
+                client.set_graduation_burn(&agent, !disabled)?
+            );
+        }
+        AgentCommand::Rename {
+            agent,
+            name,
+            symbol,
+        } => {
+            println!(
+                "signature: {}",
+                client.rename_agent(&agent, &name, &symbol)?
+            );
+        }
+        AgentCommand::AddTag { agent, tag } => {
+            println!("signature: {}", client.add_agent_tag(&agent, &tag)?);
+        }
+        AgentCommand::RemoveTag { agent, tag } => {
+            println!("signature: {}", client.remove_agent_tag(&agent, &tag)?);
+        }
+        AgentCommand::DepositRevenue {
+            agent,
+            amount,
+            source,
+            treasury,
+            mint,
+        } => {
+            let pool = if treasury {
+                RevenuePool::Treasury
+            } else {
+                RevenuePool::Stakers
+            };
+            println!(
+                "signature: {}",
+                client.deposit_revenue(&agent, pool, amount, source, mint.as_ref())?
+            );
+        }
+        AgentCommand::Dca(cmd) => run_dca(client, cmd)?,
+        AgentCommand::EarlyBuyers(cmd) => run_early_buyers(client, cmd)?,
+        AgentCommand::Mining(cmd) => run_mining(client, cmd)?,
+        AgentCommand::Migration(cmd) => run_migration(client, cmd)?,
+        AgentCommand::Presale(cmd) => run_presale(client, cmd)?,
+        AgentCommand::Staking(cmd) => run_staking(client, cmd)?,
+        AgentCommand::Governance(cmd) => run_governance(client, cmd)?,
+    }
+    Ok(())
+}
+
+fn run_dca(client: &UrsusClient, cmd: DcaCommand) -> Result<()> {
+    let signature = match cmd {
+        DcaCommand::Create {
+            agent,
+            lamports,
+            interval_secs,
+            intervals,
+            min_tokens,
+        } => client.create_dca(&agent, lamports, interval_secs, intervals, min_tokens)?,
+        DcaCommand::Execute { agent, owner } => {
+            client.execute_dca(&agent, &owner.unwrap_or_else(|| client.payer()))?
+        }
+        DcaCommand::Cancel { agent } => client.cancel_dca(&agent)?,
+    };
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+fn run_early_buyers(client: &UrsusClient, cmd: EarlyBuyersCommand) -> Result<()> {
+    match cmd {
+        EarlyBuyersCommand::Create {
+            agent,
+            bonus,
+            max_buyers,
+            deadline_ts,
+        } => {
+            let (rewards, signature) =
+                client.create_early_buyer_rewards(&agent, max_buyers, bonus, deadline_ts)?;
+            println!("rewards: {}", rewards);
+            println!("signature: {}", signature);
+        }
+        EarlyBuyersCommand::Claim { agent } => {
+            println!("signature: {}", client.claim_early_buyer_bonus(&agent)?);
+        }
+        EarlyBuyersCommand::Close { agent } => {
+            println!("signature: {}", client.close_early_buyer_rewards(&agent)?);
+        }
+    }
+    Ok(())
+}
+
+fn run_mining(client: &UrsusClient, cmd: MiningCommand) -> Result<()> {
+    let signature = match cmd {
+        MiningCommand::Create { agent, lp_mint } => {
+            let (mining, signature) = client.create_liquidity_mining(&agent, &lp_mint)?;
+            println!("mining: {}", mining);
+            signature
+        }
+        MiningCommand::Fund {
+            agent,
+            amount,
+            duration_secs,
+        } => client.fund_liquidity_mining(&agent, amount, duration_secs)?,
+        MiningCommand::Stake { agent, amount } => client.stake_lp(&agent, amount)?,
+        MiningCommand::Withdraw { agent, amount } => client.withdraw_lp(&agent, amount)?,
+        MiningCommand::Claim { agent } => client.claim_lp_rewards(&agent)?,
+    };
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+fn run_migration(client: &UrsusClient, cmd: MigrationCommand) -> Result<()> {
+    let signature = match cmd {
+        MigrationCommand::Create {
+            agent,
+            old_mint,
+            deposit,
+            numerator,
+            denominator,
+        } => {
+            let (migration, signature) =
+                client.create_migration(&agent, &old_mint, numerator, denominator, deposit)?;
+            println!("migration: {}", migration);
+            signature
+        }
+        MigrationCommand::Migrate { agent, amount } => client.migrate_tokens(&agent, amount)?,
+    };
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+fn run_presale(client: &UrsusClient, cmd: PresaleCommand) -> Result<()> {
+    let signature = match cmd {
+        PresaleCommand::Create {
+            agent,
+            start_ts,
+            end_ts,
+            soft_cap,
+            hard_cap,
+        } => client.create_presale(&agent, start_ts, end_ts, soft_cap, hard_cap)?,
+        PresaleCommand::Commit { agent, lamports } => client.commit_sol(&agent, lamports)?,
+        PresaleCommand::Finalize { agent } => client.finalize_presale(&agent)?,
+        PresaleCommand::Claim { agent } => client.claim_presale(&agent)?,
+        PresaleCommand::Refund { agent } => client.refund_commitment(&agent)?,
+    };
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+fn run_staking(client: &UrsusClient, cmd: StakingCommand) -> Result<()> {
+    let signature = match cmd {
+        StakingCommand::Create {
+            agent,
+            fee_bps,
+            emission_rate_cap,
+            update,
+        } => {
+            if update {
+                client.update_staking_pool(&agent, fee_bps, emission_rate_cap)?
+            } else {
+                client.create_staking_pool(&agent, fee_bps, emission_rate_cap)?
+            }
+        }
+        StakingCommand::Stake { agent, tokens } => client.stake(&agent, tokens)?,
+        StakingCommand::Lock { agent, unlock_ts } => client.lock_stake(&agent, unlock_ts)?,
+        StakingCommand::Unstake { agent, tokens } => client.unstake(&agent, tokens)?,
+        StakingCommand::Claim { agent } => client.claim_rewards(&agent)?,
+        StakingCommand::Fund { agent, lamports } => client.fund_staking_pool(&agent, lamports)?,
+    };
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+fn run_governance(client: &UrsusClient, cmd: GovernanceCommand) -> Result<()> {
+    let (agent, action) = match cmd {
+        GovernanceCommand::Create { agent, settings } => {
+            let signature = client.create_governance(
+                &agent,
+                settings.quorum_bps,
+                settings.threshold_bps,
+                settings.voting_period_secs,
+                settings.proposal_threshold,
+            )?;
+            println!("signature: {}", signature);
+            return Ok(());
+        }
+        GovernanceCommand::CreateTreasury { agent, fee_bps } => {
+            println!(
+                "signature: {}",
+                client.create_dao_treasury(&agent, fee_bps)?
+            );
+            return Ok(());
+        }
+        GovernanceCommand::CollectTreasuryFees { agent } => {
+            println!("signature: {}", client.collect_treasury_fees(&agent)?);
+            return Ok(());
+        }
+        GovernanceCommand::Vote {
+            agent,
+            proposal_id,
+            against,
+        } => {
+            println!(
+                "signature: {}",
+                client.cast_vote(&agent, proposal_id, !against)?
+            );
+            return Ok(());
+        }
+        GovernanceCommand::Execute { agent, proposal_id } => {
+            println!(
+                "signature: {}",
+                client.execute_proposal(&agent, proposal_id)?
+            );
+            return Ok(());
+        }
+        GovernanceCommand::ProposeStakingFee { agent, fee_bps } => (
+            agent,
+            ProposalAction::SetStakingFee {
+                staking_fee_bps: fee_bps,
+            },
+        ),
+        GovernanceCommand::ProposeServicePrices {
+            agent,
+            min_payment,
+            max_payment,
+        } => (
+            agent,
+            ProposalAction::SetServicePrices {
+                min_payment_amount: min_payment,
+                max_payment_amount: max_payment,
+            },
+        ),
+        GovernanceCommand::ProposeMetadata {
+            agent,
+            description,
+            tags,
+        } => (agent, ProposalAction::UpdateMetadata { description, tags }),
+        GovernanceCommand::ProposeTreasuryFee { agent, fee_bps } => (
+            agent,
+            ProposalAction::SetTreasuryFee {
+                treasury_fee_bps: fee_bps,
+            },
+        ),
+        GovernanceCommand::ProposeTransferSol {
+            agent,
+            recipient,
+            lamports,
+        } => (
+            agent,
+            ProposalAction::TransferSol {
+                recipient,
+                amount: lamports,
+            },
+        ),
+        GovernanceCommand::ProposeTransferToken {
+            agent,
+            mint,
+            recipient,
+            amount,
+        } => (
+            agent,
+            ProposalAction::TransferToken {
+                mint,
+                recipient,
+                amount,
+            },
+        ),
+        GovernanceCommand::ProposeGovernance { agent, settings } => (
+            agent,
+            ProposalAction::UpdateGovernance {
+                quorum_bps: settings.quorum_bps,
+                threshold_bps: settings.threshold_bps,
+                voting_period_secs: settings.voting_period_secs,
+                proposal_threshold: settings.proposal_threshold,
+            },
+        ),
+    };
+
+    let (proposal_id, signature) = client.create_proposal(&agent, action)?;
+    println!("proposal: {}", proposal_id);
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+fn run_admin(client: &UrsusClient, cmd: AdminCommand) -> Result<()> {
+    let action = match cmd {
+        AdminCommand::QueueCreationFee { lamports } => {
+            AdminAction::SetCreationFee { new_fee: lamports }
+        }
+        AdminCommand::QueueCreatorFeeVesting { secs } => {
+            AdminAction::SetCreatorFeeVesting { vesting_secs: secs }
+        }
+        AdminCommand::QueueCreationSurge {
+            bps,
+            half_life_secs,
+        } => AdminAction::SetCreationSurge {
+            surge_bps: bps,
+            half_life_secs,
+        },
+        AdminCommand::QueueRenameFee { lamports } => {
+            AdminAction::SetRenameFee { new_fee: lamports }
+        }
+        AdminCommand::QueueQuoteCreationFee { quote_mint, amount } => {
+            AdminAction::SetQuoteCreationFee {
+                quote_mint,
+                fee: amount,
+            }
+        }
+        AdminCommand::QueuePlatformTreasury { treasury } => AdminAction::SetPlatformTreasury {
+            new_treasury: treasury,
+        },
+        AdminCommand::QueueTimelockDelay { secs } => {
+            AdminAction::SetTimelockDelay { delay_secs: secs }
+        }
+        AdminCommand::QueueAuthority { authority, program } => AdminAction::SetAuthority {
+            new_authority: authority,
+            admin_is_program: program,
+        },
+        AdminCommand::QueueInsuranceFee { bps } => AdminAction::SetInsuranceFee {
+            insurance_fee_bps: bps,
+        },
+        AdminCommand::QueuePublicGoodsFee { recipient, bps } => AdminAction::SetPublicGoodsFee {
+            recipient,
+            fee_bps: bps,
+        },
+        AdminCommand::CollectPublicGoods { agent } => {
+            println!("signature: {}", client.collect_public_goods_fees(&agent)?);
+            return Ok(());
+        }
+        AdminCommand::QueueProtocolLiquidity { bps } => AdminAction::SetProtocolLiquidity {
+            protocol_liquidity_bps: bps,
+        },
+        AdminCommand::QueueFeeDiscounts {
+            platform_mint,
+            staking_pool,
+            tiers,
+        } => {
+            anyhow::ensure!(
+                tiers.len() <= FEE_DISCOUNT_TIERS,
+                "at most {} tiers",
+                FEE_DISCOUNT_TIERS
+            );
+            let mut padded = [FeeDiscountTier::default(); FEE_DISCOUNT_TIERS];
+            padded[..tiers.len()].copy_from_slice(&tiers);
+            AdminAction::SetFeeDiscounts {
+                platform_mint: platform_mint.unwrap_or_default(),
+                staking_pool: staking_pool.unwrap_or_default(),
+                tiers: padded,
+            }
+        }
+        AdminCommand::QueueArbitrationCouncil {
+            arbiters,
+            threshold,
+        } => {
+            anyhow::ensure!(
+                arbiters.len() <= MAX_ARBITERS,
+                "at most {} arbiters",
+                MAX_ARBITERS
+            );
+            let mut seats = [Pubkey::default(); MAX_ARBITERS];
+            seats[..arbiters.len()].copy_from_slice(&arbiters);
+            AdminAction::SetArbitrationCouncil {
+                arbiters: seats,
+                threshold,
+            }
+        }
+        AdminCommand::QueueAllowedModels { models } => {
+            anyhow::ensure!(
+                models.len() <= MAX_ALLOWED_MODELS,
+                "at most {} models",
+                MAX_ALLOWED_MODELS
+            );
+            let mut allowed = [[0; MODEL_ID_LEN]; MAX_ALLOWED_MODELS];
+            for (entry, model) in allowed.iter_mut().zip(&models) {
+                *entry = AgentFactory::model_id(model)
+                    .ok_or_else(|| anyhow::anyhow!("invalid model identifier: {}", model))?;
+            }
+            AdminAction::SetAllowedModels { models: allowed }
+        }
+        AdminCommand::QueueReputationAttestor { attestor } => AdminAction::SetReputationAttestor {
+            attestor: attestor.unwrap_or_default(),
+        },
+        AdminCommand::AttestReputation {
+            agent,
+            source,
+            score_bps,
+            weight_bps,
+            evidence,
+        } => {
+            let signature =
+                client.attest_reputation(&agent, source, score_bps, weight_bps, evidence)?;
+            println!("signature: {}", signature);
+            println!(
+                "reputation (bps): {}",
+                client.get_agent_reputation(&agent)?.score_bps()
+            );
+            return Ok(());
+        }
+        AdminCommand::CreatorStanding {
+            creator,
+            rugged,
+            blacklisted,
+        } => {
+            let signature = client.set_creator_standing(&creator, rugged, blacklisted)?;
+            println!("signature: {}", signature);
+            return Ok(());
+        }
+        AdminCommand::ReserveName { name, owner } => {
+            println!("signature: {}", client.reserve_name(&name, &owner)?);
+            return Ok(());
+        }
+        AdminCommand::ReleaseName { name } => {
+            println!(
+                "signature: {}",
+                client.reserve_name(&name, &Pubkey::default())?
+            );
+            return Ok(());
+        }
+        AdminCommand::CreationRateLimit {
+            max_creations,
+            window_secs,
+        } => {
+            let signature = client.set_creation_rate_limit(max_creations, window_secs)?;
+            println!("signature: {}", signature);
+            return Ok(());
+        }
+        AdminCommand::QuoteLamportRate { lamports } => {
+            println!("signature: {}", client.set_quote_lamport_rate(lamports)?);
+            return Ok(());
+        }
+        AdminCommand::FeeOverride {
+            agent,
+            platform_fee_bps,
+            creator_fee_bps,
+        } => {
+            println!(
+                "signature: {}",
+                client.set_agent_fee_override(&agent, platform_fee_bps, creator_fee_bps)?
+            );
+            return Ok(());
+        }
+        AdminCommand::FreezeX402 { agent } => {
+            println!("signature: {}", client.freeze_x402(&agent)?);
+            return Ok(());
+        }
+        AdminCommand::ThawX402 { agent } => {
+            println!("signature: {}", client.thaw_x402(&agent)?);
+            return Ok(());
+        }
+        AdminCommand::Execute { action_id } => {
+            println!("signature: {}", client.execute_admin_action(action_id)?);
+            return Ok(());
+        }
+        AdminCommand::Cancel { action_id } => {
+            println!("signature: {}", client.cancel_admin_action(action_id)?);
+            return Ok(());
+        }
+    };
+
+    let (action_id, signature) = client.queue_admin_action(action)?;
+    let queued = client.get_admin_action(action_id)?;
+    println!("action: {}", action_id);
+    println!("executable at: {}", queued.eta);
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+fn run_insurance(client: &UrsusClient, cmd: InsuranceCommand) -> Result<()> {
+    let signature = match cmd {
+        InsuranceCommand::Init => client.initialize_insurance_fund()?,
+        InsuranceCommand::Collect { agent } => client.collect_insurance_fees(&agent)?,
+        InsuranceCommand::Fund { lamports } => client.fund_insurance(lamports)?,
+        InsuranceCommand::PayClaim {
+            agent,
+            recipient,
+            lamports,
+        } => client.pay_claim(&agent, &recipient, lamports)?,
+    };
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+fn run_pol(client: &UrsusClient, cmd: PolCommand) -> Result<()> {
+    let signature = match cmd {
+        PolCommand::Init => client.initialize_protocol_liquidity()?,
+        PolCommand::Withdraw {
+            recipient,
+            lamports,
+            tokens,
+            mint,
+        } => client.withdraw_protocol_liquidity(&recipient, lamports, tokens, mint.as_ref())?,
+    };
+    println!("signature: {}", signature);
+    Ok(())
+}
+
+fn run_basket(client: &UrsusClient, cmd: BasketCommand) -> Result<()> {
+    match cmd {
+        BasketCommand::Create {
+            id,
+            name,
+            constituents,
+        } => {
+            let (agents, units): (Vec<Pubkey>, Vec<u64>) = constituents.into_iter().unzip();
+            let (basket, signature) = client.create_index_basket(id, &name, &agents, units)?;
+            println!("basket: {}", basket);
+            println!("signature: {}", signature);
+        }
+        BasketCommand::Mint { basket, amount } => {
+            println!("signature: {}", client.mint_basket(&basket, amount)?);
+        }
+        BasketCommand::Redeem { basket, amount } => {
+            println!("signature: {}", client.redeem_basket(&basket, amount)?);
+        }
+        BasketCommand::Show { basket } => {
+            let basket = client.get_index_basket(&basket)?;
+            println!("name: {}", basket.name);
+            println!("basket mint: {}", basket.basket_mint);
+            for constituent in &basket.constituents {
+                println!("{} {}", constituent.agent, constituent.units);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_dispute(client: &UrsusClient, cmd: DisputeCommand) -> Result<()> {
+    match cmd {
+        DisputeCommand::Bond { agent, amount } => {
+            println!(
+                "signature: {}",
+                client.deposit_provider_bond(&agent, amount)?
+            );
+        }
+        DisputeCommand::Unbond { agent, amount } => {
+            println!(
+                "signature: {}",
+                client.withdraw_provider_bond(&agent, amount)?
+            );
+        }
+        DisputeCommand::Submit {
+            payment_record,
+            evidence,
+            claim,
+        } => {
+            println!(
+                "signature: {}",
+                client.submit_evidence(&payment_record, evidence, claim)?
+            );
+        }
+        DisputeCommand::Vote {
+            payment_record,
+            amount,
+        } => {
+            println!(
+                "signature: {}",
+                client.cast_arbiter_vote(&payment_record, amount)?
+            );
+        }
+        DisputeCommand::ClaimInsurance { payment_record } => {
+            println!("signature: {}", client.pay_insured_claim(&payment_record)?);
+        }
+        DisputeCommand::Show { payment_record } => {
+            let dispute = client.get_dispute(&payment_record)?;
+            println!("agent: {}", dispute.agent);
+            println!("claimant: {}", dispute.claimant);
+            println!("status: {:?}", dispute.status);
+            println!("claimed: {}", dispute.claimed_amount);
+            println!("awarded: {}", dispute.awarded_amount);
+            println!("slashed: {}", dispute.slashed_amount);
+            for vote in &dispute.votes {
+                println!("vote: {} -> {}", vote.arbiter, vote.slash_amount);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_job(client: &UrsusClient, cmd: JobCommand) -> Result<()> {
+    match cmd {
+        JobCommand::Create {
+            agent,
+            mint,
+            milestones,
+            service_id,
+            duration,
+        } => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs() as i64;
+            let (job, signature) =
+                client.create_job(&agent, &mint, milestones, service_id, now + duration)?;
+            println!("job: {}", job);
+            println!("signature: {}", signature);
+        }
+        JobCommand::Submit {
+            job,
+            index,
+            deliverable,
+        } => {
+            println!(
+                "signature: {}",
+                client.submit_milestone(&job, index, deliverable)?
+            );
+        }
+        JobCommand::Release { job, index } => {
+            println!("signature: {}", client.release_milestone(&job, index)?);
+        }
+        JobCommand::Dispute {
+            job,
+            index,
+            evidence,
+        } => {
+            println!(
+                "signature: {}",
+                client.dispute_milestone(&job, index, evidence)?
+            );
+        }
+        JobCommand::Vote {
+            job,
+            index,
+            refund_bps,
+        } => {
+            println!(
+                "signature: {}",
+                client.vote_milestone_dispute(&job, index, refund_bps)?
+            );
+        }
+        JobCommand::Cancel { job } => {
+            println!("signature: {}", client.cancel_job(&job)?);
+        }
+        JobCommand::Close { job } => {
+            println!("signature: {}", client.close_job(&job)?);
+        }
+        JobCommand::Show { job } => {
+            let job = client.get_job(&job)?;
+            println!("agent: {}", job.agent);
+            println!("payer: {}", job.payer);
+            println!("deadline: {}", job.deadline);
+            println!("review period: {}s", job.review_period_secs);
+            println!("released: {}", job.released);
+            println!("refunded: {}", job.refunded);
+            for (index, milestone) in job.milestones.iter().enumerate() {
+                println!(
+                    "milestone {}: {} {:?} (refunded {})",
+                    index, milestone.amount, milestone.status, milestone.refunded
+                );
+            }
+            for vote in &job.dispute_votes {
+                println!("vote: {} -> {} bps", vote.arbiter, vote.slash_amount);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_queue(client: &UrsusClient, cmd: QueueCommand) -> Result<()> {
+    match cmd {
+        QueueCommand::Workers { agent, workers } => {
+            println!("signature: {}", client.set_queue_workers(&agent, workers)?);
+        }
+        QueueCommand::Enqueue {
+            agent,
+            mint,
+            max_price,
+            params,
+        } => {
+            let (task, signature) = client.enqueue_task(&agent, &mint, params, max_price)?;
+            println!("task: {}", task);
+            println!("signature: {}", signature);
+        }
+        QueueCommand::Claim { task } => {
+            println!("signature: {}", client.claim_task(&task)?);
+        }
+        QueueCommand::Complete {
+            task,
+            price,
+            result,
+        } => {
+            println!("signature: {}", client.complete_task(&task, price, result)?);
+        }
+        QueueCommand::Withdraw { task } => {
+            println!("signature: {}", client.withdraw_task(&task)?);
+        }
+        QueueCommand::Show { agent } => {
+            let queue = client.get_work_queue(&agent)?;
+            println!("next task id: {}", queue.next_task_id);
+            println!("open tasks: {}", queue.open_tasks);
+            for worker in &queue.workers {
+                println!("worker: {}", worker);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_keeper(client: &UrsusClient, cmd: KeeperCommand) -> Result<()> {
+    match cmd {
+        KeeperCommand::Register {
+            id,
+            kind,
+            target,
+            tip,
+            deposit,
+        } => {
+            let (task, signature) = client.register_keeper_task(id, kind, &target, tip, deposit)?;
+            println!("task: {}", task);
+            println!("signature: {}", signature);
+        }
+        KeeperCommand::Fund { task, amount } => {
+            println!("signature: {}", client.fund_keeper_task(&task, amount)?);
+        }
+        KeeperCommand::Crank { task } => {
+            println!("signature: {}", client.crank_keeper_task(&task)?);
+        }
+        KeeperCommand::Cancel { task } => {
+            println!("signature: {}", client.cancel_keeper_task(&task)?);
+        }
+        KeeperCommand::List => {
+            for (address, task) in client.get_keeper_tasks()? {
+                println!(
+                    "{} {:?} {} tip {} paid {}",
+                    address, task.kind, task.target, task.tip_lamports, task.total_tips_paid
+                );
+            }
+        }
+        KeeperCommand::Run { interval_secs } => loop {
+            // Cranks without work due fail in simulation, so trying every
+            // task costs no fees
+            for (address, _) in client.get_keeper_tasks()? {
+                if let Ok(signature) = client.crank_keeper_task(&address) {
+                    println!("{} cranked: {}", address, signature);
+                }
+            }
+            std::thread::sleep(std::time::Duration::from_secs(interval_secs));
+        },
+    }
+    Ok(())
+}
+
+fn run_trending(client: &UrsusClient, cmd: TrendingCommand) -> Result<()> {
+    match cmd {
+        TrendingCommand::Init => {
+            let (trending, signature) = client.initialize_trending()?;
+            println!("trending: {}", trending);
+            println!("signature: {}", signature);
+        }
+        TrendingCommand::Show => {
+            let trending = client.get_trending()?;
+            let elapsed = client.rpc().get_slot()?.saturating_sub(trending.slot);
+            for (rank, entry) in trending.entries.iter().enumerate() {
+                println!(
+                    "{:>2}. {} {}",
+                    rank + 1,
+                    entry.agent,
+                    decay_trending_score(entry.score, elapsed)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_x402(client: &UrsusClient, cmd: X402Command) -> Result<()> {
+    match cmd {
+        X402Command::Configure {
+            agent,
+            settings,
+            update,
+        } => {
+            let signature = if update {
+                client.update_x402(&agent, (&settings).into())?
+            } else {
+                client.configure_x402(&agent, (&settings).into())?
+            };
+            println!("signature: {}", signature);
+        }
+        X402Command::Pay {
+            agent,
+            amount,
+            service_id,
+            mint,
+            escrowed,
+            insure,
+            affiliate,
+            request_id,
+            priority_tip,
+        } => {
+            let (record, signature) = if escrowed {
+                client.pay_for_service_escrowed(
+                    &agent, &mint, amount, service_id, request_id, insure,
+                )?
+            } else {
+                let x402_config = client.get_x402_config(&agent)?;
+                client.pay_for_service(
+                    &agent,
+                    &get_associated_token_address(&client.payer(), &mint),
+                    &get_associated_token_address(&x402_config.payment_recipient, &mint),
+                    amount,
+                    service_id,
+                    request_id,
+                    priority_tip,
+                    insure,
+                    affiliate.as_ref(),
+                )?
+            };
+            println!("payment record: {}", record);
+            println!("signature: {}", signature);
+        }
+        X402Command::Verifier { agent, verifier } => {
+            println!(
+                "signature: {}",
+                client.set_x402_verifier(&agent, &verifier)?
+            );
+        }
+        X402Command::RotateRecipient {
+            agent,
+            new_recipient,
+            grace_secs,
+        } => {
+            println!(
+                "signature: {}",
+                client.rotate_x402_recipient(&agent, &new_recipient, grace_secs)?
+            );
+        }
+        X402Command::ChargebackWindow { agent, secs } => {
+            println!("signature: {}", client.set_chargeback_window(&agent, secs)?);
+        }
+        X402Command::Deprecate {
+            agent,
+            service_id,
+            sunset_ts,
+        } => {
+            println!(
+                "signature: {}",
+                client.deprecate_service(&agent, service_id, sunset_ts)?
+            );
+        }
+        X402Command::Finalize { payment_record } => {
+            println!("signature: {}", client.finalize_payment(&payment_record)?);
+        }
+        X402Command::RevenueSplit { agent, workers } => {
+            println!("signature: {}", client.set_revenue_split(&agent, &workers)?);
+        }
+        X402Command::Attest {
+            payment_record,
+            failed,
+            refund_bps,
+            hash,
+        } => {
+            let signature = match refund_bps {
+                Some(refund_bps) => {
+                    client.settle_attested_payment_partial(&payment_record, refund_bps, hash)?
+                }
+                None => client.settle_attested_payment(&payment_record, !failed, hash)?,
+            };
+            println!("signature: {}", signature);
+        }
+        X402Command::Refund { payment_record } => {
+            println!(
+                "signature: {}",
+                client.refund_expired_payment(&payment_record)?
+            );
+        }
+        X402Command::Metering {
+            agent,
+            unit_price,
+            compute_units,
+        } => {
+            let meter_unit = if compute_units {
+                MeterUnit::ComputeUnits
+            } else {
+                MeterUnit::ThousandTokens
+            };
+            println!(
+                "signature: {}",
+                client.set_x402_metering(&agent, meter_unit, unit_price)?
+            );
+        }
+        X402Command::Quote {
+            agent,
+            payer,
+            amount,
+            service_id,
+            ttl,
+        } => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs() as i64;
+            let (quote, signature) =
+                client.post_quote(&agent, &payer, service_id, amount, now + ttl)?;
+            println!("quote: {}", quote);
+            println!("signature: {}", signature);
+        }
+        X402Command::PayQuote {
+            agent,
+            service_id,
+            mint,
+            insure,
+            affiliate,
+            request_id,
+            priority_tip,
+        } => {
+            let x402_config = client.get_x402_config(&agent)?;
+            let (record, signature) = client.pay_quoted_service(
+                &agent,
+                &get_associated_token_address(&client.payer(), &mint),
+                &get_associated_token_address(&x402_config.payment_recipient, &mint),
+                service_id,
+                request_id,
+                priority_tip,
+                insure,
+                affiliate.as_ref(),
+            )?;
+            println!("payment record: {}", record);
+            println!("signature: {}", signature);
+        }
+        X402Command::Affiliate { fee_bps } => {
+            println!("signature: {}", client.register_affiliate(fee_bps)?);
+        }
+        X402Command::Webhook { agent, url, signer } => {
+            let webhook = url.as_deref().zip(signer.as_ref());
+            println!("signature: {}", client.register_webhook(&agent, webhook)?);
+        }
+        X402Command::AffiliateCap { agent, max_bps } => {
+            println!("signature: {}", client.set_affiliate_cap(&agent, max_bps)?);
+        }
+        X402Command::CloseQuote { quote } => {
+            println!("signature: {}", client.close_quote(&quote)?);
+        }
+        X402Command::SettleUsage {
+            payment_record,
+            units,
+            hash,
+        } => {
+            println!(
+                "signature: {}",
+                client.settle_metered_payment(&payment_record, units, hash)?
+            );
+        }
+        X402Command::OpenReport { agent } => {
+            println!(
+                "signature: {}",
+                client.create_consumer_stats(agent.as_ref())?
+            );
+        }
+        X402Command::Report { consumer } => {
+            let stats = client.get_consumer_stats(&consumer)?;
+            println!("total spent: {}", stats.total_spent);
+            println!("total calls: {}", stats.total_calls);
+            println!("last payment at: {}", stats.last_payment_at);
+            let ledger_hash: String = stats
+                .ledger_hash
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            println!("ledger hash: {}", ledger_hash);
+        }
+        X402Command::Stream {
+            agent,
+            mint,
+            rate_per_sec,
+            max_duration,
+        } => {
+            let (stream, signature) =
+                client.start_stream(&agent, &mint, rate_per_sec, max_duration)?;
+            println!("stream: {}", stream);
+            println!("signature: {}", signature);
+        }
+        X402Command::WithdrawStream { stream } => {
+            println!("signature: {}", client.withdraw_streamed(&stream)?);
+        }
+        X402Command::CancelStream { stream } => {
+            println!("signature: {}", client.cancel_stream(&stream)?);
+        }
+        X402Command::ShowStream { stream } => {
+            let stream = client.get_payment_stream(&stream)?;
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs() as i64;
+            println!("agent: {}", stream.agent);
+            println!("payer: {}", stream.payer);
+            println!("rate per sec: {}", stream.rate_per_sec);
+            println!("ends at: {}", stream.end_ts);
+            println!("deposited: {}", stream.deposited);
+            println!("withdrawn: {}", stream.withdrawn);
+            println!("withdrawable: {}", stream.withdrawable(now));
+            println!("refunded: {}", stream.refunded);
+            println!("cancelled: {}", stream.is_cancelled());
+        }
+        X402Command::TopUp { agent, bps } => {
+            println!("signature: {}", client.set_x402_top_up(&agent, bps)?);
+        }
+        X402Command::CollectTopUp { agent } => {
+            println!("signature: {}", client.collect_x402_top_up(&agent)?);
+        }
+        X402Command::PriceNotice { agent, secs } => {
+            println!("signature: {}", client.set_x402_price_notice(&agent, secs)?);
+        }
+        X402Command::Propose {
+            caller_agent,
+            target_agent,
+            amount,
+            service_id,
+            ttl,
+        } => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs() as i64;
+            let (agreement, signature) = client.propose_terms(
+                &caller_agent,
+                &target_agent,
+                service_id,
+                amount,
+                now + ttl,
+            )?;
+            println!("agreement: {}", agreement);
+            println!("signature: {}", signature);
+        }
+        X402Command::AcceptTerms { agreement, amount } => {
+            println!("signature: {}", client.accept_terms(&agreement, amount)?);
+        }
+        X402Command::CloseTerms { agreement } => {
+            println!("signature: {}", client.close_terms(&agreement)?);
+        }
+        X402Command::TrialDeposit { agent, amount } => {
+            println!("signature: {}", client.set_trial_deposit(&agent, amount)?);
+        }
+        X402Command::Trial {
+            agent,
+            service_id,
+            mint,
+        } => {
+            let (session, signature) = client.open_trial(&agent, &mint, service_id)?;
+            let expires_at = client
+                .get_trial_session(&agent, &client.payer())?
+                .expires_at;
+            println!("session: {}", session);
+            println!("expires at: {}", expires_at);
+            println!("signature: {}", signature);
+        }
+        X402Command::EndTrial { agent } => {
+            println!("signature: {}", client.end_trial(&agent)?);
+        }
+        X402Command::ForfeitTrial { agent, caller } => {
+            println!("signature: {}", client.forfeit_trial(&agent, &caller)?);
+        }
+        X402Command::Rate {
+            payment_record,
+            stars,
+        } => {
+            println!(
+                "signature: {}",
+                client.rate_service(&payment_record, stars)?
+            );
+        }
+        X402Command::Payments {
+            agent,
+            payer,
+            status,
+        } => {
+            for (address, record) in
+                client.get_payment_records(Some(&agent), payer.as_ref(), status)?
+            {
+                let status = match record.status {
+                    PaymentStatus::Pending => "pending",
+                    PaymentStatus::Verified => "verified",
+                    PaymentStatus::Settled => "settled",
+                    PaymentStatus::Failed => "failed",
+                };
+                println!(
+                    "{} payer {} amount {} {} at {}",
+                    address, record.payer, record.amount, status, record.timestamp
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_discount_tier(value: &str) -> Result<FeeDiscountTier> {
+    let (min_stake, discount_bps) = value
+        .split_once(',')
+        .ok_or_else(|| anyhow::anyhow!("expected `min_stake,discount_bps`"))?;
+    Ok(FeeDiscountTier {
+        min_stake: min_stake.trim().parse()?,
+        discount_bps: discount_bps.trim().parse()?,
+    })
+}
+
+fn parse_hex<const N: usize>(value: &str) -> Result<[u8; N]> {
+    anyhow::ensure!(
+        value.len() == 2 * N && value.bytes().all(|b| b.is_ascii_hexdigit()),
+        "expected {} bytes of hex",
+        N
+    );
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&value[2 * i..2 * i + 2], 16)?;
+    }
+    Ok(bytes)
+}
+
+fn parse_creator_fee_mode(value: &str) -> Result<CreatorFeeMode> {
+    match value {
+        "creator" => Ok(CreatorFeeMode::Creator),
+        "waived" => Ok(CreatorFeeMode::Waived),
+        "holders" => Ok(CreatorFeeMode::Holders),
+        _ => anyhow::bail!("expected `creator`, `waived` or `holders`"),
+    }
+}
+
+fn parse_payment_status(value: &str) -> Result<PaymentStatus> {
+    match value {
+        "pending" => Ok(PaymentStatus::Pending),
+        "verified" => Ok(PaymentStatus::Verified),
+        "settled" => Ok(PaymentStatus::Settled),
+        "failed" => Ok(PaymentStatus::Failed),
+        _ => anyhow::bail!("expected `pending`, `verified`, `settled` or `failed`"),
+    }
+}
+
+fn parse_keeper_kind(value: &str) -> Result<KeeperTaskKind> {
+    match value {
+        "dca" => Ok(KeeperTaskKind::Dca),
+        "graduation" => Ok(KeeperTaskKind::Graduation),
+        "admin-action" => Ok(KeeperTaskKind::AdminAction),
+        _ => anyhow::bail!("expected `dca`, `graduation` or `admin-action`"),
+    }
+}
+
+fn parse_constituent(value: &str) -> Result<(Pubkey, u64)> {
+    let (agent, units) = value
+        .split_once(',')
+        .ok_or_else(|| anyhow::anyhow!("expected `agent,units`"))?;
+    Ok((agent.trim().parse()?, units.trim().parse()?))
+}
+
+fn parse_revenue_share(value: &str) -> Result<(Pubkey, u16)> {
+    let (agent, bps) = value
+        .split_once(',')
+        .ok_or_else(|| anyhow::anyhow!("expected `agent,bps`"))?;
+    Ok((agent.trim().parse()?, bps.trim().parse()?))
+}
+
+fn parse_service_id(value: &str) -> Result<[u8; 32]> {
+    service_id_hash(value)
+        .ok_or_else(|| anyhow::anyhow!("service name must be non-empty printable ASCII"))
+}
+
+fn parse_source_tag(value: &str) -> Result<[u8; 32]> {
+    anyhow::ensure!(value.len() <= 32, "source tag is longer than 32 bytes");
+    let mut tag = [0u8; 32];
+    tag[..value.len()].copy_from_slice(value.as_bytes());
+    Ok(tag)
+}
+
+fn parse_reputation_source(value: &str) -> Result<[u8; REPUTATION_SOURCE_LEN]> {
+    ReputationAttestation::source_id(value).ok_or_else(|| {
+        anyhow::anyhow!("source tag must be 1-20 lowercase letters, digits, `-`, `.` or `_`")
+    })
+}
+
+fn parse_bundle_agent(value: &str) -> Result<(String, String)> {
+    let Some((name, symbol)) = value.split_once(':') else {
+        anyhow::bail!("expected `NAME:SYMBOL`");
+    };
+    Ok((name.to_string(), symbol.to_string()))
+}
+
+fn parse_tranche(value: &str) -> Result<CurveTranche> {
+    let parts = value
+        .split(',')
+        .map(|part| part.trim().parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let [supply, start_price, slope] = parts[..] else {
+        anyhow::bail!("expected `supply,start_price,slope`");
+    };
+    Ok(CurveTranche {
+        supply,
+        start_price,
+        slope,
+    })
+}
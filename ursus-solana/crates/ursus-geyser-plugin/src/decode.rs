@@ -0,0 +1,310 @@
+use agent_factory::state::{
+    Agent, AgentFactory, BondingCurve, CreatorFeeMode, CurveType, MeterUnit, PaymentStatus,
+    X402Config, X402PaymentRecord,
+};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use serde_json::{json, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Account types owned by the agent factory program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountKind {
+    Factory,
+    Agent,
+    X402Config,
+    PaymentRecord,
+}
+
+impl AccountKind {
+    /// Subject suffix the account is published under
+    pub fn subject(&self) -> &'static str {
+        match self {
+            Self::Factory => "factory",
+            Self::Agent => "agent",
+            Self::X402Config => "x402_config",
+            Self::PaymentRecord => "payment_record",
+        }
+    }
+}
+
+/// Decode a program account into its kind and a JSON view of its fields
+pub fn decode_account(data: &[u8]) -> Option<(AccountKind, Value)> {
+    let disc = data.get(..8)?;
+
+    if disc == AgentFactory::DISCRIMINATOR {
+        let factory = deserialize::<AgentFactory>(data)?;
+        return Some((
+            AccountKind::Factory,
+            json!({
+                "authority": factory.authority.to_string(),
+                "platform_treasury": factory.platform_treasury.to_string(),
+                "creation_fee": factory.creation_fee,
+                "quote_mint": factory.quote_mint.to_string(),
+                "quote_creation_fee": factory.quote_creation_fee,
+                "total_agents": factory.total_agents,
+                "timelock_delay_secs": factory.timelock_delay_secs,
+                "admin_action_count": factory.admin_action_count,
+                "admin_is_program": factory.admin_is_program,
+                "insurance_fee_bps": factory.insurance_fee_bps,
+                "platform_mint": factory.platform_mint.to_string(),
+                "platform_staking_pool": factory.platform_staking_pool.to_string(),
+                "fee_discount_tiers": factory
+                    .fee_discount_tiers
+                    .iter()
+                    .filter(|t| t.min_stake > 0)
+                    .map(|t| json!({
+                        "min_stake": t.min_stake,
+                        "discount_bps": t.discount_bps,
+                    }))
+                    .collect::<Vec<_>>(),
+                "protocol_liquidity_bps": factory.protocol_liquidity_bps,
+                "arbiters": factory
+                    .arbiters
+                    .iter()
+                    .filter(|a| **a != Pubkey::default())
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>(),
+                "arbiter_threshold": factory.arbiter_threshold,
+                "allowed_models": factory
+                    .allowed_models
+                    .iter()
+                    .filter(|m| m.iter().any(|b| *b != 0))
+                    .map(|m| String::from_utf8_lossy(m).trim_end_matches('\0').to_string())
+                    .collect::<Vec<_>>(),
+                "rename_fee": factory.rename_fee,
+                "creator_fee_vesting_secs": factory.creator_fee_vesting_secs,
+                "max_creations_per_window": factory.max_creations_per_window,
+                "creation_window_secs": factory.creation_window_secs,
+                "creation_window_start": factory.creation_window_start,
+                "creations_in_window": factory.creations_in_window,
+                "creation_surge_bps": factory.creation_surge_bps,
+                "creation_surge_half_life_secs": factory.creation_surge_half_life_secs,
+                "creation_demand": factory.creation_demand,
+                "creation_demand_updated_at": factory.creation_demand_updated_at,
+                "reputation_attestor": factory.reputation_attestor.to_string(),
+                "public_goods_fee_bps": factory.public_goods_fee_bps,
+                "public_goods_recipient": factory.public_goods_recipient.to_string(),
+                "total_public_goods_fees": factory.total_public_goods_fees,
+                "quote_lamport_rate": factory.quote_lamport_rate,
+            }),
+        ));
+    }
+    if disc == Agent::DISCRIMINATOR {
+        let agent = deserialize::<Agent>(data)?;
+        return Some((
+            AccountKind::Agent,
+            json!({
+                "agent_id": agent.agent_id,
+                "mint": agent.mint.to_string(),
+                "creator": agent.creator.to_string(),
+                "ownership_mint": agent.ownership_mint.to_string(),
+                "original_creator": agent.original_creator.to_string(),
+                "royalty_bps": agent.royalty_bps,
+                "name": agent.name,
+                "symbol": agent.symbol,
+                "model": agent.model,
+                "tags": agent.tags,
+                "created_at": agent.created_at,
+                "is_graduated": agent.is_graduated,
+                "is_presale_active": agent.is_presale_active,
+                "same_slot_protection": agent.same_slot_protection,
+                "max_buy_bps_of_reserves": agent.max_buy_bps_of_reserves,
+                "refund_deadline": agent.refund_deadline,
+                "snapshot_count": agent.snapshot_count,
+                "event_sequence": agent.event_sequence,
+                "trending_score": agent.trending_score,
+                "trending_slot": agent.trending_slot,
+                "unique_buyer_count": agent.unique_buyer_count,
+                "staking_fee_bps": agent.staking_fee_bps,
+                "pending_staking_rewards": agent.pending_staking_rewards,
+                "treasury_fee_bps": agent.treasury_fee_bps,
+                "pending_treasury_fees": agent.pending_treasury_fees,
+                "insurance_fee_bps": agent.insurance_fee_bps,
+                "pending_insurance_fees": agent.pending_insurance_fees,
+                "pending_royalties": agent.pending_royalties,
+                "last_renamed_at": agent.last_renamed_at,
+                "creator_fee_vesting_secs": agent.creator_fee_vesting_secs,
+                "streaming_creator_fees": agent.streaming_creator_fees,
+                "claimable_creator_fees": agent.claimable_creator_fees,
+                "creator_fee_stream_end": agent.creator_fee_stream_end,
+                "creator_fee_mode": creator_fee_mode_str(agent.creator_fee_mode),
+                "x402_top_up_bps": agent.x402_top_up_bps,
+                "pending_x402_top_up": agent.pending_x402_top_up,
+                "public_goods_fee_bps": agent.public_goods_fee_bps,
+                "pending_public_goods_fees": agent.pending_public_goods_fees,
+                "burn_remaining_at_graduation": agent.burn_remaining_at_graduation,
+                "last_buy_slot": agent.last_buy_slot,
+                "bonding_curve": curve_json(&agent.bonding_curve),
+            }),
+        ));
+    }
+    if disc == X402Config::DISCRIMINATOR {
+        let config = deserialize::<X402Config>(data)?;
+        return Some((
+            AccountKind::X402Config,
+            json!({
+                "agent": config.agent.to_string(),
+                "payment_recipient": config.payment_recipient.to_string(),
+                "enabled": config.enabled,
+                "min_payment_amount": config.min_payment_amount,
+                "max_payment_amount": config.max_payment_amount,
+                "service_timeout_seconds": config.service_timeout_seconds,
+                "total_payments_received": config.total_payments_received,
+                "total_service_calls": config.total_service_calls,
+                "nonce": config.nonce,
+                "attestation_verifier": config.attestation_verifier.to_string(),
+                "meter_unit": meter_unit_str(config.meter_unit),
+                "unit_price": config.unit_price,
+                "max_affiliate_bps": config.max_affiliate_bps,
+                "webhook_url_hash": config
+                    .webhook_url_hash
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>(),
+                "webhook_signer": config.webhook_signer.to_string(),
+                "frozen": config.frozen,
+                "previous_payment_recipient": config.previous_payment_recipient.to_string(),
+                "previous_recipient_valid_until": config.previous_recipient_valid_until,
+                "revenue_split_bps": config.revenue_split_bps,
+                "chargeback_window_secs": config.chargeback_window_secs,
+                "deprecated_services": config
+                    .deprecated_services
+                    .iter()
+                    .filter(|d| d.service_id != [0; 32])
+                    .map(|d| json!({
+                        "service_id": d
+                            .service_id
+                            .iter()
+                            .map(|b| format!("{:02x}", b))
+                            .collect::<String>(),
+                        "sunset_ts": d.sunset_ts,
+                    }))
+                    .collect::<Vec<_>>(),
+                "price_notice_secs": config.price_notice_secs,
+                "previous_price_notice_secs": config.previous_price_notice_secs,
+                "previous_notice_valid_until": config.previous_notice_valid_until,
+                "pending_min_payment_amount": config.pending_min_payment_amount,
+                "pending_min_payment_at": config.pending_min_payment_at,
+                "trial_deposit": config.trial_deposit,
+            }),
+        ));
+    }
+    if disc == X402PaymentRecord::DISCRIMINATOR {
+        let record = deserialize::<X402PaymentRecord>(data)?;
+        return Some((
+            AccountKind::PaymentRecord,
+            json!({
+                "agent": record.agent.to_string(),
+                "payer": record.payer.to_string(),
+                "amount": record.amount,
+                "timestamp": record.timestamp,
+                "service_id": record
+                    .service_id
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>(),
+                "status": status_str(record.status),
+                "attestation_hash": record
+                    .attestation_hash
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>(),
+                "meter_unit": meter_unit_str(record.meter_unit),
+                "unit_price": record.unit_price,
+                "metered_units": record.metered_units,
+                "request_id": record
+                    .request_id
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>(),
+                "refunded_amount": record.refunded_amount,
+                "dispute_deadline": record.dispute_deadline,
+                "finalized_at": record.finalized_at,
+                "mint": record.mint.to_string(),
+            }),
+        ));
+    }
+
+    None
+}
+
+fn deserialize<T: AccountDeserialize>(mut data: &[u8]) -> Option<T> {
+    T::try_deserialize(&mut data).ok()
+}
+
+fn curve_json(curve: &BondingCurve) -> Value {
+    // LBP prices depend on time; report them as of publishing
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(i64::MAX);
+
+    json!({
+        "virtual_sol_reserves": curve.virtual_sol_reserves,
+        "virtual_token_reserves": curve.virtual_token_reserves,
+        "real_sol_reserves": curve.real_sol_reserves,
+        "real_token_reserves": curve.real_token_reserves,
+        "graduation_threshold": curve.graduation_threshold,
+        "price": curve.get_price_at(now),
+        "market_cap": curve.get_market_cap_at(now),
+        "curve_type": curve_type_json(&curve.curve_type),
+        "creator_fee_bps": curve.creator_fee_bps,
+        "platform_fee_bps": curve.platform_fee_bps,
+    })
+}
+
+fn curve_type_json(curve_type: &CurveType) -> Value {
+    match curve_type {
+        CurveType::ConstantProduct => json!({ "kind": "constant_product" }),
+        CurveType::Stepped {
+            tranche_count,
+            tranches,
+        } => json!({
+            "kind": "stepped",
+            "tranches": tranches
+                .iter()
+                .take(*tranche_count as usize)
+                .map(|t| json!({
+                    "supply": t.supply,
+                    "start_price": t.start_price,
+                    "slope": t.slope,
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        CurveType::Lbp {
+            start_ts,
+            duration_secs,
+            extra_virtual_sol,
+        } => json!({
+            "kind": "lbp",
+            "start_ts": start_ts,
+            "duration_secs": duration_secs,
+            "extra_virtual_sol": extra_virtual_sol,
+        }),
+    }
+}
+
+fn status_str(status: PaymentStatus) -> &'static str {
+    match status {
+        PaymentStatus::Pending => "pending",
+        PaymentStatus::Verified => "verified",
+        PaymentStatus::Settled => "settled",
+        PaymentStatus::Failed => "failed",
+    }
+}
+
+fn creator_fee_mode_str(mode: CreatorFeeMode) -> &'static str {
+    match mode {
+        CreatorFeeMode::Creator => "creator",
+        CreatorFeeMode::Waived => "waived",
+        CreatorFeeMode::Holders => "holders",
+    }
+}
+
+fn meter_unit_str(unit: MeterUnit) -> &'static str {
+    match unit {
+        MeterUnit::ThousandTokens => "1k_tokens",
+        MeterUnit::ComputeUnits => "compute_units",
+    }
+}
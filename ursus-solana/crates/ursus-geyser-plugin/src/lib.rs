@@ -0,0 +1,144 @@
+//! Geyser plugin streaming agent factory account writes to NATS.
+//!
+//! Every write to an account owned by the program is decoded with the
+//! program's state structs and published as JSON on
+//! `<subject_prefix>.<kind>` (e.g. `ursus.accounts.agent`). See
+//! `config.example.json` for the plugin configuration.
+
+mod decode;
+mod publisher;
+
+use std::fs;
+
+use anchor_lang::prelude::Pubkey;
+use serde::Deserialize;
+use serde_json::json;
+use solana_geyser_plugin_interface::geyser_plugin_interface::{
+    GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, Result,
+};
+
+pub use decode::{decode_account, AccountKind};
+use publisher::Publisher;
+
+#[derive(Debug, Deserialize)]
+struct PluginConfig {
+    nats_url: String,
+    #[serde(default = "default_subject_prefix")]
+    subject_prefix: String,
+    /// Also publish accounts replayed from the snapshot at startup
+    #[serde(default)]
+    include_startup: bool,
+}
+
+fn default_subject_prefix() -> String {
+    "ursus.accounts".to_string()
+}
+
+#[derive(Default)]
+pub struct UrsusGeyserPlugin {
+    config: Option<PluginConfig>,
+    publisher: Option<Publisher>,
+}
+
+impl std::fmt::Debug for UrsusGeyserPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UrsusGeyserPlugin")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl GeyserPlugin for UrsusGeyserPlugin {
+    fn name(&self) -> &'static str {
+        "ursus-geyser-plugin"
+    }
+
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        let raw = fs::read_to_string(config_file)?;
+        let config: PluginConfig = serde_json::from_str(&raw)
+            .map_err(|e| GeyserPluginError::ConfigFileReadError { msg: e.to_string() })?;
+
+        let publisher = Publisher::connect(&config.nats_url)
+            .map_err(|e| GeyserPluginError::Custom(Box::new(e)))?;
+        log::info!("ursus-geyser-plugin publishing to {}", config.nats_url);
+
+        self.config = Some(config);
+        self.publisher = Some(publisher);
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        if let Some(publisher) = self.publisher.take() {
+            publisher.shutdown();
+        }
+    }
+
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: u64,
+        is_startup: bool,
+    ) -> Result<()> {
+        let (Some(config), Some(publisher)) = (&self.config, &self.publisher) else {
+            return Ok(());
+        };
+        if is_startup && !config.include_startup {
+            return Ok(());
+        }
+
+        let (pubkey, owner, lamports, data, write_version) = match account {
+            ReplicaAccountInfoVersions::V0_0_1(a) => {
+                (a.pubkey, a.owner, a.lamports, a.data, a.write_version)
+            }
+            ReplicaAccountInfoVersions::V0_0_2(a) => {
+                (a.pubkey, a.owner, a.lamports, a.data, a.write_version)
+            }
+            ReplicaAccountInfoVersions::V0_0_3(a) => {
+                (a.pubkey, a.owner, a.lamports, a.data, a.write_version)
+            }
+        };
+        if owner != agent_factory::ID.as_ref() {
+            return Ok(());
+        }
+        let Some((kind, fields)) = decode_account(data) else {
+            return Ok(());
+        };
+
+        let pubkey = Pubkey::try_from(pubkey)
+            .map(|key| key.to_string())
+            .unwrap_or_default();
+        let payload = json!({
+            "pubkey": pubkey,
+            "slot": slot,
+            "write_version": write_version,
+            "lamports": lamports,
+            "kind": kind.subject(),
+            "account": fields,
+        });
+
+        publisher.publish(
+            format!("{}.{}", config.subject_prefix, kind.subject()),
+            payload.to_string().into_bytes(),
+        );
+        Ok(())
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        true
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// Entry point loaded by the validator
+///
+/// # Safety
+/// Called by the validator's plugin manager, which takes ownership of the box.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    let plugin: Box<dyn GeyserPlugin> = Box::<UrsusGeyserPlugin>::default();
+    Box::into_raw(plugin)
+}
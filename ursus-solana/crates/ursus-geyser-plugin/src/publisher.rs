@@ -0,0 +1,119 @@
+//! Minimal NATS publisher.
+//!
+//! The async NATS client can't be resolved alongside the validator's pinned
+//! Solana dependencies, and a plugin only needs `PUB`, so this speaks the
+//! text protocol directly over TCP from a background thread.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Message queued for publication
+pub struct Message {
+    pub subject: String,
+    pub payload: Vec<u8>,
+}
+
+/// Publishes to NATS from a background thread so validator threads never block
+pub struct Publisher {
+    sender: Option<Sender<Message>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Publisher {
+    pub fn connect(nats_url: &str) -> std::io::Result<Self> {
+        let address = nats_url
+            .trim_start_matches("nats://")
+            .trim_end_matches('/')
+            .to_string();
+
+        // Fail plugin load early if the server is unreachable
+        let connection = Connection::open(&address)?;
+        let (sender, receiver) = channel::<Message>();
+        let worker = thread::Builder::new()
+            .name("ursus-geyser-nats".to_string())
+            .spawn(move || run(address, connection, receiver))?;
+
+        Ok(Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        })
+    }
+
+    pub fn publish(&self, subject: String, payload: Vec<u8>) {
+        let sent = self
+            .sender
+            .as_ref()
+            .map(|sender| sender.send(Message { subject, payload }).is_ok())
+            .unwrap_or(false);
+        if !sent {
+            log::error!("NATS publisher thread has stopped");
+        }
+    }
+
+    pub fn shutdown(mut self) {
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+struct Connection {
+    writer: Arc<Mutex<TcpStream>>,
+}
+
+impl Connection {
+    fn open(address: &str) -> std::io::Result<Self> {
+        let mut stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        stream.write_all(
+            b"CONNECT {\"verbose\":false,\"pedantic\":false,\"name\":\"ursus-geyser-plugin\"}\r\n",
+        )?;
+
+        let writer = Arc::new(Mutex::new(stream.try_clone()?));
+        let pong_writer = writer.clone();
+
+        // Answer server keep-alives; the thread exits when the socket closes
+        thread::Builder::new()
+            .name("ursus-geyser-nats-ping".to_string())
+            .spawn(move || {
+                for line in BufReader::new(stream).lines() {
+                    let Ok(line) = line else { break };
+                    if line.starts_with("PING") {
+                        let _ = pong_writer.lock().unwrap().write_all(b"PONG\r\n");
+                    } else if line.starts_with("-ERR") {
+                        log::error!("NATS server error: {}", line);
+                    }
+                }
+            })?;
+
+        Ok(Self { writer })
+    }
+
+    fn publish(&self, message: &Message) -> std::io::Result<()> {
+        let mut frame =
+            format!("PUB {} {}\r\n", message.subject, message.payload.len()).into_bytes();
+        frame.extend_from_slice(&message.payload);
+        frame.extend_from_slice(b"\r\n");
+        self.writer.lock().unwrap().write_all(&frame)
+    }
+}
+
+fn run(address: String, mut connection: Connection, receiver: Receiver<Message>) {
+    for message in receiver {
+        while let Err(e) = connection.publish(&message) {
+            log::error!("NATS publish failed, reconnecting: {}", e);
+            thread::sleep(RECONNECT_DELAY);
+            match Connection::open(&address) {
+                Ok(reconnected) => connection = reconnected,
+                Err(e) => log::error!("NATS reconnect failed: {}", e),
+            }
+        }
+    }
+}
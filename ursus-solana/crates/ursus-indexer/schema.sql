@@ -0,0 +1,414 @@
+-- Normalized agent factory events. Every row is keyed by the transaction
+-- signature and the event's position in that transaction, so replays from
+-- multiple RPC nodes are idempotent.
+
+CREATE TABLE IF NOT EXISTS trades (
+    signature               TEXT        NOT NULL,
+    event_index             INTEGER     NOT NULL,
+    slot                    BIGINT      NOT NULL,
+    agent                   TEXT        NOT NULL,
+    trader                  TEXT        NOT NULL,
+    is_buy                  BOOLEAN     NOT NULL,
+    sol_amount              BIGINT      NOT NULL,
+    token_amount            BIGINT      NOT NULL,
+    platform_fee            BIGINT      NOT NULL,
+    creator_fee             BIGINT      NOT NULL,
+    price                   BIGINT      NOT NULL,
+    block_time              TIMESTAMPTZ NOT NULL,
+    sequence                BIGINT      NOT NULL,
+    -- Curve reserves before and after the trade
+    virtual_sol_before      BIGINT      NOT NULL,
+    virtual_token_before    BIGINT      NOT NULL,
+    real_sol_before         BIGINT      NOT NULL,
+    real_token_before       BIGINT      NOT NULL,
+    virtual_sol_after       BIGINT      NOT NULL,
+    virtual_token_after     BIGINT      NOT NULL,
+    real_sol_after          BIGINT      NOT NULL,
+    real_token_after        BIGINT      NOT NULL,
+    price_impact_bps        BIGINT      NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS trades_agent_time_idx ON trades (agent, block_time);
+CREATE UNIQUE INDEX IF NOT EXISTS trades_agent_sequence_idx ON trades (agent, sequence);
+
+CREATE TABLE IF NOT EXISTS payments (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    payer           TEXT        NOT NULL,
+    payment_record  TEXT        NOT NULL,
+    amount          BIGINT      NOT NULL,
+    service_id      BYTEA       NOT NULL,
+    nonce           BIGINT      NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    sequence        BIGINT      NOT NULL,
+    request_id      BYTEA       NOT NULL,
+    -- Paid on top of amount to expedite the request
+    priority_tip    BIGINT      NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS payments_request_id_idx ON payments (request_id);
+CREATE INDEX IF NOT EXISTS payments_agent_time_idx ON payments (agent, block_time);
+CREATE UNIQUE INDEX IF NOT EXISTS payments_agent_sequence_idx ON payments (agent, sequence);
+
+CREATE TABLE IF NOT EXISTS agent_service_calls (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    caller_agent    TEXT        NOT NULL,
+    target_agent    TEXT        NOT NULL,
+    service_id      BYTEA       NOT NULL,
+    amount          BIGINT      NOT NULL,
+    service_params  BYTEA       NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    request_id      BYTEA       NOT NULL,
+    priority_tip    BIGINT      NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS agent_service_calls_request_id_idx ON agent_service_calls (request_id);
+
+CREATE INDEX IF NOT EXISTS agent_service_calls_target_time_idx ON agent_service_calls (target_agent, block_time);
+
+CREATE TABLE IF NOT EXISTS snapshots (
+    signature           TEXT        NOT NULL,
+    event_index         INTEGER     NOT NULL,
+    slot                BIGINT      NOT NULL,
+    agent               TEXT        NOT NULL,
+    snapshot            TEXT        NOT NULL,
+    snapshot_id         BIGINT      NOT NULL,
+    snapshot_slot       BIGINT      NOT NULL,
+    circulating_supply  BIGINT      NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS snapshots_agent_id_idx ON snapshots (agent, snapshot_id);
+
+CREATE TABLE IF NOT EXISTS admin_actions (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    action_id       BIGINT      NOT NULL,
+    action          TEXT        NOT NULL,
+    stage           TEXT        NOT NULL,
+    eta             TIMESTAMPTZ NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS admin_actions_id_idx ON admin_actions (action_id);
+
+CREATE TABLE IF NOT EXISTS insurance_claims (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    claim_id        BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    recipient       TEXT        NOT NULL,
+    amount          BIGINT      NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS insurance_claims_agent_idx ON insurance_claims (agent);
+
+CREATE TABLE IF NOT EXISTS webhooks (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    url_hash        BYTEA       NOT NULL,
+    signer          TEXT        NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS webhooks_agent_time_idx ON webhooks (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS agent_renames (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    old_name        TEXT        NOT NULL,
+    old_symbol      TEXT        NOT NULL,
+    new_name        TEXT        NOT NULL,
+    new_symbol      TEXT        NOT NULL,
+    fee             BIGINT      NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS agent_renames_agent_time_idx ON agent_renames (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS agents_created (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    agent_id        BIGINT      NOT NULL,
+    mint            TEXT        NOT NULL,
+    ownership_mint  TEXT        NOT NULL,
+    creator         TEXT        NOT NULL,
+    name            TEXT        NOT NULL,
+    symbol          TEXT        NOT NULL,
+    creation_fee    BIGINT      NOT NULL,
+    fee_mint        TEXT        NOT NULL,
+    creator_fee_mode TEXT       NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS agents_created_creator_idx ON agents_created (creator);
+
+CREATE TABLE IF NOT EXISTS fee_claims (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    recipient       TEXT        NOT NULL,
+    kind            TEXT        NOT NULL,
+    amount          BIGINT      NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS fee_claims_agent_time_idx ON fee_claims (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS x402_freezes (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    authority       TEXT        NOT NULL,
+    frozen          BOOLEAN     NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS x402_freezes_agent_time_idx ON x402_freezes (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS x402_recipient_rotations (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    old_recipient   TEXT        NOT NULL,
+    new_recipient   TEXT        NOT NULL,
+    grace_ends_at   TIMESTAMPTZ NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS x402_recipient_rotations_agent_time_idx ON x402_recipient_rotations (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS graduations (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    creator         TEXT        NOT NULL,
+    sol_reserves    BIGINT      NOT NULL,
+    token_reserves  BIGINT      NOT NULL,
+    tokens_burned   BIGINT      NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS graduations_agent_time_idx ON graduations (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS fee_overrides (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    authority       TEXT        NOT NULL,
+    platform_fee_bps INTEGER    NOT NULL,
+    creator_fee_bps INTEGER     NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS fee_overrides_agent_time_idx ON fee_overrides (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS service_deprecations (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    service_id      BYTEA       NOT NULL,
+    -- NULL when the deprecation was withdrawn
+    sunset_at       TIMESTAMPTZ,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS service_deprecations_agent_time_idx ON service_deprecations (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS revenue_deposits (
+    signature       TEXT        NOT NULL,
+    event_index     INTEGER     NOT NULL,
+    slot            BIGINT      NOT NULL,
+    agent           TEXT        NOT NULL,
+    depositor       TEXT        NOT NULL,
+    pool            TEXT        NOT NULL,
+    -- Default key (11111111111111111111111111111111) for SOL deposits
+    mint            TEXT        NOT NULL,
+    amount          BIGINT      NOT NULL,
+    source          BYTEA       NOT NULL,
+    block_time      TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS revenue_deposits_agent_time_idx ON revenue_deposits (agent, block_time);
+CREATE INDEX IF NOT EXISTS revenue_deposits_source_idx ON revenue_deposits (source);
+
+CREATE TABLE IF NOT EXISTS x402_config_updates (
+    signature           TEXT        NOT NULL,
+    event_index         INTEGER     NOT NULL,
+    slot                BIGINT      NOT NULL,
+    agent               TEXT        NOT NULL,
+    authority           TEXT        NOT NULL,
+    old_enabled         BOOLEAN     NOT NULL,
+    new_enabled         BOOLEAN     NOT NULL,
+    old_min_amount      BIGINT      NOT NULL,
+    new_min_amount      BIGINT      NOT NULL,
+    old_max_amount      BIGINT      NOT NULL,
+    new_max_amount      BIGINT      NOT NULL,
+    old_timeout_secs    BIGINT      NOT NULL,
+    new_timeout_secs    BIGINT      NOT NULL,
+    -- Later than block_time for a minimum price increase given notice
+    min_effective_at    TIMESTAMPTZ NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS x402_config_updates_agent_time_idx ON x402_config_updates (agent, block_time);
+
+-- Proposed (accepted = false) and accepted agent-to-agent service terms
+CREATE TABLE IF NOT EXISTS service_terms (
+    signature           TEXT        NOT NULL,
+    event_index         INTEGER     NOT NULL,
+    slot                BIGINT      NOT NULL,
+    agreement           TEXT        NOT NULL,
+    caller_agent        TEXT        NOT NULL,
+    target_agent        TEXT        NOT NULL,
+    service_id          BYTEA       NOT NULL,
+    amount              BIGINT      NOT NULL,
+    expires_at          TIMESTAMPTZ NOT NULL,
+    accepted            BOOLEAN     NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS service_terms_agent_time_idx ON service_terms (target_agent, block_time);
+
+-- One row per task transition: Open, Claimed, Completed or Withdrawn
+CREATE TABLE IF NOT EXISTS work_tasks (
+    signature           TEXT        NOT NULL,
+    event_index         INTEGER     NOT NULL,
+    slot                BIGINT      NOT NULL,
+    agent               TEXT        NOT NULL,
+    task                TEXT        NOT NULL,
+    task_id             BIGINT      NOT NULL,
+    requester           TEXT        NOT NULL,
+    -- Default key (11111111111111111111111111111111) while unclaimed
+    worker              TEXT        NOT NULL,
+    status              TEXT        NOT NULL,
+    params_hash         BYTEA       NOT NULL,
+    max_price           BIGINT      NOT NULL,
+    price               BIGINT      NOT NULL,
+    result_hash         BYTEA       NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS work_tasks_agent_time_idx ON work_tasks (agent, block_time);
+CREATE INDEX IF NOT EXISTS work_tasks_task_idx ON work_tasks (task);
+
+CREATE TABLE IF NOT EXISTS trial_sessions (
+    signature           TEXT        NOT NULL,
+    event_index         INTEGER     NOT NULL,
+    slot                BIGINT      NOT NULL,
+    agent               TEXT        NOT NULL,
+    session             TEXT        NOT NULL,
+    caller              TEXT        NOT NULL,
+    service_id          BYTEA       NOT NULL,
+    deposit             BIGINT      NOT NULL,
+    -- Open, Ended (refunded) or Forfeited (paid to the provider)
+    status              TEXT        NOT NULL,
+    expires_at          TIMESTAMPTZ NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS trial_sessions_agent_time_idx ON trial_sessions (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS reputation_attestations (
+    signature           TEXT        NOT NULL,
+    event_index         INTEGER     NOT NULL,
+    slot                BIGINT      NOT NULL,
+    agent               TEXT        NOT NULL,
+    attestor            TEXT        NOT NULL,
+    -- Lowercase source tag, e.g. 'olas'
+    source              TEXT        NOT NULL,
+    score_bps           INTEGER     NOT NULL,
+    -- 0 withdraws the source
+    weight_bps          INTEGER     NOT NULL,
+    evidence_hash       BYTEA       NOT NULL,
+    reputation_bps      INTEGER     NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS reputation_attestations_agent_time_idx ON reputation_attestations (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS service_ratings (
+    signature           TEXT        NOT NULL,
+    event_index         INTEGER     NOT NULL,
+    slot                BIGINT      NOT NULL,
+    agent               TEXT        NOT NULL,
+    payment_record      TEXT        NOT NULL,
+    rater               TEXT        NOT NULL,
+    service_id          BYTEA       NOT NULL,
+    stars               INTEGER     NOT NULL,
+    -- Amount paid net of refunds
+    weight              BIGINT      NOT NULL,
+    rating_bps          INTEGER     NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS service_ratings_agent_time_idx ON service_ratings (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS public_goods_fees (
+    signature           TEXT        NOT NULL,
+    event_index         INTEGER     NOT NULL,
+    slot                BIGINT      NOT NULL,
+    agent               TEXT        NOT NULL,
+    recipient           TEXT        NOT NULL,
+    -- Lamports
+    amount              BIGINT      NOT NULL,
+    total_collected     BIGINT      NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS public_goods_fees_agent_time_idx ON public_goods_fees (agent, block_time);
+
+CREATE TABLE IF NOT EXISTS ownership_transfers (
+    signature           TEXT        NOT NULL,
+    event_index         INTEGER     NOT NULL,
+    slot                BIGINT      NOT NULL,
+    agent               TEXT        NOT NULL,
+    previous_creator    TEXT        NOT NULL,
+    new_creator         TEXT        NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS ownership_transfers_agent_time_idx ON ownership_transfers (agent, block_time);
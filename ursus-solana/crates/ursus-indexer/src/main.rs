@@ -0,0 +1,57 @@
+//! `ursus-indexer` - streams agent factory events into Postgres.
+//!
+//! Configuration (environment):
+//! - `DATABASE_URL`: Postgres connection string
+//! - `WS_URL`: Solana websocket endpoint (default: devnet)
+//! - `RPC_URL`: Solana RPC endpoint used to fetch each transaction and read
+//!   its events from inner instructions (default: devnet)
+
+mod sink;
+
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use ursus_agent_client::UrsusClient;
+
+use crate::sink::PgSink;
+
+const DEFAULT_WS_URL: &str = "wss://api.devnet.solana.com";
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+fn main() -> Result<()> {
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL must be set")?;
+    let ws_url = std::env::var("WS_URL").unwrap_or_else(|_| DEFAULT_WS_URL.to_string());
+    let rpc_url = std::env::var("RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.to_string());
+
+    let mut sink = PgSink::connect(&database_url)?;
+    println!("Indexing agent factory events from {}", ws_url);
+
+    loop {
+        if let Err(e) = run(&ws_url, &rpc_url, &mut sink) {
+            eprintln!("Subscription error: {:#}", e);
+        }
+        eprintln!("Reconnecting in {}s", RECONNECT_DELAY.as_secs());
+        thread::sleep(RECONNECT_DELAY);
+    }
+}
+
+/// Consume one subscription until the stream closes
+fn run(ws_url: &str, rpc_url: &str, sink: &mut PgSink) -> Result<()> {
+    let subscription = UrsusClient::subscribe_cpi_events(ws_url, rpc_url)?;
+
+    while let Some(batch) = subscription.next_events() {
+        sink.write(&batch)
+            .with_context(|| format!("writing events of {}", batch.signature))?;
+        println!(
+            "slot {}: indexed {} event(s) from {}",
+            batch.slot,
+            batch.events.len(),
+            batch.signature
+        );
+    }
+
+    subscription.shutdown()?;
+    Ok(())
+}
@@ -0,0 +1,551 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{Context, Result};
+use postgres::{Client, NoTls};
+use ursus_agent_client::events::TransactionEvents;
+use ursus_agent_client::ProgramEvent;
+
+const SCHEMA: &str = include_str!("../schema.sql");
+
+/// Writes decoded program events into Postgres
+pub struct PgSink {
+    client: Client,
+}
+
+impl PgSink {
+    pub fn connect(database_url: &str) -> Result<Self> {
+        let mut client = Client::connect(database_url, NoTls).context("connecting to Postgres")?;
+        client.batch_execute(SCHEMA).context("applying schema")?;
+        Ok(Self { client })
+    }
+
+    /// Insert every event of a transaction atomically
+    pub fn write(&mut self, batch: &TransactionEvents) -> Result<()> {
+        let slot = to_i64(batch.slot)?;
+        let mut tx = self.client.transaction()?;
+
+        for (index, event) in batch.events.iter().enumerate() {
+            let index = index as i32;
+            match event {
+                ProgramEvent::Trade(e) => {
+                    tx.execute(
+                        "INSERT INTO trades (signature, event_index, slot, agent, trader, is_buy, \
+                         sol_amount, token_amount, platform_fee, creator_fee, price, block_time, \
+                         sequence, virtual_sol_before, virtual_token_before, real_sol_before, \
+                         real_token_before, virtual_sol_after, virtual_token_after, real_sol_after, \
+                         real_token_after, price_impact_bps) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, \
+                         $16, $17, $18, $19, $20, $21, $22) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.trader),
+                            &e.is_buy,
+                            &to_i64(e.sol_amount)?,
+                            &to_i64(e.token_amount)?,
+                            &to_i64(e.platform_fee)?,
+                            &to_i64(e.creator_fee)?,
+                            &to_i64(e.price)?,
+                            &block_time(e.timestamp),
+                            &to_i64(e.sequence)?,
+                            &to_i64(e.reserves_before.virtual_sol)?,
+                            &to_i64(e.reserves_before.virtual_token)?,
+                            &to_i64(e.reserves_before.real_sol)?,
+                            &to_i64(e.reserves_before.real_token)?,
+                            &to_i64(e.reserves_after.virtual_sol)?,
+                            &to_i64(e.reserves_after.virtual_token)?,
+                            &to_i64(e.reserves_after.real_sol)?,
+                            &to_i64(e.reserves_after.real_token)?,
+                            &to_i64(e.price_impact_bps)?,
+                        ],
+                    )?;
+                }
+                ProgramEvent::Payment(e) => {
+                    tx.execute(
+                        "INSERT INTO payments (signature, event_index, slot, agent, payer, \
+                         payment_record, amount, service_id, nonce, block_time, sequence, \
+                         request_id, priority_tip) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.payer),
+                            &key(&e.payment_record),
+                            &to_i64(e.amount)?,
+                            &&e.service_id[..],
+                            &to_i64(e.nonce)?,
+                            &block_time(e.timestamp),
+                            &to_i64(e.sequence)?,
+                            &&e.request_id[..],
+                            &to_i64(e.priority_tip)?,
+                        ],
+                    )?;
+                }
+                ProgramEvent::AgentServiceCall(e) => {
+                    tx.execute(
+                        "INSERT INTO agent_service_calls (signature, event_index, slot, caller_agent, \
+                         target_agent, service_id, amount, service_params, block_time, request_id, \
+                         priority_tip) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.caller_agent),
+                            &key(&e.target_agent),
+                            &&e.service_id[..],
+                            &to_i64(e.amount)?,
+                            &e.service_params,
+                            &block_time(e.timestamp),
+                            &&e.request_id[..],
+                            &to_i64(e.priority_tip)?,
+                        ],
+                    )?;
+                }
+                ProgramEvent::Snapshot(e) => {
+                    tx.execute(
+                        "INSERT INTO snapshots (signature, event_index, slot, agent, snapshot, \
+                         snapshot_id, snapshot_slot, circulating_supply, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.snapshot),
+                            &to_i64(e.snapshot_id)?,
+                            &to_i64(e.slot)?,
+                            &to_i64(e.circulating_supply)?,
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::AdminAction(e) => {
+                    tx.execute(
+                        "INSERT INTO admin_actions (signature, event_index, slot, action_id, \
+                         action, stage, eta, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &to_i64(e.action_id)?,
+                            &format!("{:?}", e.action),
+                            &format!("{:?}", e.stage),
+                            &block_time(e.eta),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::InsuranceClaim(e) => {
+                    tx.execute(
+                        "INSERT INTO insurance_claims (signature, event_index, slot, claim_id, \
+                         agent, recipient, amount, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &to_i64(e.claim_id)?,
+                            &key(&e.agent),
+                            &key(&e.recipient),
+                            &to_i64(e.amount)?,
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::Webhook(e) => {
+                    tx.execute(
+                        "INSERT INTO webhooks (signature, event_index, slot, agent, url_hash, \
+                         signer, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &&e.url_hash[..],
+                            &key(&e.signer),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::AgentRenamed(e) => {
+                    tx.execute(
+                        "INSERT INTO agent_renames (signature, event_index, slot, agent, \
+                         old_name, old_symbol, new_name, new_symbol, fee, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &e.old_name,
+                            &e.old_symbol,
+                            &e.new_name,
+                            &e.new_symbol,
+                            &to_i64(e.fee)?,
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::AgentCreated(e) => {
+                    tx.execute(
+                        "INSERT INTO agents_created (signature, event_index, slot, agent, \
+                         agent_id, mint, ownership_mint, creator, name, symbol, creation_fee, \
+                         fee_mint, creator_fee_mode, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &to_i64(e.agent_id)?,
+                            &key(&e.mint),
+                            &key(&e.ownership_mint),
+                            &key(&e.creator),
+                            &e.name,
+                            &e.symbol,
+                            &to_i64(e.creation_fee)?,
+                            &key(&e.fee_mint),
+                            &format!("{:?}", e.creator_fee_mode),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::FeesClaimed(e) => {
+                    tx.execute(
+                        "INSERT INTO fee_claims (signature, event_index, slot, agent, recipient, \
+                         kind, amount, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.recipient),
+                            &format!("{:?}", e.kind),
+                            &to_i64(e.amount)?,
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::X402Freeze(e) => {
+                    tx.execute(
+                        "INSERT INTO x402_freezes (signature, event_index, slot, agent, \
+                         authority, frozen, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.authority),
+                            &e.frozen,
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::X402RecipientRotated(e) => {
+                    tx.execute(
+                        "INSERT INTO x402_recipient_rotations (signature, event_index, slot, \
+                         agent, old_recipient, new_recipient, grace_ends_at, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.old_recipient),
+                            &key(&e.new_recipient),
+                            &block_time(e.grace_ends_at),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::AgentGraduated(e) => {
+                    tx.execute(
+                        "INSERT INTO graduations (signature, event_index, slot, agent, creator, \
+                         sol_reserves, token_reserves, tokens_burned, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.creator),
+                            &to_i64(e.sol_reserves)?,
+                            &to_i64(e.token_reserves)?,
+                            &to_i64(e.tokens_burned)?,
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::AgentFeeOverride(e) => {
+                    tx.execute(
+                        "INSERT INTO fee_overrides (signature, event_index, slot, agent, \
+                         authority, platform_fee_bps, creator_fee_bps, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.authority),
+                            &(e.platform_fee_bps as i32),
+                            &(e.creator_fee_bps as i32),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::ServiceDeprecated(e) => {
+                    let sunset_at = (e.sunset_ts != 0).then(|| block_time(e.sunset_ts));
+                    tx.execute(
+                        "INSERT INTO service_deprecations (signature, event_index, slot, agent, \
+                         service_id, sunset_at, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &&e.service_id[..],
+                            &sunset_at,
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::RevenueDeposited(e) => {
+                    tx.execute(
+                        "INSERT INTO revenue_deposits (signature, event_index, slot, agent, \
+                         depositor, pool, mint, amount, source, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.depositor),
+                            &format!("{:?}", e.pool),
+                            &key(&e.mint),
+                            &to_i64(e.amount)?,
+                            &&e.source[..],
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::X402ConfigUpdated(e) => {
+                    tx.execute(
+                        "INSERT INTO x402_config_updates (signature, event_index, slot, agent, \
+                         authority, old_enabled, new_enabled, old_min_amount, new_min_amount, \
+                         old_max_amount, new_max_amount, old_timeout_secs, new_timeout_secs, \
+                         min_effective_at, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, \
+                         $15) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.authority),
+                            &e.old_enabled,
+                            &e.new_enabled,
+                            &to_i64(e.old_min_payment_amount)?,
+                            &to_i64(e.new_min_payment_amount)?,
+                            &to_i64(e.old_max_payment_amount)?,
+                            &to_i64(e.new_max_payment_amount)?,
+                            &to_i64(e.old_service_timeout_seconds)?,
+                            &to_i64(e.new_service_timeout_seconds)?,
+                            &block_time(e.min_payment_effective_at),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::ServiceTerms(e) => {
+                    tx.execute(
+                        "INSERT INTO service_terms (signature, event_index, slot, agreement, \
+                         caller_agent, target_agent, service_id, amount, expires_at, accepted, \
+                         block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agreement),
+                            &key(&e.caller_agent),
+                            &key(&e.target_agent),
+                            &&e.service_id[..],
+                            &to_i64(e.amount)?,
+                            &block_time(e.expires_at),
+                            &e.accepted,
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::WorkTask(e) => {
+                    tx.execute(
+                        "INSERT INTO work_tasks (signature, event_index, slot, agent, task, \
+                         task_id, requester, worker, status, params_hash, max_price, price, \
+                         result_hash, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.task),
+                            &to_i64(e.task_id)?,
+                            &key(&e.requester),
+                            &key(&e.worker),
+                            &format!("{:?}", e.status),
+                            &&e.params_hash[..],
+                            &to_i64(e.max_price)?,
+                            &to_i64(e.price)?,
+                            &&e.result_hash[..],
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::TrialSession(e) => {
+                    tx.execute(
+                        "INSERT INTO trial_sessions (signature, event_index, slot, agent, \
+                         session, caller, service_id, deposit, status, expires_at, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.session),
+                            &key(&e.caller),
+                            &&e.service_id[..],
+                            &to_i64(e.deposit)?,
+                            &format!("{:?}", e.status),
+                            &block_time(e.expires_at),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::ReputationAttested(e) => {
+                    let source = String::from_utf8_lossy(&e.source);
+                    tx.execute(
+                        "INSERT INTO reputation_attestations (signature, event_index, slot, agent, \
+                         attestor, source, score_bps, weight_bps, evidence_hash, reputation_bps, \
+                         block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.attestor),
+                            &source.trim_end_matches('\0'),
+                            &(e.score_bps as i32),
+                            &(e.weight_bps as i32),
+                            &&e.evidence_hash[..],
+                            &(e.reputation_bps as i32),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::ServiceRated(e) => {
+                    tx.execute(
+                        "INSERT INTO service_ratings (signature, event_index, slot, agent, \
+                         payment_record, rater, service_id, stars, weight, rating_bps, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.payment_record),
+                            &key(&e.rater),
+                            &&e.service_id[..],
+                            &(e.stars as i32),
+                            &to_i64(e.weight)?,
+                            &(e.rating_bps as i32),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::PublicGoodsFeesCollected(e) => {
+                    tx.execute(
+                        "INSERT INTO public_goods_fees (signature, event_index, slot, agent, \
+                         recipient, amount, total_collected, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.recipient),
+                            &to_i64(e.amount)?,
+                            &to_i64(e.total_collected)?,
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+                ProgramEvent::AgentOwnershipTransferred(e) => {
+                    tx.execute(
+                        "INSERT INTO ownership_transfers (signature, event_index, slot, agent, \
+                         previous_creator, new_creator, block_time) \
+                         VALUES ($1, $2, $3, $4, $5, $6, $7) \
+                         ON CONFLICT DO NOTHING",
+                        &[
+                            &batch.signature,
+                            &index,
+                            &slot,
+                            &key(&e.agent),
+                            &key(&e.previous_creator),
+                            &key(&e.new_creator),
+                            &block_time(e.timestamp),
+                        ],
+                    )?;
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn key(pubkey: &Pubkey) -> String {
+    pubkey.to_string()
+}
+
+fn to_i64(value: u64) -> Result<i64> {
+    i64::try_from(value).context("value does not fit in BIGINT")
+}
+
+fn block_time(unix_timestamp: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(unix_timestamp.max(0) as u64)
+}
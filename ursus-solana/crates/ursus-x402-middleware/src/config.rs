@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use solana_sdk::pubkey::Pubkey;
+use ursus_agent_client::accounts::service_id_hash;
+
+/// Price of a single paid route
+#[derive(Debug, Clone)]
+pub struct RoutePrice {
+    /// Service name, as advertised to payers
+    pub service: String,
+    /// `service_id_hash` of `service`, which the payment record must carry
+    pub service_id: [u8; 32],
+    /// Minimum amount (payment token smallest units)
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct X402MiddlewareConfig {
+    pub rpc_url: String,
+    /// Agent whose payment records are accepted
+    pub agent: Pubkey,
+    /// Token the route amounts are priced in; records paid in any other
+    /// mint are rejected
+    pub mint: Pubkey,
+    /// Paid routes keyed by exact request path
+    pub routes: HashMap<String, RoutePrice>,
+    /// How long a verified receipt is served from cache
+    pub cache_ttl: Duration,
+    /// Reject records older than this (None = no limit)
+    pub max_receipt_age: Option<Duration>,
+    /// How long an issued payment challenge can be answered
+    pub challenge_ttl: Duration,
+}
+
+impl X402MiddlewareConfig {
+    pub fn new(rpc_url: impl Into<String>, agent: Pubkey, mint: Pubkey) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            agent,
+            mint,
+            routes: HashMap::new(),
+            cache_ttl: Duration::from_secs(300),
+            max_receipt_age: Some(Duration::from_secs(3600)),
+            challenge_ttl: Duration::from_secs(300),
+        }
+    }
+
+    /// Charge `amount` for `path` under the service named `service`.
+    /// Panics if `service` is not a valid service name
+    pub fn route(
+        mut self,
+        path: impl Into<String>,
+        service: impl Into<String>,
+        amount: u64,
+    ) -> Self {
+        let service = service.into();
+        let service_id = service_id_hash(&service)
+            .unwrap_or_else(|| panic!("invalid x402 service name {:?}", service));
+        self.routes.insert(
+            path.into(),
+            RoutePrice {
+                service,
+                service_id,
+                amount,
+            },
+        );
+        self
+    }
+
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    pub fn max_receipt_age(mut self, age: Option<Duration>) -> Self {
+        self.max_receipt_age = age;
+        self
+    }
+
+    pub fn challenge_ttl(mut self, ttl: Duration) -> Self {
+        self.challenge_ttl = ttl;
+        self
+    }
+}
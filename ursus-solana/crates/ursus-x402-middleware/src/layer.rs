@@ -0,0 +1,156 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::body::Body;
+use axum::http::{HeaderValue, Request, Response, StatusCode};
+use tower::{Layer, Service};
+use ursus_agent_client::PROGRAM_ID;
+
+use crate::config::{RoutePrice, X402MiddlewareConfig};
+use crate::verify::{PaymentError, PaymentProof, ReceiptVerifier};
+
+/// Request header carrying the payment record address
+pub const PAYMENT_HEADER: &str = "x-payment";
+/// Request header echoing the challenge from the `402` response
+pub const CHALLENGE_HEADER: &str = "x-payment-challenge";
+/// Request header carrying the payer's signature over the challenge
+pub const SIGNATURE_HEADER: &str = "x-payment-signature";
+
+/// Tower layer that gates configured routes behind an X402 payment
+#[derive(Clone)]
+pub struct X402Layer {
+    verifier: Arc<ReceiptVerifier>,
+}
+
+impl X402Layer {
+    pub fn new(config: X402MiddlewareConfig) -> Self {
+        Self {
+            verifier: Arc::new(ReceiptVerifier::new(config)),
+        }
+    }
+}
+
+impl<S> Layer<S> for X402Layer {
+    type Service = X402Service<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        X402Service {
+            inner,
+            verifier: self.verifier.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct X402Service<S> {
+    inner: S,
+    verifier: Arc<ReceiptVerifier>,
+}
+
+impl<S> Service<Request<Body>> for X402Service<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let route = match self.verifier.config().routes.get(request.uri().path()) {
+            Some(route) => route.clone(),
+            None => return Box::pin(self.inner.call(request)),
+        };
+
+        // Take the service that was driven to readiness, leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let verifier = self.verifier.clone();
+
+        Box::pin(async move {
+            let proof = payment_proof(&request);
+
+            let result = match proof {
+                Some(proof) => verifier.verify(&proof, &route).await,
+                None => Err(PaymentError::Missing),
+            };
+
+            match result {
+                Ok(()) => inner.call(request).await,
+                Err(error) => {
+                    let challenge = verifier.issue_challenge().to_string();
+                    Ok(payment_required(verifier.config(), &route, &challenge, &error))
+                }
+            }
+        })
+    }
+}
+
+/// Read the payment proof headers; `None` unless all three are present
+fn payment_proof(request: &Request<Body>) -> Option<PaymentProof> {
+    let header = |name: &str| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned)
+    };
+    Some(PaymentProof {
+        record: header(PAYMENT_HEADER)?,
+        challenge: header(CHALLENGE_HEADER)?,
+        signature: header(SIGNATURE_HEADER)?,
+    })
+}
+
+/// Build the `402 Payment Required` response advertising the payment terms
+/// and a fresh challenge for the payer to sign
+fn payment_required(
+    config: &X402MiddlewareConfig,
+    route: &RoutePrice,
+    challenge: &str,
+    error: &PaymentError,
+) -> Response<Body> {
+    let service_id: String = route
+        .service_id
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let body = serde_json::json!({
+        "error": error.to_string(),
+        "program": PROGRAM_ID.to_string(),
+        "agent": config.agent.to_string(),
+        "mint": config.mint.to_string(),
+        "service": route.service,
+        "serviceId": service_id,
+        "amount": route.amount.to_string(),
+        "challenge": challenge,
+    });
+
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = StatusCode::PAYMENT_REQUIRED;
+
+    let headers = response.headers_mut();
+    headers.insert("content-type", HeaderValue::from_static("application/json"));
+    headers.insert("x-payment-error", HeaderValue::from_static(error.code()));
+    let terms = [
+        ("x-payment-program", PROGRAM_ID.to_string()),
+        ("x-payment-agent", config.agent.to_string()),
+        ("x-payment-mint", config.mint.to_string()),
+        ("x-payment-service", route.service.clone()),
+        ("x-payment-service-id", service_id),
+        ("x-payment-amount", route.amount.to_string()),
+        (CHALLENGE_HEADER, challenge.to_owned()),
+    ];
+    for (name, value) in terms {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    }
+    response
+}
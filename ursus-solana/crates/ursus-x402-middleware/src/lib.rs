@@ -0,0 +1,27 @@
+//! HTTP 402 middleware for agent APIs monetized through X402.
+//!
+//! Configured routes answer `402 Payment Required` with the payment terms
+//! and a single-use challenge until the client retries with an `X-PAYMENT`
+//! header naming the `X402PaymentRecord` PDA created by `pay_for_service`,
+//! the challenge in `X-PAYMENT-CHALLENGE`, and in `X-PAYMENT-SIGNATURE` the
+//! record payer's signature over `challenge_message(challenge, record)`.
+//! Each record pays for one request. Records must be paid in the configured
+//! mint; they are verified over RPC and cached, so a retry after a rejected
+//! signature skips the round trip. Agent-to-agent records name the caller
+//! agent as payer, which cannot sign, so they are not accepted here.
+//!
+//! ```ignore
+//! let layer = X402Layer::new(
+//!     X402MiddlewareConfig::new(rpc_url, agent, usdc_mint)
+//!         .route("/v1/infer", "inference", 1_000_000),
+//! );
+//! let app = Router::new().route("/v1/infer", post(infer)).layer(layer);
+//! ```
+
+mod config;
+mod layer;
+mod verify;
+
+pub use config::{RoutePrice, X402MiddlewareConfig};
+pub use layer::{X402Layer, X402Service, CHALLENGE_HEADER, PAYMENT_HEADER, SIGNATURE_HEADER};
+pub use verify::{challenge_message, PaymentError, PaymentProof, ReceiptVerifier};
@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use ursus_agent_client::accounts::{decode, PaymentStatus, X402PaymentRecord};
+use ursus_agent_client::PROGRAM_ID;
+
+use crate::config::{RoutePrice, X402MiddlewareConfig};
+
+/// Why a payment proof was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaymentError {
+    Missing,
+    Malformed,
+    NotFound,
+    WrongAgent,
+    WrongService,
+    WrongMint,
+    Underpaid,
+    NotSettled,
+    Expired,
+    BadChallenge,
+    BadSignature,
+    AlreadyUsed,
+    Rpc(String),
+}
+
+impl PaymentError {
+    /// Short machine-readable code for the `X-Payment-Error` header
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Missing => "missing",
+            Self::Malformed => "malformed",
+            Self::NotFound => "not_found",
+            Self::WrongAgent => "wrong_agent",
+            Self::WrongService => "wrong_service",
+            Self::WrongMint => "wrong_mint",
+            Self::Underpaid => "underpaid",
+            Self::NotSettled => "not_settled",
+            Self::Expired => "expired",
+            Self::BadChallenge => "bad_challenge",
+            Self::BadSignature => "bad_signature",
+            Self::AlreadyUsed => "already_used",
+            Self::Rpc(_) => "rpc_error",
+        }
+    }
+}
+
+impl fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rpc(e) => write!(f, "rpc error: {}", e),
+            other => f.write_str(other.code()),
+        }
+    }
+}
+
+/// Payment proof sent back with a retried request
+#[derive(Debug, Clone)]
+pub struct PaymentProof {
+    /// Payment record address
+    pub record: String,
+    /// Challenge from the `402` response
+    pub challenge: String,
+    /// Payer's signature over `challenge_message(challenge, record)`
+    pub signature: String,
+}
+
+/// Bytes the payer signs to claim a receipt: the challenge followed by the
+/// payment record address
+pub fn challenge_message(challenge: &Hash, record: &Pubkey) -> Vec<u8> {
+    [challenge.as_ref(), record.as_ref()].concat()
+}
+
+struct CachedReceipt {
+    agent: Pubkey,
+    payer: Pubkey,
+    mint: Pubkey,
+    service_id: [u8; 32],
+    amount: u64,
+    timestamp: i64,
+    verified_at: Instant,
+}
+
+impl From<&X402PaymentRecord> for CachedReceipt {
+    fn from(record: &X402PaymentRecord) -> Self {
+        Self {
+            agent: record.agent,
+            payer: record.payer,
+            mint: record.mint,
+            service_id: record.service_id,
+            amount: record.amount,
+            timestamp: record.timestamp,
+            verified_at: Instant::now(),
+        }
+    }
+}
+
+/// Verifies payment records over RPC and caches settled ones. Each receipt
+/// is accepted once, by its payer answering a challenge this verifier issued.
+pub struct ReceiptVerifier {
+    rpc: RpcClient,
+    config: X402MiddlewareConfig,
+    cache: Mutex<HashMap<Pubkey, CachedReceipt>>,
+    /// Outstanding challenges and when they were issued
+    challenges: Mutex<HashMap<Hash, Instant>>,
+    /// Accepted records and their payment timestamps
+    consumed: Mutex<HashMap<Pubkey, i64>>,
+}
+
+impl ReceiptVerifier {
+    pub fn new(config: X402MiddlewareConfig) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(
+                config.rpc_url.clone(),
+                CommitmentConfig::confirmed(),
+            ),
+            config,
+            cache: Mutex::new(HashMap::new()),
+            challenges: Mutex::new(HashMap::new()),
+            consumed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &X402MiddlewareConfig {
+        &self.config
+    }
+
+    /// Issue a single-use challenge for the payer to sign
+    pub fn issue_challenge(&self) -> Hash {
+        let challenge = Hash::new_from_array(rand::random());
+        let mut challenges = self.challenges.lock().unwrap();
+        let ttl = self.config.challenge_ttl;
+        challenges.retain(|_, issued_at| issued_at.elapsed() < ttl);
+        challenges.insert(challenge, Instant::now());
+        challenge
+    }
+
+    /// Check that `proof` pays for `route` and is signed by the record's
+    /// payer, then consume the record
+    pub async fn verify(&self, proof: &PaymentProof, route: &RoutePrice) -> Result<(), PaymentError> {
+        let record_key: Pubkey = proof.record.trim().parse().map_err(|_| PaymentError::Malformed)?;
+        let challenge: Hash = proof.challenge.trim().parse().map_err(|_| PaymentError::Malformed)?;
+        let signature: Signature = proof.signature.trim().parse().map_err(|_| PaymentError::Malformed)?;
+
+        // The challenge is spent by any attempt, so a failed signature can't
+        // be retried against it
+        self.take_challenge(&challenge)?;
+        if self.consumed.lock().unwrap().contains_key(&record_key) {
+            return Err(PaymentError::AlreadyUsed);
+        }
+
+        let (payer, timestamp) = match self.check_cache(&record_key, route) {
+            Some(result) => result?,
+            None => {
+                let record = self.fetch_record(&record_key).await?;
+                self.check_record(&record, route)?;
+                let receipt = CachedReceipt::from(&record);
+                let entry = (receipt.payer, receipt.timestamp);
+                self.cache.lock().unwrap().insert(record_key, receipt);
+                entry
+            }
+        };
+
+        if !signature.verify(payer.as_ref(), &challenge_message(&challenge, &record_key)) {
+            return Err(PaymentError::BadSignature);
+        }
+        self.consume(record_key, timestamp)
+    }
+
+    fn take_challenge(&self, challenge: &Hash) -> Result<(), PaymentError> {
+        let issued_at = self
+            .challenges
+            .lock()
+            .unwrap()
+            .remove(challenge)
+            .ok_or(PaymentError::BadChallenge)?;
+        if issued_at.elapsed() >= self.config.challenge_ttl {
+            return Err(PaymentError::BadChallenge);
+        }
+        Ok(())
+    }
+
+    /// Mark the record used; records past `max_receipt_age` are dropped from
+    /// the set, since they are rejected as expired anyway
+    fn consume(&self, record_key: Pubkey, timestamp: i64) -> Result<(), PaymentError> {
+        let mut consumed = self.consumed.lock().unwrap();
+        if let Some(max_age) = self.config.max_receipt_age {
+            let now = unix_now();
+            consumed.retain(|_, paid_at| now.saturating_sub(*paid_at) <= max_age.as_secs() as i64);
+        }
+        if consumed.insert(record_key, timestamp).is_some() {
+            return Err(PaymentError::AlreadyUsed);
+        }
+        Ok(())
+    }
+
+    async fn fetch_record(&self, record_key: &Pubkey) -> Result<X402PaymentRecord, PaymentError> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(record_key, self.rpc.commitment())
+            .await
+            .map_err(|e| PaymentError::Rpc(e.to_string()))?
+            .value
+            .ok_or(PaymentError::NotFound)?;
+        if account.owner != PROGRAM_ID {
+            return Err(PaymentError::NotFound);
+        }
+        decode(record_key, &account.data).map_err(|_| PaymentError::Malformed)
+    }
+
+    fn check_cache(
+        &self,
+        record_key: &Pubkey,
+        route: &RoutePrice,
+    ) -> Option<Result<(Pubkey, i64), PaymentError>> {
+        let mut cache = self.cache.lock().unwrap();
+        let ttl = self.config.cache_ttl;
+        cache.retain(|_, receipt| receipt.verified_at.elapsed() < ttl);
+
+        let receipt = cache.get(record_key)?;
+        Some(
+            self.check_receipt(receipt, route)
+                .map(|()| (receipt.payer, receipt.timestamp)),
+        )
+    }
+
+    fn check_record(
+        &self,
+        record: &X402PaymentRecord,
+        route: &RoutePrice,
+    ) -> Result<(), PaymentError> {
+        self.check_receipt(&CachedReceipt::from(record), route)?;
+        if !matches!(
+            record.status,
+            PaymentStatus::Verified | PaymentStatus::Settled
+        ) {
+            return Err(PaymentError::NotSettled);
+        }
+        Ok(())
+    }
+
+    /// Checks shared by fetched and cached receipts
+    fn check_receipt(&self, receipt: &CachedReceipt, route: &RoutePrice) -> Result<(), PaymentError> {
+        if receipt.agent != self.config.agent {
+            return Err(PaymentError::WrongAgent);
+        }
+        if receipt.service_id != route.service_id {
+            return Err(PaymentError::WrongService);
+        }
+        if receipt.mint != self.config.mint {
+            return Err(PaymentError::WrongMint);
+        }
+        if receipt.amount < route.amount {
+            return Err(PaymentError::Underpaid);
+        }
+        if let Some(max_age) = self.config.max_receipt_age {
+            if unix_now().saturating_sub(receipt.timestamp) > max_age.as_secs() as i64 {
+                return Err(PaymentError::Expired);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(i64::MAX)
+}
@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
 
+/// Program errors, in non-overlapping code ranges: 6000-6999 for the
+/// factory, curves and agent management, 7000-7999 for x402 payments. New
+/// errors go at the end of their range. Checks that compare an actual value
+/// against an expected one log both (`Left` = actual, `Right` = expected).
 #[error_code]
 pub enum AgentFactoryError {
     #[msg("Math operation overflow")]
@@ -43,5 +47,513 @@ pub enum AgentFactoryError {
     
     #[msg("Maximum buy amount exceeded")]
     MaxBuyExceeded,
+    
+    #[msg("Invalid DCA schedule parameters")]
+    InvalidDcaSchedule,
+    
+    #[msg("DCA interval not yet due")]
+    DcaNotDue,
+    
+    #[msg("DCA schedule already completed")]
+    DcaCompleted,
+    
+    #[msg("Invalid bonding curve configuration")]
+    InvalidCurveConfig,
+    
+    #[msg("Invalid presale parameters")]
+    InvalidPresaleConfig,
+    
+    #[msg("Trading is paused while a presale is active")]
+    PresaleActive,
+    
+    #[msg("Presale is not accepting commitments")]
+    PresaleClosed,
+    
+    #[msg("Presale has not ended yet")]
+    PresaleNotEnded,
+    
+    #[msg("Presale already finalized")]
+    PresaleFinalized,
+    
+    #[msg("Presale not finalized yet")]
+    PresaleNotFinalized,
+    
+    #[msg("Presale missed its soft cap; refund the commitment instead")]
+    PresaleFailed,
+    
+    #[msg("Presale met its soft cap; claim the tokens instead")]
+    PresaleSucceeded,
+    
+    #[msg("Invalid refund guarantee")]
+    InvalidRefundGuarantee,
+    
+    #[msg("Graduation deadline missed; only redemptions are allowed")]
+    RedemptionOpen,
+    
+    #[msg("Redemptions are not open for this agent")]
+    RedemptionNotOpen,
+    
+    #[msg("Invalid lock amount")]
+    InvalidLockAmount,
+    
+    #[msg("Invalid unlock time")]
+    InvalidUnlockTime,
+    
+    #[msg("Tokens are still locked")]
+    TokensStillLocked,
+    
+    #[msg("Invalid staking pool configuration")]
+    InvalidStakingConfig,
+    
+    #[msg("Invalid stake amount")]
+    InvalidStakeAmount,
+    
+    #[msg("Insufficient staked balance")]
+    InsufficientStake,
+    
+    #[msg("No rewards to claim")]
+    NoRewards,
+    
+    #[msg("Stake is locked")]
+    StakeLocked,
+    
+    #[msg("Invalid governance configuration")]
+    InvalidGovernanceConfig,
+    
+    #[msg("Invalid proposal")]
+    InvalidProposal,
+    
+    #[msg("Insufficient voting power")]
+    InsufficientVotingPower,
+    
+    #[msg("Voting is closed")]
+    VotingClosed,
+    
+    #[msg("Voting has not ended yet")]
+    VotingNotEnded,
+    
+    #[msg("Proposal did not pass")]
+    ProposalNotPassed,
+    
+    #[msg("Proposal already executed")]
+    ProposalExecuted,
+    
+    #[msg("Invalid treasury configuration")]
+    InvalidTreasuryConfig,
+    
+    #[msg("Insufficient treasury funds")]
+    InsufficientTreasuryFunds,
+    
+    #[msg("Invalid admin action")]
+    InvalidAdminAction,
+    
+    #[msg("Timelock has not expired yet")]
+    TimelockNotExpired,
+    
+    #[msg("Invalid insurance amount")]
+    InvalidInsuranceAmount,
+    
+    #[msg("Insufficient insurance funds")]
+    InsufficientInsuranceFunds,
+    
+    #[msg("Invalid platform token stake")]
+    InvalidPlatformStake,
+    
+    #[msg("Invalid creation fee payment")]
+    InvalidFeePayment,
+    
+    #[msg("Invalid early buyer rewards configuration")]
+    InvalidEarlyBuyerRewards,
+    
+    #[msg("Not one of the rewarded early buyers")]
+    NotEarlyBuyer,
+    
+    #[msg("Early buyer claim window has closed")]
+    EarlyBuyerClaimsClosed,
+    
+    #[msg("Early buyer claim window is still open")]
+    EarlyBuyerClaimsOpen,
+    
+    #[msg("Protocol-owned liquidity accounts missing or invalid")]
+    ProtocolLiquidityRequired,
+    
+    #[msg("Insufficient protocol-owned liquidity")]
+    InsufficientProtocolLiquidity,
+    
+    #[msg("Agent has not graduated")]
+    NotGraduated,
+    
+    #[msg("Invalid reward schedule")]
+    InvalidRewardSchedule,
+    
+    #[msg("Invalid migration ratio")]
+    InvalidMigrationRatio,
+    
+    #[msg("Invalid migration amount")]
+    InvalidMigrationAmount,
+    
+    #[msg("Migration vault cannot cover this swap")]
+    InsufficientMigrationFunds,
+    
+    #[msg("Signer does not hold the agent's ownership NFT")]
+    NotOwnershipHolder,
+    
+    #[msg("Invalid creator royalty")]
+    InvalidRoyalty,
+    
+    #[msg("Invalid index basket configuration")]
+    InvalidBasket,
+    
+    #[msg("Basket constituent accounts missing or invalid")]
+    InvalidBasketAccounts,
+    
+    #[msg("Invalid basket amount")]
+    InvalidBasketAmount,
+    
+    #[msg("Not enough price history for this TWAP window")]
+    TwapUnavailable,
+    
+    #[msg("Cannot sell in the same slot as a buy")]
+    SameSlotSell,
+    
+    #[msg("Invalid keeper task target")]
+    InvalidKeeperTask,
+    
+    #[msg("Invalid keeper tip or deposit amount")]
+    InvalidKeeperAmount,
+    
+    #[msg("No work done since the last keeper tip")]
+    NoKeeperWork,
+    
+    #[msg("Keeper task deposit cannot cover the tip")]
+    KeeperTaskUnfunded,
+    
+    #[msg("Invalid provider bond amount")]
+    InvalidBondAmount,
+    
+    #[msg("Provider bond is locked by open disputes")]
+    BondLocked,
+    
+    #[msg("Provider bond cannot cover the slash")]
+    InsufficientBond,
+    
+    #[msg("Signer did not make this payment")]
+    NotPaymentPayer,
+    
+    #[msg("Dispute is not open")]
+    DisputeNotOpen,
+    
+    #[msg("Signer is not on the arbitration council")]
+    NotArbiter,
+    
+    #[msg("Arbiter has already voted on this dispute")]
+    ArbiterAlreadyVoted,
+    
+    #[msg("Insurance must be bought with the payment, before any dispute")]
+    InsuranceWindowClosed,
+    
+    #[msg("No insured shortfall to pay")]
+    NoInsuredShortfall,
+    
+    #[msg("Model is not on the factory allowlist")]
+    ModelNotAllowed,
+    
+    #[msg("Invalid or duplicate agent tag")]
+    InvalidTag,
+    
+    #[msg("Agent has too many tags")]
+    TooManyTags,
+    
+    #[msg("Agent does not have this tag")]
+    TagNotFound,
+    
+    #[msg("Agent was renamed too recently")]
+    RenameCooldown,
+    
+    #[msg("Curve reserves do not balance with the agent's lamports")]
+    ReserveInvariantViolated,
+    
+    #[msg("Invalid per-buy cap")]
+    InvalidMaxBuy,
+    
+    #[msg("Too many agents created recently, try again later")]
+    CreationRateLimited,
+    
+    #[msg("Invalid creation rate limit")]
+    InvalidCreationRateLimit,
+    
+    #[msg("Name or symbol is reserved for another owner")]
+    NameReserved,
+    
+    #[msg("Agent must be revealed in a later slot than its commitment")]
+    RevealTooEarly,
+    
+    #[msg("Revealed agent fields do not match the commitment")]
+    CommitmentMismatch,
+    
+    #[msg("Listing reserve must be 0.01-10% of supply, releasable at most a year after graduation")]
+    InvalidListingReserve,
+    
+    #[msg("Listing reserve is locked until graduation plus its release delay")]
+    ListingReserveLocked,
+    
+    #[msg("Release exceeds the listing reserve's remaining tokens")]
+    ListingReserveExceeded,
+    
+    #[msg("Graduation batch accounts are missing, mismatched or exceed 8 agents")]
+    InvalidGraduationBatch,
+    
+    #[msg("Fee override exceeds the standard fees or charges a waived creator fee")]
+    InvalidFeeOverride,
+    
+    // x402 payments (7000-7999)
+    #[msg("Payment amount is below minimum")]
+    PaymentTooLow = 1000,
+    
+    #[msg("Payment amount exceeds maximum")]
+    PaymentTooHigh,
+    
+    #[msg("X402 payments not enabled for this agent")]
+    PaymentsNotEnabled,
+    
+    #[msg("Invalid payment signature")]
+    InvalidPaymentSignature,
+    
+    #[msg("Payment has expired")]
+    PaymentExpired,
+    
+    #[msg("Nonce mismatch - possible replay attack")]
+    NonceMismatch,
+    
+    #[msg("Nonce overflow")]
+    NonceOverflow,
+    
+    
+    #[msg("Service timeout exceeded")]
+    ServiceTimeout,
+    
+    #[msg("Invalid service identifier")]
+    InvalidServiceId,
+    
+    #[msg("Payment already settled")]
+    PaymentAlreadySettled,
+    
+    #[msg("Insufficient payment amount")]
+    InsufficientPayment,
+    
+    #[msg("Caller is not the calling agent's creator")]
+    UnauthorizedCaller,
+    
+    #[msg("Token account is not owned by the payment recipient")]
+    WrongRecipientAccount,
+    
+    #[msg("Service params exceed 1024 bytes")]
+    ParamsTooLarge,
+    
+    #[msg("Agent has no attestation verifier")]
+    AttestationNotEnabled,
+    
+    #[msg("Signer is not the agent's attestation verifier")]
+    NotAttestationVerifier,
+    
+    #[msg("Payment is not awaiting attestation")]
+    PaymentNotPending,
+    
+    #[msg("Service timeout has not passed yet")]
+    PaymentNotExpired,
+    
+    #[msg("Metered services must be paid into escrow")]
+    MeteringRequiresEscrow,
+    
+    #[msg("Payment is not metered")]
+    PaymentNotMetered,
+    
+    #[msg("Metered payments settle by reported usage")]
+    PaymentIsMetered,
+    
+    #[msg("Quote expiry must be in the future and within 24 hours")]
+    InvalidQuoteExpiry,
+    
+    #[msg("Quote has expired")]
+    QuoteExpired,
+    
+    #[msg("Quote was already used")]
+    QuoteUsed,
+    
+    #[msg("Payment does not match the quote")]
+    QuoteMismatch,
+    
+    #[msg("Affiliate share exceeds 50%")]
+    AffiliateFeeTooHigh,
+    
+    #[msg("Affiliate token account must belong to the affiliate")]
+    WrongAffiliateAccount,
+    
+    #[msg("X402 payments to this agent are frozen by the factory authority")]
+    X402Frozen,
+    
+    #[msg("Recipient grace window exceeds 7 days or the recipient is unchanged")]
+    InvalidRecipientRotation,
+    
+    #[msg("Revenue split must have at most 4 distinct other agents and route at most 50%")]
+    InvalidRevenueSplit,
+    
+    #[msg("Revenue split treasury accounts must match the split's worker agents")]
+    InvalidRevenueSplitAccounts,
+    
+    #[msg("Agent routes revenue to other agents; pass its revenue split")]
+    RevenueSplitRequired,
+    
+    #[msg("Refund share exceeds 100%")]
+    InvalidRefundBps,
+    
+    #[msg("Chargeback window exceeds 90 days")]
+    InvalidChargebackWindow,
+    
+    #[msg("Payment's chargeback window has closed")]
+    ChargebackWindowClosed,
+    
+    #[msg("Payment is still disputable or was already finalized")]
+    PaymentNotFinalizable,
+    
+    #[msg("Payment has an open dispute")]
+    PaymentDisputeOpen,
+    
+    #[msg("Service has been sunset by the provider")]
+    ServiceSunset,
+    
+    #[msg("Sunset must not be in the past")]
+    InvalidSunset,
+    
+    #[msg("Agent already has 4 deprecated services")]
+    TooManyDeprecatedServices,
+    
+    #[msg("Stream rate and duration must be non-zero and within limits")]
+    InvalidStreamParams,
+    
+    #[msg("Stream has been cancelled")]
+    StreamCancelled,
+    
+    #[msg("Stream has already ended")]
+    StreamEnded,
+    
+    #[msg("Nothing streamed to withdraw")]
+    NothingStreamed,
+    
+    #[msg("X402 top-up share must not exceed 10000 bps")]
+    InvalidTopUpBps,
+    
+    #[msg("Caller token account is not the caller agent's x402 treasury")]
+    WrongX402Treasury,
+    
+    #[msg("Revenue deposit is missing accounts for its pool")]
+    InvalidRevenueDeposit,
+    
+    #[msg("Revenue deposit must be greater than zero")]
+    InvalidRevenueAmount,
+    
+    #[msg("Price notice must be between 0 and 30 days")]
+    InvalidPriceNotice,
+    
+    #[msg("Terms need a price and an expiry in the future and within 24 hours")]
+    InvalidTerms,
+    
+    #[msg("Terms were not accepted by the target agent")]
+    TermsNotAccepted,
+    
+    #[msg("Terms have expired")]
+    TermsExpired,
+    
+    #[msg("Terms were already used")]
+    TermsUsed,
+    
+    #[msg("Payment does not match the agreed terms")]
+    TermsMismatch,
+    
+    #[msg("Job milestones, service or deadline invalid")]
+    InvalidJobParams,
+    
+    #[msg("Milestone does not exist or is in the wrong state")]
+    InvalidMilestone,
+    
+    #[msg("Milestone review period has not ended")]
+    ReviewPeriodActive,
+    
+    #[msg("Milestone review period has ended")]
+    ReviewPeriodEnded,
+    
+    #[msg("Another milestone of this job is under dispute")]
+    MilestoneDisputeOpen,
+    
+    #[msg("Job deadline has not passed")]
+    JobNotExpired,
+    
+    #[msg("Job has unsettled milestones")]
+    JobNotSettled,
+    
+    #[msg("Work queue workers must be unique, non-default and at most 8")]
+    InvalidQueueWorkers,
+    
+    #[msg("Work queue has no workers")]
+    NoQueueWorkers,
+    
+    #[msg("Signer is not a worker of this queue")]
+    NotQueueWorker,
+    
+    #[msg("Task is claimed, completed or withdrawn")]
+    TaskNotClaimable,
+    
+    #[msg("Task is not claimed by this worker")]
+    TaskNotClaimed,
+    
+    #[msg("Task price is above its max price")]
+    InvalidTaskPrice,
+    
+    #[msg("Trial deposit requires a service timeout and must be within the max payment")]
+    InvalidTrialDeposit,
+    
+    #[msg("Agent doesn't offer trial sessions")]
+    TrialsNotOffered,
+    
+    #[msg("Trial session has expired")]
+    TrialSessionExpired,
+    
+    #[msg("Trial session hasn't expired yet")]
+    TrialSessionActive,
+    
+    #[msg("Signer is not the factory's reputation attestor")]
+    NotReputationAttestor,
+    
+    #[msg("Invalid reputation attestation")]
+    InvalidReputationAttestation,
+    
+    #[msg("Agent already has attestations from the maximum number of sources")]
+    TooManyReputationSources,
+    
+    #[msg("Rating must be 1 to 5 stars")]
+    InvalidRating,
+    
+    #[msg("Only a settled payment with an amount kept can be rated")]
+    PaymentNotRatable,
+    
+    #[msg("The agent's operator can't rate its own services")]
+    SelfRating,
+    
+    #[msg("No account type has this discriminator")]
+    UnknownAccountLayout,
+    
+    #[msg("Award exceeds the amount the claimant asked for")]
+    AwardExceedsClaim,
+    
+    #[msg("The agent can only be handed to a different wallet")]
+    InvalidNewCreator,
+    
+    #[msg("Payment mint has no lamport value: only wrapped SOL and quote mint payments at a set rate are bonded")]
+    PaymentMintNotValued,
+    
+    #[msg("Claim exceeds the lamport value the provider kept of the disputed payment")]
+    ClaimExceedsPayment,
+    
+    #[msg("Coverage exceeds the lamport value of the insured payment")]
+    CoverageExceedsPayment,
 }
-
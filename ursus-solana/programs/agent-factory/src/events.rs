@@ -0,0 +1,341 @@
+use anchor_lang::prelude::*;
+use crate::state::{AdminAction, AdminActionStage, CreatorFeeMode, CurveReserves, RevenuePool, TaskStatus, TrialStatus};
+
+/// Event emitted on every bonding curve buy or sell
+#[event]
+pub struct TradeEvent {
+    pub agent: Pubkey,
+    pub trader: Pubkey,
+    pub is_buy: bool,
+    /// Gross SOL amount of the trade (before fees)
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub platform_fee: u64,
+    pub creator_fee: u64,
+    /// Spot price after the trade
+    pub price: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+    /// Per-agent counter shared with `PaymentEvent`; consumers order by it
+    /// and treat a gap as a missed event
+    pub sequence: u64,
+    pub reserves_before: CurveReserves,
+    pub reserves_after: CurveReserves,
+    /// Spot price move caused by the trade (bps, either direction)
+    pub price_impact_bps: u64,
+}
+
+/// Event emitted when a payment for an agent service is processed
+#[event]
+pub struct PaymentEvent {
+    pub agent: Pubkey,
+    pub payer: Pubkey,
+    pub payment_record: Pubkey,
+    pub amount: u64,
+    pub service_id: [u8; 32],
+    pub nonce: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+    /// Per-agent counter shared with `TradeEvent`
+    pub sequence: u64,
+    /// Client-supplied id correlating the payment with its off-chain request
+    pub request_id: [u8; 16],
+    /// Paid to the provider on top of `amount` to expedite the request
+    pub priority_tip: u64,
+}
+
+/// Event emitted when a holder snapshot is recorded; off-chain distributions
+/// pin their balances to `slot`
+#[event]
+pub struct SnapshotEvent {
+    pub agent: Pubkey,
+    pub snapshot: Pubkey,
+    pub snapshot_id: u64,
+    pub slot: u64,
+    /// Mint supply at the snapshot
+    pub circulating_supply: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a timelocked admin action is queued, executed or
+/// cancelled. Queueing announces the change `eta - timestamp` seconds ahead.
+#[event]
+pub struct AdminActionEvent {
+    pub action_id: u64,
+    pub action: AdminAction,
+    pub stage: AdminActionStage,
+    pub eta: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the insurance fund pays a claim
+#[event]
+pub struct InsuranceClaimEvent {
+    pub claim_id: u64,
+    /// Agent whose exploit or rug the claim covers
+    pub agent: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a creator renames an agent
+#[event]
+pub struct AgentRenamedEvent {
+    pub agent: Pubkey,
+    pub old_name: String,
+    pub old_symbol: String,
+    pub new_name: String,
+    pub new_symbol: String,
+    /// Rename fee paid to the platform treasury (lamports)
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a creator hands an agent and its ownership NFT to a
+/// new creator
+#[event]
+pub struct AgentOwnershipTransferredEvent {
+    pub agent: Pubkey,
+    pub previous_creator: Pubkey,
+    pub new_creator: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an agent registers (or clears) its x402 webhook.
+/// Facilitators deliver service-call notifications to the endpoint whose URL
+/// hashes to `url_hash` and verify responses against `signer`.
+#[event]
+pub struct WebhookEvent {
+    pub agent: Pubkey,
+    /// SHA-256 of the callback URL (zero = no webhook)
+    pub url_hash: [u8; 32],
+    pub signer: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an agent is launched by any `create_agent*` instruction
+#[event]
+pub struct AgentCreatedEvent {
+    pub agent: Pubkey,
+    pub agent_id: u64,
+    pub mint: Pubkey,
+    pub ownership_mint: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    /// Creation fee paid, in lamports or in `fee_mint` units
+    pub creation_fee: u64,
+    /// Quote mint the fee was paid in (default = SOL)
+    pub fee_mint: Pubkey,
+    /// Who the agent's creator fee goes to
+    pub creator_fee_mode: CreatorFeeMode,
+    pub timestamp: i64,
+}
+
+/// Fees paid out by `FeesClaimedEvent`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeClaimKind {
+    /// Streamed creator fees, claimed by the current creator
+    CreatorFees,
+    /// Royalties, claimed by the original creator
+    Royalties,
+}
+
+/// Event emitted when a creator or original creator claims fees held by an
+/// agent
+#[event]
+pub struct FeesClaimedEvent {
+    pub agent: Pubkey,
+    pub recipient: Pubkey,
+    pub kind: FeeClaimKind,
+    /// Lamports paid out
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the factory authority freezes or thaws an agent's x402
+/// payments
+#[event]
+pub struct X402FreezeEvent {
+    pub agent: Pubkey,
+    pub authority: Pubkey,
+    /// Whether payments are now frozen
+    pub frozen: bool,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an agent's creator rotates its x402 payment recipient.
+/// `old_recipient` is still paid until `grace_ends_at`.
+#[event]
+pub struct X402RecipientRotatedEvent {
+    pub agent: Pubkey,
+    pub old_recipient: Pubkey,
+    pub new_recipient: Pubkey,
+    pub grace_ends_at: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an agent graduates, individually or in a batch
+#[event]
+pub struct AgentGraduatedEvent {
+    pub agent: Pubkey,
+    pub creator: Pubkey,
+    /// Curve SOL reserves left for DEX liquidity (lamports)
+    pub sol_reserves: u64,
+    /// Curve tokens left for DEX liquidity
+    pub token_reserves: u64,
+    /// Unsold curve tokens burned (0 unless `burn_remaining_at_graduation`)
+    pub tokens_burned: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the factory authority overrides the fees charged on an
+/// agent's trades
+#[event]
+pub struct AgentFeeOverrideEvent {
+    pub agent: Pubkey,
+    pub authority: Pubkey,
+    pub platform_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an agent's creator deprecates one of its services.
+/// New payments for it are rejected from `sunset_ts`; 0 withdraws the
+/// deprecation.
+#[event]
+pub struct ServiceDeprecatedEvent {
+    pub agent: Pubkey,
+    pub service_id: [u8; 32],
+    pub sunset_ts: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when revenue earned outside x402 is deposited into one of
+/// an agent's pools
+#[event]
+pub struct RevenueDepositedEvent {
+    pub agent: Pubkey,
+    pub depositor: Pubkey,
+    pub pool: RevenuePool,
+    /// Token deposited (`Pubkey::default()` = SOL)
+    pub mint: Pubkey,
+    pub amount: u64,
+    /// Depositor-chosen tag attributing the revenue to its source
+    pub source: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Event emitted when an agent's x402 payment settings are configured or
+/// updated. On first configuration the `old_` values are all zero.
+#[event]
+pub struct X402ConfigUpdatedEvent {
+    pub agent: Pubkey,
+    pub authority: Pubkey,
+    pub old_enabled: bool,
+    pub new_enabled: bool,
+    pub old_min_payment_amount: u64,
+    pub new_min_payment_amount: u64,
+    pub old_max_payment_amount: u64,
+    pub new_max_payment_amount: u64,
+    pub old_service_timeout_seconds: u64,
+    pub new_service_timeout_seconds: u64,
+    /// When `new_min_payment_amount` takes effect: later than `timestamp`
+    /// for an increase given notice under `price_notice_secs`
+    pub min_payment_effective_at: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when one agent proposes terms for another agent's service,
+/// and again when the target agent's creator accepts them
+#[event]
+pub struct ServiceTermsEvent {
+    pub agreement: Pubkey,
+    pub caller_agent: Pubkey,
+    pub target_agent: Pubkey,
+    pub service_id: [u8; 32],
+    pub amount: u64,
+    pub expires_at: i64,
+    pub accepted: bool,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a task enters an agent's work queue, is claimed by a
+/// worker, completed or withdrawn, so workers can follow the queue without
+/// polling it
+#[event]
+pub struct WorkTaskEvent {
+    pub agent: Pubkey,
+    pub task: Pubkey,
+    pub task_id: u64,
+    pub requester: Pubkey,
+    /// Default key while open or once withdrawn unclaimed
+    pub worker: Pubkey,
+    pub status: TaskStatus,
+    pub params_hash: [u8; 32],
+    pub max_price: u64,
+    /// Charged on completion, 0 otherwise
+    pub price: u64,
+    /// Delivered on completion, zeroes otherwise
+    pub result_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Event emitted when a caller opens a trial session against a refundable
+/// deposit, ends it and is refunded, or abandons it and forfeits the
+/// deposit to the provider
+#[event]
+pub struct TrialSessionEvent {
+    pub agent: Pubkey,
+    pub session: Pubkey,
+    pub caller: Pubkey,
+    pub service_id: [u8; 32],
+    pub deposit: u64,
+    pub status: TrialStatus,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when reputation from an external system is posted for an
+/// agent, or withdrawn with a zero weight
+#[event]
+pub struct ReputationAttestedEvent {
+    pub agent: Pubkey,
+    pub attestor: Pubkey,
+    /// Lowercase source tag, zero padded
+    pub source: [u8; 20],
+    pub score_bps: u16,
+    pub weight_bps: u16,
+    pub evidence_hash: [u8; 32],
+    /// Agent's imported score after the attestation
+    pub reputation_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a paying caller rates a service, weighted by the
+/// amount they paid
+#[event]
+pub struct ServiceRatedEvent {
+    pub agent: Pubkey,
+    pub payment_record: Pubkey,
+    pub rater: Pubkey,
+    pub service_id: [u8; 32],
+    pub stars: u8,
+    pub weight: u64,
+    /// Agent's payment-weighted rating after this one (bps of 5 stars)
+    pub rating_bps: u16,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an agent's accrued share of platform fees is paid to
+/// the factory's public goods recipient
+#[event]
+pub struct PublicGoodsFeesCollectedEvent {
+    pub agent: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// Paid to public goods across all agents so far
+    pub total_collected: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::events::ServiceTermsEvent;
+use crate::state::{Agent, ServiceAgreement};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptTerms<'info> {
+    pub target_agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        has_one = target_agent
+    )]
+    pub agreement: Account<'info, ServiceAgreement>,
+
+    /// Target agent's creator
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = target_agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Accept terms another agent proposed for one of the creator's agents'
+/// services. `amount` must match the proposed price, so terms re-proposed in
+/// the meantime aren't accepted by mistake.
+pub fn handler(ctx: Context<AcceptTerms>, amount: u64) -> Result<()> {
+    let agreement = &mut ctx.accounts.agreement;
+    let now = Clock::get()?.unix_timestamp;
+    require!(!agreement.used, AgentFactoryError::TermsUsed);
+    require!(now <= agreement.expires_at, AgentFactoryError::TermsExpired);
+    require!(agreement.amount == amount, AgentFactoryError::TermsMismatch);
+    agreement.accepted = true;
+
+    trace!("Terms accepted!");
+    trace!("Caller: {}, Amount: {}", agreement.caller_agent, amount);
+
+    emit_cpi!(ServiceTermsEvent {
+        agreement: agreement.key(),
+        caller_agent: agreement.caller_agent,
+        target_agent: agreement.target_agent,
+        service_id: agreement.service_id,
+        amount,
+        expires_at: agreement.expires_at,
+        accepted: true,
+        timestamp: now,
+    });
+
+    Ok(())
+}
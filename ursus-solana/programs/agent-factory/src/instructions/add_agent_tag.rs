@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, MAX_AGENT_TAGS};
+
+#[derive(Accounts)]
+pub struct AddAgentTag<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Tag an agent for discovery, up to `MAX_AGENT_TAGS` tags
+pub fn handler(ctx: Context<AddAgentTag>, tag: &str) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    require!(agent.tags.len() < MAX_AGENT_TAGS, AgentFactoryError::TooManyTags);
+    require!(
+        Agent::tag_valid(tag) && !agent.tags.iter().any(|t| t == tag),
+        AgentFactoryError::InvalidTag
+    );
+    agent.tags.push(tag.to_string());
+
+    trace!("Tag added: {}", tag);
+    trace!("Tags: {}", agent.tags.join(", "));
+
+    Ok(())
+}
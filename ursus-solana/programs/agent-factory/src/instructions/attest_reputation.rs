@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+use crate::events::ReputationAttestedEvent;
+use crate::state::{
+    Agent, AgentFactory, AgentReputation, ReputationAttestation, REPUTATION_SOURCE_LEN,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AttestReputation<'info> {
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        constraint = factory.is_reputation_attestor(&attestor.key()) @ AgentFactoryError::NotReputationAttestor
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    pub agent: Account<'info, Agent>,
+
+    /// Created on first attestation
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + AgentReputation::INIT_SPACE,
+        seeds = [b"reputation", agent.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, AgentReputation>,
+
+    /// Factory authority or reputation attestor; a PDA authority signs via
+    /// CPI
+    pub attestor: Signer<'info>,
+
+    /// Pays the reputation account's rent on first attestation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Post the reputation `source` reports for an agent, backed by the hash of
+/// the source's signed record, replacing the source's previous attestation.
+/// A zero weight withdraws the source.
+pub fn handler(
+    ctx: Context<AttestReputation>,
+    source: [u8; REPUTATION_SOURCE_LEN],
+    score_bps: u16,
+    weight_bps: u16,
+    evidence_hash: [u8; 32],
+) -> Result<()> {
+    require!(
+        ReputationAttestation::source_valid(&source)
+            && score_bps as u64 <= BPS_DENOMINATOR
+            && weight_bps as u64 <= BPS_DENOMINATOR,
+        AgentFactoryError::InvalidReputationAttestation
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let attestor = ctx.accounts.attestor.key();
+    let reputation = &mut ctx.accounts.reputation;
+    reputation.agent = ctx.accounts.agent.key();
+    reputation.bump = ctx.bumps.reputation;
+    require!(
+        reputation.upsert(ReputationAttestation {
+            source,
+            score_bps,
+            weight_bps,
+            evidence_hash,
+            attestor,
+            attested_at: now,
+        }),
+        AgentFactoryError::TooManyReputationSources
+    );
+
+    let reputation_bps = reputation.score_bps();
+    trace!("Reputation attested!");
+    trace!("Agent: {}, Score: {}, Weight: {}, Reputation: {}", reputation.agent, score_bps, weight_bps, reputation_bps);
+
+    emit_cpi!(ReputationAttestedEvent {
+        agent: reputation.agent,
+        attestor,
+        source,
+        score_bps,
+        weight_bps,
+        evidence_hash,
+        reputation_bps,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -1,10 +1,180 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use anchor_spl::token::{self, MintTo};
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
 use crate::errors::AgentFactoryError;
+use crate::events::TradeEvent;
+use crate::state::{deposit_to_vault, Agent, AgentFactory, BondingCurve, BuyerRecord, PriceHistory, StakePosition, TrendingLeaderboard};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BuyTokens<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        address = agent.mint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Wallet receiving the minted tokens (pass the buyer to buy for yourself)
+    pub recipient: AccountInfo<'info>,
+
+    /// Recipient's buyer marker, created on their first buy of this agent
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + BuyerRecord::INIT_SPACE,
+        seeds = [b"buyer_record", agent.key().as_ref(), recipient.key().as_ref()],
+        bump
+    )]
+    pub buyer_record: Box<Account<'info, BuyerRecord>>,
+
+    /// CHECK: Creator receives fees
+    #[account(
+        mut,
+        address = agent.creator
+    )]
+    pub creator: AccountInfo<'info>,
+
+    /// CHECK: Platform treasury receives fees
+    #[account(
+        mut,
+        address = factory.platform_treasury
+    )]
+    pub platform_treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Factory singleton, for the platform treasury and, with
+    /// `platform_stake`, the buyer's platform token fee discount
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Buyer's stake position in the platform token's staking pool
+    pub platform_stake: Option<Box<Account<'info, StakePosition>>>,
+
+    /// Agent's candle accumulator; updated when passed
+    #[account(
+        mut,
+        seeds = [b"price_history", agent.key().as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Option<Box<Account<'info, PriceHistory>>>,
+
+    /// Factory trending leaderboard; updated when passed
+    #[account(
+        mut,
+        seeds = [b"trending"],
+        bump = trending.bump
+    )]
+    pub trending: Option<Box<Account<'info, TrendingLeaderboard>>>,
+
+    /// Agent's curve SOL vault
+    #[account(
+        mut,
+        seeds = [b"sol_vault", agent.key().as_ref()],
+        bump = agent.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Agent's platform fee vault
+    #[account(
+        mut,
+        seeds = [b"platform_fee_vault", agent.key().as_ref()],
+        bump = agent.platform_fee_vault_bump
+    )]
+    pub platform_fee_vault: SystemAccount<'info>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Platform fee discount earned by `trader` through their platform token
+/// stake; 0 unless the stake position is passed
+pub fn platform_fee_discount(
+    factory: &AgentFactory,
+    platform_stake: &Option<Box<Account<StakePosition>>>,
+    trader: &Pubkey,
+) -> Result<u16> {
+    match platform_stake {
+        Some(stake) => factory.fee_discount_bps(stake, trader),
+        None => Ok(0),
+    }
+}
+
+/// Count `buyer` in the agent's unique buyers on their first buy and stamp
+/// the record with the buy slot. `bump` is the record's bump.
+pub fn record_buyer(
+    agent: &mut Account<Agent>,
+    buyer_record: &mut BuyerRecord,
+    buyer: Pubkey,
+    bump: u8,
+    clock: &Clock,
+) -> Result<()> {
+    if buyer_record.agent == Pubkey::default() {
+        agent.unique_buyer_count = agent.unique_buyer_count
+            .checked_add(1)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        
+        buyer_record.agent = agent.key();
+        buyer_record.buyer = buyer;
+        buyer_record.buyer_number = agent.unique_buyer_count;
+        buyer_record.first_buy_at = clock.unix_timestamp;
+        buyer_record.bump = bump;
+    }
+    buyer_record.last_buy_slot = clock.slot;
+    Ok(())
+}
+
+/// Record a buy of `sol_amount` that moved the curve from `curve_before` in
+/// the agent's candles, trending volume, TWAP and last buy slot, and in the
+/// leaderboard when passed
+pub fn record_buy_activity(
+    agent: &mut Account<Agent>,
+    curve_before: &BondingCurve,
+    sol_amount: u64,
+    price_history: &mut Option<Box<Account<PriceHistory>>>,
+    trending: &mut Option<Box<Account<TrendingLeaderboard>>>,
+    clock: &Clock,
+) {
+    if let Some(price_history) = price_history.as_mut() {
+        price_history.record(
+            curve_before.price_nano_u64_at(clock.unix_timestamp),
+            agent.bonding_curve.price_nano_u64_at(clock.unix_timestamp),
+            sol_amount,
+            clock.unix_timestamp,
+        );
+    }
+    let trending_score = agent.record_trending_volume(sol_amount, clock.slot);
+    agent.record_twap_price(clock.unix_timestamp, clock.slot);
+    agent.last_buy_slot = clock.slot;
+    if let Some(trending) = trending.as_mut() {
+        trending.update(agent.key(), trending_score, clock.slot);
+    }
+}
 
 pub fn handler(
-    ctx: Context<crate::BuyTokens>,
+    ctx: Context<BuyTokens>,
     sol_amount: u64,
     min_tokens_out: u64,
 ) -> Result<()> {
@@ -12,44 +182,49 @@ pub fn handler(
 
     // Check if agent is graduated
     require!(!ctx.accounts.agent.is_graduated, AgentFactoryError::AlreadyGraduated);
+    require!(!ctx.accounts.agent.is_presale_active, AgentFactoryError::PresaleActive);
+    require!(
+        !ctx.accounts.agent.is_redeemable(Clock::get()?.unix_timestamp),
+        AgentFactoryError::RedemptionOpen
+    );
 
-    // Calculate tokens to receive using bonding curve
-    let tokens_out = ctx.accounts.agent.bonding_curve.calculate_buy(sol_amount)?;
+    // Calculate tokens to receive and fees (1% platform, 1% creator) using bonding curve;
+    // platform token stakers get a platform fee discount.
+    // Near graduation the buy is partially filled and only `quote.sol_amount` is charged.
+    let discount_bps = platform_fee_discount(
+        &ctx.accounts.factory,
+        &ctx.accounts.platform_stake,
+        &ctx.accounts.buyer.key(),
+    )?;
+    let quote = ctx.accounts.agent.bonding_curve.quote_buy_discounted(sol_amount, discount_bps)?;
+    let requested_sol_amount = sol_amount;
+    let sol_amount = quote.sol_amount;
+    let tokens_out = quote.tokens_out;
+    let platform_fee = quote.platform_fee;
+    let creator_fee = quote.creator_fee;
+    let net_sol_amount = quote.net_sol_amount;
     
-    // Check slippage tolerance
-    require!(tokens_out >= min_tokens_out, AgentFactoryError::SlippageExceeded);
-
-    // Calculate fees (1% platform fee)
-    let platform_fee = sol_amount
-        .checked_mul(100)
-        .ok_or(AgentFactoryError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AgentFactoryError::MathOverflow)?;
-
-    let creator_fee = sol_amount
-        .checked_mul(100)
-        .ok_or(AgentFactoryError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AgentFactoryError::MathOverflow)?;
-
-    let net_sol_amount = sol_amount
-        .checked_sub(platform_fee)
-        .ok_or(AgentFactoryError::MathOverflow)?
-        .checked_sub(creator_fee)
-        .ok_or(AgentFactoryError::MathOverflow)?;
-
-    // Transfer SOL from buyer to agent (bonding curve reserves)
-    let cpi_context = CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        system_program::Transfer {
-            from: ctx.accounts.buyer.to_account_info(),
-            to: ctx.accounts.agent.to_account_info(),
-        },
-    );
-    system_program::transfer(cpi_context, net_sol_amount)?;
+    require!(tokens_out > 0, AgentFactoryError::InsufficientLiquidity);
+
+    // Check slippage tolerance and the per-buy cap
+    require_gte!(tokens_out, min_tokens_out, AgentFactoryError::SlippageExceeded);
+    ctx.accounts.agent.check_max_buy(tokens_out)?;
+
+    // The staking pool's and treasury's shares of the creator fee, and the insurance
+    // fund's and public goods shares of the platform fee, are held in the agent's fee vaults
+    let (creator_fee_to_creator, retained_fee) = ctx.accounts.agent.split_creator_fee(creator_fee, Clock::get()?.unix_timestamp)?;
+    let (platform_fee_to_treasury, retained_platform_fee) = ctx.accounts.agent.split_platform_fee(platform_fee)?;
+
+    // Transfer SOL from buyer to the agent's vaults (bonding curve reserves +
+    // retained fee shares)
+    let buyer_info = ctx.accounts.buyer.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+    deposit_to_vault(&buyer_info, &ctx.accounts.sol_vault, &system_program_info, net_sol_amount, &[])?;
+    deposit_to_vault(&buyer_info, &ctx.accounts.platform_fee_vault, &system_program_info, retained_platform_fee, &[])?;
+    deposit_to_vault(&buyer_info, &ctx.accounts.creator_fee_vault, &system_program_info, retained_fee, &[])?;
 
     // Transfer platform fee
-    if platform_fee > 0 {
+    if platform_fee_to_treasury > 0 {
         let cpi_context = CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
@@ -57,11 +232,11 @@ pub fn handler(
                 to: ctx.accounts.platform_treasury.to_account_info(),
             },
         );
-        system_program::transfer(cpi_context, platform_fee)?;
+        system_program::transfer(cpi_context, platform_fee_to_treasury)?;
     }
 
     // Transfer creator fee
-    if creator_fee > 0 {
+    if creator_fee_to_creator > 0 {
         let cpi_context = CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
             system_program::Transfer {
@@ -69,10 +244,10 @@ pub fn handler(
                 to: ctx.accounts.creator.to_account_info(),
             },
         );
-        system_program::transfer(cpi_context, creator_fee)?;
+        system_program::transfer(cpi_context, creator_fee_to_creator)?;
     }
 
-    // Mint tokens to buyer
+    // Mint tokens to recipient (the buyer unless buying on behalf of another wallet)
     let agent_id_bytes = ctx.accounts.agent.agent_id.to_le_bytes();
     let agent_bump = ctx.accounts.agent.bump;
     let seeds = &[
@@ -84,23 +259,71 @@ pub fn handler(
 
     let cpi_accounts = MintTo {
         mint: ctx.accounts.mint.to_account_info(),
-        to: ctx.accounts.buyer_token_account.to_account_info(),
+        to: ctx.accounts.recipient_token_account.to_account_info(),
         authority: ctx.accounts.agent.to_account_info(),
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
     token::mint_to(cpi_ctx, tokens_out)?;
 
+    // Count the recipient on their first buy
+    record_buyer(
+        &mut ctx.accounts.agent,
+        &mut ctx.accounts.buyer_record,
+        ctx.accounts.recipient.key(),
+        ctx.bumps.buyer_record,
+        &Clock::get()?,
+    )?;
+
     // Update bonding curve reserves
+    let curve_before = ctx.accounts.agent.bonding_curve;
     ctx.accounts.agent.bonding_curve.update_after_buy(net_sol_amount, tokens_out)?;
+    ctx.accounts.agent.check_reserves(
+        &ctx.accounts.sol_vault,
+        &ctx.accounts.platform_fee_vault,
+        &ctx.accounts.creator_fee_vault,
+        &curve_before,
+        Clock::get()?.unix_timestamp,
+    )?;
 
-    msg!("Tokens purchased successfully!");
-    msg!("SOL amount: {}", sol_amount);
-    msg!("Tokens received: {}", tokens_out);
-    msg!("Platform fee: {}", platform_fee);
-    msg!("Creator fee: {}", creator_fee);
-    msg!("New SOL reserves: {}", ctx.accounts.agent.bonding_curve.real_sol_reserves);
-    msg!("New token reserves: {}", ctx.accounts.agent.bonding_curve.real_token_reserves);
+    trace!("Tokens purchased successfully!");
+    trace!("SOL amount: {}", sol_amount);
+    if sol_amount < requested_sol_amount {
+        trace!("Partial fill, unspent SOL: {}", requested_sol_amount - sol_amount);
+    }
+    trace!("Tokens received: {}", tokens_out);
+    trace!("Recipient: {}", ctx.accounts.recipient.key());
+    trace!("Platform fee: {}", platform_fee);
+    trace!("Creator fee: {}", creator_fee);
+    trace!("New SOL reserves: {}", ctx.accounts.agent.bonding_curve.real_sol_reserves);
+    trace!("New token reserves: {}", ctx.accounts.agent.bonding_curve.real_token_reserves);
+
+    let sequence = ctx.accounts.agent.next_event_sequence()?;
+    let clock = Clock::get()?;
+    record_buy_activity(
+        &mut ctx.accounts.agent,
+        &curve_before,
+        sol_amount,
+        &mut ctx.accounts.price_history,
+        &mut ctx.accounts.trending,
+        &clock,
+    );
+    emit_cpi!(TradeEvent {
+        agent: ctx.accounts.agent.key(),
+        trader: ctx.accounts.buyer.key(),
+        is_buy: true,
+        sol_amount,
+        token_amount: tokens_out,
+        platform_fee,
+        creator_fee,
+        price: ctx.accounts.agent.bonding_curve.get_current_price(),
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+        sequence,
+        reserves_before: curve_before.reserves_at(clock.unix_timestamp),
+        reserves_after: ctx.accounts.agent.bonding_curve.reserves_at(clock.unix_timestamp),
+        price_impact_bps: ctx.accounts.agent.bonding_curve.price_impact_bps(&curve_before, clock.unix_timestamp),
+    });
 
     Ok(())
 }
@@ -1,71 +1,236 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer as TokenTransfer};
-use crate::state::{X402Config, X402PaymentRecord, PaymentStatus, X402Error};
+use crate::errors::AgentFactoryError;
+use crate::state::{
+    Agent, ConsumerStats, MeterUnit, PaymentStatus, RevenueSplit, ServiceAgreement, X402Config, X402PaymentRecord,
+};
+use super::set_revenue_split::route_revenue;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount: u64, service_id: [u8; 32], nonce: u64, service_params: Vec<u8>)]
+pub struct CallAgentService<'info> {
+    #[account(mut)]
+    pub caller_agent: Account<'info, Agent>,
+
+    #[account(mut)]
+    pub target_agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", target_agent.key().as_ref()],
+        bump = target_x402_config.bump
+    )]
+    pub target_x402_config: Account<'info, X402Config>,
+
+    #[account(
+        init,
+        payer = caller_authority,
+        space = 8 + X402PaymentRecord::INIT_SPACE,
+        seeds = [
+            b"payment_record",
+            target_agent.key().as_ref(),
+            caller_agent.key().as_ref(),
+            &nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub payment_record: Account<'info, X402PaymentRecord>,
+
+    #[account(mut)]
+    pub caller_authority: Signer<'info>,
+
+    /// Caller authority's account holding the caller agent's ownership NFT
+    #[account(
+        constraint = caller_agent.is_owned_by(&caller_ownership_account, &caller_authority.key()) @ AgentFactoryError::UnauthorizedCaller
+    )]
+    pub caller_ownership_account: Account<'info, TokenAccount>,
+
+    /// Caller's USDC token account, or the caller agent's x402 treasury
+    /// when `caller_x402_config` is passed
+    #[account(mut)]
+    pub caller_token_account: Account<'info, TokenAccount>,
+
+    /// Target's USDC token account
+    #[account(
+        mut,
+        constraint = target_x402_config.accepts_recipient(&target_token_account.owner, Clock::get()?.unix_timestamp) @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub target_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Target agent's revenue split, required while it routes revenue to other
+    /// agents; the worker treasuries' token accounts follow as remaining
+    /// accounts, in share order
+    #[account(
+        mut,
+        seeds = [b"revenue_split", target_agent.key().as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Box<Account<'info, RevenueSplit>>>,
+
+    /// Caller agent's spending report, updated with the payment
+    #[account(
+        mut,
+        seeds = [b"consumer_stats", caller_agent.key().as_ref()],
+        bump = consumer_stats.bump
+    )]
+    pub consumer_stats: Option<Box<Account<'info, ConsumerStats>>>,
+
+    /// Caller agent's x402 config; pays from the caller agent's x402
+    /// treasury, which it owns
+    #[account(
+        seeds = [b"x402_config", caller_agent.key().as_ref()],
+        bump = caller_x402_config.bump
+    )]
+    pub caller_x402_config: Option<Box<Account<'info, X402Config>>>,
+
+    /// Terms the target agent accepted through `accept_terms`, replacing its
+    /// listed payment bounds
+    #[account(
+        mut,
+        seeds = [
+            b"service_agreement",
+            caller_agent.key().as_ref(),
+            target_agent.key().as_ref(),
+            service_id.as_ref()
+        ],
+        bump = agreement.bump
+    )]
+    pub agreement: Option<Box<Account<'info, ServiceAgreement>>>,
+}
 
 /// Call an agent service with payment (Agent-to-Agent interaction)
 /// This enables AI agents to pay each other for services
-pub fn handler(
-    ctx: Context<crate::CallAgentService>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CallAgentService<'info>>,
     amount: u64,
-    service_id: String,
+    service_id: [u8; 32],
     nonce: u64,
     service_params: Vec<u8>, // Serialized service parameters
+    request_id: [u8; 16],    // Caller's off-chain request id
+    priority_tip: u64,       // Paid on top to the target, to expedite the call
 ) -> Result<()> {
     let x402_config = &mut ctx.accounts.target_x402_config;
     let payment_record = &mut ctx.accounts.payment_record;
     let clock = Clock::get()?;
     
     // Verify X402 is enabled for target agent
-    require!(x402_config.enabled, X402Error::PaymentsNotEnabled);
+    x402_config.require_accepting_payments()?;
     
-    // Validate payment amount
-    x402_config.validate_payment_amount(amount)?;
+    // Validate payment amount, or pay the agreed price; metered services
+    // need an escrowed budget
+    match &mut ctx.accounts.agreement {
+        Some(agreement) => {
+            agreement.validate_payment(amount, &service_id, clock.unix_timestamp)?;
+            agreement.used = true;
+        }
+        None => x402_config.validate_payment_amount(amount)?,
+    }
+    require!(!x402_config.is_metered(), AgentFactoryError::MeteringRequiresEscrow);
     
     // Verify nonce for replay protection
-    require!(nonce == x402_config.nonce + 1, X402Error::NonceMismatch);
+    require_eq!(nonce, x402_config.nonce + 1, AgentFactoryError::NonceMismatch);
     
     // Validate service ID
-    require!(service_id.len() > 0 && service_id.len() <= 32, X402Error::InvalidServiceId);
+    require!(service_id != [0; 32], AgentFactoryError::InvalidServiceId);
+    x402_config.require_service_live(&service_id)?;
     
     // Validate service params size (max 1KB)
-    require!(service_params.len() <= 1024, X402Error::InvalidServiceId);
+    require!(service_params.len() <= 1024, AgentFactoryError::ParamsTooLarge);
+
+    // Pay from the caller agent's x402 treasury, signed by its config, or
+    // from the creator's token account
+    let caller_agent_key = ctx.accounts.caller_agent.key();
+    let (payer_authority, caller_config_bump) = match &ctx.accounts.caller_x402_config {
+        Some(caller_x402_config) => {
+            let (treasury, _) = Pubkey::find_program_address(
+                &[b"x402_treasury", caller_agent_key.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                ctx.accounts.caller_token_account.key(),
+                treasury,
+                AgentFactoryError::WrongX402Treasury
+            );
+            (caller_x402_config.to_account_info(), Some(caller_x402_config.bump))
+        }
+        None => (ctx.accounts.caller_authority.to_account_info(), None),
+    };
+    let bump = [caller_config_bump.unwrap_or_default()];
+    let seeds = [b"x402_config".as_ref(), caller_agent_key.as_ref(), &bump];
+    let treasury_signer = [&seeds[..]];
+    let signer_seeds: &[&[&[u8]]] = if caller_config_bump.is_some() { &treasury_signer } else { &[] };
+
+    // Route the target's worker agents' shares
+    let routed = route_revenue(
+        x402_config,
+        ctx.accounts.revenue_split.as_deref_mut(),
+        ctx.remaining_accounts,
+        amount,
+        &ctx.accounts.caller_token_account.to_account_info(),
+        &payer_authority,
+        signer_seeds,
+        &ctx.accounts.token_program,
+    )?;
 
-    // Transfer USDC from caller to target agent's payment recipient
-    let transfer_ctx = CpiContext::new(
+    // Transfer USDC from caller to target agent's payment recipient, with
+    // the whole tip
+    let paid = amount
+        .checked_add(priority_tip)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    let transfer_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         TokenTransfer {
             from: ctx.accounts.caller_token_account.to_account_info(),
             to: ctx.accounts.target_token_account.to_account_info(),
-            authority: ctx.accounts.caller_authority.to_account_info(),
+            authority: payer_authority.clone(),
         },
+        signer_seeds,
     );
-    token::transfer(transfer_ctx, amount)?;
+    token::transfer(transfer_ctx, paid - routed)?;
     
     // Initialize payment record
     payment_record.agent = ctx.accounts.target_agent.key();
     payment_record.payer = ctx.accounts.caller_agent.key();
     payment_record.amount = amount;
     payment_record.timestamp = clock.unix_timestamp;
-    payment_record.service_id = service_id.clone();
+    payment_record.service_id = service_id;
     payment_record.status = PaymentStatus::Settled;
+    payment_record.attestation_hash = [0; 32];
+    payment_record.meter_unit = MeterUnit::ThousandTokens;
+    payment_record.unit_price = 0;
+    payment_record.metered_units = 0;
+    payment_record.request_id = request_id;
+    payment_record.refunded_amount = 0;
+    payment_record.dispute_deadline = x402_config.dispute_deadline(clock.unix_timestamp);
+    payment_record.finalized_at = 0;
+    payment_record.mint = ctx.accounts.caller_token_account.mint;
     payment_record.bump = ctx.bumps.payment_record;
     
     // Update target agent's X402 config
     x402_config.increment_nonce()?;
-    x402_config.record_payment(amount)?;
+    x402_config.record_payment(paid)?;
+    if let Some(consumer_stats) = &mut ctx.accounts.consumer_stats {
+        consumer_stats.record(&ctx.accounts.target_agent.key(), &payment_record.key(), paid, clock.unix_timestamp)?;
+    }
     
-    msg!("Agent-to-Agent service call completed");
-    msg!("Caller: {}, Target: {}", ctx.accounts.caller_agent.key(), ctx.accounts.target_agent.key());
-    msg!("Service: {}, Amount: {} USDC (smallest units)", service_id, amount);
+    trace!("Agent-to-Agent service call completed");
+    trace!("Caller: {}, Target: {}", ctx.accounts.caller_agent.key(), ctx.accounts.target_agent.key());
+    trace!("Amount: {} USDC (smallest units), Tip: {}", amount, priority_tip);
     
     // Emit event for off-chain processing
-    emit!(AgentServiceCallEvent {
+    emit_cpi!(AgentServiceCallEvent {
         caller_agent: ctx.accounts.caller_agent.key(),
         target_agent: ctx.accounts.target_agent.key(),
         service_id,
         amount,
         timestamp: clock.unix_timestamp,
         service_params,
+        request_id,
+        priority_tip,
     });
     
     Ok(())
@@ -76,8 +241,13 @@ pub fn handler(
 pub struct AgentServiceCallEvent {
     pub caller_agent: Pubkey,
     pub target_agent: Pubkey,
-    pub service_id: String,
+    pub service_id: [u8; 32],
     pub amount: u64,
     pub timestamp: i64,
     pub service_params: Vec<u8>,
+    /// Caller-supplied id correlating the call with its off-chain request
+    pub request_id: [u8; 16],
+    /// Paid to the target on top of `amount`; targets can serve calls in
+    /// tip order
+    pub priority_tip: u64,
 }
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::events::AdminActionEvent;
+use crate::state::{AdminActionStage, AgentFactory, QueuedAdminAction};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelAdminAction<'info> {
+    /// Factory singleton
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Queued action to drop; closed
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"admin_action".as_ref(), &queued_action.action_id.to_le_bytes()],
+        bump = queued_action.bump,
+        has_one = rent_payer
+    )]
+    pub queued_action: Account<'info, QueuedAdminAction>,
+
+    /// Factory authority (wallet or PDA signer)
+    pub authority: Signer<'info>,
+
+    /// CHECK: Receives the queued action's rent, checked against it
+    #[account(mut)]
+    pub rent_payer: AccountInfo<'info>,
+}
+
+/// Drop a queued admin action before it is executed
+pub fn handler(ctx: Context<CancelAdminAction>) -> Result<()> {
+    let queued = &ctx.accounts.queued_action;
+
+    emit_cpi!(AdminActionEvent {
+        action_id: queued.action_id,
+        action: queued.action,
+        stage: AdminActionStage::Cancelled,
+        eta: queued.eta,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    trace!("Admin action cancelled!");
+    trace!("Action ID: {}", queued.action_id);
+
+    Ok(())
+}
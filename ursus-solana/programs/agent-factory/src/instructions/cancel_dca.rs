@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::DcaSchedule;
+
+#[derive(Accounts)]
+pub struct CancelDca<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"dca", dca.agent.as_ref(), owner.key().as_ref()],
+        bump = dca.bump,
+        has_one = owner
+    )]
+    pub dca: Account<'info, DcaSchedule>,
+
+    /// Schedule's SOL deposit
+    #[account(
+        mut,
+        seeds = [b"dca_vault", dca.key().as_ref()],
+        bump
+    )]
+    pub dca_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Cancel a DCA schedule. The unspent deposit and the vault's rent are
+/// refunded to the owner, and the schedule account is closed to them.
+pub fn handler(ctx: Context<CancelDca>) -> Result<()> {
+    let refund = ctx.accounts.dca_vault.lamports();
+    if refund > 0 {
+        let dca_key = ctx.accounts.dca.key();
+        let vault_bump = [ctx.bumps.dca_vault];
+        let vault_seeds: &[&[u8]] = &[b"dca_vault", dca_key.as_ref(), &vault_bump];
+        let vault_signer = &[vault_seeds];
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.dca_vault.to_account_info(),
+                to: ctx.accounts.owner.to_account_info(),
+            },
+            vault_signer,
+        );
+        system_program::transfer(cpi_context, refund)?;
+    }
+
+    let dca = &ctx.accounts.dca;
+
+    trace!("DCA schedule closed!");
+    trace!("Executed intervals: {}/{}", dca.executed_intervals, dca.total_intervals);
+    trace!("Tokens bought: {}", dca.total_tokens_bought);
+    trace!("Refund: {}", refund);
+
+    Ok(())
+}
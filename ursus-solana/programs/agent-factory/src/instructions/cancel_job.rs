@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::state::{Job, MilestoneStatus, X402Config};
+use super::withdraw_streamed::pay_out_stream;
+
+#[derive(Accounts)]
+pub struct CancelJob<'info> {
+    #[account(
+        seeds = [b"x402_config", job.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        has_one = payer
+    )]
+    pub job: Box<Account<'info, Job>>,
+
+    #[account(
+        mut,
+        seeds = [b"job_escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payer's USDC token account, refunded the unsubmitted milestones
+    #[account(
+        mut,
+        constraint = payer_token_account.owner == payer.key() @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Refund every milestone the provider hasn't submitted by the job's
+/// deadline. Submitted milestones stay in escrow for review.
+pub fn handler(ctx: Context<CancelJob>) -> Result<()> {
+    require!(
+        Clock::get()?.unix_timestamp >= ctx.accounts.job.deadline,
+        AgentFactoryError::JobNotExpired
+    );
+
+    let job = &mut ctx.accounts.job;
+    let mut refund = 0u64;
+    for milestone in job.milestones.iter_mut().filter(|m| m.status == MilestoneStatus::Pending) {
+        refund += milestone.amount;
+        milestone.refunded = milestone.amount;
+        milestone.status = MilestoneStatus::Refunded;
+    }
+    require!(refund > 0, AgentFactoryError::InvalidMilestone);
+    job.refunded += refund;
+
+    pay_out_stream(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.payer_token_account.to_account_info(),
+        refund,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+
+    trace!("Job cancelled!");
+    trace!("Refunded: {}", refund);
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::state::KeeperTask;
+
+#[derive(Accounts)]
+pub struct CancelKeeperTask<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"keeper_task",
+            owner.key().as_ref(),
+            &task.task_id.to_le_bytes()
+        ],
+        bump = task.bump,
+        has_one = owner
+    )]
+    pub task: Account<'info, KeeperTask>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Cancel a keeper task. The account is closed to the owner, refunding the
+/// unspent tip deposit together with the rent.
+pub fn handler(ctx: Context<CancelKeeperTask>) -> Result<()> {
+    trace!("Keeper task closed!");
+    trace!("Tips paid: {}", ctx.accounts.task.total_tips_paid);
+
+    Ok(())
+}
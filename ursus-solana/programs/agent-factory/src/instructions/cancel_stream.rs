@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::state::{PaymentStream, X402Config};
+use super::withdraw_streamed::pay_out_stream;
+
+#[derive(Accounts)]
+pub struct CancelStream<'info> {
+    #[account(
+        seeds = [b"x402_config", stream.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        constraint = !stream.is_cancelled() @ AgentFactoryError::StreamCancelled
+    )]
+    pub stream: Box<Account<'info, PaymentStream>>,
+
+    #[account(
+        mut,
+        seeds = [b"stream_escrow", stream.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payer's USDC token account, refunded the unstreamed remainder
+    #[account(
+        mut,
+        constraint = payer_token_account.owner == payer.key() @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Stop a stream and refund the payer what hasn't streamed yet. What has
+/// streamed stays in escrow for the provider to withdraw.
+pub fn handler(ctx: Context<CancelStream>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(now < ctx.accounts.stream.end_ts, AgentFactoryError::StreamEnded);
+
+    let refund = ctx.accounts.stream.deposited - ctx.accounts.stream.streamed(now);
+    pay_out_stream(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.payer_token_account.to_account_info(),
+        refund,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+
+    let stream = &mut ctx.accounts.stream;
+    stream.end_ts = now;
+    stream.cancelled_at = now;
+    stream.refunded = refund;
+
+    trace!("Payment stream cancelled!");
+    trace!("Refunded: {}, Left to withdraw: {}", refund, stream.withdrawable(now));
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{AgentFactory, Dispute, DisputeStatus, ProviderBond};
+
+#[derive(Accounts)]
+pub struct CastArbiterVote<'info> {
+    /// Factory singleton holding the arbitration council
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        constraint = factory.is_arbiter(&arbiter.key()) @ AgentFactoryError::NotArbiter
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.payment_record.as_ref()],
+        bump = dispute.bump,
+        constraint = dispute.status == DisputeStatus::Open @ AgentFactoryError::DisputeNotOpen
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(
+        mut,
+        seeds = [b"provider_bond", dispute.agent.as_ref()],
+        bump = provider_bond.bump
+    )]
+    pub provider_bond: Account<'info, ProviderBond>,
+
+    /// CHECK: Claimant receiving the slash, checked against the dispute
+    #[account(
+        mut,
+        address = dispute.claimant
+    )]
+    pub claimant: AccountInfo<'info>,
+
+    /// Council member casting the vote
+    pub arbiter: Signer<'info>,
+}
+
+/// Vote to award the claimant `slash_amount`, slashed from the provider bond,
+/// or to dismiss the dispute with 0. The award can't exceed the lamports the
+/// claimant asked for. Once the council threshold of current
+/// members agrees on the same amount the dispute resolves and stops locking
+/// the bond. An award above the bond slashes all of it; an insurance policy
+/// on the payment covers the rest through `pay_insured_claim`.
+pub fn handler(ctx: Context<CastArbiterVote>, slash_amount: u64) -> Result<()> {
+    let factory = &ctx.accounts.factory;
+    let arbiter = ctx.accounts.arbiter.key();
+    let dispute = &mut ctx.accounts.dispute;
+
+    require!(slash_amount <= dispute.claimed_amount, AgentFactoryError::AwardExceedsClaim);
+    let agreeing = dispute.record_vote(&factory.arbiters, arbiter, slash_amount)?;
+
+    trace!("Arbiter vote cast!");
+    trace!("Arbiter: {}", arbiter);
+    trace!("Slash: {}", slash_amount);
+    trace!("Agreeing: {}/{}", agreeing, factory.arbiter_threshold);
+
+    if agreeing < factory.arbiter_threshold as usize {
+        return Ok(());
+    }
+
+    let bond = &mut ctx.accounts.provider_bond;
+    let slashed = slash_amount.min(bond.amount);
+
+    if slashed > 0 {
+        bond.amount -= slashed;
+        bond.total_slashed = bond.total_slashed
+            .checked_add(slashed)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        **bond.to_account_info().try_borrow_mut_lamports()? -= slashed;
+        **ctx.accounts.claimant.to_account_info().try_borrow_mut_lamports()? += slashed;
+    }
+    bond.open_disputes = bond.open_disputes
+        .checked_sub(1)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    dispute.status = if slash_amount > 0 { DisputeStatus::Slashed } else { DisputeStatus::Dismissed };
+    dispute.awarded_amount = slash_amount;
+    dispute.slashed_amount = slashed;
+    dispute.resolved_at = Clock::get()?.unix_timestamp;
+
+    trace!("Dispute resolved!");
+    trace!("Status: {:?}", dispute.status);
+    trace!("Slashed: {}/{}", slashed, slash_amount);
+
+    Ok(())
+}
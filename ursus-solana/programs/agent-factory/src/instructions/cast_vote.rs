@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Proposal, StakePosition, StakingPool, VoteRecord};
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            proposal.agent.as_ref(),
+            &proposal.proposal_id.to_le_bytes()
+        ],
+        bump = proposal.bump
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    #[account(
+        seeds = [b"staking_pool", proposal.agent.as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Box<Account<'info, StakingPool>>,
+
+    #[account(
+        seeds = [b"stake", staking_pool.key().as_ref(), voter.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + VoteRecord::INIT_SPACE,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote: Account<'info, VoteRecord>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Vote on an open proposal with the stake held at its snapshot
+pub fn handler(ctx: Context<CastVote>, support: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+
+    require!(
+        now >= proposal.start_ts && now < proposal.end_ts,
+        AgentFactoryError::VotingClosed
+    );
+
+    let weight = ctx.accounts.position.voting_weight_at(proposal.snapshot_ts);
+    require!(weight > 0, AgentFactoryError::InsufficientVotingPower);
+
+    if support {
+        proposal.for_votes = proposal.for_votes
+            .checked_add(weight)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+    } else {
+        proposal.against_votes = proposal.against_votes
+            .checked_add(weight)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+    }
+
+    let vote = &mut ctx.accounts.vote;
+    vote.proposal = proposal.key();
+    vote.voter = ctx.accounts.voter.key();
+    vote.weight = weight;
+    vote.support = support;
+    vote.bump = ctx.bumps.vote;
+
+    trace!("Vote cast!");
+    trace!("Weight: {} ({})", weight, if support { "for" } else { "against" });
+    trace!("For: {}, against: {}", proposal.for_votes, proposal.against_votes);
+
+    Ok(())
+}
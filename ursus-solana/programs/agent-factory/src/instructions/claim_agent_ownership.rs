@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::Agent;
+
+#[derive(Accounts)]
+pub struct ClaimAgentOwnership<'info> {
+    #[account(
+        mut,
+        seeds = [b"agent", agent.agent_id.to_le_bytes().as_ref()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+
+    /// Holder's account with the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&ownership_token_account, &holder.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub ownership_token_account: Account<'info, TokenAccount>,
+
+    pub holder: Signer<'info>,
+}
+
+/// Record the ownership NFT holder as the agent's creator, e.g. after buying
+/// the NFT on a marketplace. Creator-gated instructions already check the
+/// NFT; this moves creator fees and the agent's other uses of its creator
+/// wallet to the holder.
+pub fn handler(ctx: Context<ClaimAgentOwnership>) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let previous = agent.creator;
+    agent.creator = ctx.accounts.holder.key();
+
+    trace!("Agent ownership claimed!");
+    trace!("Agent: {}", agent.key());
+    trace!("Previous creator: {}", previous);
+    trace!("New creator: {}", agent.creator);
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::events::{FeeClaimKind, FeesClaimedEvent};
+use crate::state::{Agent, AgentVault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Pay out the creator fees streamed out so far; the rest keeps streaming
+pub fn handler(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    agent.release_creator_fees(Clock::get()?.unix_timestamp)?;
+    let claimable = agent.claimable_creator_fees;
+    require!(claimable > 0, AgentFactoryError::NoRewards);
+
+    AgentVault::CreatorFees.pay(
+        &agent.key(),
+        agent,
+        &ctx.accounts.creator_fee_vault,
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.system_program,
+        claimable,
+    )?;
+    agent.claimable_creator_fees = 0;
+
+    trace!("Creator fees claimed: {}", claimable);
+    trace!("Still streaming: {} until {}", agent.streaming_creator_fees, agent.creator_fee_stream_end);
+
+    emit_cpi!(FeesClaimedEvent {
+        agent: agent.key(),
+        recipient: ctx.accounts.creator.key(),
+        kind: FeeClaimKind::CreatorFees,
+        amount: claimable,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{BuyerRecord, EarlyBuyerClaim, EarlyBuyerRewards};
+
+#[derive(Accounts)]
+pub struct ClaimEarlyBuyerBonus<'info> {
+    #[account(
+        mut,
+        seeds = [b"early_buyer_rewards", rewards.agent.as_ref()],
+        bump = rewards.bump
+    )]
+    pub rewards: Account<'info, EarlyBuyerRewards>,
+
+    #[account(
+        mut,
+        seeds = [b"early_buyer_vault", rewards.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// Buyer's first-buy marker, numbering them among the agent's buyers
+    #[account(
+        seeds = [b"buyer_record", rewards.agent.as_ref(), buyer.key().as_ref()],
+        bump = buyer_record.bump,
+        constraint = buyer_record.buyer_number <= rewards.max_buyers @ AgentFactoryError::NotEarlyBuyer
+    )]
+    pub buyer_record: Account<'info, BuyerRecord>,
+
+    /// Created here, so a second claim fails
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + EarlyBuyerClaim::INIT_SPACE,
+        seeds = [b"early_buyer_claim", rewards.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, EarlyBuyerClaim>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = buyer
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim the early buyer bonus of an agent the signer was among the first
+/// buyers of
+pub fn handler(ctx: Context<ClaimEarlyBuyerBonus>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let rewards = &ctx.accounts.rewards;
+    require!(rewards.is_claim_open(now), AgentFactoryError::EarlyBuyerClaimsClosed);
+
+    let agent_key = rewards.agent;
+    let seeds = &[
+        b"early_buyer_rewards",
+        agent_key.as_ref(),
+        &[rewards.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: ctx.accounts.rewards.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, rewards.bonus_per_buyer)?;
+
+    let rewards = &mut ctx.accounts.rewards;
+    rewards.claimed_count = rewards.claimed_count
+        .checked_add(1)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    let claim = &mut ctx.accounts.claim;
+    claim.rewards = rewards.key();
+    claim.buyer = ctx.accounts.buyer.key();
+    claim.claimed_at = now;
+    claim.bump = ctx.bumps.claim;
+
+    trace!("Early buyer bonus claimed!");
+    trace!("Buyer number: {}", ctx.accounts.buyer_record.buyer_number);
+    trace!("Amount: {}", rewards.bonus_per_buyer);
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{LiquidityMining, LiquidityMiningPosition};
+
+#[derive(Accounts)]
+pub struct ClaimLpRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"liquidity_mining", mining.agent.as_ref()],
+        bump = mining.bump
+    )]
+    pub mining: Box<Account<'info, LiquidityMining>>,
+
+    #[account(
+        mut,
+        seeds = [b"lm_reward_vault", mining.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"lm_position", mining.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, LiquidityMiningPosition>,
+
+    #[account(
+        mut,
+        token::mint = reward_vault.mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Pay out the agent tokens earned by an LP stake
+pub fn handler(ctx: Context<ClaimLpRewards>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let mining = &mut ctx.accounts.mining;
+    mining.accrue(now)?;
+    ctx.accounts.position.settle(mining)?;
+
+    let rewards = ctx.accounts.position.pending_rewards;
+    require!(rewards > 0, AgentFactoryError::NoRewards);
+    ctx.accounts.position.pending_rewards = 0;
+
+    let agent_key = mining.agent;
+    let seeds = &[
+        b"liquidity_mining",
+        agent_key.as_ref(),
+        &[mining.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.mining.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, rewards)?;
+
+    trace!("LP rewards claimed!");
+    trace!("Rewards: {}", rewards);
+
+    Ok(())
+}
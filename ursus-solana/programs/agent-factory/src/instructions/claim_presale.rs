@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, Presale, PresaleCommitment};
+
+#[derive(Accounts)]
+pub struct ClaimPresale<'info> {
+    #[account(
+        mut,
+        seeds = [b"presale", agent.key().as_ref()],
+        bump = presale.bump,
+        has_one = agent
+    )]
+    pub presale: Account<'info, Presale>,
+
+    #[account(
+        mut,
+        close = committer,
+        seeds = [b"commitment", presale.key().as_ref(), committer.key().as_ref()],
+        bump = commitment.bump,
+        has_one = presale,
+        has_one = committer
+    )]
+    pub commitment: Account<'info, PresaleCommitment>,
+
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        address = agent.mint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = committer
+    )]
+    pub committer_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub committer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Claim the pro-rata token allocation of a successful presale, together with
+/// the share of the commitment that was not spent (oversubscription or a
+/// partial fill). The commitment account is closed to the committer.
+pub fn handler(ctx: Context<ClaimPresale>) -> Result<()> {
+    let presale = &ctx.accounts.presale;
+    require!(presale.is_finalized, AgentFactoryError::PresaleNotFinalized);
+    require!(presale.is_successful, AgentFactoryError::PresaleFailed);
+
+    let amount = ctx.accounts.commitment.amount;
+    let (tokens, refund) = presale
+        .allocation(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    // Mint the allocation to the committer
+    let agent_id_bytes = ctx.accounts.agent.agent_id.to_le_bytes();
+    let agent_bump = ctx.accounts.agent.bump;
+    let seeds = &[
+        b"agent",
+        agent_id_bytes.as_ref(),
+        &[agent_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    if tokens > 0 {
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.committer_token_account.to_account_info(),
+            authority: ctx.accounts.agent.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token::mint_to(cpi_ctx, tokens)?;
+    }
+
+    // Refund the unspent share out of the presale vault
+    if refund > 0 {
+        **ctx.accounts.presale.to_account_info().try_borrow_mut_lamports()? -= refund;
+        **ctx.accounts.committer.to_account_info().try_borrow_mut_lamports()? += refund;
+    }
+
+    trace!("Presale allocation claimed!");
+    trace!("Committed: {}", amount);
+    trace!("Tokens: {}", tokens);
+    trace!("Refund: {}", refund);
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, StakePosition, StakingPool};
+use super::stake::collect_and_accrue;
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", agent.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Box<Account<'info, StakingPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Pay out the SOL rewards earned by a stake position
+pub fn handler(ctx: Context<ClaimRewards>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    collect_and_accrue(
+        &mut ctx.accounts.agent,
+        &ctx.accounts.creator_fee_vault,
+        &mut ctx.accounts.staking_pool,
+        &ctx.accounts.system_program,
+        now,
+    )?;
+    ctx.accounts.position.settle(&ctx.accounts.staking_pool)?;
+
+    let rewards = ctx.accounts.position.pending_rewards;
+    require!(rewards > 0, AgentFactoryError::NoRewards);
+
+    **ctx.accounts.staking_pool.to_account_info().try_borrow_mut_lamports()? -= rewards;
+    **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += rewards;
+    ctx.accounts.position.pending_rewards = 0;
+
+    trace!("Staking rewards claimed!");
+    trace!("Rewards: {}", rewards);
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::events::{FeeClaimKind, FeesClaimedEvent};
+use crate::state::{Agent, AgentVault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimRoyalties<'info> {
+    #[account(
+        mut,
+        has_one = original_creator
+    )]
+    pub agent: Account<'info, Agent>,
+
+    #[account(mut)]
+    pub original_creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Pay out the royalties held for the original creator
+pub fn handler(ctx: Context<ClaimRoyalties>) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let pending = agent.pending_royalties;
+    require!(pending > 0, AgentFactoryError::NoRewards);
+
+    AgentVault::CreatorFees.pay(
+        &agent.key(),
+        agent,
+        &ctx.accounts.creator_fee_vault,
+        &ctx.accounts.original_creator.to_account_info(),
+        &ctx.accounts.system_program,
+        pending,
+    )?;
+    agent.pending_royalties = 0;
+
+    trace!("Royalties claimed: {}", pending);
+
+    emit_cpi!(FeesClaimedEvent {
+        agent: agent.key(),
+        recipient: ctx.accounts.original_creator.key(),
+        kind: FeeClaimKind::Royalties,
+        amount: pending,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
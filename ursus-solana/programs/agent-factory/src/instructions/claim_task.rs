@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::events::WorkTaskEvent;
+use crate::state::{TaskStatus, WorkQueue, WorkTask, X402Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimTask<'info> {
+    /// Its service timeout is the claim's lease
+    #[account(
+        seeds = [b"x402_config", task.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        seeds = [b"work_queue", task.agent.as_ref()],
+        bump = queue.bump,
+        constraint = queue.is_worker(&worker.key()) @ AgentFactoryError::NotQueueWorker
+    )]
+    pub queue: Box<Account<'info, WorkQueue>>,
+
+    #[account(mut)]
+    pub task: Box<Account<'info, WorkTask>>,
+
+    pub worker: Signer<'info>,
+}
+
+/// Take a task off the queue. The claim lasts the agent's service timeout;
+/// if the task isn't completed by then, another worker can claim it.
+pub fn handler(ctx: Context<ClaimTask>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let lease_secs = ctx.accounts.x402_config.service_timeout_seconds as i64;
+    let task = &mut ctx.accounts.task;
+    require!(task.is_claimable(now, lease_secs), AgentFactoryError::TaskNotClaimable);
+
+    task.status = TaskStatus::Claimed;
+    task.worker = ctx.accounts.worker.key();
+    task.claimed_at = now;
+
+    trace!("Task claimed!");
+    trace!("Task: {}, Worker: {}", task.task_id, task.worker);
+
+    emit_cpi!(WorkTaskEvent {
+        agent: task.agent,
+        task: task.key(),
+        task_id: task.task_id,
+        requester: task.requester,
+        worker: task.worker,
+        status: TaskStatus::Claimed,
+        params_hash: task.params_hash,
+        max_price: task.max_price,
+        price: 0,
+        result_hash: [0; 32],
+        timestamp: now,
+    });
+
+    Ok(())
+}
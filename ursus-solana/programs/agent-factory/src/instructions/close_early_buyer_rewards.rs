@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, EarlyBuyerRewards};
+
+#[derive(Accounts)]
+pub struct CloseEarlyBuyerRewards<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"early_buyer_rewards", agent.key().as_ref()],
+        bump = rewards.bump
+    )]
+    pub rewards: Account<'info, EarlyBuyerRewards>,
+
+    #[account(
+        mut,
+        seeds = [b"early_buyer_vault", rewards.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = creator
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Return the unclaimed bonuses to the creator once the claim window has
+/// closed. The vault and the rewards account are closed to the creator.
+pub fn handler(ctx: Context<CloseEarlyBuyerRewards>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let rewards = &ctx.accounts.rewards;
+    require!(!rewards.is_claim_open(now), AgentFactoryError::EarlyBuyerClaimsOpen);
+
+    let agent_key = rewards.agent;
+    let seeds = &[
+        b"early_buyer_rewards",
+        agent_key.as_ref(),
+        &[rewards.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Return the unclaimed tokens
+    let unclaimed = ctx.accounts.vault.amount;
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.creator_token_account.to_account_info(),
+            authority: ctx.accounts.rewards.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, unclaimed)?;
+
+    // Reclaim the vault rent
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault.to_account_info(),
+            destination: ctx.accounts.creator.to_account_info(),
+            authority: ctx.accounts.rewards.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::close_account(cpi_ctx)?;
+
+    trace!("Early buyer rewards closed!");
+    trace!("Claimed: {}/{}", ctx.accounts.rewards.claimed_count, ctx.accounts.rewards.max_buyers);
+    trace!("Unclaimed tokens returned: {}", unclaimed);
+
+    Ok(())
+}
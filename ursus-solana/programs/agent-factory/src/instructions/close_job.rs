@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::Job;
+
+#[derive(Accounts)]
+pub struct CloseJob<'info> {
+    #[account(
+        mut,
+        has_one = payer,
+        constraint = job.is_settled() @ AgentFactoryError::JobNotSettled,
+        close = payer
+    )]
+    pub job: Box<Account<'info, Job>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
+/// Clean up a job whose milestones are all paid out or refunded, returning
+/// its rent. Its escrow closed when it was drained.
+pub fn handler(ctx: Context<CloseJob>) -> Result<()> {
+    trace!("Job closed!");
+    trace!("Released: {}, Refunded: {}", ctx.accounts.job.released, ctx.accounts.job.refunded);
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::state::{Agent, ServiceQuote};
+use crate::errors::AgentFactoryError;
+
+#[derive(Accounts)]
+pub struct CloseQuote<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        has_one = agent,
+        close = creator
+    )]
+    pub quote: Account<'info, ServiceQuote>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Withdraw a quote, or clean up a used or expired one, returning its rent
+pub fn handler(ctx: Context<CloseQuote>) -> Result<()> {
+    trace!("Quote closed!");
+    trace!("Payer: {}, Used: {}", ctx.accounts.quote.payer, ctx.accounts.quote.used);
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::state::ServiceAgreement;
+
+#[derive(Accounts)]
+pub struct CloseTerms<'info> {
+    #[account(
+        mut,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub agreement: Account<'info, ServiceAgreement>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+}
+
+/// Withdraw proposed terms, or clean up used or expired ones, returning
+/// their rent
+pub fn handler(ctx: Context<CloseTerms>) -> Result<()> {
+    trace!("Terms closed!");
+    trace!("Target: {}, Used: {}", ctx.accounts.agreement.target_agent, ctx.accounts.agreement.used);
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, AgentFactory, AgentVault, InsuranceFund};
+
+#[derive(Accounts)]
+pub struct CollectInsuranceFees<'info> {
+    /// Factory singleton; source of the current insurance fee rate
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Agent accruing the insurance fees
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    /// Insurance fund receiving the fees
+    #[account(
+        mut,
+        seeds = [b"insurance_fund"],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Agent's platform fee vault
+    #[account(
+        mut,
+        seeds = [b"platform_fee_vault", agent.key().as_ref()],
+        bump = agent.platform_fee_vault_bump
+    )]
+    pub platform_fee_vault: SystemAccount<'info>,
+}
+
+/// Sweep accrued insurance fees from an agent into the fund and sync the
+/// agent's rate with the factory (permissionless)
+pub fn handler(ctx: Context<CollectInsuranceFees>) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let fund = &mut ctx.accounts.insurance_fund;
+
+    let pending = agent.pending_insurance_fees;
+    if pending > 0 {
+        AgentVault::PlatformFees.pay(
+            &agent.key(),
+            agent,
+            &ctx.accounts.platform_fee_vault,
+            &fund.to_account_info(),
+            &ctx.accounts.system_program,
+            pending,
+        )?;
+        agent.pending_insurance_fees = 0;
+        fund.total_fees_collected = fund.total_fees_collected
+            .checked_add(pending)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+    }
+    agent.insurance_fee_bps = ctx.accounts.factory.insurance_fee_bps;
+
+    trace!("Insurance fees collected: {}", pending);
+    trace!("Total collected: {}", fund.total_fees_collected);
+
+    Ok(())
+}
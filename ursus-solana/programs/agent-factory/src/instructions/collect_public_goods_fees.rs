@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::events::PublicGoodsFeesCollectedEvent;
+use crate::state::{Agent, AgentFactory, AgentVault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CollectPublicGoodsFees<'info> {
+    /// Factory singleton; source of the current public goods fee rate
+    #[account(
+        mut,
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Agent accruing the public goods fees
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    /// CHECK: The factory's public goods recipient, receiving the fees
+    #[account(
+        mut,
+        address = factory.public_goods_recipient @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub public_goods_recipient: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Agent's platform fee vault
+    #[account(
+        mut,
+        seeds = [b"platform_fee_vault", agent.key().as_ref()],
+        bump = agent.platform_fee_vault_bump
+    )]
+    pub platform_fee_vault: SystemAccount<'info>,
+}
+
+/// Sweep accrued public goods fees from an agent to the factory's public
+/// goods recipient and sync the agent's rate with the factory
+/// (permissionless)
+pub fn handler(ctx: Context<CollectPublicGoodsFees>) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let factory = &mut ctx.accounts.factory;
+
+    let pending = agent.pending_public_goods_fees;
+    if pending > 0 {
+        AgentVault::PlatformFees.pay(
+            &agent.key(),
+            agent,
+            &ctx.accounts.platform_fee_vault,
+            &ctx.accounts.public_goods_recipient.to_account_info(),
+            &ctx.accounts.system_program,
+            pending,
+        )?;
+        agent.pending_public_goods_fees = 0;
+        factory.total_public_goods_fees = factory.total_public_goods_fees
+            .checked_add(pending)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+    }
+    agent.public_goods_fee_bps = factory.public_goods_fee_bps;
+
+    trace!("Public goods fees collected: {}", pending);
+    trace!("Total collected: {}", factory.total_public_goods_fees);
+
+    emit_cpi!(PublicGoodsFeesCollectedEvent {
+        agent: agent.key(),
+        recipient: factory.public_goods_recipient,
+        amount: pending,
+        total_collected: factory.total_public_goods_fees,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
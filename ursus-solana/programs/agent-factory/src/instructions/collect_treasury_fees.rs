@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, AgentVault, DaoTreasury};
+
+#[derive(Accounts)]
+pub struct CollectTreasuryFees<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"dao_treasury", agent.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, DaoTreasury>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Move the treasury's creator fee share held in the agent's creator fee
+/// vault into the treasury
+pub fn collect<'info>(
+    agent: &mut Account<'info, Agent>,
+    creator_fee_vault: &AccountInfo<'info>,
+    treasury: &mut Account<'info, DaoTreasury>,
+    system_program: &AccountInfo<'info>,
+) -> Result<u64> {
+    let pending = agent.pending_treasury_fees;
+    if pending > 0 {
+        AgentVault::CreatorFees.pay(
+            &agent.key(),
+            agent,
+            creator_fee_vault,
+            &treasury.to_account_info(),
+            system_program,
+            pending,
+        )?;
+        agent.pending_treasury_fees = 0;
+        treasury.total_fees_collected = treasury.total_fees_collected
+            .checked_add(pending)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+    }
+    Ok(pending)
+}
+
+/// Sweep accrued treasury fees from the agent (permissionless)
+pub fn handler(ctx: Context<CollectTreasuryFees>) -> Result<()> {
+    let collected = collect(
+        &mut ctx.accounts.agent,
+        &ctx.accounts.creator_fee_vault,
+        &mut ctx.accounts.treasury,
+        &ctx.accounts.system_program,
+    )?;
+
+    trace!("Treasury fees collected: {}", collected);
+    trace!("Total collected: {}", ctx.accounts.treasury.total_fees_collected);
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, SyncNative, Token, TokenAccount};
+use crate::state::{Agent, AgentVault};
+
+#[derive(Accounts)]
+pub struct CollectX402TopUp<'info> {
+    #[account(mut)]
+    pub agent: Box<Account<'info, Agent>>,
+
+    /// Agent's x402 treasury
+    #[account(
+        mut,
+        seeds = [b"x402_treasury", agent.key().as_ref()],
+        bump
+    )]
+    pub x402_treasury: Box<Account<'info, TokenAccount>>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sweep the top-up share held in the agent's creator fee vault into its
+/// x402 treasury (permissionless)
+pub fn handler(ctx: Context<CollectX402TopUp>) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let pending = agent.pending_x402_top_up;
+    if pending > 0 {
+        AgentVault::CreatorFees.pay(
+            &agent.key(),
+            agent,
+            &ctx.accounts.creator_fee_vault,
+            &ctx.accounts.x402_treasury.to_account_info(),
+            &ctx.accounts.system_program,
+            pending,
+        )?;
+        token::sync_native(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            SyncNative {
+                account: ctx.accounts.x402_treasury.to_account_info(),
+            },
+        ))?;
+        agent.pending_x402_top_up = 0;
+    }
+
+    trace!("X402 top-up collected: {}", pending);
+    trace!("Treasury: {}", ctx.accounts.x402_treasury.key());
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::AgentCommit;
+
+#[derive(Accounts)]
+#[instruction(commit_hash: [u8; 32])]
+pub struct CommitAgent<'info> {
+    /// Commitment: `["agent_commit", creator, commit_hash]`
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AgentCommit::INIT_SPACE,
+        seeds = [b"agent_commit", creator.key().as_ref(), commit_hash.as_ref()],
+        bump
+    )]
+    pub agent_commit: Account<'info, AgentCommit>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Commit to launching an agent whose fields hash to `commit_hash`
+/// (`AgentCommit::commit_hash`); `reveal_agent` creates it in a later slot
+pub fn handler(ctx: Context<CommitAgent>, commit_hash: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    let agent_commit = &mut ctx.accounts.agent_commit;
+    agent_commit.creator = ctx.accounts.creator.key();
+    agent_commit.commit_hash = commit_hash;
+    agent_commit.slot = clock.slot;
+    agent_commit.committed_at = clock.unix_timestamp;
+    agent_commit.bump = ctx.bumps.agent_commit;
+
+    trace!("Agent launch committed!");
+    trace!("Commitment: {}", agent_commit.key());
+    trace!("Slot: {}", agent_commit.slot);
+
+    Ok(())
+}
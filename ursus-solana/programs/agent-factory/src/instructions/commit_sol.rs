@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::AgentFactoryError;
+use crate::state::{Presale, PresaleCommitment};
+
+#[derive(Accounts)]
+pub struct CommitSol<'info> {
+    #[account(
+        mut,
+        seeds = [b"presale", presale.agent.as_ref()],
+        bump = presale.bump
+    )]
+    pub presale: Account<'info, Presale>,
+
+    #[account(
+        init_if_needed,
+        payer = committer,
+        space = 8 + PresaleCommitment::INIT_SPACE,
+        seeds = [b"commitment", presale.key().as_ref(), committer.key().as_ref()],
+        bump
+    )]
+    pub commitment: Account<'info, PresaleCommitment>,
+
+    #[account(mut)]
+    pub committer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Commit SOL to an open presale. Commitments are escrowed in the presale PDA
+/// and may exceed the hard cap; the excess is refunded pro rata at claim time.
+pub fn handler(ctx: Context<CommitSol>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidBuyAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(ctx.accounts.presale.is_open(now), AgentFactoryError::PresaleClosed);
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.committer.to_account_info(),
+            to: ctx.accounts.presale.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)?;
+
+    let commitment = &mut ctx.accounts.commitment;
+    let presale = &mut ctx.accounts.presale;
+
+    // First commitment from this wallet
+    if commitment.amount == 0 {
+        commitment.presale = presale.key();
+        commitment.committer = ctx.accounts.committer.key();
+        commitment.bump = ctx.bumps.commitment;
+        presale.committer_count = presale.committer_count
+            .checked_add(1)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+    }
+
+    commitment.amount = commitment.amount
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    presale.total_committed = presale.total_committed
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("SOL committed to presale!");
+    trace!("Amount: {}", amount);
+    trace!("Commitment: {}", commitment.amount);
+    trace!("Total committed: {}", presale.total_committed);
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::events::WorkTaskEvent;
+use crate::state::{RevenueSplit, TaskStatus, WorkQueue, WorkTask, X402Config};
+use super::settle_attested_payment::{route_escrowed_revenue, split_escrow};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CompleteTask<'info> {
+    #[account(
+        mut,
+        seeds = [b"x402_config", task.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        seeds = [b"work_queue", task.agent.as_ref()],
+        bump = queue.bump
+    )]
+    pub queue: Box<Account<'info, WorkQueue>>,
+
+    #[account(
+        mut,
+        has_one = requester,
+        constraint = task.status == TaskStatus::Claimed && task.worker == worker.key() @ AgentFactoryError::TaskNotClaimed,
+        close = requester
+    )]
+    pub task: Box<Account<'info, WorkTask>>,
+
+    #[account(
+        mut,
+        seeds = [b"task_escrow", task.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Recipient's USDC token account, paid the task's price
+    #[account(
+        mut,
+        constraint = x402_config.accepts_recipient(&recipient_token_account.owner, Clock::get()?.unix_timestamp) @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Requester's USDC token account, refunded the rest of the max price
+    #[account(
+        mut,
+        constraint = requester_token_account.owner == requester.key() @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub requester_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Requester, receives the task's and its escrow's rent
+    #[account(mut)]
+    pub requester: AccountInfo<'info>,
+
+    /// Worker holding the claim
+    pub worker: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Agent's revenue split, required while it routes revenue to other
+    /// agents; the worker treasuries' token accounts follow as remaining
+    /// accounts, in share order
+    #[account(
+        mut,
+        seeds = [b"revenue_split", task.agent.as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Box<Account<'info, RevenueSplit>>>,
+}
+
+/// Deliver a claimed task with the hash of its result, charging `price` of
+/// its escrow to the agent and refunding the rest to the requester. Closes
+/// the task, so the event is the record of its result.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CompleteTask<'info>>,
+    price: u64,
+    result_hash: [u8; 32],
+) -> Result<()> {
+    require!(price <= ctx.accounts.task.max_price, AgentFactoryError::InvalidTaskPrice);
+    // A frozen agent can't collect
+    require!(price == 0 || !ctx.accounts.x402_config.frozen, AgentFactoryError::X402Frozen);
+
+    let routed = route_escrowed_revenue(
+        &ctx.accounts.x402_config,
+        ctx.accounts.revenue_split.as_deref_mut(),
+        ctx.remaining_accounts,
+        &ctx.accounts.escrow_token_account,
+        price,
+        &ctx.accounts.token_program,
+    )?;
+    if routed > 0 {
+        ctx.accounts.escrow_token_account.reload()?;
+    }
+    split_escrow(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        price - routed,
+        &ctx.accounts.requester_token_account.to_account_info(),
+        &ctx.accounts.requester,
+        &ctx.accounts.token_program,
+    )?;
+
+    if price > 0 {
+        ctx.accounts.x402_config.record_payment(price)?;
+    }
+    let queue = &mut ctx.accounts.queue;
+    queue.open_tasks = queue.open_tasks.saturating_sub(1);
+
+    let task = &ctx.accounts.task;
+    trace!("Task completed!");
+    trace!("Task: {}, Price: {}/{}", task.task_id, price, task.max_price);
+
+    emit_cpi!(WorkTaskEvent {
+        agent: task.agent,
+        task: task.key(),
+        task_id: task.task_id,
+        requester: task.requester,
+        worker: task.worker,
+        status: TaskStatus::Completed,
+        params_hash: task.params_hash,
+        max_price: task.max_price,
+        price,
+        result_hash,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
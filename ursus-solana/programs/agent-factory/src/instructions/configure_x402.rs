@@ -1,9 +1,40 @@
 use anchor_lang::prelude::*;
-use crate::state::{X402Config};
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::events::X402ConfigUpdatedEvent;
+use crate::state::{Agent, DeprecatedService, MeterUnit, X402Config, MAX_DEPRECATED_SERVICES};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(enabled: bool, min_payment_amount: u64, max_payment_amount: u64, service_timeout_seconds: u64)]
+pub struct ConfigureX402<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + X402Config::INIT_SPACE,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Authority's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &authority.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
 
 /// Configure X402 payment settings for an agent (first time setup)
 pub fn handler(
-    ctx: Context<crate::ConfigureX402>,
+    ctx: Context<ConfigureX402>,
     enabled: bool,
     min_payment_amount: u64,
     max_payment_amount: u64,
@@ -22,10 +53,43 @@ pub fn handler(
     x402_config.total_payments_received = 0;
     x402_config.total_service_calls = 0;
     x402_config.nonce = 0;
+    x402_config.attestation_verifier = Pubkey::default();
+    x402_config.meter_unit = MeterUnit::ThousandTokens;
+    x402_config.unit_price = 0;
+    x402_config.max_affiliate_bps = 0;
+    x402_config.webhook_url_hash = [0; 32];
+    x402_config.webhook_signer = Pubkey::default();
+    x402_config.frozen = false;
+    x402_config.previous_payment_recipient = Pubkey::default();
+    x402_config.previous_recipient_valid_until = 0;
+    x402_config.revenue_split_bps = 0;
+    x402_config.chargeback_window_secs = 0;
+    x402_config.deprecated_services = [DeprecatedService::default(); MAX_DEPRECATED_SERVICES];
+    x402_config.price_notice_secs = 0;
+    x402_config.previous_price_notice_secs = 0;
+    x402_config.previous_notice_valid_until = 0;
+    x402_config.pending_min_payment_amount = 0;
+    x402_config.pending_min_payment_at = 0;
+    x402_config.trial_deposit = 0;
     x402_config.bump = ctx.bumps.x402_config;
 
-    msg!("X402 configured for agent: {}", agent.key());
-    msg!("Enabled: {}, Min: {}, Max: {}", enabled, min_payment_amount, max_payment_amount);
+    trace!("X402 configured for agent: {}", agent.key());
+    trace!("Enabled: {}, Min: {}, Max: {}", enabled, min_payment_amount, max_payment_amount);
+
+    emit_cpi!(X402ConfigUpdatedEvent {
+        agent: agent.key(),
+        authority: ctx.accounts.authority.key(),
+        old_enabled: false,
+        new_enabled: enabled,
+        old_min_payment_amount: 0,
+        new_min_payment_amount: min_payment_amount,
+        old_max_payment_amount: 0,
+        new_max_payment_amount: max_payment_amount,
+        old_service_timeout_seconds: 0,
+        new_service_timeout_seconds: service_timeout_seconds,
+        min_payment_effective_at: Clock::get()?.unix_timestamp,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
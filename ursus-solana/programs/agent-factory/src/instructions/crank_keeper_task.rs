@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::KeeperTask;
+
+#[derive(Accounts)]
+pub struct CrankKeeperTask<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"keeper_task",
+            task.owner.as_ref(),
+            &task.task_id.to_le_bytes()
+        ],
+        bump = task.bump
+    )]
+    pub task: Account<'info, KeeperTask>,
+
+    /// CHECK: Account the task cranks, checked against it
+    #[account(address = task.target)]
+    pub target: UncheckedAccount<'info>,
+
+    /// Keeper receiving the tip
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+/// Pay the keeper for the work done on the task's target since the last tip.
+/// Keepers send this right after the crank itself, in the same transaction,
+/// so nobody else can collect their tip.
+pub fn handler(ctx: Context<CrankKeeperTask>) -> Result<()> {
+    let task = &mut ctx.accounts.task;
+    let progress = task.kind.progress(&ctx.accounts.target.to_account_info())?;
+    require!(progress > task.progress, AgentFactoryError::NoKeeperWork);
+
+    let tip = (progress - task.progress)
+        .checked_mul(task.tip_lamports)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    let task_info = task.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(task_info.data_len());
+    require!(
+        task_info.lamports().saturating_sub(rent_exempt) >= tip,
+        AgentFactoryError::KeeperTaskUnfunded
+    );
+
+    **task_info.try_borrow_mut_lamports()? -= tip;
+    **ctx.accounts.keeper.to_account_info().try_borrow_mut_lamports()? += tip;
+
+    task.progress = progress;
+    task.total_tips_paid = task.total_tips_paid
+        .checked_add(tip)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("Keeper task cranked!");
+    trace!("Progress: {}", progress);
+    trace!("Tip: {}", tip);
+
+    Ok(())
+}
@@ -1,64 +1,411 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::BondingCurve;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata::types::DataV2;
+use anchor_spl::metadata::{self, CreateMetadataAccountsV3, Metadata};
+use anchor_spl::token;
+use anchor_spl::token::spl_token::instruction::AuthorityType;
+use anchor_spl::token::{Mint, MintTo, SetAuthority, Token, TokenAccount};
+use crate::state::{deposit_to_vault, Agent, AgentFactory, BondingCurve, CreatorFeeMode, CreatorStats, NameReservation, TwapAccumulator, OWNERSHIP_NFT_SYMBOL};
 use crate::errors::AgentFactoryError;
+use crate::events::AgentCreatedEvent;
 
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateAgent<'info> {
+    #[account(
+        mut,
+        seeds = [b"factory"],
+        bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Agent::INIT_SPACE,
+        seeds = [b"agent", factory.total_agents.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = 9,
+        mint::authority = agent,
+        seeds = [b"mint", agent.key().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Agent token's Metaplex metadata, created by the metadata
+    /// program
+    #[account(
+        mut,
+        seeds = [b"metadata", metadata_program.key().as_ref(), mint.key().as_ref()],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub mint_metadata: UncheckedAccount<'info>,
+
+    /// Ownership NFT: a single token held by the creator; whoever holds it
+    /// passes the creator checks, so it can be sold or listed like any NFT
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = 0,
+        mint::authority = agent,
+        seeds = [b"ownership_mint", agent.key().as_ref()],
+        bump
+    )]
+    pub ownership_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Ownership NFT's Metaplex metadata, created by the metadata
+    /// program
+    #[account(
+        mut,
+        seeds = [b"metadata", metadata_program.key().as_ref(), ownership_mint.key().as_ref()],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub ownership_metadata: UncheckedAccount<'info>,
+
+    /// Creator's ATA receiving the ownership NFT
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = ownership_mint,
+        associated_token::authority = creator
+    )]
+    pub creator_ownership_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// CHECK: Platform treasury, receives the SOL creation fee
+    #[account(
+        mut,
+        address = factory.platform_treasury @ AgentFactoryError::InvalidFeePayment
+    )]
+    pub platform_treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    pub metadata_program: Program<'info, Metadata>,
+
+    /// Creator's track record: `["creator_stats", creator]`; prices the
+    /// creation fee
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + CreatorStats::INIT_SPACE,
+        seeds = [b"creator_stats", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_stats: Box<Account<'info, CreatorStats>>,
+
+    /// Creator's `factory.quote_mint` account; pass both quote accounts to
+    /// pay the creation fee in the quote mint instead of SOL
+    #[account(
+        mut,
+        constraint = creator_quote_account.mint == factory.quote_mint @ AgentFactoryError::InvalidFeePayment
+    )]
+    pub creator_quote_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Platform treasury's `factory.quote_mint` account
+    #[account(
+        mut,
+        constraint = treasury_quote_account.mint == factory.quote_mint @ AgentFactoryError::InvalidFeePayment,
+        constraint = treasury_quote_account.owner == factory.platform_treasury @ AgentFactoryError::InvalidFeePayment
+    )]
+    pub treasury_quote_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: Reservation of the name: `["name_reservation", name_hash(name)]`,
+    /// usually not created; address and contents checked in the handler
+    pub name_reservation: UncheckedAccount<'info>,
+
+    /// CHECK: Reservation of the symbol: `["name_reservation", name_hash(symbol)]`
+    pub symbol_reservation: UncheckedAccount<'info>,
+
+    /// Curve SOL vault: `["sol_vault", agent]`; funded with its rent here
+    #[account(
+        mut,
+        seeds = [b"sol_vault", agent.key().as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Platform fee vault: `["platform_fee_vault", agent]`
+    #[account(
+        mut,
+        seeds = [b"platform_fee_vault", agent.key().as_ref()],
+        bump
+    )]
+    pub platform_fee_vault: SystemAccount<'info>,
+
+    /// Creator fee vault: `["creator_fee_vault", agent]`
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Metaplex metadata of an agent's token or ownership NFT: name and symbol
+/// only, no URI, royalties or creators
+pub(crate) fn token_metadata(name: &str, symbol: &str) -> DataV2 {
+    DataV2 {
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        uri: String::new(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
-    ctx: Context<crate::CreateAgent>,
+    ctx: Context<CreateAgent>,
     name: &str,
     symbol: &str,
     description: &str,
     instructions: &str,
     model: &str,
-    category: &str,
+    tags: &[String],
+    mut bonding_curve: BondingCurve,
+    creator_fee_mode: CreatorFeeMode,
 ) -> Result<()> {
     // Validate inputs
-    require!(name.len() > 0 && name.len() <= 32, AgentFactoryError::InvalidName);
-    require!(symbol.len() > 0 && symbol.len() <= 10, AgentFactoryError::InvalidSymbol);
+    require!(!name.is_empty() && name.len() <= 32, AgentFactoryError::InvalidName);
+    require!(!symbol.is_empty() && symbol.len() <= 10, AgentFactoryError::InvalidSymbol);
     require!(description.len() <= 200, AgentFactoryError::DescriptionTooLong);
     require!(instructions.len() <= 500, AgentFactoryError::InstructionsTooLong);
+    require!(ctx.accounts.factory.is_model_allowed(model), AgentFactoryError::ModelNotAllowed);
+    Agent::validate_tags(tags)?;
+    // Verified brands' names and symbols are theirs alone
+    NameReservation::check(&ctx.accounts.name_reservation, name, &ctx.accounts.creator.key())?;
+    NameReservation::check(&ctx.accounts.symbol_reservation, symbol, &ctx.accounts.creator.key())?;
 
     let factory = &mut ctx.accounts.factory;
+    let now = Clock::get()?.unix_timestamp;
+    // Priced on the demand before this creation
+    let surge_bps = factory.creation_surge_bps_at(now);
+    factory.record_creation(now)?;
     let agent = &mut ctx.accounts.agent;
-
-    // Transfer creation fee to platform treasury
-    if factory.creation_fee > 0 {
-        let cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.creator.to_account_info(),
-                to: ctx.accounts.platform_treasury.to_account_info(),
-            },
-        );
-        system_program::transfer(cpi_context, factory.creation_fee)?;
+    let creator_stats = &mut ctx.accounts.creator_stats;
+    if creator_stats.creator == Pubkey::default() {
+        creator_stats.creator = ctx.accounts.creator.key();
+        creator_stats.bump = ctx.bumps.creator_stats;
     }
 
+    // Proven creators pay less, creators of rugged agents pay more, and
+    // everyone pays more during a creation surge
+    let creation_fee = AgentFactory::apply_creation_surge(
+        creator_stats.adjusted_creation_fee(factory.creation_fee)?,
+        surge_bps,
+    )?;
+    let quote_creation_fee = AgentFactory::apply_creation_surge(
+        creator_stats.adjusted_creation_fee(factory.quote_creation_fee)?,
+        surge_bps,
+    )?;
+
+    // Transfer creation fee to platform treasury, in the quote mint when both
+    // quote accounts are passed and in SOL otherwise
+    let (fee_paid, fee_mint) = match (&ctx.accounts.creator_quote_account, &ctx.accounts.treasury_quote_account) {
+        (Some(from), Some(to)) => {
+            require!(factory.quote_mint != Pubkey::default(), AgentFactoryError::InvalidFeePayment);
+            if quote_creation_fee > 0 {
+                let cpi_context = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token::Transfer {
+                        from: from.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.creator.to_account_info(),
+                    },
+                );
+                token::transfer(cpi_context, quote_creation_fee)?;
+            }
+            trace!("Creation fee paid: {} of {}", quote_creation_fee, factory.quote_mint);
+            (quote_creation_fee, factory.quote_mint)
+        }
+        (None, None) => {
+            if creation_fee > 0 {
+                let cpi_context = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: ctx.accounts.platform_treasury.to_account_info(),
+                    },
+                );
+                system_program::transfer(cpi_context, creation_fee)?;
+            }
+            (creation_fee, Pubkey::default())
+        }
+        _ => return err!(AgentFactoryError::InvalidFeePayment),
+    };
+
     // Initialize agent
     agent.agent_id = factory.total_agents;
     agent.mint = ctx.accounts.mint.key();
     agent.creator = ctx.accounts.creator.key();
+    agent.ownership_mint = ctx.accounts.ownership_mint.key();
+    agent.original_creator = ctx.accounts.creator.key();
+    agent.royalty_bps = 0;
     agent.name = name.to_string();
     agent.symbol = symbol.to_string();
     agent.description = description.to_string();
     agent.instructions = instructions.to_string();
     agent.model = model.to_string();
-    agent.category = category.to_string();
+    agent.tags = tags.to_vec();
     agent.created_at = Clock::get()?.unix_timestamp;
     agent.is_graduated = false;
-    agent.bonding_curve = BondingCurve::new();
+    agent.is_presale_active = false;
+    agent.same_slot_protection = false;
+    agent.max_buy_bps_of_reserves = 0;
+    agent.refund_deadline = 0;
+    agent.snapshot_count = 0;
+    agent.staking_fee_bps = 0;
+    agent.pending_staking_rewards = 0;
+    agent.treasury_fee_bps = 0;
+    agent.pending_treasury_fees = 0;
+    agent.insurance_fee_bps = factory.insurance_fee_bps;
+    agent.pending_insurance_fees = 0;
+    agent.pending_royalties = 0;
+    agent.last_renamed_at = 0;
+    agent.creator_fee_vesting_secs = factory.creator_fee_vesting_secs;
+    agent.streaming_creator_fees = 0;
+    agent.claimable_creator_fees = 0;
+    agent.creator_fee_stream_end = 0;
+    agent.creator_fee_stream_updated_at = 0;
+    agent.sol_vault_bump = ctx.bumps.sol_vault;
+    agent.platform_fee_vault_bump = ctx.bumps.platform_fee_vault;
+    agent.creator_fee_vault_bump = ctx.bumps.creator_fee_vault;
+    agent.commit_slot = 0;
+    agent.graduated_at = 0;
+    agent.creator_fee_mode = creator_fee_mode;
+    agent.x402_top_up_bps = 0;
+    agent.pending_x402_top_up = 0;
+    agent.public_goods_fee_bps = factory.public_goods_fee_bps;
+    agent.pending_public_goods_fees = 0;
+    agent.burn_remaining_at_graduation = false;
+    agent.last_buy_slot = 0;
+    bonding_curve.creator_fee_bps = creator_fee_mode.fee_bps();
+    agent.bonding_curve = bonding_curve;
+    let clock = Clock::get()?;
+    agent.twap = TwapAccumulator::new(bonding_curve.price_nano_u64_at(clock.unix_timestamp), clock.slot);
     agent.bump = ctx.bumps.agent;
 
+    // The agent's lamports live in its vaults, which must stay rent-exempt
+    let vault_rent = Rent::get()?.minimum_balance(0);
+    for vault in [&ctx.accounts.sol_vault, &ctx.accounts.platform_fee_vault, &ctx.accounts.creator_fee_vault] {
+        deposit_to_vault(
+            &ctx.accounts.creator.to_account_info(),
+            &vault.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            vault_rent.saturating_sub(vault.lamports()),
+            &[],
+        )?;
+    }
+
+    let agent_id_bytes = agent.agent_id.to_le_bytes();
+    let seeds = &[
+        b"agent",
+        agent_id_bytes.as_ref(),
+        &[agent.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Wallets and explorers show the token under the agent's name and symbol;
+    // the agent stays update authority so `rename_agent` can change them
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.metadata_program.to_account_info(),
+        CreateMetadataAccountsV3 {
+            metadata: ctx.accounts.mint_metadata.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            mint_authority: agent.to_account_info(),
+            payer: ctx.accounts.creator.to_account_info(),
+            update_authority: agent.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        },
+        signer_seeds,
+    );
+    metadata::create_metadata_accounts_v3(cpi_ctx, token_metadata(name, symbol), true, true, None)?;
+
+    // Mint the ownership NFT and fix its supply at one
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.ownership_mint.to_account_info(),
+            to: ctx.accounts.creator_ownership_account.to_account_info(),
+            authority: agent.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::mint_to(cpi_ctx, 1)?;
+
+    // Wallets and marketplaces show the NFT under the agent's name
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.metadata_program.to_account_info(),
+        CreateMetadataAccountsV3 {
+            metadata: ctx.accounts.ownership_metadata.to_account_info(),
+            mint: ctx.accounts.ownership_mint.to_account_info(),
+            mint_authority: agent.to_account_info(),
+            payer: ctx.accounts.creator.to_account_info(),
+            update_authority: agent.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        },
+        signer_seeds,
+    );
+    metadata::create_metadata_accounts_v3(cpi_ctx, token_metadata(name, OWNERSHIP_NFT_SYMBOL), true, true, None)?;
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        SetAuthority {
+            current_authority: agent.to_account_info(),
+            account_or_mint: ctx.accounts.ownership_mint.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::set_authority(cpi_ctx, AuthorityType::MintTokens, None)?;
+
+    creator_stats.agents_created = creator_stats.agents_created
+        .checked_add(1)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
     // Increment total agents
     factory.total_agents = factory.total_agents
         .checked_add(1)
         .ok_or(AgentFactoryError::MathOverflow)?;
 
-    msg!("Agent created successfully!");
-    msg!("Agent ID: {}", agent.agent_id);
-    msg!("Name: {}", name);
-    msg!("Symbol: {}", symbol);
-    msg!("Mint: {}", agent.mint);
-    msg!("Creator: {}", agent.creator);
+    trace!("Agent created successfully!");
+    trace!("Agent ID: {}", agent.agent_id);
+    trace!("Name: {}", name);
+    trace!("Symbol: {}", symbol);
+    trace!("Mint: {}", agent.mint);
+    trace!("Creator: {}", agent.creator);
+    trace!("Ownership NFT: {}", agent.ownership_mint);
+
+    emit_cpi!(AgentCreatedEvent {
+        agent: agent.key(),
+        agent_id: agent.agent_id,
+        mint: agent.mint,
+        ownership_mint: agent.ownership_mint,
+        creator: agent.creator,
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        creation_fee: fee_paid,
+        fee_mint,
+        creator_fee_mode,
+        timestamp: agent.created_at,
+    });
 
     Ok(())
 }
@@ -0,0 +1,223 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, AssociatedToken, Create as CreateAta};
+use anchor_spl::token::{self, MintTo};
+use crate::errors::AgentFactoryError;
+use crate::events::{TradeEvent, X402ConfigUpdatedEvent};
+use crate::state::{
+    deposit_to_vault, BondingCurve, CreatorFeeMode, DeprecatedService, MeterUnit, X402Config, X402Settings,
+    MAX_DEPRECATED_SERVICES,
+};
+// The nested `CreateAgent` needs its generated client and bumps types in scope
+use super::create_agent::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateAgentFull<'info> {
+    /// Same accounts as `create_agent`
+    pub create: CreateAgent<'info>,
+
+    /// Agent's x402 config: `["x402_config", agent]`
+    #[account(
+        init,
+        payer = create.creator,
+        space = 8 + X402Config::INIT_SPACE,
+        seeds = [b"x402_config", create.agent.key().as_ref()],
+        bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    /// CHECK: Creator's ATA for the new mint, created by the dev buy (unused
+    /// without one); its address is checked by the associated token program
+    #[account(mut)]
+    pub creator_token_account: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Create an agent, configure x402 payments and optionally make the
+/// creator's first buy in one transaction, so the agent is never live
+/// half-configured
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateAgentFull<'info>>,
+    name: &str,
+    symbol: &str,
+    description: &str,
+    instructions: &str,
+    model: &str,
+    tags: &[String],
+    x402: X402Settings,
+    dev_buy_sol_amount: u64,
+    min_tokens_out: u64,
+) -> Result<()> {
+    super::create_agent::handler(
+        Context::new(
+            ctx.program_id,
+            &mut ctx.accounts.create,
+            ctx.remaining_accounts,
+            ctx.bumps.create,
+        ),
+        name,
+        symbol,
+        description,
+        instructions,
+        model,
+        tags,
+        BondingCurve::new(),
+        CreatorFeeMode::Creator,
+    )?;
+
+    // Configure x402 payments, paid out to the creator
+    let agent_key = ctx.accounts.create.agent.key();
+    let x402_config = &mut ctx.accounts.x402_config;
+    x402_config.agent = agent_key;
+    x402_config.payment_recipient = ctx.accounts.create.creator.key();
+    x402_config.enabled = x402.enabled;
+    x402_config.min_payment_amount = x402.min_payment_amount;
+    x402_config.max_payment_amount = x402.max_payment_amount;
+    x402_config.service_timeout_seconds = x402.service_timeout_seconds;
+    x402_config.total_payments_received = 0;
+    x402_config.total_service_calls = 0;
+    x402_config.nonce = 0;
+    x402_config.attestation_verifier = Pubkey::default();
+    x402_config.meter_unit = MeterUnit::ThousandTokens;
+    x402_config.unit_price = 0;
+    x402_config.max_affiliate_bps = 0;
+    x402_config.webhook_url_hash = [0; 32];
+    x402_config.webhook_signer = Pubkey::default();
+    x402_config.frozen = false;
+    x402_config.previous_payment_recipient = Pubkey::default();
+    x402_config.previous_recipient_valid_until = 0;
+    x402_config.revenue_split_bps = 0;
+    x402_config.chargeback_window_secs = 0;
+    x402_config.deprecated_services = [DeprecatedService::default(); MAX_DEPRECATED_SERVICES];
+    x402_config.price_notice_secs = 0;
+    x402_config.previous_price_notice_secs = 0;
+    x402_config.previous_notice_valid_until = 0;
+    x402_config.pending_min_payment_amount = 0;
+    x402_config.pending_min_payment_at = 0;
+    x402_config.trial_deposit = 0;
+    x402_config.bump = ctx.bumps.x402_config;
+
+    trace!("X402 configured for agent: {}", agent_key);
+
+    emit_cpi!(X402ConfigUpdatedEvent {
+        agent: agent_key,
+        authority: ctx.accounts.create.creator.key(),
+        old_enabled: false,
+        new_enabled: x402.enabled,
+        old_min_payment_amount: 0,
+        new_min_payment_amount: x402.min_payment_amount,
+        old_max_payment_amount: 0,
+        new_max_payment_amount: x402.max_payment_amount,
+        old_service_timeout_seconds: 0,
+        new_service_timeout_seconds: x402.service_timeout_seconds,
+        min_payment_effective_at: Clock::get()?.unix_timestamp,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    if dev_buy_sol_amount > 0 {
+        let trade = dev_buy(ctx.accounts, dev_buy_sol_amount, min_tokens_out)?;
+        emit_cpi!(trade);
+    }
+
+    Ok(())
+}
+
+/// The creator's first buy, on the same terms as `buy_tokens`; returns the
+/// trade for the caller to emit
+fn dev_buy(accounts: &mut CreateAgentFull, sol_amount: u64, min_tokens_out: u64) -> Result<TradeEvent> {
+    let create = &mut accounts.create;
+
+    let quote = create.agent.bonding_curve.quote_buy(sol_amount)?;
+    require!(quote.tokens_out > 0, AgentFactoryError::InsufficientLiquidity);
+    require_gte!(quote.tokens_out, min_tokens_out, AgentFactoryError::SlippageExceeded);
+
+    // The paid-out creator fee would go straight back to the creator, so only
+    // the platform, insurance and public goods shares, the creator fee shares the agent
+    // holds and the curve reserves move
+    let (platform_fee_to_treasury, retained_platform_fee) = create.agent.split_platform_fee(quote.platform_fee)?;
+    let (_, retained_fee) = create.agent.split_creator_fee(quote.creator_fee, Clock::get()?.unix_timestamp)?;
+
+    let creator_info = create.creator.to_account_info();
+    let system_program_info = create.system_program.to_account_info();
+    deposit_to_vault(&creator_info, &create.sol_vault, &system_program_info, quote.net_sol_amount, &[])?;
+    deposit_to_vault(&creator_info, &create.platform_fee_vault, &system_program_info, retained_platform_fee, &[])?;
+    deposit_to_vault(&creator_info, &create.creator_fee_vault, &system_program_info, retained_fee, &[])?;
+
+    if platform_fee_to_treasury > 0 {
+        let cpi_context = CpiContext::new(
+            create.system_program.to_account_info(),
+            system_program::Transfer {
+                from: create.creator.to_account_info(),
+                to: create.platform_treasury.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, platform_fee_to_treasury)?;
+    }
+
+    // The mint was created by this instruction, so its ATA was too
+    associated_token::create(CpiContext::new(
+        accounts.associated_token_program.to_account_info(),
+        CreateAta {
+            payer: create.creator.to_account_info(),
+            associated_token: accounts.creator_token_account.to_account_info(),
+            authority: create.creator.to_account_info(),
+            mint: create.mint.to_account_info(),
+            system_program: create.system_program.to_account_info(),
+            token_program: create.token_program.to_account_info(),
+        },
+    ))?;
+
+    let agent_id_bytes = create.agent.agent_id.to_le_bytes();
+    let agent_bump = create.agent.bump;
+    let seeds = &[
+        b"agent",
+        agent_id_bytes.as_ref(),
+        &[agent_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = MintTo {
+        mint: create.mint.to_account_info(),
+        to: accounts.creator_token_account.to_account_info(),
+        authority: create.agent.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(create.token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token::mint_to(cpi_ctx, quote.tokens_out)?;
+
+    let curve_before = create.agent.bonding_curve;
+    create.agent.bonding_curve.update_after_buy(quote.net_sol_amount, quote.tokens_out)?;
+    create.agent.check_reserves(
+        &create.sol_vault,
+        &create.platform_fee_vault,
+        &create.creator_fee_vault,
+        &curve_before,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    trace!("Dev buy: {} tokens for {} lamports", quote.tokens_out, quote.sol_amount);
+
+    let clock = Clock::get()?;
+    create.agent.record_trending_volume(quote.sol_amount, clock.slot);
+    create.agent.record_twap_price(clock.unix_timestamp, clock.slot);
+    create.agent.last_buy_slot = clock.slot;
+    Ok(TradeEvent {
+        agent: create.agent.key(),
+        trader: create.creator.key(),
+        is_buy: true,
+        sol_amount: quote.sol_amount,
+        token_amount: quote.tokens_out,
+        platform_fee: quote.platform_fee,
+        creator_fee: quote.creator_fee,
+        price: create.agent.bonding_curve.get_current_price(),
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+        sequence: create.agent.next_event_sequence()?,
+        reserves_before: curve_before.reserves_at(clock.unix_timestamp),
+        reserves_after: create.agent.bonding_curve.reserves_at(clock.unix_timestamp),
+        price_impact_bps: create.agent.bonding_curve.price_impact_bps(&curve_before, clock.unix_timestamp),
+    })
+}
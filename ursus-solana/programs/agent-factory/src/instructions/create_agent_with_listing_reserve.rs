@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, MintTo, Token, TokenAccount};
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+use crate::state::{BondingCurve, CreatorFeeMode, ListingReserve, MAX_LISTING_RELEASE_DELAY_SECS, MAX_LISTING_RESERVE_BPS};
+// The nested `CreateAgent` needs its generated client and bumps types in scope
+use super::create_agent::*;
+
+#[derive(Accounts)]
+pub struct CreateAgentWithListingReserve<'info> {
+    /// Same accounts as `create_agent`
+    pub create: CreateAgent<'info>,
+
+    /// Listing reserve: `["listing_reserve", agent]`
+    #[account(
+        init,
+        payer = create.creator,
+        space = 8 + ListingReserve::INIT_SPACE,
+        seeds = [b"listing_reserve", create.agent.key().as_ref()],
+        bump
+    )]
+    pub listing_reserve: Box<Account<'info, ListingReserve>>,
+
+    /// Token account holding the reserve, owned by the listing reserve PDA
+    #[account(
+        init,
+        payer = create.creator,
+        seeds = [b"listing_reserve_vault", listing_reserve.key().as_ref()],
+        bump,
+        token::mint = create.mint,
+        token::authority = listing_reserve
+    )]
+    pub listing_reserve_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Create an agent and mint `reserve_bps` of its total supply into a listing
+/// reserve, out of the supply kept outside the curve. The reserve is declared
+/// at launch and unlocks `release_delay_secs` after graduation.
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateAgentWithListingReserve<'info>>,
+    name: &str,
+    symbol: &str,
+    description: &str,
+    instructions: &str,
+    model: &str,
+    tags: &[String],
+    reserve_bps: u16,
+    release_delay_secs: i64,
+) -> Result<()> {
+    require!(
+        reserve_bps > 0 && reserve_bps <= MAX_LISTING_RESERVE_BPS,
+        AgentFactoryError::InvalidListingReserve
+    );
+    require!(
+        (0..=MAX_LISTING_RELEASE_DELAY_SECS).contains(&release_delay_secs),
+        AgentFactoryError::InvalidListingReserve
+    );
+
+    super::create_agent::handler(
+        Context::new(
+            ctx.program_id,
+            &mut ctx.accounts.create,
+            ctx.remaining_accounts,
+            ctx.bumps.create,
+        ),
+        name,
+        symbol,
+        description,
+        instructions,
+        model,
+        tags,
+        BondingCurve::new(),
+        CreatorFeeMode::Creator,
+    )?;
+
+    let agent = &ctx.accounts.create.agent;
+    let amount = (agent.bonding_curve.total_supply as u128 * reserve_bps as u128
+        / BPS_DENOMINATOR as u128) as u64;
+
+    // Mint the reserve, signed by the agent as mint authority
+    let agent_id_bytes = agent.agent_id.to_le_bytes();
+    let seeds = &[
+        b"agent",
+        agent_id_bytes.as_ref(),
+        &[agent.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.create.mint.to_account_info(),
+            to: ctx.accounts.listing_reserve_vault.to_account_info(),
+            authority: agent.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::mint_to(cpi_ctx, amount)?;
+
+    let listing_reserve = &mut ctx.accounts.listing_reserve;
+    listing_reserve.agent = agent.key();
+    listing_reserve.reserve_bps = reserve_bps;
+    listing_reserve.amount = amount;
+    listing_reserve.released = 0;
+    listing_reserve.release_delay_secs = release_delay_secs;
+    listing_reserve.created_at = agent.created_at;
+    listing_reserve.bump = ctx.bumps.listing_reserve;
+
+    trace!("Listing reserve created!");
+    trace!("Reserved: {} ({} bps)", amount, reserve_bps);
+    trace!("Release delay after graduation: {}s", release_delay_secs);
+
+    Ok(())
+}
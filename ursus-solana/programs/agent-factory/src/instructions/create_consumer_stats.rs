@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, ConsumerStats};
+
+#[derive(Accounts)]
+#[instruction(consumer: Pubkey)]
+pub struct CreateConsumerStats<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ConsumerStats::INIT_SPACE,
+        seeds = [b"consumer_stats", consumer.as_ref()],
+        bump
+    )]
+    pub consumer_stats: Account<'info, ConsumerStats>,
+
+    /// The consumer itself, or the creator of the consumer agent
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Consumer agent, for reports on its agent-to-agent calls
+    pub payer_agent: Option<Account<'info, Agent>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open a spending report for `consumer`, the paying wallet or (with
+/// `payer_agent`) the paying agent; its x402 payments passing the report are
+/// added to it
+pub fn handler(ctx: Context<CreateConsumerStats>, consumer: Pubkey) -> Result<()> {
+    let payer = ctx.accounts.payer.key();
+    let authorized = match &ctx.accounts.payer_agent {
+        Some(payer_agent) => payer_agent.key() == consumer && payer_agent.creator == payer,
+        None => consumer == payer,
+    };
+    require!(authorized, AgentFactoryError::UnauthorizedCaller);
+
+    let consumer_stats = &mut ctx.accounts.consumer_stats;
+    consumer_stats.payer = consumer;
+    consumer_stats.total_spent = 0;
+    consumer_stats.total_calls = 0;
+    consumer_stats.ledger_hash = [0; 32];
+    consumer_stats.last_payment_at = 0;
+    consumer_stats.created_at = Clock::get()?.unix_timestamp;
+    consumer_stats.bump = ctx.bumps.consumer_stats;
+
+    trace!("Consumer stats created!");
+    trace!("Consumer: {}", consumer);
+
+    Ok(())
+}
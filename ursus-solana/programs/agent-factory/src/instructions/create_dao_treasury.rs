@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, DaoTreasury, Governance};
+
+#[derive(Accounts)]
+pub struct CreateDaoTreasury<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    /// Treasury spending needs proposals, so governance must exist first
+    #[account(
+        seeds = [b"governance", agent.key().as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + DaoTreasury::INIT_SPACE,
+        seeds = [b"dao_treasury", agent.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, DaoTreasury>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open the DAO treasury of one of the creator's agents. From now on
+/// `treasury_fee_bps` of every creator fee is routed to it.
+pub fn handler(ctx: Context<CreateDaoTreasury>, treasury_fee_bps: u16) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    require!(
+        Agent::fee_shares_valid(agent.staking_fee_bps, treasury_fee_bps),
+        AgentFactoryError::InvalidTreasuryConfig
+    );
+
+    agent.treasury_fee_bps = treasury_fee_bps;
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.agent = agent.key();
+    treasury.total_fees_collected = 0;
+    treasury.total_sol_spent = 0;
+    treasury.created_at = Clock::get()?.unix_timestamp;
+    treasury.bump = ctx.bumps.treasury;
+
+    trace!("DAO treasury created!");
+    trace!("Agent: {}", treasury.agent);
+    trace!("Creator fee share: {} bps", treasury_fee_bps);
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, DcaSchedule};
+
+#[derive(Accounts)]
+pub struct CreateDca<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DcaSchedule::INIT_SPACE,
+        seeds = [b"dca", agent.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub dca: Account<'info, DcaSchedule>,
+
+    /// Schedule's SOL deposit
+    #[account(
+        mut,
+        seeds = [b"dca_vault", dca.key().as_ref()],
+        bump
+    )]
+    pub dca_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create a DCA schedule and deposit the SOL for every interval up front.
+/// `min_tokens_per_interval` bounds the price every execution may pay, since
+/// anyone can crank it.
+pub fn handler(
+    ctx: Context<CreateDca>,
+    amount_per_interval: u64,
+    interval_secs: i64,
+    total_intervals: u64,
+    min_tokens_per_interval: u64,
+) -> Result<()> {
+    require!(amount_per_interval > 0, AgentFactoryError::InvalidBuyAmount);
+    require!(
+        interval_secs > 0 && total_intervals > 0 && min_tokens_per_interval > 0,
+        AgentFactoryError::InvalidDcaSchedule
+    );
+    require!(!ctx.accounts.agent.is_graduated, AgentFactoryError::AlreadyGraduated);
+
+    let deposit = amount_per_interval
+        .checked_mul(total_intervals)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    // Move the full deposit into the schedule's vault, which keeps its rent
+    // exemption until the schedule is cancelled
+    let vault_rent = Rent::get()?.minimum_balance(0)
+        .saturating_sub(ctx.accounts.dca_vault.lamports());
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.dca_vault.to_account_info(),
+        },
+    );
+    system_program::transfer(
+        cpi_context,
+        deposit.checked_add(vault_rent).ok_or(AgentFactoryError::MathOverflow)?,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let dca = &mut ctx.accounts.dca;
+    dca.owner = ctx.accounts.owner.key();
+    dca.agent = ctx.accounts.agent.key();
+    dca.amount_per_interval = amount_per_interval;
+    dca.min_tokens_per_interval = min_tokens_per_interval;
+    dca.interval_secs = interval_secs;
+    dca.total_intervals = total_intervals;
+    dca.executed_intervals = 0;
+    dca.next_execution_ts = now;
+    dca.total_tokens_bought = 0;
+    dca.created_at = now;
+    dca.bump = ctx.bumps.dca;
+
+    trace!("DCA schedule created!");
+    trace!("Agent: {}", dca.agent);
+    trace!("Amount per interval: {}", amount_per_interval);
+    trace!("Min tokens per interval: {}", min_tokens_per_interval);
+    trace!("Interval: {}s x {}", interval_secs, total_intervals);
+    trace!("Deposit: {}", deposit);
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, EarlyBuyerRewards};
+
+#[derive(Accounts)]
+pub struct CreateEarlyBuyerRewards<'info> {
+    #[account(has_one = mint)]
+    pub agent: Account<'info, Agent>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + EarlyBuyerRewards::INIT_SPACE,
+        seeds = [b"early_buyer_rewards", agent.key().as_ref()],
+        bump
+    )]
+    pub rewards: Account<'info, EarlyBuyerRewards>,
+
+    /// Token account holding the bonuses, owned by the rewards PDA
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"early_buyer_vault", rewards.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = rewards
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = creator
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Fund a bonus of `bonus_per_buyer` tokens for each of the first
+/// `max_buyers` unique buyers of one of the creator's agents, claimable until
+/// `claim_deadline`
+pub fn handler(
+    ctx: Context<CreateEarlyBuyerRewards>,
+    max_buyers: u64,
+    bonus_per_buyer: u64,
+    claim_deadline: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        max_buyers > 0 && bonus_per_buyer > 0 && claim_deadline > now,
+        AgentFactoryError::InvalidEarlyBuyerRewards
+    );
+    let total = max_buyers
+        .checked_mul(bonus_per_buyer)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    // Fund every bonus up front
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.creator_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, total)?;
+
+    let rewards = &mut ctx.accounts.rewards;
+    rewards.agent = ctx.accounts.agent.key();
+    rewards.max_buyers = max_buyers;
+    rewards.bonus_per_buyer = bonus_per_buyer;
+    rewards.claim_deadline = claim_deadline;
+    rewards.claimed_count = 0;
+    rewards.created_at = now;
+    rewards.bump = ctx.bumps.rewards;
+
+    trace!("Early buyer rewards created!");
+    trace!("First buyers: {}", max_buyers);
+    trace!("Bonus per buyer: {}", bonus_per_buyer);
+    trace!("Claim deadline: {}", claim_deadline);
+
+    Ok(())
+}
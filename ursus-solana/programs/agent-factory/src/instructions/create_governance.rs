@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::state::{Agent, Governance};
+use crate::errors::AgentFactoryError;
+
+#[derive(Accounts)]
+pub struct CreateGovernance<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Governance::INIT_SPACE,
+        seeds = [b"governance", agent.key().as_ref()],
+        bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Hand parameter changes of one of the creator's agents to its stakers
+pub fn handler(
+    ctx: Context<CreateGovernance>,
+    quorum_bps: u16,
+    threshold_bps: u16,
+    voting_period_secs: i64,
+    proposal_threshold: u64,
+) -> Result<()> {
+    Governance::validate_config(quorum_bps, threshold_bps, voting_period_secs)?;
+
+    let governance = &mut ctx.accounts.governance;
+    governance.agent = ctx.accounts.agent.key();
+    governance.quorum_bps = quorum_bps;
+    governance.threshold_bps = threshold_bps;
+    governance.voting_period_secs = voting_period_secs;
+    governance.proposal_threshold = proposal_threshold;
+    governance.proposal_count = 0;
+    governance.bump = ctx.bumps.governance;
+
+    trace!("Governance created!");
+    trace!("Agent: {}", governance.agent);
+    trace!("Quorum: {} bps, threshold: {} bps", quorum_bps, threshold_bps);
+    trace!("Voting period: {}s", voting_period_secs);
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, BasketConstituent, IndexBasket, MAX_BASKET_CONSTITUENTS};
+
+#[derive(Accounts)]
+#[instruction(basket_id: u64)]
+pub struct CreateIndexBasket<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + IndexBasket::INIT_SPACE,
+        seeds = [
+            b"index_basket",
+            creator.key().as_ref(),
+            &basket_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub basket: Box<Account<'info, IndexBasket>>,
+
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = 9,
+        mint::authority = basket,
+        seeds = [b"basket_mint", basket.key().as_ref()],
+        bump
+    )]
+    pub basket_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Define a basket holding `units[i]` base units of the i-th agent token per
+/// whole basket token. The agents are passed as remaining accounts, in the
+/// same order as `units`.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateIndexBasket<'info>>,
+    basket_id: u64,
+    name: &str,
+    units: &[u64],
+) -> Result<()> {
+    require!(!name.is_empty() && name.len() <= 32, AgentFactoryError::InvalidName);
+    require!(
+        !units.is_empty() && units.len() <= MAX_BASKET_CONSTITUENTS,
+        AgentFactoryError::InvalidBasket
+    );
+    require!(
+        ctx.remaining_accounts.len() == units.len(),
+        AgentFactoryError::InvalidBasketAccounts
+    );
+
+    let mut constituents: Vec<BasketConstituent> = Vec::with_capacity(units.len());
+    for (info, &units) in ctx.remaining_accounts.iter().zip(units) {
+        let agent = Account::<Agent>::try_from(info)?;
+        require!(units > 0, AgentFactoryError::InvalidBasket);
+        require!(
+            constituents.iter().all(|c| c.agent != agent.key()),
+            AgentFactoryError::InvalidBasket
+        );
+        constituents.push(BasketConstituent {
+            agent: agent.key(),
+            mint: agent.mint,
+            units,
+        });
+    }
+
+    let basket = &mut ctx.accounts.basket;
+    basket.basket_id = basket_id;
+    basket.creator = ctx.accounts.creator.key();
+    basket.name = name.to_string();
+    basket.basket_mint = ctx.accounts.basket_mint.key();
+    basket.constituents = constituents;
+    basket.created_at = Clock::get()?.unix_timestamp;
+    basket.bump = ctx.bumps.basket;
+
+    trace!("Index basket created!");
+    trace!("Name: {}", basket.name);
+    trace!("Basket mint: {}", basket.basket_mint);
+    trace!("Constituents: {}", basket.constituents.len());
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, Job, Milestone, X402Config, MAX_JOB_DURATION_SECS, MAX_JOB_MILESTONES};
+
+#[derive(Accounts)]
+#[instruction(milestone_amounts: Vec<u64>, service_id: [u8; 32], deadline: i64, nonce: u64)]
+pub struct CreateJob<'info> {
+    pub agent: Box<Account<'info, Agent>>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Job::INIT_SPACE,
+        seeds = [
+            b"job",
+            agent.key().as_ref(),
+            payer.key().as_ref(),
+            &nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub job: Box<Account<'info, Job>>,
+
+    /// Holds the unreleased milestones until they are paid out or refunded
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = x402_config,
+        seeds = [b"job_escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payment token (e.g. USDC)
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Payer's USDC token account
+    #[account(mut)]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Hire an agent for a job paid in milestones. The sum of
+/// `milestone_amounts` is locked in escrow now and checked against the
+/// agent's payment limits; unsubmitted milestones can be reclaimed after
+/// `deadline`. `nonce` must be the agent's next x402 nonce.
+pub fn handler(
+    ctx: Context<CreateJob>,
+    milestone_amounts: Vec<u64>,
+    service_id: [u8; 32],
+    deadline: i64,
+    nonce: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !milestone_amounts.is_empty()
+            && milestone_amounts.len() <= MAX_JOB_MILESTONES
+            && milestone_amounts.iter().all(|&amount| amount > 0)
+            && deadline > now
+            && deadline - now <= MAX_JOB_DURATION_SECS,
+        AgentFactoryError::InvalidJobParams
+    );
+    let total = milestone_amounts
+        .iter()
+        .try_fold(0u64, |total, &amount| total.checked_add(amount))
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    let x402_config = &mut ctx.accounts.x402_config;
+    x402_config.validate_payment_request(nonce, &service_id)?;
+    x402_config.validate_payment_amount(total)?;
+    x402_config.increment_nonce()?;
+
+    // Lock every milestone in escrow
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TokenTransfer {
+            from: ctx.accounts.payer_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, total)?;
+
+    let job = &mut ctx.accounts.job;
+    job.agent = ctx.accounts.agent.key();
+    job.payer = ctx.accounts.payer.key();
+    job.mint = ctx.accounts.mint.key();
+    job.service_id = service_id;
+    job.milestones = milestone_amounts
+        .iter()
+        .map(|&amount| Milestone { amount, ..Milestone::default() })
+        .collect();
+    job.review_period_secs = x402_config.service_timeout_seconds as i64;
+    job.deadline = deadline;
+    job.released = 0;
+    job.refunded = 0;
+    job.evidence_hash = [0; 32];
+    job.dispute_votes = Vec::new();
+    job.created_at = now;
+    job.bump = ctx.bumps.job;
+
+    trace!("Job created!");
+    trace!("Milestones: {}, Total: {}, Deadline: {}", job.milestones.len(), total, deadline);
+
+    Ok(())
+}
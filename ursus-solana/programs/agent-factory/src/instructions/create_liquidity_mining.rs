@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, LiquidityMining};
+
+#[derive(Accounts)]
+pub struct CreateLiquidityMining<'info> {
+    #[account(
+        has_one = mint,
+        constraint = agent.is_graduated @ AgentFactoryError::NotGraduated
+    )]
+    pub agent: Account<'info, Agent>,
+
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// LP token of the agent's DEX pool
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + LiquidityMining::INIT_SPACE,
+        seeds = [b"liquidity_mining", agent.key().as_ref()],
+        bump
+    )]
+    pub mining: Box<Account<'info, LiquidityMining>>,
+
+    /// Token account holding the staked LP tokens, owned by the mining PDA
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"lm_lp_vault", mining.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = mining
+    )]
+    pub lp_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Token account holding the agent token rewards, owned by the mining PDA
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"lm_reward_vault", mining.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = mining
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Open liquidity mining for the DEX pool of one of the creator's graduated
+/// agents. Emissions start once the creator funds a reward period.
+pub fn handler(ctx: Context<CreateLiquidityMining>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let mining = &mut ctx.accounts.mining;
+    mining.agent = ctx.accounts.agent.key();
+    mining.lp_mint = ctx.accounts.lp_mint.key();
+    mining.total_staked = 0;
+    mining.reward_per_token = 0;
+    mining.reward_rate = 0;
+    mining.period_finish = now;
+    mining.last_update_ts = now;
+    mining.total_rewards_funded = 0;
+    mining.created_at = now;
+    mining.bump = ctx.bumps.mining;
+
+    trace!("Liquidity mining created!");
+    trace!("Agent: {}", mining.agent);
+    trace!("LP mint: {}", mining.lp_mint);
+
+    Ok(())
+}
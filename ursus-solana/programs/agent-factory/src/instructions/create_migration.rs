@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, TokenMigration};
+
+#[derive(Accounts)]
+pub struct CreateMigration<'info> {
+    #[account(has_one = mint)]
+    pub agent: Account<'info, Agent>,
+
+    pub mint: Box<Account<'info, Mint>>,
+
+    /// Mint of the token being migrated from
+    #[account(constraint = old_mint.key() != mint.key() @ AgentFactoryError::InvalidMigrationRatio)]
+    pub old_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + TokenMigration::INIT_SPACE,
+        seeds = [b"migration", agent.key().as_ref()],
+        bump
+    )]
+    pub migration: Box<Account<'info, TokenMigration>>,
+
+    /// Token account receiving the old tokens, owned by the migration PDA
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"migration_old_vault", migration.key().as_ref()],
+        bump,
+        token::mint = old_mint,
+        token::authority = migration
+    )]
+    pub old_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Token account holding the agent tokens paid out, owned by the
+    /// migration PDA
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"migration_vault", migration.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = migration
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = creator
+    )]
+    pub creator_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Register a swap from `old_mint` into one of the creator's agent tokens at
+/// `ratio_numerator / ratio_denominator`, funding the payout vault with
+/// `deposit_amount` agent tokens
+pub fn handler(
+    ctx: Context<CreateMigration>,
+    ratio_numerator: u64,
+    ratio_denominator: u64,
+    deposit_amount: u64,
+) -> Result<()> {
+    require!(
+        ratio_numerator > 0 && ratio_denominator > 0,
+        AgentFactoryError::InvalidMigrationRatio
+    );
+    require!(deposit_amount > 0, AgentFactoryError::InsufficientMigrationFunds);
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.creator_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, deposit_amount)?;
+
+    let migration = &mut ctx.accounts.migration;
+    migration.agent = ctx.accounts.agent.key();
+    migration.old_mint = ctx.accounts.old_mint.key();
+    migration.ratio_numerator = ratio_numerator;
+    migration.ratio_denominator = ratio_denominator;
+    migration.total_migrated = 0;
+    migration.total_paid = 0;
+    migration.created_at = Clock::get()?.unix_timestamp;
+    migration.bump = ctx.bumps.migration;
+
+    trace!("Token migration created!");
+    trace!("Old mint: {}", migration.old_mint);
+    trace!("Ratio: {}/{}", ratio_numerator, ratio_denominator);
+    trace!("Deposit: {}", deposit_amount);
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, Presale};
+
+#[derive(Accounts)]
+pub struct CreatePresale<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Presale::INIT_SPACE,
+        seeds = [b"presale", agent.key().as_ref()],
+        bump
+    )]
+    pub presale: Account<'info, Presale>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open a presale for a freshly created agent. Curve trading is paused until
+/// the presale is finalized.
+pub fn handler(
+    ctx: Context<CreatePresale>,
+    start_ts: i64,
+    end_ts: i64,
+    soft_cap: u64,
+    hard_cap: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let agent = &mut ctx.accounts.agent;
+
+    require!(!agent.is_graduated, AgentFactoryError::AlreadyGraduated);
+    require!(
+        agent.bonding_curve.tokens_sold() == 0 && agent.bonding_curve.real_sol_reserves == 0,
+        AgentFactoryError::InvalidPresaleConfig
+    );
+    require!(end_ts > start_ts && end_ts > now, AgentFactoryError::InvalidPresaleConfig);
+    require!(soft_cap > 0 && hard_cap >= soft_cap, AgentFactoryError::InvalidPresaleConfig);
+
+    agent.is_presale_active = true;
+
+    let presale = &mut ctx.accounts.presale;
+    presale.agent = agent.key();
+    presale.creator = ctx.accounts.creator.key();
+    presale.start_ts = start_ts;
+    presale.end_ts = end_ts;
+    presale.soft_cap = soft_cap;
+    presale.hard_cap = hard_cap;
+    presale.total_committed = 0;
+    presale.committer_count = 0;
+    presale.sol_spent = 0;
+    presale.tokens_allocated = 0;
+    presale.is_finalized = false;
+    presale.is_successful = false;
+    presale.bump = ctx.bumps.presale;
+
+    trace!("Presale created!");
+    trace!("Agent: {}", presale.agent);
+    trace!("Window: {} - {}", start_ts, end_ts);
+    trace!("Soft cap: {}, hard cap: {}", soft_cap, hard_cap);
+
+    Ok(())
+}
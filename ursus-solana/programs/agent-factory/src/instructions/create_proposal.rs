@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Governance, Proposal, ProposalAction, StakePosition, StakingPool};
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"governance", governance.agent.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        seeds = [b"staking_pool", governance.agent.as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Box<Account<'info, StakingPool>>,
+
+    /// Proposer's stake, checked against the proposal threshold
+    #[account(
+        seeds = [b"stake", staking_pool.key().as_ref(), proposer.key().as_ref()],
+        bump = position.bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [
+            b"proposal",
+            governance.agent.as_ref(),
+            &governance.proposal_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Put a parameter change to a vote of the agent's stakers
+pub fn handler(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+    action.validate()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let governance = &mut ctx.accounts.governance;
+
+    require!(
+        ctx.accounts.position.voting_weight(now) >= governance.proposal_threshold,
+        AgentFactoryError::InsufficientVotingPower
+    );
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.agent = governance.agent;
+    proposal.proposal_id = governance.proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.action = action;
+    // Weight is read just before creation so it cannot be moved between
+    // wallets within the voting window
+    proposal.snapshot_ts = now - 1;
+    proposal.start_ts = now;
+    proposal.end_ts = now
+        .checked_add(governance.voting_period_secs)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    proposal.total_voting_weight = ctx.accounts.staking_pool.total_voting_weight(now);
+    proposal.quorum_bps = governance.quorum_bps;
+    proposal.threshold_bps = governance.threshold_bps;
+    proposal.for_votes = 0;
+    proposal.against_votes = 0;
+    proposal.executed = false;
+    proposal.bump = ctx.bumps.proposal;
+
+    governance.proposal_count += 1;
+
+    trace!("Proposal created!");
+    trace!("Proposal ID: {}", proposal.proposal_id);
+    trace!("Voting ends: {}", proposal.end_ts);
+    trace!("Total voting weight: {}", proposal.total_voting_weight);
+
+    Ok(())
+}
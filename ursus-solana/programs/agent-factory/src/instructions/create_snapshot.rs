@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::events::SnapshotEvent;
+use crate::state::{Agent, HolderSnapshot};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateSnapshot<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(address = agent.mint)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + HolderSnapshot::INIT_SPACE,
+        seeds = [b"snapshot", agent.key().as_ref(), agent.snapshot_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub snapshot: Account<'info, HolderSnapshot>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Record a holder snapshot for one of the creator's agents
+pub fn handler(ctx: Context<CreateSnapshot>) -> Result<()> {
+    let clock = Clock::get()?;
+    let agent = &mut ctx.accounts.agent;
+    let snapshot_id = agent.snapshot_count;
+
+    let snapshot = &mut ctx.accounts.snapshot;
+    snapshot.agent = agent.key();
+    snapshot.snapshot_id = snapshot_id;
+    snapshot.slot = clock.slot;
+    snapshot.timestamp = clock.unix_timestamp;
+    snapshot.circulating_supply = ctx.accounts.mint.supply;
+    snapshot.bump = ctx.bumps.snapshot;
+
+    agent.snapshot_count = agent.snapshot_count
+        .checked_add(1)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("Holder snapshot recorded!");
+    trace!("Snapshot ID: {}", snapshot_id);
+    trace!("Slot: {}", clock.slot);
+    trace!("Circulating supply: {}", snapshot.circulating_supply);
+
+    emit_cpi!(SnapshotEvent {
+        agent: agent.key(),
+        snapshot: snapshot.key(),
+        snapshot_id,
+        slot: clock.slot,
+        circulating_supply: snapshot.circulating_supply,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
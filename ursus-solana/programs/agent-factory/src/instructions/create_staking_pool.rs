@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, StakingPool};
+
+#[derive(Accounts)]
+pub struct CreateStakingPool<'info> {
+    #[account(mut, has_one = mint)]
+    pub agent: Account<'info, Agent>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// Boxed: the vote-escrow slope table makes the pool too large for the stack
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + StakingPool::INIT_SPACE,
+        seeds = [b"staking_pool", agent.key().as_ref()],
+        bump
+    )]
+    pub staking_pool: Box<Account<'info, StakingPool>>,
+
+    /// Token account holding the staked tokens, owned by the pool PDA
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"stake_vault", staking_pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = staking_pool
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStakingPool<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", agent.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Box<Account<'info, StakingPool>>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Box<Account<'info, TokenAccount>>,
+}
+
+/// Open the staking pool of one of the creator's agents. From now on
+/// `staking_fee_bps` of every creator fee is routed to stakers.
+pub fn handler(
+    ctx: Context<CreateStakingPool>,
+    staking_fee_bps: u16,
+    emission_rate_cap: u64,
+) -> Result<()> {
+    require!(
+        Agent::fee_shares_valid(staking_fee_bps, ctx.accounts.agent.treasury_fee_bps),
+        AgentFactoryError::InvalidStakingConfig
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.agent.staking_fee_bps = staking_fee_bps;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    pool.agent = ctx.accounts.agent.key();
+    pool.total_staked = 0;
+    pool.reward_per_token = 0;
+    pool.undistributed_rewards = 0;
+    pool.total_rewards_distributed = 0;
+    pool.emission_rate_cap = emission_rate_cap;
+    pool.last_update_ts = now;
+    pool.created_at = now;
+    pool.ve_last_ts = now;
+    pool.ve_bias = 0;
+    pool.ve_slope = 0;
+    pool.bump = ctx.bumps.staking_pool;
+
+    trace!("Staking pool created!");
+    trace!("Agent: {}", pool.agent);
+    trace!("Creator fee share: {} bps", staking_fee_bps);
+    trace!("Emission rate cap: {} lamports/s", emission_rate_cap);
+
+    Ok(())
+}
+
+/// Update the fee share and emission cap of an agent's staking pool
+pub fn update_handler(
+    ctx: Context<UpdateStakingPool>,
+    staking_fee_bps: u16,
+    emission_rate_cap: u64,
+) -> Result<()> {
+    require!(
+        Agent::fee_shares_valid(staking_fee_bps, ctx.accounts.agent.treasury_fee_bps),
+        AgentFactoryError::InvalidStakingConfig
+    );
+
+    // Stream what was due under the old cap first
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.staking_pool.accrue(now)?;
+
+    ctx.accounts.agent.staking_fee_bps = staking_fee_bps;
+    ctx.accounts.staking_pool.emission_rate_cap = emission_rate_cap;
+
+    trace!("Staking pool updated!");
+    trace!("Creator fee share: {} bps", staking_fee_bps);
+    trace!("Emission rate cap: {} lamports/s", emission_rate_cap);
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, ProviderBond};
+
+#[derive(Accounts)]
+pub struct DepositProviderBond<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + ProviderBond::INIT_SPACE,
+        seeds = [b"provider_bond", agent.key().as_ref()],
+        bump
+    )]
+    pub provider_bond: Account<'info, ProviderBond>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Post (or add to) the SOL bond backing an agent's services
+pub fn handler(ctx: Context<DepositProviderBond>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidBondAmount);
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.creator.to_account_info(),
+            to: ctx.accounts.provider_bond.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)?;
+
+    let bond = &mut ctx.accounts.provider_bond;
+    if bond.agent == Pubkey::default() {
+        bond.agent = ctx.accounts.agent.key();
+        bond.created_at = Clock::get()?.unix_timestamp;
+        bond.bump = ctx.bumps.provider_bond;
+    }
+    bond.amount = bond.amount
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("Provider bond deposited!");
+    trace!("Amount: {}", amount);
+    trace!("Bonded: {}", bond.amount);
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer as TokenTransfer};
+use crate::errors::AgentFactoryError;
+use crate::events::RevenueDepositedEvent;
+use crate::state::{Agent, DaoTreasury, RevenuePool, StakingPool};
+use super::stake::collect_and_accrue;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DepositRevenue<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Agent's creator fee vault, swept into the staking pool first
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+
+    /// Required for `RevenuePool::Stakers`
+    #[account(
+        mut,
+        seeds = [b"staking_pool", agent.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Option<Box<Account<'info, StakingPool>>>,
+
+    /// Required for `RevenuePool::Treasury`
+    #[account(
+        mut,
+        seeds = [b"dao_treasury", agent.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Box<Account<'info, DaoTreasury>>>,
+
+    /// Depositor's token account, for token deposits into the treasury
+    #[account(mut)]
+    pub depositor_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Treasury's token account for the same mint
+    #[account(mut)]
+    pub treasury_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+/// Push revenue an agent earned outside x402 (from another program or an
+/// off-chain business) into its staking pool or DAO treasury, tagged with
+/// its `source` (permissionless). SOL unless the depositor's and treasury's
+/// token accounts are passed, which only the treasury takes.
+pub fn handler(
+    ctx: Context<DepositRevenue>,
+    pool: RevenuePool,
+    amount: u64,
+    source: [u8; 32],
+) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidRevenueAmount);
+    let token_deposit = ctx.accounts.depositor_token_account.is_some();
+
+    let mint = match pool {
+        RevenuePool::Stakers => {
+            require!(!token_deposit, AgentFactoryError::InvalidRevenueDeposit);
+            let staking_pool = ctx.accounts.staking_pool
+                .as_mut()
+                .ok_or(AgentFactoryError::InvalidRevenueDeposit)?;
+            collect_and_accrue(
+                &mut ctx.accounts.agent,
+                &ctx.accounts.creator_fee_vault,
+                staking_pool,
+                &ctx.accounts.system_program,
+                Clock::get()?.unix_timestamp,
+            )?;
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.depositor.to_account_info(),
+                        to: staking_pool.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+            staking_pool.deposit_rewards(amount)?;
+            Pubkey::default()
+        }
+        RevenuePool::Treasury => {
+            let treasury = ctx.accounts.treasury
+                .as_ref()
+                .ok_or(AgentFactoryError::InvalidRevenueDeposit)?;
+            match (
+                &ctx.accounts.depositor_token_account,
+                &ctx.accounts.treasury_token_account,
+                &ctx.accounts.token_program,
+            ) {
+                (Some(from), Some(to), Some(token_program)) => {
+                    require_keys_eq!(to.owner, treasury.key(), AgentFactoryError::InvalidRevenueDeposit);
+                    token::transfer(
+                        CpiContext::new(
+                            token_program.to_account_info(),
+                            TokenTransfer {
+                                from: from.to_account_info(),
+                                to: to.to_account_info(),
+                                authority: ctx.accounts.depositor.to_account_info(),
+                            },
+                        ),
+                        amount,
+                    )?;
+                    to.mint
+                }
+                (None, None, _) => {
+                    system_program::transfer(
+                        CpiContext::new(
+                            ctx.accounts.system_program.to_account_info(),
+                            system_program::Transfer {
+                                from: ctx.accounts.depositor.to_account_info(),
+                                to: treasury.to_account_info(),
+                            },
+                        ),
+                        amount,
+                    )?;
+                    Pubkey::default()
+                }
+                _ => return err!(AgentFactoryError::InvalidRevenueDeposit),
+            }
+        }
+    };
+
+    trace!("Revenue deposited!");
+    trace!("Pool: {:?}, Mint: {}, Amount: {}", pool, mint, amount);
+
+    emit_cpi!(RevenueDepositedEvent {
+        agent: ctx.accounts.agent.key(),
+        depositor: ctx.accounts.depositor.key(),
+        pool,
+        mint,
+        amount,
+        source,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
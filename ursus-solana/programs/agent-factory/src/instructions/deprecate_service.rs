@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::events::ServiceDeprecatedEvent;
+use crate::state::{Agent, X402Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DeprecateService<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Mark `service_id` as deprecated: new payments for it are rejected from
+/// `sunset_ts`, while escrowed payments already made can still settle. A
+/// later call moves the sunset; 0 withdraws the deprecation. Once the sunset
+/// has passed it is final.
+pub fn handler(ctx: Context<DeprecateService>, service_id: [u8; 32], sunset_ts: i64) -> Result<()> {
+    require!(service_id != [0; 32], AgentFactoryError::InvalidServiceId);
+    ctx.accounts.x402_config.require_service_live(&service_id)?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(sunset_ts == 0 || sunset_ts >= now, AgentFactoryError::InvalidSunset);
+
+    let x402_config = &mut ctx.accounts.x402_config;
+    x402_config.deprecate_service(&service_id, sunset_ts)?;
+
+    trace!("Service deprecated!");
+    trace!("Sunset: {}", sunset_ts);
+
+    emit_cpi!(ServiceDeprecatedEvent {
+        agent: x402_config.agent,
+        service_id,
+        sunset_ts,
+        timestamp: now,
+    });
+
+    Ok(())
+}
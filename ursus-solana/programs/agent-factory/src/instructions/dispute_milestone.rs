@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Job, MilestoneStatus};
+
+#[derive(Accounts)]
+pub struct DisputeMilestone<'info> {
+    #[account(
+        mut,
+        has_one = payer
+    )]
+    pub job: Box<Account<'info, Job>>,
+
+    pub payer: Signer<'info>,
+}
+
+/// Dispute a submitted milestone within its review period, recording the
+/// hash of the evidence. The arbitration council decides how much of it
+/// goes back to the payer; a job has one disputed milestone at a time.
+pub fn handler(ctx: Context<DisputeMilestone>, index: u8, evidence_hash: [u8; 32]) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let job = &mut ctx.accounts.job;
+    require!(!job.has_dispute(), AgentFactoryError::MilestoneDisputeOpen);
+    let milestone = *job.milestone_in(index, MilestoneStatus::Submitted)?;
+    require!(!job.review_ended(&milestone, now), AgentFactoryError::ReviewPeriodEnded);
+
+    job.milestones[index as usize].status = MilestoneStatus::Disputed;
+    job.evidence_hash = evidence_hash;
+    job.dispute_votes = Vec::new();
+
+    trace!("Milestone disputed!");
+    trace!("Job: {}, Milestone: {}", job.key(), index);
+
+    Ok(())
+}
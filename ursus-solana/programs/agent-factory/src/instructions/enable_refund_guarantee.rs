@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, MAX_REFUND_GUARANTEE_DAYS};
+
+#[derive(Accounts)]
+pub struct EnableRefundGuarantee<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Opt an agent into the refund guarantee: if it has not graduated within
+/// `days` of creation, trading stops and holders can `redeem_for_sol`. Only
+/// allowed before the first trade so every holder buys under the same terms.
+pub fn handler(ctx: Context<EnableRefundGuarantee>, days: u16) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+
+    require!(
+        days > 0 && days <= MAX_REFUND_GUARANTEE_DAYS,
+        AgentFactoryError::InvalidRefundGuarantee
+    );
+    require!(agent.refund_deadline == 0, AgentFactoryError::InvalidRefundGuarantee);
+    require!(
+        agent.bonding_curve.tokens_sold() == 0 && !agent.is_presale_active,
+        AgentFactoryError::InvalidRefundGuarantee
+    );
+
+    agent.refund_deadline = agent.created_at
+        .checked_add(days as i64 * 24 * 60 * 60)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("Refund guarantee enabled!");
+    trace!("Agent ID: {}", agent.agent_id);
+    trace!("Graduation deadline: {}", agent.refund_deadline);
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::events::TrialSessionEvent;
+use crate::state::{TrialSession, TrialStatus, X402Config};
+use super::settle_attested_payment::release_escrow;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EndTrial<'info> {
+    #[account(
+        seeds = [b"x402_config", session.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        has_one = caller,
+        close = caller
+    )]
+    pub session: Box<Account<'info, TrialSession>>,
+
+    #[account(
+        mut,
+        seeds = [b"trial_escrow", session.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Caller's USDC token account, refunded the deposit
+    #[account(
+        mut,
+        constraint = caller_token_account.owner == caller.key() @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub caller_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// End a trial session once served, refunding the deposit. Only possible
+/// before the session expires, unless the agent is frozen and can't collect
+/// a forfeit.
+pub fn handler(ctx: Context<EndTrial>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        !ctx.accounts.session.is_expired(now) || ctx.accounts.x402_config.frozen,
+        AgentFactoryError::TrialSessionExpired
+    );
+
+    release_escrow(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.caller_token_account.to_account_info(),
+        &ctx.accounts.caller.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+
+    let session = &ctx.accounts.session;
+    trace!("Trial ended!");
+    trace!("Caller: {}, Refunded: {}", session.caller, session.deposit);
+
+    emit_cpi!(TrialSessionEvent {
+        agent: session.agent,
+        session: session.key(),
+        caller: session.caller,
+        service_id: session.service_id,
+        deposit: session.deposit,
+        status: TrialStatus::Ended,
+        expires_at: session.expires_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
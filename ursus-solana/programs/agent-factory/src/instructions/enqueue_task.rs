@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use crate::errors::AgentFactoryError;
+use crate::events::WorkTaskEvent;
+use crate::state::{Agent, TaskStatus, WorkQueue, WorkTask, X402Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EnqueueTask<'info> {
+    pub agent: Box<Account<'info, Agent>>,
+
+    #[account(
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        seeds = [b"work_queue", agent.key().as_ref()],
+        bump = queue.bump
+    )]
+    pub queue: Box<Account<'info, WorkQueue>>,
+
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + WorkTask::INIT_SPACE,
+        seeds = [
+            b"work_task",
+            agent.key().as_ref(),
+            &queue.next_task_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub task: Box<Account<'info, WorkTask>>,
+
+    /// Holds `max_price` until the task is completed or withdrawn
+    #[account(
+        init,
+        payer = requester,
+        token::mint = mint,
+        token::authority = x402_config,
+        seeds = [b"task_escrow", task.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payment token (e.g. USDC)
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    /// Requester's USDC token account
+    #[account(mut)]
+    pub requester_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Queue a work request for the agent's workers with the hash of its
+/// parameters, escrowing up to `max_price`, which must be within the
+/// agent's payment limits. The task takes the queue's next id.
+pub fn handler(ctx: Context<EnqueueTask>, params_hash: [u8; 32], max_price: u64) -> Result<()> {
+    let x402_config = &ctx.accounts.x402_config;
+    x402_config.require_accepting_payments()?;
+    x402_config.validate_payment_amount(max_price)?;
+    require!(!ctx.accounts.queue.workers.is_empty(), AgentFactoryError::NoQueueWorkers);
+
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TokenTransfer {
+            from: ctx.accounts.requester_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.requester.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, max_price)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let queue = &mut ctx.accounts.queue;
+    let task = &mut ctx.accounts.task;
+    task.agent = ctx.accounts.agent.key();
+    task.task_id = queue.next_task_id;
+    task.requester = ctx.accounts.requester.key();
+    task.mint = ctx.accounts.mint.key();
+    task.params_hash = params_hash;
+    task.max_price = max_price;
+    task.status = TaskStatus::Open;
+    task.worker = Pubkey::default();
+    task.claimed_at = 0;
+    task.price = 0;
+    task.result_hash = [0; 32];
+    task.created_at = now;
+    task.bump = ctx.bumps.task;
+
+    queue.next_task_id = queue.next_task_id
+        .checked_add(1)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    queue.open_tasks = queue.open_tasks
+        .checked_add(1)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("Task enqueued!");
+    trace!("Task: {}, Max price: {}, Open: {}", task.task_id, max_price, queue.open_tasks);
+
+    emit_cpi!(WorkTaskEvent {
+        agent: task.agent,
+        task: task.key(),
+        task_id: task.task_id,
+        requester: task.requester,
+        worker: Pubkey::default(),
+        status: TaskStatus::Open,
+        params_hash,
+        max_price,
+        price: 0,
+        result_hash: [0; 32],
+        timestamp: now,
+    });
+
+    Ok(())
+}
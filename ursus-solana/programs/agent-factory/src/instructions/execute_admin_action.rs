@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::events::AdminActionEvent;
+use crate::state::{AdminAction, AdminActionStage, AgentFactory, QueuedAdminAction, MODEL_ID_LEN};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteAdminAction<'info> {
+    /// Factory singleton, updated by the action
+    #[account(
+        mut,
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Queued action whose timelock has expired; closed on execution
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"admin_action".as_ref(), &queued_action.action_id.to_le_bytes()],
+        bump = queued_action.bump,
+        has_one = rent_payer
+    )]
+    pub queued_action: Account<'info, QueuedAdminAction>,
+
+    /// CHECK: Receives the queued action's rent, checked against it
+    #[account(mut)]
+    pub rent_payer: AccountInfo<'info>,
+}
+
+/// Apply a queued admin action whose timelock has expired (permissionless)
+pub fn handler(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let queued = &ctx.accounts.queued_action;
+    let factory = &mut ctx.accounts.factory;
+
+    require!(now >= queued.eta, AgentFactoryError::TimelockNotExpired);
+
+    match queued.action {
+        AdminAction::SetCreationFee { new_fee } => {
+            trace!("Creation fee: {} -> {} lamports", factory.creation_fee, new_fee);
+            factory.creation_fee = new_fee;
+        }
+        AdminAction::SetQuoteCreationFee { quote_mint, fee } => {
+            trace!("Quote creation fee: {} of {}", fee, quote_mint);
+            // A rate set for the old quote mint doesn't value the new one
+            if quote_mint != factory.quote_mint {
+                factory.quote_lamport_rate = 0;
+            }
+            factory.quote_mint = quote_mint;
+            factory.quote_creation_fee = fee;
+        }
+        AdminAction::SetPlatformTreasury { new_treasury } => {
+            trace!("Platform treasury: {} -> {}", factory.platform_treasury, new_treasury);
+            factory.platform_treasury = new_treasury;
+        }
+        AdminAction::SetTimelockDelay { delay_secs } => {
+            trace!("Timelock delay: {}s -> {}s", factory.timelock_delay_secs, delay_secs);
+            factory.timelock_delay_secs = delay_secs;
+        }
+        AdminAction::SetAuthority { new_authority, admin_is_program } => {
+            trace!("Authority: {} -> {}", factory.authority, new_authority);
+            factory.authority = new_authority;
+            factory.admin_is_program = admin_is_program;
+        }
+        AdminAction::SetInsuranceFee { insurance_fee_bps } => {
+            trace!("Insurance fee: {} -> {} bps", factory.insurance_fee_bps, insurance_fee_bps);
+            factory.insurance_fee_bps = insurance_fee_bps;
+        }
+        AdminAction::SetFeeDiscounts { platform_mint, staking_pool, tiers } => {
+            trace!("Platform token: {} -> {}", factory.platform_mint, platform_mint);
+            factory.platform_mint = platform_mint;
+            factory.platform_staking_pool = staking_pool;
+            factory.fee_discount_tiers = tiers;
+        }
+        AdminAction::SetProtocolLiquidity { protocol_liquidity_bps } => {
+            trace!(
+                "Protocol liquidity: {} -> {} bps",
+                factory.protocol_liquidity_bps,
+                protocol_liquidity_bps
+            );
+            factory.protocol_liquidity_bps = protocol_liquidity_bps;
+        }
+        AdminAction::SetArbitrationCouncil { arbiters, threshold } => {
+            trace!("Arbitration council: {}-of-{} -> {}-of-{}",
+                factory.arbiter_threshold,
+                factory.arbiters.iter().filter(|a| **a != Pubkey::default()).count(),
+                threshold,
+                arbiters.iter().filter(|a| **a != Pubkey::default()).count()
+            );
+            factory.arbiters = arbiters;
+            factory.arbiter_threshold = threshold;
+        }
+        AdminAction::SetRenameFee { new_fee } => {
+            trace!("Rename fee: {} -> {} lamports", factory.rename_fee, new_fee);
+            factory.rename_fee = new_fee;
+        }
+        AdminAction::SetCreatorFeeVesting { vesting_secs } => {
+            trace!("Creator fee vesting: {} -> {} secs", factory.creator_fee_vesting_secs, vesting_secs);
+            factory.creator_fee_vesting_secs = vesting_secs;
+        }
+        AdminAction::SetAllowedModels { models } => {
+            let used = |models: &[[u8; MODEL_ID_LEN]]| models.iter().filter(|m| **m != [0; MODEL_ID_LEN]).count();
+            trace!("Allowed models: {} -> {}", used(&factory.allowed_models), used(&models));
+            factory.allowed_models = models;
+        }
+        AdminAction::SetCreationSurge { surge_bps, half_life_secs } => {
+            trace!("Creation surge: {} -> {} bps", factory.creation_surge_bps, surge_bps);
+            trace!("Creation surge half-life: {} -> {} secs", factory.creation_surge_half_life_secs, half_life_secs);
+            // Rebase the demand on the old half-life before switching
+            let now = Clock::get()?.unix_timestamp;
+            factory.creation_demand = factory.creation_demand_at(now);
+            factory.creation_demand_updated_at = now;
+            factory.creation_surge_bps = surge_bps;
+            factory.creation_surge_half_life_secs = half_life_secs;
+        }
+        AdminAction::SetReputationAttestor { attestor } => {
+            trace!("Reputation attestor: {} -> {}", factory.reputation_attestor, attestor);
+            factory.reputation_attestor = attestor;
+        }
+        AdminAction::SetPublicGoodsFee { recipient, fee_bps } => {
+            trace!("Public goods fee: {} -> {} bps", factory.public_goods_fee_bps, fee_bps);
+            trace!("Public goods recipient: {} -> {}", factory.public_goods_recipient, recipient);
+            factory.public_goods_fee_bps = fee_bps;
+            factory.public_goods_recipient = recipient;
+        }
+    }
+
+    emit_cpi!(AdminActionEvent {
+        action_id: queued.action_id,
+        action: queued.action,
+        stage: AdminActionStage::Executed,
+        eta: queued.eta,
+        timestamp: now,
+    });
+
+    trace!("Admin action executed!");
+    trace!("Action ID: {}", queued.action_id);
+
+    Ok(())
+}
@@ -0,0 +1,266 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::events::TradeEvent;
+use crate::state::{deposit_to_vault, Agent, AgentFactory, BuyerRecord, DcaSchedule, PriceHistory, TrendingLeaderboard};
+use super::buy_tokens::{record_buy_activity, record_buyer};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteDca<'info> {
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    #[account(
+        mut,
+        seeds = [b"dca", agent.key().as_ref(), dca.owner.as_ref()],
+        bump = dca.bump,
+        has_one = agent
+    )]
+    pub dca: Account<'info, DcaSchedule>,
+
+    /// Schedule's SOL deposit
+    #[account(
+        mut,
+        seeds = [b"dca_vault", dca.key().as_ref()],
+        bump
+    )]
+    pub dca_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        address = agent.mint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = dca.owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    /// Owner's buyer marker, created on their first buy of this agent
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = 8 + BuyerRecord::INIT_SPACE,
+        seeds = [b"buyer_record", agent.key().as_ref(), dca.owner.as_ref()],
+        bump
+    )]
+    pub buyer_record: Box<Account<'info, BuyerRecord>>,
+
+    /// CHECK: Creator receives fees
+    #[account(
+        mut,
+        address = agent.creator
+    )]
+    pub creator: AccountInfo<'info>,
+
+    /// CHECK: Platform treasury receives fees
+    #[account(
+        mut,
+        address = factory.platform_treasury
+    )]
+    pub platform_treasury: AccountInfo<'info>,
+
+    /// Keeper cranking the schedule; pays for the owner's buyer record on
+    /// the first execution
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Agent's candle accumulator; updated when passed
+    #[account(
+        mut,
+        seeds = [b"price_history", agent.key().as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Option<Box<Account<'info, PriceHistory>>>,
+
+    /// Factory trending leaderboard; updated when passed
+    #[account(
+        mut,
+        seeds = [b"trending"],
+        bump = trending.bump
+    )]
+    pub trending: Option<Box<Account<'info, TrendingLeaderboard>>>,
+
+    /// Agent's curve SOL vault
+    #[account(
+        mut,
+        seeds = [b"sol_vault", agent.key().as_ref()],
+        bump = agent.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Agent's platform fee vault
+    #[account(
+        mut,
+        seeds = [b"platform_fee_vault", agent.key().as_ref()],
+        bump = agent.platform_fee_vault_bump
+    )]
+    pub platform_fee_vault: SystemAccount<'info>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Execute the next due interval of a DCA schedule. Permissionless so any
+/// keeper can crank it; tokens always go to the schedule owner.
+pub fn handler(ctx: Context<ExecuteDca>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(!ctx.accounts.dca.is_complete(), AgentFactoryError::DcaCompleted);
+    require!(ctx.accounts.dca.is_due(now), AgentFactoryError::DcaNotDue);
+    require!(!ctx.accounts.agent.is_graduated, AgentFactoryError::AlreadyGraduated);
+    require!(!ctx.accounts.agent.is_presale_active, AgentFactoryError::PresaleActive);
+    require!(!ctx.accounts.agent.is_redeemable(now), AgentFactoryError::RedemptionOpen);
+
+    // Calculate tokens to receive and fees (1% platform, 1% creator) using bonding curve.
+    // A partial fill near graduation leaves the unspent SOL in the deposit.
+    let quote = ctx.accounts.agent.bonding_curve.quote_buy(ctx.accounts.dca.amount_per_interval)?;
+    let sol_amount = quote.sol_amount;
+    let tokens_out = quote.tokens_out;
+    let platform_fee = quote.platform_fee;
+    let creator_fee = quote.creator_fee;
+    let net_sol_amount = quote.net_sol_amount;
+    require!(tokens_out > 0, AgentFactoryError::InsufficientLiquidity);
+    let min_tokens_out = ctx.accounts.dca.min_tokens_out(sol_amount).ok_or(AgentFactoryError::MathOverflow)?;
+    require_gte!(tokens_out, min_tokens_out, AgentFactoryError::SlippageExceeded);
+    ctx.accounts.agent.check_max_buy(tokens_out)?;
+
+    // The staking pool's and treasury's shares of the creator fee, and the insurance
+    // fund's and public goods shares of the platform fee, are held in the agent's fee vaults
+    let (creator_fee_to_creator, retained_fee) = ctx.accounts.agent.split_creator_fee(creator_fee, now)?;
+    let (platform_fee_to_treasury, retained_platform_fee) = ctx.accounts.agent.split_platform_fee(platform_fee)?;
+
+    // Pay out of the schedule's deposit vault into the agent's vaults
+    // (bonding curve reserves + retained fee shares), the treasury and the creator
+    let dca_key = ctx.accounts.dca.key();
+    let vault_bump = [ctx.bumps.dca_vault];
+    let vault_seeds: &[&[u8]] = &[b"dca_vault", dca_key.as_ref(), &vault_bump];
+    let vault_signer = &[vault_seeds];
+    let dca_vault_info = ctx.accounts.dca_vault.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+    deposit_to_vault(&dca_vault_info, &ctx.accounts.sol_vault, &system_program_info, net_sol_amount, vault_signer)?;
+    deposit_to_vault(&dca_vault_info, &ctx.accounts.platform_fee_vault, &system_program_info, retained_platform_fee, vault_signer)?;
+    deposit_to_vault(&dca_vault_info, &ctx.accounts.creator_fee_vault, &system_program_info, retained_fee, vault_signer)?;
+    for (to, amount) in [
+        (&ctx.accounts.platform_treasury, platform_fee_to_treasury),
+        (&ctx.accounts.creator, creator_fee_to_creator),
+    ] {
+        if amount > 0 {
+            let cpi_context = CpiContext::new_with_signer(
+                system_program_info.clone(),
+                system_program::Transfer {
+                    from: dca_vault_info.clone(),
+                    to: to.to_account_info(),
+                },
+                vault_signer,
+            );
+            system_program::transfer(cpi_context, amount)?;
+        }
+    }
+
+    // Mint tokens to the schedule owner
+    let agent_id_bytes = ctx.accounts.agent.agent_id.to_le_bytes();
+    let agent_bump = ctx.accounts.agent.bump;
+    let seeds = &[
+        b"agent",
+        agent_id_bytes.as_ref(),
+        &[agent_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.owner_token_account.to_account_info(),
+        authority: ctx.accounts.agent.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    token::mint_to(cpi_ctx, tokens_out)?;
+
+    // Count the owner on their first buy
+    let clock = Clock::get()?;
+    let owner = ctx.accounts.dca.owner;
+    record_buyer(
+        &mut ctx.accounts.agent,
+        &mut ctx.accounts.buyer_record,
+        owner,
+        ctx.bumps.buyer_record,
+        &clock,
+    )?;
+
+    // Update bonding curve reserves
+    let curve_before = ctx.accounts.agent.bonding_curve;
+    ctx.accounts.agent.bonding_curve.update_after_buy(net_sol_amount, tokens_out)?;
+    ctx.accounts.agent.check_reserves(
+        &ctx.accounts.sol_vault,
+        &ctx.accounts.platform_fee_vault,
+        &ctx.accounts.creator_fee_vault,
+        &curve_before,
+        now,
+    )?;
+
+    // Advance the schedule
+    let dca = &mut ctx.accounts.dca;
+    dca.executed_intervals = dca.executed_intervals
+        .checked_add(1)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    dca.total_tokens_bought = dca.total_tokens_bought
+        .checked_add(tokens_out)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    dca.next_execution_ts = now
+        .checked_add(dca.interval_secs)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("DCA interval executed!");
+    trace!("Interval: {}/{}", dca.executed_intervals, dca.total_intervals);
+    trace!("SOL amount: {}", sol_amount);
+    trace!("Tokens received: {}", tokens_out);
+
+    let slot = clock.slot;
+    record_buy_activity(
+        &mut ctx.accounts.agent,
+        &curve_before,
+        sol_amount,
+        &mut ctx.accounts.price_history,
+        &mut ctx.accounts.trending,
+        &clock,
+    );
+    let sequence = ctx.accounts.agent.next_event_sequence()?;
+    emit_cpi!(TradeEvent {
+        agent: ctx.accounts.agent.key(),
+        trader: owner,
+        is_buy: true,
+        sol_amount,
+        token_amount: tokens_out,
+        platform_fee,
+        creator_fee,
+        price: ctx.accounts.agent.bonding_curve.get_current_price(),
+        timestamp: now,
+        slot,
+        sequence,
+        reserves_before: curve_before.reserves_at(now),
+        reserves_after: ctx.accounts.agent.bonding_curve.reserves_at(now),
+        price_impact_bps: ctx.accounts.agent.bonding_curve.price_impact_bps(&curve_before, now),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, DaoTreasury, Governance, Proposal, ProposalAction, X402Config};
+use super::collect_treasury_fees::collect;
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"governance", agent.key().as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            agent.key().as_ref(),
+            &proposal.proposal_id.to_le_bytes()
+        ],
+        bump = proposal.bump,
+        has_one = agent
+    )]
+    pub proposal: Box<Account<'info, Proposal>>,
+
+    /// Required for service price proposals
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Option<Account<'info, X402Config>>,
+
+    /// Required for treasury proposals
+    #[account(
+        mut,
+        seeds = [b"dao_treasury", agent.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Account<'info, DaoTreasury>>,
+
+    /// CHECK: SOL recipient of a `TransferSol` proposal, checked against it
+    #[account(mut)]
+    pub recipient: Option<UncheckedAccount<'info>>,
+
+    /// Source of a `TransferToken` proposal, owned by the treasury
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Destination of a `TransferToken` proposal, checked against it
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    /// Required for `TransferSol` proposals, which first collect the
+    /// treasury's pending fees
+    pub system_program: Option<Program<'info, System>>,
+
+    /// Agent's creator fee vault; required for `TransferSol` proposals
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: Option<SystemAccount<'info>>,
+}
+
+/// Apply a passed proposal once voting has ended (permissionless)
+pub fn handler(ctx: Context<ExecuteProposal>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+
+    require!(!proposal.executed, AgentFactoryError::ProposalExecuted);
+    require!(now >= proposal.end_ts, AgentFactoryError::VotingNotEnded);
+    require!(proposal.is_passed(), AgentFactoryError::ProposalNotPassed);
+
+    let agent = &mut ctx.accounts.agent;
+    match proposal.action.clone() {
+        ProposalAction::SetStakingFee { staking_fee_bps } => {
+            require!(
+                Agent::fee_shares_valid(staking_fee_bps, agent.treasury_fee_bps),
+                AgentFactoryError::InvalidProposal
+            );
+            agent.staking_fee_bps = staking_fee_bps;
+            trace!("Staking fee share: {} bps", staking_fee_bps);
+        }
+        ProposalAction::SetServicePrices { min_payment_amount, max_payment_amount } => {
+            let x402_config = ctx.accounts.x402_config
+                .as_mut()
+                .ok_or(AgentFactoryError::InvalidProposal)?;
+            x402_config.set_min_payment_amount(min_payment_amount, Clock::get()?.unix_timestamp)?;
+            x402_config.max_payment_amount = max_payment_amount;
+            trace!("Service prices: min {}, max {}", min_payment_amount, max_payment_amount);
+        }
+        ProposalAction::UpdateMetadata { description, tags } => {
+            agent.description = description;
+            agent.tags = tags;
+            trace!("Metadata updated");
+        }
+        ProposalAction::UpdateGovernance {
+            quorum_bps,
+            threshold_bps,
+            voting_period_secs,
+            proposal_threshold,
+        } => {
+            let governance = &mut ctx.accounts.governance;
+            governance.quorum_bps = quorum_bps;
+            governance.threshold_bps = threshold_bps;
+            governance.voting_period_secs = voting_period_secs;
+            governance.proposal_threshold = proposal_threshold;
+            trace!("Governance updated");
+        }
+        ProposalAction::SetTreasuryFee { treasury_fee_bps } => {
+            // Only route fees to a treasury that exists
+            require!(ctx.accounts.treasury.is_some(), AgentFactoryError::InvalidProposal);
+            require!(
+                Agent::fee_shares_valid(agent.staking_fee_bps, treasury_fee_bps),
+                AgentFactoryError::InvalidProposal
+            );
+            agent.treasury_fee_bps = treasury_fee_bps;
+            trace!("Treasury fee share: {} bps", treasury_fee_bps);
+        }
+        ProposalAction::TransferSol { recipient, amount } => {
+            let treasury = ctx.accounts.treasury
+                .as_mut()
+                .ok_or(AgentFactoryError::InvalidProposal)?;
+            let recipient_info = ctx.accounts.recipient
+                .as_ref()
+                .filter(|account| account.key() == recipient)
+                .ok_or(AgentFactoryError::InvalidProposal)?;
+
+            let (Some(creator_fee_vault), Some(system_program)) = (
+                ctx.accounts.creator_fee_vault.as_ref(),
+                ctx.accounts.system_program.as_ref(),
+            ) else {
+                return err!(AgentFactoryError::InvalidProposal);
+            };
+            collect(agent, creator_fee_vault, treasury, system_program)?;
+
+            // Keep the treasury rent-exempt
+            let treasury_info = treasury.to_account_info();
+            let rent_exempt = Rent::get()?.minimum_balance(treasury_info.data_len());
+            require!(
+                treasury_info.lamports().saturating_sub(rent_exempt) >= amount,
+                AgentFactoryError::InsufficientTreasuryFunds
+            );
+
+            **treasury_info.try_borrow_mut_lamports()? -= amount;
+            **recipient_info.try_borrow_mut_lamports()? += amount;
+            treasury.total_sol_spent = treasury.total_sol_spent
+                .checked_add(amount)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            trace!("Treasury paid {} lamports to {}", amount, recipient);
+        }
+        ProposalAction::TransferToken { mint, recipient, amount } => {
+            let (
+                Some(treasury),
+                Some(treasury_token_account),
+                Some(recipient_token_account),
+                Some(token_program),
+            ) = (
+                ctx.accounts.treasury.as_ref(),
+                ctx.accounts.treasury_token_account.as_ref(),
+                ctx.accounts.recipient_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+            ) else {
+                return err!(AgentFactoryError::InvalidProposal);
+            };
+            require!(
+                treasury_token_account.owner == treasury.key()
+                    && treasury_token_account.mint == mint
+                    && recipient_token_account.key() == recipient,
+                AgentFactoryError::InvalidProposal
+            );
+            require!(
+                treasury_token_account.amount >= amount,
+                AgentFactoryError::InsufficientTreasuryFunds
+            );
+
+            let agent_key = agent.key();
+            let seeds = &[
+                b"dao_treasury",
+                agent_key.as_ref(),
+                &[treasury.bump],
+            ];
+            let signer_seeds = &[&seeds[..]];
+
+            let cpi_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Transfer {
+                    from: treasury_token_account.to_account_info(),
+                    to: recipient_token_account.to_account_info(),
+                    authority: treasury.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, amount)?;
+            trace!("Treasury paid {} of {} to {}", amount, mint, recipient);
+        }
+    }
+
+    proposal.executed = true;
+
+    trace!("Proposal executed!");
+    trace!("Proposal ID: {}", proposal.proposal_id);
+
+    Ok(())
+}
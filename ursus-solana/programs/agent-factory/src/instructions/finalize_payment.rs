@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Dispute, DisputeStatus, PaymentStatus, X402PaymentRecord};
+
+#[derive(Accounts)]
+pub struct FinalizePayment<'info> {
+    #[account(
+        mut,
+        constraint = matches!(
+            payment_record.status,
+            PaymentStatus::Verified | PaymentStatus::Settled
+        ) @ AgentFactoryError::PaymentNotFinalizable
+    )]
+    pub payment_record: Account<'info, X402PaymentRecord>,
+
+    /// CHECK: The payment's dispute PDA, checked for an open dispute if it
+    /// exists
+    #[account(
+        seeds = [b"dispute", payment_record.key().as_ref()],
+        bump
+    )]
+    pub dispute: UncheckedAccount<'info>,
+}
+
+/// Close a settled payment to disputes once its chargeback window has
+/// elapsed, giving the provider finality; payments settled without a window
+/// can be finalized at once. Permissionless; a dispute filed within the
+/// window must be resolved first.
+pub fn handler(ctx: Context<FinalizePayment>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let payment_record = &mut ctx.accounts.payment_record;
+    require!(
+        payment_record.finalized_at == 0 && now > payment_record.dispute_deadline,
+        AgentFactoryError::PaymentNotFinalizable
+    );
+
+    // Only the program can fill its own PDA, so any data is a dispute
+    if !ctx.accounts.dispute.data_is_empty() {
+        let dispute = Dispute::try_deserialize(&mut &ctx.accounts.dispute.try_borrow_data()?[..])?;
+        require!(dispute.status != DisputeStatus::Open, AgentFactoryError::PaymentDisputeOpen);
+    }
+
+    payment_record.finalized_at = now;
+
+    trace!("Payment finalized!");
+    trace!("Agent: {}, Payer: {}", payment_record.agent, payment_record.payer);
+
+    Ok(())
+}
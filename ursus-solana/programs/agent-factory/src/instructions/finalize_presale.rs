@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::events::TradeEvent;
+use crate::state::{Agent, AgentFactory, Presale};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizePresale<'info> {
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    #[account(
+        mut,
+        seeds = [b"presale", agent.key().as_ref()],
+        bump = presale.bump,
+        has_one = agent
+    )]
+    pub presale: Account<'info, Presale>,
+
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    /// CHECK: Creator receives fees
+    #[account(
+        mut,
+        address = agent.creator
+    )]
+    pub creator: AccountInfo<'info>,
+
+    /// CHECK: Platform treasury receives fees
+    #[account(
+        mut,
+        address = factory.platform_treasury
+    )]
+    pub platform_treasury: AccountInfo<'info>,
+
+    /// Anyone may finalize once the window has ended
+    pub cranker: Signer<'info>,
+
+    /// Agent's curve SOL vault
+    #[account(
+        mut,
+        seeds = [b"sol_vault", agent.key().as_ref()],
+        bump = agent.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Agent's platform fee vault
+    #[account(
+        mut,
+        seeds = [b"platform_fee_vault", agent.key().as_ref()],
+        bump = agent.platform_fee_vault_bump
+    )]
+    pub platform_fee_vault: SystemAccount<'info>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Close the presale once its window has ended (permissionless). If the soft
+/// cap was met, up to `hard_cap` of the committed SOL buys into the curve in a
+/// single trade, seeding its reserves at one uniform clearing price; the
+/// tokens are minted to committers by `claim_presale`. Either way curve
+/// trading resumes.
+pub fn handler(ctx: Context<FinalizePresale>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(!ctx.accounts.presale.is_finalized, AgentFactoryError::PresaleFinalized);
+    require!(now >= ctx.accounts.presale.end_ts, AgentFactoryError::PresaleNotEnded);
+
+    let total_committed = ctx.accounts.presale.total_committed;
+    let successful = total_committed > 0 && total_committed >= ctx.accounts.presale.soft_cap;
+
+    ctx.accounts.presale.is_finalized = true;
+    ctx.accounts.presale.is_successful = successful;
+    ctx.accounts.agent.is_presale_active = false;
+
+    if !successful {
+        trace!("Presale failed: soft cap not met");
+        trace!("Total committed: {}", total_committed);
+        return Ok(());
+    }
+
+    // Buy into the curve with the accepted SOL. A partial fill near
+    // graduation leaves the unspent SOL in the vault for refunds.
+    let spend = total_committed.min(ctx.accounts.presale.hard_cap);
+    let quote = ctx.accounts.agent.bonding_curve.quote_buy(spend)?;
+    let sol_amount = quote.sol_amount;
+    let tokens_out = quote.tokens_out;
+    let platform_fee = quote.platform_fee;
+    let creator_fee = quote.creator_fee;
+    let net_sol_amount = quote.net_sol_amount;
+    require!(tokens_out > 0, AgentFactoryError::InsufficientLiquidity);
+
+    // Pay out of the presale vault (program-owned, so debit directly).
+    // The staking pool's and treasury's shares of the creator fee, and the insurance
+    // fund's and public goods shares of the platform fee, are held in the agent's fee vaults.
+    let (creator_fee_to_creator, retained_fee) = ctx.accounts.agent.split_creator_fee(creator_fee, now)?;
+    let (platform_fee_to_treasury, retained_platform_fee) = ctx.accounts.agent.split_platform_fee(platform_fee)?;
+    let presale_info = ctx.accounts.presale.to_account_info();
+    **presale_info.try_borrow_mut_lamports()? -= sol_amount;
+    **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? += net_sol_amount;
+    **ctx.accounts.platform_fee_vault.to_account_info().try_borrow_mut_lamports()? += retained_platform_fee;
+    **ctx.accounts.creator_fee_vault.to_account_info().try_borrow_mut_lamports()? += retained_fee;
+    **ctx.accounts.platform_treasury.to_account_info().try_borrow_mut_lamports()? += platform_fee_to_treasury;
+    **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += creator_fee_to_creator;
+
+    // Seed the curve; tokens are minted lazily as committers claim
+    let curve_before = ctx.accounts.agent.bonding_curve;
+    ctx.accounts.agent.bonding_curve.update_after_buy(net_sol_amount, tokens_out)?;
+    ctx.accounts.agent.check_reserves(
+        &ctx.accounts.sol_vault,
+        &ctx.accounts.platform_fee_vault,
+        &ctx.accounts.creator_fee_vault,
+        &curve_before,
+        now,
+    )?;
+    let slot = Clock::get()?.slot;
+    ctx.accounts.agent.record_twap_price(now, slot);
+    ctx.accounts.agent.last_buy_slot = slot;
+
+    let presale = &mut ctx.accounts.presale;
+    presale.sol_spent = sol_amount;
+    presale.tokens_allocated = tokens_out;
+
+    trace!("Presale finalized!");
+    trace!("Total committed: {}", total_committed);
+    trace!("SOL spent: {}", sol_amount);
+    trace!("Tokens allocated: {}", tokens_out);
+
+    let sequence = ctx.accounts.agent.next_event_sequence()?;
+    emit_cpi!(TradeEvent {
+        agent: ctx.accounts.agent.key(),
+        trader: presale.key(),
+        is_buy: true,
+        sol_amount,
+        token_amount: tokens_out,
+        platform_fee,
+        creator_fee,
+        price: ctx.accounts.agent.bonding_curve.get_current_price(),
+        timestamp: now,
+        slot: Clock::get()?.slot,
+        sequence,
+        reserves_before: curve_before.reserves_at(now),
+        reserves_after: ctx.accounts.agent.bonding_curve.reserves_at(now),
+        price_impact_bps: ctx.accounts.agent.bonding_curve.price_impact_bps(&curve_before, now),
+    });
+
+    Ok(())
+}
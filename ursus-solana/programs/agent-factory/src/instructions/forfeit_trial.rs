@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::events::TrialSessionEvent;
+use crate::state::{TrialSession, TrialStatus, X402Config};
+use super::settle_attested_payment::release_escrow;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ForfeitTrial<'info> {
+    #[account(
+        seeds = [b"x402_config", session.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        has_one = caller,
+        close = caller
+    )]
+    pub session: Box<Account<'info, TrialSession>>,
+
+    #[account(
+        mut,
+        seeds = [b"trial_escrow", session.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Recipient's USDC token account, paid the deposit
+    #[account(
+        mut,
+        constraint = x402_config.accepts_recipient(&recipient_token_account.owner, Clock::get()?.unix_timestamp) @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Caller, receives the session's and its escrow's rent
+    #[account(mut)]
+    pub caller: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Forfeit an expired trial session the caller abandoned, paying its
+/// deposit to the provider. Funds only go to the agent's payment recipient,
+/// so anyone can crank it.
+pub fn handler(ctx: Context<ForfeitTrial>) -> Result<()> {
+    // A frozen agent can't collect; the caller can still end the session
+    require!(!ctx.accounts.x402_config.frozen, AgentFactoryError::X402Frozen);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.session.is_expired(now),
+        AgentFactoryError::TrialSessionActive
+    );
+
+    release_escrow(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        &ctx.accounts.caller,
+        &ctx.accounts.token_program,
+    )?;
+
+    let session = &ctx.accounts.session;
+    trace!("Trial forfeited!");
+    trace!("Caller: {}, Forfeited: {}", session.caller, session.deposit);
+
+    emit_cpi!(TrialSessionEvent {
+        agent: session.agent,
+        session: session.key(),
+        caller: session.caller,
+        service_id: session.service_id,
+        deposit: session.deposit,
+        status: TrialStatus::Forfeited,
+        expires_at: session.expires_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
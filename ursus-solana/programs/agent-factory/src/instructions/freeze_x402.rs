@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::events::X402FreezeEvent;
+use crate::state::{AgentFactory, X402Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FreezeX402<'info> {
+    /// Factory singleton
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", x402_config.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    /// Factory authority (wallet, multisig or DAO PDA signer)
+    pub authority: Signer<'info>,
+}
+
+/// Freeze or thaw an agent's x402 payments. Applies immediately rather than
+/// through the timelock so the authority can stop payments to an agent whose
+/// operator keys were compromised; the creator can't lift it with `enabled`.
+pub fn handler(ctx: Context<FreezeX402>, frozen: bool) -> Result<()> {
+    let x402_config = &mut ctx.accounts.x402_config;
+    x402_config.frozen = frozen;
+
+    trace!("X402 payments {}!", if frozen { "frozen" } else { "thawed" });
+    trace!("Agent: {}", x402_config.agent);
+
+    emit_cpi!(X402FreezeEvent {
+        agent: x402_config.agent,
+        authority: ctx.accounts.authority.key(),
+        frozen,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::AgentFactoryError;
+use crate::state::InsuranceFund;
+
+#[derive(Accounts)]
+pub struct FundInsurance<'info> {
+    /// Insurance fund receiving the deposit
+    #[account(
+        mut,
+        seeds = [b"insurance_fund"],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    /// Anyone may deposit
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Deposit SOL into the insurance fund, e.g. a share of x402 service revenue
+pub fn handler(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidInsuranceAmount);
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.insurance_fund.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)?;
+
+    let fund = &mut ctx.accounts.insurance_fund;
+    fund.total_deposits = fund.total_deposits
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("Insurance fund deposit: {}", amount);
+    trace!("Total deposits: {}", fund.total_deposits);
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::AgentFactoryError;
+use crate::state::KeeperTask;
+
+#[derive(Accounts)]
+pub struct FundKeeperTask<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"keeper_task",
+            task.owner.as_ref(),
+            &task.task_id.to_le_bytes()
+        ],
+        bump = task.bump
+    )]
+    pub task: Account<'info, KeeperTask>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Top up a keeper task's tip deposit (permissionless)
+pub fn handler(ctx: Context<FundKeeperTask>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidKeeperAmount);
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.task.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)?;
+
+    trace!("Keeper task funded!");
+    trace!("Amount: {}", amount);
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, LiquidityMining};
+
+#[derive(Accounts)]
+pub struct FundLiquidityMining<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"liquidity_mining", agent.key().as_ref()],
+        bump = mining.bump
+    )]
+    pub mining: Box<Account<'info, LiquidityMining>>,
+
+    #[account(
+        mut,
+        seeds = [b"lm_reward_vault", mining.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = agent.mint,
+        token::authority = creator
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Deposit `amount` agent tokens and emit them, plus what the running period
+/// has left, evenly over the next `duration_secs`
+pub fn handler(ctx: Context<FundLiquidityMining>, amount: u64, duration_secs: i64) -> Result<()> {
+    require!(amount > 0 && duration_secs > 0, AgentFactoryError::InvalidRewardSchedule);
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.creator_token_account.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let mining = &mut ctx.accounts.mining;
+    mining.accrue(now)?;
+    mining.schedule(amount, duration_secs, now)?;
+
+    trace!("Liquidity mining funded!");
+    trace!("Amount: {}", amount);
+    trace!("Reward rate: {}/s", mining.reward_rate);
+    trace!("Period ends at: {}", mining.period_finish);
+
+    Ok(())
+}
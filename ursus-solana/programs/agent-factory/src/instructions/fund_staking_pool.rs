@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, StakingPool};
+use super::stake::collect_and_accrue;
+
+#[derive(Accounts)]
+pub struct FundStakingPool<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", agent.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Box<Account<'info, StakingPool>>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Add SOL rewards to a staking pool, e.g. the agent's share of x402 service
+/// revenue. Streamed to stakers under the same emission cap as fee rewards.
+pub fn handler(ctx: Context<FundStakingPool>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidStakeAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    collect_and_accrue(
+        &mut ctx.accounts.agent,
+        &ctx.accounts.creator_fee_vault,
+        &mut ctx.accounts.staking_pool,
+        &ctx.accounts.system_program,
+        now,
+    )?;
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.staking_pool.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, amount)?;
+    ctx.accounts.staking_pool.deposit_rewards(amount)?;
+
+    trace!("Staking pool funded!");
+    trace!("Amount: {}", amount);
+    trace!("Undistributed rewards: {}", ctx.accounts.staking_pool.undistributed_rewards);
+
+    Ok(())
+}
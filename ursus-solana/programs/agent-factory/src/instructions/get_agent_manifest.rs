@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::state::{Agent, AgentManifest, X402Config, MAX_MANIFEST_MINTS};
+
+#[derive(Accounts)]
+pub struct GetAgentManifest<'info> {
+    pub agent: Account<'info, Agent>,
+
+    /// Agent's x402 config, if it has one
+    #[account(
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Option<Account<'info, X402Config>>,
+}
+
+/// Return the agent's `AgentManifest`: capabilities, service prices,
+/// deprecated services and operator keys. Remaining accounts are token
+/// accounts for the mints the caller could pay in; those owned by the
+/// payment recipient are listed as accepted mints.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, GetAgentManifest<'info>>) -> Result<AgentManifest> {
+    let config = ctx.accounts.x402_config.as_deref();
+    let accepted_mints = match config {
+        Some(config) => ctx.remaining_accounts
+            .iter()
+            .filter_map(|info| Account::<TokenAccount>::try_from(info).ok())
+            .filter(|token_account| token_account.owner == config.payment_recipient)
+            .map(|token_account| token_account.mint)
+            .take(MAX_MANIFEST_MINTS)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(AgentManifest::new(
+        ctx.accounts.agent.key(),
+        &ctx.accounts.agent,
+        config,
+        accepted_mints,
+        Clock::get()?.unix_timestamp,
+    ))
+}
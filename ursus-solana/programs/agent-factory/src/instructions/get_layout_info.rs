@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::registry::{layout_of, LayoutInfo};
+use crate::state::AgentFactory;
+
+#[derive(Accounts)]
+pub struct GetLayoutInfo<'info> {
+    /// Factory singleton, identifying the deployment being queried
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+}
+
+/// Return the name, size and layout version of the account type with
+/// `discriminator` from the program's layout registry
+pub fn handler(_ctx: Context<GetLayoutInfo>, discriminator: [u8; 8]) -> Result<LayoutInfo> {
+    let layout = layout_of(&discriminator).ok_or(AgentFactoryError::UnknownAccountLayout)?;
+    Ok(layout.into())
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::Agent;
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    pub agent: Account<'info, Agent>,
+}
+
+/// Return the agent's TWAP (nano-lamports per token) over at least
+/// `window_slots`, for programs that CPI for a price
+pub fn handler(ctx: Context<GetTwap>, window_slots: u64) -> Result<u64> {
+    let slot = Clock::get()?.slot;
+    ctx.accounts.agent
+        .get_twap(window_slots, slot)
+        .ok_or(error!(AgentFactoryError::TwapUnavailable))
+}
@@ -1,19 +1,92 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
 use crate::errors::AgentFactoryError;
+use crate::events::AgentGraduatedEvent;
+use crate::state::{Agent, AgentFactory, AgentVault, CreatorStats, ProtocolLiquidity};
 
-pub fn handler(ctx: Context<crate::GraduateAgent>) -> Result<()> {
-    let agent = &mut ctx.accounts.agent;
-    
-    // Check if agent can graduate
-    require!(agent.can_graduate(), AgentFactoryError::CannotGraduate);
+#[event_cpi]
+#[derive(Accounts)]
+pub struct GraduateAgent<'info> {
+    #[account(
+        mut,
+        constraint = !agent.is_graduated @ AgentFactoryError::AlreadyGraduated
+    )]
+    pub agent: Account<'info, Agent>,
 
-    // Mark as graduated
-    agent.is_graduated = true;
+    #[account(
+        mut,
+        address = agent.mint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: DEX program for liquidity
+    pub dex_program: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Creator's track record, credited with the graduation
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + CreatorStats::INIT_SPACE,
+        seeds = [b"creator_stats", agent.creator.as_ref()],
+        bump
+    )]
+    pub creator_stats: Box<Account<'info, CreatorStats>>,
+
+    /// Factory singleton, read for the protocol-owned liquidity share
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Box<Account<'info, AgentFactory>>,
+
+    /// Protocol-owned liquidity; required while the share is non-zero
+    #[account(
+        mut,
+        seeds = [b"protocol_liquidity"],
+        bump = protocol_liquidity.bump
+    )]
+    pub protocol_liquidity: Option<Box<Account<'info, ProtocolLiquidity>>>,
+
+    /// Protocol-owned liquidity's token account for this agent's mint
+    #[account(mut)]
+    pub protocol_liquidity_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Agent's curve SOL vault
+    #[account(
+        mut,
+        seeds = [b"sol_vault", agent.key().as_ref()],
+        bump = agent.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+}
+
+pub fn handler(ctx: Context<GraduateAgent>) -> Result<()> {
+    // Credit the creator's track record
+    let creator_stats = &mut ctx.accounts.creator_stats;
+    if creator_stats.creator == Pubkey::default() {
+        creator_stats.creator = ctx.accounts.agent.creator;
+        creator_stats.bump = ctx.bumps.creator_stats;
+    }
 
-    msg!("Agent graduated to DEX!");
-    msg!("Agent ID: {}", agent.agent_id);
-    msg!("Final SOL reserves: {}", agent.bonding_curve.real_sol_reserves);
-    msg!("Tokens for DEX liquidity: {}", agent.bonding_curve.real_token_reserves);
+    let event = graduate(
+        &mut ctx.accounts.agent,
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.sol_vault,
+        creator_stats,
+        ctx.accounts.factory.protocol_liquidity_bps,
+        ctx.accounts.protocol_liquidity.as_deref_mut(),
+        ctx.accounts.protocol_liquidity_token_account.as_deref(),
+        &ctx.accounts.token_program,
+        &ctx.accounts.system_program,
+        Clock::get()?.unix_timestamp,
+    )?;
+    emit_cpi!(event);
 
     // TODO: Integrate with Raydium/Orca to create liquidity pool
     // This would involve:
@@ -24,3 +97,112 @@ pub fn handler(ctx: Context<crate::GraduateAgent>) -> Result<()> {
     Ok(())
 }
 
+/// Graduate an eligible agent: credit the creator's track record, keep the
+/// protocol's liquidity share and burn the unsold curve tokens if the creator
+/// chose to.
+/// Shared by `graduate_agent` and `graduate_batch`.
+#[allow(clippy::too_many_arguments)]
+pub fn graduate<'info>(
+    agent: &mut Account<'info, Agent>,
+    mint: &AccountInfo<'info>,
+    sol_vault: &AccountInfo<'info>,
+    creator_stats: &mut CreatorStats,
+    protocol_liquidity_bps: u16,
+    protocol_liquidity: Option<&mut Account<'info, ProtocolLiquidity>>,
+    protocol_liquidity_token_account: Option<&Account<'info, TokenAccount>>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    now: i64,
+) -> Result<AgentGraduatedEvent> {
+    // Check if agent can graduate
+    require!(agent.can_graduate(), AgentFactoryError::CannotGraduate);
+    require!(!agent.is_redeemable(now), AgentFactoryError::RedemptionOpen);
+
+    // Mark as graduated
+    agent.is_graduated = true;
+    agent.graduated_at = now;
+
+    creator_stats.agents_graduated = creator_stats.agents_graduated
+        .checked_add(1)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    creator_stats.graduated_volume = creator_stats.graduated_volume
+        .checked_add(agent.bonding_curve.real_sol_reserves)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    // Keep the protocol's share of the liquidity, before any burn so it
+    // gets its matching tokens
+    if protocol_liquidity_bps > 0 {
+        let (Some(protocol_liquidity), Some(pol_token_account)) =
+            (protocol_liquidity, protocol_liquidity_token_account)
+        else {
+            return err!(AgentFactoryError::ProtocolLiquidityRequired);
+        };
+        require!(
+            pol_token_account.mint == agent.mint && pol_token_account.owner == protocol_liquidity.key(),
+            AgentFactoryError::ProtocolLiquidityRequired
+        );
+        
+        let (pol_sol, pol_tokens) = agent.bonding_curve.take_protocol_liquidity(protocol_liquidity_bps);
+        
+        AgentVault::Sol.pay(
+            &agent.key(),
+            agent,
+            sol_vault,
+            &protocol_liquidity.to_account_info(),
+            system_program,
+            pol_sol,
+        )?;
+        
+        let agent_id_bytes = agent.agent_id.to_le_bytes();
+        let seeds = &[
+            b"agent",
+            agent_id_bytes.as_ref(),
+            &[agent.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.clone(),
+            MintTo {
+                mint: mint.clone(),
+                to: pol_token_account.to_account_info(),
+                authority: agent.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::mint_to(cpi_ctx, pol_tokens)?;
+        
+        protocol_liquidity.total_sol_contributed = protocol_liquidity.total_sol_contributed
+            .checked_add(pol_sol)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        protocol_liquidity.graduations = protocol_liquidity.graduations
+            .checked_add(1)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        
+        trace!("Protocol-owned liquidity: {} lamports, {} tokens", pol_sol, pol_tokens);
+    }
+
+    // Supply-reduction graduation: curve tokens are only minted when bought,
+    // so burning the undistributed reserves retires them from the supply
+    let tokens_burned = if agent.burn_remaining_at_graduation {
+        let burned = agent.bonding_curve.burn_remaining()?;
+        trace!("Unsold curve tokens burned: {}", burned);
+        burned
+    } else {
+        0
+    };
+
+    trace!("Agent graduated to DEX!");
+    trace!("Agent ID: {}", agent.agent_id);
+    trace!("Final SOL reserves: {}", agent.bonding_curve.real_sol_reserves);
+    trace!("Tokens for DEX liquidity: {}", agent.bonding_curve.real_token_reserves);
+    trace!("Total supply: {}", agent.bonding_curve.total_supply);
+
+    Ok(AgentGraduatedEvent {
+        agent: agent.key(),
+        creator: agent.creator,
+        sol_reserves: agent.bonding_curve.real_sol_reserves,
+        token_reserves: agent.bonding_curve.real_token_reserves,
+        tokens_burned,
+        timestamp: now,
+    })
+}
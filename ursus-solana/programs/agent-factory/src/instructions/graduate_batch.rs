@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, AgentFactory, AgentVault, CreatorStats, ProtocolLiquidity};
+use super::graduate_agent::graduate;
+
+/// Most agents one `graduate_batch` can graduate
+pub const MAX_GRADUATION_BATCH: usize = 8;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct GraduateBatch<'info> {
+    /// Factory singleton, read for the protocol-owned liquidity share
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Box<Account<'info, AgentFactory>>,
+
+    /// Protocol-owned liquidity; required while the share is non-zero
+    #[account(
+        mut,
+        seeds = [b"protocol_liquidity"],
+        bump = protocol_liquidity.bump
+    )]
+    pub protocol_liquidity: Option<Box<Account<'info, ProtocolLiquidity>>>,
+
+    /// Keeper sending the crank
+    pub cranker: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts one agent in a batch needs, from remaining accounts laid out as
+/// `[agent, mint, sol_vault, creator_stats]` per agent, plus the protocol
+/// liquidity's token account for the agent's mint while the share is non-zero
+pub fn graduation_accounts<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    with_protocol_liquidity: bool,
+) -> Result<Vec<BatchGraduation<'info>>> {
+    let group = if with_protocol_liquidity { 5 } else { 4 };
+    let agents = remaining_accounts.len() / group;
+    require!(
+        (1..=MAX_GRADUATION_BATCH).contains(&agents) && agents * group == remaining_accounts.len(),
+        AgentFactoryError::InvalidGraduationBatch
+    );
+
+    remaining_accounts.chunks(group)
+        .map(|accounts| {
+            let agent = Account::<Agent>::try_from(&accounts[0])?;
+            let creator_stats = Account::<CreatorStats>::try_from(&accounts[3])?;
+            let sol_vault = Pubkey::create_program_address(
+                &[AgentVault::Sol.seed(), agent.key().as_ref(), &[agent.sol_vault_bump]],
+                &crate::ID,
+            ).map_err(|_| AgentFactoryError::InvalidGraduationBatch)?;
+            require!(
+                accounts[1].key() == agent.mint
+                    && accounts[2].key() == sol_vault
+                    && creator_stats.creator == agent.creator,
+                AgentFactoryError::InvalidGraduationBatch
+            );
+            let protocol_liquidity_token_account = accounts.get(4)
+                .map(Account::<TokenAccount>::try_from)
+                .transpose()?;
+            Ok(BatchGraduation {
+                agent,
+                mint: &accounts[1],
+                sol_vault: &accounts[2],
+                creator_stats,
+                protocol_liquidity_token_account,
+            })
+        })
+        .collect()
+}
+
+pub struct BatchGraduation<'info> {
+    pub agent: Account<'info, Agent>,
+    pub mint: &'info AccountInfo<'info>,
+    pub sol_vault: &'info AccountInfo<'info>,
+    pub creator_stats: Account<'info, CreatorStats>,
+    pub protocol_liquidity_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Graduate every eligible agent passed in remaining accounts (see
+/// `graduation_accounts`), emitting one event each. Agents that can't
+/// graduate yet, or already did, are skipped so a crank racing another one
+/// doesn't fail. Each agent's unsold curve tokens are burned or pooled as
+/// its creator chose. The creator's stats must already exist, as they do for
+/// agents created since creator stats were introduced.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GraduateBatch<'info>>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let protocol_liquidity_bps = ctx.accounts.factory.protocol_liquidity_bps;
+    let mut graduations = graduation_accounts(ctx.remaining_accounts, protocol_liquidity_bps > 0)?;
+
+    let mut events = Vec::with_capacity(graduations.len());
+    for graduation in graduations.iter_mut() {
+        // An earlier graduation in the batch may have written this agent or
+        // creator's stats through a duplicate entry
+        graduation.agent.reload()?;
+        graduation.creator_stats.reload()?;
+        if !graduation.agent.can_graduate() || graduation.agent.is_redeemable(now) {
+            trace!("Skipping agent: {}", graduation.agent.key());
+            continue;
+        }
+
+        events.push(graduate(
+            &mut graduation.agent,
+            graduation.mint,
+            graduation.sol_vault,
+            &mut graduation.creator_stats,
+            protocol_liquidity_bps,
+            ctx.accounts.protocol_liquidity.as_deref_mut(),
+            graduation.protocol_liquidity_token_account.as_ref(),
+            &ctx.accounts.token_program,
+            &ctx.accounts.system_program,
+            now,
+        )?);
+        graduation.agent.exit(&crate::ID)?;
+        graduation.creator_stats.exit(&crate::ID)?;
+    }
+
+    trace!("Graduated {} of {} agents", events.len(), graduations.len());
+
+    for event in events {
+        emit_cpi!(event);
+    }
+
+    Ok(())
+}
@@ -1,18 +1,77 @@
 use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{
+    AgentFactory, DEFAULT_ALLOWED_MODELS, DEFAULT_CREATION_WINDOW_SECS, DEFAULT_CREATOR_FEE_VESTING_SECS,
+    DEFAULT_TIMELOCK_DELAY_SECS, FEE_DISCOUNT_TIERS, FeeDiscountTier, MAX_ALLOWED_MODELS, MAX_ARBITERS,
+    MODEL_ID_LEN,
+};
 
-pub fn handler(ctx: Context<crate::Initialize>, creation_fee: u64) -> Result<()> {
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AgentFactory::INIT_SPACE,
+        seeds = [b"factory"],
+        bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Platform treasury account
+    pub platform_treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<Initialize>, creation_fee: u64) -> Result<()> {
     let factory = &mut ctx.accounts.factory;
     
     factory.authority = ctx.accounts.authority.key();
     factory.platform_treasury = ctx.accounts.platform_treasury.key();
     factory.creation_fee = creation_fee;
+    factory.quote_mint = Pubkey::default();
+    factory.quote_creation_fee = 0;
     factory.total_agents = 0;
+    factory.timelock_delay_secs = DEFAULT_TIMELOCK_DELAY_SECS;
+    factory.admin_action_count = 0;
+    factory.admin_is_program = false;
+    factory.insurance_fee_bps = 0;
+    factory.platform_mint = Pubkey::default();
+    factory.platform_staking_pool = Pubkey::default();
+    factory.fee_discount_tiers = [FeeDiscountTier::default(); FEE_DISCOUNT_TIERS];
+    factory.protocol_liquidity_bps = 0;
+    // The authority arbitrates alone until a council is set
+    factory.arbiters = [Pubkey::default(); MAX_ARBITERS];
+    factory.arbiters[0] = ctx.accounts.authority.key();
+    factory.arbiter_threshold = 1;
+    factory.allowed_models = [[0; MODEL_ID_LEN]; MAX_ALLOWED_MODELS];
+    for (entry, model) in factory.allowed_models.iter_mut().zip(DEFAULT_ALLOWED_MODELS) {
+        *entry = AgentFactory::model_id(model).ok_or(AgentFactoryError::ModelNotAllowed)?;
+    }
+    factory.rename_fee = creation_fee;
+    factory.creator_fee_vesting_secs = DEFAULT_CREATOR_FEE_VESTING_SECS;
+    factory.max_creations_per_window = 0;
+    factory.creation_window_secs = DEFAULT_CREATION_WINDOW_SECS;
+    factory.creation_window_start = 0;
+    factory.creations_in_window = 0;
+    factory.creation_surge_bps = 0;
+    factory.creation_surge_half_life_secs = DEFAULT_CREATION_WINDOW_SECS;
+    factory.creation_demand = 0;
+    factory.creation_demand_updated_at = 0;
+    factory.reputation_attestor = Pubkey::default();
+    factory.public_goods_fee_bps = 0;
+    factory.public_goods_recipient = Pubkey::default();
+    factory.total_public_goods_fees = 0;
+    factory.quote_lamport_rate = 0;
     factory.bump = ctx.bumps.factory;
 
-    msg!("Agent Factory initialized!");
-    msg!("Authority: {}", factory.authority);
-    msg!("Platform Treasury: {}", factory.platform_treasury);
-    msg!("Creation Fee: {} lamports", factory.creation_fee);
+    trace!("Agent Factory initialized!");
+    trace!("Authority: {}", factory.authority);
+    trace!("Platform Treasury: {}", factory.platform_treasury);
+    trace!("Creation Fee: {} lamports", factory.creation_fee);
 
     Ok(())
 }
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{AgentFactory, InsuranceFund};
+
+#[derive(Accounts)]
+pub struct InitializeInsuranceFund<'info> {
+    /// Factory singleton
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Insurance fund singleton: `["insurance_fund"]`
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + InsuranceFund::INIT_SPACE,
+        seeds = [b"insurance_fund"],
+        bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    /// Factory authority (wallet or PDA signer)
+    pub authority: Signer<'info>,
+
+    /// Pays the fund's rent. Must be the authority unless
+    /// `factory.admin_is_program` is set.
+    #[account(
+        mut,
+        constraint = factory.admin_is_program || payer.key() == authority.key() @ AgentFactoryError::InvalidAdminAction
+    )]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open the platform insurance fund. Platform fee slices start flowing into
+/// it once `insurance_fee_bps` is set through the admin timelock.
+pub fn handler(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+    let fund = &mut ctx.accounts.insurance_fund;
+    fund.total_fees_collected = 0;
+    fund.total_deposits = 0;
+    fund.total_claims_paid = 0;
+    fund.claim_count = 0;
+    fund.total_premiums = 0;
+    fund.created_at = Clock::get()?.unix_timestamp;
+    fund.bump = ctx.bumps.insurance_fund;
+
+    trace!("Insurance fund initialized!");
+    trace!("Platform fee share: {} bps", ctx.accounts.factory.insurance_fee_bps);
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::{Agent, PriceHistory};
+
+#[derive(Accounts)]
+pub struct InitializePriceHistory<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PriceHistory::INIT_SPACE,
+        seeds = [b"price_history", agent.key().as_ref()],
+        bump
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create an agent's candle accumulator; anyone may pay for it
+pub fn handler(ctx: Context<InitializePriceHistory>) -> Result<()> {
+    let price_history = &mut ctx.accounts.price_history;
+    price_history.agent = ctx.accounts.agent.key();
+    price_history.head = 0;
+    price_history.candles = Vec::new();
+    price_history.bump = ctx.bumps.price_history;
+
+    trace!("Price history initialized for agent {}", price_history.agent);
+
+    Ok(())
+}
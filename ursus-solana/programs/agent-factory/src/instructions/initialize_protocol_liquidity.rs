@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{AgentFactory, ProtocolLiquidity};
+
+#[derive(Accounts)]
+pub struct InitializeProtocolLiquidity<'info> {
+    /// Factory singleton
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Protocol-owned liquidity singleton: `["protocol_liquidity"]`
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ProtocolLiquidity::INIT_SPACE,
+        seeds = [b"protocol_liquidity"],
+        bump
+    )]
+    pub protocol_liquidity: Account<'info, ProtocolLiquidity>,
+
+    /// Factory authority (wallet or PDA signer)
+    pub authority: Signer<'info>,
+
+    /// Pays the vault's rent. Must be the authority unless
+    /// `factory.admin_is_program` is set.
+    #[account(
+        mut,
+        constraint = factory.admin_is_program || payer.key() == authority.key() @ AgentFactoryError::InvalidAdminAction
+    )]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Open the protocol-owned liquidity vault. Graduations start contributing
+/// once `protocol_liquidity_bps` is set through the admin timelock.
+pub fn handler(ctx: Context<InitializeProtocolLiquidity>) -> Result<()> {
+    let vault = &mut ctx.accounts.protocol_liquidity;
+    vault.total_sol_contributed = 0;
+    vault.total_sol_withdrawn = 0;
+    vault.graduations = 0;
+    vault.created_at = Clock::get()?.unix_timestamp;
+    vault.bump = ctx.bumps.protocol_liquidity;
+
+    trace!("Protocol-owned liquidity initialized!");
+
+    Ok(())
+}
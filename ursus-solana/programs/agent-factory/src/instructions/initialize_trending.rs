@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::TrendingLeaderboard;
+
+#[derive(Accounts)]
+pub struct InitializeTrending<'info> {
+    /// Trending leaderboard singleton: `["trending"]`
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TrendingLeaderboard::INIT_SPACE,
+        seeds = [b"trending"],
+        bump
+    )]
+    pub trending: Box<Account<'info, TrendingLeaderboard>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the factory trending leaderboard; anyone may pay for it
+pub fn handler(ctx: Context<InitializeTrending>) -> Result<()> {
+    let trending = &mut ctx.accounts.trending;
+    trending.slot = Clock::get()?.slot;
+    trending.entries = Vec::new();
+    trending.bump = ctx.bumps.trending;
+
+    trace!("Trending leaderboard initialized!");
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, AgentFactory, InsuranceFund, InsurancePolicy, X402PaymentRecord};
+use super::submit_evidence::is_payment_payer;
+
+#[derive(Accounts)]
+pub struct InsurePayment<'info> {
+    /// Factory singleton, valuing the payment in lamports
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Payment being insured
+    pub payment_record: Account<'info, X402PaymentRecord>,
+
+    /// Insurance fund receiving the premium
+    #[account(
+        mut,
+        seeds = [b"insurance_fund"],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    #[account(
+        init,
+        payer = holder,
+        space = 8 + InsurancePolicy::INIT_SPACE,
+        seeds = [b"insurance_policy", payment_record.key().as_ref()],
+        bump
+    )]
+    pub insurance_policy: Account<'info, InsurancePolicy>,
+
+    /// Payer of the payment, or the creator of the agent that paid
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    /// Paying agent, for agent-to-agent payments
+    pub payer_agent: Option<Account<'info, Agent>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Insure a payment for `coverage` lamports, at most the payment's lamport
+/// value (quote payments at the factory's `quote_lamport_rate`), paying the
+/// premium into the insurance fund. Must be bundled with the payment itself
+/// so the policy cannot be bought once the service turned out badly.
+pub fn handler(ctx: Context<InsurePayment>, coverage: u64) -> Result<()> {
+    require!(coverage > 0, AgentFactoryError::InvalidInsuranceAmount);
+
+    let payment_record = &ctx.accounts.payment_record;
+    let holder = ctx.accounts.holder.key();
+    require!(
+        is_payment_payer(payment_record, &holder, &ctx.accounts.payer_agent),
+        AgentFactoryError::NotPaymentPayer
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(payment_record.timestamp == now, AgentFactoryError::InsuranceWindowClosed);
+
+    let payment_value = ctx.accounts.factory
+        .lamport_value(&payment_record.mint, payment_record.amount)
+        .ok_or(AgentFactoryError::PaymentMintNotValued)?;
+    require!(coverage <= payment_value, AgentFactoryError::CoverageExceedsPayment);
+
+    let premium = InsurancePolicy::premium_for(coverage)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.holder.to_account_info(),
+            to: ctx.accounts.insurance_fund.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_context, premium)?;
+
+    let fund = &mut ctx.accounts.insurance_fund;
+    fund.total_premiums = fund.total_premiums
+        .checked_add(premium)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    let policy = &mut ctx.accounts.insurance_policy;
+    policy.payment_record = payment_record.key();
+    policy.holder = holder;
+    policy.coverage = coverage;
+    policy.premium = premium;
+    policy.claimed_amount = 0;
+    policy.created_at = now;
+    policy.bump = ctx.bumps.insurance_policy;
+
+    trace!("Payment insured!");
+    trace!("Payment: {}", policy.payment_record);
+    trace!("Coverage: {}", coverage);
+    trace!("Premium: {}", premium);
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{MAX_VE_LOCK_SECS, StakePosition, StakingPool, ve_lock_end};
+
+#[derive(Accounts)]
+pub struct LockStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking_pool", staking_pool.agent.as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Box<Account<'info, StakingPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    pub owner: Signer<'info>,
+}
+
+/// Lock a stake position until `unlock_ts` (rounded down to a whole epoch) for
+/// boosted voting weight. Existing locks can only be extended.
+pub fn handler(ctx: Context<LockStake>, unlock_ts: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.staking_pool;
+    let position = &mut ctx.accounts.position;
+
+    require!(position.amount > 0, AgentFactoryError::InvalidStakeAmount);
+
+    let lock_end = ve_lock_end(unlock_ts);
+    require!(
+        lock_end > now && lock_end > position.lock_end && lock_end <= now + MAX_VE_LOCK_SECS,
+        AgentFactoryError::InvalidUnlockTime
+    );
+
+    pool.update_ve(now, position.amount, position.lock_end, position.amount, lock_end)?;
+    position.lock_end = lock_end;
+    position.write_checkpoint(now);
+
+    trace!("Stake locked!");
+    trace!("Amount: {}", position.amount);
+    trace!("Lock end: {}", lock_end);
+    trace!("Voting weight: {}", position.voting_weight(now));
+    trace!("Pool voting weight: {}", pool.total_voting_weight(now));
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, MAX_LOCK_DURATION_SECS, TokenLock};
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64)]
+pub struct LockTokens<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(address = agent.mint)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TokenLock::INIT_SPACE,
+        seeds = [
+            b"lock",
+            agent.key().as_ref(),
+            owner.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub lock: Account<'info, TokenLock>,
+
+    /// Token account holding the locked tokens, owned by the lock PDA
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"lock_vault", lock.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = lock
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Lock agent tokens in a per-lock vault until `unlock_ts`
+pub fn handler(
+    ctx: Context<LockTokens>,
+    lock_id: u64,
+    amount: u64,
+    unlock_ts: i64,
+) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidLockAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        unlock_ts > now && unlock_ts - now <= MAX_LOCK_DURATION_SECS,
+        AgentFactoryError::InvalidUnlockTime
+    );
+
+    // Move the tokens into the lock vault
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let lock = &mut ctx.accounts.lock;
+    lock.owner = ctx.accounts.owner.key();
+    lock.agent = ctx.accounts.agent.key();
+    lock.lock_id = lock_id;
+    lock.amount = amount;
+    lock.unlock_ts = unlock_ts;
+    lock.created_at = now;
+    lock.bump = ctx.bumps.lock;
+
+    trace!("Tokens locked!");
+    trace!("Lock ID: {}", lock_id);
+    trace!("Amount: {}", amount);
+    trace!("Unlocks at: {}", unlock_ts);
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::TokenMigration;
+
+#[derive(Accounts)]
+pub struct MigrateTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"migration", migration.agent.as_ref()],
+        bump = migration.bump
+    )]
+    pub migration: Box<Account<'info, TokenMigration>>,
+
+    #[account(
+        mut,
+        seeds = [b"migration_old_vault", migration.key().as_ref()],
+        bump
+    )]
+    pub old_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"migration_vault", migration.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = migration.old_mint,
+        token::authority = holder
+    )]
+    pub holder_old_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Swap `amount` old tokens for agent tokens at the migration ratio
+pub fn handler(ctx: Context<MigrateTokens>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidMigrationAmount);
+
+    let payout = ctx.accounts.migration.payout(amount)?;
+    require!(payout > 0, AgentFactoryError::InvalidMigrationAmount);
+    require!(
+        payout <= ctx.accounts.vault.amount,
+        AgentFactoryError::InsufficientMigrationFunds
+    );
+
+    // Lock the old tokens
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.holder_old_account.to_account_info(),
+            to: ctx.accounts.old_vault.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let agent_key = ctx.accounts.migration.agent;
+    let seeds = &[
+        b"migration",
+        agent_key.as_ref(),
+        &[ctx.accounts.migration.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.holder_token_account.to_account_info(),
+            authority: ctx.accounts.migration.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, payout)?;
+
+    let migration = &mut ctx.accounts.migration;
+    migration.total_migrated = migration.total_migrated
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    migration.total_paid = migration.total_paid
+        .checked_add(payout)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("Tokens migrated!");
+    trace!("Old tokens: {}", amount);
+    trace!("Agent tokens: {}", payout);
+
+    Ok(())
+}
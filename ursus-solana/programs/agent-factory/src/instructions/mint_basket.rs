@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::IndexBasket;
+
+#[derive(Accounts)]
+pub struct MintBasket<'info> {
+    #[account(
+        seeds = [
+            b"index_basket",
+            basket.creator.as_ref(),
+            &basket.basket_id.to_le_bytes()
+        ],
+        bump = basket.bump,
+        has_one = basket_mint
+    )]
+    pub basket: Box<Account<'info, IndexBasket>>,
+
+    #[account(mut)]
+    pub basket_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = basket_mint,
+        token::authority = holder
+    )]
+    pub holder_basket_account: Account<'info, TokenAccount>,
+
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Pair each basket constituent with the holder's token account and the
+/// basket's vault (the basket PDA's ATA), from remaining accounts laid out as
+/// `[holder_account, vault]` per constituent
+pub fn constituent_accounts<'info>(
+    basket: &Account<'info, IndexBasket>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    holder: &Pubkey,
+) -> Result<Vec<(Account<'info, TokenAccount>, Account<'info, TokenAccount>)>> {
+    require!(
+        remaining_accounts.len() == basket.constituents.len() * 2,
+        AgentFactoryError::InvalidBasketAccounts
+    );
+
+    basket.constituents.iter()
+        .zip(remaining_accounts.chunks(2))
+        .map(|(constituent, accounts)| {
+            let holder_account = Account::<TokenAccount>::try_from(&accounts[0])?;
+            let vault = Account::<TokenAccount>::try_from(&accounts[1])?;
+            require!(
+                holder_account.mint == constituent.mint && holder_account.owner == *holder,
+                AgentFactoryError::InvalidBasketAccounts
+            );
+            require!(
+                vault.key() == get_associated_token_address(&basket.key(), &constituent.mint),
+                AgentFactoryError::InvalidBasketAccounts
+            );
+            Ok((holder_account, vault))
+        })
+        .collect()
+}
+
+/// Deposit every constituent in proportion to its weight and mint `amount`
+/// basket tokens (base units). Remaining accounts are
+/// `[holder_account, vault]` per constituent, in basket order.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, MintBasket<'info>>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidBasketAmount);
+
+    let holder = ctx.accounts.holder.key();
+    let accounts = constituent_accounts(&ctx.accounts.basket, ctx.remaining_accounts, &holder)?;
+
+    for (constituent, (holder_account, vault)) in ctx.accounts.basket.constituents.iter().zip(&accounts) {
+        let deposit = IndexBasket::constituent_amount(constituent.units, amount, true)?;
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: holder_account.to_account_info(),
+                to: vault.to_account_info(),
+                authority: ctx.accounts.holder.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, deposit)?;
+    }
+
+    let creator = ctx.accounts.basket.creator;
+    let basket_id_bytes = ctx.accounts.basket.basket_id.to_le_bytes();
+    let seeds = &[
+        b"index_basket",
+        creator.as_ref(),
+        basket_id_bytes.as_ref(),
+        &[ctx.accounts.basket.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.basket_mint.to_account_info(),
+            to: ctx.accounts.holder_basket_account.to_account_info(),
+            authority: ctx.accounts.basket.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::mint_to(cpi_ctx, amount)?;
+
+    trace!("Basket tokens minted!");
+    trace!("Basket: {}", ctx.accounts.basket.name);
+    trace!("Amount: {}", amount);
+
+    Ok(())
+}
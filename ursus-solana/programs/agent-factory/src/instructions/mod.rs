@@ -1,11 +1,289 @@
 pub mod initialize;
 pub mod create_agent;
+pub mod create_agent_full;
 pub mod buy_tokens;
 pub mod sell_tokens;
 pub mod graduate_agent;
-pub mod update_fee;
+pub mod queue_admin_action;
+pub mod execute_admin_action;
+pub mod cancel_admin_action;
 pub mod configure_x402;
 pub mod update_x402;
 pub mod pay_for_service;
 pub mod call_agent_service;
+pub mod create_dca;
+pub mod execute_dca;
+pub mod cancel_dca;
+pub mod create_presale;
+pub mod commit_sol;
+pub mod finalize_presale;
+pub mod claim_presale;
+pub mod refund_commitment;
+pub mod enable_refund_guarantee;
+pub mod redeem_for_sol;
+pub mod create_snapshot;
+pub mod lock_tokens;
+pub mod unlock_tokens;
+pub mod create_staking_pool;
+pub mod stake;
+pub mod unstake;
+pub mod claim_rewards;
+pub mod fund_staking_pool;
+pub mod lock_stake;
+pub mod create_governance;
+pub mod create_proposal;
+pub mod cast_vote;
+pub mod execute_proposal;
+pub mod create_dao_treasury;
+pub mod collect_treasury_fees;
+pub mod initialize_insurance_fund;
+pub mod collect_insurance_fees;
+pub mod fund_insurance;
+pub mod pay_claim;
+pub mod set_creator_standing;
+pub mod initialize_price_history;
+pub mod initialize_trending;
+pub mod create_early_buyer_rewards;
+pub mod claim_early_buyer_bonus;
+pub mod close_early_buyer_rewards;
+pub mod initialize_protocol_liquidity;
+pub mod withdraw_protocol_liquidity;
+pub mod create_liquidity_mining;
+pub mod fund_liquidity_mining;
+pub mod stake_lp;
+pub mod withdraw_lp;
+pub mod claim_lp_rewards;
+pub mod create_migration;
+pub mod migrate_tokens;
+pub mod create_index_basket;
+pub mod mint_basket;
+pub mod redeem_basket;
+pub mod get_twap;
+pub mod claim_agent_ownership;
+pub mod transfer_agent_ownership;
+pub mod set_creator_royalty;
+pub mod claim_royalties;
+pub mod set_same_slot_protection;
+pub mod register_keeper_task;
+pub mod fund_keeper_task;
+pub mod crank_keeper_task;
+pub mod cancel_keeper_task;
+pub mod set_x402_verifier;
+pub mod pay_for_service_escrowed;
+pub mod settle_attested_payment;
+pub mod refund_expired_payment;
+pub mod deposit_provider_bond;
+pub mod withdraw_provider_bond;
+pub mod submit_evidence;
+pub mod cast_arbiter_vote;
+pub mod insure_payment;
+pub mod pay_insured_claim;
+pub mod set_x402_metering;
+pub mod settle_metered_payment;
+pub mod post_quote;
+pub mod close_quote;
+pub mod register_affiliate;
+pub mod set_affiliate_cap;
+pub mod register_webhook;
+pub mod set_agent_model;
+pub mod add_agent_tag;
+pub mod remove_agent_tag;
+pub mod rename_agent;
+pub mod set_max_buy;
+pub mod set_graduation_burn;
+pub mod claim_creator_fees;
+pub mod set_creation_rate_limit;
+pub mod reserve_name;
+pub mod view_price;
+pub mod commit_agent;
+pub mod reveal_agent;
+pub mod create_agent_with_listing_reserve;
+pub mod release_listing_reserve;
+pub mod freeze_x402;
+pub mod rotate_x402_recipient;
+pub mod graduate_batch;
+pub mod set_agent_fee_override;
+pub mod set_revenue_split;
+pub mod set_chargeback_window;
+pub mod finalize_payment;
+pub mod deprecate_service;
+pub mod create_consumer_stats;
+pub mod start_stream;
+pub mod withdraw_streamed;
+pub mod cancel_stream;
+pub mod set_x402_top_up;
+pub mod collect_x402_top_up;
+pub mod deposit_revenue;
+pub mod set_x402_price_notice;
+pub mod get_agent_manifest;
+pub mod propose_terms;
+pub mod accept_terms;
+pub mod close_terms;
+pub mod create_job;
+pub mod submit_milestone;
+pub mod release_milestone;
+pub mod dispute_milestone;
+pub mod vote_milestone_dispute;
+pub mod cancel_job;
+pub mod close_job;
+pub mod set_queue_workers;
+pub mod enqueue_task;
+pub mod claim_task;
+pub mod complete_task;
+pub mod withdraw_task;
+pub mod set_trial_deposit;
+pub mod open_trial;
+pub mod end_trial;
+pub mod forfeit_trial;
+pub mod attest_reputation;
+pub mod rate_service;
+pub mod collect_public_goods_fees;
+pub mod get_layout_info;
+pub mod set_quote_lamport_rate;
 
+// Every module's `handler` clashes below; handlers are always called by
+// path, the globs only surface the `Accounts` structs and their generated
+// client modules at the crate root
+#[allow(ambiguous_glob_reexports)]
+pub use initialize::*;
+pub use create_agent::*;
+pub use create_agent_full::*;
+pub use buy_tokens::*;
+pub use sell_tokens::*;
+pub use graduate_agent::*;
+pub use queue_admin_action::*;
+pub use execute_admin_action::*;
+pub use cancel_admin_action::*;
+pub use configure_x402::*;
+pub use update_x402::*;
+pub use pay_for_service::*;
+pub use call_agent_service::*;
+pub use create_dca::*;
+pub use execute_dca::*;
+pub use cancel_dca::*;
+pub use create_presale::*;
+pub use commit_sol::*;
+pub use finalize_presale::*;
+pub use claim_presale::*;
+pub use refund_commitment::*;
+pub use enable_refund_guarantee::*;
+pub use redeem_for_sol::*;
+pub use create_snapshot::*;
+pub use lock_tokens::*;
+pub use unlock_tokens::*;
+pub use create_staking_pool::*;
+pub use stake::*;
+pub use unstake::*;
+pub use claim_rewards::*;
+pub use fund_staking_pool::*;
+pub use lock_stake::*;
+pub use create_governance::*;
+pub use create_proposal::*;
+pub use cast_vote::*;
+pub use execute_proposal::*;
+pub use create_dao_treasury::*;
+pub use collect_treasury_fees::*;
+pub use initialize_insurance_fund::*;
+pub use collect_insurance_fees::*;
+pub use fund_insurance::*;
+pub use pay_claim::*;
+pub use set_creator_standing::*;
+pub use initialize_price_history::*;
+pub use initialize_trending::*;
+pub use create_early_buyer_rewards::*;
+pub use claim_early_buyer_bonus::*;
+pub use close_early_buyer_rewards::*;
+pub use initialize_protocol_liquidity::*;
+pub use withdraw_protocol_liquidity::*;
+pub use create_liquidity_mining::*;
+pub use fund_liquidity_mining::*;
+pub use stake_lp::*;
+pub use withdraw_lp::*;
+pub use claim_lp_rewards::*;
+pub use create_migration::*;
+pub use migrate_tokens::*;
+pub use create_index_basket::*;
+pub use mint_basket::*;
+pub use redeem_basket::*;
+pub use get_twap::*;
+pub use claim_agent_ownership::*;
+pub use transfer_agent_ownership::*;
+pub use set_creator_royalty::*;
+pub use claim_royalties::*;
+pub use set_same_slot_protection::*;
+pub use register_keeper_task::*;
+pub use fund_keeper_task::*;
+pub use crank_keeper_task::*;
+pub use cancel_keeper_task::*;
+pub use set_x402_verifier::*;
+pub use pay_for_service_escrowed::*;
+pub use settle_attested_payment::*;
+pub use refund_expired_payment::*;
+pub use deposit_provider_bond::*;
+pub use withdraw_provider_bond::*;
+pub use submit_evidence::*;
+pub use cast_arbiter_vote::*;
+pub use insure_payment::*;
+pub use pay_insured_claim::*;
+pub use set_x402_metering::*;
+pub use settle_metered_payment::*;
+pub use post_quote::*;
+pub use close_quote::*;
+pub use register_affiliate::*;
+pub use set_affiliate_cap::*;
+pub use register_webhook::*;
+pub use set_agent_model::*;
+pub use add_agent_tag::*;
+pub use remove_agent_tag::*;
+pub use rename_agent::*;
+pub use set_max_buy::*;
+pub use set_graduation_burn::*;
+pub use claim_creator_fees::*;
+pub use set_creation_rate_limit::*;
+pub use reserve_name::*;
+pub use view_price::*;
+pub use commit_agent::*;
+pub use reveal_agent::*;
+pub use create_agent_with_listing_reserve::*;
+pub use release_listing_reserve::*;
+pub use freeze_x402::*;
+pub use rotate_x402_recipient::*;
+pub use graduate_batch::*;
+pub use set_agent_fee_override::*;
+pub use set_revenue_split::*;
+pub use set_chargeback_window::*;
+pub use finalize_payment::*;
+pub use deprecate_service::*;
+pub use create_consumer_stats::*;
+pub use start_stream::*;
+pub use withdraw_streamed::*;
+pub use cancel_stream::*;
+pub use set_x402_top_up::*;
+pub use collect_x402_top_up::*;
+pub use deposit_revenue::*;
+pub use set_x402_price_notice::*;
+pub use get_agent_manifest::*;
+pub use propose_terms::*;
+pub use accept_terms::*;
+pub use close_terms::*;
+pub use create_job::*;
+pub use submit_milestone::*;
+pub use release_milestone::*;
+pub use dispute_milestone::*;
+pub use vote_milestone_dispute::*;
+pub use cancel_job::*;
+pub use close_job::*;
+pub use set_queue_workers::*;
+pub use enqueue_task::*;
+pub use claim_task::*;
+pub use complete_task::*;
+pub use withdraw_task::*;
+pub use set_trial_deposit::*;
+pub use open_trial::*;
+pub use end_trial::*;
+pub use forfeit_trial::*;
+pub use attest_reputation::*;
+pub use rate_service::*;
+pub use collect_public_goods_fees::*;
+pub use get_layout_info::*;
+pub use set_quote_lamport_rate::*;
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use crate::errors::AgentFactoryError;
+use crate::events::TrialSessionEvent;
+use crate::state::{Agent, TrialSession, TrialStatus, X402Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OpenTrial<'info> {
+    pub agent: Box<Account<'info, Agent>>,
+
+    #[account(
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    /// One per caller and agent at a time
+    #[account(
+        init,
+        payer = caller,
+        space = 8 + TrialSession::INIT_SPACE,
+        seeds = [b"trial_session", agent.key().as_ref(), caller.key().as_ref()],
+        bump
+    )]
+    pub session: Box<Account<'info, TrialSession>>,
+
+    /// Holds the deposit until the session is ended or forfeited
+    #[account(
+        init,
+        payer = caller,
+        token::mint = mint,
+        token::authority = x402_config,
+        seeds = [b"trial_escrow", session.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Deposit token (e.g. USDC)
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// Caller's USDC token account
+    #[account(mut)]
+    pub caller_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Open a trial session with the agent's free tier of `service_id`,
+/// escrowing its trial deposit. The session runs for the service timeout;
+/// the agent serves callers with an open session.
+pub fn handler(ctx: Context<OpenTrial>, service_id: [u8; 32]) -> Result<()> {
+    let x402_config = &ctx.accounts.x402_config;
+    x402_config.require_accepting_payments()?;
+    require!(x402_config.trial_deposit > 0, AgentFactoryError::TrialsNotOffered);
+    require!(service_id != [0; 32], AgentFactoryError::InvalidServiceId);
+    x402_config.require_service_live(&service_id)?;
+
+    let deposit = x402_config.trial_deposit;
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TokenTransfer {
+            from: ctx.accounts.caller_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.caller.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, deposit)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let session = &mut ctx.accounts.session;
+    session.agent = ctx.accounts.agent.key();
+    session.caller = ctx.accounts.caller.key();
+    session.mint = ctx.accounts.mint.key();
+    session.service_id = service_id;
+    session.deposit = deposit;
+    session.opened_at = now;
+    session.expires_at = now
+        .checked_add(x402_config.service_timeout_seconds as i64)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    session.bump = ctx.bumps.session;
+
+    trace!("Trial opened!");
+    trace!("Caller: {}, Deposit: {}, Expires: {}", session.caller, deposit, session.expires_at);
+
+    emit_cpi!(TrialSessionEvent {
+        agent: session.agent,
+        session: session.key(),
+        caller: session.caller,
+        service_id,
+        deposit,
+        status: TrialStatus::Open,
+        expires_at: session.expires_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
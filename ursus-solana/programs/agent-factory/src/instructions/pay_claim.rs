@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::events::InsuranceClaimEvent;
+use crate::state::{Agent, AgentFactory, InsuranceFund};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PayClaim<'info> {
+    /// Factory singleton
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Insurance fund paying the claim; stays rent-exempt
+    #[account(
+        mut,
+        seeds = [b"insurance_fund"],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    /// Agent whose exploit or rug the claim covers
+    pub agent: Account<'info, Agent>,
+
+    /// CHECK: Harmed user receiving the payout
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    /// Factory authority (wallet, multisig or DAO PDA signer)
+    pub authority: Signer<'info>,
+}
+
+/// Compensate a user harmed by an exploited or rugged agent out of the
+/// insurance fund. Gated by the factory authority, which may be a multisig
+/// or DAO PDA.
+pub fn handler(ctx: Context<PayClaim>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidInsuranceAmount);
+
+    let fund_info = ctx.accounts.insurance_fund.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(fund_info.data_len());
+    let available = fund_info.lamports().saturating_sub(rent_floor);
+    require!(amount <= available, AgentFactoryError::InsufficientInsuranceFunds);
+
+    **fund_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.recipient.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let fund = &mut ctx.accounts.insurance_fund;
+    let claim_id = fund.claim_count;
+    fund.claim_count += 1;
+    fund.total_claims_paid = fund.total_claims_paid
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    emit_cpi!(InsuranceClaimEvent {
+        claim_id,
+        agent: ctx.accounts.agent.key(),
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    trace!("Insurance claim paid!");
+    trace!("Claim ID: {}", claim_id);
+    trace!("Amount: {}", amount);
+
+    Ok(())
+}
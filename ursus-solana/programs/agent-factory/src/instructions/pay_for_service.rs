@@ -1,32 +1,188 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer as TokenTransfer};
-use crate::state::{X402Config, X402PaymentRecord, PaymentStatus, X402Error};
+use crate::events::PaymentEvent;
+use crate::errors::AgentFactoryError;
+use crate::state::{Affiliate, Agent, AgentFactory, MeterUnit, PaymentStatus, ConsumerStats, RevenueSplit, ServiceQuote, StakePosition, X402Config, X402PaymentRecord};
+use super::buy_tokens::platform_fee_discount;
+use super::set_revenue_split::route_revenue;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount: u64, service_id: [u8; 32], nonce: u64)]
+pub struct PayForService<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + X402PaymentRecord::INIT_SPACE,
+        seeds = [
+            b"payment_record",
+            agent.key().as_ref(),
+            payer.key().as_ref(),
+            &nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub payment_record: Account<'info, X402PaymentRecord>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Payer's USDC token account
+    #[account(mut)]
+    pub payer_token_account: Account<'info, TokenAccount>,
+
+    /// Recipient's USDC token account
+    #[account(
+        mut,
+        constraint = x402_config.accepts_recipient(&recipient_token_account.owner, Clock::get()?.unix_timestamp) @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Factory singleton; with `platform_stake`, applies the payer's
+    /// platform token discount to the minimum service price
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Option<Account<'info, AgentFactory>>,
+
+    /// Payer's stake position in the platform token's staking pool
+    pub platform_stake: Option<Box<Account<'info, StakePosition>>>,
+
+    /// Negotiated price replacing the listed payment bounds
+    #[account(
+        mut,
+        seeds = [b"service_quote", agent.key().as_ref(), payer.key().as_ref(), service_id.as_ref()],
+        bump = quote.bump
+    )]
+    pub quote: Option<Box<Account<'info, ServiceQuote>>>,
+
+    /// Affiliate that routed the payment; paid its share capped by the
+    /// provider's `max_affiliate_bps`
+    #[account(
+        mut,
+        seeds = [b"affiliate", affiliate.wallet.as_ref()],
+        bump = affiliate.bump
+    )]
+    pub affiliate: Option<Box<Account<'info, Affiliate>>>,
+
+    /// Affiliate's USDC token account
+    #[account(mut)]
+    pub affiliate_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Agent's revenue split, required while it routes revenue to other
+    /// agents; the worker treasuries' token accounts follow as remaining
+    /// accounts, in share order
+    #[account(
+        mut,
+        seeds = [b"revenue_split", agent.key().as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Box<Account<'info, RevenueSplit>>>,
+
+    /// Payer's spending report, updated with the payment
+    #[account(
+        mut,
+        seeds = [b"consumer_stats", payer.key().as_ref()],
+        bump = consumer_stats.bump
+    )]
+    pub consumer_stats: Option<Box<Account<'info, ConsumerStats>>>,
+}
 
 /// Pay for an agent service using X402 protocol
-/// This instruction handles the payment verification and settlement
-pub fn handler(
-    ctx: Context<crate::PayForService>,
+/// This instruction handles the payment verification and settlement;
+/// `request_id` correlates it with the caller's off-chain request.
+/// `priority_tip` is paid on top of the price, all of it to the provider.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PayForService<'info>>,
     amount: u64,
-    service_id: String,
+    service_id: [u8; 32],
     nonce: u64,
+    request_id: [u8; 16],
+    priority_tip: u64,
 ) -> Result<()> {
     let x402_config = &mut ctx.accounts.x402_config;
     let payment_record = &mut ctx.accounts.payment_record;
     let clock = Clock::get()?;
     
-    // Verify X402 is enabled
-    require!(x402_config.enabled, X402Error::PaymentsNotEnabled);
-    
-    // Validate payment amount
-    x402_config.validate_payment_amount(amount)?;
-    
-    // Verify nonce for replay protection
-    require!(nonce == x402_config.nonce + 1, X402Error::NonceMismatch);
-    
-    // Validate service ID
-    require!(service_id.len() > 0 && service_id.len() <= 32, X402Error::InvalidServiceId);
+    // Validate payment amount, nonce and service ID; platform token stakers
+    // pay a discounted minimum and quoted payments pay the negotiated price
+    match &mut ctx.accounts.quote {
+        Some(quote) => {
+            x402_config.validate_payment_request(nonce, &service_id)?;
+            quote.validate_payment(&ctx.accounts.payer.key(), amount, &service_id, clock.unix_timestamp)?;
+            quote.used = true;
+        }
+        None => {
+            // Stakers only get a discount when the factory is passed
+            let discount_bps = ctx.accounts.factory.as_deref().map_or(Ok(0), |factory| {
+                platform_fee_discount(factory, &ctx.accounts.platform_stake, &ctx.accounts.payer.key())
+            })?;
+            x402_config.validate_payment(amount, discount_bps, nonce, &service_id)?;
+        }
+    }
+    require!(!x402_config.is_metered(), AgentFactoryError::MeteringRequiresEscrow);
+
+    // Split off the referring affiliate's share
+    let affiliate_share = match (&mut ctx.accounts.affiliate, &ctx.accounts.affiliate_token_account) {
+        (Some(affiliate), Some(affiliate_token_account)) => {
+            require!(
+                affiliate_token_account.owner == affiliate.wallet,
+                AgentFactoryError::WrongAffiliateAccount
+            );
+            let share = x402_config.affiliate_share(amount, affiliate.fee_bps);
+            if share > 0 {
+                let transfer_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TokenTransfer {
+                        from: ctx.accounts.payer_token_account.to_account_info(),
+                        to: affiliate_token_account.to_account_info(),
+                        authority: ctx.accounts.payer.to_account_info(),
+                    },
+                );
+                token::transfer(transfer_ctx, share)?;
+            }
+            affiliate.total_referred = affiliate.total_referred
+                .checked_add(1)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            affiliate.total_earned = affiliate.total_earned
+                .checked_add(share)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            share
+        }
+        (None, None) => 0,
+        _ => return err!(AgentFactoryError::WrongAffiliateAccount),
+    };
 
-    // Transfer USDC from payer to payment recipient
+    // Route the worker agents' shares of the rest
+    let routed = route_revenue(
+        x402_config,
+        ctx.accounts.revenue_split.as_deref_mut(),
+        ctx.remaining_accounts,
+        amount - affiliate_share,
+        &ctx.accounts.payer_token_account.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &[],
+        &ctx.accounts.token_program,
+    )?;
+
+    // Transfer USDC from payer to payment recipient, with the whole tip
+    let paid = amount
+        .checked_add(priority_tip)
+        .ok_or(AgentFactoryError::MathOverflow)?;
     let transfer_ctx = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         TokenTransfer {
@@ -35,23 +191,59 @@ pub fn handler(
             authority: ctx.accounts.payer.to_account_info(),
         },
     );
-    token::transfer(transfer_ctx, amount)?;
+    token::transfer(transfer_ctx, paid - affiliate_share - routed)?;
     
     // Initialize payment record
     payment_record.agent = ctx.accounts.agent.key();
     payment_record.payer = ctx.accounts.payer.key();
     payment_record.amount = amount;
     payment_record.timestamp = clock.unix_timestamp;
-    payment_record.service_id = service_id.clone();
+    payment_record.service_id = service_id;
     payment_record.status = PaymentStatus::Verified;
+    payment_record.attestation_hash = [0; 32];
+    payment_record.meter_unit = MeterUnit::ThousandTokens;
+    payment_record.unit_price = 0;
+    payment_record.metered_units = 0;
+    payment_record.request_id = request_id;
+    payment_record.refunded_amount = 0;
+    payment_record.dispute_deadline = x402_config.dispute_deadline(clock.unix_timestamp);
+    payment_record.finalized_at = 0;
+    payment_record.mint = ctx.accounts.payer_token_account.mint;
     payment_record.bump = ctx.bumps.payment_record;
     
     // Update X402 config
     x402_config.increment_nonce()?;
-    x402_config.record_payment(amount)?;
+    x402_config.record_payment(paid)?;
+    if let Some(consumer_stats) = &mut ctx.accounts.consumer_stats {
+        consumer_stats.record(&ctx.accounts.agent.key(), &payment_record.key(), paid, clock.unix_timestamp)?;
+    }
     
-    msg!("Payment processed: {} USDC (smallest units) for service: {}", amount, service_id);
-    msg!("Payer: {}, Recipient: {}", ctx.accounts.payer.key(), ctx.accounts.recipient_token_account.key());
+    trace!("Payment processed: {} USDC (smallest units)", amount);
+    trace!("Payer: {}, Recipient: {}", ctx.accounts.payer.key(), ctx.accounts.recipient_token_account.key());
+    if affiliate_share > 0 {
+        trace!("Affiliate share: {}", affiliate_share);
+    }
+    if routed > 0 {
+        trace!("Routed to worker agents: {}", routed);
+    }
+    if priority_tip > 0 {
+        trace!("Priority tip: {}", priority_tip);
+    }
+
+    let sequence = ctx.accounts.agent.next_event_sequence()?;
+    emit_cpi!(PaymentEvent {
+        agent: ctx.accounts.agent.key(),
+        payer: ctx.accounts.payer.key(),
+        payment_record: payment_record.key(),
+        amount,
+        service_id,
+        nonce,
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+        sequence,
+        request_id,
+        priority_tip,
+    });
     
     Ok(())
 }
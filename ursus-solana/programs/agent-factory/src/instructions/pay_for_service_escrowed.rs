@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use crate::events::PaymentEvent;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, AgentFactory, ConsumerStats, PaymentStatus, StakePosition, X402Config, X402PaymentRecord};
+use super::buy_tokens::platform_fee_discount;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(amount: u64, service_id: [u8; 32], nonce: u64)]
+pub struct PayForServiceEscrowed<'info> {
+    #[account(mut)]
+    pub agent: Box<Account<'info, Agent>>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump,
+        constraint = x402_config.attestation_verifier != Pubkey::default() || x402_config.is_metered() @ AgentFactoryError::AttestationNotEnabled
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + X402PaymentRecord::INIT_SPACE,
+        seeds = [
+            b"payment_record",
+            agent.key().as_ref(),
+            payer.key().as_ref(),
+            &nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub payment_record: Box<Account<'info, X402PaymentRecord>>,
+
+    /// Holds the payment until it is attested or refunded
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = x402_config,
+        seeds = [b"payment_escrow", payment_record.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payment token (e.g. USDC)
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Payer's USDC token account
+    #[account(mut)]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Factory singleton; with `platform_stake`, applies the payer's
+    /// platform token discount to the minimum service price
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Option<Account<'info, AgentFactory>>,
+
+    /// Payer's stake position in the platform token's staking pool
+    pub platform_stake: Option<Box<Account<'info, StakePosition>>>,
+
+    /// Payer's spending report, updated with the payment
+    #[account(
+        mut,
+        seeds = [b"consumer_stats", payer.key().as_ref()],
+        bump = consumer_stats.bump
+    )]
+    pub consumer_stats: Option<Box<Account<'info, ConsumerStats>>>,
+}
+
+/// Pay for an agent service into escrow. The payment is released to the
+/// recipient once the agent's attestation verifier confirms the output
+/// passed its check, and refunded if it fails or nobody attests it within
+/// the service timeout. For metered services `amount` is the budget the
+/// usage reported at settlement is charged against.
+pub fn handler(
+    ctx: Context<PayForServiceEscrowed>,
+    amount: u64,
+    service_id: [u8; 32],
+    nonce: u64,
+    request_id: [u8; 16],
+) -> Result<()> {
+    let clock = Clock::get()?;
+    // Stakers only get a discount when the factory is passed
+    let discount_bps = ctx.accounts.factory.as_deref().map_or(Ok(0), |factory| {
+        platform_fee_discount(factory, &ctx.accounts.platform_stake, &ctx.accounts.payer.key())
+    })?;
+    let x402_config = &mut ctx.accounts.x402_config;
+    x402_config.validate_payment(amount, discount_bps, nonce, &service_id)?;
+
+    // Move the payment into escrow
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TokenTransfer {
+            from: ctx.accounts.payer_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, amount)?;
+
+    let payment_record = &mut ctx.accounts.payment_record;
+    payment_record.agent = ctx.accounts.agent.key();
+    payment_record.payer = ctx.accounts.payer.key();
+    payment_record.amount = amount;
+    payment_record.timestamp = clock.unix_timestamp;
+    payment_record.service_id = service_id;
+    payment_record.status = PaymentStatus::Pending;
+    payment_record.attestation_hash = [0; 32];
+    payment_record.meter_unit = x402_config.meter_unit;
+    payment_record.unit_price = x402_config.unit_price;
+    payment_record.metered_units = 0;
+    payment_record.request_id = request_id;
+    payment_record.refunded_amount = 0;
+    payment_record.dispute_deadline = 0;
+    payment_record.finalized_at = 0;
+    payment_record.mint = ctx.accounts.mint.key();
+    payment_record.bump = ctx.bumps.payment_record;
+
+    // Counted as received once released
+    x402_config.increment_nonce()?;
+    if let Some(consumer_stats) = &mut ctx.accounts.consumer_stats {
+        consumer_stats.record(&ctx.accounts.agent.key(), &payment_record.key(), amount, clock.unix_timestamp)?;
+    }
+
+    trace!("Payment escrowed: {} USDC (smallest units)", amount);
+    trace!("Payer: {}, Verifier: {}", ctx.accounts.payer.key(), x402_config.attestation_verifier);
+
+    let sequence = ctx.accounts.agent.next_event_sequence()?;
+    emit_cpi!(PaymentEvent {
+        agent: ctx.accounts.agent.key(),
+        payer: ctx.accounts.payer.key(),
+        payment_record: payment_record.key(),
+        amount,
+        service_id,
+        nonce,
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+        sequence,
+        request_id,
+        priority_tip: 0,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::events::InsuranceClaimEvent;
+use crate::state::{Dispute, DisputeStatus, InsuranceFund, InsurancePolicy};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PayInsuredClaim<'info> {
+    /// Insurance fund covering the shortfall; stays rent-exempt
+    #[account(
+        mut,
+        seeds = [b"insurance_fund"],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+
+    /// Upheld dispute over the insured payment
+    #[account(
+        seeds = [b"dispute", dispute.payment_record.as_ref()],
+        bump = dispute.bump,
+        constraint = dispute.status == DisputeStatus::Slashed @ AgentFactoryError::NoInsuredShortfall
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance_policy", dispute.payment_record.as_ref()],
+        bump = insurance_policy.bump,
+        constraint = insurance_policy.claimed_amount == 0 @ AgentFactoryError::NoInsuredShortfall
+    )]
+    pub insurance_policy: Account<'info, InsurancePolicy>,
+
+    /// CHECK: Claimant receiving the payout, checked against the dispute
+    #[account(
+        mut,
+        address = dispute.claimant
+    )]
+    pub claimant: AccountInfo<'info>,
+}
+
+/// Pay the part of an upheld dispute's award the provider bond could not
+/// cover, up to the policy's coverage. Permissionless: the payout always goes
+/// to the claimant.
+pub fn handler(ctx: Context<PayInsuredClaim>) -> Result<()> {
+    let dispute = &ctx.accounts.dispute;
+    let amount = ctx.accounts.insurance_policy.payout(dispute.awarded_amount, dispute.slashed_amount);
+    require!(amount > 0, AgentFactoryError::NoInsuredShortfall);
+
+    let fund_info = ctx.accounts.insurance_fund.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(fund_info.data_len());
+    let available = fund_info.lamports().saturating_sub(rent_floor);
+    require!(amount <= available, AgentFactoryError::InsufficientInsuranceFunds);
+
+    **fund_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.claimant.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    ctx.accounts.insurance_policy.claimed_amount = amount;
+
+    let fund = &mut ctx.accounts.insurance_fund;
+    let claim_id = fund.claim_count;
+    fund.claim_count += 1;
+    fund.total_claims_paid = fund.total_claims_paid
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    emit_cpi!(InsuranceClaimEvent {
+        claim_id,
+        agent: dispute.agent,
+        recipient: dispute.claimant,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    trace!("Insured claim paid!");
+    trace!("Claim ID: {}", claim_id);
+    trace!("Amount: {}", amount);
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, ServiceQuote, X402Config, MAX_QUOTE_TTL_SECS};
+
+#[derive(Accounts)]
+#[instruction(payer: Pubkey, service_id: [u8; 32])]
+pub struct PostQuote<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    /// Re-posting replaces an earlier quote for the same payer and service
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + ServiceQuote::INIT_SPACE,
+        seeds = [b"service_quote", agent.key().as_ref(), payer.as_ref(), service_id.as_ref()],
+        bump
+    )]
+    pub quote: Account<'info, ServiceQuote>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Quote `payer` a negotiated `amount` for `service_id`, payable through
+/// `pay_for_service` until `expires_at` (at most 24 hours out). Services
+/// past their sunset can't be quoted.
+pub fn handler(
+    ctx: Context<PostQuote>,
+    payer: Pubkey,
+    service_id: [u8; 32],
+    amount: u64,
+    expires_at: i64,
+) -> Result<()> {
+    ctx.accounts.x402_config.require_accepting_payments()?;
+    require!(service_id != [0; 32], AgentFactoryError::InvalidServiceId);
+    ctx.accounts.x402_config.require_service_live(&service_id)?;
+    require!(amount > 0, AgentFactoryError::InsufficientPayment);
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        expires_at > now && expires_at - now <= MAX_QUOTE_TTL_SECS,
+        AgentFactoryError::InvalidQuoteExpiry
+    );
+
+    let quote = &mut ctx.accounts.quote;
+    quote.agent = ctx.accounts.agent.key();
+    quote.payer = payer;
+    quote.service_id = service_id;
+    quote.amount = amount;
+    quote.expires_at = expires_at;
+    quote.used = false;
+    quote.bump = ctx.bumps.quote;
+
+    trace!("Quote posted!");
+    trace!("Payer: {}", payer);
+    trace!("Amount: {}, Expires: {}", amount, expires_at);
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::events::ServiceTermsEvent;
+use crate::state::{Agent, ServiceAgreement, X402Config, MAX_QUOTE_TTL_SECS};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(service_id: [u8; 32])]
+pub struct ProposeTerms<'info> {
+    pub caller_agent: Account<'info, Agent>,
+
+    pub target_agent: Account<'info, Agent>,
+
+    #[account(
+        seeds = [b"x402_config", target_agent.key().as_ref()],
+        bump = target_x402_config.bump
+    )]
+    pub target_x402_config: Account<'info, X402Config>,
+
+    /// Re-proposing replaces earlier terms for the same agents and service,
+    /// which then need accepting again
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + ServiceAgreement::INIT_SPACE,
+        seeds = [
+            b"service_agreement",
+            caller_agent.key().as_ref(),
+            target_agent.key().as_ref(),
+            service_id.as_ref()
+        ],
+        bump
+    )]
+    pub agreement: Account<'info, ServiceAgreement>,
+
+    /// Caller agent's creator
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = caller_agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Propose paying `amount` for the target agent's `service_id` through
+/// `call_agent_service`, open until `expires_at` (at most 24 hours out)
+pub fn handler(
+    ctx: Context<ProposeTerms>,
+    service_id: [u8; 32],
+    amount: u64,
+    expires_at: i64,
+) -> Result<()> {
+    let target_x402_config = &ctx.accounts.target_x402_config;
+    target_x402_config.require_accepting_payments()?;
+    require!(service_id != [0; 32], AgentFactoryError::InvalidServiceId);
+    target_x402_config.require_service_live(&service_id)?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        amount > 0 && expires_at > now && expires_at - now <= MAX_QUOTE_TTL_SECS,
+        AgentFactoryError::InvalidTerms
+    );
+
+    let agreement = &mut ctx.accounts.agreement;
+    agreement.caller_agent = ctx.accounts.caller_agent.key();
+    agreement.target_agent = ctx.accounts.target_agent.key();
+    agreement.service_id = service_id;
+    agreement.amount = amount;
+    agreement.expires_at = expires_at;
+    agreement.accepted = false;
+    agreement.used = false;
+    agreement.proposer = ctx.accounts.creator.key();
+    agreement.bump = ctx.bumps.agreement;
+
+    trace!("Terms proposed!");
+    trace!("Caller: {}, Target: {}", agreement.caller_agent, agreement.target_agent);
+    trace!("Amount: {}, Expires: {}", amount, expires_at);
+
+    emit_cpi!(ServiceTermsEvent {
+        agreement: agreement.key(),
+        caller_agent: agreement.caller_agent,
+        target_agent: agreement.target_agent,
+        service_id,
+        amount,
+        expires_at,
+        accepted: false,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+use crate::events::AdminActionEvent;
+use crate::state::{
+    AdminAction, AdminActionStage, Agent, AgentFactory, ProtocolLiquidity, QueuedAdminAction,
+    StakingPool, MAX_CREATION_WINDOW_SECS, MAX_CREATOR_FEE_VESTING_SECS, MAX_PROTOCOL_LIQUIDITY_BPS,
+    MAX_TIMELOCK_DELAY_SECS,
+    MIN_TIMELOCK_DELAY_SECS,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct QueueAdminAction<'info> {
+    /// Factory singleton; its `admin_action_count` is the new action id
+    #[account(
+        mut,
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// New queued action: `["admin_action", action_id]`
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + QueuedAdminAction::INIT_SPACE,
+        seeds = [b"admin_action".as_ref(), &factory.admin_action_count.to_le_bytes()],
+        bump
+    )]
+    pub queued_action: Account<'info, QueuedAdminAction>,
+
+    /// Factory authority; a PDA (e.g. a Squads vault) signing via CPI when
+    /// `factory.admin_is_program` is set
+    pub authority: Signer<'info>,
+
+    /// Pays the queued action's rent and gets it back when the action is
+    /// executed or cancelled. Must be the authority unless
+    /// `factory.admin_is_program` is set.
+    #[account(
+        mut,
+        constraint = factory.admin_is_program || payer.key() == authority.key() @ AgentFactoryError::InvalidAdminAction
+    )]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// `SetFeeDiscounts` only: the staking pool being configured
+    pub platform_staking_pool: Option<Box<Account<'info, StakingPool>>>,
+
+    /// `SetFeeDiscounts` only: the platform token's agent, owner of the pool
+    pub platform_agent: Option<Account<'info, Agent>>,
+
+    /// `SetProtocolLiquidity` with a non-zero share only: the vault that
+    /// will receive it must exist
+    #[account(
+        seeds = [b"protocol_liquidity"],
+        bump = protocol_liquidity.bump
+    )]
+    pub protocol_liquidity: Option<Account<'info, ProtocolLiquidity>>,
+}
+
+/// Queue a factory setting change; it becomes executable after the timelock
+pub fn handler(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+    match action {
+        AdminAction::SetTimelockDelay { delay_secs } => require!(
+            (MIN_TIMELOCK_DELAY_SECS..=MAX_TIMELOCK_DELAY_SECS).contains(&delay_secs),
+            AgentFactoryError::InvalidAdminAction
+        ),
+        AdminAction::SetAuthority { new_authority, .. } => require!(
+            new_authority != Pubkey::default(),
+            AgentFactoryError::InvalidAdminAction
+        ),
+        AdminAction::SetInsuranceFee { insurance_fee_bps } => require!(
+            insurance_fee_bps as u64 <= BPS_DENOMINATOR,
+            AgentFactoryError::InvalidAdminAction
+        ),
+        AdminAction::SetFeeDiscounts { platform_mint, staking_pool, tiers } => {
+            require!(
+                AgentFactory::fee_discount_tiers_valid(&tiers),
+                AgentFactoryError::InvalidAdminAction
+            );
+            // The pool must be the platform token agent's staking pool
+            if platform_mint != Pubkey::default() {
+                let (Some(pool), Some(agent)) =
+                    (&ctx.accounts.platform_staking_pool, &ctx.accounts.platform_agent)
+                else {
+                    return err!(AgentFactoryError::InvalidAdminAction);
+                };
+                require!(
+                    pool.key() == staking_pool && pool.agent == agent.key() && agent.mint == platform_mint,
+                    AgentFactoryError::InvalidAdminAction
+                );
+            }
+        }
+        AdminAction::SetProtocolLiquidity { protocol_liquidity_bps } => require!(
+            protocol_liquidity_bps <= MAX_PROTOCOL_LIQUIDITY_BPS
+                && (protocol_liquidity_bps == 0 || ctx.accounts.protocol_liquidity.is_some()),
+            AgentFactoryError::InvalidAdminAction
+        ),
+        AdminAction::SetArbitrationCouncil { arbiters, threshold } => require!(
+            AgentFactory::arbitration_council_valid(&arbiters, threshold),
+            AgentFactoryError::InvalidAdminAction
+        ),
+        AdminAction::SetCreatorFeeVesting { vesting_secs } => require!(
+            (0..=MAX_CREATOR_FEE_VESTING_SECS).contains(&vesting_secs),
+            AgentFactoryError::InvalidAdminAction
+        ),
+        AdminAction::SetAllowedModels { models } => require!(
+            AgentFactory::allowed_models_valid(&models),
+            AgentFactoryError::InvalidAdminAction
+        ),
+        AdminAction::SetCreationSurge { surge_bps, half_life_secs } => require!(
+            surge_bps as u64 <= BPS_DENOMINATOR
+                && half_life_secs > 0
+                && half_life_secs <= MAX_CREATION_WINDOW_SECS,
+            AgentFactoryError::InvalidAdminAction
+        ),
+        AdminAction::SetPublicGoodsFee { recipient, fee_bps } => require!(
+            fee_bps as u64 <= BPS_DENOMINATOR && recipient != Pubkey::default(),
+            AgentFactoryError::InvalidAdminAction
+        ),
+        AdminAction::SetCreationFee { .. }
+        | AdminAction::SetQuoteCreationFee { .. }
+        | AdminAction::SetPlatformTreasury { .. }
+        | AdminAction::SetRenameFee { .. }
+        | AdminAction::SetReputationAttestor { .. } => {}
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let factory = &mut ctx.accounts.factory;
+
+    let queued = &mut ctx.accounts.queued_action;
+    queued.action_id = factory.admin_action_count;
+    queued.action = action;
+    queued.queued_at = now;
+    queued.rent_payer = ctx.accounts.payer.key();
+    queued.eta = now
+        .checked_add(factory.timelock_delay_secs)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    queued.bump = ctx.bumps.queued_action;
+
+    factory.admin_action_count += 1;
+
+    emit_cpi!(AdminActionEvent {
+        action_id: queued.action_id,
+        action,
+        stage: AdminActionStage::Queued,
+        eta: queued.eta,
+        timestamp: now,
+    });
+
+    trace!("Admin action queued!");
+    trace!("Action ID: {}", queued.action_id);
+    trace!("Executable at: {}", queued.eta);
+
+    Ok(())
+}
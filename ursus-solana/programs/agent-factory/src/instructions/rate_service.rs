@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::events::ServiceRatedEvent;
+use crate::state::{
+    Agent, AgentFactory, AgentReputation, PaymentStatus, ServiceRating, X402Config,
+    X402PaymentRecord, MAX_RATING_STARS,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RateService<'info> {
+    #[account(
+        constraint = agent.creator != payer.key() @ AgentFactoryError::SelfRating
+    )]
+    pub agent: Box<Account<'info, Agent>>,
+
+    #[account(
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump,
+        constraint = x402_config.payment_recipient != payer.key()
+            && x402_config.previous_payment_recipient != payer.key() @ AgentFactoryError::SelfRating
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    /// Factory singleton, pinning the quote mint ratings are weighed in
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Box<Account<'info, AgentFactory>>,
+
+    /// Payment being rated; only payments in the factory's quote mint carry
+    /// weight
+    #[account(
+        has_one = agent,
+        has_one = payer,
+        constraint = matches!(
+            payment_record.status,
+            PaymentStatus::Verified | PaymentStatus::Settled
+        ) @ AgentFactoryError::PaymentNotRatable,
+        constraint = factory.quote_mint != Pubkey::default()
+            && payment_record.mint == factory.quote_mint @ AgentFactoryError::PaymentNotRatable
+    )]
+    pub payment_record: Box<Account<'info, X402PaymentRecord>>,
+
+    /// One per payment
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ServiceRating::INIT_SPACE,
+        seeds = [b"rating", payment_record.key().as_ref()],
+        bump
+    )]
+    pub rating: Box<Account<'info, ServiceRating>>,
+
+    /// Created on the agent's first rating
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + AgentReputation::INIT_SPACE,
+        seeds = [b"reputation", agent.key().as_ref()],
+        bump
+    )]
+    pub reputation: Box<Account<'info, AgentReputation>>,
+
+    /// Payer of the rated payment, giving the rating
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Rate the service behind one of the caller's settled payments with 1 to 5
+/// stars. The rating counts towards the agent's reputation weighted by the
+/// amount the caller kept paying after refunds, and each payment can be
+/// rated once. Only payments in the factory's quote mint count, so the
+/// weight can't be bought with a self-minted token.
+pub fn handler(ctx: Context<RateService>, stars: u8) -> Result<()> {
+    require!((1..=MAX_RATING_STARS).contains(&stars), AgentFactoryError::InvalidRating);
+
+    let payment_record = &ctx.accounts.payment_record;
+    let paid = payment_record.amount.saturating_sub(payment_record.refunded_amount);
+    require!(paid > 0, AgentFactoryError::PaymentNotRatable);
+
+    let now = Clock::get()?.unix_timestamp;
+    let rating = &mut ctx.accounts.rating;
+    rating.agent = ctx.accounts.agent.key();
+    rating.payment_record = payment_record.key();
+    rating.rater = ctx.accounts.payer.key();
+    rating.stars = stars;
+    rating.weight = paid;
+    rating.rated_at = now;
+    rating.bump = ctx.bumps.rating;
+
+    let reputation = &mut ctx.accounts.reputation;
+    reputation.agent = rating.agent;
+    reputation.bump = ctx.bumps.reputation;
+    reputation.record_rating(stars, paid)?;
+    let rating_bps = reputation.rating_bps();
+
+    trace!("Service rated!");
+    trace!("Stars: {}, Weight: {}, Rating: {} bps", stars, paid, rating_bps);
+
+    emit_cpi!(ServiceRatedEvent {
+        agent: rating.agent,
+        payment_record: rating.payment_record,
+        rater: rating.rater,
+        service_id: payment_record.service_id,
+        stars,
+        weight: paid,
+        rating_bps,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::IndexBasket;
+use super::mint_basket::constituent_accounts;
+
+#[derive(Accounts)]
+pub struct RedeemBasket<'info> {
+    #[account(
+        seeds = [
+            b"index_basket",
+            basket.creator.as_ref(),
+            &basket.basket_id.to_le_bytes()
+        ],
+        bump = basket.bump,
+        has_one = basket_mint
+    )]
+    pub basket: Box<Account<'info, IndexBasket>>,
+
+    #[account(mut)]
+    pub basket_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = basket_mint,
+        token::authority = holder
+    )]
+    pub holder_basket_account: Account<'info, TokenAccount>,
+
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Burn `amount` basket tokens (base units) and pay out the constituents
+/// backing them. Remaining accounts are `[holder_account, vault]` per
+/// constituent, in basket order.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RedeemBasket<'info>>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidBasketAmount);
+
+    let holder = ctx.accounts.holder.key();
+    let accounts = constituent_accounts(&ctx.accounts.basket, ctx.remaining_accounts, &holder)?;
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.basket_mint.to_account_info(),
+            from: ctx.accounts.holder_basket_account.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        },
+    );
+    token::burn(cpi_ctx, amount)?;
+
+    let creator = ctx.accounts.basket.creator;
+    let basket_id_bytes = ctx.accounts.basket.basket_id.to_le_bytes();
+    let seeds = &[
+        b"index_basket",
+        creator.as_ref(),
+        basket_id_bytes.as_ref(),
+        &[ctx.accounts.basket.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    for (constituent, (holder_account, vault)) in ctx.accounts.basket.constituents.iter().zip(&accounts) {
+        let payout = IndexBasket::constituent_amount(constituent.units, amount, false)?;
+        if payout == 0 {
+            continue;
+        }
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: holder_account.to_account_info(),
+                authority: ctx.accounts.basket.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, payout)?;
+    }
+
+    trace!("Basket tokens redeemed!");
+    trace!("Basket: {}", ctx.accounts.basket.name);
+    trace!("Amount: {}", amount);
+
+    Ok(())
+}
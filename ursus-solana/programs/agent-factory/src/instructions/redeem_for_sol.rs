@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::events::TradeEvent;
+use crate::state::{Agent, AgentVault};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RedeemForSol<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        address = agent.mint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Agent's curve SOL vault
+    #[account(
+        mut,
+        seeds = [b"sol_vault", agent.key().as_ref()],
+        bump = agent.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Agent's platform fee vault, checked against its booked balance
+    #[account(
+        seeds = [b"platform_fee_vault", agent.key().as_ref()],
+        bump = agent.platform_fee_vault_bump
+    )]
+    pub platform_fee_vault: SystemAccount<'info>,
+
+    /// Agent's creator fee vault, checked against its booked balance
+    #[account(
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Redeem tokens of an agent that missed its guaranteed graduation deadline.
+/// Tokens are burned for their share of the remaining reserves, i.e. the
+/// average entry price of the outstanding supply; no fees are charged.
+pub fn handler(
+    ctx: Context<RedeemForSol>,
+    token_amount: u64,
+    min_sol_out: u64,
+) -> Result<()> {
+    require!(token_amount > 0, AgentFactoryError::InvalidSellAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let agent = &mut ctx.accounts.agent;
+    require!(agent.is_redeemable(now), AgentFactoryError::RedemptionNotOpen);
+
+    let sol_out = agent.bonding_curve.redemption_value(token_amount)?;
+    require_gte!(sol_out, min_sol_out, AgentFactoryError::SlippageExceeded);
+
+    // Burn the redeemed tokens
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.mint.to_account_info(),
+        from: ctx.accounts.holder_token_account.to_account_info(),
+        authority: ctx.accounts.holder.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::burn(cpi_ctx, token_amount)?;
+
+    // Pay out of the curve SOL vault
+    AgentVault::Sol.pay(
+        &agent.key(),
+        agent,
+        &ctx.accounts.sol_vault,
+        &ctx.accounts.holder.to_account_info(),
+        &ctx.accounts.system_program,
+        sol_out,
+    )?;
+
+    let curve_before = agent.bonding_curve;
+    agent.bonding_curve.update_after_sell(token_amount, sol_out)?;
+    agent.check_reserves(
+        &ctx.accounts.sol_vault,
+        &ctx.accounts.platform_fee_vault,
+        &ctx.accounts.creator_fee_vault,
+        &curve_before,
+        now,
+    )?;
+    agent.record_twap_price(now, Clock::get()?.slot);
+
+    trace!("Tokens redeemed!");
+    trace!("Tokens redeemed: {}", token_amount);
+    trace!("SOL received: {}", sol_out);
+    trace!("Remaining SOL reserves: {}", agent.bonding_curve.real_sol_reserves);
+
+    let sequence = agent.next_event_sequence()?;
+    emit_cpi!(TradeEvent {
+        agent: agent.key(),
+        trader: ctx.accounts.holder.key(),
+        is_buy: false,
+        sol_amount: sol_out,
+        token_amount,
+        platform_fee: 0,
+        creator_fee: 0,
+        price: agent.bonding_curve.get_current_price(),
+        timestamp: now,
+        slot: Clock::get()?.slot,
+        sequence,
+        reserves_before: curve_before.reserves_at(now),
+        reserves_after: agent.bonding_curve.reserves_at(now),
+        price_impact_bps: agent.bonding_curve.price_impact_bps(&curve_before, now),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Presale, PresaleCommitment};
+
+#[derive(Accounts)]
+pub struct RefundCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"presale", presale.agent.as_ref()],
+        bump = presale.bump
+    )]
+    pub presale: Account<'info, Presale>,
+
+    #[account(
+        mut,
+        close = committer,
+        seeds = [b"commitment", presale.key().as_ref(), committer.key().as_ref()],
+        bump = commitment.bump,
+        has_one = presale,
+        has_one = committer
+    )]
+    pub commitment: Account<'info, PresaleCommitment>,
+
+    #[account(mut)]
+    pub committer: Signer<'info>,
+}
+
+/// Refund a commitment to a presale that missed its soft cap. The commitment
+/// account is closed to the committer.
+pub fn handler(ctx: Context<RefundCommitment>) -> Result<()> {
+    let presale = &ctx.accounts.presale;
+    require!(presale.is_finalized, AgentFactoryError::PresaleNotFinalized);
+    require!(!presale.is_successful, AgentFactoryError::PresaleSucceeded);
+
+    let amount = ctx.accounts.commitment.amount;
+    **ctx.accounts.presale.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.committer.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    trace!("Presale commitment refunded!");
+    trace!("Amount: {}", amount);
+
+    Ok(())
+}
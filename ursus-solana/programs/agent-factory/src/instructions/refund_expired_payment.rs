@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::state::{PaymentStatus, X402Config, X402PaymentRecord};
+use super::settle_attested_payment::release_escrow;
+
+#[derive(Accounts)]
+pub struct RefundExpiredPayment<'info> {
+    #[account(
+        seeds = [b"x402_config", payment_record.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        constraint = payment_record.status == PaymentStatus::Pending @ AgentFactoryError::PaymentNotPending
+    )]
+    pub payment_record: Box<Account<'info, X402PaymentRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"payment_escrow", payment_record.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payer's USDC token account
+    #[account(
+        mut,
+        constraint = payer_token_account.owner == payer.key() @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Refund an escrowed payment nobody attested within the service timeout
+pub fn handler(ctx: Context<RefundExpiredPayment>) -> Result<()> {
+    let expires_at = ctx.accounts.payment_record.timestamp
+        .saturating_add(ctx.accounts.x402_config.service_timeout_seconds as i64);
+    require!(
+        Clock::get()?.unix_timestamp >= expires_at,
+        AgentFactoryError::PaymentNotExpired
+    );
+
+    let amount = ctx.accounts.escrow_token_account.amount;
+    release_escrow(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.payer_token_account.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+    ctx.accounts.payment_record.status = PaymentStatus::Failed;
+    ctx.accounts.payment_record.refunded_amount = amount;
+
+    trace!("Expired payment refunded!");
+    trace!("Amount: {}", amount);
+
+    Ok(())
+}
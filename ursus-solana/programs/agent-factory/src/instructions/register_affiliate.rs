@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Affiliate, MAX_AFFILIATE_BPS};
+
+#[derive(Accounts)]
+pub struct RegisterAffiliate<'info> {
+    /// Re-registering updates the affiliate's fee
+    #[account(
+        init_if_needed,
+        payer = wallet,
+        space = 8 + Affiliate::INIT_SPACE,
+        seeds = [b"affiliate", wallet.key().as_ref()],
+        bump
+    )]
+    pub affiliate: Account<'info, Affiliate>,
+
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register as an affiliate asking `fee_bps` of the x402 payments it refers,
+/// or update the fee of an existing registration
+pub fn handler(ctx: Context<RegisterAffiliate>, fee_bps: u16) -> Result<()> {
+    require!(fee_bps <= MAX_AFFILIATE_BPS, AgentFactoryError::AffiliateFeeTooHigh);
+
+    let affiliate = &mut ctx.accounts.affiliate;
+    if affiliate.wallet == Pubkey::default() {
+        affiliate.wallet = ctx.accounts.wallet.key();
+        affiliate.total_referred = 0;
+        affiliate.total_earned = 0;
+        affiliate.created_at = Clock::get()?.unix_timestamp;
+        affiliate.bump = ctx.bumps.affiliate;
+    }
+    affiliate.fee_bps = fee_bps;
+
+    trace!("Affiliate registered!");
+    trace!("Wallet: {}, Fee: {} bps", affiliate.wallet, fee_bps);
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::errors::AgentFactoryError;
+use crate::state::{KeeperTask, KeeperTaskKind};
+
+#[derive(Accounts)]
+#[instruction(task_id: u64)]
+pub struct RegisterKeeperTask<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + KeeperTask::INIT_SPACE,
+        seeds = [
+            b"keeper_task",
+            owner.key().as_ref(),
+            &task_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub task: Account<'info, KeeperTask>,
+
+    /// CHECK: DCA schedule, agent or queued admin action, checked against
+    /// the task kind
+    pub target: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register a crank on `target` for keepers, tipping `tip_lamports` per unit
+/// of work out of `deposit`. Only work done after registration is tipped.
+pub fn handler(
+    ctx: Context<RegisterKeeperTask>,
+    task_id: u64,
+    kind: KeeperTaskKind,
+    tip_lamports: u64,
+    deposit: u64,
+) -> Result<()> {
+    require!(tip_lamports > 0, AgentFactoryError::InvalidKeeperAmount);
+    let target = ctx.accounts.target.to_account_info();
+    require!(
+        target.owner == &crate::ID && !target.data_is_empty(),
+        AgentFactoryError::InvalidKeeperTask
+    );
+    let progress = kind.progress(&target)?;
+
+    if deposit > 0 {
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.task.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, deposit)?;
+    }
+
+    let task = &mut ctx.accounts.task;
+    task.owner = ctx.accounts.owner.key();
+    task.task_id = task_id;
+    task.kind = kind;
+    task.target = target.key();
+    task.tip_lamports = tip_lamports;
+    task.progress = progress;
+    task.total_tips_paid = 0;
+    task.created_at = Clock::get()?.unix_timestamp;
+    task.bump = ctx.bumps.task;
+
+    trace!("Keeper task registered!");
+    trace!("Kind: {:?}", kind);
+    trace!("Target: {}", task.target);
+    trace!("Tip: {}", tip_lamports);
+    trace!("Deposit: {}", deposit);
+
+    Ok(())
+}
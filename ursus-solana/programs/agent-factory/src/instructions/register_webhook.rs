@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::events::WebhookEvent;
+use crate::state::{Agent, X402Config};
+use crate::errors::AgentFactoryError;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RegisterWebhook<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Commit to the agent's callback endpoint (`url_hash`, SHA-256 of the URL)
+/// and the key signing its responses. A zero hash clears the webhook.
+pub fn handler(ctx: Context<RegisterWebhook>, url_hash: [u8; 32], signer: Pubkey) -> Result<()> {
+    let x402_config = &mut ctx.accounts.x402_config;
+    x402_config.webhook_url_hash = url_hash;
+    x402_config.webhook_signer = signer;
+
+    trace!("Webhook registered!");
+    trace!("Signer: {}", signer);
+
+    emit_cpi!(WebhookEvent {
+        agent: ctx.accounts.agent.key(),
+        url_hash,
+        signer,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
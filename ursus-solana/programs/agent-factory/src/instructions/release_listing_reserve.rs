@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, ListingReserve};
+
+#[derive(Accounts)]
+pub struct ReleaseListingReserve<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"listing_reserve", agent.key().as_ref()],
+        bump = listing_reserve.bump
+    )]
+    pub listing_reserve: Account<'info, ListingReserve>,
+
+    #[account(
+        mut,
+        seeds = [b"listing_reserve_vault", listing_reserve.key().as_ref()],
+        bump
+    )]
+    pub listing_reserve_vault: Account<'info, TokenAccount>,
+
+    /// Exchange or market maker account receiving the tokens
+    #[account(
+        mut,
+        token::mint = agent.mint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Release `amount` reserved tokens to an exchange or market maker once the
+/// agent has graduated and the reserve's delay has passed
+pub fn handler(ctx: Context<ReleaseListingReserve>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let listing_reserve = &ctx.accounts.listing_reserve;
+    let unlocks_at = listing_reserve
+        .unlocks_at(&ctx.accounts.agent)
+        .ok_or(AgentFactoryError::ListingReserveLocked)?;
+    require_gte!(now, unlocks_at, AgentFactoryError::ListingReserveLocked);
+    let remaining = listing_reserve.amount.saturating_sub(listing_reserve.released);
+    require_gte!(remaining, amount, AgentFactoryError::ListingReserveExceeded);
+
+    let agent_key = ctx.accounts.agent.key();
+    let seeds = &[
+        b"listing_reserve",
+        agent_key.as_ref(),
+        &[listing_reserve.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.listing_reserve_vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.listing_reserve.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let listing_reserve = &mut ctx.accounts.listing_reserve;
+    listing_reserve.released = listing_reserve.released
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("Listing reserve released!");
+    trace!("Amount: {}", amount);
+    trace!("Remaining: {}", listing_reserve.amount - listing_reserve.released);
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::state::{Job, MilestoneStatus, RevenueSplit, X402Config};
+use super::settle_attested_payment::route_escrowed_revenue;
+use super::withdraw_streamed::pay_out_stream;
+
+#[derive(Accounts)]
+pub struct ReleaseMilestone<'info> {
+    #[account(
+        mut,
+        seeds = [b"x402_config", job.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(mut)]
+    pub job: Box<Account<'info, Job>>,
+
+    #[account(
+        mut,
+        seeds = [b"job_escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Recipient's USDC token account, paid the milestone
+    #[account(
+        mut,
+        constraint = x402_config.accepts_recipient(&recipient_token_account.owner, Clock::get()?.unix_timestamp) @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Payer, receives the escrow account's rent once it's drained
+    #[account(
+        mut,
+        address = job.payer
+    )]
+    pub payer: AccountInfo<'info>,
+
+    /// The payer, or anyone once the review period has ended
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Agent's revenue split, required while it routes revenue to other
+    /// agents; the worker treasuries' token accounts follow as remaining
+    /// accounts, in share order
+    #[account(
+        mut,
+        seeds = [b"revenue_split", job.agent.as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Box<Account<'info, RevenueSplit>>>,
+}
+
+/// Pay a submitted milestone to the provider. The payer can release it
+/// any time; once its review period ends undisputed, anyone can.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReleaseMilestone<'info>>,
+    index: u8,
+) -> Result<()> {
+    // A frozen agent can't collect
+    require!(!ctx.accounts.x402_config.frozen, AgentFactoryError::X402Frozen);
+
+    let now = Clock::get()?.unix_timestamp;
+    let is_payer = ctx.accounts.authority.key() == ctx.accounts.job.payer;
+    let job = &mut ctx.accounts.job;
+    let milestone = *job.milestone_in(index, MilestoneStatus::Submitted)?;
+    require!(is_payer || job.review_ended(&milestone, now), AgentFactoryError::ReviewPeriodActive);
+
+    let routed = route_escrowed_revenue(
+        &ctx.accounts.x402_config,
+        ctx.accounts.revenue_split.as_deref_mut(),
+        ctx.remaining_accounts,
+        &ctx.accounts.escrow_token_account,
+        milestone.amount,
+        &ctx.accounts.token_program,
+    )?;
+    if routed > 0 {
+        ctx.accounts.escrow_token_account.reload()?;
+    }
+    pay_out_stream(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        milestone.amount - routed,
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+    )?;
+
+    let job = &mut ctx.accounts.job;
+    record_job_payment(&mut ctx.accounts.x402_config, job, milestone.amount)?;
+    job.milestones[index as usize].status = MilestoneStatus::Released;
+
+    trace!("Milestone released!");
+    trace!("Milestone: {}, Amount: {}, Released so far: {}", index, milestone.amount, job.released);
+
+    Ok(())
+}
+
+/// Count `amount` of a job as paid to the agent; the job counts as one
+/// service call, on its first payment
+pub fn record_job_payment(x402_config: &mut X402Config, job: &mut Job, amount: u64) -> Result<()> {
+    if job.released == 0 {
+        x402_config.record_payment(amount)?;
+    } else {
+        x402_config.total_payments_received = x402_config.total_payments_received
+            .checked_add(amount)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+    }
+    job.released = job.released
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::Agent;
+
+#[derive(Accounts)]
+pub struct RemoveAgentTag<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Drop one of an agent's discovery tags
+pub fn handler(ctx: Context<RemoveAgentTag>, tag: &str) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let index = agent.tags
+        .iter()
+        .position(|t| t == tag)
+        .ok_or(AgentFactoryError::TagNotFound)?;
+    agent.tags.remove(index);
+
+    trace!("Tag removed: {}", tag);
+    trace!("Tags: {}", agent.tags.join(", "));
+
+    Ok(())
+}
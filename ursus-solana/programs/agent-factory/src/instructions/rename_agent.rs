@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::metadata::{self, Metadata, UpdateMetadataAccountsV2};
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::events::AgentRenamedEvent;
+use crate::instructions::create_agent::token_metadata;
+use crate::state::{Agent, AgentFactory, NameReservation, OWNERSHIP_NFT_SYMBOL, RENAME_COOLDOWN_SECS};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RenameAgent<'info> {
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Platform treasury, receives the rename fee
+    #[account(
+        mut,
+        address = factory.platform_treasury @ AgentFactoryError::InvalidFeePayment
+    )]
+    pub platform_treasury: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Reservation of the new name: `["name_reservation",
+    /// name_hash(new_name)]`; checked in the handler as on creation
+    pub name_reservation: UncheckedAccount<'info>,
+
+    /// CHECK: Reservation of the new symbol
+    pub symbol_reservation: UncheckedAccount<'info>,
+
+    /// CHECK: Agent token's Metaplex metadata; agents created before token
+    /// metadata have none, and it is skipped
+    #[account(
+        mut,
+        seeds = [b"metadata", metadata_program.key().as_ref(), agent.mint.as_ref()],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub mint_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Ownership NFT's Metaplex metadata, renamed along with the
+    /// agent; skipped when it doesn't exist
+    #[account(
+        mut,
+        seeds = [b"metadata", metadata_program.key().as_ref(), agent.ownership_mint.as_ref()],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub ownership_metadata: UncheckedAccount<'info>,
+
+    pub metadata_program: Program<'info, Metadata>,
+}
+
+/// Rename an agent and its token symbol for the factory's rename fee, at
+/// most once per `RENAME_COOLDOWN_SECS`. Updates the agent account and the
+/// Metaplex metadata of its token and ownership NFT.
+pub fn handler(ctx: Context<RenameAgent>, new_name: &str, new_symbol: &str) -> Result<()> {
+    require!(!new_name.is_empty() && new_name.len() <= 32, AgentFactoryError::InvalidName);
+    require!(!new_symbol.is_empty() && new_symbol.len() <= 10, AgentFactoryError::InvalidSymbol);
+    // Renaming can't take a verified brand's name or symbol either
+    NameReservation::check(&ctx.accounts.name_reservation, new_name, &ctx.accounts.creator.key())?;
+    NameReservation::check(&ctx.accounts.symbol_reservation, new_symbol, &ctx.accounts.creator.key())?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let agent = &ctx.accounts.agent;
+    require!(
+        agent.last_renamed_at == 0 || now >= agent.last_renamed_at + RENAME_COOLDOWN_SECS,
+        AgentFactoryError::RenameCooldown
+    );
+
+    let fee = ctx.accounts.factory.rename_fee;
+    if fee > 0 {
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.creator.to_account_info(),
+                to: ctx.accounts.platform_treasury.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_context, fee)?;
+    }
+
+    let agent = &mut ctx.accounts.agent;
+    let old_name = std::mem::replace(&mut agent.name, new_name.to_string());
+    let old_symbol = std::mem::replace(&mut agent.symbol, new_symbol.to_string());
+    agent.last_renamed_at = now;
+
+    let agent_id_bytes = agent.agent_id.to_le_bytes();
+    let seeds = &[
+        b"agent",
+        agent_id_bytes.as_ref(),
+        &[agent.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    for (metadata_account, symbol) in [
+        (&ctx.accounts.mint_metadata, new_symbol),
+        (&ctx.accounts.ownership_metadata, OWNERSHIP_NFT_SYMBOL),
+    ] {
+        if metadata_account.data_is_empty() {
+            continue;
+        }
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: metadata_account.to_account_info(),
+                update_authority: agent.to_account_info(),
+            },
+            signer_seeds,
+        );
+        metadata::update_metadata_accounts_v2(cpi_ctx, None, Some(token_metadata(new_name, symbol)), None, None)?;
+    }
+
+    trace!("Agent renamed: {} ({}) -> {} ({})", old_name, old_symbol, new_name, new_symbol);
+
+    emit_cpi!(AgentRenamedEvent {
+        agent: agent.key(),
+        old_name,
+        old_symbol,
+        new_name: new_name.to_string(),
+        new_symbol: new_symbol.to_string(),
+        fee,
+        timestamp: now,
+    });
+
+    Ok(())
+}
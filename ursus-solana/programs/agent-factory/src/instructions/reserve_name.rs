@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{AgentFactory, NameReservation};
+
+#[derive(Accounts)]
+#[instruction(name_hash: [u8; 32])]
+pub struct ReserveName<'info> {
+    /// Factory singleton
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Reservation: `["name_reservation", name_hash]`
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + NameReservation::INIT_SPACE,
+        seeds = [b"name_reservation", name_hash.as_ref()],
+        bump
+    )]
+    pub name_reservation: Account<'info, NameReservation>,
+
+    /// Factory authority (wallet, multisig or DAO PDA signer)
+    pub authority: Signer<'info>,
+
+    /// Pays the reservation's rent if it doesn't exist yet. Must be the
+    /// authority unless `factory.admin_is_program` is set.
+    #[account(
+        mut,
+        constraint = factory.admin_is_program || payer.key() == authority.key() @ AgentFactoryError::InvalidAdminAction
+    )]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Reserve a name or symbol (`NameReservation::name_hash`) for `owner`, such
+/// as a trademark holder; a default owner releases it
+pub fn handler(ctx: Context<ReserveName>, name_hash: [u8; 32], owner: Pubkey) -> Result<()> {
+    let reservation = &mut ctx.accounts.name_reservation;
+    reservation.name_hash = name_hash;
+    reservation.owner = owner;
+    reservation.reserved_at = Clock::get()?.unix_timestamp;
+    reservation.bump = ctx.bumps.name_reservation;
+
+    trace!("Name reservation updated!");
+    trace!("Reservation: {}", reservation.key());
+    trace!("Owner: {}", owner);
+
+    Ok(())
+}
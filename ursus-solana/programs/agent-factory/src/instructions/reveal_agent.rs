@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{AgentCommit, BondingCurve, CreatorFeeMode};
+// The nested `CreateAgent` needs its generated client and bumps types in scope
+use super::create_agent::*;
+
+#[derive(Accounts)]
+pub struct RevealAgent<'info> {
+    /// Same accounts as `create_agent`
+    pub create: CreateAgent<'info>,
+
+    /// Commitment being revealed; closed to the creator
+    #[account(
+        mut,
+        seeds = [b"agent_commit", create.creator.key().as_ref(), agent_commit.commit_hash.as_ref()],
+        bump = agent_commit.bump
+    )]
+    pub agent_commit: Account<'info, AgentCommit>,
+}
+
+/// Create the agent committed to by `commit_agent`. The fields and `salt`
+/// must hash to the commitment, and the commitment must have landed in an
+/// earlier slot.
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RevealAgent<'info>>,
+    name: &str,
+    symbol: &str,
+    description: &str,
+    instructions: &str,
+    model: &str,
+    tags: &[String],
+    salt: [u8; 32],
+) -> Result<()> {
+    let agent_commit = &ctx.accounts.agent_commit;
+    let commit_slot = agent_commit.slot;
+    require_gt!(Clock::get()?.slot, commit_slot, AgentFactoryError::RevealTooEarly);
+    require!(
+        AgentCommit::commit_hash(name, symbol, description, instructions, model, tags, &salt) == agent_commit.commit_hash,
+        AgentFactoryError::CommitmentMismatch
+    );
+
+    super::create_agent::handler(
+        Context::new(
+            ctx.program_id,
+            &mut ctx.accounts.create,
+            ctx.remaining_accounts,
+            ctx.bumps.create,
+        ),
+        name,
+        symbol,
+        description,
+        instructions,
+        model,
+        tags,
+        BondingCurve::new(),
+        CreatorFeeMode::Creator,
+    )?;
+
+    // A clone launched after seeing the reveal has no commitment, or a later
+    // one, so the original stays identifiable
+    ctx.accounts.create.agent.commit_slot = commit_slot;
+    ctx.accounts.agent_commit.close(ctx.accounts.create.creator.to_account_info())?;
+
+    trace!("Agent revealed from commitment at slot {}", commit_slot);
+
+    Ok(())
+}
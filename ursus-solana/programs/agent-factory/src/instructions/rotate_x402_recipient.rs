@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::events::X402RecipientRotatedEvent;
+use crate::state::{Agent, X402Config, MAX_RECIPIENT_GRACE_SECS};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RotateX402Recipient<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Move x402 payments to `new_recipient`. The old recipient stays valid for
+/// `grace_secs` so facilitator settlements built against it don't fail
+/// mid-rotation; a later rotation replaces any open grace window.
+pub fn handler(ctx: Context<RotateX402Recipient>, new_recipient: Pubkey, grace_secs: i64) -> Result<()> {
+    let x402_config = &mut ctx.accounts.x402_config;
+    require!(
+        (0..=MAX_RECIPIENT_GRACE_SECS).contains(&grace_secs) && new_recipient != x402_config.payment_recipient,
+        AgentFactoryError::InvalidRecipientRotation
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let old_recipient = x402_config.payment_recipient;
+    x402_config.previous_payment_recipient = old_recipient;
+    x402_config.previous_recipient_valid_until = now + grace_secs;
+    x402_config.payment_recipient = new_recipient;
+
+    trace!("X402 payment recipient rotated!");
+    trace!("Old: {}, New: {}", old_recipient, new_recipient);
+    trace!("Grace ends at: {}", x402_config.previous_recipient_valid_until);
+
+    emit_cpi!(X402RecipientRotatedEvent {
+        agent: x402_config.agent,
+        old_recipient,
+        new_recipient,
+        grace_ends_at: x402_config.previous_recipient_valid_until,
+        timestamp: now,
+    });
+
+    Ok(())
+}
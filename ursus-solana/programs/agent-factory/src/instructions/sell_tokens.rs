@@ -1,43 +1,139 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Burn};
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
 use crate::errors::AgentFactoryError;
+use crate::events::TradeEvent;
+use crate::state::{Agent, AgentFactory, AgentVault, PriceHistory, StakePosition, TrendingLeaderboard};
+use super::buy_tokens::platform_fee_discount;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SellTokens<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        address = agent.mint
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = seller
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: Creator receives fees
+    #[account(
+        mut,
+        address = agent.creator
+    )]
+    pub creator: AccountInfo<'info>,
+
+    /// CHECK: Platform treasury receives fees
+    #[account(
+        mut,
+        address = factory.platform_treasury
+    )]
+    pub platform_treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Factory singleton, for the platform treasury and, with
+    /// `platform_stake`, the seller's platform token fee discount
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Seller's stake position in the platform token's staking pool
+    pub platform_stake: Option<Box<Account<'info, StakePosition>>>,
+
+    /// Agent's candle accumulator; updated when passed
+    #[account(
+        mut,
+        seeds = [b"price_history", agent.key().as_ref()],
+        bump = price_history.bump
+    )]
+    pub price_history: Option<Box<Account<'info, PriceHistory>>>,
+
+    /// Factory trending leaderboard; updated when passed
+    #[account(
+        mut,
+        seeds = [b"trending"],
+        bump = trending.bump
+    )]
+    pub trending: Option<Box<Account<'info, TrendingLeaderboard>>>,
+
+    /// Agent's curve SOL vault
+    #[account(
+        mut,
+        seeds = [b"sol_vault", agent.key().as_ref()],
+        bump = agent.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Agent's platform fee vault
+    #[account(
+        mut,
+        seeds = [b"platform_fee_vault", agent.key().as_ref()],
+        bump = agent.platform_fee_vault_bump
+    )]
+    pub platform_fee_vault: SystemAccount<'info>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
 
 pub fn handler(
-    ctx: Context<crate::SellTokens>,
+    ctx: Context<SellTokens>,
     token_amount: u64,
     min_sol_out: u64,
 ) -> Result<()> {
     require!(token_amount > 0, AgentFactoryError::InvalidSellAmount);
     
+    let discount_bps = platform_fee_discount(
+        &ctx.accounts.factory,
+        &ctx.accounts.platform_stake,
+        &ctx.accounts.seller.key(),
+    )?;
     let agent = &mut ctx.accounts.agent;
     
     // Check if agent is graduated
     require!(!agent.is_graduated, AgentFactoryError::AlreadyGraduated);
+    require!(!agent.is_presale_active, AgentFactoryError::PresaleActive);
+    require!(
+        !agent.is_redeemable(Clock::get()?.unix_timestamp),
+        AgentFactoryError::RedemptionOpen
+    );
+    if agent.same_slot_protection {
+        require!(
+            agent.last_buy_slot != Clock::get()?.slot,
+            AgentFactoryError::SameSlotSell
+        );
+    }
 
-    // Calculate SOL to receive using bonding curve
-    let sol_out = agent.bonding_curve.calculate_sell(token_amount)?;
+    // Calculate SOL to receive and fees (1% platform, 1% creator) using bonding curve;
+    // platform token stakers get a platform fee discount
+    let quote = agent.bonding_curve.quote_sell_discounted(token_amount, discount_bps)?;
+    let sol_out = quote.gross_sol_out;
+    let platform_fee = quote.platform_fee;
+    let creator_fee = quote.creator_fee;
+    let net_sol_out = quote.net_sol_out;
     
     // Check slippage tolerance
-    require!(sol_out >= min_sol_out, AgentFactoryError::SlippageExceeded);
-
-    // Calculate fees (1% platform fee)
-    let platform_fee = sol_out
-        .checked_mul(100)
-        .ok_or(AgentFactoryError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AgentFactoryError::MathOverflow)?;
-
-    let creator_fee = sol_out
-        .checked_mul(100)
-        .ok_or(AgentFactoryError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AgentFactoryError::MathOverflow)?;
-
-    let net_sol_out = sol_out
-        .checked_sub(platform_fee)
-        .ok_or(AgentFactoryError::MathOverflow)?
-        .checked_sub(creator_fee)
-        .ok_or(AgentFactoryError::MathOverflow)?;
+    require_gte!(sol_out, min_sol_out, AgentFactoryError::SlippageExceeded);
 
     // Burn tokens from seller
     let cpi_accounts = Burn {
@@ -49,39 +145,81 @@ pub fn handler(
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
     token::burn(cpi_ctx, token_amount)?;
 
-    // Transfer SOL from agent to seller
-    let agent_lamports = agent.to_account_info().lamports();
+    // The insurance and public goods shares of the platform fee move to the
+    // platform fee vault; the staking, treasury and royalty shares of the
+    // creator fee, and a streaming creator share, move to the creator fee vault
+    let (platform_fee_to_treasury, retained_platform_fee) = agent.split_platform_fee(platform_fee)?;
+    let (creator_fee_to_creator, retained_fee) = agent.split_creator_fee(creator_fee, Clock::get()?.unix_timestamp)?;
+
+    // Pay the gross proceeds out of the curve SOL vault
+    let rent_exempt = Rent::get()?.minimum_balance(0);
     require!(
-        agent_lamports >= net_sol_out,
+        ctx.accounts.sol_vault.lamports().saturating_sub(rent_exempt) >= sol_out,
         AgentFactoryError::InsufficientLiquidity
     );
-
-    **agent.to_account_info().try_borrow_mut_lamports()? -= net_sol_out;
-    **ctx.accounts.seller.to_account_info().try_borrow_mut_lamports()? += net_sol_out;
-
-    // Transfer platform fee
-    if platform_fee > 0 {
-        **agent.to_account_info().try_borrow_mut_lamports()? -= platform_fee;
-        **ctx.accounts.platform_treasury.to_account_info().try_borrow_mut_lamports()? += platform_fee;
-    }
-
-    // Transfer creator fee
-    if creator_fee > 0 {
-        **agent.to_account_info().try_borrow_mut_lamports()? -= creator_fee;
-        **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += creator_fee;
+    let agent_key = agent.key();
+    let sol_vault = ctx.accounts.sol_vault.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    for (to, amount) in [
+        (ctx.accounts.seller.to_account_info(), net_sol_out),
+        (ctx.accounts.platform_treasury.to_account_info(), platform_fee_to_treasury),
+        (ctx.accounts.platform_fee_vault.to_account_info(), retained_platform_fee),
+        (ctx.accounts.creator.to_account_info(), creator_fee_to_creator),
+        (ctx.accounts.creator_fee_vault.to_account_info(), retained_fee),
+    ] {
+        AgentVault::Sol.pay(&agent_key, agent, &sol_vault, &to, &system_program, amount)?;
     }
 
     // Update bonding curve reserves
+    let curve_before = agent.bonding_curve;
     agent.bonding_curve.update_after_sell(token_amount, sol_out)?;
+    agent.check_reserves(
+        &ctx.accounts.sol_vault,
+        &ctx.accounts.platform_fee_vault,
+        &ctx.accounts.creator_fee_vault,
+        &curve_before,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    trace!("Tokens sold successfully!");
+    trace!("Tokens sold: {}", token_amount);
+    trace!("SOL received: {}", net_sol_out);
+    trace!("Platform fee: {}", platform_fee);
+    trace!("Creator fee: {}", creator_fee);
+    trace!("New SOL reserves: {}", agent.bonding_curve.real_sol_reserves);
+    trace!("New token reserves: {}", agent.bonding_curve.real_token_reserves);
 
-    msg!("Tokens sold successfully!");
-    msg!("Tokens sold: {}", token_amount);
-    msg!("SOL received: {}", net_sol_out);
-    msg!("Platform fee: {}", platform_fee);
-    msg!("Creator fee: {}", creator_fee);
-    msg!("New SOL reserves: {}", agent.bonding_curve.real_sol_reserves);
-    msg!("New token reserves: {}", agent.bonding_curve.real_token_reserves);
+    let sequence = agent.next_event_sequence()?;
+    let clock = Clock::get()?;
+    if let Some(price_history) = ctx.accounts.price_history.as_mut() {
+        price_history.record(
+            curve_before.price_nano_u64_at(clock.unix_timestamp),
+            agent.bonding_curve.price_nano_u64_at(clock.unix_timestamp),
+            sol_out,
+            clock.unix_timestamp,
+        );
+    }
+    let trending_score = agent.record_trending_volume(sol_out, clock.slot);
+    agent.record_twap_price(clock.unix_timestamp, clock.slot);
+    if let Some(trending) = ctx.accounts.trending.as_mut() {
+        trending.update(agent.key(), trending_score, clock.slot);
+    }
+    emit_cpi!(TradeEvent {
+        agent: agent.key(),
+        trader: ctx.accounts.seller.key(),
+        is_buy: false,
+        sol_amount: sol_out,
+        token_amount,
+        platform_fee,
+        creator_fee,
+        price: agent.bonding_curve.get_current_price(),
+        timestamp: clock.unix_timestamp,
+        slot: clock.slot,
+        sequence,
+        reserves_before: curve_before.reserves_at(clock.unix_timestamp),
+        reserves_after: agent.bonding_curve.reserves_at(clock.unix_timestamp),
+        price_impact_bps: agent.bonding_curve.price_impact_bps(&curve_before, clock.unix_timestamp),
+    });
 
     Ok(())
 }
-
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, X402Config, MAX_AFFILIATE_BPS};
+
+#[derive(Accounts)]
+pub struct SetAffiliateCap<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Set the largest share of a payment an affiliate routing traffic to the
+/// agent is paid; 0 stops affiliate payouts
+pub fn handler(ctx: Context<SetAffiliateCap>, max_affiliate_bps: u16) -> Result<()> {
+    require!(max_affiliate_bps <= MAX_AFFILIATE_BPS, AgentFactoryError::AffiliateFeeTooHigh);
+
+    ctx.accounts.x402_config.max_affiliate_bps = max_affiliate_bps;
+
+    trace!("Affiliate cap set!");
+    trace!("Max affiliate share: {} bps", max_affiliate_bps);
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use bonding_curve_math::{CREATOR_FEE_BPS, PLATFORM_FEE_BPS};
+use crate::errors::AgentFactoryError;
+use crate::events::AgentFeeOverrideEvent;
+use crate::state::{Agent, AgentFactory, CreatorFeeMode};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetAgentFeeOverride<'info> {
+    /// Factory singleton
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    /// Factory authority (wallet, multisig or DAO PDA signer)
+    pub authority: Signer<'info>,
+}
+
+/// Set the platform and creator fees (bps) charged on an agent's trades,
+/// e.g. a zero platform fee weekend for a featured agent. Overrides can only
+/// lower the standard fees, so they apply immediately rather than through
+/// the timelock; setting the standard fees again ends the override. A
+/// `Waived` agent's creator fee stays zero.
+pub fn handler(ctx: Context<SetAgentFeeOverride>, platform_fee_bps: u16, creator_fee_bps: u16) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    require!(
+        platform_fee_bps as u64 <= PLATFORM_FEE_BPS && creator_fee_bps as u64 <= CREATOR_FEE_BPS,
+        AgentFactoryError::InvalidFeeOverride
+    );
+    require!(
+        agent.creator_fee_mode != CreatorFeeMode::Waived || creator_fee_bps == 0,
+        AgentFactoryError::InvalidFeeOverride
+    );
+
+    agent.bonding_curve.platform_fee_bps = platform_fee_bps;
+    agent.bonding_curve.creator_fee_bps = creator_fee_bps;
+
+    trace!("Agent fee override set!");
+    trace!("Agent: {}", agent.key());
+    trace!("Platform fee: {} bps, creator fee: {} bps", platform_fee_bps, creator_fee_bps);
+
+    emit_cpi!(AgentFeeOverrideEvent {
+        agent: agent.key(),
+        authority: ctx.accounts.authority.key(),
+        platform_fee_bps,
+        creator_fee_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
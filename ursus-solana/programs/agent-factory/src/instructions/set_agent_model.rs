@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, AgentFactory};
+
+#[derive(Accounts)]
+pub struct SetAgentModel<'info> {
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Switch the model an agent claims to run on, which must be on the
+/// factory allowlist like at creation
+pub fn handler(ctx: Context<SetAgentModel>, model: &str) -> Result<()> {
+    require!(ctx.accounts.factory.is_model_allowed(model), AgentFactoryError::ModelNotAllowed);
+
+    let agent = &mut ctx.accounts.agent;
+    trace!("Agent model: {} -> {}", agent.model, model);
+    agent.model = model.to_string();
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, X402Config, MAX_CHARGEBACK_WINDOW_SECS};
+
+#[derive(Accounts)]
+pub struct SetChargebackWindow<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Set how long after settlement the agent's payments can be disputed;
+/// 0 turns chargebacks off, so payments settle final. Payments keep the
+/// window they settled under.
+pub fn handler(ctx: Context<SetChargebackWindow>, chargeback_window_secs: u64) -> Result<()> {
+    require!(
+        chargeback_window_secs <= MAX_CHARGEBACK_WINDOW_SECS,
+        AgentFactoryError::InvalidChargebackWindow
+    );
+
+    ctx.accounts.x402_config.chargeback_window_secs = chargeback_window_secs;
+
+    trace!("Chargeback window set!");
+    trace!("Window: {}s", chargeback_window_secs);
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{AgentFactory, MAX_CREATION_WINDOW_SECS};
+
+#[derive(Accounts)]
+pub struct SetCreationRateLimit<'info> {
+    /// Factory singleton
+    #[account(
+        mut,
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Factory authority (wallet, multisig or DAO PDA signer)
+    pub authority: Signer<'info>,
+}
+
+/// Cap agent creations at `max_creations_per_window` per `window_secs`
+/// (0 = no limit). Applies immediately rather than through the timelock so
+/// the authority can throttle a spam wave while it is happening; the current
+/// window's count is kept.
+pub fn handler(
+    ctx: Context<SetCreationRateLimit>,
+    max_creations_per_window: u32,
+    window_secs: i64,
+) -> Result<()> {
+    require!(
+        window_secs > 0 && window_secs <= MAX_CREATION_WINDOW_SECS,
+        AgentFactoryError::InvalidCreationRateLimit
+    );
+
+    let factory = &mut ctx.accounts.factory;
+    factory.max_creations_per_window = max_creations_per_window;
+    factory.creation_window_secs = window_secs;
+
+    trace!("Creation rate limit set!");
+    trace!("Max creations: {} per {} secs", max_creations_per_window, window_secs);
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, MAX_CREATOR_ROYALTY_BPS};
+
+#[derive(Accounts)]
+pub struct SetCreatorRoyalty<'info> {
+    #[account(
+        mut,
+        has_one = original_creator
+    )]
+    pub agent: Account<'info, Agent>,
+
+    pub original_creator: Signer<'info>,
+
+    /// Original creator's account with the ownership NFT, while they still
+    /// hold it
+    pub original_creator_ownership_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Set the share of creator fees the original creator keeps once the agent
+/// changes hands. While the original creator holds the ownership NFT it can
+/// be set freely up to the cap; afterwards it can only be lowered, so buyers
+/// keep the terms they bought.
+pub fn handler(ctx: Context<SetCreatorRoyalty>, royalty_bps: u16) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let holds_nft = ctx
+        .accounts
+        .original_creator_ownership_account
+        .as_ref()
+        .is_some_and(|account| agent.is_owned_by(account, &agent.original_creator));
+    require!(royalty_bps <= MAX_CREATOR_ROYALTY_BPS, AgentFactoryError::InvalidRoyalty);
+    require!(
+        holds_nft || royalty_bps <= agent.royalty_bps,
+        AgentFactoryError::InvalidRoyalty
+    );
+
+    agent.royalty_bps = royalty_bps;
+
+    trace!("Creator royalty set!");
+    trace!("Royalty: {} bps", royalty_bps);
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{AgentFactory, CreatorStats};
+
+#[derive(Accounts)]
+pub struct SetCreatorStanding<'info> {
+    /// Factory singleton
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// CHECK: Creator wallet being flagged
+    pub creator: AccountInfo<'info>,
+
+    /// Creator's track record: `["creator_stats", creator]`
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + CreatorStats::INIT_SPACE,
+        seeds = [b"creator_stats", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_stats: Account<'info, CreatorStats>,
+
+    /// Factory authority (wallet, multisig or DAO PDA signer)
+    pub authority: Signer<'info>,
+
+    /// Pays the stats' rent if they don't exist yet. Must be the authority
+    /// unless `factory.admin_is_program` is set.
+    #[account(
+        mut,
+        constraint = factory.admin_is_program || payer.key() == authority.key() @ AgentFactoryError::InvalidAdminAction
+    )]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Record a creator's rugged agents and blacklist status; both surcharge
+/// their future creation fees
+pub fn handler(
+    ctx: Context<SetCreatorStanding>,
+    rugged_agents: u16,
+    is_blacklisted: bool,
+) -> Result<()> {
+    let creator_stats = &mut ctx.accounts.creator_stats;
+    if creator_stats.creator == Pubkey::default() {
+        creator_stats.creator = ctx.accounts.creator.key();
+        creator_stats.bump = ctx.bumps.creator_stats;
+    }
+    creator_stats.rugged_agents = rugged_agents;
+    creator_stats.is_blacklisted = is_blacklisted;
+
+    trace!("Creator standing updated!");
+    trace!("Creator: {}", creator_stats.creator);
+    trace!("Rugged agents: {}, blacklisted: {}", rugged_agents, is_blacklisted);
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::Agent;
+
+#[derive(Accounts)]
+pub struct SetGraduationBurn<'info> {
+    #[account(
+        mut,
+        constraint = !agent.is_graduated @ AgentFactoryError::AlreadyGraduated
+    )]
+    pub agent: Account<'info, Agent>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Choose whether the unsold curve tokens are burned at graduation instead
+/// of seeding the pool. Graduation is permissionless, so the choice is
+/// stored on the agent rather than left to whoever graduates it.
+pub fn handler(ctx: Context<SetGraduationBurn>, burn_remaining: bool) -> Result<()> {
+    ctx.accounts.agent.burn_remaining_at_graduation = burn_remaining;
+
+    trace!("Burn unsold curve tokens at graduation: {}", burn_remaining);
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, MIN_MAX_BUY_BPS};
+
+#[derive(Accounts)]
+pub struct SetMaxBuy<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Cap each buy at `max_buy_bps_of_reserves` of the curve tokens left, so a
+/// single transaction can't take most of the curve and move the price at
+/// will (0 = no cap)
+pub fn handler(ctx: Context<SetMaxBuy>, max_buy_bps_of_reserves: u16) -> Result<()> {
+    require!(
+        max_buy_bps_of_reserves == 0
+            || (max_buy_bps_of_reserves >= MIN_MAX_BUY_BPS
+                && max_buy_bps_of_reserves as u64 <= BPS_DENOMINATOR),
+        AgentFactoryError::InvalidMaxBuy
+    );
+    ctx.accounts.agent.max_buy_bps_of_reserves = max_buy_bps_of_reserves;
+
+    trace!("Max buy: {} bps of remaining curve tokens", max_buy_bps_of_reserves);
+
+    Ok(())
+}
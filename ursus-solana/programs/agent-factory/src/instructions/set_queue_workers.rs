@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, WorkQueue, MAX_QUEUE_WORKERS};
+
+#[derive(Accounts)]
+pub struct SetQueueWorkers<'info> {
+    pub agent: Account<'info, Agent>,
+
+    /// Created on first use
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + WorkQueue::INIT_SPACE,
+        seeds = [b"work_queue", agent.key().as_ref()],
+        bump
+    )]
+    pub queue: Account<'info, WorkQueue>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Replace the workers allowed to serve the agent's work queue, opening the
+/// queue if needed. Tasks already claimed stay with their worker until the
+/// lease ends.
+pub fn handler(ctx: Context<SetQueueWorkers>, workers: Vec<Pubkey>) -> Result<()> {
+    require!(
+        workers.len() <= MAX_QUEUE_WORKERS
+            && workers.iter().all(|w| *w != Pubkey::default())
+            && workers.iter().enumerate().all(|(i, w)| !workers[..i].contains(w)),
+        AgentFactoryError::InvalidQueueWorkers
+    );
+
+    let queue = &mut ctx.accounts.queue;
+    queue.agent = ctx.accounts.agent.key();
+    queue.workers = workers;
+    queue.bump = ctx.bumps.queue;
+
+    trace!("Queue workers set!");
+    trace!("Agent: {}, Workers: {}", queue.agent, queue.workers.len());
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentFactory, QUOTE_RATE_UNIT};
+
+#[derive(Accounts)]
+pub struct SetQuoteLamportRate<'info> {
+    /// Factory singleton
+    #[account(
+        mut,
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Factory authority (wallet, multisig or DAO PDA signer)
+    pub authority: Signer<'info>,
+}
+
+/// Value `QUOTE_RATE_UNIT` base units of the quote mint at `lamports` when
+/// disputes and insurance cap claims and coverage (0 = quote payments can't
+/// be disputed or insured). Applies immediately rather than through the
+/// timelock so the rate can follow the market.
+pub fn handler(ctx: Context<SetQuoteLamportRate>, lamports: u64) -> Result<()> {
+    let factory = &mut ctx.accounts.factory;
+    factory.quote_lamport_rate = lamports;
+
+    trace!("Quote lamport rate set!");
+    trace!("{} base units of {} = {} lamports", QUOTE_RATE_UNIT, factory.quote_mint, lamports);
+
+    Ok(())
+}
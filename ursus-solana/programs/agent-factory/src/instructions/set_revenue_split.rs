@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer as TokenTransfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{
+    Agent, DaoTreasury, RevenueShare, RevenueSplit, X402Config, MAX_REVENUE_SPLIT_BPS,
+    MAX_REVENUE_SPLIT_RECIPIENTS,
+};
+
+#[derive(Accounts)]
+pub struct SetRevenueSplit<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + RevenueSplit::INIT_SPACE,
+        seeds = [b"revenue_split", agent.key().as_ref()],
+        bump
+    )]
+    pub revenue_split: Account<'info, RevenueSplit>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Route `bps[i]` of every x402 payment to the i-th worker agent's DAO
+/// treasury, replacing any previous split. The worker agents' DAO treasuries
+/// are passed as remaining accounts, in the same order as `bps`; an empty
+/// `bps` stops routing.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetRevenueSplit<'info>>,
+    bps: &[u16],
+) -> Result<()> {
+    require!(bps.len() <= MAX_REVENUE_SPLIT_RECIPIENTS, AgentFactoryError::InvalidRevenueSplit);
+    require!(
+        ctx.remaining_accounts.len() == bps.len(),
+        AgentFactoryError::InvalidRevenueSplitAccounts
+    );
+
+    let agent_key = ctx.accounts.agent.key();
+    let mut shares: Vec<RevenueShare> = Vec::with_capacity(bps.len());
+    for (info, &bps) in ctx.remaining_accounts.iter().zip(bps) {
+        let treasury = Account::<DaoTreasury>::try_from(info)?;
+        require!(bps > 0, AgentFactoryError::InvalidRevenueSplit);
+        require!(
+            treasury.agent != agent_key && shares.iter().all(|s| s.agent != treasury.agent),
+            AgentFactoryError::InvalidRevenueSplit
+        );
+        shares.push(RevenueShare {
+            agent: treasury.agent,
+            treasury: treasury.key(),
+            bps,
+        });
+    }
+    let total_bps = shares.iter().map(|s| s.bps as u32).sum::<u32>();
+    require!(total_bps <= MAX_REVENUE_SPLIT_BPS as u32, AgentFactoryError::InvalidRevenueSplit);
+
+    let revenue_split = &mut ctx.accounts.revenue_split;
+    revenue_split.agent = agent_key;
+    revenue_split.shares = shares;
+    revenue_split.bump = ctx.bumps.revenue_split;
+    ctx.accounts.x402_config.revenue_split_bps = total_bps as u16;
+
+    trace!("Revenue split set!");
+    trace!("Worker agents: {}, Total share: {} bps", revenue_split.shares.len(), total_bps);
+
+    Ok(())
+}
+
+/// Pay each worker agent its share of `amount` out of `from`, into the
+/// treasury token accounts passed as `treasury_accounts` in share order. The
+/// split must be passed whenever the provider has one; returns the total
+/// routed, which the provider's recipient doesn't get.
+#[allow(clippy::too_many_arguments)]
+pub fn route_revenue<'info>(
+    x402_config: &X402Config,
+    revenue_split: Option<&mut Account<'info, RevenueSplit>>,
+    treasury_accounts: &'info [AccountInfo<'info>],
+    amount: u64,
+    from: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    token_program: &Program<'info, Token>,
+) -> Result<u64> {
+    let Some(revenue_split) = revenue_split else {
+        require!(x402_config.revenue_split_bps == 0, AgentFactoryError::RevenueSplitRequired);
+        return Ok(0);
+    };
+    require!(
+        treasury_accounts.len() == revenue_split.shares.len(),
+        AgentFactoryError::InvalidRevenueSplitAccounts
+    );
+
+    let mut routed: u64 = 0;
+    for (share, info) in revenue_split.shares.iter().zip(treasury_accounts) {
+        let treasury_account = Account::<TokenAccount>::try_from(info)?;
+        require_keys_eq!(
+            treasury_account.owner,
+            share.treasury,
+            AgentFactoryError::InvalidRevenueSplitAccounts
+        );
+
+        let share_amount = share.of(amount);
+        if share_amount == 0 {
+            continue;
+        }
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TokenTransfer {
+                    from: from.clone(),
+                    to: treasury_account.to_account_info(),
+                    authority: authority.clone(),
+                },
+                signer_seeds,
+            ),
+            share_amount,
+        )?;
+        routed += share_amount;
+    }
+
+    revenue_split.total_routed = revenue_split.total_routed
+        .checked_add(routed)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    Ok(routed)
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::state::Agent;
+use crate::errors::AgentFactoryError;
+
+#[derive(Accounts)]
+pub struct SetSameSlotProtection<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Turn same-slot sell protection on or off. While on, no wallet can sell
+/// in a slot that already has a buy, so a buy and sell can't be bundled
+/// around another trader's large buy, even with the tokens moved to another
+/// wallet in between.
+pub fn handler(ctx: Context<SetSameSlotProtection>, enabled: bool) -> Result<()> {
+    ctx.accounts.agent.same_slot_protection = enabled;
+
+    trace!("Same-slot protection {}", if enabled { "enabled" } else { "disabled" });
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, X402Config};
+
+#[derive(Accounts)]
+pub struct SetTrialDeposit<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Set the refundable deposit callers escrow to open a trial session with
+/// the agent's free tier; 0 stops new trials. Sessions last the service
+/// timeout, so one must be set, and open sessions keep their deposit.
+pub fn handler(ctx: Context<SetTrialDeposit>, trial_deposit: u64) -> Result<()> {
+    let x402_config = &mut ctx.accounts.x402_config;
+    require!(
+        trial_deposit == 0
+            || (x402_config.service_timeout_seconds > 0
+                && (x402_config.max_payment_amount == 0 || trial_deposit <= x402_config.max_payment_amount)),
+        AgentFactoryError::InvalidTrialDeposit
+    );
+
+    x402_config.trial_deposit = trial_deposit;
+
+    trace!("Trial deposit set!");
+    trace!("Deposit: {}", trial_deposit);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::state::{Agent, MeterUnit, X402Config};
+use crate::errors::AgentFactoryError;
+
+#[derive(Accounts)]
+pub struct SetX402Metering<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Bill the agent's services by usage: `unit_price` per 1K LLM tokens or per
+/// compute unit, charged against an escrowed budget when the creator reports
+/// usage. A zero price returns to flat pricing; payments already escrowed
+/// keep the price they were made at.
+pub fn handler(ctx: Context<SetX402Metering>, meter_unit: MeterUnit, unit_price: u64) -> Result<()> {
+    let x402_config = &mut ctx.accounts.x402_config;
+    x402_config.meter_unit = meter_unit;
+    x402_config.unit_price = unit_price;
+
+    trace!("X402 metering set!");
+    trace!("Unit: {:?}, Price: {}", meter_unit, unit_price);
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, X402Config, MAX_PRICE_NOTICE_SECS};
+
+#[derive(Accounts)]
+pub struct SetX402PriceNotice<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Require `price_notice_secs` of notice before an increase of the minimum
+/// payment amount takes effect, so consumers with open subscriptions or
+/// allowances aren't repriced by surprise. Raising the notice applies at
+/// once; lowering it only once the current notice has run out.
+pub fn handler(ctx: Context<SetX402PriceNotice>, price_notice_secs: i64) -> Result<()> {
+    require!(
+        (0..=MAX_PRICE_NOTICE_SECS).contains(&price_notice_secs),
+        AgentFactoryError::InvalidPriceNotice
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let x402_config = &mut ctx.accounts.x402_config;
+    let notice = x402_config.price_notice(now);
+    if price_notice_secs < notice {
+        x402_config.previous_price_notice_secs = notice;
+        x402_config.previous_notice_valid_until = now + notice;
+    }
+    x402_config.price_notice_secs = price_notice_secs;
+
+    trace!("X402 price notice set!");
+    trace!("Notice: {}s, in force: {}s", price_notice_secs, x402_config.price_notice(now));
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{spl_token::native_mint, Mint, Token, TokenAccount};
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, X402Config};
+
+#[derive(Accounts)]
+pub struct SetX402TopUp<'info> {
+    #[account(mut)]
+    pub agent: Box<Account<'info, Agent>>,
+
+    #[account(
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    /// Agent's x402 treasury, a wrapped SOL account the agent pays other
+    /// agents from
+    #[account(
+        init_if_needed,
+        payer = creator,
+        token::mint = native_mint,
+        token::authority = x402_config,
+        seeds = [b"x402_treasury", agent.key().as_ref()],
+        bump
+    )]
+    pub x402_treasury: Box<Account<'info, TokenAccount>>,
+
+    #[account(address = native_mint::ID)]
+    pub native_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Top up `x402_top_up_bps` of every creator fee into the agent's x402
+/// treasury, out of the creator's own cut, so an agent paying other agents
+/// keeps funded from its trading fees. 0 stops topping up; fees already set
+/// aside are still collected.
+pub fn handler(ctx: Context<SetX402TopUp>, x402_top_up_bps: u16) -> Result<()> {
+    require!(
+        x402_top_up_bps as u64 <= BPS_DENOMINATOR,
+        AgentFactoryError::InvalidTopUpBps
+    );
+    ctx.accounts.agent.x402_top_up_bps = x402_top_up_bps;
+
+    trace!("X402 top-up set!");
+    trace!("Share: {} bps, Treasury: {}", x402_top_up_bps, ctx.accounts.x402_treasury.key());
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::state::{Agent, X402Config};
+use crate::errors::AgentFactoryError;
+
+#[derive(Accounts)]
+pub struct SetX402Verifier<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Set the signer whose attestation releases escrowed payments, typically a
+/// Switchboard Function's enclave signer. Pending payments move to the new
+/// verifier; the default key turns escrowed payments off, leaving pending
+/// ones to be refunded after the timeout.
+pub fn handler(ctx: Context<SetX402Verifier>, verifier: Pubkey) -> Result<()> {
+    ctx.accounts.x402_config.attestation_verifier = verifier;
+
+    trace!("X402 attestation verifier set!");
+    trace!("Verifier: {}", verifier);
+
+    Ok(())
+}
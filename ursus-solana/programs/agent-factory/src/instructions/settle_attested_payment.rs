@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+use bonding_curve_math::BPS_DENOMINATOR;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer as TokenTransfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{PaymentStatus, RevenueSplit, X402Config, X402PaymentRecord};
+use super::set_revenue_split::route_revenue;
+
+#[derive(Accounts)]
+pub struct SettleAttestedPayment<'info> {
+    #[account(
+        mut,
+        seeds = [b"x402_config", payment_record.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        constraint = payment_record.status == PaymentStatus::Pending @ AgentFactoryError::PaymentNotPending,
+        constraint = !payment_record.is_metered() @ AgentFactoryError::PaymentIsMetered
+    )]
+    pub payment_record: Box<Account<'info, X402PaymentRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"payment_escrow", payment_record.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Recipient's USDC token account, paid when the check passed
+    #[account(
+        mut,
+        constraint = x402_config.accepts_recipient(&recipient_token_account.owner, Clock::get()?.unix_timestamp) @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payer's USDC token account, refunded when the check failed
+    #[account(
+        mut,
+        constraint = payer_token_account.owner == payment_record.payer @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Payer, receives the escrow account's rent
+    #[account(
+        mut,
+        address = payment_record.payer
+    )]
+    pub payer: AccountInfo<'info>,
+
+    #[account(
+        constraint = verifier.key() == x402_config.attestation_verifier @ AgentFactoryError::NotAttestationVerifier
+    )]
+    pub verifier: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Agent's revenue split, required while it routes revenue to other
+    /// agents; the worker treasuries' token accounts follow as remaining
+    /// accounts, in share order
+    #[account(
+        mut,
+        seeds = [b"revenue_split", payment_record.agent.as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Box<Account<'info, RevenueSplit>>>,
+}
+
+/// Settle an escrowed payment with the verifier's attestation of the
+/// service output (`attestation_hash`): `refund_bps` of it is refunded to
+/// the payer and the rest released to the recipient, both in this settlement.
+/// A passed check refunds nothing, a failed one everything.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleAttestedPayment<'info>>,
+    refund_bps: u16,
+    attestation_hash: [u8; 32],
+) -> Result<()> {
+    require!(refund_bps as u64 <= BPS_DENOMINATOR, AgentFactoryError::InvalidRefundBps);
+    let settled = (refund_bps as u64) < BPS_DENOMINATOR;
+
+    // A frozen agent can't collect, but full refunds still go through
+    require!(!settled || !ctx.accounts.x402_config.frozen, AgentFactoryError::X402Frozen);
+
+    let amount = ctx.accounts.escrow_token_account.amount;
+    let refund = (amount as u128 * refund_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    let provider_share = amount - refund;
+
+    let routed = if settled {
+        route_escrowed_revenue(
+            &ctx.accounts.x402_config,
+            ctx.accounts.revenue_split.as_deref_mut(),
+            ctx.remaining_accounts,
+            &ctx.accounts.escrow_token_account,
+            provider_share,
+            &ctx.accounts.token_program,
+        )?
+    } else {
+        0
+    };
+    if routed > 0 {
+        ctx.accounts.escrow_token_account.reload()?;
+    }
+    split_escrow(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        provider_share - routed,
+        &ctx.accounts.payer_token_account.to_account_info(),
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if settled {
+        ctx.accounts.x402_config.record_payment(provider_share)?;
+        ctx.accounts.payment_record.dispute_deadline = ctx.accounts.x402_config.dispute_deadline(now);
+    }
+    let payment_record = &mut ctx.accounts.payment_record;
+    payment_record.status = if settled { PaymentStatus::Settled } else { PaymentStatus::Failed };
+    payment_record.attestation_hash = attestation_hash;
+    payment_record.refunded_amount = refund;
+
+    trace!("Escrowed payment attested!");
+    trace!("Amount: {}, Refunded: {}", amount, refund);
+
+    Ok(())
+}
+
+/// Pay the worker agents' shares of `amount` out of an escrow, signed by
+/// the x402 config
+pub fn route_escrowed_revenue<'info>(
+    x402_config: &Account<'info, X402Config>,
+    revenue_split: Option<&mut Account<'info, RevenueSplit>>,
+    treasury_accounts: &'info [AccountInfo<'info>],
+    escrow: &Account<'info, TokenAccount>,
+    amount: u64,
+    token_program: &Program<'info, Token>,
+) -> Result<u64> {
+    let bump = [x402_config.bump];
+    let seeds = &[b"x402_config".as_ref(), x402_config.agent.as_ref(), &bump];
+    route_revenue(
+        x402_config,
+        revenue_split,
+        treasury_accounts,
+        amount,
+        &escrow.to_account_info(),
+        &x402_config.to_account_info(),
+        &[&seeds[..]],
+        token_program,
+    )
+}
+
+/// Transfer an escrow's whole balance to `to` and close it to the payer
+pub fn release_escrow<'info>(
+    x402_config: &Account<'info, X402Config>,
+    escrow: &Account<'info, TokenAccount>,
+    to: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    split_escrow(x402_config, escrow, to, escrow.amount, to, payer, token_program)
+}
+
+/// Transfer `amount` of an escrow to `to`, the rest to `rest_to`, and close
+/// it to the payer
+pub fn split_escrow<'info>(
+    x402_config: &Account<'info, X402Config>,
+    escrow: &Account<'info, TokenAccount>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+    rest_to: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let bump = [x402_config.bump];
+    let seeds = &[b"x402_config".as_ref(), x402_config.agent.as_ref(), &bump];
+    let signer_seeds = &[&seeds[..]];
+
+    for (destination, transfer_amount) in [(to, amount), (rest_to, escrow.amount - amount)] {
+        if transfer_amount == 0 {
+            continue;
+        }
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TokenTransfer {
+                    from: escrow.to_account_info(),
+                    to: destination.clone(),
+                    authority: x402_config.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            transfer_amount,
+        )?;
+    }
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow.to_account_info(),
+            destination: payer.clone(),
+            authority: x402_config.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
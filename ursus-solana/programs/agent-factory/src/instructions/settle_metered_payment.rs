@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, PaymentStatus, RevenueSplit, X402Config, X402PaymentRecord};
+use super::settle_attested_payment::{route_escrowed_revenue, split_escrow};
+
+#[derive(Accounts)]
+pub struct SettleMeteredPayment<'info> {
+    #[account(
+        address = payment_record.agent
+    )]
+    pub agent: Box<Account<'info, Agent>>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", payment_record.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        constraint = payment_record.status == PaymentStatus::Pending @ AgentFactoryError::PaymentNotPending,
+        constraint = payment_record.is_metered() @ AgentFactoryError::PaymentNotMetered
+    )]
+    pub payment_record: Box<Account<'info, X402PaymentRecord>>,
+
+    #[account(
+        mut,
+        seeds = [b"payment_escrow", payment_record.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Recipient's USDC token account, paid the usage charge
+    #[account(
+        mut,
+        constraint = x402_config.accepts_recipient(&recipient_token_account.owner, Clock::get()?.unix_timestamp) @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payer's USDC token account, refunded the unused budget
+    #[account(
+        mut,
+        constraint = payer_token_account.owner == payment_record.payer @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Payer, receives the escrow account's rent
+    #[account(
+        mut,
+        address = payment_record.payer
+    )]
+    pub payer: AccountInfo<'info>,
+
+    /// Agent creator operating the service and reporting its usage
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Agent's revenue split, required while it routes revenue to other
+    /// agents; the worker treasuries' token accounts follow as remaining
+    /// accounts, in share order
+    #[account(
+        mut,
+        seeds = [b"revenue_split", payment_record.agent.as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Box<Account<'info, RevenueSplit>>>,
+}
+
+/// Settle a metered payment with the operator's usage report: `units` are
+/// charged at the price locked in at payment, capped at the escrowed budget,
+/// and the rest is refunded. `usage_hash` commits to the off-chain usage
+/// log; payers contest inflated reports through `submit_evidence`.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleMeteredPayment<'info>>,
+    units: u64,
+    usage_hash: [u8; 32],
+) -> Result<()> {
+    // A frozen agent can't collect; the payer is refunded once the service
+    // times out
+    require!(!ctx.accounts.x402_config.frozen, AgentFactoryError::X402Frozen);
+
+    let charge = ctx.accounts.payment_record
+        .metered_charge(units)
+        .ok_or(AgentFactoryError::MathOverflow)?
+        .min(ctx.accounts.escrow_token_account.amount);
+
+    let routed = route_escrowed_revenue(
+        &ctx.accounts.x402_config,
+        ctx.accounts.revenue_split.as_deref_mut(),
+        ctx.remaining_accounts,
+        &ctx.accounts.escrow_token_account,
+        charge,
+        &ctx.accounts.token_program,
+    )?;
+    if routed > 0 {
+        ctx.accounts.escrow_token_account.reload()?;
+    }
+
+    split_escrow(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        charge - routed,
+        &ctx.accounts.payer_token_account.to_account_info(),
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+    )?;
+    ctx.accounts.x402_config.record_payment(charge)?;
+
+    let payment_record = &mut ctx.accounts.payment_record;
+    payment_record.amount = charge;
+    payment_record.metered_units = units;
+    payment_record.attestation_hash = usage_hash;
+    payment_record.status = PaymentStatus::Settled;
+    payment_record.dispute_deadline = ctx.accounts.x402_config.dispute_deadline(Clock::get()?.unix_timestamp);
+
+    trace!("Metered payment settled!");
+    trace!("Units: {}, Charge: {}", units, charge);
+
+    Ok(())
+}
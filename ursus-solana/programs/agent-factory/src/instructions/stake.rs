@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, AgentVault, StakePosition, StakingPool};
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", agent.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Box<Account<'info, StakingPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", staking_pool.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [b"stake", staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        token::mint = agent.mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Move the creator fee share held in the agent's creator fee vault into
+/// the pool and stream rewards up to `now`. Every staking instruction starts
+/// with this.
+pub fn collect_and_accrue<'info>(
+    agent: &mut Account<'info, Agent>,
+    creator_fee_vault: &AccountInfo<'info>,
+    pool: &mut Account<'info, StakingPool>,
+    system_program: &AccountInfo<'info>,
+    now: i64,
+) -> Result<()> {
+    let pending = agent.pending_staking_rewards;
+    if pending > 0 {
+        AgentVault::CreatorFees.pay(
+            &agent.key(),
+            agent,
+            creator_fee_vault,
+            &pool.to_account_info(),
+            system_program,
+            pending,
+        )?;
+        agent.pending_staking_rewards = 0;
+    }
+
+    // Stream with the old balance before queueing the new rewards
+    pool.accrue(now)?;
+    pool.deposit_rewards(pending)
+}
+
+/// Stake agent tokens into the pool vault
+pub fn handler(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidStakeAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    collect_and_accrue(
+        &mut ctx.accounts.agent,
+        &ctx.accounts.creator_fee_vault,
+        &mut ctx.accounts.staking_pool,
+        &ctx.accounts.system_program,
+        now,
+    )?;
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    let position = &mut ctx.accounts.position;
+
+    // First stake from this wallet
+    if position.pool == Pubkey::default() {
+        position.pool = pool.key();
+        position.owner = ctx.accounts.owner.key();
+        position.reward_per_token_paid = pool.reward_per_token;
+        position.bump = ctx.bumps.position;
+    }
+
+    position.settle(pool)?;
+    let old_amount = position.amount;
+    position.amount = position.amount
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    pool.total_staked = pool.total_staked
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    // Tokens added to a locked position join the lock
+    pool.update_ve(now, old_amount, position.lock_end, position.amount, position.lock_end)?;
+    position.write_checkpoint(now);
+
+    trace!("Tokens staked!");
+    trace!("Amount: {}", amount);
+    trace!("Position: {}", position.amount);
+    trace!("Total staked: {}", pool.total_staked);
+
+    Ok(())
+}
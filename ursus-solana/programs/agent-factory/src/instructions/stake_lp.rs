@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{LiquidityMining, LiquidityMiningPosition};
+
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    #[account(
+        mut,
+        seeds = [b"liquidity_mining", mining.agent.as_ref()],
+        bump = mining.bump
+    )]
+    pub mining: Box<Account<'info, LiquidityMining>>,
+
+    #[account(
+        mut,
+        seeds = [b"lm_lp_vault", mining.key().as_ref()],
+        bump
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + LiquidityMiningPosition::INIT_SPACE,
+        seeds = [b"lm_position", mining.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, LiquidityMiningPosition>,
+
+    #[account(
+        mut,
+        token::mint = mining.lp_mint,
+        token::authority = owner
+    )]
+    pub owner_lp_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Stake LP tokens into the mining vault
+pub fn handler(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidStakeAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.mining.accrue(now)?;
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.owner_lp_account.to_account_info(),
+            to: ctx.accounts.lp_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        },
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let mining = &mut ctx.accounts.mining;
+    let position = &mut ctx.accounts.position;
+
+    // First stake from this wallet
+    if position.mining == Pubkey::default() {
+        position.mining = mining.key();
+        position.owner = ctx.accounts.owner.key();
+        position.reward_per_token_paid = mining.reward_per_token;
+        position.bump = ctx.bumps.position;
+    }
+
+    position.settle(mining)?;
+    position.amount = position.amount
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    mining.total_staked = mining.total_staked
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("LP tokens staked!");
+    trace!("Amount: {}", amount);
+    trace!("Position: {}", position.amount);
+    trace!("Total staked: {}", mining.total_staked);
+
+    Ok(())
+}
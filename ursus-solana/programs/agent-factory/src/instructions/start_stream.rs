@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, PaymentStream, X402Config, MAX_STREAM_DURATION_SECS};
+
+#[derive(Accounts)]
+#[instruction(rate_per_sec: u64, max_duration: u64, nonce: u64)]
+pub struct StartStream<'info> {
+    pub agent: Box<Account<'info, Agent>>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PaymentStream::INIT_SPACE,
+        seeds = [
+            b"payment_stream",
+            agent.key().as_ref(),
+            payer.key().as_ref(),
+            &nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    pub stream: Box<Account<'info, PaymentStream>>,
+
+    /// Holds the unwithdrawn deposit until it streams to the provider or is
+    /// refunded on cancel
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = x402_config,
+        seeds = [b"stream_escrow", stream.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payment token (e.g. USDC)
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Payer's USDC token account
+    #[account(mut)]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Start paying an agent `rate_per_sec` for up to `max_duration` seconds.
+/// The whole `rate_per_sec * max_duration` is locked in escrow now and
+/// checked against the agent's payment limits; `nonce` must be the agent's
+/// next x402 nonce.
+pub fn handler(
+    ctx: Context<StartStream>,
+    rate_per_sec: u64,
+    max_duration: u64,
+    nonce: u64,
+) -> Result<()> {
+    require!(
+        rate_per_sec > 0 && max_duration > 0 && max_duration <= MAX_STREAM_DURATION_SECS,
+        AgentFactoryError::InvalidStreamParams
+    );
+    let deposit = rate_per_sec
+        .checked_mul(max_duration)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    let x402_config = &mut ctx.accounts.x402_config;
+    x402_config.require_accepting_payments()?;
+    x402_config.validate_payment_amount(deposit)?;
+    require_eq!(nonce, x402_config.nonce + 1, AgentFactoryError::NonceMismatch);
+    x402_config.increment_nonce()?;
+
+    // Lock the whole deposit in escrow
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        TokenTransfer {
+            from: ctx.accounts.payer_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, deposit)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let stream = &mut ctx.accounts.stream;
+    stream.agent = ctx.accounts.agent.key();
+    stream.payer = ctx.accounts.payer.key();
+    stream.mint = ctx.accounts.mint.key();
+    stream.rate_per_sec = rate_per_sec;
+    stream.start_ts = now;
+    stream.end_ts = now + max_duration as i64;
+    stream.deposited = deposit;
+    stream.withdrawn = 0;
+    stream.refunded = 0;
+    stream.cancelled_at = 0;
+    stream.bump = ctx.bumps.stream;
+
+    trace!("Payment stream started!");
+    trace!("Rate: {}/s, Duration: {}s, Deposit: {}", rate_per_sec, max_duration, deposit);
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, AgentFactory, Dispute, DisputeStatus, ProviderBond, X402PaymentRecord};
+
+#[derive(Accounts)]
+pub struct SubmitEvidence<'info> {
+    /// Factory singleton, valuing the payment in lamports
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Disputed payment
+    pub payment_record: Account<'info, X402PaymentRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"provider_bond", payment_record.agent.as_ref()],
+        bump = provider_bond.bump
+    )]
+    pub provider_bond: Account<'info, ProviderBond>,
+
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + Dispute::INIT_SPACE,
+        seeds = [b"dispute", payment_record.key().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    /// Payer of the payment, or the creator of the agent that paid
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// Paying agent, for agent-to-agent payments
+    pub payer_agent: Option<Account<'info, Agent>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Whether `wallet` paid `payment_record`, directly or as the creator of the
+/// paying agent
+pub fn is_payment_payer(
+    payment_record: &X402PaymentRecord,
+    wallet: &Pubkey,
+    payer_agent: &Option<Account<Agent>>,
+) -> bool {
+    match payer_agent {
+        Some(payer_agent) => payer_agent.key() == payment_record.payer && payer_agent.creator == *wallet,
+        None => payment_record.payer == *wallet,
+    }
+}
+
+/// Dispute a payment to a bonded agent within its chargeback window,
+/// recording the hash of the evidence and the lamports claimed, at most the
+/// lamport value of what the provider kept of the payment. Locks the
+/// provider bond until the council resolves it.
+pub fn handler(ctx: Context<SubmitEvidence>, evidence_hash: [u8; 32], claimed_amount: u64) -> Result<()> {
+    let claimant = ctx.accounts.claimant.key();
+    require!(
+        is_payment_payer(&ctx.accounts.payment_record, &claimant, &ctx.accounts.payer_agent),
+        AgentFactoryError::NotPaymentPayer
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.payment_record.is_disputable(now),
+        AgentFactoryError::ChargebackWindowClosed
+    );
+
+    let payment_record = &ctx.accounts.payment_record;
+    let kept_value = ctx.accounts.factory
+        .lamport_value(
+            &payment_record.mint,
+            payment_record.amount.saturating_sub(payment_record.refunded_amount),
+        )
+        .ok_or(AgentFactoryError::PaymentMintNotValued)?;
+    require!(claimed_amount <= kept_value, AgentFactoryError::ClaimExceedsPayment);
+
+    let bond = &mut ctx.accounts.provider_bond;
+    bond.open_disputes = bond.open_disputes
+        .checked_add(1)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.payment_record = ctx.accounts.payment_record.key();
+    dispute.agent = ctx.accounts.payment_record.agent;
+    dispute.claimant = claimant;
+    dispute.evidence_hash = evidence_hash;
+    dispute.claimed_amount = claimed_amount;
+    dispute.status = DisputeStatus::Open;
+    dispute.awarded_amount = 0;
+    dispute.slashed_amount = 0;
+    dispute.votes = Vec::new();
+    dispute.created_at = now;
+    dispute.resolved_at = 0;
+    dispute.bump = ctx.bumps.dispute;
+
+    trace!("Dispute opened!");
+    trace!("Payment: {}", dispute.payment_record);
+    trace!("Claimant: {}", claimant);
+    trace!("Claimed: {}", claimed_amount);
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::state::{Agent, Job, MilestoneStatus};
+use crate::errors::AgentFactoryError;
+
+#[derive(Accounts)]
+pub struct SubmitMilestone<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        has_one = agent
+    )]
+    pub job: Box<Account<'info, Job>>,
+
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Box<Account<'info, TokenAccount>>,
+}
+
+/// Deliver milestone `index` of a job with the hash of its deliverable,
+/// starting the payer's review period
+pub fn handler(ctx: Context<SubmitMilestone>, index: u8, deliverable_hash: [u8; 32]) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let milestone = ctx.accounts.job.milestone_in(index, MilestoneStatus::Pending)?;
+    milestone.status = MilestoneStatus::Submitted;
+    milestone.deliverable_hash = deliverable_hash;
+    milestone.submitted_at = now;
+
+    trace!("Milestone submitted!");
+    trace!("Job: {}, Milestone: {}", ctx.accounts.job.key(), index);
+
+    Ok(())
+}
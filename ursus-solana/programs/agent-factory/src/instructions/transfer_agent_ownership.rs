@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::events::AgentOwnershipTransferredEvent;
+use crate::state::Agent;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferAgentOwnership<'info> {
+    #[account(
+        mut,
+        has_one = ownership_mint
+    )]
+    pub agent: Account<'info, Agent>,
+
+    pub ownership_mint: Account<'info, Mint>,
+
+    /// Creator's account holding the ownership NFT
+    #[account(
+        mut,
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// CHECK: Wallet taking over the agent
+    pub new_creator: AccountInfo<'info>,
+
+    /// New creator's account receiving the ownership NFT
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = ownership_mint,
+        associated_token::authority = new_creator
+    )]
+    pub new_creator_ownership_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Hand the agent to `new_creator`: the ownership NFT moves and the new
+/// holder is recorded as the creator in one step, so creator fees follow
+/// without a separate `claim_agent_ownership`.
+pub fn handler(ctx: Context<TransferAgentOwnership>) -> Result<()> {
+    let new_creator = ctx.accounts.new_creator.key();
+    require!(new_creator != ctx.accounts.creator.key(), AgentFactoryError::InvalidNewCreator);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.creator_ownership_account.to_account_info(),
+                to: ctx.accounts.new_creator_ownership_account.to_account_info(),
+                authority: ctx.accounts.creator.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+
+    let agent = &mut ctx.accounts.agent;
+    let previous_creator = std::mem::replace(&mut agent.creator, new_creator);
+
+    trace!("Agent ownership transferred!");
+    trace!("Agent: {}", agent.key());
+    trace!("Previous creator: {}", previous_creator);
+    trace!("New creator: {}", new_creator);
+
+    emit_cpi!(AgentOwnershipTransferredEvent {
+        agent: agent.key(),
+        previous_creator,
+        new_creator,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::TokenLock;
+
+#[derive(Accounts)]
+pub struct UnlockTokens<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"lock",
+            lock.agent.as_ref(),
+            owner.key().as_ref(),
+            &lock.lock_id.to_le_bytes()
+        ],
+        bump = lock.bump,
+        has_one = owner
+    )]
+    pub lock: Account<'info, TokenLock>,
+
+    #[account(
+        mut,
+        seeds = [b"lock_vault", lock.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraw the tokens of an expired lock. The vault and the lock account are
+/// closed to the owner.
+pub fn handler(ctx: Context<UnlockTokens>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let lock = &ctx.accounts.lock;
+    require!(lock.is_unlocked(now), AgentFactoryError::TokensStillLocked);
+
+    let agent_key = lock.agent;
+    let owner_key = lock.owner;
+    let lock_id_bytes = lock.lock_id.to_le_bytes();
+    let seeds = &[
+        b"lock",
+        agent_key.as_ref(),
+        owner_key.as_ref(),
+        lock_id_bytes.as_ref(),
+        &[lock.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Return the tokens
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.lock.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, ctx.accounts.vault.amount)?;
+
+    // Reclaim the vault rent
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.lock.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::close_account(cpi_ctx)?;
+
+    trace!("Tokens unlocked!");
+    trace!("Lock ID: {}", ctx.accounts.lock.lock_id);
+    trace!("Amount: {}", ctx.accounts.lock.amount);
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, StakePosition, StakingPool};
+use super::stake::collect_and_accrue;
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"staking_pool", agent.key().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Box<Account<'info, StakingPool>>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", staking_pool.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        token::mint = agent.mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// Agent's creator fee vault
+    #[account(
+        mut,
+        seeds = [b"creator_fee_vault", agent.key().as_ref()],
+        bump = agent.creator_fee_vault_bump
+    )]
+    pub creator_fee_vault: SystemAccount<'info>,
+}
+
+/// Withdraw staked tokens; earned rewards stay claimable
+pub fn handler(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidStakeAmount);
+    require!(
+        amount <= ctx.accounts.position.amount,
+        AgentFactoryError::InsufficientStake
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= ctx.accounts.position.lock_end, AgentFactoryError::StakeLocked);
+
+    collect_and_accrue(
+        &mut ctx.accounts.agent,
+        &ctx.accounts.creator_fee_vault,
+        &mut ctx.accounts.staking_pool,
+        &ctx.accounts.system_program,
+        now,
+    )?;
+    ctx.accounts.position.settle(&ctx.accounts.staking_pool)?;
+
+    // Return the tokens from the vault
+    let agent_key = ctx.accounts.agent.key();
+    let pool_bump = ctx.accounts.staking_pool.bump;
+    let seeds = &[
+        b"staking_pool",
+        agent_key.as_ref(),
+        &[pool_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.staking_pool.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    let position = &mut ctx.accounts.position;
+    position.amount -= amount;
+    pool.total_staked -= amount;
+    position.write_checkpoint(now);
+
+    trace!("Tokens unstaked!");
+    trace!("Amount: {}", amount);
+    trace!("Position: {}", position.amount);
+    trace!("Total staked: {}", pool.total_staked);
+
+    Ok(())
+}
@@ -1,9 +1,36 @@
 use anchor_lang::prelude::*;
-use crate::state::X402Config;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::events::X402ConfigUpdatedEvent;
+use crate::state::{Agent, X402Config};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(enabled: bool, min_payment_amount: u64, max_payment_amount: u64, service_timeout_seconds: u64)]
+pub struct UpdateX402<'info> {
+    #[account(mut)]
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", agent.key().as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Account<'info, X402Config>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Authority's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &authority.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
 
 /// Update X402 payment settings for an agent
 pub fn handler(
-    ctx: Context<crate::UpdateX402>,
+    ctx: Context<UpdateX402>,
     enabled: bool,
     min_payment_amount: u64,
     max_payment_amount: u64,
@@ -11,15 +38,32 @@ pub fn handler(
 ) -> Result<()> {
     let x402_config = &mut ctx.accounts.x402_config;
     let agent = &ctx.accounts.agent;
+    let now = Clock::get()?.unix_timestamp;
+    let mut event = X402ConfigUpdatedEvent {
+        agent: agent.key(),
+        authority: ctx.accounts.authority.key(),
+        old_enabled: x402_config.enabled,
+        new_enabled: enabled,
+        old_min_payment_amount: x402_config.current_min_payment_amount(now),
+        new_min_payment_amount: min_payment_amount,
+        old_max_payment_amount: x402_config.max_payment_amount,
+        new_max_payment_amount: max_payment_amount,
+        old_service_timeout_seconds: x402_config.service_timeout_seconds,
+        new_service_timeout_seconds: service_timeout_seconds,
+        min_payment_effective_at: now,
+        timestamp: now,
+    };
     
-    // Update settings
+    // Update settings; a higher minimum waits out the price notice
     x402_config.enabled = enabled;
-    x402_config.min_payment_amount = min_payment_amount;
+    event.min_payment_effective_at = x402_config.set_min_payment_amount(min_payment_amount, now)?;
     x402_config.max_payment_amount = max_payment_amount;
     x402_config.service_timeout_seconds = service_timeout_seconds;
     
-    msg!("X402 updated for agent: {}", agent.key());
-    msg!("Enabled: {}, Min: {}, Max: {}", enabled, min_payment_amount, max_payment_amount);
+    trace!("X402 updated for agent: {}", agent.key());
+    trace!("Enabled: {}, Min: {}, Max: {}", enabled, min_payment_amount, max_payment_amount);
+    
+    emit_cpi!(event);
     
     Ok(())
 }
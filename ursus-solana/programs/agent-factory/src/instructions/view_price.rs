@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+use crate::state::{Agent, PriceView};
+
+#[derive(Accounts)]
+pub struct ViewPrice<'info> {
+    pub agent: Account<'info, Agent>,
+}
+
+/// Return the agent's spot price, market cap and reserves, for programs
+/// that CPI for a price
+pub fn handler(ctx: Context<ViewPrice>) -> Result<PriceView> {
+    Ok(ctx.accounts.agent.price_view(Clock::get()?.unix_timestamp))
+}
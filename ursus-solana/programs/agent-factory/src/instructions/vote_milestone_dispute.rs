@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+use crate::state::{AgentFactory, ArbiterVote, Job, MilestoneStatus, RevenueSplit, X402Config};
+use super::release_milestone::record_job_payment;
+use super::settle_attested_payment::route_escrowed_revenue;
+use super::withdraw_streamed::pay_out_stream;
+
+#[derive(Accounts)]
+pub struct VoteMilestoneDispute<'info> {
+    /// Factory singleton holding the arbitration council
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        constraint = factory.is_arbiter(&arbiter.key()) @ AgentFactoryError::NotArbiter
+    )]
+    pub factory: Box<Account<'info, AgentFactory>>,
+
+    #[account(
+        mut,
+        seeds = [b"x402_config", job.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(mut)]
+    pub job: Box<Account<'info, Job>>,
+
+    #[account(
+        mut,
+        seeds = [b"job_escrow", job.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Recipient's USDC token account, paid the unrefunded share
+    #[account(
+        mut,
+        constraint = x402_config.accepts_recipient(&recipient_token_account.owner, Clock::get()?.unix_timestamp) @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Payer's USDC token account, refunded the awarded share
+    #[account(
+        mut,
+        constraint = payer_token_account.owner == job.payer @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Payer, receives the escrow account's rent once it's drained
+    #[account(
+        mut,
+        address = job.payer
+    )]
+    pub payer: AccountInfo<'info>,
+
+    /// Council member casting the vote
+    pub arbiter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Agent's revenue split, required while it routes revenue to other
+    /// agents; the worker treasuries' token accounts follow as remaining
+    /// accounts, in share order
+    #[account(
+        mut,
+        seeds = [b"revenue_split", job.agent.as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Box<Account<'info, RevenueSplit>>>,
+}
+
+/// Vote to refund `refund_bps` of a disputed milestone to the payer, the
+/// rest going to the provider (0 = dismiss the dispute). Once the council
+/// threshold of current members agrees on the same share the milestone
+/// settles.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, VoteMilestoneDispute<'info>>,
+    index: u8,
+    refund_bps: u16,
+) -> Result<()> {
+    require!(refund_bps as u64 <= BPS_DENOMINATOR, AgentFactoryError::InvalidRefundBps);
+    let factory = &ctx.accounts.factory;
+    let arbiter = ctx.accounts.arbiter.key();
+    let job = &mut ctx.accounts.job;
+    let milestone = *job.milestone_in(index, MilestoneStatus::Disputed)?;
+
+    require!(
+        !job.dispute_votes.iter().any(|v| v.arbiter == arbiter),
+        AgentFactoryError::ArbiterAlreadyVoted
+    );
+    // As for payment disputes, votes of removed members no longer count
+    job.dispute_votes.retain(|v| factory.is_arbiter(&v.arbiter));
+    job.dispute_votes.push(ArbiterVote { arbiter, slash_amount: refund_bps as u64 });
+
+    let agreeing = job.dispute_votes
+        .iter()
+        .filter(|v| v.slash_amount == refund_bps as u64)
+        .count();
+
+    trace!("Milestone dispute vote cast!");
+    trace!("Arbiter: {}, Refund: {} bps", arbiter, refund_bps);
+    trace!("Agreeing: {}/{}", agreeing, factory.arbiter_threshold);
+
+    if agreeing < factory.arbiter_threshold as usize {
+        return Ok(());
+    }
+
+    let refund = (milestone.amount as u128 * refund_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    let provider_share = milestone.amount - refund;
+
+    // A frozen agent can't collect, but full refunds still go through
+    require!(provider_share == 0 || !ctx.accounts.x402_config.frozen, AgentFactoryError::X402Frozen);
+
+    let routed = route_escrowed_revenue(
+        &ctx.accounts.x402_config,
+        ctx.accounts.revenue_split.as_deref_mut(),
+        ctx.remaining_accounts,
+        &ctx.accounts.escrow_token_account,
+        provider_share,
+        &ctx.accounts.token_program,
+    )?;
+    if routed > 0 {
+        ctx.accounts.escrow_token_account.reload()?;
+    }
+    pay_out_stream(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        provider_share - routed,
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+    )?;
+    // The refund is still in escrow, so it wasn't closed above
+    if refund > 0 {
+        ctx.accounts.escrow_token_account.reload()?;
+        pay_out_stream(
+            &ctx.accounts.x402_config,
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.payer_token_account.to_account_info(),
+            refund,
+            &ctx.accounts.payer,
+            &ctx.accounts.token_program,
+        )?;
+    }
+
+    let job = &mut ctx.accounts.job;
+    if provider_share > 0 {
+        record_job_payment(&mut ctx.accounts.x402_config, job, provider_share)?;
+    }
+    job.refunded = job.refunded
+        .checked_add(refund)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+    job.dispute_votes = Vec::new();
+    let milestone = &mut job.milestones[index as usize];
+    milestone.refunded = refund;
+    milestone.status = if provider_share == 0 { MilestoneStatus::Refunded } else { MilestoneStatus::Released };
+
+    trace!("Milestone dispute resolved!");
+    trace!("Status: {:?}, Refunded: {}/{}", milestone.status, refund, milestone.amount);
+
+    Ok(())
+}
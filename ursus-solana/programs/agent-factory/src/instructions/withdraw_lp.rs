@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{LiquidityMining, LiquidityMiningPosition};
+
+#[derive(Accounts)]
+pub struct WithdrawLp<'info> {
+    #[account(
+        mut,
+        seeds = [b"liquidity_mining", mining.agent.as_ref()],
+        bump = mining.bump
+    )]
+    pub mining: Box<Account<'info, LiquidityMining>>,
+
+    #[account(
+        mut,
+        seeds = [b"lm_lp_vault", mining.key().as_ref()],
+        bump
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"lm_position", mining.key().as_ref(), owner.key().as_ref()],
+        bump = position.bump,
+        has_one = owner
+    )]
+    pub position: Account<'info, LiquidityMiningPosition>,
+
+    #[account(
+        mut,
+        token::mint = mining.lp_mint,
+        token::authority = owner
+    )]
+    pub owner_lp_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraw staked LP tokens; earned rewards stay claimable
+pub fn handler(ctx: Context<WithdrawLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, AgentFactoryError::InvalidStakeAmount);
+    require!(amount <= ctx.accounts.position.amount, AgentFactoryError::InsufficientStake);
+
+    let now = Clock::get()?.unix_timestamp;
+    let mining = &mut ctx.accounts.mining;
+    mining.accrue(now)?;
+
+    let position = &mut ctx.accounts.position;
+    position.settle(mining)?;
+    position.amount -= amount;
+    mining.total_staked -= amount;
+
+    let agent_key = mining.agent;
+    let seeds = &[
+        b"liquidity_mining",
+        agent_key.as_ref(),
+        &[mining.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.lp_vault.to_account_info(),
+            to: ctx.accounts.owner_lp_account.to_account_info(),
+            authority: ctx.accounts.mining.to_account_info(),
+        },
+        signer_seeds,
+    );
+    token::transfer(cpi_ctx, amount)?;
+
+    trace!("LP tokens withdrawn!");
+    trace!("Amount: {}", amount);
+    trace!("Position: {}", ctx.accounts.position.amount);
+
+    Ok(())
+}
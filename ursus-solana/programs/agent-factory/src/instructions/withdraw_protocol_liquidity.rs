@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{AgentFactory, ProtocolLiquidity};
+
+#[derive(Accounts)]
+pub struct WithdrawProtocolLiquidity<'info> {
+    /// Factory singleton
+    #[account(
+        seeds = [b"factory"],
+        bump = factory.bump,
+        has_one = authority
+    )]
+    pub factory: Account<'info, AgentFactory>,
+
+    /// Protocol-owned liquidity; stays rent-exempt
+    #[account(
+        mut,
+        seeds = [b"protocol_liquidity"],
+        bump = protocol_liquidity.bump
+    )]
+    pub protocol_liquidity: Account<'info, ProtocolLiquidity>,
+
+    /// CHECK: Receives the SOL
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    /// Token withdrawals only: the vault's token account to withdraw from
+    #[account(
+        mut,
+        token::authority = protocol_liquidity
+    )]
+    pub protocol_liquidity_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Token withdrawals only: receives the tokens
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+
+    /// Factory authority (wallet, multisig or DAO PDA signer)
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraw protocol-owned SOL and/or agent tokens, e.g. to seed a DEX
+/// position. Gated by the factory authority, which may be a multisig or DAO
+/// PDA.
+pub fn handler(
+    ctx: Context<WithdrawProtocolLiquidity>,
+    lamports: u64,
+    token_amount: u64,
+) -> Result<()> {
+    if lamports > 0 {
+        let vault_info = ctx.accounts.protocol_liquidity.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(vault_info.data_len());
+        let available = vault_info.lamports().saturating_sub(rent_floor);
+        require!(lamports <= available, AgentFactoryError::InsufficientProtocolLiquidity);
+
+        **vault_info.try_borrow_mut_lamports()? -= lamports;
+        **ctx.accounts.recipient.to_account_info().try_borrow_mut_lamports()? += lamports;
+
+        let vault = &mut ctx.accounts.protocol_liquidity;
+        vault.total_sol_withdrawn = vault.total_sol_withdrawn
+            .checked_add(lamports)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+    }
+
+    if token_amount > 0 {
+        let (Some(from), Some(to)) = (
+            &ctx.accounts.protocol_liquidity_token_account,
+            &ctx.accounts.recipient_token_account,
+        ) else {
+            return err!(AgentFactoryError::ProtocolLiquidityRequired);
+        };
+        require!(token_amount <= from.amount, AgentFactoryError::InsufficientProtocolLiquidity);
+
+        let seeds = &[
+            b"protocol_liquidity".as_ref(),
+            &[ctx.accounts.protocol_liquidity.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: from.to_account_info(),
+                to: to.to_account_info(),
+                authority: ctx.accounts.protocol_liquidity.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, token_amount)?;
+    }
+
+    trace!("Protocol-owned liquidity withdrawn!");
+    trace!("SOL: {}", lamports);
+    trace!("Tokens: {}", token_amount);
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, ProviderBond};
+
+#[derive(Accounts)]
+pub struct WithdrawProviderBond<'info> {
+    pub agent: Account<'info, Agent>,
+
+    #[account(
+        mut,
+        seeds = [b"provider_bond", agent.key().as_ref()],
+        bump = provider_bond.bump
+    )]
+    pub provider_bond: Account<'info, ProviderBond>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Creator's account holding the agent's ownership NFT
+    #[account(
+        constraint = agent.is_owned_by(&creator_ownership_account, &creator.key()) @ AgentFactoryError::NotOwnershipHolder
+    )]
+    pub creator_ownership_account: Account<'info, TokenAccount>,
+}
+
+/// Withdraw bonded SOL while no dispute against the agent is open
+pub fn handler(ctx: Context<WithdrawProviderBond>, amount: u64) -> Result<()> {
+    let bond = &mut ctx.accounts.provider_bond;
+    require!(amount > 0 && amount <= bond.amount, AgentFactoryError::InvalidBondAmount);
+    require!(bond.open_disputes == 0, AgentFactoryError::BondLocked);
+
+    bond.amount -= amount;
+    **bond.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    trace!("Provider bond withdrawn!");
+    trace!("Amount: {}", amount);
+    trace!("Bonded: {}", bond.amount);
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer as TokenTransfer};
+use crate::errors::AgentFactoryError;
+use crate::state::{PaymentStream, RevenueSplit, X402Config};
+use super::settle_attested_payment::route_escrowed_revenue;
+
+#[derive(Accounts)]
+pub struct WithdrawStreamed<'info> {
+    #[account(
+        mut,
+        seeds = [b"x402_config", stream.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(mut)]
+    pub stream: Box<Account<'info, PaymentStream>>,
+
+    #[account(
+        mut,
+        seeds = [b"stream_escrow", stream.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Recipient's USDC token account, paid the streamed amount
+    #[account(
+        mut,
+        constraint = x402_config.accepts_recipient(&recipient_token_account.owner, Clock::get()?.unix_timestamp) @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Payer, receives the escrow account's rent once it's drained
+    #[account(
+        mut,
+        address = stream.payer
+    )]
+    pub payer: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// Agent's revenue split, required while it routes revenue to other
+    /// agents; the worker treasuries' token accounts follow as remaining
+    /// accounts, in share order
+    #[account(
+        mut,
+        seeds = [b"revenue_split", stream.agent.as_ref()],
+        bump = revenue_split.bump
+    )]
+    pub revenue_split: Option<Box<Account<'info, RevenueSplit>>>,
+}
+
+/// Pay the provider everything streamed and not yet withdrawn. Funds only
+/// go to the agent's payment recipient, so anyone can crank it.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawStreamed<'info>>,
+) -> Result<()> {
+    // A frozen agent can't collect; the payer can still cancel
+    require!(!ctx.accounts.x402_config.frozen, AgentFactoryError::X402Frozen);
+
+    let amount = ctx.accounts.stream.withdrawable(Clock::get()?.unix_timestamp);
+    require!(amount > 0, AgentFactoryError::NothingStreamed);
+
+    let routed = route_escrowed_revenue(
+        &ctx.accounts.x402_config,
+        ctx.accounts.revenue_split.as_deref_mut(),
+        ctx.remaining_accounts,
+        &ctx.accounts.escrow_token_account,
+        amount,
+        &ctx.accounts.token_program,
+    )?;
+    if routed > 0 {
+        ctx.accounts.escrow_token_account.reload()?;
+    }
+    pay_out_stream(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.recipient_token_account.to_account_info(),
+        amount - routed,
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+    )?;
+
+    // The stream counts as one service call, on its first withdrawal
+    let stream = &mut ctx.accounts.stream;
+    let x402_config = &mut ctx.accounts.x402_config;
+    if stream.withdrawn == 0 {
+        x402_config.record_payment(amount)?;
+    } else {
+        x402_config.total_payments_received = x402_config.total_payments_received
+            .checked_add(amount)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+    }
+    stream.withdrawn = stream.withdrawn
+        .checked_add(amount)
+        .ok_or(AgentFactoryError::MathOverflow)?;
+
+    trace!("Streamed payment withdrawn!");
+    trace!("Amount: {}, Withdrawn so far: {}", amount, stream.withdrawn);
+
+    Ok(())
+}
+
+/// Transfer `amount` of a stream's escrow to `to`, closing the escrow to
+/// the payer once it's drained
+pub fn pay_out_stream<'info>(
+    x402_config: &Account<'info, X402Config>,
+    escrow: &Account<'info, TokenAccount>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+    payer: &AccountInfo<'info>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    let bump = [x402_config.bump];
+    let seeds = &[b"x402_config".as_ref(), x402_config.agent.as_ref(), &bump];
+    let signer_seeds = &[&seeds[..]];
+
+    if amount > 0 {
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TokenTransfer {
+                    from: escrow.to_account_info(),
+                    to: to.clone(),
+                    authority: x402_config.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+    }
+    if escrow.amount == amount {
+        token::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: escrow.to_account_info(),
+                destination: payer.clone(),
+                authority: x402_config.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+    Ok(())
+}
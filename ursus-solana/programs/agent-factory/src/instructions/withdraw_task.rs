@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::errors::AgentFactoryError;
+use crate::events::WorkTaskEvent;
+use crate::state::{TaskStatus, WorkQueue, WorkTask, X402Config};
+use super::settle_attested_payment::release_escrow;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawTask<'info> {
+    #[account(
+        seeds = [b"x402_config", task.agent.as_ref()],
+        bump = x402_config.bump
+    )]
+    pub x402_config: Box<Account<'info, X402Config>>,
+
+    #[account(
+        mut,
+        seeds = [b"work_queue", task.agent.as_ref()],
+        bump = queue.bump
+    )]
+    pub queue: Box<Account<'info, WorkQueue>>,
+
+    #[account(
+        mut,
+        has_one = requester,
+        close = requester
+    )]
+    pub task: Box<Account<'info, WorkTask>>,
+
+    #[account(
+        mut,
+        seeds = [b"task_escrow", task.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Requester's USDC token account, refunded the max price
+    #[account(
+        mut,
+        constraint = requester_token_account.owner == requester.key() @ AgentFactoryError::WrongRecipientAccount
+    )]
+    pub requester_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Take a task no worker is serving off the queue: open, or claimed by a
+/// worker whose lease ran out. Refunds its escrow and closes it.
+pub fn handler(ctx: Context<WithdrawTask>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let lease_secs = ctx.accounts.x402_config.service_timeout_seconds as i64;
+    require!(
+        ctx.accounts.task.is_claimable(now, lease_secs),
+        AgentFactoryError::TaskNotClaimable
+    );
+
+    release_escrow(
+        &ctx.accounts.x402_config,
+        &ctx.accounts.escrow_token_account,
+        &ctx.accounts.requester_token_account.to_account_info(),
+        &ctx.accounts.requester.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+    let queue = &mut ctx.accounts.queue;
+    queue.open_tasks = queue.open_tasks.saturating_sub(1);
+
+    let task = &ctx.accounts.task;
+    trace!("Task withdrawn!");
+    trace!("Task: {}, Refunded: {}", task.task_id, task.max_price);
+
+    emit_cpi!(WorkTaskEvent {
+        agent: task.agent,
+        task: task.key(),
+        task_id: task.task_id,
+        requester: task.requester,
+        worker: task.worker,
+        status: TaskStatus::Withdrawn,
+        params_hash: task.params_hash,
+        max_price: task.max_price,
+        price: 0,
+        result_hash: [0; 32],
+        timestamp: now,
+    });
+
+    Ok(())
+}
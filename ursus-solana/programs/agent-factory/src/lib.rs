@@ -1,14 +1,28 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
 
 declare_id!("4m6mpe2jdRiM24ui1Z3AGbCheu1DfQEjmEGtaGKD2ftU");
 
+/// `msg!` that compiles out unless the `verbose-logs` feature is on. Only dev
+/// builds turn it on, so anything clients need must be in an event rather
+/// than only in a trace.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "verbose-logs") {
+            anchor_lang::prelude::msg!($($arg)*);
+        }
+    };
+}
+
 pub mod instructions;
 pub mod state;
 pub mod errors;
+pub mod events;
+pub mod pda;
+pub mod registry;
 
+pub use instructions::*;
 use state::*;
-use errors::*;
+use registry::LayoutInfo;
 
 #[program]
 pub mod agent_factory {
@@ -27,8 +41,93 @@ pub mod agent_factory {
         description: String,
         agent_instructions: String,
         model: String,
-        category: String,
+        tags: Vec<String>,
+    ) -> Result<()> {
+        instructions::create_agent::handler(
+            ctx,
+            &name,
+            &symbol,
+            &description,
+            &agent_instructions,
+            &model,
+            &tags,
+            BondingCurve::new(),
+            CreatorFeeMode::Creator,
+        )
+    }
+
+    /// Create an agent, configure x402 payments and optionally make a dev
+    /// buy atomically
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_agent_full<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateAgentFull<'info>>,
+        name: String,
+        symbol: String,
+        description: String,
+        agent_instructions: String,
+        model: String,
+        tags: Vec<String>,
+        x402: X402Settings,
+        dev_buy_sol_amount: u64,
+        min_tokens_out: u64,
+    ) -> Result<()> {
+        instructions::create_agent_full::handler(
+            ctx,
+            &name,
+            &symbol,
+            &description,
+            &agent_instructions,
+            &model,
+            &tags,
+            x402,
+            dev_buy_sol_amount,
+            min_tokens_out,
+        )
+    }
+
+    /// Create a new AI Agent with a stepped multi-phase bonding curve
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_agent_with_curve(
+        ctx: Context<CreateAgent>,
+        name: String,
+        symbol: String,
+        description: String,
+        agent_instructions: String,
+        model: String,
+        tags: Vec<String>,
+        tranches: Vec<CurveTranche>,
+    ) -> Result<()> {
+        let bonding_curve = BondingCurve::stepped(&tranches)?;
+        instructions::create_agent::handler(
+            ctx,
+            &name,
+            &symbol,
+            &description,
+            &agent_instructions,
+            &model,
+            &tags,
+            bonding_curve,
+            CreatorFeeMode::Creator,
+        )
+    }
+
+    /// Create a new AI Agent launched as a liquidity bootstrapping (Dutch
+    /// auction) curve: the price opens at `start_price_multiplier` times the
+    /// regular curve and decays to it over `duration_secs` absent demand
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_agent_lbp(
+        ctx: Context<CreateAgent>,
+        name: String,
+        symbol: String,
+        description: String,
+        agent_instructions: String,
+        model: String,
+        tags: Vec<String>,
+        duration_secs: i64,
+        start_price_multiplier: u64,
     ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let bonding_curve = BondingCurve::lbp(duration_secs, start_price_multiplier, now)?;
         instructions::create_agent::handler(
             ctx,
             &name,
@@ -36,7 +135,9 @@ pub mod agent_factory {
             &description,
             &agent_instructions,
             &model,
-            &category,
+            &tags,
+            bonding_curve,
+            CreatorFeeMode::Creator,
         )
     }
 
@@ -50,14 +151,478 @@ pub mod agent_factory {
         instructions::sell_tokens::handler(ctx, token_amount, min_sol_out)
     }
 
-    /// Graduate agent to DEX when threshold is reached
+    /// Graduate agent to DEX when threshold is reached. The unsold curve
+    /// tokens are burned instead of seeding the pool if the creator chose so
+    /// with `set_graduation_burn`.
     pub fn graduate_agent(ctx: Context<GraduateAgent>) -> Result<()> {
         instructions::graduate_agent::handler(ctx)
     }
 
-    /// Update platform fee
-    pub fn update_creation_fee(ctx: Context<UpdateFee>, new_fee: u64) -> Result<()> {
-        instructions::update_fee::handler(ctx, new_fee)
+    /// Record the holder of an agent's ownership NFT as its creator wallet
+    pub fn claim_agent_ownership(ctx: Context<ClaimAgentOwnership>) -> Result<()> {
+        instructions::claim_agent_ownership::handler(ctx)
+    }
+
+    /// Hand an agent's ownership NFT and creator role to a new wallet in one
+    /// step (creator)
+    pub fn transfer_agent_ownership(ctx: Context<TransferAgentOwnership>) -> Result<()> {
+        instructions::transfer_agent_ownership::handler(ctx)
+    }
+
+    /// Set the original creator's royalty on creator fees after a transfer
+    /// (original creator)
+    pub fn set_creator_royalty(ctx: Context<SetCreatorRoyalty>, royalty_bps: u16) -> Result<()> {
+        instructions::set_creator_royalty::handler(ctx, royalty_bps)
+    }
+
+    /// Claim the royalties held for the original creator
+    pub fn claim_royalties(ctx: Context<ClaimRoyalties>) -> Result<()> {
+        instructions::claim_royalties::handler(ctx)
+    }
+
+    /// Reject sells in a slot that already has a buy (creator)
+    pub fn set_same_slot_protection(ctx: Context<SetSameSlotProtection>, enabled: bool) -> Result<()> {
+        instructions::set_same_slot_protection::handler(ctx, enabled)
+    }
+
+    // ============================================================================
+    // Admin Timelock Instructions
+    // ============================================================================
+
+    /// Queue a factory setting change (creation fee, platform treasury,
+    /// timelock delay, authority, insurance fee, fee discounts, protocol
+    /// liquidity); emits an event and waits out the timelock
+    pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+        instructions::queue_admin_action::handler(ctx, action)
+    }
+
+    /// Apply a queued admin action once its timelock has expired (permissionless)
+    pub fn execute_admin_action(ctx: Context<ExecuteAdminAction>) -> Result<()> {
+        instructions::execute_admin_action::handler(ctx)
+    }
+
+    /// Cancel a queued admin action
+    pub fn cancel_admin_action(ctx: Context<CancelAdminAction>) -> Result<()> {
+        instructions::cancel_admin_action::handler(ctx)
+    }
+
+    // ============================================================================
+    // Refund Guarantee Instructions
+    // ============================================================================
+
+    /// Opt into the refund guarantee: redemptions open if the agent hasn't
+    /// graduated within `days` of creation
+    pub fn enable_refund_guarantee(ctx: Context<EnableRefundGuarantee>, days: u16) -> Result<()> {
+        instructions::enable_refund_guarantee::handler(ctx, days)
+    }
+
+    /// Redeem tokens at the average entry price once the graduation deadline is missed
+    pub fn redeem_for_sol(ctx: Context<RedeemForSol>, token_amount: u64, min_sol_out: u64) -> Result<()> {
+        instructions::redeem_for_sol::handler(ctx, token_amount, min_sol_out)
+    }
+
+    // ============================================================================
+    // Snapshot Instructions
+    // ============================================================================
+
+    /// Record a holder snapshot (slot, block time, circulating supply) for
+    /// off-chain distributions
+    pub fn create_snapshot(ctx: Context<CreateSnapshot>) -> Result<()> {
+        instructions::create_snapshot::handler(ctx)
+    }
+
+    // ============================================================================
+    // Price History and Trending Instructions
+    // ============================================================================
+
+    /// Create an agent's on-chain candle accumulator, updated by buys and
+    /// sells that pass it
+    pub fn initialize_price_history(ctx: Context<InitializePriceHistory>) -> Result<()> {
+        instructions::initialize_price_history::handler(ctx)
+    }
+
+    /// Create the factory trending leaderboard, updated by buys and sells
+    /// that pass it
+    pub fn initialize_trending(ctx: Context<InitializeTrending>) -> Result<()> {
+        instructions::initialize_trending::handler(ctx)
+    }
+
+    /// Return the agent's manipulation-resistant TWAP over at least
+    /// `window_slots` (nano-lamports per token)
+    pub fn get_twap(ctx: Context<GetTwap>, window_slots: u64) -> Result<u64> {
+        instructions::get_twap::handler(ctx, window_slots)
+    }
+
+    /// Return the agent's spot price, market cap and reserves as a
+    /// `PriceView`
+    pub fn view_price(ctx: Context<ViewPrice>) -> Result<PriceView> {
+        instructions::view_price::handler(ctx)
+    }
+
+    // ============================================================================
+    // Early Buyer Rewards Instructions
+    // ============================================================================
+
+    /// Fund a token bonus for the first `max_buyers` unique buyers of one of
+    /// the creator's agents
+    pub fn create_early_buyer_rewards(
+        ctx: Context<CreateEarlyBuyerRewards>,
+        max_buyers: u64,
+        bonus_per_buyer: u64,
+        claim_deadline: i64,
+    ) -> Result<()> {
+        instructions::create_early_buyer_rewards::handler(ctx, max_buyers, bonus_per_buyer, claim_deadline)
+    }
+
+    /// Claim the early buyer bonus as one of the agent's first buyers
+    pub fn claim_early_buyer_bonus(ctx: Context<ClaimEarlyBuyerBonus>) -> Result<()> {
+        instructions::claim_early_buyer_bonus::handler(ctx)
+    }
+
+    /// Return unclaimed early buyer bonuses to the creator after the deadline
+    pub fn close_early_buyer_rewards(ctx: Context<CloseEarlyBuyerRewards>) -> Result<()> {
+        instructions::close_early_buyer_rewards::handler(ctx)
+    }
+
+    // ============================================================================
+    // Token Lock Instructions
+    // ============================================================================
+
+    /// Lock agent tokens until `unlock_ts` in a per-lock vault
+    pub fn lock_tokens(ctx: Context<LockTokens>, lock_id: u64, amount: u64, unlock_ts: i64) -> Result<()> {
+        instructions::lock_tokens::handler(ctx, lock_id, amount, unlock_ts)
+    }
+
+    /// Withdraw the tokens of an expired lock
+    pub fn unlock_tokens(ctx: Context<UnlockTokens>) -> Result<()> {
+        instructions::unlock_tokens::handler(ctx)
+    }
+
+    // ============================================================================
+    // Staking Instructions
+    // ============================================================================
+
+    /// Open an agent's staking pool, routing `staking_fee_bps` of creator fees to stakers
+    pub fn create_staking_pool(
+        ctx: Context<CreateStakingPool>,
+        staking_fee_bps: u16,
+        emission_rate_cap: u64,
+    ) -> Result<()> {
+        instructions::create_staking_pool::handler(ctx, staking_fee_bps, emission_rate_cap)
+    }
+
+    /// Update the creator fee share and emission cap of a staking pool
+    pub fn update_staking_pool(
+        ctx: Context<UpdateStakingPool>,
+        staking_fee_bps: u16,
+        emission_rate_cap: u64,
+    ) -> Result<()> {
+        instructions::create_staking_pool::update_handler(ctx, staking_fee_bps, emission_rate_cap)
+    }
+
+    /// Stake agent tokens to earn a share of the pool rewards
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        instructions::stake::handler(ctx, amount)
+    }
+
+    /// Lock (or extend the lock of) a stake for boosted, decaying voting weight
+    pub fn lock_stake(ctx: Context<LockStake>, unlock_ts: i64) -> Result<()> {
+        instructions::lock_stake::handler(ctx, unlock_ts)
+    }
+
+    /// Withdraw staked agent tokens once their lock has ended
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        instructions::unstake::handler(ctx, amount)
+    }
+
+    /// Claim the SOL rewards earned by a stake position
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        instructions::claim_rewards::handler(ctx)
+    }
+
+    /// Deposit SOL rewards into a staking pool (permissionless)
+    pub fn fund_staking_pool(ctx: Context<FundStakingPool>, amount: u64) -> Result<()> {
+        instructions::fund_staking_pool::handler(ctx, amount)
+    }
+
+    // ============================================================================
+    // Governance Instructions
+    // ============================================================================
+
+    /// Let an agent's stakers vote on its parameter changes
+    pub fn create_governance(
+        ctx: Context<CreateGovernance>,
+        quorum_bps: u16,
+        threshold_bps: u16,
+        voting_period_secs: i64,
+        proposal_threshold: u64,
+    ) -> Result<()> {
+        instructions::create_governance::handler(
+            ctx,
+            quorum_bps,
+            threshold_bps,
+            voting_period_secs,
+            proposal_threshold,
+        )
+    }
+
+    /// Propose a parameter change (requires `proposal_threshold` voting weight)
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+        instructions::create_proposal::handler(ctx, action)
+    }
+
+    /// Vote on an open proposal with the stake held at its snapshot
+    pub fn cast_vote(ctx: Context<CastVote>, support: bool) -> Result<()> {
+        instructions::cast_vote::handler(ctx, support)
+    }
+
+    /// Apply a passed proposal after voting ends (permissionless)
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        instructions::execute_proposal::handler(ctx)
+    }
+
+    /// Open a DAO treasury that receives `treasury_fee_bps` of creator fees
+    /// and can only be spent by proposals
+    pub fn create_dao_treasury(ctx: Context<CreateDaoTreasury>, treasury_fee_bps: u16) -> Result<()> {
+        instructions::create_dao_treasury::handler(ctx, treasury_fee_bps)
+    }
+
+    /// Sweep accrued treasury fees from the agent into the treasury (permissionless)
+    pub fn collect_treasury_fees(ctx: Context<CollectTreasuryFees>) -> Result<()> {
+        instructions::collect_treasury_fees::handler(ctx)
+    }
+
+    // ============================================================================
+    // Insurance Fund Instructions
+    // ============================================================================
+
+    /// Open the platform insurance fund, capitalized by `insurance_fee_bps`
+    /// of platform fees
+    pub fn initialize_insurance_fund(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+        instructions::initialize_insurance_fund::handler(ctx)
+    }
+
+    /// Sweep an agent's accrued insurance fees into the fund (permissionless)
+    pub fn collect_insurance_fees(ctx: Context<CollectInsuranceFees>) -> Result<()> {
+        instructions::collect_insurance_fees::handler(ctx)
+    }
+
+    /// Deposit SOL into the insurance fund (e.g. x402 revenue)
+    pub fn fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+        instructions::fund_insurance::handler(ctx, amount)
+    }
+
+    /// Pay a claim to a user harmed by an exploited or rugged agent
+    pub fn pay_claim(ctx: Context<PayClaim>, amount: u64) -> Result<()> {
+        instructions::pay_claim::handler(ctx, amount)
+    }
+
+    // ============================================================================
+    // Protocol-Owned Liquidity Instructions
+    // ============================================================================
+
+    /// Open the protocol-owned liquidity vault, fed by `protocol_liquidity_bps`
+    /// of every graduation (factory authority)
+    pub fn initialize_protocol_liquidity(ctx: Context<InitializeProtocolLiquidity>) -> Result<()> {
+        instructions::initialize_protocol_liquidity::handler(ctx)
+    }
+
+    /// Withdraw protocol-owned SOL and/or agent tokens (factory authority)
+    pub fn withdraw_protocol_liquidity(
+        ctx: Context<WithdrawProtocolLiquidity>,
+        lamports: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_protocol_liquidity::handler(ctx, lamports, token_amount)
+    }
+
+    // ============================================================================
+    // Liquidity Mining Instructions
+    // ============================================================================
+
+    /// Open liquidity mining for the DEX pool of a graduated agent (creator)
+    pub fn create_liquidity_mining(ctx: Context<CreateLiquidityMining>) -> Result<()> {
+        instructions::create_liquidity_mining::handler(ctx)
+    }
+
+    /// Fund agent token emissions over `duration_secs` (creator)
+    pub fn fund_liquidity_mining(
+        ctx: Context<FundLiquidityMining>,
+        amount: u64,
+        duration_secs: i64,
+    ) -> Result<()> {
+        instructions::fund_liquidity_mining::handler(ctx, amount, duration_secs)
+    }
+
+    /// Stake LP tokens to earn agent token emissions
+    pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+        instructions::stake_lp::handler(ctx, amount)
+    }
+
+    /// Withdraw staked LP tokens
+    pub fn withdraw_lp(ctx: Context<WithdrawLp>, amount: u64) -> Result<()> {
+        instructions::withdraw_lp::handler(ctx, amount)
+    }
+
+    /// Claim agent token emissions earned by an LP stake
+    pub fn claim_lp_rewards(ctx: Context<ClaimLpRewards>) -> Result<()> {
+        instructions::claim_lp_rewards::handler(ctx)
+    }
+
+    // ============================================================================
+    // Token Migration Instructions
+    // ============================================================================
+
+    /// Register a swap from a previous token into an agent token (creator)
+    pub fn create_migration(
+        ctx: Context<CreateMigration>,
+        ratio_numerator: u64,
+        ratio_denominator: u64,
+        deposit_amount: u64,
+    ) -> Result<()> {
+        instructions::create_migration::handler(ctx, ratio_numerator, ratio_denominator, deposit_amount)
+    }
+
+    /// Swap previous tokens for agent tokens at the migration ratio
+    pub fn migrate_tokens(ctx: Context<MigrateTokens>, amount: u64) -> Result<()> {
+        instructions::migrate_tokens::handler(ctx, amount)
+    }
+
+    // ============================================================================
+    // Index Basket Instructions
+    // ============================================================================
+
+    /// Define a weighted basket of agent tokens with its own token; pass the
+    /// agents as remaining accounts in `units` order
+    pub fn create_index_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateIndexBasket<'info>>,
+        basket_id: u64,
+        name: String,
+        units: Vec<u64>,
+    ) -> Result<()> {
+        instructions::create_index_basket::handler(ctx, basket_id, &name, &units)
+    }
+
+    /// Deposit the constituents and mint basket tokens
+    pub fn mint_basket<'info>(ctx: Context<'_, '_, 'info, 'info, MintBasket<'info>>, amount: u64) -> Result<()> {
+        instructions::mint_basket::handler(ctx, amount)
+    }
+
+    /// Burn basket tokens for the constituents
+    pub fn redeem_basket<'info>(ctx: Context<'_, '_, 'info, 'info, RedeemBasket<'info>>, amount: u64) -> Result<()> {
+        instructions::redeem_basket::handler(ctx, amount)
+    }
+
+    // ============================================================================
+    // Creator Stats Instructions
+    // ============================================================================
+
+    /// Flag a creator's rugged agents or blacklist them, surcharging their
+    /// creation fees (factory authority)
+    pub fn set_creator_standing(
+        ctx: Context<SetCreatorStanding>,
+        rugged_agents: u16,
+        is_blacklisted: bool,
+    ) -> Result<()> {
+        instructions::set_creator_standing::handler(ctx, rugged_agents, is_blacklisted)
+    }
+
+    // ============================================================================
+    // DCA Instructions
+    // ============================================================================
+
+    /// Create a DCA schedule funded with `amount_per_interval * total_intervals` SOL;
+    /// executions returning fewer than `min_tokens_per_interval` tokens fail
+    pub fn create_dca(
+        ctx: Context<CreateDca>,
+        amount_per_interval: u64,
+        interval_secs: i64,
+        total_intervals: u64,
+        min_tokens_per_interval: u64,
+    ) -> Result<()> {
+        instructions::create_dca::handler(
+            ctx,
+            amount_per_interval,
+            interval_secs,
+            total_intervals,
+            min_tokens_per_interval,
+        )
+    }
+
+    /// Execute the next due DCA interval (permissionless crank)
+    pub fn execute_dca(ctx: Context<ExecuteDca>) -> Result<()> {
+        instructions::execute_dca::handler(ctx)
+    }
+
+    /// Cancel a DCA schedule and refund the remaining deposit
+    pub fn cancel_dca(ctx: Context<CancelDca>) -> Result<()> {
+        instructions::cancel_dca::handler(ctx)
+    }
+
+    // ============================================================================
+    // Keeper Instructions
+    // ============================================================================
+
+    /// Register a crank (DCA interval, graduation or admin action) that tips
+    /// keepers `tip_lamports` per unit of work out of `deposit`
+    pub fn register_keeper_task(
+        ctx: Context<RegisterKeeperTask>,
+        task_id: u64,
+        kind: KeeperTaskKind,
+        tip_lamports: u64,
+        deposit: u64,
+    ) -> Result<()> {
+        instructions::register_keeper_task::handler(ctx, task_id, kind, tip_lamports, deposit)
+    }
+
+    /// Top up a keeper task's tip deposit (permissionless)
+    pub fn fund_keeper_task(ctx: Context<FundKeeperTask>, amount: u64) -> Result<()> {
+        instructions::fund_keeper_task::handler(ctx, amount)
+    }
+
+    /// Collect the tip for work done on a keeper task's target (permissionless)
+    pub fn crank_keeper_task(ctx: Context<CrankKeeperTask>) -> Result<()> {
+        instructions::crank_keeper_task::handler(ctx)
+    }
+
+    /// Cancel a keeper task and refund the remaining deposit
+    pub fn cancel_keeper_task(ctx: Context<CancelKeeperTask>) -> Result<()> {
+        instructions::cancel_keeper_task::handler(ctx)
+    }
+
+    // ============================================================================
+    // Presale Instructions
+    // ============================================================================
+
+    /// Open a presale window for an agent whose curve has not traded yet
+    pub fn create_presale(
+        ctx: Context<CreatePresale>,
+        start_ts: i64,
+        end_ts: i64,
+        soft_cap: u64,
+        hard_cap: u64,
+    ) -> Result<()> {
+        instructions::create_presale::handler(ctx, start_ts, end_ts, soft_cap, hard_cap)
+    }
+
+    /// Commit SOL to an open presale
+    pub fn commit_sol(ctx: Context<CommitSol>, amount: u64) -> Result<()> {
+        instructions::commit_sol::handler(ctx, amount)
+    }
+
+    /// Finalize an ended presale and seed the curve if the soft cap was met (permissionless)
+    pub fn finalize_presale(ctx: Context<FinalizePresale>) -> Result<()> {
+        instructions::finalize_presale::handler(ctx)
+    }
+
+    /// Claim the token allocation (and unspent SOL) of a successful presale
+    pub fn claim_presale(ctx: Context<ClaimPresale>) -> Result<()> {
+        instructions::claim_presale::handler(ctx)
+    }
+
+    /// Refund a commitment to a presale that missed its soft cap
+    pub fn refund_commitment(ctx: Context<RefundCommitment>) -> Result<()> {
+        instructions::refund_commitment::handler(ctx)
     }
 
     // ============================================================================
@@ -98,350 +663,655 @@ pub mod agent_factory {
         )
     }
 
-    /// Pay for an agent service using X402 protocol
-    pub fn pay_for_service(
-        ctx: Context<PayForService>,
+    /// Pay for an agent service using X402 protocol. `service_id` is the
+    /// `service_id_hash` of the service name; `priority_tip` is paid on top,
+    /// all of it to the provider
+    pub fn pay_for_service<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PayForService<'info>>,
         amount: u64,
-        service_id: String,
+        service_id: [u8; 32],
         nonce: u64,
+        request_id: [u8; 16],
+        priority_tip: u64,
     ) -> Result<()> {
-        instructions::pay_for_service::handler(ctx, amount, service_id, nonce)
+        instructions::pay_for_service::handler(ctx, amount, service_id, nonce, request_id, priority_tip)
     }
 
-    /// Call an agent service with payment (Agent-to-Agent interaction)
-    pub fn call_agent_service(
-        ctx: Context<CallAgentService>,
+    /// Call an agent service with payment (Agent-to-Agent interaction),
+    /// optionally tipping the target to expedite it
+    pub fn call_agent_service<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CallAgentService<'info>>,
         amount: u64,
-        service_id: String,
+        service_id: [u8; 32],
         nonce: u64,
         service_params: Vec<u8>,
+        request_id: [u8; 16],
+        priority_tip: u64,
     ) -> Result<()> {
-        instructions::call_agent_service::handler(ctx, amount, service_id, nonce, service_params)
+        instructions::call_agent_service::handler(
+            ctx,
+            amount,
+            service_id,
+            nonce,
+            service_params,
+            request_id,
+            priority_tip,
+        )
+    }
+
+    /// Set the signer attesting escrowed payments, e.g. a Switchboard
+    /// Function's enclave signer (creator)
+    pub fn set_x402_verifier(ctx: Context<SetX402Verifier>, verifier: Pubkey) -> Result<()> {
+        instructions::set_x402_verifier::handler(ctx, verifier)
+    }
+
+    /// Pay for an agent service into escrow, released on attestation
+    pub fn pay_for_service_escrowed(
+        ctx: Context<PayForServiceEscrowed>,
+        amount: u64,
+        service_id: [u8; 32],
+        nonce: u64,
+        request_id: [u8; 16],
+    ) -> Result<()> {
+        instructions::pay_for_service_escrowed::handler(ctx, amount, service_id, nonce, request_id)
+    }
+
+    /// Release an escrowed payment if the attested output passed its check,
+    /// refund it otherwise (attestation verifier)
+    pub fn settle_attested_payment<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleAttestedPayment<'info>>,
+        passed: bool,
+        attestation_hash: [u8; 32],
+    ) -> Result<()> {
+        let refund_bps = if passed { 0 } else { bonding_curve_math::BPS_DENOMINATOR as u16 };
+        instructions::settle_attested_payment::handler(ctx, refund_bps, attestation_hash)
     }
-}
 
-// ============================================================================
-// Initialize Instruction
-// ============================================================================
+    /// Settle an escrowed payment with a partial refund: `refund_bps` back
+    /// to the payer, the rest to the recipient (attestation verifier)
+    pub fn settle_attested_payment_partial<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleAttestedPayment<'info>>,
+        refund_bps: u16,
+        attestation_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::settle_attested_payment::handler(ctx, refund_bps, attestation_hash)
+    }
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + AgentFactory::INIT_SPACE,
-        seeds = [b"factory"],
-        bump
-    )]
-    pub factory: Account<'info, AgentFactory>,
+    /// Refund an escrowed payment left unattested past the service timeout
+    pub fn refund_expired_payment(ctx: Context<RefundExpiredPayment>) -> Result<()> {
+        instructions::refund_expired_payment::handler(ctx)
+    }
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    // ============================================================================
+    // Provider Bond and Dispute Instructions
+    // ============================================================================
 
-    /// CHECK: Platform treasury account
-    pub platform_treasury: AccountInfo<'info>,
+    /// Post (or add to) the SOL bond backing an agent's services (creator)
+    pub fn deposit_provider_bond(ctx: Context<DepositProviderBond>, amount: u64) -> Result<()> {
+        instructions::deposit_provider_bond::handler(ctx, amount)
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    /// Withdraw bonded SOL while no dispute is open (creator)
+    pub fn withdraw_provider_bond(ctx: Context<WithdrawProviderBond>, amount: u64) -> Result<()> {
+        instructions::withdraw_provider_bond::handler(ctx, amount)
+    }
 
-// ============================================================================
-// Create Agent Instruction
-// ============================================================================
-
-#[derive(Accounts)]
-pub struct CreateAgent<'info> {
-    #[account(
-        mut,
-        seeds = [b"factory"],
-        bump
-    )]
-    pub factory: Account<'info, AgentFactory>,
-
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + Agent::INIT_SPACE,
-        seeds = [b"agent", factory.total_agents.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub agent: Account<'info, Agent>,
-
-    #[account(
-        init,
-        payer = creator,
-        mint::decimals = 9,
-        mint::authority = agent,
-        seeds = [b"mint", agent.key().as_ref()],
-        bump
-    )]
-    pub mint: Account<'info, Mint>,
-
-    #[account(mut)]
-    pub creator: Signer<'info>,
-
-    /// CHECK: Platform treasury
-    #[account(mut)]
-    pub platform_treasury: AccountInfo<'info>,
-
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+    /// Dispute a payment to a bonded agent with the hash of the evidence and
+    /// the lamports claimed, up to the payment's lamport value
+    pub fn submit_evidence(
+        ctx: Context<SubmitEvidence>,
+        evidence_hash: [u8; 32],
+        claimed_amount: u64,
+    ) -> Result<()> {
+        instructions::submit_evidence::handler(ctx, evidence_hash, claimed_amount)
+    }
 
-// ============================================================================
-// Buy Tokens Instruction
-// ============================================================================
-
-#[derive(Accounts)]
-pub struct BuyTokens<'info> {
-    #[account(mut)]
-    pub agent: Account<'info, Agent>,
-
-    #[account(
-        mut,
-        address = agent.mint
-    )]
-    pub mint: Account<'info, Mint>,
-
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = buyer
-    )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-
-    /// CHECK: Creator receives fees
-    #[account(mut)]
-    pub creator: AccountInfo<'info>,
-
-    /// CHECK: Platform treasury receives fees
-    #[account(mut)]
-    pub platform_treasury: AccountInfo<'info>,
-
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+    /// Vote on a dispute as an arbitration council member; resolves it once
+    /// the threshold agrees on a slash amount (0 = dismiss)
+    pub fn cast_arbiter_vote(ctx: Context<CastArbiterVote>, slash_amount: u64) -> Result<()> {
+        instructions::cast_arbiter_vote::handler(ctx, slash_amount)
+    }
 
-// ============================================================================
-// Sell Tokens Instruction
-// ============================================================================
-
-#[derive(Accounts)]
-pub struct SellTokens<'info> {
-    #[account(mut)]
-    pub agent: Account<'info, Agent>,
-
-    #[account(
-        mut,
-        address = agent.mint
-    )]
-    pub mint: Account<'info, Mint>,
-
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = seller
-    )]
-    pub seller_token_account: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub seller: Signer<'info>,
-
-    /// CHECK: Creator receives fees
-    #[account(mut)]
-    pub creator: AccountInfo<'info>,
-
-    /// CHECK: Platform treasury receives fees
-    #[account(mut)]
-    pub platform_treasury: AccountInfo<'info>,
-
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+    /// Insure a payment for `coverage` lamports, up to its lamport value; the
+    /// premium goes to the insurance fund (bundle with the payment)
+    pub fn insure_payment(ctx: Context<InsurePayment>, coverage: u64) -> Result<()> {
+        instructions::insure_payment::handler(ctx, coverage)
+    }
 
-// ============================================================================
-// Graduate Agent Instruction
-// ============================================================================
+    /// Pay an upheld dispute's award shortfall from the insurance fund, up
+    /// to the payment's policy coverage
+    pub fn pay_insured_claim(ctx: Context<PayInsuredClaim>) -> Result<()> {
+        instructions::pay_insured_claim::handler(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct GraduateAgent<'info> {
-    #[account(
-        mut,
-        constraint = !agent.is_graduated @ AgentFactoryError::AlreadyGraduated
-    )]
-    pub agent: Account<'info, Agent>,
+    /// Bill an agent's services per 1K LLM tokens or per compute unit; 0
+    /// returns to flat pricing (creator)
+    pub fn set_x402_metering(
+        ctx: Context<SetX402Metering>,
+        meter_unit: MeterUnit,
+        unit_price: u64,
+    ) -> Result<()> {
+        instructions::set_x402_metering::handler(ctx, meter_unit, unit_price)
+    }
 
-    #[account(
-        mut,
-        address = agent.mint
-    )]
-    pub mint: Account<'info, Mint>,
+    /// Charge a metered payment's escrow for the reported usage and refund
+    /// the rest (agent creator)
+    pub fn settle_metered_payment<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleMeteredPayment<'info>>,
+        units: u64,
+        usage_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::settle_metered_payment::handler(ctx, units, usage_hash)
+    }
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    /// Quote a payer a negotiated service price until `expires_at` (creator)
+    pub fn post_quote(
+        ctx: Context<PostQuote>,
+        payer: Pubkey,
+        service_id: [u8; 32],
+        amount: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::post_quote::handler(ctx, payer, service_id, amount, expires_at)
+    }
 
-    /// CHECK: DEX program for liquidity
-    pub dex_program: AccountInfo<'info>,
+    /// Close a quote, returning its rent (creator)
+    pub fn close_quote(ctx: Context<CloseQuote>) -> Result<()> {
+        instructions::close_quote::handler(ctx)
+    }
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+    /// Register as an x402 affiliate asking `fee_bps` of referred payments
+    pub fn register_affiliate(ctx: Context<RegisterAffiliate>, fee_bps: u16) -> Result<()> {
+        instructions::register_affiliate::handler(ctx, fee_bps)
+    }
 
-// ============================================================================
-// Update Fee Instruction
-// ============================================================================
-
-#[derive(Accounts)]
-pub struct UpdateFee<'info> {
-    #[account(
-        mut,
-        seeds = [b"factory"],
-        bump = factory.bump,
-        has_one = authority
-    )]
-    pub factory: Account<'info, AgentFactory>,
-
-    pub authority: Signer<'info>,
-}
+    /// Cap the affiliate share of an agent's service payments (creator)
+    pub fn set_affiliate_cap(ctx: Context<SetAffiliateCap>, max_affiliate_bps: u16) -> Result<()> {
+        instructions::set_affiliate_cap::handler(ctx, max_affiliate_bps)
+    }
 
-// ============================================================================
-// X402 Payment Protocol Instructions
-// ============================================================================
-
-#[derive(Accounts)]
-#[instruction(enabled: bool, min_payment_amount: u64, max_payment_amount: u64, service_timeout_seconds: u64)]
-pub struct ConfigureX402<'info> {
-    #[account(mut)]
-    pub agent: Account<'info, Agent>,
-
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + X402Config::INIT_SPACE,
-        seeds = [b"x402_config", agent.key().as_ref()],
-        bump
-    )]
-    pub x402_config: Account<'info, X402Config>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-
-    pub system_program: Program<'info, System>,
-}
+    /// Register the agent's webhook URL hash and response signing key
+    /// (creator)
+    pub fn register_webhook(
+        ctx: Context<RegisterWebhook>,
+        url_hash: [u8; 32],
+        signer: Pubkey,
+    ) -> Result<()> {
+        instructions::register_webhook::handler(ctx, url_hash, signer)
+    }
 
-#[derive(Accounts)]
-#[instruction(enabled: bool, min_payment_amount: u64, max_payment_amount: u64, service_timeout_seconds: u64)]
-pub struct UpdateX402<'info> {
-    #[account(mut)]
-    pub agent: Account<'info, Agent>,
-
-    #[account(
-        mut,
-        seeds = [b"x402_config", agent.key().as_ref()],
-        bump = x402_config.bump
-    )]
-    pub x402_config: Account<'info, X402Config>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-}
+    /// Switch the model an agent claims to run on, from the factory
+    /// allowlist (creator)
+    pub fn set_agent_model(ctx: Context<SetAgentModel>, model: String) -> Result<()> {
+        instructions::set_agent_model::handler(ctx, &model)
+    }
 
-#[derive(Accounts)]
-#[instruction(amount: u64, service_id: String, nonce: u64)]
-pub struct PayForService<'info> {
-    #[account(mut)]
-    pub agent: Account<'info, Agent>,
-
-    #[account(
-        mut,
-        seeds = [b"x402_config", agent.key().as_ref()],
-        bump = x402_config.bump
-    )]
-    pub x402_config: Account<'info, X402Config>,
-
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + X402PaymentRecord::INIT_SPACE,
-        seeds = [
-            b"payment_record",
-            agent.key().as_ref(),
-            payer.key().as_ref(),
-            &nonce.to_le_bytes()
-        ],
-        bump
-    )]
-    pub payment_record: Account<'info, X402PaymentRecord>,
-
-    #[account(mut)]
-    pub payer: Signer<'info>,
-
-    /// Payer's USDC token account
-    #[account(mut)]
-    pub payer_token_account: Account<'info, TokenAccount>,
-
-    /// Recipient's USDC token account
-    #[account(
-        mut,
-        constraint = recipient_token_account.owner == x402_config.payment_recipient @ X402Error::InvalidServiceId
-    )]
-    pub recipient_token_account: Account<'info, TokenAccount>,
-
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+    /// Add a discovery tag to an agent (creator)
+    pub fn add_agent_tag(ctx: Context<AddAgentTag>, tag: String) -> Result<()> {
+        instructions::add_agent_tag::handler(ctx, &tag)
+    }
 
-#[derive(Accounts)]
-#[instruction(amount: u64, service_id: String, nonce: u64, service_params: Vec<u8>)]
-pub struct CallAgentService<'info> {
-    #[account(mut)]
-    pub caller_agent: Account<'info, Agent>,
-
-    #[account(mut)]
-    pub target_agent: Account<'info, Agent>,
-
-    #[account(
-        mut,
-        seeds = [b"x402_config", target_agent.key().as_ref()],
-        bump = target_x402_config.bump
-    )]
-    pub target_x402_config: Account<'info, X402Config>,
-
-    #[account(
-        init,
-        payer = caller_authority,
-        space = 8 + X402PaymentRecord::INIT_SPACE,
-        seeds = [
-            b"payment_record",
-            target_agent.key().as_ref(),
-            caller_agent.key().as_ref(),
-            &nonce.to_le_bytes()
-        ],
-        bump
-    )]
-    pub payment_record: Account<'info, X402PaymentRecord>,
-
-    #[account(
-        mut,
-        constraint = caller_authority.key() == caller_agent.creator @ X402Error::InvalidServiceId
-    )]
-    pub caller_authority: Signer<'info>,
-
-    /// Caller's USDC token account
-    #[account(mut)]
-    pub caller_token_account: Account<'info, TokenAccount>,
-
-    /// Target's USDC token account
-    #[account(
-        mut,
-        constraint = target_token_account.owner == target_x402_config.payment_recipient @ X402Error::InvalidServiceId
-    )]
-    pub target_token_account: Account<'info, TokenAccount>,
-
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+    /// Remove a discovery tag from an agent (creator)
+    pub fn remove_agent_tag(ctx: Context<RemoveAgentTag>, tag: String) -> Result<()> {
+        instructions::remove_agent_tag::handler(ctx, &tag)
+    }
+
+    /// Rename an agent for a fee, subject to a cooldown (creator)
+    pub fn rename_agent(ctx: Context<RenameAgent>, new_name: String, new_symbol: String) -> Result<()> {
+        instructions::rename_agent::handler(ctx, &new_name, &new_symbol)
+    }
+
+    /// Cap each buy at a share of the remaining curve tokens (creator)
+    pub fn set_max_buy(ctx: Context<SetMaxBuy>, max_buy_bps_of_reserves: u16) -> Result<()> {
+        instructions::set_max_buy::handler(ctx, max_buy_bps_of_reserves)
+    }
+
+    /// Choose whether the unsold curve tokens are burned at graduation
+    /// (creator)
+    pub fn set_graduation_burn(ctx: Context<SetGraduationBurn>, burn_remaining: bool) -> Result<()> {
+        instructions::set_graduation_burn::handler(ctx, burn_remaining)
+    }
+
+    /// Claim the creator fees streamed out so far (creator)
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        instructions::claim_creator_fees::handler(ctx)
+    }
+
+    /// Cap agent creations per rolling window, effective immediately
+    /// (factory authority)
+    pub fn set_creation_rate_limit(
+        ctx: Context<SetCreationRateLimit>,
+        max_creations_per_window: u32,
+        window_secs: i64,
+    ) -> Result<()> {
+        instructions::set_creation_rate_limit::handler(ctx, max_creations_per_window, window_secs)
+    }
+
+    /// Set the lamport value of the quote mint used to cap disputes and
+    /// insurance, effective immediately (factory authority)
+    pub fn set_quote_lamport_rate(ctx: Context<SetQuoteLamportRate>, lamports: u64) -> Result<()> {
+        instructions::set_quote_lamport_rate::handler(ctx, lamports)
+    }
+
+    /// Reserve an agent name or symbol for a verified brand, or release it
+    /// with a default owner (factory authority)
+    pub fn reserve_name(ctx: Context<ReserveName>, name_hash: [u8; 32], owner: Pubkey) -> Result<()> {
+        instructions::reserve_name::handler(ctx, name_hash, owner)
+    }
+
+    /// Commit to an agent launch by the hash of its fields and a salt, so
+    /// its name and symbol stay hidden until `reveal_agent`
+    pub fn commit_agent(ctx: Context<CommitAgent>, commit_hash: [u8; 32]) -> Result<()> {
+        instructions::commit_agent::handler(ctx, commit_hash)
+    }
+
+    /// Create a committed agent in a later slot than its commitment
+    #[allow(clippy::too_many_arguments)]
+    pub fn reveal_agent<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevealAgent<'info>>,
+        name: String,
+        symbol: String,
+        description: String,
+        agent_instructions: String,
+        model: String,
+        tags: Vec<String>,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_agent::handler(
+            ctx,
+            &name,
+            &symbol,
+            &description,
+            &agent_instructions,
+            &model,
+            &tags,
+            salt,
+        )
+    }
+
+    /// Create an agent with `reserve_bps` of its supply (at most 10%) held
+    /// in a listing reserve until `release_delay_secs` after graduation
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_agent_with_listing_reserve<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateAgentWithListingReserve<'info>>,
+        name: String,
+        symbol: String,
+        description: String,
+        agent_instructions: String,
+        model: String,
+        tags: Vec<String>,
+        reserve_bps: u16,
+        release_delay_secs: i64,
+    ) -> Result<()> {
+        instructions::create_agent_with_listing_reserve::handler(
+            ctx,
+            &name,
+            &symbol,
+            &description,
+            &agent_instructions,
+            &model,
+            &tags,
+            reserve_bps,
+            release_delay_secs,
+        )
+    }
+
+    /// Release listing reserve tokens to an exchange or market maker once
+    /// unlocked (creator)
+    pub fn release_listing_reserve(ctx: Context<ReleaseListingReserve>, amount: u64) -> Result<()> {
+        instructions::release_listing_reserve::handler(ctx, amount)
+    }
+
+    /// Hard-stop x402 payments to an agent, e.g. after its operator keys were
+    /// compromised (factory authority)
+    pub fn freeze_x402(ctx: Context<FreezeX402>) -> Result<()> {
+        instructions::freeze_x402::handler(ctx, true)
+    }
+
+    /// Lift a freeze on an agent's x402 payments (factory authority)
+    pub fn thaw_x402(ctx: Context<FreezeX402>) -> Result<()> {
+        instructions::freeze_x402::handler(ctx, false)
+    }
+
+    /// Rotate an agent's x402 payment recipient, keeping the old one valid
+    /// for a grace window (creator only)
+    pub fn rotate_x402_recipient(
+        ctx: Context<RotateX402Recipient>,
+        new_recipient: Pubkey,
+        grace_secs: i64,
+    ) -> Result<()> {
+        instructions::rotate_x402_recipient::handler(ctx, new_recipient, grace_secs)
+    }
+
+    /// Graduate every eligible agent passed in remaining accounts, for
+    /// clearing a backlog of threshold-crossed agents (permissionless)
+    pub fn graduate_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GraduateBatch<'info>>,
+    ) -> Result<()> {
+        instructions::graduate_batch::handler(ctx)
+    }
+
+    /// Create an agent whose creator fee is waived or routed entirely to its
+    /// staking pool, for community-first launches with no creator take
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_agent_with_fee_mode(
+        ctx: Context<CreateAgent>,
+        name: String,
+        symbol: String,
+        description: String,
+        agent_instructions: String,
+        model: String,
+        tags: Vec<String>,
+        creator_fee_mode: CreatorFeeMode,
+    ) -> Result<()> {
+        instructions::create_agent::handler(
+            ctx,
+            &name,
+            &symbol,
+            &description,
+            &agent_instructions,
+            &model,
+            &tags,
+            BondingCurve::new(),
+            creator_fee_mode,
+        )
+    }
+
+    /// Override the platform and creator fees on an agent's trades, up to
+    /// the standard fees (factory authority)
+    pub fn set_agent_fee_override(
+        ctx: Context<SetAgentFeeOverride>,
+        platform_fee_bps: u16,
+        creator_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::set_agent_fee_override::handler(ctx, platform_fee_bps, creator_fee_bps)
+    }
+
+    /// Route `bps[i]` of every x402 payment to the i-th worker agent's DAO
+    /// treasury; pass the worker agents' DAO treasuries as remaining accounts
+    /// in `bps` order (creator)
+    pub fn set_revenue_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetRevenueSplit<'info>>,
+        bps: Vec<u16>,
+    ) -> Result<()> {
+        instructions::set_revenue_split::handler(ctx, &bps)
+    }
+
+    /// Set how long after settlement the agent's payments can be disputed
+    /// (creator)
+    pub fn set_chargeback_window(
+        ctx: Context<SetChargebackWindow>,
+        chargeback_window_secs: u64,
+    ) -> Result<()> {
+        instructions::set_chargeback_window::handler(ctx, chargeback_window_secs)
+    }
+
+    /// Close a payment to disputes after its chargeback window
+    pub fn finalize_payment(ctx: Context<FinalizePayment>) -> Result<()> {
+        instructions::finalize_payment::handler(ctx)
+    }
+
+    /// Deprecate a service, rejecting new payments for it from `sunset_ts`
+    /// (creator)
+    pub fn deprecate_service(
+        ctx: Context<DeprecateService>,
+        service_id: [u8; 32],
+        sunset_ts: i64,
+    ) -> Result<()> {
+        instructions::deprecate_service::handler(ctx, service_id, sunset_ts)
+    }
+
+    /// Open a spending report for a paying wallet or agent, updated by the
+    /// x402 payments passing it
+    pub fn create_consumer_stats(ctx: Context<CreateConsumerStats>, consumer: Pubkey) -> Result<()> {
+        instructions::create_consumer_stats::handler(ctx, consumer)
+    }
+
+    /// Lock `rate_per_sec * max_duration` and stream it to an agent per
+    /// second
+    pub fn start_stream(
+        ctx: Context<StartStream>,
+        rate_per_sec: u64,
+        max_duration: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::start_stream::handler(ctx, rate_per_sec, max_duration, nonce)
+    }
+
+    /// Pay the provider what a stream has streamed so far
+    pub fn withdraw_streamed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawStreamed<'info>>,
+    ) -> Result<()> {
+        instructions::withdraw_streamed::handler(ctx)
+    }
+
+    /// Stop a stream and refund the unstreamed remainder (payer)
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        instructions::cancel_stream::handler(ctx)
+    }
+
+    /// Top up a share of creator fees into the agent's x402 treasury
+    /// (creator)
+    pub fn set_x402_top_up(ctx: Context<SetX402TopUp>, x402_top_up_bps: u16) -> Result<()> {
+        instructions::set_x402_top_up::handler(ctx, x402_top_up_bps)
+    }
+
+    /// Sweep the pending top-up into the agent's x402 treasury
+    pub fn collect_x402_top_up(ctx: Context<CollectX402TopUp>) -> Result<()> {
+        instructions::collect_x402_top_up::handler(ctx)
+    }
+
+    /// Deposit revenue earned outside x402 into an agent's staking pool or
+    /// DAO treasury, tagged with its source (permissionless)
+    pub fn deposit_revenue(
+        ctx: Context<DepositRevenue>,
+        pool: RevenuePool,
+        amount: u64,
+        source: [u8; 32],
+    ) -> Result<()> {
+        instructions::deposit_revenue::handler(ctx, pool, amount, source)
+    }
+
+    /// Set the notice given before minimum price increases take effect
+    /// (creator)
+    pub fn set_x402_price_notice(ctx: Context<SetX402PriceNotice>, price_notice_secs: i64) -> Result<()> {
+        instructions::set_x402_price_notice::handler(ctx, price_notice_secs)
+    }
+
+    /// Return the agent's capabilities, service prices, accepted mints and
+    /// operator keys as an `AgentManifest`
+    pub fn get_agent_manifest<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetAgentManifest<'info>>,
+    ) -> Result<AgentManifest> {
+        instructions::get_agent_manifest::handler(ctx)
+    }
+
+    /// Return the name, size and layout version of the account type with
+    /// `discriminator` as a `LayoutInfo`
+    pub fn get_layout_info(
+        ctx: Context<GetLayoutInfo>,
+        discriminator: [u8; 8],
+    ) -> Result<LayoutInfo> {
+        instructions::get_layout_info::handler(ctx, discriminator)
+    }
+
+    /// Propose a price for another agent's service, payable through
+    /// `call_agent_service` once accepted (caller agent's creator)
+    pub fn propose_terms(
+        ctx: Context<ProposeTerms>,
+        service_id: [u8; 32],
+        amount: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::propose_terms::handler(ctx, service_id, amount, expires_at)
+    }
+
+    /// Accept terms proposed for one of the creator's agents' services
+    /// (target agent's creator)
+    pub fn accept_terms(ctx: Context<AcceptTerms>, amount: u64) -> Result<()> {
+        instructions::accept_terms::handler(ctx, amount)
+    }
+
+    /// Withdraw or clean up proposed terms (proposer)
+    pub fn close_terms(ctx: Context<CloseTerms>) -> Result<()> {
+        instructions::close_terms::handler(ctx)
+    }
+
+    // ============================================================================
+    // Milestone Job Instructions
+    // ============================================================================
 
+    /// Escrow a job paid out per milestone to an agent
+    pub fn create_job(
+        ctx: Context<CreateJob>,
+        milestone_amounts: Vec<u64>,
+        service_id: [u8; 32],
+        deadline: i64,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::create_job::handler(ctx, milestone_amounts, service_id, deadline, nonce)
+    }
+
+    /// Deliver a job milestone (agent's creator)
+    pub fn submit_milestone(
+        ctx: Context<SubmitMilestone>,
+        index: u8,
+        deliverable_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::submit_milestone::handler(ctx, index, deliverable_hash)
+    }
+
+    /// Pay a submitted milestone to the provider (payer, or anyone after
+    /// its review period)
+    pub fn release_milestone<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleaseMilestone<'info>>,
+        index: u8,
+    ) -> Result<()> {
+        instructions::release_milestone::handler(ctx, index)
+    }
+
+    /// Dispute a submitted milestone within its review period (payer)
+    pub fn dispute_milestone(
+        ctx: Context<DisputeMilestone>,
+        index: u8,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::dispute_milestone::handler(ctx, index, evidence_hash)
+    }
+
+    /// Vote on the share of a disputed milestone refunded to the payer as
+    /// an arbitration council member; settles it once the threshold agrees
+    pub fn vote_milestone_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VoteMilestoneDispute<'info>>,
+        index: u8,
+        refund_bps: u16,
+    ) -> Result<()> {
+        instructions::vote_milestone_dispute::handler(ctx, index, refund_bps)
+    }
+
+    /// Refund the milestones left unsubmitted past the job's deadline (payer)
+    pub fn cancel_job(ctx: Context<CancelJob>) -> Result<()> {
+        instructions::cancel_job::handler(ctx)
+    }
+
+    /// Clean up a settled job (payer)
+    pub fn close_job(ctx: Context<CloseJob>) -> Result<()> {
+        instructions::close_job::handler(ctx)
+    }
+
+    // ============================================================================
+    // Work Queue Instructions
+    // ============================================================================
+
+    /// Set the workers serving the agent's work queue, opening it if needed
+    /// (creator)
+    pub fn set_queue_workers(ctx: Context<SetQueueWorkers>, workers: Vec<Pubkey>) -> Result<()> {
+        instructions::set_queue_workers::handler(ctx, workers)
+    }
+
+    /// Queue a paid work request for an agent's workers
+    pub fn enqueue_task(
+        ctx: Context<EnqueueTask>,
+        params_hash: [u8; 32],
+        max_price: u64,
+    ) -> Result<()> {
+        instructions::enqueue_task::handler(ctx, params_hash, max_price)
+    }
+
+    /// Claim a queued task for the agent's service timeout (queue worker)
+    pub fn claim_task(ctx: Context<ClaimTask>) -> Result<()> {
+        instructions::claim_task::handler(ctx)
+    }
+
+    /// Deliver a claimed task, charging its price and refunding the rest
+    /// (claiming worker)
+    pub fn complete_task<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CompleteTask<'info>>,
+        price: u64,
+        result_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::complete_task::handler(ctx, price, result_hash)
+    }
+
+    /// Withdraw a task no worker is serving, refunding it (requester)
+    pub fn withdraw_task(ctx: Context<WithdrawTask>) -> Result<()> {
+        instructions::withdraw_task::handler(ctx)
+    }
+
+    // ============================================================================
+    // Trial Session Instructions
+    // ============================================================================
+
+    /// Set the refundable deposit for trial sessions, 0 to stop them (creator)
+    pub fn set_trial_deposit(ctx: Context<SetTrialDeposit>, trial_deposit: u64) -> Result<()> {
+        instructions::set_trial_deposit::handler(ctx, trial_deposit)
+    }
+
+    /// Open a free-tier trial session, escrowing the trial deposit (caller)
+    pub fn open_trial(ctx: Context<OpenTrial>, service_id: [u8; 32]) -> Result<()> {
+        instructions::open_trial::handler(ctx, service_id)
+    }
+
+    /// End a trial session before it expires, refunding the deposit (caller)
+    pub fn end_trial(ctx: Context<EndTrial>) -> Result<()> {
+        instructions::end_trial::handler(ctx)
+    }
+
+    /// Forfeit an abandoned trial session's deposit to the provider
+    /// (permissionless)
+    pub fn forfeit_trial(ctx: Context<ForfeitTrial>) -> Result<()> {
+        instructions::forfeit_trial::handler(ctx)
+    }
+
+    // ============================================================================
+    // Reputation Instructions
+    // ============================================================================
+
+    /// Post an agent's reputation from an external system, 0 weight to
+    /// withdraw it (factory authority or reputation attestor)
+    pub fn attest_reputation(
+        ctx: Context<AttestReputation>,
+        source: [u8; 20],
+        score_bps: u16,
+        weight_bps: u16,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::attest_reputation::handler(ctx, source, score_bps, weight_bps, evidence_hash)
+    }
+
+    /// Rate the service behind a settled payment with 1 to 5 stars, weighted
+    /// by the amount paid; once per payment (payer)
+    pub fn rate_service(ctx: Context<RateService>, stars: u8) -> Result<()> {
+        instructions::rate_service::handler(ctx, stars)
+    }
+
+    // ============================================================================
+    // Public Goods Instructions
+    // ============================================================================
+
+    /// Pay an agent's accrued public goods fees to the factory's public goods
+    /// recipient (permissionless)
+    pub fn collect_public_goods_fees(ctx: Context<CollectPublicGoodsFees>) -> Result<()> {
+        instructions::collect_public_goods_fees::handler(ctx)
+    }
+}
@@ -0,0 +1,479 @@
+//! Addresses of the program's PDAs, the same seeds its account constraints
+//! use. Off-chain crates use them through the `no-entrypoint` feature rather
+//! than re-deriving seeds by hand.
+
+use anchor_lang::prelude::Pubkey;
+
+use crate::state::NameReservation;
+use crate::ID as PROGRAM_ID;
+
+/// Factory singleton: `["factory"]`
+pub fn find_factory_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"factory"], &PROGRAM_ID)
+}
+
+/// Signer of `emit_cpi!` self-invocations: `["__event_authority"]`
+pub fn find_event_authority_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID)
+}
+
+/// Trending leaderboard singleton: `["trending"]`
+pub fn find_trending_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"trending"], &PROGRAM_ID)
+}
+
+/// Timelocked admin action: `["admin_action", action_id (le)]`
+pub fn find_admin_action_pda(action_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"admin_action", action_id.to_le_bytes().as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Insurance fund singleton: `["insurance_fund"]`
+pub fn find_insurance_fund_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"insurance_fund"], &PROGRAM_ID)
+}
+
+/// Protocol-owned liquidity singleton: `["protocol_liquidity"]`
+pub fn find_protocol_liquidity_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"protocol_liquidity"], &PROGRAM_ID)
+}
+
+/// Creator track record: `["creator_stats", creator]`
+pub fn find_creator_stats_pda(creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"creator_stats", creator.as_ref()], &PROGRAM_ID)
+}
+
+/// Reservation of an agent name or symbol:
+/// `["name_reservation", NameReservation::name_hash(name)]`
+pub fn find_name_reservation_pda(name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"name_reservation",
+            NameReservation::name_hash(name).as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Sealed agent launch:
+/// `["agent_commit", creator, AgentCommit::commit_hash(..)]`
+pub fn find_agent_commit_pda(creator: &Pubkey, commit_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"agent_commit", creator.as_ref(), commit_hash.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Agent account: `["agent", agent_id (le)]`
+pub fn find_agent_pda(agent_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"agent", agent_id.to_le_bytes().as_ref()], &PROGRAM_ID)
+}
+
+/// Agent token mint: `["mint", agent]`
+pub fn find_mint_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"mint", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Agent ownership NFT mint: `["ownership_mint", agent]`
+pub fn find_ownership_mint_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"ownership_mint", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Metaplex metadata of `mint`: `["metadata", metadata_program, mint]`,
+/// owned by the Metaplex token metadata program
+pub fn find_metadata_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    let metadata_program = anchor_spl::metadata::ID;
+    Pubkey::find_program_address(
+        &[b"metadata", metadata_program.as_ref(), mint.as_ref()],
+        &metadata_program,
+    )
+}
+
+/// Agent's curve SOL vault: `["sol_vault", agent]`
+pub fn find_sol_vault_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"sol_vault", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Agent's platform fee vault: `["platform_fee_vault", agent]`
+pub fn find_platform_fee_vault_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"platform_fee_vault", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Agent's creator fee vault: `["creator_fee_vault", agent]`
+pub fn find_creator_fee_vault_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"creator_fee_vault", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// DCA schedule: `["dca", agent, owner]`
+pub fn find_dca_pda(agent: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"dca", agent.as_ref(), owner.as_ref()], &PROGRAM_ID)
+}
+
+/// DCA schedule's SOL deposit: `["dca_vault", dca]`
+pub fn find_dca_vault_pda(dca: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"dca_vault", dca.as_ref()], &PROGRAM_ID)
+}
+
+/// Presale vault: `["presale", agent]`
+pub fn find_presale_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"presale", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Presale commitment: `["commitment", presale, committer]`
+pub fn find_commitment_pda(presale: &Pubkey, committer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"commitment", presale.as_ref(), committer.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Holder snapshot: `["snapshot", agent, snapshot_id (le)]`
+pub fn find_snapshot_pda(agent: &Pubkey, snapshot_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"snapshot",
+            agent.as_ref(),
+            snapshot_id.to_le_bytes().as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Buyer marker, stamped with the last buy slot: `["buyer_record", agent, buyer]`
+pub fn find_buyer_record_pda(agent: &Pubkey, buyer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"buyer_record", agent.as_ref(), buyer.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Early buyer bonus pool: `["early_buyer_rewards", agent]`
+pub fn find_early_buyer_rewards_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"early_buyer_rewards", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Early buyer bonus token vault: `["early_buyer_vault", rewards]`
+pub fn find_early_buyer_vault_pda(rewards: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"early_buyer_vault", rewards.as_ref()], &PROGRAM_ID)
+}
+
+/// Early buyer bonus claim marker: `["early_buyer_claim", rewards, buyer]`
+pub fn find_early_buyer_claim_pda(rewards: &Pubkey, buyer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"early_buyer_claim", rewards.as_ref(), buyer.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Liquidity mining for a graduated agent's pool: `["liquidity_mining", agent]`
+pub fn find_liquidity_mining_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"liquidity_mining", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Staked LP token vault: `["lm_lp_vault", mining]`
+pub fn find_lm_lp_vault_pda(mining: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"lm_lp_vault", mining.as_ref()], &PROGRAM_ID)
+}
+
+/// Liquidity mining reward vault: `["lm_reward_vault", mining]`
+pub fn find_lm_reward_vault_pda(mining: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"lm_reward_vault", mining.as_ref()], &PROGRAM_ID)
+}
+
+/// LP stake position: `["lm_position", mining, owner]`
+pub fn find_lm_position_pda(mining: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"lm_position", mining.as_ref(), owner.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Token migration into an agent token: `["migration", agent]`
+pub fn find_migration_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"migration", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Migrated (old) token vault: `["migration_old_vault", migration]`
+pub fn find_migration_old_vault_pda(migration: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"migration_old_vault", migration.as_ref()], &PROGRAM_ID)
+}
+
+/// Migration payout vault: `["migration_vault", migration]`
+pub fn find_migration_vault_pda(migration: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"migration_vault", migration.as_ref()], &PROGRAM_ID)
+}
+
+/// Index basket: `["index_basket", creator, basket_id (le)]`
+pub fn find_index_basket_pda(creator: &Pubkey, basket_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"index_basket", creator.as_ref(), &basket_id.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Index basket token mint: `["basket_mint", basket]`
+pub fn find_basket_mint_pda(basket: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"basket_mint", basket.as_ref()], &PROGRAM_ID)
+}
+
+/// Keeper task: `["keeper_task", owner, task_id (le)]`
+pub fn find_keeper_task_pda(owner: &Pubkey, task_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"keeper_task", owner.as_ref(), &task_id.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Candle accumulator: `["price_history", agent]`
+pub fn find_price_history_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"price_history", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Token lock: `["lock", agent, owner, lock_id (le)]`
+pub fn find_lock_pda(agent: &Pubkey, owner: &Pubkey, lock_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"lock",
+            agent.as_ref(),
+            owner.as_ref(),
+            lock_id.to_le_bytes().as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Token lock vault: `["lock_vault", lock]`
+pub fn find_lock_vault_pda(lock: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"lock_vault", lock.as_ref()], &PROGRAM_ID)
+}
+
+/// Listing reserve: `["listing_reserve", agent]`
+pub fn find_listing_reserve_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"listing_reserve", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Listing reserve token vault: `["listing_reserve_vault", listing_reserve]`
+pub fn find_listing_reserve_vault_pda(listing_reserve: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"listing_reserve_vault", listing_reserve.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Staking pool: `["staking_pool", agent]`
+pub fn find_staking_pool_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"staking_pool", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Staking pool vault: `["stake_vault", staking_pool]`
+pub fn find_stake_vault_pda(staking_pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"stake_vault", staking_pool.as_ref()], &PROGRAM_ID)
+}
+
+/// Stake position: `["stake", staking_pool, owner]`
+pub fn find_stake_position_pda(staking_pool: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"stake", staking_pool.as_ref(), owner.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Governance: `["governance", agent]`
+pub fn find_governance_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"governance", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Proposal: `["proposal", agent, proposal_id (le)]`
+pub fn find_proposal_pda(agent: &Pubkey, proposal_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"proposal",
+            agent.as_ref(),
+            proposal_id.to_le_bytes().as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Vote record: `["vote", proposal, voter]`
+pub fn find_vote_pda(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vote", proposal.as_ref(), voter.as_ref()], &PROGRAM_ID)
+}
+
+/// DAO treasury: `["dao_treasury", agent]`
+pub fn find_dao_treasury_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"dao_treasury", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// X402 payment config: `["x402_config", agent]`
+pub fn find_x402_config_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"x402_config", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// X402 payment record: `["payment_record", agent, payer, nonce (le)]`
+///
+/// For agent-to-agent calls the payer is the caller agent's PDA, not its creator.
+pub fn find_payment_record_pda(agent: &Pubkey, payer: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"payment_record",
+            agent.as_ref(),
+            payer.as_ref(),
+            nonce.to_le_bytes().as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Provider bond: `["provider_bond", agent]`
+pub fn find_provider_bond_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"provider_bond", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Payment dispute: `["dispute", payment_record]`
+pub fn find_dispute_pda(payment_record: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"dispute", payment_record.as_ref()], &PROGRAM_ID)
+}
+
+/// Negotiated service price:
+/// `["service_quote", agent, payer, service_id]`
+pub fn find_service_quote_pda(agent: &Pubkey, payer: &Pubkey, service_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"service_quote",
+            agent.as_ref(),
+            payer.as_ref(),
+            service_id.as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// x402 affiliate registration: `["affiliate", wallet]`
+pub fn find_affiliate_pda(wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"affiliate", wallet.as_ref()], &PROGRAM_ID)
+}
+
+/// Payment's service insurance: `["insurance_policy", payment_record]`
+pub fn find_insurance_policy_pda(payment_record: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"insurance_policy", payment_record.as_ref()], &PROGRAM_ID)
+}
+
+/// Escrowed payment's token account: `["payment_escrow", payment_record]`
+pub fn find_payment_escrow_pda(payment_record: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"payment_escrow", payment_record.as_ref()], &PROGRAM_ID)
+}
+
+/// Agent's x402 revenue split: `["revenue_split", agent]`
+pub fn find_revenue_split_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"revenue_split", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Payer's x402 spending report: `["consumer_stats", payer]`
+pub fn find_consumer_stats_pda(payer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"consumer_stats", payer.as_ref()], &PROGRAM_ID)
+}
+
+/// Payment stream: `["payment_stream", agent, payer, nonce_le]`, keyed by the
+/// agent's x402 nonce like payment records
+pub fn find_payment_stream_pda(agent: &Pubkey, payer: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"payment_stream",
+            agent.as_ref(),
+            payer.as_ref(),
+            nonce.to_le_bytes().as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Payment stream's token account: `["stream_escrow", stream]`
+pub fn find_stream_escrow_pda(stream: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"stream_escrow", stream.as_ref()], &PROGRAM_ID)
+}
+
+/// Agent's x402 treasury, a wrapped SOL token account: `["x402_treasury", agent]`
+pub fn find_x402_treasury_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"x402_treasury", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Terms negotiated between two agents:
+/// `["service_agreement", caller_agent, target_agent, service_id]`
+pub fn find_service_agreement_pda(
+    caller_agent: &Pubkey,
+    target_agent: &Pubkey,
+    service_id: &[u8; 32],
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"service_agreement",
+            caller_agent.as_ref(),
+            target_agent.as_ref(),
+            service_id.as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Milestone job: `["job", agent, payer, nonce_le]`, keyed by the agent's
+/// x402 nonce like payment streams
+pub fn find_job_pda(agent: &Pubkey, payer: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"job",
+            agent.as_ref(),
+            payer.as_ref(),
+            nonce.to_le_bytes().as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Milestone job's token account: `["job_escrow", job]`
+pub fn find_job_escrow_pda(job: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"job_escrow", job.as_ref()], &PROGRAM_ID)
+}
+
+/// Agent's work queue: `["work_queue", agent]`
+pub fn find_work_queue_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"work_queue", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Queued task: `["work_task", agent, task_id_le]`, numbered by the queue
+pub fn find_work_task_pda(agent: &Pubkey, task_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"work_task", agent.as_ref(), task_id.to_le_bytes().as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Queued task's token account: `["task_escrow", task]`
+pub fn find_task_escrow_pda(task: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"task_escrow", task.as_ref()], &PROGRAM_ID)
+}
+
+/// Caller's trial session with an agent: `["trial_session", agent, caller]`
+pub fn find_trial_session_pda(agent: &Pubkey, caller: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"trial_session", agent.as_ref(), caller.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Trial session's deposit token account: `["trial_escrow", session]`
+pub fn find_trial_escrow_pda(session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"trial_escrow", session.as_ref()], &PROGRAM_ID)
+}
+
+/// Reputation imported from other platforms: `["reputation", agent]`
+pub fn find_reputation_pda(agent: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"reputation", agent.as_ref()], &PROGRAM_ID)
+}
+
+/// Rating of a paid service call: `["rating", payment_record]`
+pub fn find_rating_pda(payment_record: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"rating", payment_record.as_ref()], &PROGRAM_ID)
+}
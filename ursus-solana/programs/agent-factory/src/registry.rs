@@ -0,0 +1,118 @@
+//! Discriminators, sizes and layout versions of every account the program
+//! owns, so third-party indexers (webhooks, Geyser consumers) can recognize
+//! and size accounts without vendoring this crate. Also served on-chain by
+//! the `get_layout_info` view.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::state::*;
+
+/// Registry entry for one account type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountLayout {
+    /// Account type name, as in the IDL
+    pub name: &'static str,
+
+    /// First 8 bytes of the account's data
+    pub discriminator: [u8; 8],
+
+    /// Allocated size in bytes, discriminator included; accounts with
+    /// variable-length fields may use less
+    pub size: usize,
+
+    /// Starts at 1 and is bumped whenever the type's layout changes. A bump
+    /// that only appends fields still parses older, shorter accounts; one
+    /// that moves fields does not.
+    pub version: u16,
+}
+
+/// `AccountLayout` as returned by `get_layout_info`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LayoutInfo {
+    pub name: String,
+    pub discriminator: [u8; 8],
+    pub size: u32,
+    pub version: u16,
+}
+
+impl From<&AccountLayout> for LayoutInfo {
+    fn from(layout: &AccountLayout) -> Self {
+        Self {
+            name: layout.name.to_string(),
+            discriminator: layout.discriminator,
+            size: layout.size as u32,
+            version: layout.version,
+        }
+    }
+}
+
+macro_rules! account_layouts {
+    ($($account:ident => $version:expr),* $(,)?) => {
+        /// Every account type the program owns
+        pub const ACCOUNT_LAYOUTS: &[AccountLayout] = &[$(
+            AccountLayout {
+                name: stringify!($account),
+                discriminator: <$account as Discriminator>::DISCRIMINATOR,
+                size: 8 + <$account>::INIT_SPACE,
+                version: $version,
+            },
+        )*];
+    };
+}
+
+account_layouts! {
+    AgentFactory => 1,
+    Agent => 3,
+    AgentCommit => 1,
+    AgentReputation => 1,
+    Affiliate => 1,
+    BuyerRecord => 1,
+    ConsumerStats => 1,
+    CreatorStats => 1,
+    DaoTreasury => 1,
+    DcaSchedule => 1,
+    Dispute => 1,
+    EarlyBuyerClaim => 1,
+    EarlyBuyerRewards => 1,
+    Governance => 1,
+    HolderSnapshot => 1,
+    IndexBasket => 1,
+    InsuranceFund => 1,
+    InsurancePolicy => 1,
+    Job => 1,
+    KeeperTask => 1,
+    LiquidityMining => 1,
+    LiquidityMiningPosition => 1,
+    ListingReserve => 1,
+    NameReservation => 1,
+    PaymentStream => 1,
+    Presale => 1,
+    PresaleCommitment => 1,
+    PriceHistory => 1,
+    Proposal => 1,
+    ProtocolLiquidity => 1,
+    ProviderBond => 1,
+    QueuedAdminAction => 1,
+    RevenueSplit => 1,
+    ServiceAgreement => 1,
+    ServiceQuote => 1,
+    ServiceRating => 1,
+    StakePosition => 1,
+    StakingPool => 1,
+    TokenLock => 1,
+    TokenMigration => 1,
+    TrendingLeaderboard => 1,
+    TrialSession => 1,
+    VoteRecord => 1,
+    WorkQueue => 1,
+    WorkTask => 1,
+    X402Config => 1,
+    X402PaymentRecord => 2,
+}
+
+/// Layout of the account type whose data starts with `discriminator`
+pub fn layout_of(discriminator: &[u8]) -> Option<&'static AccountLayout> {
+    ACCOUNT_LAYOUTS
+        .iter()
+        .find(|layout| discriminator.get(..8) == Some(&layout.discriminator[..]))
+}
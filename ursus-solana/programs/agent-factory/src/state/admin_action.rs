@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use crate::state::{FeeDiscountTier, FEE_DISCOUNT_TIERS, MAX_ALLOWED_MODELS, MAX_ARBITERS, MODEL_ID_LEN};
+
+/// Timelock delay of a new factory, and the bounds governance of the delay
+/// itself must respect
+pub const DEFAULT_TIMELOCK_DELAY_SECS: i64 = 2 * 24 * 60 * 60;
+pub const MIN_TIMELOCK_DELAY_SECS: i64 = 60 * 60;
+pub const MAX_TIMELOCK_DELAY_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Factory setting change that must wait out the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AdminAction {
+    /// Change the agent creation fee (in lamports)
+    SetCreationFee {
+        new_fee: u64,
+    },
+    
+    /// Accept the creation fee in `quote_mint` (e.g. USDC) at `fee` base
+    /// units; a default mint accepts SOL only
+    SetQuoteCreationFee {
+        quote_mint: Pubkey,
+        fee: u64,
+    },
+    
+    /// Send platform fees to a new treasury
+    SetPlatformTreasury {
+        new_treasury: Pubkey,
+    },
+    
+    /// Change the timelock delay itself
+    SetTimelockDelay {
+        delay_secs: i64,
+    },
+    
+    /// Hand the factory to a new authority; `admin_is_program` marks a PDA
+    /// authority such as a multisig vault
+    SetAuthority {
+        new_authority: Pubkey,
+        admin_is_program: bool,
+    },
+    
+    /// Change the share of platform fees routed to the insurance fund
+    SetInsuranceFee {
+        insurance_fee_bps: u16,
+    },
+    
+    /// Set the platform token, its agent's staking pool and the fee discount
+    /// tiers for its stakers (`platform_mint` default = discounts off)
+    SetFeeDiscounts {
+        platform_mint: Pubkey,
+        staking_pool: Pubkey,
+        tiers: [FeeDiscountTier; FEE_DISCOUNT_TIERS],
+    },
+    
+    /// Change the share of graduation liquidity kept as protocol-owned
+    /// liquidity
+    SetProtocolLiquidity {
+        protocol_liquidity_bps: u16,
+    },
+    
+    /// Replace the arbitration council and its vote threshold
+    SetArbitrationCouncil {
+        arbiters: [Pubkey; MAX_ARBITERS],
+        threshold: u8,
+    },
+    
+    /// Change the agent rename fee (in lamports)
+    SetRenameFee {
+        new_fee: u64,
+    },
+    
+    /// Change the period new agents stream creator fees over (0 = paid out
+    /// per trade)
+    SetCreatorFeeVesting {
+        vesting_secs: i64,
+    },
+    
+    /// Replace the allowlist of models agents may claim to run on (all
+    /// unused = any model)
+    SetAllowedModels {
+        models: [[u8; MODEL_ID_LEN]; MAX_ALLOWED_MODELS],
+    },
+    
+    /// Surge-price the creation fee: `surge_bps` surcharge per recent
+    /// creation, with demand halving every `half_life_secs` (0 bps = off)
+    SetCreationSurge {
+        surge_bps: u16,
+        half_life_secs: i64,
+    },
+    
+    /// Designate the key posting imported agent reputation besides the
+    /// authority (default = authority only)
+    SetReputationAttestor {
+        attestor: Pubkey,
+    },
+    
+    /// Route a share of platform fees to a public goods recipient, e.g. an
+    /// ecosystem grants program (0 bps = off; the recipient still collects
+    /// what agents accrued before)
+    SetPublicGoodsFee {
+        recipient: Pubkey,
+        fee_bps: u16,
+    },
+}
+
+impl AdminAction {
+    pub const INIT_SPACE: usize =
+        1 +     // variant
+        MODEL_ID_LEN * MAX_ALLOWED_MODELS; // models (largest variant: SetAllowedModels)
+}
+
+/// Lifecycle stage reported by `AdminActionEvent`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdminActionStage {
+    Queued,
+    Executed,
+    Cancelled,
+}
+
+/// Admin action waiting for its timelock to expire
+#[account]
+#[derive(InitSpace)]
+pub struct QueuedAdminAction {
+    /// Sequential id, starting at 0
+    pub action_id: u64,
+    
+    /// Change applied on execution
+    pub action: AdminAction,
+    
+    /// Queue timestamp
+    pub queued_at: i64,
+    
+    /// Earliest execution time
+    pub eta: i64,
+    
+    /// Account that paid the rent and gets it back
+    pub rent_payer: Pubkey,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl QueuedAdminAction {
+    pub const INIT_SPACE: usize =
+        8 +     // action_id
+        AdminAction::INIT_SPACE + // action
+        8 +     // queued_at
+        8 +     // eta
+        32 +    // rent_payer
+        1;      // bump
+}
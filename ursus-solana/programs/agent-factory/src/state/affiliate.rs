@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+/// Largest share of a service payment an affiliate can ask for or a
+/// provider can allow
+pub const MAX_AFFILIATE_BPS: u16 = 5000;
+
+/// Marketplace or aggregator routing x402 traffic to agents. Earns its
+/// `fee_bps` of payments it refers, capped per agent by the provider's
+/// `max_affiliate_bps`.
+#[account]
+#[derive(InitSpace)]
+pub struct Affiliate {
+    /// Wallet whose token accounts receive the affiliate's share
+    pub wallet: Pubkey,
+    
+    /// Share of referred payments asked for (bps)
+    pub fee_bps: u16,
+    
+    /// Payments referred so far
+    pub total_referred: u64,
+    
+    /// Earned so far, summed across payment tokens (smallest units)
+    pub total_earned: u64,
+    
+    /// Registration timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Affiliate {
+    pub const INIT_SPACE: usize =
+        32 +    // wallet
+        2 +     // fee_bps
+        8 +     // total_referred
+        8 +     // total_earned
+        8 +     // created_at
+        1;      // bump
+}
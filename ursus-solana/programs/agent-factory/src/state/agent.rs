@@ -1,5 +1,57 @@
 use anchor_lang::prelude::*;
-use super::BondingCurve;
+use anchor_spl::token::TokenAccount;
+use bonding_curve_math::{BPS_DENOMINATOR, CREATOR_FEE_BPS};
+use super::{decay_trending_score, AgentVault, BondingCurve, PriceView, TwapAccumulator};
+use crate::errors::AgentFactoryError;
+
+/// Longest graduation deadline a refund guarantee can promise
+pub const MAX_REFUND_GUARANTEE_DAYS: u16 = 365;
+
+/// Largest share of creator fees an original creator can keep after the
+/// agent changes hands
+pub const MAX_CREATOR_ROYALTY_BPS: u16 = 5000;
+
+/// Discovery tags an agent can carry, and the longest tag
+pub const MAX_AGENT_TAGS: usize = 5;
+pub const MAX_TAG_LEN: usize = 20;
+
+/// Smallest per-buy cap on remaining curve tokens a creator can set, so
+/// the cap can't freeze buying
+pub const MIN_MAX_BUY_BPS: u16 = 50;
+
+/// Creator fee streaming period of a new factory, and the longest one
+/// governance can set
+pub const DEFAULT_CREATOR_FEE_VESTING_SECS: i64 = 7 * 24 * 60 * 60;
+pub const MAX_CREATOR_FEE_VESTING_SECS: i64 = 90 * 24 * 60 * 60;
+
+/// Shortest wait between two renames of an agent
+pub const RENAME_COOLDOWN_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Metaplex symbol of every agent's ownership NFT
+pub const OWNERSHIP_NFT_SYMBOL: &str = "URSUSOWN";
+
+/// Who an agent's creator fee goes to, fixed at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum CreatorFeeMode {
+    /// Creator fee charged and split between the creator, staking pool,
+    /// treasury and original creator
+    #[default]
+    Creator,
+    /// No creator fee is charged on trades
+    Waived,
+    /// Creator fee charged and routed entirely to the staking pool
+    Holders,
+}
+
+impl CreatorFeeMode {
+    /// Creator fee charged on trades (bps)
+    pub fn fee_bps(self) -> u16 {
+        match self {
+            CreatorFeeMode::Waived => 0,
+            CreatorFeeMode::Creator | CreatorFeeMode::Holders => CREATOR_FEE_BPS as u16,
+        }
+    }
+}
 
 #[account]
 #[derive(InitSpace)]
@@ -10,9 +62,22 @@ pub struct Agent {
     /// SPL Token mint address
     pub mint: Pubkey,
     
-    /// Creator's wallet address
+    /// Creator's wallet address, receiving creator fees; the ownership NFT
+    /// holder claims it with `claim_agent_ownership`
     pub creator: Pubkey,
     
+    /// Ownership NFT mint; its holder passes the creator checks (default
+    /// for agents created before ownership NFTs)
+    pub ownership_mint: Pubkey,
+    
+    /// Wallet that created the agent; keeps receiving royalties after the
+    /// creator role changes hands
+    pub original_creator: Pubkey,
+    
+    /// Share of creator fees routed to `original_creator` once `creator`
+    /// differs from it (bps)
+    pub royalty_bps: u16,
+    
     /// Agent name (max 32 chars)
     #[max_len(32)]
     pub name: String,
@@ -33,9 +98,10 @@ pub struct Agent {
     #[max_len(20)]
     pub model: String,
     
-    /// Category (max 20 chars)
-    #[max_len(20)]
-    pub category: String,
+    /// Discovery tags, e.g. "trading", "solana", "news" (up to 5, max 20
+    /// chars each)
+    #[max_len(MAX_AGENT_TAGS, MAX_TAG_LEN)]
+    pub tags: Vec<String>,
     
     /// Creation timestamp
     pub created_at: i64,
@@ -43,6 +109,122 @@ pub struct Agent {
     /// Whether agent has graduated to DEX
     pub is_graduated: bool,
     
+    /// Whether a presale is collecting commitments (curve trading paused)
+    pub is_presale_active: bool,
+    
+    /// Whether sells are rejected in a slot that already has a buy
+    pub same_slot_protection: bool,
+    
+    /// Largest share of the remaining curve tokens a single buy can take
+    /// (bps, 0 = no cap)
+    pub max_buy_bps_of_reserves: u16,
+    
+    /// Refund guarantee: if not graduated by this timestamp, holders can
+    /// redeem tokens for SOL from the reserves (0 = no guarantee)
+    pub refund_deadline: i64,
+    
+    /// Number of holder snapshots recorded (next snapshot id)
+    pub snapshot_count: u64,
+    
+    /// Sequence number of the last trade or payment event (0 = none yet)
+    pub event_sequence: u64,
+    
+    /// Volume-weighted trending score (lamports) as of `trending_slot`,
+    /// halving every `TRENDING_HALF_LIFE_SLOTS`
+    pub trending_score: u64,
+    
+    /// Slot the trending score was last updated at
+    pub trending_slot: u64,
+    
+    /// Wallets that have received tokens from a direct buy
+    pub unique_buyer_count: u64,
+    
+    /// Time-weighted average price accumulator
+    pub twap: TwapAccumulator,
+    
+    /// Share of creator fees routed to the staking pool (bps, 0 = no pool)
+    pub staking_fee_bps: u16,
+    
+    /// Staking fee share held in the creator fee vault until the pool collects it
+    pub pending_staking_rewards: u64,
+    
+    /// Share of creator fees routed to the DAO treasury (bps, 0 = no treasury)
+    pub treasury_fee_bps: u16,
+    
+    /// Treasury fee share held in the creator fee vault until the treasury collects it
+    pub pending_treasury_fees: u64,
+    
+    /// Share of platform fees routed to the insurance fund (bps), synced
+    /// from the factory whenever the fund collects from this agent
+    pub insurance_fee_bps: u16,
+    
+    /// Insurance fee share held in the platform fee vault until the fund collects it
+    pub pending_insurance_fees: u64,
+    
+    /// Royalty share held in the creator fee vault until the original creator claims it
+    pub pending_royalties: u64,
+    
+    /// Last rename timestamp (0 = never renamed)
+    pub last_renamed_at: i64,
+    
+    /// Period the creator's fees stream over before they can be claimed
+    /// (0 = paid out with each trade); fixed from the factory at creation
+    pub creator_fee_vesting_secs: i64,
+    
+    /// Creator fees still streaming as of `creator_fee_stream_updated_at`
+    pub streaming_creator_fees: u64,
+    
+    /// Creator fees streamed out and not yet claimed
+    pub claimable_creator_fees: u64,
+    
+    /// When the streaming balance is fully unlocked
+    pub creator_fee_stream_end: i64,
+    
+    /// Last time the stream was advanced
+    pub creator_fee_stream_updated_at: i64,
+    
+    /// Bump of the curve SOL vault (`["sol_vault", agent]`)
+    pub sol_vault_bump: u8,
+    
+    /// Bump of the platform fee vault (`["platform_fee_vault", agent]`)
+    pub platform_fee_vault_bump: u8,
+    
+    /// Bump of the creator fee vault (`["creator_fee_vault", agent]`)
+    pub creator_fee_vault_bump: u8,
+    
+    /// Slot of the `commit_agent` commitment the agent was revealed from
+    /// (0 = launched without one)
+    pub commit_slot: u64,
+    
+    /// Graduation timestamp (0 = not graduated)
+    pub graduated_at: i64,
+    
+    /// Who the creator fee goes to; `Waived` and `Holders` launches take no
+    /// creator cut
+    pub creator_fee_mode: CreatorFeeMode,
+    
+    /// Share of creator fees topped up into the agent's x402 treasury,
+    /// carved out of the creator's own cut (bps, 0 = off)
+    pub x402_top_up_bps: u16,
+    
+    /// Top-up share held in the creator fee vault until collected into the
+    /// x402 treasury
+    pub pending_x402_top_up: u64,
+    
+    /// Share of platform fees routed to the factory's public goods recipient
+    /// (bps), synced from the factory whenever it collects from this agent
+    pub public_goods_fee_bps: u16,
+    
+    /// Public goods share held in the platform fee vault until collected
+    pub pending_public_goods_fees: u64,
+    
+    /// Creator's choice to burn the unsold curve tokens at graduation instead
+    /// of adding them to the pool
+    pub burn_remaining_at_graduation: bool,
+    
+    /// Slot of the latest curve buy, for same-slot sell protection
+    pub last_buy_slot: u64,
+    
     /// Bonding curve parameters
     pub bonding_curve: BondingCurve,
     
@@ -55,14 +237,52 @@ impl Agent {
         8 +           // agent_id
         32 +          // mint
         32 +          // creator
+        32 +          // ownership_mint
+        32 +          // original_creator
+        2 +           // royalty_bps
         4 + 32 +      // name
         4 + 10 +      // symbol
         4 + 200 +     // description
         4 + 500 +     // instructions
         4 + 20 +      // model
-        4 + 20 +      // category
+        4 + MAX_AGENT_TAGS * (4 + MAX_TAG_LEN) + // tags
         8 +           // created_at
         1 +           // is_graduated
+        1 +           // is_presale_active
+        1 +           // same_slot_protection
+        2 +           // max_buy_bps_of_reserves
+        8 +           // refund_deadline
+        8 +           // snapshot_count
+        8 +           // event_sequence
+        8 +           // trending_score
+        8 +           // trending_slot
+        8 +           // unique_buyer_count
+        TwapAccumulator::INIT_SPACE + // twap
+        2 +           // staking_fee_bps
+        8 +           // pending_staking_rewards
+        2 +           // treasury_fee_bps
+        8 +           // pending_treasury_fees
+        2 +           // insurance_fee_bps
+        8 +           // pending_insurance_fees
+        8 +           // pending_royalties
+        8 +           // last_renamed_at
+        8 +           // creator_fee_vesting_secs
+        8 +           // streaming_creator_fees
+        8 +           // claimable_creator_fees
+        8 +           // creator_fee_stream_end
+        8 +           // creator_fee_stream_updated_at
+        1 +           // sol_vault_bump
+        1 +           // platform_fee_vault_bump
+        1 +           // creator_fee_vault_bump
+        8 +           // commit_slot
+        8 +           // graduated_at
+        1 +           // creator_fee_mode
+        2 +           // x402_top_up_bps
+        8 +           // pending_x402_top_up
+        2 +           // public_goods_fee_bps
+        8 +           // pending_public_goods_fees
+        1 +           // burn_remaining_at_graduation
+        8 +           // last_buy_slot
         BondingCurve::INIT_SPACE + // bonding_curve
         1;            // bump
 
@@ -71,5 +291,333 @@ impl Agent {
         !self.is_graduated && 
         self.bonding_curve.real_sol_reserves >= self.bonding_curve.graduation_threshold
     }
+
+    /// Whether `holder` owns the agent's ownership NFT in `account`, which
+    /// makes them its creator for creator-gated instructions. Agents without
+    /// an ownership NFT fall back to the recorded creator.
+    pub fn is_owned_by(&self, account: &TokenAccount, holder: &Pubkey) -> bool {
+        if self.ownership_mint == Pubkey::default() {
+            return *holder == self.creator;
+        }
+        account.mint == self.ownership_mint && account.owner == *holder && account.amount == 1
+    }
+
+    /// Carve the staking pool's and the treasury's shares, then the original
+    /// creator's royalty and the x402 top-up, out of a creator fee. They stay
+    /// in the creator fee vault (in `pending_staking_rewards`,
+    /// `pending_treasury_fees`, `pending_royalties` and
+    /// `pending_x402_top_up`) until collected. In `Holders` mode all of it goes
+    /// to the staking pool. With a vesting period, the
+    /// creator's own share stays too and streams out from `now`; returns
+    /// `(to_creator, retained_in_vault)`.
+    pub fn split_creator_fee(&mut self, creator_fee: u64, now: i64) -> Result<(u64, u64)> {
+        // Community-first launches hand the whole fee to stakers
+        if self.creator_fee_mode == CreatorFeeMode::Holders {
+            self.pending_staking_rewards = self.pending_staking_rewards
+                .checked_add(creator_fee)
+                .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+            return Ok((0, creator_fee));
+        }
+        
+        let share = |bps: u16| (creator_fee as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let to_staking = share(self.staking_fee_bps);
+        let to_treasury = share(self.treasury_fee_bps).min(creator_fee - to_staking);
+        let to_royalty = if self.royalty_active() {
+            share(self.royalty_bps).min(creator_fee - to_staking - to_treasury)
+        } else {
+            0
+        };
+        let to_top_up = share(self.x402_top_up_bps)
+            .min(creator_fee - to_staking - to_treasury - to_royalty);
+        
+        self.pending_staking_rewards = self.pending_staking_rewards
+            .checked_add(to_staking)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        self.pending_treasury_fees = self.pending_treasury_fees
+            .checked_add(to_treasury)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        self.pending_royalties = self.pending_royalties
+            .checked_add(to_royalty)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        self.pending_x402_top_up = self.pending_x402_top_up
+            .checked_add(to_top_up)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        
+        let retained = to_staking + to_treasury + to_royalty + to_top_up;
+        let to_creator = creator_fee - retained;
+        if self.creator_fee_vesting_secs == 0 {
+            return Ok((to_creator, retained));
+        }
+        self.stream_creator_fee(to_creator, now)?;
+        Ok((0, creator_fee))
+    }
+
+    /// Move the creator fees unlocked since the last update from the stream
+    /// to `claimable_creator_fees`. The stream unlocks linearly until
+    /// `creator_fee_stream_end`.
+    pub fn release_creator_fees(&mut self, now: i64) -> Result<()> {
+        if now <= self.creator_fee_stream_updated_at {
+            return Ok(());
+        }
+        let released = if now >= self.creator_fee_stream_end {
+            self.streaming_creator_fees
+        } else {
+            (self.streaming_creator_fees as u128
+                * (now - self.creator_fee_stream_updated_at) as u128
+                / (self.creator_fee_stream_end - self.creator_fee_stream_updated_at) as u128) as u64
+        };
+        self.streaming_creator_fees -= released;
+        self.claimable_creator_fees = self.claimable_creator_fees
+            .checked_add(released)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        self.creator_fee_stream_updated_at = now;
+        Ok(())
+    }
+
+    /// Add a creator fee to the stream. Whatever is still locked streams out
+    /// together with it over a fresh vesting period from `now`.
+    fn stream_creator_fee(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.release_creator_fees(now)?;
+        self.streaming_creator_fees = self.streaming_creator_fees
+            .checked_add(amount)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        self.creator_fee_stream_end = now
+            .checked_add(self.creator_fee_vesting_secs)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        self.creator_fee_stream_updated_at = now;
+        Ok(())
+    }
+
+    /// Whether creator fees owe the original creator a royalty: the creator
+    /// role has changed hands and a royalty is set
+    pub fn royalty_active(&self) -> bool {
+        self.royalty_bps > 0 && self.creator != self.original_creator
+    }
+
+    /// Carve the insurance fund's share, then the public goods share, out of
+    /// a platform fee. They stay in the platform fee vault (in
+    /// `pending_insurance_fees` and `pending_public_goods_fees`) until
+    /// collected; returns `(to_platform, retained_in_vault)`.
+    pub fn split_platform_fee(&mut self, platform_fee: u64) -> Result<(u64, u64)> {
+        let share = |bps: u16| (platform_fee as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let to_insurance = share(self.insurance_fee_bps).min(platform_fee);
+        let to_public_goods = share(self.public_goods_fee_bps).min(platform_fee - to_insurance);
+        
+        self.pending_insurance_fees = self.pending_insurance_fees
+            .checked_add(to_insurance)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        self.pending_public_goods_fees = self.pending_public_goods_fees
+            .checked_add(to_public_goods)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        
+        let retained = to_insurance + to_public_goods;
+        Ok((platform_fee - retained, retained))
+    }
+
+    /// Advance and return the sequence number stamped on the agent's next
+    /// trade or payment event
+    pub fn next_event_sequence(&mut self) -> Result<u64> {
+        self.event_sequence = self.event_sequence
+            .checked_add(1)
+            .ok_or(crate::errors::AgentFactoryError::MathOverflow)?;
+        Ok(self.event_sequence)
+    }
+
+    /// Trending score decayed to `slot`
+    pub fn trending_score_at(&self, slot: u64) -> u64 {
+        decay_trending_score(self.trending_score, slot.saturating_sub(self.trending_slot))
+    }
+
+    /// Decay the trending score to `slot` and add a trade's SOL `volume`;
+    /// returns the new score
+    pub fn record_trending_volume(&mut self, volume: u64, slot: u64) -> u64 {
+        self.trending_score = self.trending_score_at(slot).saturating_add(volume);
+        self.trending_slot = self.trending_slot.max(slot);
+        self.trending_score
+    }
+
+    /// Feed the spot price after a trade at unix time `now` into the TWAP
+    pub fn record_twap_price(&mut self, now: i64, slot: u64) {
+        let price = self.bonding_curve.price_nano_u64_at(now);
+        self.twap.update(price, slot);
+    }
+
+    /// Manipulation-resistant price (nano-lamports per token) averaged over
+    /// at least `window_slots` up to `slot`; `None` without enough history
+    pub fn get_twap(&self, window_slots: u64, slot: u64) -> Option<u64> {
+        self.twap.twap(window_slots, slot)
+    }
+
+    /// Price, market cap and reserves at unix time `now`, as returned by
+    /// `view_price`
+    pub fn price_view(&self, now: i64) -> PriceView {
+        PriceView {
+            price: self.bonding_curve.price_nano_u64_at(now),
+            market_cap: self.bonding_curve.get_market_cap_at(now),
+            reserves: self.bonding_curve.reserves_at(now),
+            is_graduated: self.is_graduated,
+        }
+    }
+
+    /// Whether the staking and treasury shares fit within the creator fee
+    pub fn fee_shares_valid(staking_fee_bps: u16, treasury_fee_bps: u16) -> bool {
+        staking_fee_bps as u64 + treasury_fee_bps as u64 <= BPS_DENOMINATOR
+    }
+
+    /// Whether `tag` is a valid discovery tag: lowercase ASCII letters,
+    /// digits or `-`, at most `MAX_TAG_LEN` bytes
+    pub fn tag_valid(tag: &str) -> bool {
+        !tag.is_empty()
+            && tag.len() <= MAX_TAG_LEN
+            && tag.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+    }
+
+    /// Check a tag set: at most `MAX_AGENT_TAGS` valid, distinct tags
+    pub fn validate_tags(tags: &[String]) -> Result<()> {
+        require!(tags.len() <= MAX_AGENT_TAGS, AgentFactoryError::TooManyTags);
+        for (i, tag) in tags.iter().enumerate() {
+            require!(
+                Self::tag_valid(tag) && !tags[..i].contains(tag),
+                AgentFactoryError::InvalidTag
+            );
+        }
+        Ok(())
+    }
+
+    /// Reject a buy of `tokens_out` taking more than `max_buy_bps_of_reserves`
+    /// of the remaining curve tokens
+    pub fn check_max_buy(&self, tokens_out: u64) -> Result<()> {
+        if self.max_buy_bps_of_reserves == 0 {
+            return Ok(());
+        }
+        let cap = self.bonding_curve.real_token_reserves as u128
+            * self.max_buy_bps_of_reserves as u128
+            / BPS_DENOMINATOR as u128;
+        require!(tokens_out as u128 <= cap, AgentFactoryError::MaxBuyExceeded);
+        Ok(())
+    }
+
+    /// Check the books after a trade priced off `curve_before`: the
+    /// constant-product `k` never shrinks, and each vault still holds its
+    /// rent exemption plus the balance booked to it; donations may push it
+    /// above. Skipped in builds with the `no-reserve-checks` feature.
+    pub fn check_reserves<'info>(
+        &self,
+        sol_vault: &AccountInfo<'info>,
+        platform_fee_vault: &AccountInfo<'info>,
+        creator_fee_vault: &AccountInfo<'info>,
+        curve_before: &BondingCurve,
+        now: i64,
+    ) -> Result<()> {
+        if cfg!(feature = "no-reserve-checks") {
+            return Ok(());
+        }
+        if let (Some(k_before), Some(k_after)) = (
+            curve_before.constant_product_at(now),
+            self.bonding_curve.constant_product_at(now),
+        ) {
+            require!(k_after >= k_before, AgentFactoryError::ReserveInvariantViolated);
+        }
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        for (vault, info) in AgentVault::ALL.into_iter().zip([sol_vault, platform_fee_vault, creator_fee_vault]) {
+            let booked = self.vault_balance(vault)
+                .and_then(|balance| balance.checked_add(rent_exempt))
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            require!(info.lamports() >= booked, AgentFactoryError::ReserveInvariantViolated);
+        }
+        Ok(())
+    }
+
+    /// Whether the refund guarantee has kicked in: the agent missed its
+    /// graduation deadline, so trading stops and holders can only redeem
+    pub fn is_redeemable(&self, now: i64) -> bool {
+        self.refund_deadline > 0 && !self.is_graduated && now >= self.refund_deadline
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+
+    const NOW: i64 = 1_700_000_000;
+
+    /// Serves the Rent and Clock sysvars off-chain
+    struct Sysvars;
+
+    impl SyscallStubs for Sysvars {
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { *(var_addr as *mut Rent) = Rent::default() };
+            0
+        }
+
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = Clock { unix_timestamp: NOW, ..Clock::default() };
+            unsafe { *(var_addr as *mut Clock) = clock };
+            0
+        }
+    }
+
+    /// A fresh agent on the default constant-product curve
+    fn new_agent() -> Agent {
+        let data = vec![0u8; Agent::INIT_SPACE];
+        let mut agent = Agent::deserialize(&mut &data[..]).unwrap();
+        agent.bonding_curve = BondingCurve::new();
+        agent
+    }
+
+    /// `check_reserves` with vaults holding rent plus exactly the booked balances
+    fn check(agent: &Agent, curve_before: &BondingCurve) -> Result<()> {
+        let rent = Rent::default().minimum_balance(0);
+        let owner = Pubkey::default();
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut lamports = AgentVault::ALL.map(|vault| agent.vault_balance(vault).unwrap() + rent);
+        let [sol, platform, creator] = &mut lamports;
+        let (mut d0, mut d1, mut d2) = ([], [], []);
+        let sol_vault = AccountInfo::new(&keys[0], false, true, sol, &mut d0, &owner, false, 0);
+        let platform_fee_vault = AccountInfo::new(&keys[1], false, true, platform, &mut d1, &owner, false, 0);
+        let creator_fee_vault = AccountInfo::new(&keys[2], false, true, creator, &mut d2, &owner, false, 0);
+        agent.check_reserves(&sol_vault, &platform_fee_vault, &creator_fee_vault, curve_before, NOW)
+    }
+
+    #[test]
+    fn buy_and_sell_pass_reserve_checks() {
+        set_syscall_stubs(Box::new(Sysvars));
+        let mut agent = new_agent();
+
+        for sol_amount in [1_000_000_000, 7_777_777, 2_500_000_000] {
+            let quote = agent.bonding_curve.quote_buy_at(sol_amount, NOW).unwrap();
+            let curve_before = agent.bonding_curve;
+            agent.bonding_curve.update_after_buy(quote.net_sol_amount, quote.tokens_out).unwrap();
+            check(&agent, &curve_before).unwrap();
+        }
+
+        for token_amount in [1_000_000_000_000, 33_333_333_333, 10_000_000_000_000] {
+            let quote = agent.bonding_curve.quote_sell_at(token_amount, NOW).unwrap();
+            let curve_before = agent.bonding_curve;
+            agent.bonding_curve.update_after_sell(token_amount, quote.gross_sol_out).unwrap();
+            check(&agent, &curve_before).unwrap();
+        }
+    }
+
+    #[test]
+    fn shrinking_k_fails_reserve_checks() {
+        set_syscall_stubs(Box::new(Sysvars));
+        let mut agent = new_agent();
+
+        // Crediting only the net SOL for tokens priced on the gross amount
+        let quote = agent.bonding_curve.quote_buy_at(1_000_000_000, NOW).unwrap();
+        let tokens = bonding_curve_math::tokens_out(
+            agent.bonding_curve.virtual_sol_reserves,
+            agent.bonding_curve.virtual_token_reserves,
+            quote.sol_amount,
+        )
+        .unwrap();
+        let curve_before = agent.bonding_curve;
+        agent.bonding_curve.update_after_buy(quote.net_sol_amount, tokens).unwrap();
+        assert_eq!(
+            check(&agent, &curve_before).unwrap_err(),
+            AgentFactoryError::ReserveInvariantViolated.into()
+        );
+    }
+}
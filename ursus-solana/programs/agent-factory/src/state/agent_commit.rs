@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+/// Sealed launch of an agent: `["agent_commit", creator, commit_hash]`.
+/// The creator commits to the hash of the agent's fields, then reveals them
+/// in a later slot, so the name and symbol aren't visible before the slot the
+/// agent goes live in.
+#[account]
+#[derive(InitSpace)]
+pub struct AgentCommit {
+    /// Creator that may reveal the commitment
+    pub creator: Pubkey,
+
+    /// `AgentCommit::commit_hash` of the agent's fields and a salt
+    pub commit_hash: [u8; 32],
+
+    /// Slot the commitment landed in; the reveal must come after it
+    pub slot: u64,
+
+    /// Time the commitment landed
+    pub committed_at: i64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl AgentCommit {
+    pub const INIT_SPACE: usize =
+        32 +    // creator
+        32 +    // commit_hash
+        8 +     // slot
+        8 +     // committed_at
+        1;      // bump
+
+    /// SHA-256 of the Borsh encoding of `(name, symbol, description,
+    /// instructions, model, tags, salt)`, as passed to `reveal_agent`
+    pub fn commit_hash(
+        name: &str,
+        symbol: &str,
+        description: &str,
+        instructions: &str,
+        model: &str,
+        tags: &[String],
+        salt: &[u8; 32],
+    ) -> [u8; 32] {
+        fn put_str(data: &mut Vec<u8>, value: &str) {
+            data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            data.extend_from_slice(value.as_bytes());
+        }
+
+        let mut data = Vec::new();
+        for field in [name, symbol, description, instructions, model] {
+            put_str(&mut data, field);
+        }
+        data.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+        for tag in tags {
+            put_str(&mut data, tag);
+        }
+        data.extend_from_slice(salt);
+        hash(&data).to_bytes()
+    }
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use super::{Agent, DeprecatedService, MeterUnit, X402Config};
+
+/// Accepts x402 payments (enabled and not frozen)
+#[constant]
+pub const CAPABILITY_X402: u32 = 1 << 0;
+/// Takes escrowed payments released on attestation
+#[constant]
+pub const CAPABILITY_ESCROW: u32 = 1 << 1;
+/// Bills services by metered usage
+#[constant]
+pub const CAPABILITY_METERED: u32 = 1 << 2;
+/// Delivers service-call notifications to a webhook
+#[constant]
+pub const CAPABILITY_WEBHOOK: u32 = 1 << 3;
+/// Pays affiliates a share of referred payments
+#[constant]
+pub const CAPABILITY_AFFILIATES: u32 = 1 << 4;
+/// Routes part of each payment to other agents
+#[constant]
+pub const CAPABILITY_REVENUE_SPLIT: u32 = 1 << 5;
+/// Offers a bounded chargeback window
+#[constant]
+pub const CAPABILITY_CHARGEBACKS: u32 = 1 << 6;
+/// Funds its x402 treasury from creator fees to pay other agents
+#[constant]
+pub const CAPABILITY_PAYS_AGENTS: u32 = 1 << 7;
+/// Has graduated from its bonding curve
+#[constant]
+pub const CAPABILITY_GRADUATED: u32 = 1 << 8;
+/// Opens free-tier trial sessions against a refundable deposit
+#[constant]
+pub const CAPABILITY_TRIALS: u32 = 1 << 9;
+
+/// Most accepted mints a manifest lists, keeping it within the return data
+/// limit
+pub const MAX_MANIFEST_MINTS: usize = 8;
+
+/// Machine-readable description of an agent returned by `get_agent_manifest`
+/// as return data, so other agents can discover what it offers and on what
+/// terms in one simulated call. Fields are only ever appended.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AgentManifest {
+    pub agent: Pubkey,
+    pub mint: Pubkey,
+
+    /// `CAPABILITY_*` flags
+    pub capabilities: u32,
+
+    /// Operator keys: the creator, the x402 payment recipient, the escrow
+    /// attestation verifier and the webhook signer (default = unset)
+    pub creator: Pubkey,
+    pub payment_recipient: Pubkey,
+    pub attestation_verifier: Pubkey,
+    pub webhook_signer: Pubkey,
+
+    /// Service prices in payment token units, as in force now
+    pub min_payment_amount: u64,
+    pub max_payment_amount: u64,
+    pub meter_unit: MeterUnit,
+    pub unit_price: u64,
+    pub service_timeout_seconds: u64,
+
+    /// Scheduled minimum price increase (0 = none pending)
+    pub pending_min_payment_amount: u64,
+    pub pending_min_payment_at: i64,
+
+    pub max_affiliate_bps: u16,
+    pub chargeback_window_secs: u64,
+
+    /// Services being retired, with their sunsets
+    pub deprecated_services: Vec<DeprecatedService>,
+
+    /// Mints the payment recipient holds a token account for, out of those
+    /// the caller asked about
+    pub accepted_mints: Vec<Pubkey>,
+
+    /// Deposit a trial session escrows (0 = no trials)
+    pub trial_deposit: u64,
+}
+
+impl AgentManifest {
+    /// Manifest of `agent` at `now`, with x402 terms from `config` when the
+    /// agent has one
+    pub fn new(
+        agent_key: Pubkey,
+        agent: &Agent,
+        config: Option<&X402Config>,
+        accepted_mints: Vec<Pubkey>,
+        now: i64,
+    ) -> Self {
+        let mut manifest = Self {
+            agent: agent_key,
+            mint: agent.mint,
+            creator: agent.creator,
+            accepted_mints,
+            ..Self::default()
+        };
+        if agent.is_graduated {
+            manifest.capabilities |= CAPABILITY_GRADUATED;
+        }
+        if agent.x402_top_up_bps > 0 {
+            manifest.capabilities |= CAPABILITY_PAYS_AGENTS;
+        }
+
+        let Some(config) = config else {
+            return manifest;
+        };
+        let flags = [
+            (config.enabled && !config.frozen, CAPABILITY_X402),
+            (config.attestation_verifier != Pubkey::default(), CAPABILITY_ESCROW),
+            (config.is_metered(), CAPABILITY_METERED),
+            (config.webhook_url_hash != [0; 32], CAPABILITY_WEBHOOK),
+            (config.max_affiliate_bps > 0, CAPABILITY_AFFILIATES),
+            (config.revenue_split_bps > 0, CAPABILITY_REVENUE_SPLIT),
+            (config.chargeback_window_secs > 0, CAPABILITY_CHARGEBACKS),
+            (config.trial_deposit > 0, CAPABILITY_TRIALS),
+        ];
+        for (set, flag) in flags {
+            if set {
+                manifest.capabilities |= flag;
+            }
+        }
+
+        manifest.payment_recipient = config.payment_recipient;
+        manifest.attestation_verifier = config.attestation_verifier;
+        manifest.webhook_signer = config.webhook_signer;
+        manifest.min_payment_amount = config.current_min_payment_amount(now);
+        manifest.max_payment_amount = config.max_payment_amount;
+        manifest.meter_unit = config.meter_unit;
+        manifest.unit_price = config.unit_price;
+        manifest.service_timeout_seconds = config.service_timeout_seconds;
+        if config.pending_min_payment_at > now {
+            manifest.pending_min_payment_amount = config.pending_min_payment_amount;
+            manifest.pending_min_payment_at = config.pending_min_payment_at;
+        }
+        manifest.max_affiliate_bps = config.max_affiliate_bps;
+        manifest.chargeback_window_secs = config.chargeback_window_secs;
+        manifest.deprecated_services = config.deprecated_services
+            .iter()
+            .filter(|d| d.service_id != [0; 32])
+            .copied()
+            .collect();
+        manifest.trial_deposit = config.trial_deposit;
+        manifest
+    }
+}
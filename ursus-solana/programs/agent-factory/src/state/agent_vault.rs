@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use super::Agent;
+
+/// System-owned PDAs holding an agent's lamports, one per bucket, so the
+/// agent account itself only holds its rent. Each vault keeps its rent
+/// exemption plus the balance booked to it by `Agent::vault_balance`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AgentVault {
+    /// Curve SOL reserves: `["sol_vault", agent]`
+    Sol,
+    /// Platform fee shares held for the insurance fund:
+    /// `["platform_fee_vault", agent]`
+    PlatformFees,
+    /// Creator fee shares held for the creator, original creator, staking
+    /// pool and DAO treasury: `["creator_fee_vault", agent]`
+    CreatorFees,
+}
+
+impl AgentVault {
+    pub const ALL: [AgentVault; 3] = [AgentVault::Sol, AgentVault::PlatformFees, AgentVault::CreatorFees];
+
+    pub fn seed(self) -> &'static [u8] {
+        match self {
+            AgentVault::Sol => b"sol_vault",
+            AgentVault::PlatformFees => b"platform_fee_vault",
+            AgentVault::CreatorFees => b"creator_fee_vault",
+        }
+    }
+
+    /// Pay `amount` out of this vault of the agent at `agent_key`, signed by
+    /// the vault's seeds
+    pub fn pay<'info>(
+        self,
+        agent_key: &Pubkey,
+        agent: &Agent,
+        vault: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let bump = [agent.vault_bump(self)];
+        let seeds: &[&[u8]] = &[self.seed(), agent_key.as_ref(), &bump];
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Transfer { from: vault.clone(), to: to.clone() },
+                &[seeds],
+            ),
+            amount,
+        )
+    }
+}
+
+/// Deposit `amount` from a wallet into an agent vault; `signer_seeds` sign
+/// for a PDA wallet and are empty otherwise
+pub fn deposit_to_vault<'info>(
+    from: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Transfer { from: from.clone(), to: vault.clone() },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+impl Agent {
+    pub fn vault_bump(&self, vault: AgentVault) -> u8 {
+        match vault {
+            AgentVault::Sol => self.sol_vault_bump,
+            AgentVault::PlatformFees => self.platform_fee_vault_bump,
+            AgentVault::CreatorFees => self.creator_fee_vault_bump,
+        }
+    }
+
+    /// Lamports booked to `vault` on top of its rent exemption: the curve's
+    /// real SOL reserves, the insurance and public goods fee shares, or the
+    /// creator fee shares held until collected (the creator's streamed fees
+    /// included)
+    pub fn vault_balance(&self, vault: AgentVault) -> Option<u64> {
+        match vault {
+            AgentVault::Sol => Some(self.bonding_curve.real_sol_reserves),
+            AgentVault::PlatformFees => self.pending_insurance_fees
+                .checked_add(self.pending_public_goods_fees),
+            AgentVault::CreatorFees => self.pending_staking_rewards
+                .checked_add(self.pending_treasury_fees)?
+                .checked_add(self.pending_royalties)?
+                .checked_add(self.pending_x402_top_up)?
+                .checked_add(self.streaming_creator_fees)?
+                .checked_add(self.claimable_creator_fees),
+        }
+    }
+}
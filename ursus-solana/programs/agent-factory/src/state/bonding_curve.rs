@@ -1,4 +1,72 @@
 use anchor_lang::prelude::*;
+use bonding_curve_math::stepped::{self, Tranche};
+use bonding_curve_math::{MathError, BPS_DENOMINATOR, CREATOR_FEE_BPS, PLATFORM_FEE_BPS};
+use crate::errors::AgentFactoryError;
+
+pub use bonding_curve_math::{BuyQuote, SellQuote, MINIMUM_SOL_LIQUIDITY, MINIMUM_TOKEN_LIQUIDITY};
+
+/// Maximum number of tranches in a stepped curve
+pub const MAX_CURVE_TRANCHES: usize = 4;
+
+/// Longest liquidity bootstrapping period (30 days)
+pub const MAX_LBP_DURATION_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Bounds on the LBP opening price as a multiple of the regular curve price
+pub const MIN_LBP_PRICE_MULTIPLIER: u64 = 2;
+pub const MAX_LBP_PRICE_MULTIPLIER: u64 = 100;
+
+/// One phase of a stepped curve. Prices are nano-lamports per whole token
+/// (`get_current_price` reports whole lamports per token).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub struct CurveTranche {
+    /// Tokens sold in this tranche (base units)
+    pub supply: u64,
+    
+    /// Price at the start of the tranche
+    pub start_price: u64,
+    
+    /// Price increase per whole token sold within the tranche (0 = flat)
+    pub slope: u64,
+}
+
+/// Pricing model of a bonding curve
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum CurveType {
+    /// pump.fun style constant product over virtual reserves
+    ConstantProduct,
+    
+    /// Consecutive tranches, each with its own linear price slope
+    Stepped {
+        tranche_count: u8,
+        tranches: [CurveTranche; 4],
+    },
+    
+    /// Liquidity bootstrapping (Dutch auction): constant product whose extra
+    /// virtual SOL decays linearly to zero over `duration_secs`
+    Lbp {
+        start_ts: i64,
+        duration_secs: i64,
+        extra_virtual_sol: u64,
+    },
+}
+
+impl CurveType {
+    pub const INIT_SPACE: usize =
+        1 +                             // variant
+        1 +                             // tranche_count (largest variant: Stepped)
+        MAX_CURVE_TRANCHES * 24;        // tranches
+}
+
+/// Curve reserves around a trade. `virtual_sol` includes any remaining LBP
+/// premium; stepped curves are priced by tokens sold (`real_token`) instead
+/// of the virtual reserves.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CurveReserves {
+    pub virtual_sol: u64,
+    pub virtual_token: u64,
+    pub real_sol: u64,
+    pub real_token: u64,
+}
 
 /// Bonding curve parameters for pump.fun style pricing
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
@@ -23,10 +91,21 @@ pub struct BondingCurve {
     
     /// Total token supply (e.g., 1B tokens)
     pub total_supply: u64,
+    
+    /// Pricing model
+    pub curve_type: CurveType,
+    
+    /// Creator fee charged on trades (bps), set from the agent's
+    /// `CreatorFeeMode` at creation or by a factory fee override
+    pub creator_fee_bps: u16,
+    
+    /// Platform fee charged on trades before any platform token discount
+    /// (bps); lowered by a factory fee override
+    pub platform_fee_bps: u16,
 }
 
 impl BondingCurve {
-    pub const INIT_SPACE: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8;
+    pub const INIT_SPACE: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + CurveType::INIT_SPACE + 2 + 2;
 
     /// Create new bonding curve with pump.fun parameters
     pub fn new() -> Self {
@@ -54,51 +133,253 @@ impl BondingCurve {
             
             // 1B total supply
             total_supply: 1_000_000_000 * TOKEN_DECIMALS,
+            
+            curve_type: CurveType::ConstantProduct,
+            
+            creator_fee_bps: CREATOR_FEE_BPS as u16,
+            
+            platform_fee_bps: PLATFORM_FEE_BPS as u16,
         }
     }
 
-    /// Calculate tokens received for SOL amount (constant product formula)
-    /// Formula: tokens_out = virtual_token_reserves - (virtual_sol_reserves * virtual_token_reserves) / (virtual_sol_reserves + sol_in)
-    pub fn calculate_buy(&self, sol_amount: u64) -> Result<u64> {
-        let new_sol_reserves = self.virtual_sol_reserves
-            .checked_add(sol_amount)
-            .ok_or(error!(crate::errors::AgentFactoryError::MathOverflow))?;
+    /// Create a stepped curve; the tranches must sell exactly the bonding curve supply
+    pub fn stepped(tranches: &[CurveTranche]) -> Result<Self> {
+        let mut curve = Self::new();
+        
+        require!(
+            !tranches.is_empty() && tranches.len() <= MAX_CURVE_TRANCHES,
+            AgentFactoryError::InvalidCurveConfig
+        );
+        
+        let mut supply: u64 = 0;
+        for tranche in tranches {
+            require!(
+                tranche.supply > 0 && tranche.start_price > 0,
+                AgentFactoryError::InvalidCurveConfig
+            );
+            supply = supply
+                .checked_add(tranche.supply)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+        }
+        require!(supply == curve.bonding_curve_supply, AgentFactoryError::InvalidCurveConfig);
+        
+        let mut padded = [CurveTranche::default(); MAX_CURVE_TRANCHES];
+        padded[..tranches.len()].copy_from_slice(tranches);
+        curve.curve_type = CurveType::Stepped {
+            tranche_count: tranches.len() as u8,
+            tranches: padded,
+        };
+        
+        Ok(curve)
+    }
 
-        let product = (self.virtual_sol_reserves as u128)
-            .checked_mul(self.virtual_token_reserves as u128)
-            .ok_or(error!(crate::errors::AgentFactoryError::MathOverflow))?;
+    /// Create a liquidity bootstrapping curve opening at `start_price_multiplier`
+    /// times the regular price and decaying to it over `duration_secs`
+    pub fn lbp(duration_secs: i64, start_price_multiplier: u64, now: i64) -> Result<Self> {
+        let mut curve = Self::new();
+        
+        require!(
+            duration_secs > 0 && duration_secs <= MAX_LBP_DURATION_SECS,
+            AgentFactoryError::InvalidCurveConfig
+        );
+        require!(
+            (MIN_LBP_PRICE_MULTIPLIER..=MAX_LBP_PRICE_MULTIPLIER).contains(&start_price_multiplier),
+            AgentFactoryError::InvalidCurveConfig
+        );
+        
+        let extra_virtual_sol = curve.virtual_sol_reserves
+            .checked_mul(start_price_multiplier - 1)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        curve.curve_type = CurveType::Lbp {
+            start_ts: now,
+            duration_secs,
+            extra_virtual_sol,
+        };
+        
+        Ok(curve)
+    }
 
-        let new_token_reserves = product
-            .checked_div(new_sol_reserves as u128)
-            .ok_or(error!(crate::errors::AgentFactoryError::MathOverflow))? as u64;
+    /// Virtual SOL reserves used for pricing at `now` (includes any
+    /// remaining LBP premium)
+    pub fn virtual_sol_at(&self, now: i64) -> u64 {
+        match self.curve_type {
+            CurveType::Lbp { start_ts, duration_secs, extra_virtual_sol } => bonding_curve_math::lbp_virtual_sol(
+                self.virtual_sol_reserves,
+                extra_virtual_sol,
+                start_ts,
+                duration_secs,
+                now,
+            ),
+            _ => self.virtual_sol_reserves,
+        }
+    }
 
-        let tokens_out = self.virtual_token_reserves
-            .checked_sub(new_token_reserves)
-            .ok_or(error!(crate::errors::AgentFactoryError::InsufficientLiquidity))?;
+    /// Platform fee after a platform token discount
+    fn discounted_platform_fee_bps(&self, discount_bps: u16) -> u64 {
+        let fee = self.platform_fee_bps as u64;
+        let discount = (discount_bps as u64).min(BPS_DENOMINATOR);
+        fee - fee * discount / BPS_DENOMINATOR
+    }
+
+    /// Tokens sold from the curve so far
+    pub fn tokens_sold(&self) -> u64 {
+        self.bonding_curve_supply.saturating_sub(self.real_token_reserves)
+    }
 
-        Ok(tokens_out)
+    /// Tranches in the shared math representation (None for constant product)
+    fn stepped_tranches(&self) -> Option<([Tranche; MAX_CURVE_TRANCHES], usize)> {
+        match self.curve_type {
+            CurveType::ConstantProduct | CurveType::Lbp { .. } => None,
+            CurveType::Stepped { tranche_count, tranches } => Some((
+                tranches.map(|t| Tranche {
+                    supply: t.supply,
+                    start_price: t.start_price,
+                    slope: t.slope,
+                }),
+                (tranche_count as usize).min(MAX_CURVE_TRANCHES),
+            )),
+        }
+    }
+
+    /// Calculate tokens received for SOL amount
+    /// Constant product: tokens_out = virtual_token_reserves - (virtual_sol_reserves * virtual_token_reserves) / (virtual_sol_reserves + sol_in)
+    pub fn calculate_buy(&self, sol_amount: u64) -> Result<u64> {
+        match self.stepped_tranches() {
+            Some((tranches, count)) => stepped::tokens_out(&tranches[..count], self.tokens_sold(), sol_amount)
+                .map(|(tokens_out, _)| tokens_out),
+            None => bonding_curve_math::tokens_out(self.virtual_sol_at(now()), self.virtual_token_reserves, sol_amount),
+        }
+        .map_err(math_error)
     }
 
-    /// Calculate SOL received for token amount (constant product formula)
-    /// Formula: sol_out = virtual_sol_reserves - (virtual_sol_reserves * virtual_token_reserves) / (virtual_token_reserves + tokens_in)
+    /// Calculate SOL received for token amount
+    /// Constant product: sol_out = virtual_sol_reserves - (virtual_sol_reserves * virtual_token_reserves) / (virtual_token_reserves + tokens_in)
     pub fn calculate_sell(&self, token_amount: u64) -> Result<u64> {
-        let new_token_reserves = self.virtual_token_reserves
-            .checked_add(token_amount)
-            .ok_or(error!(crate::errors::AgentFactoryError::MathOverflow))?;
+        match self.stepped_tranches() {
+            Some((tranches, count)) => stepped::sol_out(&tranches[..count], self.tokens_sold(), token_amount),
+            None => bonding_curve_math::sol_out(self.virtual_sol_at(now()), self.virtual_token_reserves, token_amount),
+        }
+        .map_err(math_error)
+    }
 
-        let product = (self.virtual_sol_reserves as u128)
-            .checked_mul(self.virtual_token_reserves as u128)
-            .ok_or(error!(crate::errors::AgentFactoryError::MathOverflow))?;
+    /// Quote a buy including platform and creator fees
+    ///
+    /// Buys near the end of the curve are partially filled: at most the
+    /// remaining `real_token_reserves` are sold and the net SOL stops at the
+    /// graduation threshold. `BuyQuote::sol_amount` is the SOL actually spent.
+    pub fn quote_buy(&self, sol_amount: u64) -> Result<BuyQuote> {
+        self.quote_buy_at(sol_amount, now())
+    }
+
+    /// Quote a buy at unix time `now` (LBP curves price by time)
+    pub fn quote_buy_at(&self, sol_amount: u64, now: i64) -> Result<BuyQuote> {
+        self.quote_buy_discounted_at(sol_amount, 0, now)
+    }
+
+    /// Quote a buy with the platform fee reduced by `discount_bps`
+    pub fn quote_buy_discounted(&self, sol_amount: u64, discount_bps: u16) -> Result<BuyQuote> {
+        self.quote_buy_discounted_at(sol_amount, discount_bps, now())
+    }
+
+    /// Quote a discounted buy at unix time `now`
+    pub fn quote_buy_discounted_at(
+        &self,
+        sol_amount: u64,
+        discount_bps: u16,
+        now: i64,
+    ) -> Result<BuyQuote> {
+        let platform_fee_bps = self.discounted_platform_fee_bps(discount_bps);
+        match self.stepped_tranches() {
+            Some((tranches, count)) => stepped::quote_buy(
+                &tranches[..count],
+                self.tokens_sold(),
+                sol_amount,
+                self.remaining_sol_capacity(),
+                platform_fee_bps,
+                self.creator_fee_bps as u64,
+            ),
+            None => bonding_curve_math::quote_buy_capped(
+                self.virtual_sol_at(now),
+                self.virtual_token_reserves,
+                sol_amount,
+                bonding_curve_math::buyable_tokens(self.real_token_reserves),
+                self.remaining_sol_capacity(),
+                platform_fee_bps,
+                self.creator_fee_bps as u64,
+            ),
+        }
+        .map_err(math_error)
+    }
+
+    /// Quote a sell including platform and creator fees
+    pub fn quote_sell(&self, token_amount: u64) -> Result<SellQuote> {
+        self.quote_sell_at(token_amount, now())
+    }
 
-        let new_sol_reserves = product
-            .checked_div(new_token_reserves as u128)
-            .ok_or(error!(crate::errors::AgentFactoryError::MathOverflow))? as u64;
+    /// Quote a sell at unix time `now` (LBP curves price by time)
+    pub fn quote_sell_at(&self, token_amount: u64, now: i64) -> Result<SellQuote> {
+        self.quote_sell_discounted_at(token_amount, 0, now)
+    }
 
-        let sol_out = self.virtual_sol_reserves
-            .checked_sub(new_sol_reserves)
-            .ok_or(error!(crate::errors::AgentFactoryError::InsufficientLiquidity))?;
+    /// Quote a sell with the platform fee reduced by `discount_bps`
+    pub fn quote_sell_discounted(&self, token_amount: u64, discount_bps: u16) -> Result<SellQuote> {
+        self.quote_sell_discounted_at(token_amount, discount_bps, now())
+    }
 
-        Ok(sol_out)
+    /// Quote a discounted sell at unix time `now`
+    pub fn quote_sell_discounted_at(
+        &self,
+        token_amount: u64,
+        discount_bps: u16,
+        now: i64,
+    ) -> Result<SellQuote> {
+        let platform_fee_bps = self.discounted_platform_fee_bps(discount_bps);
+        let quote = match self.stepped_tranches() {
+            Some((tranches, count)) => stepped::quote_sell(
+                &tranches[..count],
+                self.tokens_sold(),
+                token_amount,
+                platform_fee_bps,
+                self.creator_fee_bps as u64,
+            ),
+            None => bonding_curve_math::quote_sell(
+                self.virtual_sol_at(now),
+                self.virtual_token_reserves,
+                token_amount,
+                platform_fee_bps,
+                self.creator_fee_bps as u64,
+            ),
+        }
+        .map_err(math_error)?;
+        
+        // Leave the minimum liquidity in the reserves
+        require!(
+            quote.gross_sol_out <= bonding_curve_math::sellable_sol(self.real_sol_reserves),
+            AgentFactoryError::InsufficientLiquidity
+        );
+        Ok(quote)
+    }
+
+    /// SOL paid for redeeming `token_amount` under a refund guarantee: the
+    /// average entry price of the outstanding tokens (reserves / tokens sold),
+    /// rounded down so later redeemers are always covered
+    pub fn redemption_value(&self, token_amount: u64) -> Result<u64> {
+        let sold = self.tokens_sold();
+        require!(token_amount <= sold, AgentFactoryError::InsufficientLiquidity);
+        if sold == 0 {
+            return Ok(0);
+        }
+        
+        let value = (self.real_sol_reserves as u128)
+            .checked_mul(token_amount as u128)
+            .ok_or(AgentFactoryError::MathOverflow)?
+            / sold as u128;
+        Ok(value as u64)
+    }
+
+    /// Net SOL the curve can still take before reaching the graduation threshold
+    pub fn remaining_sol_capacity(&self) -> u64 {
+        self.graduation_threshold.saturating_sub(self.real_sol_reserves)
     }
 
     /// Update reserves after buy
@@ -143,28 +424,115 @@ impl BondingCurve {
         Ok(())
     }
 
+    /// Carve `bps` of the SOL reserves and of the unsold curve tokens out of
+    /// the graduation liquidity; returns `(sol, tokens)`. The tokens count as
+    /// sold, since they are minted to the protocol.
+    pub fn take_protocol_liquidity(&mut self, bps: u16) -> (u64, u64) {
+        let share = |amount: u64| (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let sol = share(self.real_sol_reserves);
+        let tokens = share(self.real_token_reserves);
+        
+        self.real_sol_reserves -= sol;
+        self.real_token_reserves -= tokens;
+        
+        (sol, tokens)
+    }
+
+    /// Retire the unsold curve tokens at graduation; returns the amount burned.
+    /// Tokens sold so far are unaffected.
+    pub fn burn_remaining(&mut self) -> Result<u64> {
+        let burned = self.real_token_reserves;
+        
+        self.bonding_curve_supply = self.bonding_curve_supply
+            .checked_sub(burned)
+            .ok_or(error!(crate::errors::AgentFactoryError::MathOverflow))?;
+        
+        self.total_supply = self.total_supply
+            .checked_sub(burned)
+            .ok_or(error!(crate::errors::AgentFactoryError::MathOverflow))?;
+        
+        self.real_token_reserves = 0;
+        
+        Ok(burned)
+    }
+
     /// Get current price (SOL per token)
     pub fn get_current_price(&self) -> u64 {
-        if self.virtual_token_reserves == 0 {
-            return 0;
+        self.get_price_at(now())
+    }
+
+    /// Price (SOL per token) at unix time `now`
+    pub fn get_price_at(&self, now: i64) -> u64 {
+        match self.stepped_tranches() {
+            Some((tranches, count)) => stepped::spot_price(&tranches[..count], self.tokens_sold()),
+            None => bonding_curve_math::spot_price(self.virtual_sol_at(now), self.virtual_token_reserves),
+        }
+    }
+
+    /// Price in nano-lamports per token at unix time `now`, precise enough
+    /// for price impact
+    fn price_nano_at(&self, now: i64) -> u128 {
+        match self.stepped_tranches() {
+            Some((tranches, count)) => stepped::spot_price_nano(&tranches[..count], self.tokens_sold()) as u128,
+            None => bonding_curve_math::spot_price_nano(self.virtual_sol_at(now), self.virtual_token_reserves),
+        }
+    }
+
+    /// Price in nano-lamports per token at unix time `now`, saturated to
+    /// u64, as recorded in price history candles
+    pub fn price_nano_u64_at(&self, now: i64) -> u64 {
+        u64::try_from(self.price_nano_at(now)).unwrap_or(u64::MAX)
+    }
+
+    /// Price move (bps, either direction) from the curve `before` a trade to
+    /// this one, both priced at unix time `now`
+    pub fn price_impact_bps(&self, before: &BondingCurve, now: i64) -> u64 {
+        bonding_curve_math::price_impact_bps(before.price_nano_at(now), self.price_nano_at(now))
+    }
+
+    /// Constant-product invariant `k` of the virtual reserves at unix time
+    /// `now` (None for stepped curves, which are priced by tokens sold)
+    pub fn constant_product_at(&self, now: i64) -> Option<u128> {
+        self.stepped_tranches()
+            .is_none()
+            .then(|| self.virtual_sol_at(now) as u128 * self.virtual_token_reserves as u128)
+    }
+
+    /// Reserves at unix time `now`, as reported in trade events
+    pub fn reserves_at(&self, now: i64) -> CurveReserves {
+        CurveReserves {
+            virtual_sol: self.virtual_sol_at(now),
+            virtual_token: self.virtual_token_reserves,
+            real_sol: self.real_sol_reserves,
+            real_token: self.real_token_reserves,
         }
-        
-        // Price = virtual_sol_reserves / virtual_token_reserves
-        ((self.virtual_sol_reserves as u128)
-            .checked_mul(1_000_000_000) // Scale for precision
-            .unwrap_or(0) / self.virtual_token_reserves as u128) as u64
     }
 
     /// Get market cap in SOL
     pub fn get_market_cap(&self) -> u64 {
-        let price = self.get_current_price();
-        let circulating_supply = self.bonding_curve_supply
-            .checked_sub(self.real_token_reserves)
-            .unwrap_or(0);
-        
-        ((circulating_supply as u128)
-            .checked_mul(price as u128)
-            .unwrap_or(0) / 1_000_000_000) as u64
+        self.get_market_cap_at(now())
+    }
+
+    /// Market cap in SOL at unix time `now`
+    pub fn get_market_cap_at(&self, now: i64) -> u64 {
+        bonding_curve_math::market_cap(
+            self.get_price_at(now),
+            self.bonding_curve_supply,
+            self.real_token_reserves,
+        )
+    }
+}
+
+/// Current unix time from the Clock sysvar. Off-chain there is no clock, so
+/// LBP curves are treated as fully decayed; use the `_at` methods there.
+fn now() -> i64 {
+    Clock::get().map(|clock| clock.unix_timestamp).unwrap_or(i64::MAX)
+}
+
+fn math_error(e: MathError) -> Error {
+    match e {
+        MathError::Overflow => error!(AgentFactoryError::MathOverflow),
+        MathError::InsufficientLiquidity => error!(AgentFactoryError::InsufficientLiquidity),
     }
 }
 
@@ -174,3 +542,50 @@ impl Default for BondingCurve {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh constant-product curve with `sold` base units sold for
+    /// `reserves` lamports
+    fn curve_with_sales(sold: u64, reserves: u64) -> BondingCurve {
+        let mut curve = BondingCurve::new();
+        curve.real_token_reserves = curve.bonding_curve_supply - sold;
+        curve.real_sol_reserves = reserves;
+        curve
+    }
+
+    #[test]
+    fn redemption_pays_the_average_entry_price_rounded_down() {
+        let curve = curve_with_sales(3, 10);
+
+        assert_eq!(curve.redemption_value(1).unwrap(), 3);
+        assert_eq!(curve.redemption_value(2).unwrap(), 6);
+        assert_eq!(curve.redemption_value(3).unwrap(), 10);
+    }
+
+    #[test]
+    fn rounding_leaves_later_redeemers_covered() {
+        let mut curve = curve_with_sales(7, 100);
+        for _ in 0..7 {
+            let sol_out = curve.redemption_value(1).unwrap();
+            assert!(sol_out >= 100 / 7);
+            curve.update_after_sell(1, sol_out).unwrap();
+        }
+
+        assert_eq!(curve.tokens_sold(), 0);
+        assert_eq!(curve.real_sol_reserves, 0);
+    }
+
+    #[test]
+    fn redemption_is_capped_at_the_tokens_sold() {
+        let curve = curve_with_sales(3, 10);
+
+        assert_eq!(
+            curve.redemption_value(4).unwrap_err(),
+            AgentFactoryError::InsufficientLiquidity.into()
+        );
+        assert_eq!(curve_with_sales(0, 10).redemption_value(0).unwrap(), 0);
+    }
+}
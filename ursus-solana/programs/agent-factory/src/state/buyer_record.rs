@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+/// Marks a wallet that has received an agent's tokens from a direct buy;
+/// created on its first buy, which counts it in `Agent::unique_buyer_count`,
+/// and stamped with the slot of every buy
+#[account]
+#[derive(InitSpace)]
+pub struct BuyerRecord {
+    /// Agent bought
+    pub agent: Pubkey,
+    
+    /// Wallet that received the tokens
+    pub buyer: Pubkey,
+    
+    /// Order of the buyer's first buy (1 = first buyer), for early buyer
+    /// incentives
+    pub buyer_number: u64,
+    
+    /// Timestamp of the first buy
+    pub first_buy_at: i64,
+    
+    /// Slot of the latest buy
+    pub last_buy_slot: u64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl BuyerRecord {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        32 +    // buyer
+        8 +     // buyer_number
+        8 +     // first_buy_at
+        8 +     // last_buy_slot
+        1;      // bump
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::errors::AgentFactoryError;
+
+/// Opt-in spending report of one x402 payer, for reconciling on-chain spend
+/// against internal budgets without running an indexer.
+///
+/// Payments passing it add to the totals and extend `ledger_hash`, a rolling
+/// SHA-256 over each payment's `(agent, payment_record, amount)`. Replaying
+/// an internal per-agent ledger through the same chain reproduces it exactly
+/// when the books match.
+#[account]
+#[derive(InitSpace)]
+pub struct ConsumerStats {
+    /// Paying wallet, or paying agent for agent-to-agent calls
+    pub payer: Pubkey,
+    
+    /// Paid so far, summed across payment tokens (smallest units); escrowed
+    /// payments count when escrowed
+    pub total_spent: u64,
+    
+    /// Payments recorded so far
+    pub total_calls: u64,
+    
+    /// `sha256(previous || agent || payment_record || amount_le)` over every
+    /// recorded payment, starting from zero
+    pub ledger_hash: [u8; 32],
+    
+    /// Timestamp of the last recorded payment (0 = none)
+    pub last_payment_at: i64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ConsumerStats {
+    pub const INIT_SPACE: usize =
+        32 +    // payer
+        8 +     // total_spent
+        8 +     // total_calls
+        32 +    // ledger_hash
+        8 +     // last_payment_at
+        8 +     // created_at
+        1;      // bump
+
+    /// Add a payment of `amount` to `agent` to the report
+    pub fn record(&mut self, agent: &Pubkey, payment_record: &Pubkey, amount: u64, now: i64) -> Result<()> {
+        self.total_spent = self.total_spent
+            .checked_add(amount)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        self.total_calls = self.total_calls
+            .checked_add(1)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        self.ledger_hash = hashv(&[
+            &self.ledger_hash,
+            agent.as_ref(),
+            payment_record.as_ref(),
+            &amount.to_le_bytes(),
+        ])
+        .to_bytes();
+        self.last_payment_at = now;
+        Ok(())
+    }
+}
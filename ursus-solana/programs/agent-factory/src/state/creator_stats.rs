@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+
+/// Creation fee discounts for proven creators: `(graduated volume in
+/// lamports, discount bps)`, ascending
+pub const PROVEN_CREATOR_TIERS: [(u64, u16); 2] = [
+    (100_000_000_000, 2_500),   // 100 SOL graduated: 25% off
+    (1_000_000_000_000, 5_000), // 1,000 SOL graduated: 50% off
+];
+
+/// Creation fee surcharge per rugged agent, and the cap that also applies to
+/// blacklisted creators
+pub const RUG_SURCHARGE_BPS: u64 = 10_000;
+pub const MAX_CREATOR_SURCHARGE_BPS: u64 = 40_000;
+
+/// Track record of a creator wallet, used to price their creation fee
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorStats {
+    /// Creator wallet
+    pub creator: Pubkey,
+    
+    /// Agents created so far
+    pub agents_created: u64,
+    
+    /// Agents that reached graduation
+    pub agents_graduated: u64,
+    
+    /// SOL reserves of the creator's agents at graduation (in lamports)
+    pub graduated_volume: u64,
+    
+    /// Agents the factory authority flagged as rugged or exploited
+    pub rugged_agents: u16,
+    
+    /// Set by the factory authority for known bad actors
+    pub is_blacklisted: bool,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl CreatorStats {
+    pub const INIT_SPACE: usize =
+        32 +    // creator
+        8 +     // agents_created
+        8 +     // agents_graduated
+        8 +     // graduated_volume
+        2 +     // rugged_agents
+        1 +     // is_blacklisted
+        1;      // bump
+
+    /// Creation fee for this creator: surcharged per rugged agent (capped,
+    /// and at the cap when blacklisted), otherwise discounted by graduated
+    /// volume
+    pub fn adjusted_creation_fee(&self, base_fee: u64) -> Result<u64> {
+        let multiplier_bps = if self.is_blacklisted {
+            BPS_DENOMINATOR + MAX_CREATOR_SURCHARGE_BPS
+        } else if self.rugged_agents > 0 {
+            BPS_DENOMINATOR
+                + (self.rugged_agents as u64 * RUG_SURCHARGE_BPS).min(MAX_CREATOR_SURCHARGE_BPS)
+        } else {
+            let discount_bps = PROVEN_CREATOR_TIERS
+                .iter()
+                .filter(|(volume, _)| self.graduated_volume >= *volume)
+                .map(|(_, discount_bps)| *discount_bps as u64)
+                .max()
+                .unwrap_or(0);
+            BPS_DENOMINATOR - discount_bps
+        };
+        
+        u64::try_from(base_fee as u128 * multiplier_bps as u128 / BPS_DENOMINATOR as u128)
+            .map_err(|_| error!(AgentFactoryError::MathOverflow))
+    }
+}
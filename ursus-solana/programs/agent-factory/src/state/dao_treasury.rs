@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+/// Per-agent DAO treasury. Holds SOL (in this account) and SPL tokens (in
+/// token accounts it owns); it can only be spent by passed proposals.
+#[account]
+#[derive(InitSpace)]
+pub struct DaoTreasury {
+    /// Agent whose community owns the treasury
+    pub agent: Pubkey,
+    
+    /// Creator fee share collected so far (in lamports)
+    pub total_fees_collected: u64,
+    
+    /// SOL paid out by proposals so far (in lamports)
+    pub total_sol_spent: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl DaoTreasury {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        8 +     // total_fees_collected
+        8 +     // total_sol_spent
+        8 +     // created_at
+        1;      // bump
+}
+
+/// Agent pool receiving a `deposit_revenue`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RevenuePool {
+    /// Staking pool rewards, streamed to stakers (SOL only)
+    Stakers,
+    /// DAO treasury (SOL or SPL tokens)
+    Treasury,
+}
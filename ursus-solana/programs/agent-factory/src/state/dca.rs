@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+/// Dollar-cost-averaging schedule for buying an agent token over time.
+/// The schedule's vault (`["dca_vault", dca]`) holds the whole SOL deposit
+/// and pays out one `amount_per_interval` buy each time it is cranked.
+#[account]
+#[derive(InitSpace)]
+pub struct DcaSchedule {
+    /// Investor that funded the schedule and receives the tokens
+    pub owner: Pubkey,
+    
+    /// Agent whose token is being accumulated
+    pub agent: Pubkey,
+    
+    /// SOL spent per execution (in lamports, fees included)
+    pub amount_per_interval: u64,
+    
+    /// Fewest tokens a full `amount_per_interval` buy may return; partial
+    /// fills are held to the same price
+    pub min_tokens_per_interval: u64,
+    
+    /// Minimum seconds between executions
+    pub interval_secs: i64,
+    
+    /// Number of executions funded by the deposit
+    pub total_intervals: u64,
+    
+    /// Number of executions performed so far
+    pub executed_intervals: u64,
+    
+    /// Earliest timestamp the next execution may run
+    pub next_execution_ts: i64,
+    
+    /// Total tokens bought by the schedule
+    pub total_tokens_bought: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl DcaSchedule {
+    pub const INIT_SPACE: usize =
+        32 +    // owner
+        32 +    // agent
+        8 +     // amount_per_interval
+        8 +     // min_tokens_per_interval
+        8 +     // interval_secs
+        8 +     // total_intervals
+        8 +     // executed_intervals
+        8 +     // next_execution_ts
+        8 +     // total_tokens_bought
+        8 +     // created_at
+        1;      // bump
+
+    /// Whether every funded interval has been executed
+    pub fn is_complete(&self) -> bool {
+        self.executed_intervals >= self.total_intervals
+    }
+
+    /// Whether the next interval can be executed at `now`
+    pub fn is_due(&self, now: i64) -> bool {
+        !self.is_complete() && now >= self.next_execution_ts
+    }
+
+    /// Fewest tokens a buy spending `sol_amount` may return, scaling
+    /// `min_tokens_per_interval` down for partial fills and rounding up
+    pub fn min_tokens_out(&self, sol_amount: u64) -> Option<u64> {
+        let numerator = (self.min_tokens_per_interval as u128).checked_mul(sol_amount as u128)?;
+        let denominator = self.amount_per_interval as u128;
+        u64::try_from(numerator.div_ceil(denominator)).ok()
+    }
+}
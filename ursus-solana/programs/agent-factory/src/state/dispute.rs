@@ -0,0 +1,221 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use super::MAX_ARBITERS;
+
+/// SOL bond an agent's creator posts to back its x402 services. Slashed to
+/// compensate payers whose disputes the arbitration council upholds; locked
+/// while any dispute against the agent is open.
+#[account]
+#[derive(InitSpace)]
+pub struct ProviderBond {
+    /// Agent the bond backs
+    pub agent: Pubkey,
+    
+    /// Bonded SOL above the account's rent (in lamports)
+    pub amount: u64,
+    
+    /// Disputes against the agent awaiting the council
+    pub open_disputes: u32,
+    
+    /// Slashed so far (in lamports)
+    pub total_slashed: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ProviderBond {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        8 +     // amount
+        4 +     // open_disputes
+        8 +     // total_slashed
+        8 +     // created_at
+        1;      // bump
+}
+
+/// Outcome of a dispute
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum DisputeStatus {
+    /// Awaiting the council
+    Open,
+    
+    /// Upheld; the provider bond was slashed to the claimant (as far as it
+    /// held the award)
+    Slashed,
+    
+    /// Rejected without a slash
+    Dismissed,
+}
+
+/// Council member's vote on a dispute: the award they back (0 = dismiss)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct ArbiterVote {
+    pub arbiter: Pubkey,
+    pub slash_amount: u64,
+}
+
+impl ArbiterVote {
+    pub const INIT_SPACE: usize = 32 + 8;
+}
+
+/// Payer's dispute of one x402 payment, with the hash of their evidence
+/// (stored off-chain)
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    /// Disputed payment
+    pub payment_record: Pubkey,
+    
+    /// Agent that was paid
+    pub agent: Pubkey,
+    
+    /// Wallet that filed the dispute and receives any slash
+    pub claimant: Pubkey,
+    
+    /// Hash of the evidence submitted
+    pub evidence_hash: [u8; 32],
+    
+    /// Compensation the claimant asked for (in lamports), at most the lamport
+    /// value of what the provider kept of the payment; the council can't
+    /// award more
+    pub claimed_amount: u64,
+    
+    /// Current status
+    pub status: DisputeStatus,
+    
+    /// Owed to the claimant by the council's decision (in lamports)
+    pub awarded_amount: u64,
+    
+    /// Slashed from the provider bond to the claimant (in lamports); below
+    /// `awarded_amount` when the bond ran short
+    pub slashed_amount: u64,
+    
+    /// Council votes cast so far
+    #[max_len(MAX_ARBITERS)]
+    pub votes: Vec<ArbiterVote>,
+    
+    /// Filing timestamp
+    pub created_at: i64,
+    
+    /// Resolution timestamp (0 while open)
+    pub resolved_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Dispute {
+    pub const INIT_SPACE: usize =
+        32 +    // payment_record
+        32 +    // agent
+        32 +    // claimant
+        32 +    // evidence_hash
+        8 +     // claimed_amount
+        1 +     // status
+        8 +     // awarded_amount
+        8 +     // slashed_amount
+        4 + ArbiterVote::INIT_SPACE * MAX_ARBITERS + // votes
+        8 +     // created_at
+        8 +     // resolved_at
+        1;      // bump
+
+    /// Record `arbiter`'s vote for `slash_amount`, dropping the votes of
+    /// members no longer seated in `arbiters` so their seats can be reused
+    /// without exceeding the vote capacity. Returns how many current members
+    /// back that same amount.
+    pub fn record_vote(&mut self, arbiters: &[Pubkey], arbiter: Pubkey, slash_amount: u64) -> Result<usize> {
+        require!(
+            !self.votes.iter().any(|v| v.arbiter == arbiter),
+            AgentFactoryError::ArbiterAlreadyVoted
+        );
+        let seated = |key: &Pubkey| *key != Pubkey::default() && arbiters.contains(key);
+        self.votes.retain(|v| seated(&v.arbiter));
+        self.votes.push(ArbiterVote { arbiter, slash_amount });
+
+        Ok(self.votes.iter().filter(|v| v.slash_amount == slash_amount).count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_dispute() -> Dispute {
+        Dispute {
+            payment_record: Pubkey::new_unique(),
+            agent: Pubkey::new_unique(),
+            claimant: Pubkey::new_unique(),
+            evidence_hash: [0; 32],
+            claimed_amount: 0,
+            status: DisputeStatus::Open,
+            awarded_amount: 0,
+            slashed_amount: 0,
+            votes: Vec::new(),
+            created_at: 0,
+            resolved_at: 0,
+            bump: 0,
+        }
+    }
+
+    fn council(size: usize) -> [Pubkey; MAX_ARBITERS] {
+        let mut arbiters = [Pubkey::default(); MAX_ARBITERS];
+        for arbiter in arbiters.iter_mut().take(size) {
+            *arbiter = Pubkey::new_unique();
+        }
+        arbiters
+    }
+
+    #[test]
+    fn agreeing_votes_reach_the_threshold() {
+        let arbiters = council(3);
+        let mut dispute = open_dispute();
+
+        assert_eq!(dispute.record_vote(&arbiters, arbiters[0], 500).unwrap(), 1);
+        assert_eq!(dispute.record_vote(&arbiters, arbiters[1], 500).unwrap(), 2);
+        assert_eq!(dispute.record_vote(&arbiters, arbiters[2], 500).unwrap(), 3);
+    }
+
+    #[test]
+    fn differing_awards_are_tallied_apart() {
+        let arbiters = council(3);
+        let mut dispute = open_dispute();
+
+        assert_eq!(dispute.record_vote(&arbiters, arbiters[0], 500).unwrap(), 1);
+        assert_eq!(dispute.record_vote(&arbiters, arbiters[1], 0).unwrap(), 1);
+        assert_eq!(dispute.record_vote(&arbiters, arbiters[2], 0).unwrap(), 2);
+        assert_eq!(dispute.votes.len(), 3);
+    }
+
+    #[test]
+    fn arbiters_vote_once() {
+        let arbiters = council(2);
+        let mut dispute = open_dispute();
+
+        dispute.record_vote(&arbiters, arbiters[0], 500).unwrap();
+        assert_eq!(
+            dispute.record_vote(&arbiters, arbiters[0], 0).unwrap_err(),
+            AgentFactoryError::ArbiterAlreadyVoted.into()
+        );
+        assert_eq!(dispute.votes, vec![ArbiterVote { arbiter: arbiters[0], slash_amount: 500 }]);
+    }
+
+    #[test]
+    fn removed_members_votes_stop_counting() {
+        let mut arbiters = council(MAX_ARBITERS);
+        let mut dispute = open_dispute();
+        for arbiter in arbiters {
+            dispute.record_vote(&arbiters, arbiter, 500).unwrap();
+        }
+
+        // A replacement takes a removed member's seat and can still vote
+        let removed = arbiters[0];
+        arbiters[0] = Pubkey::new_unique();
+        assert_eq!(dispute.record_vote(&arbiters, arbiters[0], 500).unwrap(), MAX_ARBITERS);
+        assert_eq!(dispute.votes.len(), MAX_ARBITERS);
+        assert!(dispute.votes.iter().all(|v| v.arbiter != removed));
+    }
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+/// Creator-funded bonus for an agent's first buyers. The tokens sit in a
+/// vault owned by this PDA; each of the first `max_buyers` unique buyers
+/// (by `BuyerRecord::buyer_number`) can claim `bonus_per_buyer` once until
+/// `claim_deadline`, after which the creator can reclaim the rest.
+#[account]
+#[derive(InitSpace)]
+pub struct EarlyBuyerRewards {
+    /// Agent whose early buyers are rewarded
+    pub agent: Pubkey,
+    
+    /// Buyers numbered up to this are eligible
+    pub max_buyers: u64,
+    
+    /// Tokens each eligible buyer can claim (base units)
+    pub bonus_per_buyer: u64,
+    
+    /// Claims are accepted until this timestamp
+    pub claim_deadline: i64,
+    
+    /// Bonuses claimed so far
+    pub claimed_count: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl EarlyBuyerRewards {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        8 +     // max_buyers
+        8 +     // bonus_per_buyer
+        8 +     // claim_deadline
+        8 +     // claimed_count
+        8 +     // created_at
+        1;      // bump
+
+    /// Whether claims are accepted at `now`
+    pub fn is_claim_open(&self, now: i64) -> bool {
+        now <= self.claim_deadline
+    }
+}
+
+/// Marks an early buyer bonus as claimed
+#[account]
+#[derive(InitSpace)]
+pub struct EarlyBuyerClaim {
+    /// Rewards the bonus was claimed from
+    pub rewards: Pubkey,
+    
+    /// Claiming buyer
+    pub buyer: Pubkey,
+    
+    /// Claim timestamp
+    pub claimed_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl EarlyBuyerClaim {
+    pub const INIT_SPACE: usize =
+        32 +    // rewards
+        32 +    // buyer
+        8 +     // claimed_at
+        1;      // bump
+}
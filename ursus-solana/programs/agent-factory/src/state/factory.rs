@@ -1,4 +1,50 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token::native_mint;
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+use crate::state::{decay_by_half_life, StakePosition};
+
+/// Number of platform-token fee discount tiers
+pub const FEE_DISCOUNT_TIERS: usize = 4;
+
+/// Seats on the arbitration council
+pub const MAX_ARBITERS: usize = 7;
+
+/// Entries in the model allowlist, and the length of a model identifier
+/// (matches the `model` field of an agent)
+pub const MAX_ALLOWED_MODELS: usize = 16;
+pub const MODEL_ID_LEN: usize = 20;
+
+/// Models accepted by a new factory
+pub const DEFAULT_ALLOWED_MODELS: [&str; 4] = ["gpt-4", "gpt-4o", "claude-3", "llama-3"];
+
+/// Creation rate-limit window of a new factory, and the longest window the
+/// authority can set
+pub const DEFAULT_CREATION_WINDOW_SECS: i64 = 24 * 60 * 60;
+pub const MAX_CREATION_WINDOW_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Creation demand added per agent created, in `creation_demand`'s fixed
+/// point
+pub const CREATION_DEMAND_UNIT: u64 = 1_000_000;
+
+/// Largest surge surcharge on the creation fee (bps), i.e. at most 10x
+pub const MAX_CREATION_SURGE_BPS: u64 = 90_000;
+
+/// Base units of the quote mint `quote_lamport_rate` prices, i.e. one whole
+/// token at 6 decimals
+pub const QUOTE_RATE_UNIT: u64 = 1_000_000;
+
+/// Platform fee discount for traders staking at least `min_stake` platform
+/// tokens (an unused tier has `min_stake == 0`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct FeeDiscountTier {
+    pub min_stake: u64,
+    pub discount_bps: u16,
+}
+
+impl FeeDiscountTier {
+    pub const INIT_SPACE: usize = 8 + 2;
+}
 
 #[account]
 #[derive(InitSpace)]
@@ -12,14 +58,273 @@ pub struct AgentFactory {
     /// Fee required to create a new agent (in lamports)
     pub creation_fee: u64,
     
+    /// Alternative currency for the creation fee, e.g. USDC (default = SOL only)
+    pub quote_mint: Pubkey,
+    
+    /// Creation fee when paid in `quote_mint` (in its base units)
+    pub quote_creation_fee: u64,
+    
     /// Total number of agents created
     pub total_agents: u64,
     
+    /// Delay between queueing an admin action and executing it
+    pub timelock_delay_secs: i64,
+    
+    /// Number of admin actions queued (next action id)
+    pub admin_action_count: u64,
+    
+    /// Whether `authority` is a program PDA (e.g. a multisig vault) that signs
+    /// via CPI; admin rent is then paid by a separate payer
+    pub admin_is_program: bool,
+    
+    /// Share of platform fees routed to the insurance fund (bps)
+    pub insurance_fee_bps: u16,
+    
+    /// Platform token; stakers get fee discounts (default = discounts off)
+    pub platform_mint: Pubkey,
+    
+    /// Staking pool of the platform token's agent, read for discounts
+    pub platform_staking_pool: Pubkey,
+    
+    /// Discount tiers by staked platform tokens, ascending
+    pub fee_discount_tiers: [FeeDiscountTier; FEE_DISCOUNT_TIERS],
+    
+    /// Share of graduation liquidity kept as protocol-owned liquidity (bps)
+    pub protocol_liquidity_bps: u16,
+    
+    /// Arbitration council resolving payment disputes (default = empty seat)
+    pub arbiters: [Pubkey; MAX_ARBITERS],
+    
+    /// Matching council votes needed to resolve a dispute
+    pub arbiter_threshold: u8,
+    
+    /// Models agents may claim to run on, lowercase and zero padded (all
+    /// zeroes = unused entry; no used entry = any model)
+    pub allowed_models: [[u8; MODEL_ID_LEN]; MAX_ALLOWED_MODELS],
+    
+    /// Fee for renaming an agent (in lamports)
+    pub rename_fee: u64,
+    
+    /// Period new agents stream creator fees over (0 = paid out per trade)
+    pub creator_fee_vesting_secs: i64,
+    
+    /// Most agents that may be created per rate-limit window (0 = no limit)
+    pub max_creations_per_window: u32,
+    
+    /// Length of the creation rate-limit window
+    pub creation_window_secs: i64,
+    
+    /// Start of the current rate-limit window
+    pub creation_window_start: i64,
+    
+    /// Agents created since `creation_window_start`
+    pub creations_in_window: u32,
+    
+    /// Creation fee surcharge per unit of recent creation demand (bps of the
+    /// fee; 0 = surge pricing off)
+    pub creation_surge_bps: u16,
+    
+    /// Time recent creation demand takes to decay by half
+    pub creation_surge_half_life_secs: i64,
+    
+    /// Recent creations decayed by `creation_surge_half_life_secs`, in units
+    /// of `CREATION_DEMAND_UNIT` per agent
+    pub creation_demand: u64,
+    
+    /// Last time `creation_demand` was updated
+    pub creation_demand_updated_at: i64,
+    
+    /// Key allowed to post reputation imported from other platforms besides
+    /// the authority (default = authority only)
+    pub reputation_attestor: Pubkey,
+    
+    /// Share of platform fees routed to `public_goods_recipient`, e.g. an
+    /// ecosystem grants program (bps, 0 = off)
+    pub public_goods_fee_bps: u16,
+    
+    /// Address receiving the public goods share
+    pub public_goods_recipient: Pubkey,
+    
+    /// Public goods fees paid out so far (lamports)
+    pub total_public_goods_fees: u64,
+    
+    /// Lamports `QUOTE_RATE_UNIT` base units of `quote_mint` are worth when
+    /// disputes and insurance value quote payments (0 = not valued)
+    pub quote_lamport_rate: u64,
+    
     /// Bump seed for PDA
     pub bump: u8,
 }
 
 impl AgentFactory {
-    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 1;
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 32 + 8 + 8 + 8 + 8 + 1 + 2 + 32 + 32
+        + FeeDiscountTier::INIT_SPACE * FEE_DISCOUNT_TIERS + 2 + 32 * MAX_ARBITERS + 1
+        + MODEL_ID_LEN * MAX_ALLOWED_MODELS + 8 + 8 + 4 + 8 + 8 + 4 + 2 + 8 + 8 + 8 + 32 + 2 + 32 + 8 + 8 + 1;
+
+    /// Whether discount tiers are well formed: each used tier needs more
+    /// stake and gives a larger discount than the one before, and unused
+    /// tiers come last
+    pub fn fee_discount_tiers_valid(tiers: &[FeeDiscountTier; FEE_DISCOUNT_TIERS]) -> bool {
+        let mut prev = FeeDiscountTier::default();
+        let mut ended = false;
+        for tier in tiers {
+            if tier.min_stake == 0 {
+                ended = true;
+                if tier.discount_bps != 0 {
+                    return false;
+                }
+                continue;
+            }
+            if ended
+                || tier.min_stake <= prev.min_stake
+                || tier.discount_bps <= prev.discount_bps
+                || tier.discount_bps as u64 > BPS_DENOMINATOR
+            {
+                return false;
+            }
+            prev = *tier;
+        }
+        true
+    }
+
+    /// Whether a council is well formed: no duplicate members and a
+    /// threshold between 1 and the number of seated members
+    pub fn arbitration_council_valid(arbiters: &[Pubkey; MAX_ARBITERS], threshold: u8) -> bool {
+        let seated: Vec<&Pubkey> = arbiters.iter().filter(|a| **a != Pubkey::default()).collect();
+        let unique = seated
+            .iter()
+            .enumerate()
+            .all(|(i, arbiter)| !seated[..i].contains(arbiter));
+        unique && threshold >= 1 && threshold as usize <= seated.len()
+    }
+
+    /// Allowlist entry for `model`: lowercase ASCII letters, digits, `-`,
+    /// `.` or `_`, at most `MODEL_ID_LEN` bytes
+    pub fn model_id(model: &str) -> Option<[u8; MODEL_ID_LEN]> {
+        let valid = !model.is_empty()
+            && model.len() <= MODEL_ID_LEN
+            && model.bytes().all(|b| {
+                b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'.' || b == b'_'
+            });
+        if !valid {
+            return None;
+        }
+        let mut id = [0u8; MODEL_ID_LEN];
+        id[..model.len()].copy_from_slice(model.as_bytes());
+        Some(id)
+    }
+
+    /// Whether an allowlist is well formed: used entries are valid, zero
+    /// padded identifiers with no duplicates
+    pub fn allowed_models_valid(models: &[[u8; MODEL_ID_LEN]; MAX_ALLOWED_MODELS]) -> bool {
+        let used: Vec<&[u8; MODEL_ID_LEN]> = models.iter().filter(|m| **m != [0; MODEL_ID_LEN]).collect();
+        used.iter().enumerate().all(|(i, id)| {
+            let len = id.iter().position(|b| *b == 0).unwrap_or(MODEL_ID_LEN);
+            id[len..].iter().all(|b| *b == 0)
+                && std::str::from_utf8(&id[..len]).ok().and_then(Self::model_id) == Some(**id)
+                && !used[..i].contains(id)
+        })
+    }
+
+    /// Whether agents may claim to run on `model`, compared case-insensitively
+    /// against the allowlist; an empty allowlist accepts any model that fits
+    /// an agent
+    pub fn is_model_allowed(&self, model: &str) -> bool {
+        if self.allowed_models.iter().all(|m| *m == [0; MODEL_ID_LEN]) {
+            return model.len() <= MODEL_ID_LEN;
+        }
+        match Self::model_id(&model.to_ascii_lowercase()) {
+            Some(id) => self.allowed_models.contains(&id),
+            None => false,
+        }
+    }
+
+    /// Count a new agent against the creation rate limit. A window opens
+    /// with the first creation after the previous one has elapsed, so a
+    /// spam wave can create at most `max_creations_per_window` agents in any
+    /// window it starts.
+    pub fn record_creation(&mut self, now: i64) -> Result<()> {
+        if now.saturating_sub(self.creation_window_start) >= self.creation_window_secs {
+            self.creation_window_start = now;
+            self.creations_in_window = 0;
+        }
+        require!(
+            self.max_creations_per_window == 0
+                || self.creations_in_window < self.max_creations_per_window,
+            AgentFactoryError::CreationRateLimited
+        );
+        self.creations_in_window = self.creations_in_window
+            .checked_add(1)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        self.creation_demand = self.creation_demand_at(now).saturating_add(CREATION_DEMAND_UNIT);
+        self.creation_demand_updated_at = now;
+        Ok(())
+    }
+
+    /// Recent creation demand decayed to `now`
+    pub fn creation_demand_at(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.creation_demand_updated_at).max(0) as u64;
+        decay_by_half_life(self.creation_demand, elapsed, self.creation_surge_half_life_secs.max(0) as u64)
+    }
+
+    /// Creation fee surcharge (bps) at `now`: `creation_surge_bps` per recent
+    /// creation, capped at `MAX_CREATION_SURGE_BPS`
+    pub fn creation_surge_bps_at(&self, now: i64) -> u64 {
+        if self.creation_surge_bps == 0 {
+            return 0;
+        }
+        let surge = self.creation_demand_at(now) as u128 * self.creation_surge_bps as u128
+            / CREATION_DEMAND_UNIT as u128;
+        surge.min(MAX_CREATION_SURGE_BPS as u128) as u64
+    }
+
+    /// `fee` with a surge surcharge of `surge_bps`
+    pub fn apply_creation_surge(fee: u64, surge_bps: u64) -> Result<u64> {
+        let surcharge = fee as u128 * surge_bps as u128 / BPS_DENOMINATOR as u128;
+        u64::try_from(fee as u128 + surcharge).map_err(|_| error!(AgentFactoryError::MathOverflow))
+    }
+
+    /// Value of `amount` of `mint` in lamports: wrapped SOL at par, and the
+    /// quote mint at the authority-set `quote_lamport_rate`. Other mints, and
+    /// the quote mint before a rate is set, have no lamport value.
+    pub fn lamport_value(&self, mint: &Pubkey, amount: u64) -> Option<u64> {
+        if *mint == native_mint::ID {
+            return Some(amount);
+        }
+        if *mint != self.quote_mint || self.quote_mint == Pubkey::default() || self.quote_lamport_rate == 0 {
+            return None;
+        }
+        let value = amount as u128 * self.quote_lamport_rate as u128 / QUOTE_RATE_UNIT as u128;
+        u64::try_from(value).ok()
+    }
+
+    /// Whether `key` holds a council seat
+    pub fn is_arbiter(&self, key: &Pubkey) -> bool {
+        *key != Pubkey::default() && self.arbiters.contains(key)
+    }
+
+    /// Whether `key` may post imported reputation: the authority or the
+    /// designated attestor
+    pub fn is_reputation_attestor(&self, key: &Pubkey) -> bool {
+        *key == self.authority
+            || (*key != Pubkey::default() && *key == self.reputation_attestor)
+    }
+
+    /// Platform fee discount (bps) earned by `trader` through their stake in
+    /// the platform token's staking pool
+    pub fn fee_discount_bps(&self, stake: &StakePosition, trader: &Pubkey) -> Result<u16> {
+        require!(
+            self.platform_mint != Pubkey::default()
+                && stake.pool == self.platform_staking_pool
+                && stake.owner == *trader,
+            AgentFactoryError::InvalidPlatformStake
+        );
+        Ok(self.fee_discount_tiers
+            .iter()
+            .filter(|tier| tier.min_stake > 0 && stake.amount >= tier.min_stake)
+            .map(|tier| tier.discount_bps)
+            .max()
+            .unwrap_or(0))
+    }
 }
 
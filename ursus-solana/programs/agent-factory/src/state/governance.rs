@@ -0,0 +1,251 @@
+use anchor_lang::prelude::*;
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+use crate::state::{Agent, MAX_AGENT_TAGS, MAX_TAG_LEN};
+
+/// Shortest and longest voting period a governance can use
+pub const MIN_VOTING_PERIOD_SECS: i64 = 60 * 60;
+pub const MAX_VOTING_PERIOD_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Per-agent governance settings. Voting weight comes from the agent's
+/// staking pool (1x per staked token, more when vote-escrow locked).
+#[account]
+#[derive(InitSpace)]
+pub struct Governance {
+    /// Agent governed
+    pub agent: Pubkey,
+    
+    /// Share of the total voting weight that must vote (in bps)
+    pub quorum_bps: u16,
+    
+    /// Share of the cast votes that must be in favor (in bps)
+    pub threshold_bps: u16,
+    
+    /// How long proposals are open for voting
+    pub voting_period_secs: i64,
+    
+    /// Voting weight needed to create a proposal
+    pub proposal_threshold: u64,
+    
+    /// Number of proposals created (next proposal id)
+    pub proposal_count: u64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Governance {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        2 +     // quorum_bps
+        2 +     // threshold_bps
+        8 +     // voting_period_secs
+        8 +     // proposal_threshold
+        8 +     // proposal_count
+        1;      // bump
+
+    pub fn validate_config(quorum_bps: u16, threshold_bps: u16, voting_period_secs: i64) -> Result<()> {
+        require!(
+            quorum_bps > 0 && quorum_bps as u64 <= BPS_DENOMINATOR,
+            AgentFactoryError::InvalidGovernanceConfig
+        );
+        require!(
+            threshold_bps > 0 && threshold_bps as u64 <= BPS_DENOMINATOR,
+            AgentFactoryError::InvalidGovernanceConfig
+        );
+        require!(
+            (MIN_VOTING_PERIOD_SECS..=MAX_VOTING_PERIOD_SECS).contains(&voting_period_secs),
+            AgentFactoryError::InvalidGovernanceConfig
+        );
+        Ok(())
+    }
+}
+
+/// Parameter change a proposal applies when executed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum ProposalAction {
+    /// Change the share of creator fees routed to stakers
+    SetStakingFee {
+        staking_fee_bps: u16,
+    },
+    
+    /// Change the x402 service price range
+    SetServicePrices {
+        min_payment_amount: u64,
+        max_payment_amount: u64,
+    },
+    
+    /// Replace the agent description and tags
+    UpdateMetadata {
+        #[max_len(200)]
+        description: String,
+        #[max_len(MAX_AGENT_TAGS, MAX_TAG_LEN)]
+        tags: Vec<String>,
+    },
+    
+    /// Change the governance settings themselves
+    UpdateGovernance {
+        quorum_bps: u16,
+        threshold_bps: u16,
+        voting_period_secs: i64,
+        proposal_threshold: u64,
+    },
+    
+    /// Change the share of creator fees routed to the DAO treasury
+    SetTreasuryFee {
+        treasury_fee_bps: u16,
+    },
+    
+    /// Pay SOL from the DAO treasury
+    TransferSol {
+        recipient: Pubkey,
+        amount: u64,
+    },
+    
+    /// Pay SPL tokens from the DAO treasury's token account for `mint`
+    TransferToken {
+        mint: Pubkey,
+        /// Recipient token account
+        recipient: Pubkey,
+        amount: u64,
+    },
+}
+
+impl ProposalAction {
+    pub const INIT_SPACE: usize =
+        1 +                 // variant
+        4 + 200 +           // description (largest variant: UpdateMetadata)
+        4 + MAX_AGENT_TAGS * (4 + MAX_TAG_LEN); // tags
+
+    /// Check the action can be applied, so invalid proposals fail up front
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ProposalAction::SetStakingFee { staking_fee_bps } => {
+                require!(
+                    *staking_fee_bps as u64 <= BPS_DENOMINATOR,
+                    AgentFactoryError::InvalidProposal
+                );
+            }
+            ProposalAction::SetServicePrices { min_payment_amount, max_payment_amount } => {
+                require!(
+                    *max_payment_amount == 0 || min_payment_amount <= max_payment_amount,
+                    AgentFactoryError::InvalidProposal
+                );
+            }
+            ProposalAction::UpdateMetadata { description, tags } => {
+                require!(description.len() <= 200, AgentFactoryError::DescriptionTooLong);
+                Agent::validate_tags(tags)?;
+            }
+            ProposalAction::UpdateGovernance { quorum_bps, threshold_bps, voting_period_secs, .. } => {
+                Governance::validate_config(*quorum_bps, *threshold_bps, *voting_period_secs)?;
+            }
+            ProposalAction::SetTreasuryFee { treasury_fee_bps } => {
+                require!(
+                    *treasury_fee_bps as u64 <= BPS_DENOMINATOR,
+                    AgentFactoryError::InvalidProposal
+                );
+            }
+            ProposalAction::TransferSol { amount, .. }
+            | ProposalAction::TransferToken { amount, .. } => {
+                require!(*amount > 0, AgentFactoryError::InvalidProposal);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A parameter change put to a vote of the agent's stakers
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    /// Agent the proposal applies to
+    pub agent: Pubkey,
+    
+    /// Sequential id per agent, starting at 0
+    pub proposal_id: u64,
+    
+    /// Wallet that created the proposal
+    pub proposer: Pubkey,
+    
+    /// Change applied on execution
+    pub action: ProposalAction,
+    
+    /// Voting weight is read from stake checkpoints at this time
+    pub snapshot_ts: i64,
+    
+    /// Voting window
+    pub start_ts: i64,
+    pub end_ts: i64,
+    
+    /// Pool voting weight when the proposal was created (quorum base)
+    pub total_voting_weight: u64,
+    
+    /// Quorum and threshold copied from the governance at creation
+    pub quorum_bps: u16,
+    pub threshold_bps: u16,
+    
+    /// Votes cast (voting weight)
+    pub for_votes: u64,
+    pub against_votes: u64,
+    
+    /// Whether the action has been applied
+    pub executed: bool,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        8 +     // proposal_id
+        32 +    // proposer
+        ProposalAction::INIT_SPACE + // action
+        8 +     // snapshot_ts
+        8 +     // start_ts
+        8 +     // end_ts
+        8 +     // total_voting_weight
+        2 +     // quorum_bps
+        2 +     // threshold_bps
+        8 +     // for_votes
+        8 +     // against_votes
+        1 +     // executed
+        1;      // bump
+
+    /// Whether enough weight voted and enough of it was in favor
+    pub fn is_passed(&self) -> bool {
+        let cast = self.for_votes as u128 + self.against_votes as u128;
+        let quorum = self.total_voting_weight as u128 * self.quorum_bps as u128
+            / BPS_DENOMINATOR as u128;
+        
+        self.for_votes > 0
+            && cast >= quorum
+            && self.for_votes as u128 * BPS_DENOMINATOR as u128
+                >= cast * self.threshold_bps as u128
+    }
+}
+
+/// One staker's vote on a proposal; its existence prevents double voting
+#[account]
+#[derive(InitSpace)]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    
+    /// Voting weight at the proposal snapshot
+    pub weight: u64,
+    
+    pub support: bool,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub const INIT_SPACE: usize =
+        32 +    // proposal
+        32 +    // voter
+        8 +     // weight
+        1 +     // support
+        1;      // bump
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+
+/// Most agent tokens a basket can hold
+pub const MAX_BASKET_CONSTITUENTS: usize = 8;
+
+/// Base units in one whole basket token (9 decimals, like agent tokens)
+pub const BASKET_TOKEN_UNIT: u64 = 1_000_000_000;
+
+/// One agent token in a basket
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct BasketConstituent {
+    /// Agent whose token is held
+    pub agent: Pubkey,
+    
+    /// Agent token mint
+    pub mint: Pubkey,
+    
+    /// Agent token base units backing one whole basket token; the ratio
+    /// between constituents sets their weights
+    pub units: u64,
+}
+
+/// Weighted basket of agent tokens with its own SPL token.
+///
+/// `mint_basket` takes every constituent in proportion to its `units` and
+/// mints basket tokens; `redeem_basket` burns them and pays the constituents
+/// back out. The constituents sit in ATAs owned by this PDA, so each basket
+/// token is always fully backed.
+#[account]
+#[derive(InitSpace)]
+pub struct IndexBasket {
+    /// Creator-chosen basket ID, part of the PDA seeds
+    pub basket_id: u64,
+    
+    /// Wallet that defined the basket
+    pub creator: Pubkey,
+    
+    /// Basket name (max 32 chars)
+    #[max_len(32)]
+    pub name: String,
+    
+    /// Basket token mint: `["basket_mint", basket]`
+    pub basket_mint: Pubkey,
+    
+    /// Agent tokens held per basket token
+    #[max_len(MAX_BASKET_CONSTITUENTS)]
+    pub constituents: Vec<BasketConstituent>,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl IndexBasket {
+    pub const INIT_SPACE: usize =
+        8 +             // basket_id
+        32 +            // creator
+        4 + 32 +        // name
+        32 +            // basket_mint
+        4 + MAX_BASKET_CONSTITUENTS * (32 + 32 + 8) + // constituents
+        8 +             // created_at
+        1;              // bump
+
+    /// Constituent base units backing `amount` basket base units; minting
+    /// rounds up so deposits always cover the new supply, redeeming rounds
+    /// down
+    pub fn constituent_amount(units: u64, amount: u64, round_up: bool) -> Result<u64> {
+        let product = (units as u128)
+            .checked_mul(amount as u128)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        let unit = BASKET_TOKEN_UNIT as u128;
+        let result = if round_up {
+            product.div_ceil(unit)
+        } else {
+            product / unit
+        };
+        Ok(u64::try_from(result).map_err(|_| AgentFactoryError::MathOverflow)?)
+    }
+}
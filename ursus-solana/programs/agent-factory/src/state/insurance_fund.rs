@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+
+/// Premium charged on service insurance coverage (5%)
+pub const INSURANCE_PREMIUM_BPS: u64 = 500;
+
+/// Platform-wide insurance fund. Holds SOL (in this account) capitalized by
+/// a slice of platform fees and voluntary deposits; the factory authority
+/// pays claims from it to users harmed by exploited or rugged agents.
+#[account]
+#[derive(InitSpace)]
+pub struct InsuranceFund {
+    /// Platform fee share collected so far (in lamports)
+    pub total_fees_collected: u64,
+    
+    /// Voluntary deposits so far, e.g. x402 revenue (in lamports)
+    pub total_deposits: u64,
+    
+    /// Claims paid out so far (in lamports)
+    pub total_claims_paid: u64,
+    
+    /// Number of claims paid (next claim id)
+    pub claim_count: u64,
+    
+    /// Service insurance premiums collected so far (in lamports)
+    pub total_premiums: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl InsuranceFund {
+    pub const INIT_SPACE: usize =
+        8 +     // total_fees_collected
+        8 +     // total_deposits
+        8 +     // total_claims_paid
+        8 +     // claim_count
+        8 +     // total_premiums
+        8 +     // created_at
+        1;      // bump
+}
+
+/// Insurance bought alongside an x402 payment. If a dispute over the payment
+/// awards the claimant more than the provider bond holds, the insurance fund
+/// covers the shortfall up to `coverage`.
+#[account]
+#[derive(InitSpace)]
+pub struct InsurancePolicy {
+    /// Insured payment
+    pub payment_record: Pubkey,
+    
+    /// Wallet that bought the policy
+    pub holder: Pubkey,
+    
+    /// Maximum shortfall covered (in lamports), at most the payment's lamport
+    /// value when the policy was bought (quote payments at the factory's
+    /// `quote_lamport_rate`)
+    pub coverage: u64,
+    
+    /// Premium paid into the insurance fund (in lamports)
+    pub premium: u64,
+    
+    /// Paid out by `pay_insured_claim` (in lamports, 0 until claimed)
+    pub claimed_amount: u64,
+    
+    /// Purchase timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl InsurancePolicy {
+    pub const INIT_SPACE: usize =
+        32 +    // payment_record
+        32 +    // holder
+        8 +     // coverage
+        8 +     // premium
+        8 +     // claimed_amount
+        8 +     // created_at
+        1;      // bump
+
+    /// Premium owed for `coverage` lamports of coverage, rounded up
+    pub fn premium_for(coverage: u64) -> Option<u64> {
+        (coverage as u128)
+            .checked_mul(INSURANCE_PREMIUM_BPS as u128)?
+            .checked_add(9_999)
+            .map(|p| p / 10_000)
+            .and_then(|p| u64::try_from(p).ok())
+    }
+
+    /// Payout owed on a dispute awarding `awarded_amount`, of which the
+    /// provider bond covered `slashed_amount`: the shortfall, up to the
+    /// coverage
+    pub fn payout(&self, awarded_amount: u64, slashed_amount: u64) -> u64 {
+        awarded_amount.saturating_sub(slashed_amount).min(self.coverage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(coverage: u64) -> InsurancePolicy {
+        InsurancePolicy {
+            payment_record: Pubkey::new_unique(),
+            holder: Pubkey::new_unique(),
+            coverage,
+            premium: InsurancePolicy::premium_for(coverage).unwrap(),
+            claimed_amount: 0,
+            created_at: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn payout_covers_the_shortfall() {
+        assert_eq!(policy(500).payout(600, 200), 400);
+    }
+
+    #[test]
+    fn payout_is_capped_at_the_coverage() {
+        assert_eq!(policy(500).payout(900, 200), 500);
+    }
+
+    #[test]
+    fn nothing_is_paid_when_the_bond_covers_the_award() {
+        assert_eq!(policy(500).payout(600, 600), 0);
+        assert_eq!(policy(500).payout(0, 0), 0);
+    }
+
+    #[test]
+    fn premiums_round_up() {
+        assert_eq!(InsurancePolicy::premium_for(20).unwrap(), 1);
+        assert_eq!(InsurancePolicy::premium_for(21).unwrap(), 2);
+        assert_eq!(InsurancePolicy::premium_for(0).unwrap(), 0);
+    }
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use super::{ArbiterVote, MAX_ARBITERS};
+
+/// Most milestones a job can be split into
+pub const MAX_JOB_MILESTONES: usize = 8;
+
+/// Longest a job may run before the payer can reclaim unsubmitted
+/// milestones (365 days)
+pub const MAX_JOB_DURATION_SECS: i64 = 365 * 24 * 60 * 60;
+
+/// Progress of one milestone of a job
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum MilestoneStatus {
+    /// Awaiting the provider's work
+    #[default]
+    Pending,
+
+    /// Delivered; the payer can release or dispute it until the review
+    /// period ends, after which anyone can release it
+    Submitted,
+
+    /// Paid to the provider, less any refund the council awarded
+    Released,
+
+    /// Disputed by the payer, awaiting the council
+    Disputed,
+
+    /// Refunded in full to the payer
+    Refunded,
+}
+
+/// One installment of a job's escrow
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct Milestone {
+    /// Escrowed for this milestone (smallest units)
+    pub amount: u64,
+
+    pub status: MilestoneStatus,
+
+    /// Hash of the deliverable the provider submitted (stored off-chain)
+    pub deliverable_hash: [u8; 32],
+
+    /// Submission timestamp (0 = not submitted)
+    pub submitted_at: i64,
+
+    /// Refunded to the payer by the council (smallest units)
+    pub refunded: u64,
+}
+
+impl Milestone {
+    pub const INIT_SPACE: usize = 8 + 1 + 32 + 8 + 8;
+
+    /// Whether the milestone is paid out or refunded
+    pub fn is_settled(&self) -> bool {
+        matches!(self.status, MilestoneStatus::Released | MilestoneStatus::Refunded)
+    }
+}
+
+/// Escrowed engagement with an agent paid out per milestone, such as a
+/// research report delivered in parts.
+///
+/// The payer locks the sum of all milestones up front. The provider submits
+/// each milestone's deliverable, and the payer releases it or disputes it
+/// to the arbitration council within the review period. Milestones never
+/// submitted by the deadline can be reclaimed.
+#[account]
+#[derive(InitSpace)]
+pub struct Job {
+    /// Agent providing the service
+    pub agent: Pubkey,
+
+    /// Payer funding the job
+    pub payer: Pubkey,
+
+    /// Payment token (e.g. USDC)
+    pub mint: Pubkey,
+
+    /// Hashed service the job is for
+    pub service_id: [u8; 32],
+
+    #[max_len(MAX_JOB_MILESTONES)]
+    pub milestones: Vec<Milestone>,
+
+    /// How long the payer has to review a submitted milestone (seconds),
+    /// the agent's service timeout at creation
+    pub review_period_secs: i64,
+
+    /// After this the payer can reclaim unsubmitted milestones
+    pub deadline: i64,
+
+    /// Paid to the provider so far (smallest units)
+    pub released: u64,
+
+    /// Refunded to the payer so far (smallest units)
+    pub refunded: u64,
+
+    /// Hash of the payer's evidence for the disputed milestone
+    pub evidence_hash: [u8; 32],
+
+    /// Council votes on the disputed milestone: the refund (bps) they back
+    #[max_len(MAX_ARBITERS)]
+    pub dispute_votes: Vec<ArbiterVote>,
+
+    /// Creation timestamp
+    pub created_at: i64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Job {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        32 +    // payer
+        32 +    // mint
+        32 +    // service_id
+        4 + Milestone::INIT_SPACE * MAX_JOB_MILESTONES + // milestones
+        8 +     // review_period_secs
+        8 +     // deadline
+        8 +     // released
+        8 +     // refunded
+        32 +    // evidence_hash
+        4 + ArbiterVote::INIT_SPACE * MAX_ARBITERS + // dispute_votes
+        8 +     // created_at
+        1;      // bump
+
+    /// Milestone `index`, which must be in `status`
+    pub fn milestone_in(&mut self, index: u8, status: MilestoneStatus) -> Result<&mut Milestone> {
+        let milestone = self.milestones
+            .get_mut(index as usize)
+            .ok_or(AgentFactoryError::InvalidMilestone)?;
+        require!(milestone.status == status, AgentFactoryError::InvalidMilestone);
+        Ok(milestone)
+    }
+
+    /// Whether a submitted milestone's review period has ended by `now`
+    pub fn review_ended(&self, milestone: &Milestone, now: i64) -> bool {
+        now >= milestone.submitted_at.saturating_add(self.review_period_secs)
+    }
+
+    /// Whether any milestone awaits the council; one dispute per job at a time
+    pub fn has_dispute(&self) -> bool {
+        self.milestones.iter().any(|m| m.status == MilestoneStatus::Disputed)
+    }
+
+    /// Whether every milestone is paid out or refunded
+    pub fn is_settled(&self) -> bool {
+        self.milestones.iter().all(Milestone::is_settled)
+    }
+}
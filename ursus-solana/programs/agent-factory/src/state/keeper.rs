@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use super::{Agent, DcaSchedule, QueuedAdminAction};
+
+/// Permissionless crank a keeper task tips for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum KeeperTaskKind {
+    /// Each executed interval of a DCA schedule (`execute_dca`)
+    Dca,
+    
+    /// Graduating an agent once it reaches its threshold (`graduate_agent`)
+    Graduation,
+    
+    /// Applying a queued admin action once its timelock expires
+    /// (`execute_admin_action`); cancelling the action also closes it, so
+    /// cancel its task too
+    AdminAction,
+}
+
+impl KeeperTaskKind {
+    /// Units of work done on `target` so far: DCA intervals executed, or 1
+    /// once the agent has graduated or the admin action has been applied
+    pub fn progress(&self, target: &AccountInfo) -> Result<u64> {
+        let live = target.owner == &crate::ID && !target.data_is_empty();
+        match self {
+            KeeperTaskKind::Dca => {
+                require!(live, AgentFactoryError::InvalidKeeperTask);
+                let dca = DcaSchedule::try_deserialize(&mut &target.try_borrow_data()?[..])?;
+                Ok(dca.executed_intervals)
+            }
+            KeeperTaskKind::Graduation => {
+                require!(live, AgentFactoryError::InvalidKeeperTask);
+                let agent = Agent::try_deserialize(&mut &target.try_borrow_data()?[..])?;
+                Ok(agent.is_graduated as u64)
+            }
+            // Executed actions are closed
+            KeeperTaskKind::AdminAction => {
+                if !live {
+                    return Ok(1);
+                }
+                QueuedAdminAction::try_deserialize(&mut &target.try_borrow_data()?[..])?;
+                Ok(0)
+            }
+        }
+    }
+}
+
+/// Recurring or one-off crank registered once and tipped from a SOL deposit.
+///
+/// Keepers run the crank itself (e.g. `execute_dca`), then `crank_keeper_task`
+/// in the same transaction; the task pays `tip_lamports` for each unit of
+/// work done on `target` since the last tip. The deposit is held by this PDA.
+#[account]
+#[derive(InitSpace)]
+pub struct KeeperTask {
+    /// Wallet that registered the task and can cancel it
+    pub owner: Pubkey,
+    
+    /// Owner-chosen task id
+    pub task_id: u64,
+    
+    /// Crank being tipped
+    pub kind: KeeperTaskKind,
+    
+    /// Account the crank works on (DCA schedule, agent or queued action)
+    pub target: Pubkey,
+    
+    /// Tip per unit of work (in lamports)
+    pub tip_lamports: u64,
+    
+    /// Work done on `target` as of the last tip
+    pub progress: u64,
+    
+    /// Tips paid to keepers so far (in lamports)
+    pub total_tips_paid: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl KeeperTask {
+    pub const INIT_SPACE: usize =
+        32 +    // owner
+        8 +     // task_id
+        1 +     // kind
+        32 +    // target
+        8 +     // tip_lamports
+        8 +     // progress
+        8 +     // total_tips_paid
+        8 +     // created_at
+        1;      // bump
+}
@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+use super::REWARD_PRECISION;
+
+/// Per-agent liquidity mining program for a graduated agent's DEX pool.
+///
+/// LP tokens of the pool are staked into a vault owned by this PDA and earn
+/// the agent token at `reward_rate` per second until `period_finish`. The
+/// creator schedules emissions by funding the rewards vault; funding a
+/// running period rolls its undistributed rewards into the new one. Rewards
+/// emitted while nothing is staked stay in the vault.
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityMining {
+    /// Graduated agent whose token is emitted
+    pub agent: Pubkey,
+    
+    /// LP token of the agent's DEX pool
+    pub lp_mint: Pubkey,
+    
+    /// LP tokens currently staked (base units)
+    pub total_staked: u64,
+    
+    /// Accumulated rewards per staked LP base unit, scaled by `REWARD_PRECISION`
+    pub reward_per_token: u128,
+    
+    /// Agent tokens emitted per second (base units)
+    pub reward_rate: u64,
+    
+    /// End of the current emission period
+    pub period_finish: i64,
+    
+    /// Last time `reward_per_token` was brought up to date
+    pub last_update_ts: i64,
+    
+    /// Agent tokens funded so far (base units)
+    pub total_rewards_funded: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl LiquidityMining {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        32 +    // lp_mint
+        8 +     // total_staked
+        16 +    // reward_per_token
+        8 +     // reward_rate
+        8 +     // period_finish
+        8 +     // last_update_ts
+        8 +     // total_rewards_funded
+        8 +     // created_at
+        1;      // bump
+
+    /// Emit rewards to stakers up to `now` (or the end of the period)
+    pub fn accrue(&mut self, now: i64) -> Result<()> {
+        let applicable = now.min(self.period_finish);
+        if applicable > self.last_update_ts && self.total_staked > 0 {
+            let emitted = (self.reward_rate as u128)
+                .checked_mul((applicable - self.last_update_ts) as u128)
+                .and_then(|emitted| emitted.checked_mul(REWARD_PRECISION))
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            self.reward_per_token = self.reward_per_token
+                .checked_add(emitted / self.total_staked as u128)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+        }
+        self.last_update_ts = self.last_update_ts.max(applicable);
+        Ok(())
+    }
+
+    /// Start a `duration_secs` emission period from `now` with `amount` new
+    /// tokens plus whatever the running period had left; call after `accrue`
+    pub fn schedule(&mut self, amount: u64, duration_secs: i64, now: i64) -> Result<()> {
+        let leftover = if now < self.period_finish {
+            (self.period_finish - now) as u128 * self.reward_rate as u128
+        } else {
+            0
+        };
+        let reward_rate = (amount as u128 + leftover) / duration_secs as u128;
+        require!(reward_rate > 0, AgentFactoryError::InvalidRewardSchedule);
+        
+        self.reward_rate = u64::try_from(reward_rate).map_err(|_| AgentFactoryError::MathOverflow)?;
+        self.period_finish = now
+            .checked_add(duration_secs)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        self.last_update_ts = now;
+        self.total_rewards_funded = self.total_rewards_funded
+            .checked_add(amount)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+/// One wallet's LP stake in a liquidity mining program
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityMiningPosition {
+    /// Program the LP tokens are staked in
+    pub mining: Pubkey,
+    
+    /// Staker's wallet
+    pub owner: Pubkey,
+    
+    /// LP tokens staked (base units)
+    pub amount: u64,
+    
+    /// Program `reward_per_token` at the last settlement
+    pub reward_per_token_paid: u128,
+    
+    /// Agent tokens earned but not yet claimed (base units)
+    pub pending_rewards: u64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl LiquidityMiningPosition {
+    pub const INIT_SPACE: usize =
+        32 +    // mining
+        32 +    // owner
+        8 +     // amount
+        16 +    // reward_per_token_paid
+        8 +     // pending_rewards
+        1;      // bump
+
+    /// Credit rewards earned since the last settlement; call after
+    /// `LiquidityMining::accrue` and before changing `amount`
+    pub fn settle(&mut self, mining: &LiquidityMining) -> Result<()> {
+        let earned = (self.amount as u128)
+            .checked_mul(mining.reward_per_token - self.reward_per_token_paid)
+            .ok_or(AgentFactoryError::MathOverflow)?
+            / REWARD_PRECISION;
+        
+        self.pending_rewards = u64::try_from(earned)
+            .ok()
+            .and_then(|earned| self.pending_rewards.checked_add(earned))
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        self.reward_per_token_paid = mining.reward_per_token;
+        
+        Ok(())
+    }
+}
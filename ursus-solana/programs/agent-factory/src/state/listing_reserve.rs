@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use super::Agent;
+
+/// Largest share of the total supply an agent can reserve for listings (10%)
+pub const MAX_LISTING_RESERVE_BPS: u16 = 1000;
+
+/// Longest delay after graduation before a listing reserve unlocks (1 year)
+pub const MAX_LISTING_RELEASE_DELAY_SECS: i64 = 365 * 24 * 60 * 60;
+
+/// Supply set aside at creation for exchange listings and market making:
+/// `["listing_reserve", agent]`. The tokens sit in a vault owned by this PDA
+/// (`["listing_reserve_vault", listing_reserve]`) and the creator can only
+/// release them once the agent has graduated and `release_delay_secs` passed.
+#[account]
+#[derive(InitSpace)]
+pub struct ListingReserve {
+    /// Agent whose tokens are reserved
+    pub agent: Pubkey,
+
+    /// Share of the total supply reserved (basis points)
+    pub reserve_bps: u16,
+
+    /// Tokens minted into the reserve at creation (base units)
+    pub amount: u64,
+
+    /// Tokens released so far
+    pub released: u64,
+
+    /// Seconds after graduation before the reserve unlocks
+    pub release_delay_secs: i64,
+
+    /// Creation timestamp
+    pub created_at: i64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ListingReserve {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        2 +     // reserve_bps
+        8 +     // amount
+        8 +     // released
+        8 +     // release_delay_secs
+        8 +     // created_at
+        1;      // bump
+
+    /// Timestamp the reserve unlocks at, once `agent` has graduated
+    pub fn unlocks_at(&self, agent: &Agent) -> Option<i64> {
+        if !agent.is_graduated {
+            return None;
+        }
+        agent.graduated_at.checked_add(self.release_delay_secs)
+    }
+}
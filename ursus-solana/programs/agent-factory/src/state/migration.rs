@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+
+/// Swap from a project's previous token into its relaunched agent token.
+///
+/// Holders send old tokens to `migrate_tokens` and receive
+/// `amount * ratio_numerator / ratio_denominator` agent tokens from a vault
+/// the creator funds up front. The old tokens stay locked in a vault owned by
+/// this PDA; nothing can move them out.
+#[account]
+#[derive(InitSpace)]
+pub struct TokenMigration {
+    /// Agent token paid out
+    pub agent: Pubkey,
+    
+    /// Mint of the token being migrated from
+    pub old_mint: Pubkey,
+    
+    /// Agent tokens paid per `ratio_denominator` old tokens (base units)
+    pub ratio_numerator: u64,
+    
+    /// Old tokens per `ratio_numerator` agent tokens (base units)
+    pub ratio_denominator: u64,
+    
+    /// Old tokens received so far (base units)
+    pub total_migrated: u64,
+    
+    /// Agent tokens paid out so far (base units)
+    pub total_paid: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl TokenMigration {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        32 +    // old_mint
+        8 +     // ratio_numerator
+        8 +     // ratio_denominator
+        8 +     // total_migrated
+        8 +     // total_paid
+        8 +     // created_at
+        1;      // bump
+
+    /// Agent tokens owed for `amount` old tokens, rounded down
+    pub fn payout(&self, amount: u64) -> Result<u64> {
+        let payout = (amount as u128)
+            .checked_mul(self.ratio_numerator as u128)
+            .ok_or(AgentFactoryError::MathOverflow)?
+            / self.ratio_denominator as u128;
+        Ok(u64::try_from(payout).map_err(|_| AgentFactoryError::MathOverflow)?)
+    }
+}
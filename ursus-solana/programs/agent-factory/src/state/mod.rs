@@ -1,10 +1,79 @@
 pub mod factory;
+pub mod admin_action;
 pub mod agent;
+pub mod agent_commit;
+pub mod agent_vault;
 pub mod bonding_curve;
 pub mod x402_config;
+pub mod dca;
+pub mod presale;
+pub mod snapshot;
+pub mod token_lock;
+pub mod staking;
+pub mod governance;
+pub mod dao_treasury;
+pub mod insurance_fund;
+pub mod creator_stats;
+pub mod price_history;
+pub mod trending;
+pub mod buyer_record;
+pub mod early_buyer_rewards;
+pub mod protocol_liquidity;
+pub mod liquidity_mining;
+pub mod migration;
+pub mod index_basket;
+pub mod twap;
+pub mod keeper;
+pub mod dispute;
+pub mod affiliate;
+pub mod name_reservation;
+pub mod price_view;
+pub mod listing_reserve;
+pub mod revenue_split;
+pub mod consumer_stats;
+pub mod payment_stream;
+pub mod agent_manifest;
+pub mod job;
+pub mod work_queue;
+pub mod trial_session;
+pub mod reputation;
 
 pub use factory::*;
+pub use admin_action::*;
 pub use agent::*;
+pub use agent_commit::*;
+pub use agent_vault::*;
 pub use bonding_curve::*;
 pub use x402_config::*;
-
+pub use dca::*;
+pub use presale::*;
+pub use snapshot::*;
+pub use token_lock::*;
+pub use staking::*;
+pub use governance::*;
+pub use dao_treasury::*;
+pub use insurance_fund::*;
+pub use creator_stats::*;
+pub use price_history::*;
+pub use trending::*;
+pub use buyer_record::*;
+pub use early_buyer_rewards::*;
+pub use protocol_liquidity::*;
+pub use liquidity_mining::*;
+pub use migration::*;
+pub use index_basket::*;
+pub use twap::*;
+pub use keeper::*;
+pub use dispute::*;
+pub use affiliate::*;
+pub use name_reservation::*;
+pub use price_view::*;
+pub use listing_reserve::*;
+pub use revenue_split::*;
+pub use consumer_stats::*;
+pub use payment_stream::*;
+pub use agent_manifest::*;
+pub use job::*;
+pub use work_queue::*;
+pub use trial_session::*;
+pub use reputation::*;
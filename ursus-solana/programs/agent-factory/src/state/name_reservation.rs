@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use crate::errors::AgentFactoryError;
+
+/// Agent name or symbol reserved for a verified brand:
+/// `["name_reservation", name_hash]`. Only the owner may launch an agent
+/// under it; a default owner releases the reservation.
+#[account]
+#[derive(InitSpace)]
+pub struct NameReservation {
+    /// `NameReservation::name_hash` of the reserved name or symbol
+    pub name_hash: [u8; 32],
+    
+    /// Wallet allowed to use the name (default = released)
+    pub owner: Pubkey,
+    
+    /// Time the reservation was last set
+    pub reserved_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl NameReservation {
+    pub const INIT_SPACE: usize =
+        32 +    // name_hash
+        32 +    // owner
+        8 +     // reserved_at
+        1;      // bump
+
+    /// Registry key of a name or symbol: SHA-256 of its ASCII-lowercased
+    /// bytes, so reservations are case-insensitive
+    pub fn name_hash(name: &str) -> [u8; 32] {
+        hash(name.to_ascii_lowercase().as_bytes()).to_bytes()
+    }
+
+    /// Fail unless `info` is the reservation address of `name` and `creator`
+    /// may use the name: it was never reserved, was released, or is reserved
+    /// for `creator`
+    pub fn check(info: &AccountInfo, name: &str, creator: &Pubkey) -> Result<()> {
+        let (address, _) = crate::pda::find_name_reservation_pda(name);
+        require_keys_eq!(*info.key, address, ErrorCode::ConstraintSeeds);
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(());
+        }
+        let reservation = NameReservation::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            reservation.owner == Pubkey::default() || reservation.owner == *creator,
+            AgentFactoryError::NameReserved
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+/// Longest a payment stream may run (365 days)
+pub const MAX_STREAM_DURATION_SECS: u64 = 365 * 24 * 60 * 60;
+
+/// Per-second x402 payment for a long-running agent task, such as
+/// monitoring or a continuous data feed.
+///
+/// The payer locks `rate_per_sec * max_duration` in the stream's escrow up
+/// front. The provider withdraws what has streamed so far at any time, and
+/// the payer can cancel to stop the stream and reclaim the unstreamed rest.
+#[account]
+#[derive(InitSpace)]
+pub struct PaymentStream {
+    /// Agent providing the service
+    pub agent: Pubkey,
+
+    /// Payer funding the stream
+    pub payer: Pubkey,
+
+    /// Payment token (e.g. USDC)
+    pub mint: Pubkey,
+
+    /// Streamed per second (smallest units)
+    pub rate_per_sec: u64,
+
+    /// Start timestamp
+    pub start_ts: i64,
+
+    /// End timestamp; moved up to the cancellation time on cancel
+    pub end_ts: i64,
+
+    /// Locked at start (smallest units)
+    pub deposited: u64,
+
+    /// Withdrawn by the provider so far (smallest units)
+    pub withdrawn: u64,
+
+    /// Refunded to the payer on cancel (smallest units)
+    pub refunded: u64,
+
+    /// Cancellation timestamp (0 = not cancelled)
+    pub cancelled_at: i64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl PaymentStream {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        32 +    // payer
+        32 +    // mint
+        8 +     // rate_per_sec
+        8 +     // start_ts
+        8 +     // end_ts
+        8 +     // deposited
+        8 +     // withdrawn
+        8 +     // refunded
+        8 +     // cancelled_at
+        1;      // bump
+
+    /// Streamed to the provider by `now`, withdrawn or not
+    pub fn streamed(&self, now: i64) -> u64 {
+        let elapsed = (now.min(self.end_ts) - self.start_ts).max(0) as u128;
+        (elapsed * self.rate_per_sec as u128).min(self.deposited as u128) as u64
+    }
+
+    /// Streamed by `now` and not yet withdrawn
+    pub fn withdrawable(&self, now: i64) -> u64 {
+        self.streamed(now).saturating_sub(self.withdrawn)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled_at != 0
+    }
+}
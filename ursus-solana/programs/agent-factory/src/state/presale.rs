@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+
+/// Presale window for an agent launched before its curve opens. The presale
+/// PDA escrows every commitment; at finalization the accepted SOL buys into
+/// the curve in one trade, so every committer pays the same clearing price.
+#[account]
+#[derive(InitSpace)]
+pub struct Presale {
+    /// Agent whose token is being presold
+    pub agent: Pubkey,
+    
+    /// Agent creator that opened the presale
+    pub creator: Pubkey,
+    
+    /// Commitments are accepted from this timestamp...
+    pub start_ts: i64,
+    
+    /// ...until this one (exclusive)
+    pub end_ts: i64,
+    
+    /// Minimum total commitment for the presale to succeed (in lamports)
+    pub soft_cap: u64,
+    
+    /// Maximum SOL spent on the curve; oversubscription is refunded pro rata
+    pub hard_cap: u64,
+    
+    /// Total SOL committed
+    pub total_committed: u64,
+    
+    /// Number of commitment accounts opened
+    pub committer_count: u64,
+    
+    /// SOL spent buying into the curve at finalization (fees included)
+    pub sol_spent: u64,
+    
+    /// Tokens bought at finalization, shared pro rata between committers
+    pub tokens_allocated: u64,
+    
+    /// Whether `finalize_presale` has run
+    pub is_finalized: bool,
+    
+    /// Whether the soft cap was met (tokens claimable rather than refunded)
+    pub is_successful: bool,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Presale {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        32 +    // creator
+        8 +     // start_ts
+        8 +     // end_ts
+        8 +     // soft_cap
+        8 +     // hard_cap
+        8 +     // total_committed
+        8 +     // committer_count
+        8 +     // sol_spent
+        8 +     // tokens_allocated
+        1 +     // is_finalized
+        1 +     // is_successful
+        1;      // bump
+
+    /// Whether commitments are accepted at `now`
+    pub fn is_open(&self, now: i64) -> bool {
+        !self.is_finalized && now >= self.start_ts && now < self.end_ts
+    }
+
+    /// Tokens and SOL refund owed for a commitment of `amount` once the
+    /// presale succeeded. Tokens round down and the SOL share spent rounds up,
+    /// so the vault always covers every claim.
+    pub fn allocation(&self, amount: u64) -> Option<(u64, u64)> {
+        if self.total_committed == 0 {
+            return Some((0, amount));
+        }
+        let total = self.total_committed as u128;
+        let tokens = (self.tokens_allocated as u128)
+            .checked_mul(amount as u128)?
+            / total;
+        let spent = (self.sol_spent as u128)
+            .checked_mul(amount as u128)?
+            .div_ceil(total);
+        let refund = (amount as u128).checked_sub(spent)?;
+        Some((u64::try_from(tokens).ok()?, u64::try_from(refund).ok()?))
+    }
+}
+
+/// One wallet's SOL committed to a presale
+#[account]
+#[derive(InitSpace)]
+pub struct PresaleCommitment {
+    /// Presale the SOL was committed to
+    pub presale: Pubkey,
+    
+    /// Committer's wallet, receives the tokens or the refund
+    pub committer: Pubkey,
+    
+    /// SOL committed (in lamports)
+    pub amount: u64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl PresaleCommitment {
+    pub const INIT_SPACE: usize =
+        32 +    // presale
+        32 +    // committer
+        8 +     // amount
+        1;      // bump
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+/// Length of one candle
+pub const CANDLE_INTERVAL_SECS: i64 = 60;
+
+/// Candles kept per agent (3 hours of 1-minute candles)
+pub const PRICE_HISTORY_CANDLES: usize = 180;
+
+/// One OHLCV bucket. Prices are nano-lamports per token, volume is gross
+/// SOL traded.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct Candle {
+    /// Unix time the bucket starts at (a multiple of `CANDLE_INTERVAL_SECS`)
+    pub start_ts: i64,
+    pub open: u64,
+    pub high: u64,
+    pub low: u64,
+    pub close: u64,
+    pub volume: u64,
+}
+
+/// Optional per-agent ring buffer of recent candles, updated by buys and
+/// sells that pass it, so a frontend can draw a chart from a single account
+/// fetch without an indexer
+#[account]
+#[derive(InitSpace)]
+pub struct PriceHistory {
+    /// Agent whose trades are recorded
+    pub agent: Pubkey,
+    
+    /// Index of the latest candle in `candles`
+    pub head: u16,
+    
+    /// Ring buffer; grows to `PRICE_HISTORY_CANDLES`, then the oldest candle
+    /// is overwritten. Buckets without trades are skipped.
+    #[max_len(180)]
+    pub candles: Vec<Candle>,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl PriceHistory {
+    pub const INIT_SPACE: usize =
+        32 +                                // agent
+        2 +                                 // head
+        4 + PRICE_HISTORY_CANDLES * 48 +    // candles
+        1;                                  // bump
+
+    /// Record a trade that moved the price from `price_before` to
+    /// `price_after` at unix time `now`
+    pub fn record(&mut self, price_before: u64, price_after: u64, volume: u64, now: i64) {
+        let start_ts = now - now.rem_euclid(CANDLE_INTERVAL_SECS);
+        
+        if let Some(candle) = self.candles.get_mut(self.head as usize) {
+            if candle.start_ts == start_ts {
+                candle.high = candle.high.max(price_after);
+                candle.low = candle.low.min(price_after);
+                candle.close = price_after;
+                candle.volume = candle.volume.saturating_add(volume);
+                return;
+            }
+        }
+        
+        let candle = Candle {
+            start_ts,
+            open: price_before,
+            high: price_before.max(price_after),
+            low: price_before.min(price_after),
+            close: price_after,
+            volume,
+        };
+        if self.candles.len() < PRICE_HISTORY_CANDLES {
+            self.candles.push(candle);
+            self.head = (self.candles.len() - 1) as u16;
+        } else {
+            self.head = ((self.head as usize + 1) % PRICE_HISTORY_CANDLES) as u16;
+            self.candles[self.head as usize] = candle;
+        }
+    }
+
+    /// Candles from oldest to newest
+    pub fn ordered_candles(&self) -> Vec<Candle> {
+        let split = (self.head as usize + 1).min(self.candles.len());
+        let (newest, oldest) = self.candles.split_at(split);
+        oldest.iter().chain(newest).copied().collect()
+    }
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use super::CurveReserves;
+
+/// Discriminators (first 8 bytes of account data) of the accounts other
+/// programs may read directly. Their fields are only ever appended, so a
+/// reader can decode a known prefix.
+#[constant]
+pub const AGENT_DISCRIMINATOR: [u8; 8] = [47, 166, 112, 147, 155, 197, 86, 7];
+#[constant]
+pub const AGENT_FACTORY_DISCRIMINATOR: [u8; 8] = [30, 22, 123, 75, 74, 182, 182, 54];
+#[constant]
+pub const X402_CONFIG_DISCRIMINATOR: [u8; 8] = [208, 61, 218, 60, 245, 234, 160, 128];
+
+/// Agent price returned by `view_price` as return data, for protocols that
+/// CPI for a price (perps, lending) without copying `Agent`. Fields are only
+/// ever appended.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriceView {
+    /// Spot price in nano-lamports per token (the unit of `get_twap`)
+    pub price: u64,
+    
+    /// Market cap of the circulating curve supply (in lamports)
+    pub market_cap: u64,
+    
+    /// Curve reserves, as reported in trade events
+    pub reserves: CurveReserves,
+    
+    /// Whether the agent has left its curve; the price is then its last
+    /// curve price
+    pub is_graduated: bool,
+}
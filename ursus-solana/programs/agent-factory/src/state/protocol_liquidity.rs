@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+/// Largest share of graduation liquidity the protocol can keep, so the
+/// public pool always gets at least half
+pub const MAX_PROTOCOL_LIQUIDITY_BPS: u16 = 5_000;
+
+/// Platform-wide protocol-owned liquidity. Holds SOL (in this account) and
+/// agent tokens (in token accounts it owns) carved out of every graduation
+/// at `AgentFactory::protocol_liquidity_bps`; only the factory authority
+/// (e.g. a DAO PDA) can withdraw them.
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolLiquidity {
+    /// Graduation SOL received so far (in lamports)
+    pub total_sol_contributed: u64,
+    
+    /// SOL withdrawn so far (in lamports)
+    pub total_sol_withdrawn: u64,
+    
+    /// Graduations that contributed
+    pub graduations: u64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ProtocolLiquidity {
+    pub const INIT_SPACE: usize =
+        8 +     // total_sol_contributed
+        8 +     // total_sol_withdrawn
+        8 +     // graduations
+        8 +     // created_at
+        1;      // bump
+}
@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+
+/// Most external sources an agent's reputation holds attestations from
+pub const MAX_REPUTATION_SOURCES: usize = 8;
+
+/// Highest star rating a caller can give a paid service
+pub const MAX_RATING_STARS: u8 = 5;
+
+/// Longest source tag, e.g. `olas` or `virtuals-protocol`
+pub const REPUTATION_SOURCE_LEN: usize = 20;
+
+/// Reputation an external system reports for an agent, posted by the
+/// factory authority or its reputation attestor
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct ReputationAttestation {
+    /// System the reputation comes from, lowercase and zero padded
+    pub source: [u8; REPUTATION_SOURCE_LEN],
+
+    /// Score on the source, normalized to bps
+    pub score_bps: u16,
+
+    /// Weight of the source in the agent's imported score (bps)
+    pub weight_bps: u16,
+
+    /// Hash of the source's signed record backing the attestation
+    pub evidence_hash: [u8; 32],
+
+    /// Key that posted the attestation
+    pub attestor: Pubkey,
+
+    pub attested_at: i64,
+}
+
+impl ReputationAttestation {
+    pub const INIT_SPACE: usize = REPUTATION_SOURCE_LEN + 2 + 2 + 32 + 32 + 8;
+
+    /// Source tag of `source`: 1 to `REPUTATION_SOURCE_LEN` lowercase
+    /// letters, digits, `-`, `.` or `_`, zero padded
+    pub fn source_id(source: &str) -> Option<[u8; REPUTATION_SOURCE_LEN]> {
+        let valid = !source.is_empty()
+            && source.len() <= REPUTATION_SOURCE_LEN
+            && source.bytes().all(|b| {
+                b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'.' || b == b'_'
+            });
+        if !valid {
+            return None;
+        }
+        let mut id = [0u8; REPUTATION_SOURCE_LEN];
+        id[..source.len()].copy_from_slice(source.as_bytes());
+        Some(id)
+    }
+
+    /// Whether `source` is a well-formed source tag
+    pub fn source_valid(source: &[u8; REPUTATION_SOURCE_LEN]) -> bool {
+        let len = source.iter().position(|b| *b == 0).unwrap_or(REPUTATION_SOURCE_LEN);
+        source[len..].iter().all(|b| *b == 0)
+            && std::str::from_utf8(&source[..len]).ok().and_then(Self::source_id) == Some(*source)
+    }
+}
+
+/// Agent's reputation: attestations imported from other platforms,
+/// bootstrapping trust before it has a payment history here, one per
+/// source; and the ratings its paying callers give, weighted by the amount
+/// paid so fake reviews cost real volume.
+#[account]
+#[derive(InitSpace)]
+pub struct AgentReputation {
+    pub agent: Pubkey,
+
+    #[max_len(MAX_REPUTATION_SOURCES)]
+    pub attestations: Vec<ReputationAttestation>,
+
+    /// Ratings given, one per payment
+    pub rating_count: u64,
+
+    /// Paid in the rated payments (payment token units)
+    pub rated_amount: u64,
+
+    /// Sum of stars times amount paid over the rated payments
+    pub weighted_stars: u128,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl AgentReputation {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        4 + ReputationAttestation::INIT_SPACE * MAX_REPUTATION_SOURCES + // attestations
+        8 +     // rating_count
+        8 +     // rated_amount
+        16 +    // weighted_stars
+        1;      // bump
+
+    /// Replace the attestation from `attestation.source`, add it if the
+    /// source is new, or drop the source's with a zero weight. Returns
+    /// false when there is no room for a new source.
+    pub fn upsert(&mut self, attestation: ReputationAttestation) -> bool {
+        let existing = self.attestations.iter().position(|a| a.source == attestation.source);
+        match (existing, attestation.weight_bps) {
+            (Some(i), 0) => {
+                self.attestations.remove(i);
+            }
+            (Some(i), _) => self.attestations[i] = attestation,
+            (None, 0) => {}
+            (None, _) if self.attestations.len() >= MAX_REPUTATION_SOURCES => return false,
+            (None, _) => self.attestations.push(attestation),
+        }
+        true
+    }
+
+    /// Weighted average of the attested scores (bps, 0 = no attestations)
+    pub fn score_bps(&self) -> u16 {
+        let weight: u64 = self.attestations.iter().map(|a| a.weight_bps as u64).sum();
+        if weight == 0 {
+            return 0;
+        }
+        let weighted: u64 = self.attestations
+            .iter()
+            .map(|a| a.score_bps as u64 * a.weight_bps as u64)
+            .sum();
+        (weighted / weight).min(BPS_DENOMINATOR) as u16
+    }
+
+    /// Count a rating of `stars` for a payment of `paid`
+    pub fn record_rating(&mut self, stars: u8, paid: u64) -> Result<()> {
+        self.rating_count = self.rating_count
+            .checked_add(1)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        self.rated_amount = self.rated_amount
+            .checked_add(paid)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        self.weighted_stars = self.weighted_stars
+            .checked_add(stars as u128 * paid as u128)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Payment-weighted average rating as a share of `MAX_RATING_STARS`
+    /// (bps, 0 = not rated)
+    pub fn rating_bps(&self) -> u16 {
+        if self.rated_amount == 0 {
+            return 0;
+        }
+        (self.weighted_stars * BPS_DENOMINATOR as u128
+            / (self.rated_amount as u128 * MAX_RATING_STARS as u128)) as u16
+    }
+}
+
+/// A paying caller's rating of the service behind one payment, which also
+/// keeps the payment from being rated twice
+#[account]
+#[derive(InitSpace)]
+pub struct ServiceRating {
+    pub agent: Pubkey,
+
+    /// Rated payment
+    pub payment_record: Pubkey,
+
+    /// Payer of the rated payment
+    pub rater: Pubkey,
+
+    /// 1 to `MAX_RATING_STARS`
+    pub stars: u8,
+
+    /// Amount paid net of refunds, the rating's weight
+    pub weight: u64,
+
+    pub rated_at: i64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ServiceRating {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        32 +    // payment_record
+        32 +    // rater
+        1 +     // stars
+        8 +     // weight
+        8 +     // rated_at
+        1;      // bump
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use bonding_curve_math::BPS_DENOMINATOR;
+
+/// Most worker agents one revenue split can route to
+pub const MAX_REVENUE_SPLIT_RECIPIENTS: usize = 4;
+
+/// Largest share of each payment a provider can route to other agents,
+/// in total
+pub const MAX_REVENUE_SPLIT_BPS: u16 = 5000;
+
+/// One worker agent's share of a manager agent's x402 revenue
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct RevenueShare {
+    /// Worker agent
+    pub agent: Pubkey,
+
+    /// Worker agent's DAO treasury, whose token accounts receive the share
+    pub treasury: Pubkey,
+
+    /// Share of each payment (bps)
+    pub bps: u16,
+}
+
+impl RevenueShare {
+    /// Share of `amount`, rounded down
+    pub fn of(&self, amount: u64) -> u64 {
+        (amount as u128 * self.bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+/// Revenue split of an agent subcontracting to other agents.
+///
+/// Every x402 payment to the agent pays each share straight into the worker
+/// agent's DAO treasury; the provider's recipient gets the rest. While the
+/// split is non-empty the agent's `X402Config::revenue_split_bps` is set and
+/// payments must pass it along with the treasury token accounts.
+#[account]
+#[derive(InitSpace)]
+pub struct RevenueSplit {
+    /// Manager agent whose revenue is split
+    pub agent: Pubkey,
+
+    /// Worker agents' shares
+    #[max_len(MAX_REVENUE_SPLIT_RECIPIENTS)]
+    pub shares: Vec<RevenueShare>,
+
+    /// Routed to worker agents so far, summed across payment tokens
+    /// (smallest units)
+    pub total_routed: u64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl RevenueSplit {
+    pub const INIT_SPACE: usize =
+        32 +            // agent
+        4 + MAX_REVENUE_SPLIT_RECIPIENTS * (32 + 32 + 2) + // shares
+        8 +             // total_routed
+        1;              // bump
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+/// Canonical point in time for an off-chain holder distribution. Tooling
+/// builds its merkle tree from balances at `slot`, so everyone agrees on
+/// when the snapshot was taken.
+#[account]
+#[derive(InitSpace)]
+pub struct HolderSnapshot {
+    /// Agent whose holders are snapshotted
+    pub agent: Pubkey,
+    
+    /// Sequential id per agent, starting at 0
+    pub snapshot_id: u64,
+    
+    /// Slot the snapshot was recorded in
+    pub slot: u64,
+    
+    /// Block time of that slot
+    pub timestamp: i64,
+    
+    /// Tokens in circulation (mint supply) at the snapshot
+    pub circulating_supply: u64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl HolderSnapshot {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        8 +     // snapshot_id
+        8 +     // slot
+        8 +     // timestamp
+        8 +     // circulating_supply
+        1;      // bump
+}
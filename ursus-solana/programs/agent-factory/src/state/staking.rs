@@ -0,0 +1,358 @@
+use anchor_lang::prelude::*;
+use crate::errors::AgentFactoryError;
+
+/// Fixed-point scale of `reward_per_token` (lamports per base unit)
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+/// Vote-escrow lock ends are rounded down to whole epochs
+pub const VE_EPOCH_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Longest vote-escrow lock (52 epochs, about a year)
+pub const MAX_VE_LOCK_SECS: i64 = 52 * VE_EPOCH_SECS;
+
+/// Voting weight of a stake locked for `MAX_VE_LOCK_SECS`, as a multiple of
+/// the amount. The bonus decays linearly to 1x at the end of the lock.
+pub const MAX_VE_MULTIPLIER: u64 = 4;
+
+/// Slope change slots, one per epoch a lock can end in
+pub const VE_SLOPE_SLOTS: usize = 53;
+
+/// Voting checkpoints kept per stake position
+pub const VE_CHECKPOINTS: usize = 8;
+
+/// Bonus slope of a lock: its bonus weight, scaled by `MAX_VE_LOCK_SECS`,
+/// drops by this much every second
+fn ve_slope(amount: u64) -> u128 {
+    amount as u128 * (MAX_VE_MULTIPLIER - 1) as u128
+}
+
+fn ve_slot(epoch_ts: i64) -> usize {
+    (epoch_ts / VE_EPOCH_SECS) as usize % VE_SLOPE_SLOTS
+}
+
+/// Voting weight of `amount` tokens locked until `lock_end`, at `now`
+pub fn ve_weight(amount: u64, lock_end: i64, now: i64) -> u64 {
+    if now >= lock_end {
+        return amount;
+    }
+    let bonus = ve_slope(amount) * (lock_end - now) as u128 / MAX_VE_LOCK_SECS as u128;
+    amount.saturating_add(bonus as u64)
+}
+
+/// Round a lock end down to the start of its epoch
+pub fn ve_lock_end(unlock_ts: i64) -> i64 {
+    unlock_ts / VE_EPOCH_SECS * VE_EPOCH_SECS
+}
+
+/// Per-agent staking pool paying SOL rewards to agent token stakers.
+///
+/// Rewards arrive as a slice of the agent's creator fees (and direct
+/// deposits) into `undistributed_rewards`, then stream out to stakers no
+/// faster than `emission_rate_cap` lamports per second.
+///
+/// Stakers can also lock their stake (vote-escrow). Voting weight is the
+/// staked amount plus a bonus of up to `MAX_VE_MULTIPLIER - 1` times the amount
+/// that decays linearly until the lock ends; the pool tracks the total.
+#[account]
+#[derive(InitSpace)]
+pub struct StakingPool {
+    /// Agent whose token is staked
+    pub agent: Pubkey,
+    
+    /// Tokens currently staked (base units)
+    pub total_staked: u64,
+    
+    /// Accumulated rewards per staked base unit, scaled by `REWARD_PRECISION`
+    pub reward_per_token: u128,
+    
+    /// Rewards received but not yet streamed to stakers (in lamports)
+    pub undistributed_rewards: u64,
+    
+    /// Rewards streamed to stakers so far (in lamports)
+    pub total_rewards_distributed: u64,
+    
+    /// Maximum lamports streamed per second (0 = release immediately)
+    pub emission_rate_cap: u64,
+    
+    /// Last time rewards were streamed
+    pub last_update_ts: i64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Last time the vote-escrow totals were brought up to date
+    pub ve_last_ts: i64,
+    
+    /// Bonus weight of all live locks at `ve_last_ts`, scaled by `MAX_VE_LOCK_SECS`
+    pub ve_bias: u128,
+    
+    /// Per-second decay of `ve_bias`
+    pub ve_slope: u128,
+    
+    /// Slope that expires at each epoch boundary, indexed by epoch mod `VE_SLOPE_SLOTS`
+    pub ve_slope_changes: [u128; VE_SLOPE_SLOTS],
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl StakingPool {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        8 +     // total_staked
+        16 +    // reward_per_token
+        8 +     // undistributed_rewards
+        8 +     // total_rewards_distributed
+        8 +     // emission_rate_cap
+        8 +     // last_update_ts
+        8 +     // created_at
+        8 +     // ve_last_ts
+        16 +    // ve_bias
+        16 +    // ve_slope
+        16 * VE_SLOPE_SLOTS + // ve_slope_changes
+        1;      // bump
+
+    /// Stream undistributed rewards to stakers up to `now`. Nothing is
+    /// released while the pool is empty.
+    pub fn accrue(&mut self, now: i64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.last_update_ts).max(0) as u64;
+        self.last_update_ts = now;
+        
+        if self.total_staked == 0 || self.undistributed_rewards == 0 {
+            return Ok(());
+        }
+        
+        let release = if self.emission_rate_cap == 0 {
+            self.undistributed_rewards
+        } else {
+            self.emission_rate_cap
+                .saturating_mul(elapsed)
+                .min(self.undistributed_rewards)
+        };
+        
+        let increment = (release as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(AgentFactoryError::MathOverflow)?
+            / self.total_staked as u128;
+        
+        self.reward_per_token = self.reward_per_token
+            .checked_add(increment)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        self.undistributed_rewards -= release;
+        self.total_rewards_distributed = self.total_rewards_distributed
+            .checked_add(release)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        
+        Ok(())
+    }
+
+    /// Queue `amount` lamports (already transferred to the pool) for streaming
+    pub fn deposit_rewards(&mut self, amount: u64) -> Result<()> {
+        self.undistributed_rewards = self.undistributed_rewards
+            .checked_add(amount)
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Total voting weight (staked tokens plus lock bonuses) at `now`, which
+    /// must not be before `ve_last_ts`
+    pub fn total_voting_weight(&self, now: i64) -> u64 {
+        let mut bias = self.ve_bias;
+        let mut slope = self.ve_slope;
+        let mut last = self.ve_last_ts;
+        let mut boundary = ve_lock_end(last) + VE_EPOCH_SECS;
+        
+        for _ in 0..VE_SLOPE_SLOTS {
+            if boundary > now {
+                break;
+            }
+            bias = bias.saturating_sub(slope * (boundary - last) as u128);
+            slope = slope.saturating_sub(self.ve_slope_changes[ve_slot(boundary)]);
+            last = boundary;
+            boundary += VE_EPOCH_SECS;
+        }
+        bias = bias.saturating_sub(slope * (now - last).max(0) as u128);
+        
+        let bonus = (bias / MAX_VE_LOCK_SECS as u128) as u64;
+        self.total_staked.saturating_add(bonus)
+    }
+
+    /// Decay the vote-escrow totals up to `now`, retiring locks that ended
+    fn checkpoint_ve(&mut self, now: i64) {
+        let mut boundary = ve_lock_end(self.ve_last_ts) + VE_EPOCH_SECS;
+        
+        for _ in 0..VE_SLOPE_SLOTS {
+            if boundary > now {
+                break;
+            }
+            let slot = ve_slot(boundary);
+            self.ve_bias = self.ve_bias
+                .saturating_sub(self.ve_slope * (boundary - self.ve_last_ts) as u128);
+            self.ve_slope = self.ve_slope.saturating_sub(self.ve_slope_changes[slot]);
+            self.ve_slope_changes[slot] = 0;
+            self.ve_last_ts = boundary;
+            boundary += VE_EPOCH_SECS;
+        }
+        
+        if now > self.ve_last_ts {
+            self.ve_bias = self.ve_bias
+                .saturating_sub(self.ve_slope * (now - self.ve_last_ts) as u128);
+            self.ve_last_ts = now;
+        }
+    }
+
+    /// Replace a position's lock `(old_amount, old_end)` with
+    /// `(new_amount, new_end)` in the vote-escrow totals
+    pub fn update_ve(
+        &mut self,
+        now: i64,
+        old_amount: u64,
+        old_end: i64,
+        new_amount: u64,
+        new_end: i64,
+    ) -> Result<()> {
+        self.checkpoint_ve(now);
+        
+        if old_end > now {
+            let slope = ve_slope(old_amount);
+            let slot = ve_slot(old_end);
+            self.ve_bias = slope
+                .checked_mul((old_end - now) as u128)
+                .and_then(|bias| self.ve_bias.checked_sub(bias))
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            self.ve_slope = self.ve_slope
+                .checked_sub(slope)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            self.ve_slope_changes[slot] = self.ve_slope_changes[slot]
+                .checked_sub(slope)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+        }
+        
+        if new_end > now {
+            let slope = ve_slope(new_amount);
+            let slot = ve_slot(new_end);
+            self.ve_bias = slope
+                .checked_mul((new_end - now) as u128)
+                .and_then(|bias| self.ve_bias.checked_add(bias))
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            self.ve_slope = self.ve_slope
+                .checked_add(slope)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            self.ve_slope_changes[slot] = self.ve_slope_changes[slot]
+                .checked_add(slope)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+        }
+        
+        Ok(())
+    }
+}
+
+/// Stake amount and lock of a position from `ts` until the next checkpoint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub struct VeCheckpoint {
+    pub ts: i64,
+    pub amount: u64,
+    pub lock_end: i64,
+}
+
+/// One wallet's stake in an agent's staking pool
+#[account]
+#[derive(InitSpace)]
+pub struct StakePosition {
+    /// Pool the tokens are staked in
+    pub pool: Pubkey,
+    
+    /// Staker's wallet
+    pub owner: Pubkey,
+    
+    /// Tokens staked (base units)
+    pub amount: u64,
+    
+    /// Pool `reward_per_token` at the last settlement
+    pub reward_per_token_paid: u128,
+    
+    /// Rewards earned but not yet claimed (in lamports)
+    pub pending_rewards: u64,
+    
+    /// End of the vote-escrow lock (0 = never locked); tokens cannot be
+    /// unstaked before it
+    pub lock_end: i64,
+    
+    /// Checkpoints written so far; the latest is at `(count - 1) % VE_CHECKPOINTS`
+    pub checkpoint_count: u64,
+    
+    /// Ring of the most recent stake/lock changes, for historical voting weight
+    pub checkpoints: [VeCheckpoint; VE_CHECKPOINTS],
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl StakePosition {
+    pub const INIT_SPACE: usize =
+        32 +    // pool
+        32 +    // owner
+        8 +     // amount
+        16 +    // reward_per_token_paid
+        8 +     // pending_rewards
+        8 +     // lock_end
+        8 +     // checkpoint_count
+        24 * VE_CHECKPOINTS + // checkpoints
+        1;      // bump
+
+    /// Credit rewards earned since the last settlement; call after
+    /// `StakingPool::accrue` and before changing `amount`
+    pub fn settle(&mut self, pool: &StakingPool) -> Result<()> {
+        let earned = (self.amount as u128)
+            .checked_mul(pool.reward_per_token - self.reward_per_token_paid)
+            .ok_or(AgentFactoryError::MathOverflow)?
+            / REWARD_PRECISION;
+        
+        self.pending_rewards = u64::try_from(earned)
+            .ok()
+            .and_then(|earned| self.pending_rewards.checked_add(earned))
+            .ok_or(AgentFactoryError::MathOverflow)?;
+        self.reward_per_token_paid = pool.reward_per_token;
+        
+        Ok(())
+    }
+    /// Current voting weight
+    pub fn voting_weight(&self, now: i64) -> u64 {
+        ve_weight(self.amount, self.lock_end, now)
+    }
+
+    /// Voting weight at a past time `ts`, from the checkpoints. Returns 0 if
+    /// the position did not exist then or its history has been overwritten.
+    pub fn voting_weight_at(&self, ts: i64) -> u64 {
+        let recorded = (self.checkpoint_count as usize).min(VE_CHECKPOINTS);
+        (1..=recorded)
+            .map(|back| {
+                let index = (self.checkpoint_count as usize - back) % VE_CHECKPOINTS;
+                self.checkpoints[index]
+            })
+            .find(|checkpoint| checkpoint.ts <= ts)
+            .map(|checkpoint| ve_weight(checkpoint.amount, checkpoint.lock_end, ts))
+            .unwrap_or(0)
+    }
+
+    /// Record the current amount and lock; call after every change to either
+    pub fn write_checkpoint(&mut self, now: i64) {
+        let checkpoint = VeCheckpoint {
+            ts: now,
+            amount: self.amount,
+            lock_end: self.lock_end,
+        };
+        
+        // Several changes in one block keep a single checkpoint
+        if self.checkpoint_count > 0 {
+            let latest = (self.checkpoint_count as usize - 1) % VE_CHECKPOINTS;
+            if self.checkpoints[latest].ts == now {
+                self.checkpoints[latest] = checkpoint;
+                return;
+            }
+        }
+        
+        self.checkpoints[self.checkpoint_count as usize % VE_CHECKPOINTS] = checkpoint;
+        self.checkpoint_count += 1;
+    }
+}
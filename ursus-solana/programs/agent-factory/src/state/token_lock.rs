@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+/// Maximum lock duration (10 years)
+pub const MAX_LOCK_DURATION_SECS: i64 = 10 * 365 * 24 * 60 * 60;
+
+/// Agent tokens locked until `unlock_ts`. The tokens sit in a vault token
+/// account owned by this PDA, so anyone can verify a team or holder lock
+/// on-chain.
+#[account]
+#[derive(InitSpace)]
+pub struct TokenLock {
+    /// Wallet that locked the tokens and can withdraw them after unlock
+    pub owner: Pubkey,
+    
+    /// Agent whose token is locked
+    pub agent: Pubkey,
+    
+    /// Owner-chosen id, lets one wallet hold several locks per agent
+    pub lock_id: u64,
+    
+    /// Tokens locked (base units)
+    pub amount: u64,
+    
+    /// Tokens can be withdrawn from this timestamp
+    pub unlock_ts: i64,
+    
+    /// Creation timestamp
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl TokenLock {
+    pub const INIT_SPACE: usize =
+        32 +    // owner
+        32 +    // agent
+        8 +     // lock_id
+        8 +     // amount
+        8 +     // unlock_ts
+        8 +     // created_at
+        1;      // bump
+
+    /// Whether the tokens can be withdrawn at `now`
+    pub fn is_unlocked(&self, now: i64) -> bool {
+        now >= self.unlock_ts
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+/// Slots for a trending score to halve (about an hour of 400ms slots)
+pub const TRENDING_HALF_LIFE_SLOTS: u64 = 9_000;
+
+/// Agents ranked on the trending leaderboard
+pub const TRENDING_LEADERBOARD_SIZE: usize = 10;
+
+/// `score` decayed over `elapsed_slots`: halved every
+/// `TRENDING_HALF_LIFE_SLOTS`, linearly between halvings
+pub fn decay_trending_score(score: u64, elapsed_slots: u64) -> u64 {
+    decay_by_half_life(score, elapsed_slots, TRENDING_HALF_LIFE_SLOTS)
+}
+
+/// `value` decayed over `elapsed`: halved every `half_life` (in the same
+/// unit), linearly between halvings
+pub fn decay_by_half_life(value: u64, elapsed: u64, half_life: u64) -> u64 {
+    if half_life == 0 {
+        return 0;
+    }
+    let halvings = elapsed / half_life;
+    if halvings >= u64::BITS as u64 {
+        return 0;
+    }
+    let value = value >> halvings;
+    let remainder = elapsed % half_life;
+    value - (value as u128 * remainder as u128 / (2 * half_life as u128)) as u64
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct TrendingEntry {
+    pub agent: Pubkey,
+    
+    /// Trending score decayed to the leaderboard's `slot`
+    pub score: u64,
+}
+
+/// Factory-wide top agents by trending score, updated by buys and sells that
+/// pass it, so the homepage can rank agents from a single account fetch
+#[account]
+#[derive(InitSpace)]
+pub struct TrendingLeaderboard {
+    /// Slot all entry scores are decayed to
+    pub slot: u64,
+    
+    /// Highest score first, at most `TRENDING_LEADERBOARD_SIZE` entries
+    #[max_len(10)]
+    pub entries: Vec<TrendingEntry>,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl TrendingLeaderboard {
+    pub const INIT_SPACE: usize =
+        8 +                                         // slot
+        4 + TRENDING_LEADERBOARD_SIZE * (32 + 8) +  // entries
+        1;                                          // bump
+
+    /// Record `agent`'s score as of `slot`; it enters the board when it
+    /// beats the lowest entry
+    pub fn update(&mut self, agent: Pubkey, score: u64, slot: u64) {
+        let elapsed = slot.saturating_sub(self.slot);
+        for entry in self.entries.iter_mut() {
+            entry.score = decay_trending_score(entry.score, elapsed);
+        }
+        self.slot = self.slot.max(slot);
+        
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.agent == agent) {
+            entry.score = score;
+        } else if self.entries.len() < TRENDING_LEADERBOARD_SIZE {
+            self.entries.push(TrendingEntry { agent, score });
+        } else {
+            match self.entries.last_mut() {
+                Some(lowest) if score > lowest.score => *lowest = TrendingEntry { agent, score },
+                _ => return,
+            }
+        }
+        
+        self.entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+    }
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+/// Outcome of a trial session
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TrialStatus {
+    /// Deposit escrowed, the agent serves the caller
+    Open,
+
+    /// Ended by the caller in time and the deposit refunded
+    Ended,
+
+    /// Abandoned past expiry and the deposit paid to the provider
+    Forfeited,
+}
+
+/// Open free-tier trial of an agent's service, backed by the caller's
+/// refundable deposit.
+///
+/// A caller holds at most one session per agent. Ending the session before
+/// it expires returns the deposit; a session the caller abandons past its
+/// expiry can be forfeited, paying the deposit to the provider.
+#[account]
+#[derive(InitSpace)]
+pub struct TrialSession {
+    /// Agent providing the service
+    pub agent: Pubkey,
+
+    /// Caller holding the session
+    pub caller: Pubkey,
+
+    /// Deposit token (e.g. USDC)
+    pub mint: Pubkey,
+
+    /// Service being tried
+    pub service_id: [u8; 32],
+
+    /// Escrowed at open (smallest units)
+    pub deposit: u64,
+
+    /// Open timestamp
+    pub opened_at: i64,
+
+    /// Forfeitable from this timestamp on
+    pub expires_at: i64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl TrialSession {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        32 +    // caller
+        32 +    // mint
+        32 +    // service_id
+        8 +     // deposit
+        8 +     // opened_at
+        8 +     // expires_at
+        1;      // bump
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+}
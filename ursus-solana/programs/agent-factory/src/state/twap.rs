@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+/// Observations kept for TWAP lookback
+pub const TWAP_OBSERVATIONS: usize = 12;
+
+/// Minimum slots between stored observations (~2 minutes), so the ring
+/// covers roughly the last 24 minutes
+pub const TWAP_OBSERVATION_INTERVAL_SLOTS: u64 = 300;
+
+/// Cumulative price at a past slot
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct TwapObservation {
+    /// Slot of the observation (0 = unused)
+    pub slot: u64,
+    
+    /// `price_cumulative` as of `slot`
+    pub price_cumulative: u128,
+}
+
+/// Time-weighted average price accumulator, kept on the agent.
+///
+/// `price_cumulative` sums the spot price (nano-lamports per token) over
+/// every slot. A price set by a trade only counts from the following slots
+/// on, so moving the price within one transaction or slot barely moves the
+/// average. Prices between trades (including LBP decay) are taken as the
+/// price after the last trade.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct TwapAccumulator {
+    /// Sum of price * slots up to `last_slot`
+    pub price_cumulative: u128,
+    
+    /// Price in effect since `last_slot` (nano-lamports per token)
+    pub last_price: u64,
+    
+    /// Slot the accumulator was last brought up to date
+    pub last_slot: u64,
+    
+    /// Index of the most recent observation
+    pub head: u8,
+    
+    /// Ring of past cumulative prices, at least
+    /// `TWAP_OBSERVATION_INTERVAL_SLOTS` apart
+    pub observations: [TwapObservation; TWAP_OBSERVATIONS],
+}
+
+impl TwapAccumulator {
+    pub const INIT_SPACE: usize =
+        16 +                            // price_cumulative
+        8 +                             // last_price
+        8 +                             // last_slot
+        1 +                             // head
+        TWAP_OBSERVATIONS * (8 + 16);   // observations
+
+    /// Start accumulating at `price` from `slot`, with a first observation
+    /// there
+    pub fn new(price: u64, slot: u64) -> Self {
+        let mut observations = [TwapObservation::default(); TWAP_OBSERVATIONS];
+        observations[0].slot = slot;
+        Self {
+            price_cumulative: 0,
+            last_price: price,
+            last_slot: slot,
+            head: 0,
+            observations,
+        }
+    }
+
+    /// Cumulative price extrapolated to `slot`
+    pub fn cumulative_at(&self, slot: u64) -> u128 {
+        self.price_cumulative
+            .wrapping_add(self.last_price as u128 * slot.saturating_sub(self.last_slot) as u128)
+    }
+
+    /// Accrue the old price up to `slot`, store an observation if the last
+    /// one is old enough, then switch to `price`; call after every price
+    /// change
+    pub fn update(&mut self, price: u64, slot: u64) {
+        if slot > self.last_slot {
+            self.price_cumulative = self.cumulative_at(slot);
+            self.last_slot = slot;
+            
+            let latest = self.observations[self.head as usize];
+            if latest.slot == 0 || slot >= latest.slot + TWAP_OBSERVATION_INTERVAL_SLOTS {
+                if latest.slot != 0 {
+                    self.head = ((self.head as usize + 1) % TWAP_OBSERVATIONS) as u8;
+                }
+                self.observations[self.head as usize] = TwapObservation {
+                    slot,
+                    price_cumulative: self.price_cumulative,
+                };
+            }
+        }
+        self.last_price = price;
+    }
+
+    /// Average price over at least the last `window_slots` slots as of
+    /// `slot`, measured from the newest observation that old; `None` if the
+    /// history doesn't reach back that far
+    pub fn twap(&self, window_slots: u64, slot: u64) -> Option<u64> {
+        let cutoff = slot.checked_sub(window_slots.max(1))?;
+        let observation = self.observations.iter()
+            .filter(|observation| observation.slot != 0 && observation.slot <= cutoff)
+            .max_by_key(|observation| observation.slot)?;
+        
+        let elapsed = (slot - observation.slot) as u128;
+        let average = self.cumulative_at(slot).wrapping_sub(observation.price_cumulative) / elapsed;
+        u64::try_from(average).ok()
+    }
+}
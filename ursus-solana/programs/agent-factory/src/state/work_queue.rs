@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+
+/// Most workers a work queue can authorize
+pub const MAX_QUEUE_WORKERS: usize = 8;
+
+/// Agent's on-chain queue of paid work requests, served by the operator
+/// workers its creator authorizes. Workers claim tasks for a lease of the
+/// agent's service timeout, so two workers never serve the same request and
+/// a stalled worker's task goes back to the queue.
+#[account]
+#[derive(InitSpace)]
+pub struct WorkQueue {
+    /// Agent serving the queue
+    pub agent: Pubkey,
+
+    /// Keys allowed to claim and complete tasks
+    #[max_len(MAX_QUEUE_WORKERS)]
+    pub workers: Vec<Pubkey>,
+
+    /// Id of the next task enqueued
+    pub next_task_id: u64,
+
+    /// Tasks enqueued and not yet completed or withdrawn
+    pub open_tasks: u32,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl WorkQueue {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        4 + 32 * MAX_QUEUE_WORKERS + // workers
+        8 +     // next_task_id
+        4 +     // open_tasks
+        1;      // bump
+
+    pub fn is_worker(&self, key: &Pubkey) -> bool {
+        self.workers.contains(key)
+    }
+}
+
+/// Progress of a queued task
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TaskStatus {
+    /// Waiting for a worker
+    Open,
+
+    /// Being served by `worker` until its lease ends
+    Claimed,
+
+    /// Served and paid
+    Completed,
+
+    /// Withdrawn by the requester and refunded
+    Withdrawn,
+}
+
+/// Paid work request in an agent's queue. The requester escrows up to
+/// `max_price`; the worker completing it charges what the work cost and the
+/// rest is refunded.
+#[account]
+#[derive(InitSpace)]
+pub struct WorkTask {
+    /// Agent serving the task
+    pub agent: Pubkey,
+
+    /// Position in the agent's queue
+    pub task_id: u64,
+
+    /// Wallet that enqueued and pays for the task
+    pub requester: Pubkey,
+
+    /// Payment token (e.g. USDC)
+    pub mint: Pubkey,
+
+    /// Hash of the request parameters (stored off-chain)
+    pub params_hash: [u8; 32],
+
+    /// Most the requester pays (smallest units)
+    pub max_price: u64,
+
+    pub status: TaskStatus,
+
+    /// Worker serving or that served the task (default = none)
+    pub worker: Pubkey,
+
+    /// When the current claim started (0 = unclaimed)
+    pub claimed_at: i64,
+
+    /// Charged on completion (smallest units)
+    pub price: u64,
+
+    /// Hash of the result the worker delivered
+    pub result_hash: [u8; 32],
+
+    /// Enqueue timestamp
+    pub created_at: i64,
+
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl WorkTask {
+    pub const INIT_SPACE: usize =
+        32 +    // agent
+        8 +     // task_id
+        32 +    // requester
+        32 +    // mint
+        32 +    // params_hash
+        8 +     // max_price
+        1 +     // status
+        32 +    // worker
+        8 +     // claimed_at
+        8 +     // price
+        32 +    // result_hash
+        8 +     // created_at
+        1;      // bump
+
+    /// Whether a worker can claim the task at `now`: open, or claimed by a
+    /// worker whose `lease_secs` lease has run out
+    pub fn is_claimable(&self, now: i64, lease_secs: i64) -> bool {
+        match self.status {
+            TaskStatus::Open => true,
+            TaskStatus::Claimed => now >= self.claimed_at.saturating_add(lease_secs),
+            TaskStatus::Completed | TaskStatus::Withdrawn => false,
+        }
+    }
+}
@@ -1,4 +1,41 @@
 use anchor_lang::prelude::*;
+use bonding_curve_math::BPS_DENOMINATOR;
+use crate::errors::AgentFactoryError;
+
+/// X402 payment settings supplied when configuring an agent in
+/// `create_agent_full`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct X402Settings {
+    pub enabled: bool,
+    pub min_payment_amount: u64,
+    pub max_payment_amount: u64,
+    pub service_timeout_seconds: u64,
+}
+
+/// Canonical 32-byte service ID for a service name: the sha256 of the
+/// trimmed, ASCII-lowercased name. None for an empty name or one with
+/// characters outside printable ASCII, so lookalike Unicode names can't be
+/// registered or paid for.
+pub fn service_id_hash(name: &str) -> Option<[u8; 32]> {
+    let name = name.trim();
+    if name.is_empty() || !name.bytes().all(|b| b.is_ascii_graphic() || b == b' ') {
+        return None;
+    }
+    Some(anchor_lang::solana_program::hash::hash(name.to_ascii_lowercase().as_bytes()).to_bytes())
+}
+
+/// Most services an agent can have deprecated at once
+pub const MAX_DEPRECATED_SERVICES: usize = 4;
+
+/// Service being retired: new payments for it are rejected from `sunset_ts`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct DeprecatedService {
+    /// Service ID (all zero = free slot)
+    pub service_id: [u8; 32],
+    
+    /// First timestamp new payments are rejected at
+    pub sunset_ts: i64,
+}
 
 /// X402 Payment Configuration for an Agent
 /// This structure holds the payment settings for agent services
@@ -32,6 +69,70 @@ pub struct X402Config {
     /// Nonce for replay protection
     pub nonce: u64,
     
+    /// Signer attesting escrowed payments, e.g. a Switchboard Function's
+    /// enclave signer (default = escrowed payments disabled)
+    pub attestation_verifier: Pubkey,
+    
+    /// Unit usage is metered in when `unit_price` is set
+    pub meter_unit: MeterUnit,
+    
+    /// Price per meter unit in payment token units (0 = flat pricing)
+    pub unit_price: u64,
+    
+    /// Largest affiliate share of a payment the provider allows (bps,
+    /// 0 = no affiliate payouts)
+    pub max_affiliate_bps: u16,
+    
+    /// SHA-256 of the agent's callback URL (zero = no webhook)
+    pub webhook_url_hash: [u8; 32],
+    
+    /// Key the agent signs webhook responses with
+    pub webhook_signer: Pubkey,
+    
+    /// Set by the factory authority to hard-stop payments, e.g. after the
+    /// operator's keys were compromised; independent of `enabled`
+    pub frozen: bool,
+    
+    /// Recipient replaced by the last rotation, still paid until
+    /// `previous_recipient_valid_until`
+    pub previous_payment_recipient: Pubkey,
+    
+    /// End of the rotation grace window (0 = no rotation)
+    pub previous_recipient_valid_until: i64,
+    
+    /// Total share of each payment routed to other agents by the agent's
+    /// `RevenueSplit` (bps, 0 = no split)
+    pub revenue_split_bps: u16,
+    
+    /// Time after settlement during which a payment can still be disputed
+    /// (0 = no chargebacks, payments settle final)
+    pub chargeback_window_secs: u64,
+    
+    /// Services being retired by `deprecate_service`
+    pub deprecated_services: [DeprecatedService; MAX_DEPRECATED_SERVICES],
+    
+    /// Notice given before an increase of `min_payment_amount` takes effect
+    /// (0 = immediately)
+    pub price_notice_secs: i64,
+    
+    /// Notice replaced by the last `set_x402_price_notice` that lowered it,
+    /// still enforced until `previous_notice_valid_until`
+    pub previous_price_notice_secs: i64,
+    
+    /// When the lowered notice takes over (0 = never lowered)
+    pub previous_notice_valid_until: i64,
+    
+    /// Minimum payment amount scheduled to replace `min_payment_amount`
+    pub pending_min_payment_amount: u64,
+    
+    /// When `pending_min_payment_amount` takes effect (0 = no increase
+    /// pending)
+    pub pending_min_payment_at: i64,
+    
+    /// Refundable deposit a caller escrows to open a trial session with the
+    /// agent's free tier (0 = trial sessions disabled)
+    pub trial_deposit: u64,
+    
     /// Bump seed for PDA
     pub bump: u8,
 }
@@ -47,23 +148,183 @@ impl X402Config {
         8 +     // total_payments_received
         8 +     // total_service_calls
         8 +     // nonce
+        32 +    // attestation_verifier
+        1 +     // meter_unit
+        8 +     // unit_price
+        2 +     // max_affiliate_bps
+        32 +    // webhook_url_hash
+        32 +    // webhook_signer
+        1 +     // frozen
+        32 +    // previous_payment_recipient
+        8 +     // previous_recipient_valid_until
+        2 +     // revenue_split_bps
+        8 +     // chargeback_window_secs
+        (32 + 8) * MAX_DEPRECATED_SERVICES + // deprecated_services
+        8 +     // price_notice_secs
+        8 +     // previous_price_notice_secs
+        8 +     // previous_notice_valid_until
+        8 +     // pending_min_payment_amount
+        8 +     // pending_min_payment_at
+        8 +     // trial_deposit
         1;      // bump
 
+    /// Whether `owner` may receive payments at `now`: the current recipient,
+    /// or the previous one during a rotation's grace window so in-flight
+    /// settlements built against it still land
+    pub fn accepts_recipient(&self, owner: &Pubkey, now: i64) -> bool {
+        *owner == self.payment_recipient
+            || (*owner == self.previous_payment_recipient && now <= self.previous_recipient_valid_until)
+    }
+
+    /// Affiliate share of `amount` for an affiliate asking `fee_bps`,
+    /// capped by the provider's `max_affiliate_bps`
+    pub fn affiliate_share(&self, amount: u64, fee_bps: u16) -> u64 {
+        let bps = fee_bps.min(self.max_affiliate_bps) as u128;
+        (amount as u128 * bps / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Whether services are billed by reported usage
+    pub fn is_metered(&self) -> bool {
+        self.unit_price > 0
+    }
+
+    /// Minimum payment amount in force at `now`, including a scheduled
+    /// increase once its notice has run out
+    pub fn current_min_payment_amount(&self, now: i64) -> u64 {
+        if self.pending_min_payment_at != 0 && now >= self.pending_min_payment_at {
+            self.pending_min_payment_amount
+        } else {
+            self.min_payment_amount
+        }
+    }
+
+    /// Notice an increase of the minimum payment amount must give at `now`:
+    /// a lowered notice only applies once the old one has run out
+    pub fn price_notice(&self, now: i64) -> i64 {
+        if now < self.previous_notice_valid_until {
+            self.price_notice_secs.max(self.previous_price_notice_secs)
+        } else {
+            self.price_notice_secs
+        }
+    }
+
+    /// Change the minimum payment amount at `now`. Decreases apply at once
+    /// and drop any pending increase; increases are scheduled behind the
+    /// price notice. Returns when the new amount takes effect.
+    pub fn set_min_payment_amount(&mut self, amount: u64, now: i64) -> Result<i64> {
+        self.min_payment_amount = self.current_min_payment_amount(now);
+        let notice = self.price_notice(now);
+        if amount > self.min_payment_amount && notice > 0 {
+            self.pending_min_payment_amount = amount;
+            self.pending_min_payment_at = now
+                .checked_add(notice)
+                .ok_or(AgentFactoryError::MathOverflow)?;
+            Ok(self.pending_min_payment_at)
+        } else {
+            self.min_payment_amount = amount;
+            self.pending_min_payment_amount = 0;
+            self.pending_min_payment_at = 0;
+            Ok(now)
+        }
+    }
+
     /// Validate payment amount
     pub fn validate_payment_amount(&self, amount: u64) -> Result<()> {
-        require!(amount >= self.min_payment_amount, X402Error::PaymentTooLow);
+        self.validate_discounted_payment_amount(amount, 0)
+    }
+
+    /// Validate payment amount against a minimum price reduced by `discount_bps`
+    pub fn validate_discounted_payment_amount(&self, amount: u64, discount_bps: u16) -> Result<()> {
+        let discount = (discount_bps as u128).min(BPS_DENOMINATOR as u128);
+        let min_payment_amount = self.current_min_payment_amount(Clock::get()?.unix_timestamp);
+        let min_payment = min_payment_amount as u128 * (BPS_DENOMINATOR as u128 - discount)
+            / BPS_DENOMINATOR as u128;
+        require_gte!(amount as u128, min_payment, AgentFactoryError::PaymentTooLow);
         
         if self.max_payment_amount > 0 {
-            require!(amount <= self.max_payment_amount, X402Error::PaymentTooHigh);
+            require!(amount <= self.max_payment_amount, AgentFactoryError::PaymentTooHigh);
         }
         
         Ok(())
     }
 
+    /// Validate a service payment: payments enabled and not frozen, discounted
+    /// amount within
+    /// bounds, next nonce and a valid service ID
+    pub fn validate_payment(
+        &self,
+        amount: u64,
+        discount_bps: u16,
+        nonce: u64,
+        service_id: &[u8; 32],
+    ) -> Result<()> {
+        self.validate_payment_request(nonce, service_id)?;
+        self.validate_discounted_payment_amount(amount, discount_bps)
+    }
+
+    /// Validate a service payment apart from its amount: payments enabled
+    /// and not frozen, next nonce and a valid service ID
+    pub fn validate_payment_request(&self, nonce: u64, service_id: &[u8; 32]) -> Result<()> {
+        self.require_accepting_payments()?;
+        
+        // Verify nonce for replay protection
+        require_eq!(nonce, self.nonce + 1, AgentFactoryError::NonceMismatch);
+        
+        require!(*service_id != [0; 32], AgentFactoryError::InvalidServiceId);
+        
+        self.require_service_live(service_id)
+    }
+
+    /// Fail once a deprecated service has reached its sunset
+    pub fn require_service_live(&self, service_id: &[u8; 32]) -> Result<()> {
+        if let Some(deprecated) = self.deprecated_services.iter().find(|d| d.service_id == *service_id) {
+            require!(
+                Clock::get()?.unix_timestamp < deprecated.sunset_ts,
+                AgentFactoryError::ServiceSunset
+            );
+        }
+        Ok(())
+    }
+
+    /// Deprecate `service_id` with new payments stopping at `sunset_ts`, or
+    /// withdraw its deprecation with 0
+    pub fn deprecate_service(&mut self, service_id: &[u8; 32], sunset_ts: i64) -> Result<()> {
+        let slot = match self.deprecated_services.iter().position(|d| d.service_id == *service_id) {
+            Some(slot) => slot,
+            None if sunset_ts == 0 => return Ok(()),
+            None => self.deprecated_services
+                .iter()
+                .position(|d| d.service_id == [0; 32])
+                .ok_or(AgentFactoryError::TooManyDeprecatedServices)?,
+        };
+        self.deprecated_services[slot] = if sunset_ts == 0 {
+            DeprecatedService::default()
+        } else {
+            DeprecatedService { service_id: *service_id, sunset_ts }
+        };
+        Ok(())
+    }
+
+    /// Fail unless payments are enabled by the creator and not frozen by
+    /// the factory authority
+    pub fn require_accepting_payments(&self) -> Result<()> {
+        require!(!self.frozen, AgentFactoryError::X402Frozen);
+        require!(self.enabled, AgentFactoryError::PaymentsNotEnabled);
+        Ok(())
+    }
+
+    /// Dispute deadline of a payment settling at `now` (0 = not disputable)
+    pub fn dispute_deadline(&self, now: i64) -> i64 {
+        if self.chargeback_window_secs == 0 {
+            return 0;
+        }
+        now.saturating_add(self.chargeback_window_secs as i64)
+    }
+
     /// Increment nonce for replay protection
     pub fn increment_nonce(&mut self) -> Result<u64> {
         self.nonce = self.nonce.checked_add(1)
-            .ok_or(X402Error::NonceOverflow)?;
+            .ok_or(AgentFactoryError::NonceOverflow)?;
         Ok(self.nonce)
     }
 
@@ -71,17 +332,19 @@ impl X402Config {
     pub fn record_payment(&mut self, amount: u64) -> Result<()> {
         self.total_payments_received = self.total_payments_received
             .checked_add(amount)
-            .ok_or(X402Error::MathOverflow)?;
+            .ok_or(AgentFactoryError::MathOverflow)?;
         
         self.total_service_calls = self.total_service_calls
             .checked_add(1)
-            .ok_or(X402Error::MathOverflow)?;
+            .ok_or(AgentFactoryError::MathOverflow)?;
         
         Ok(())
     }
 }
 
-/// X402 Payment Record - stores individual payment details
+/// X402 Payment Record - stores individual payment details. `agent`,
+/// `payer` and `status` come first, at the fixed offsets below, so RPC
+/// `getProgramAccounts` memcmp filters can select payments without an indexer.
 #[account]
 #[derive(InitSpace)]
 pub struct X402PaymentRecord {
@@ -91,18 +354,48 @@ pub struct X402PaymentRecord {
     /// Payer address
     pub payer: Pubkey,
     
+    /// Payment status
+    pub status: PaymentStatus,
+    
     /// Payment amount in lamports
     pub amount: u64,
     
     /// Timestamp of payment
     pub timestamp: i64,
     
-    /// Service identifier (hash of service name)
-    #[max_len(32)]
-    pub service_id: String,
-    
-    /// Payment status
-    pub status: PaymentStatus,
+    /// Service identifier (`service_id_hash` of the service name)
+    pub service_id: [u8; 32],
+
+    /// Hash of the attested service output (escrowed payments, zero until
+    /// attested), or of the usage report for metered payments
+    pub attestation_hash: [u8; 32],
+
+    /// Unit the payment is metered in
+    pub meter_unit: MeterUnit,
+
+    /// Price per meter unit locked in at payment (0 = flat payment)
+    pub unit_price: u64,
+
+    /// Units reported at settlement (metered payments)
+    pub metered_units: u64,
+
+    /// Client-supplied id of the off-chain request the payment is for
+    pub request_id: [u8; 16],
+
+    /// Refunded to the payer out of an escrowed payment's `amount` when it
+    /// was attested (partially, or all of it on a failed check) or expired
+    pub refunded_amount: u64,
+
+    /// End of the chargeback window, locked in at settlement from the
+    /// provider's `chargeback_window_secs` (0 = not disputable)
+    pub dispute_deadline: i64,
+
+    /// When `finalize_payment` closed the payment to disputes (0 = not
+    /// finalized)
+    pub finalized_at: i64,
+
+    /// Token mint the payment was made in
+    pub mint: Pubkey,
 
     /// Bump seed for PDA
     pub bump: u8,
@@ -112,63 +405,266 @@ impl X402PaymentRecord {
     pub const INIT_SPACE: usize =
         32 +        // agent
         32 +        // payer
+        1 +         // status
         8 +         // amount
         8 +         // timestamp
-        4 + 32 +    // service_id
-        1 +         // status
+        32 +        // service_id
+        32 +        // attestation_hash
+        1 +         // meter_unit
+        8 +         // unit_price
+        8 +         // metered_units
+        16 +        // request_id
+        8 +         // refunded_amount
+        8 +         // dispute_deadline
+        8 +         // finalized_at
+        32 +        // mint
         1;          // bump
-}
 
-/// Payment status enum
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
-pub enum PaymentStatus {
-    /// Payment is pending verification
-    Pending,
-    /// Payment has been verified
-    Verified,
-    /// Payment has been settled
-    Settled,
-    /// Payment failed
-    Failed,
+    /// Byte offset of `agent` in the account data, after the discriminator
+    pub const AGENT_OFFSET: usize = 8;
+
+    /// Byte offset of `payer`
+    pub const PAYER_OFFSET: usize = Self::AGENT_OFFSET + 32;
+
+    /// Byte offset of `status`, a single byte holding the `PaymentStatus`
+    /// variant index
+    pub const STATUS_OFFSET: usize = Self::PAYER_OFFSET + 32;
+
+    /// Whether the payment is billed by reported usage
+    pub fn is_metered(&self) -> bool {
+        self.unit_price > 0
+    }
+
+    /// Whether the payment can still be disputed at `now`; payments settled
+    /// without a chargeback window never can
+    pub fn is_disputable(&self, now: i64) -> bool {
+        self.finalized_at == 0 && now <= self.dispute_deadline
+    }
+
+    /// Charge for `units` of usage at the locked-in price, rounded up and
+    /// capped at the escrowed budget
+    pub fn metered_charge(&self, units: u64) -> Option<u64> {
+        let divisor = self.meter_unit.units_per_price() as u128;
+        let charge = (units as u128)
+            .checked_mul(self.unit_price as u128)?
+            .checked_add(divisor - 1)?
+            / divisor;
+        Some(charge.min(self.amount as u128) as u64)
+    }
 }
 
-/// X402 specific errors
-#[error_code]
-pub enum X402Error {
-    #[msg("Payment amount is below minimum")]
-    PaymentTooLow,
+/// Longest the previous recipient stays valid after a rotation (7 days)
+pub const MAX_RECIPIENT_GRACE_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Longest notice a provider can give before raising prices (30 days)
+pub const MAX_PRICE_NOTICE_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Longest chargeback window a provider can offer (90 days)
+pub const MAX_CHARGEBACK_WINDOW_SECS: u64 = 90 * 24 * 60 * 60;
+
+/// Longest a quote may stay open (24 hours)
+pub const MAX_QUOTE_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// Price an agent's creator negotiated with one payer for one service,
+/// payable through `pay_for_service` in place of the listed bounds until it
+/// expires. Single use; the creator closes it to reclaim the rent.
+#[account]
+#[derive(InitSpace)]
+pub struct ServiceQuote {
+    /// Quoting agent
+    pub agent: Pubkey,
     
-    #[msg("Payment amount exceeds maximum")]
-    PaymentTooHigh,
+    /// Payer the quote is for
+    pub payer: Pubkey,
     
-    #[msg("X402 payments not enabled for this agent")]
-    PaymentsNotEnabled,
+    /// Quoted service ID
+    pub service_id: [u8; 32],
     
-    #[msg("Invalid payment signature")]
-    InvalidPaymentSignature,
+    /// Negotiated price in payment token units
+    pub amount: u64,
     
-    #[msg("Payment has expired")]
-    PaymentExpired,
+    /// Unix timestamp after which the quote can no longer be paid
+    pub expires_at: i64,
     
-    #[msg("Nonce mismatch - possible replay attack")]
-    NonceMismatch,
+    /// Whether a payment already used the quote
+    pub used: bool,
     
-    #[msg("Nonce overflow")]
-    NonceOverflow,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ServiceQuote {
+    pub const INIT_SPACE: usize =
+        32 +        // agent
+        32 +        // payer
+        32 +        // service_id
+        8 +         // amount
+        8 +         // expires_at
+        1 +         // used
+        1;          // bump
+
+    /// Validate a payment of `amount` for `service_id` by `payer` at `now`
+    /// against the quote
+    pub fn validate_payment(
+        &self,
+        payer: &Pubkey,
+        amount: u64,
+        service_id: &[u8; 32],
+        now: i64,
+    ) -> Result<()> {
+        require!(!self.used, AgentFactoryError::QuoteUsed);
+        require!(now <= self.expires_at, AgentFactoryError::QuoteExpired);
+        require!(
+            self.payer == *payer && self.amount == amount && self.service_id == *service_id,
+            AgentFactoryError::QuoteMismatch
+        );
+        Ok(())
+    }
+}
+
+/// Terms one agent proposed for another agent's service, payable through
+/// `call_agent_service` in place of the listed bounds once the target
+/// agent's creator accepts them and until they expire. Single use; the
+/// proposer closes it to reclaim the rent.
+#[account]
+#[derive(InitSpace)]
+pub struct ServiceAgreement {
+    /// Agent that pays for the service
+    pub caller_agent: Pubkey,
     
-    #[msg("Math operation overflow")]
-    MathOverflow,
+    /// Agent providing the service
+    pub target_agent: Pubkey,
     
-    #[msg("Service timeout exceeded")]
-    ServiceTimeout,
+    /// Negotiated service ID
+    pub service_id: [u8; 32],
     
-    #[msg("Invalid service identifier")]
-    InvalidServiceId,
+    /// Negotiated price in payment token units
+    pub amount: u64,
     
-    #[msg("Payment already settled")]
-    PaymentAlreadySettled,
+    /// Unix timestamp after which the terms can no longer be accepted or paid
+    pub expires_at: i64,
     
-    #[msg("Insufficient payment amount")]
-    InsufficientPayment,
+    /// Whether the target agent's creator accepted the terms
+    pub accepted: bool,
+    
+    /// Whether a service call already used the terms
+    pub used: bool,
+    
+    /// Caller agent's creator who proposed the terms and paid the rent
+    pub proposer: Pubkey,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
 }
 
+impl ServiceAgreement {
+    pub const INIT_SPACE: usize =
+        32 +        // caller_agent
+        32 +        // target_agent
+        32 +        // service_id
+        8 +         // amount
+        8 +         // expires_at
+        1 +         // accepted
+        1 +         // used
+        32 +        // proposer
+        1;          // bump
+
+    /// Validate a service call paying `amount` for `service_id` at `now`
+    /// against the agreed terms
+    pub fn validate_payment(&self, amount: u64, service_id: &[u8; 32], now: i64) -> Result<()> {
+        require!(self.accepted, AgentFactoryError::TermsNotAccepted);
+        require!(!self.used, AgentFactoryError::TermsUsed);
+        require!(now <= self.expires_at, AgentFactoryError::TermsExpired);
+        require!(
+            self.amount == amount && self.service_id == *service_id,
+            AgentFactoryError::TermsMismatch
+        );
+        Ok(())
+    }
+}
+
+/// Unit metered services are priced in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum MeterUnit {
+    /// Price per 1K LLM tokens
+    #[default]
+    ThousandTokens,
+    /// Price per compute unit
+    ComputeUnits,
+}
+
+impl MeterUnit {
+    /// Reported units covered by one `unit_price`
+    pub fn units_per_price(&self) -> u64 {
+        match self {
+            MeterUnit::ThousandTokens => 1_000,
+            MeterUnit::ComputeUnits => 1,
+        }
+    }
+}
+
+/// Payment status enum
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PaymentStatus {
+    /// Payment is pending verification
+    Pending,
+    /// Payment has been verified
+    Verified,
+    /// Payment has been settled
+    Settled,
+    /// Payment failed
+    Failed,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn config_with_window(chargeback_window_secs: u64) -> X402Config {
+        let data = vec![0u8; X402Config::INIT_SPACE];
+        let mut config = X402Config::deserialize(&mut &data[..]).unwrap();
+        config.chargeback_window_secs = chargeback_window_secs;
+        config
+    }
+
+    fn payment_with_deadline(dispute_deadline: i64) -> X402PaymentRecord {
+        let data = vec![0u8; X402PaymentRecord::INIT_SPACE];
+        let mut record = X402PaymentRecord::deserialize(&mut &data[..]).unwrap();
+        record.dispute_deadline = dispute_deadline;
+        record
+    }
+
+    #[test]
+    fn dispute_deadline_follows_the_chargeback_window() {
+        assert_eq!(config_with_window(0).dispute_deadline(NOW), 0);
+        assert_eq!(config_with_window(3_600).dispute_deadline(NOW), NOW + 3_600);
+        assert_eq!(config_with_window(3_600).dispute_deadline(i64::MAX - 1), i64::MAX);
+    }
+
+    #[test]
+    fn payments_are_disputable_until_the_deadline() {
+        let record = payment_with_deadline(NOW + 3_600);
+
+        assert!(record.is_disputable(NOW));
+        assert!(record.is_disputable(NOW + 3_600));
+        assert!(!record.is_disputable(NOW + 3_601));
+    }
+
+    #[test]
+    fn payments_without_a_window_are_never_disputable() {
+        let record = payment_with_deadline(config_with_window(0).dispute_deadline(NOW));
+
+        assert!(!record.is_disputable(NOW));
+    }
+
+    #[test]
+    fn finalized_payments_are_not_disputable() {
+        let mut record = payment_with_deadline(NOW + 3_600);
+        record.finalized_at = NOW;
+
+        assert!(!record.is_disputable(NOW));
+    }
+}
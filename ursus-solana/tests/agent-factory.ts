@@ -4,6 +4,7 @@ import { AgentFactory } from "../target/types/agent_factory";
 import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token";
 import { assert } from "chai";
+import { nameReservationPda } from "./helpers";
 
 describe("agent-factory", () => {
   // Configure the client to use the local cluster
@@ -92,7 +93,7 @@ describe("agent-factory", () => {
         "A test AI agent for trading",
         "You are a helpful trading assistant",
         "gpt-4",
-        "trading"
+        ["trading"]
       )
       .accounts({
         factory: factoryPda,
@@ -101,6 +102,8 @@ describe("agent-factory", () => {
         tokenVault: tokenVaultPda,
         creator: creator.publicKey,
         platformTreasury: platformTreasury.publicKey,
+        nameReservation: nameReservationPda(program.programId, "Test Agent"),
+        symbolReservation: nameReservationPda(program.programId, "TEST"),
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -148,8 +151,9 @@ describe("agent-factory", () => {
         agent: agentPda,
         mint: mintPda,
         tokenVault: tokenVaultPda,
-        buyerTokenAccount: buyerTokenAccount,
+        recipientTokenAccount: buyerTokenAccount,
         buyer: buyer.publicKey,
+        recipient: buyer.publicKey,
         creator: creator.publicKey,
         platformTreasury: platformTreasury.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -195,8 +199,9 @@ describe("agent-factory", () => {
         agent: agentPda,
         mint: mintPda,
         tokenVault: tokenVaultPda,
-        buyerTokenAccount: sellerTokenAccount,
+        recipientTokenAccount: sellerTokenAccount,
         buyer: seller.publicKey,
+        recipient: seller.publicKey,
         creator: creator.publicKey,
         platformTreasury: platformTreasury.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
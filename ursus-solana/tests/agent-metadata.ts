@@ -0,0 +1,211 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddressSync, transfer } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  airdrop,
+  createTestAgent,
+  metadataPda,
+  nameReservationPda,
+  METADATA_PROGRAM_ID,
+  ownershipAccount,
+} from "./helpers";
+
+describe("Agent metadata and ownership", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts
+  const creator = Keypair.generate();
+  const buyer = Keypair.generate();
+  let platformTreasury: PublicKey;
+
+  let factoryPda: PublicKey;
+  let agentPda: PublicKey;
+  let mintPda: PublicKey;
+  let ownershipMintPda: PublicKey;
+
+  // Name, symbol and update authority from a Metaplex metadata account:
+  // key, update authority, mint, then the zero-padded name and symbol
+  const readMetadata = async (mint: PublicKey) => {
+    const info = await provider.connection.getAccountInfo(metadataPda(mint));
+    expect(info, "metadata account").to.not.be.null;
+    const data = info!.data;
+    const readString = (offset: number): [string, number] => {
+      const len = data.readUInt32LE(offset);
+      const value = data.subarray(offset + 4, offset + 4 + len).toString("utf8").replace(/\0/g, "");
+      return [value, offset + 4 + len];
+    };
+    const updateAuthority = new PublicKey(data.subarray(1, 33));
+    const [name, symbolOffset] = readString(65);
+    const [symbol] = readString(symbolOffset);
+    return { updateAuthority, name, symbol };
+  };
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, buyer], 20);
+
+    ({ factoryPda, platformTreasury, agentPda, mintPda, ownershipMintPda } = await createTestAgent(
+      program,
+      creator,
+      "Metadata Agent",
+      "META",
+      "Agent with token metadata"
+    ));
+  });
+
+  it("Creates Metaplex metadata for the token and the ownership NFT", async () => {
+    const token = await readMetadata(mintPda);
+    expect(token.name).to.equal("Metadata Agent");
+    expect(token.symbol).to.equal("META");
+    expect(token.updateAuthority.toBase58()).to.equal(agentPda.toBase58());
+
+    const nft = await readMetadata(ownershipMintPda);
+    expect(nft.name).to.equal("Metadata Agent");
+    expect(nft.symbol).to.equal("URSUSOWN");
+    expect(nft.updateAuthority.toBase58()).to.equal(agentPda.toBase58());
+
+    const nftAccount = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(ownershipMintPda, creator.publicKey)
+    );
+    expect(Number(nftAccount.amount)).to.equal(1);
+    expect(nftAccount.isFrozen).to.be.false;
+  });
+
+  it("Renames the agent and its token metadata", async () => {
+    await program.methods
+      .renameAgent("Renamed Agent", "RNMD")
+      .accounts({
+        factory: factoryPda,
+        agent: agentPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+        platformTreasury,
+        systemProgram: SystemProgram.programId,
+        nameReservation: nameReservationPda(program.programId, "Renamed Agent"),
+        symbolReservation: nameReservationPda(program.programId, "RNMD"),
+        mintMetadata: metadataPda(mintPda),
+        ownershipMetadata: metadataPda(ownershipMintPda),
+        metadataProgram: METADATA_PROGRAM_ID,
+      })
+      .signers([creator])
+      .rpc();
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    expect(agent.name).to.equal("Renamed Agent");
+    expect(agent.symbol).to.equal("RNMD");
+
+    const token = await readMetadata(mintPda);
+    expect(token.name).to.equal("Renamed Agent");
+    expect(token.symbol).to.equal("RNMD");
+
+    const nft = await readMetadata(ownershipMintPda);
+    expect(nft.name).to.equal("Renamed Agent");
+    expect(nft.symbol).to.equal("URSUSOWN");
+  });
+
+  it("Rejects a second rename within the cooldown", async () => {
+    try {
+      await program.methods
+        .renameAgent("Renamed Again", "AGAIN")
+        .accounts({
+          factory: factoryPda,
+          agent: agentPda,
+          creator: creator.publicKey,
+          creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+          platformTreasury,
+          systemProgram: SystemProgram.programId,
+          nameReservation: nameReservationPda(program.programId, "Renamed Again"),
+          symbolReservation: nameReservationPda(program.programId, "AGAIN"),
+          mintMetadata: metadataPda(mintPda),
+          ownershipMetadata: metadataPda(ownershipMintPda),
+          metadataProgram: METADATA_PROGRAM_ID,
+        })
+        .signers([creator])
+        .rpc();
+      expect.fail("rename within the cooldown should fail");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.equal("RenameCooldown");
+    }
+
+    const token = await readMetadata(mintPda);
+    expect(token.name).to.equal("Renamed Agent");
+  });
+
+  it("Moves the ownership NFT and the creator role together", async () => {
+    await program.methods
+      .transferAgentOwnership()
+      .accounts({
+        agent: agentPda,
+        ownershipMint: ownershipMintPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+        newCreator: buyer.publicKey,
+      })
+      .signers([creator])
+      .rpc();
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    expect(agent.creator.toBase58()).to.equal(buyer.publicKey.toBase58());
+
+    const oldAccount = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(ownershipMintPda, creator.publicKey)
+    );
+    expect(Number(oldAccount.amount)).to.equal(0);
+
+    const newAccount = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(ownershipMintPda, buyer.publicKey)
+    );
+    expect(Number(newAccount.amount)).to.equal(1);
+    expect(newAccount.isFrozen).to.be.false;
+  });
+
+  it("Rejects creator-gated calls from a wallet without the NFT", async () => {
+    try {
+      await program.methods
+        .setMaxBuy(0)
+        .accounts({
+          agent: agentPda,
+          creator: creator.publicKey,
+          creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+        })
+        .signers([creator])
+        .rpc();
+      expect.fail("the previous creator no longer holds the NFT");
+    } catch (err: any) {
+      expect(err.error?.errorCode?.code).to.equal("NotOwnershipHolder");
+    }
+  });
+
+  it("Lets whoever receives the NFT claim the creator role", async () => {
+    // A plain token transfer, as a marketplace sale would do
+    await transfer(
+      provider.connection,
+      buyer,
+      getAssociatedTokenAddressSync(ownershipMintPda, buyer.publicKey),
+      getAssociatedTokenAddressSync(ownershipMintPda, creator.publicKey),
+      buyer,
+      1
+    );
+
+    await program.methods
+      .claimAgentOwnership()
+      .accounts({
+        agent: agentPda,
+        ownershipTokenAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+        holder: creator.publicKey,
+      })
+      .signers([creator])
+      .rpc();
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    expect(agent.creator.toBase58()).to.equal(creator.publicKey.toBase58());
+  });
+});
@@ -3,6 +3,8 @@ import { Program } from "@coral-xyz/anchor";
 import { AgentFactory } from "../target/types/agent_factory";
 import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { expect } from "chai";
+import { createHash } from "crypto";
+import { nameReservationPda, ownershipAccount } from "./helpers";
 
 describe("Agent-to-Agent X402 Payments", () => {
   const provider = anchor.AnchorProvider.env();
@@ -27,6 +29,9 @@ describe("Agent-to-Agent X402 Payments", () => {
   const MIN_PAYMENT = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
   const MAX_PAYMENT = new anchor.BN(1 * LAMPORTS_PER_SOL);
   const SERVICE_TIMEOUT = new anchor.BN(300);
+  const requestId = Array.from({ length: 16 }, (_, i) => i + 1);
+  const serviceIdHash = (name: string) =>
+    Array.from(createHash("sha256").update(name.trim().toLowerCase()).digest());
   
   before(async () => {
     // Airdrop SOL
@@ -72,7 +77,7 @@ describe("Agent-to-Agent X402 Payments", () => {
         "AI agent for market analysis",
         "Analyze crypto market trends",
         "GPT-4",
-        "Trading"
+        ["trading"]
       )
       .accounts({
         factory: factoryPda,
@@ -80,6 +85,8 @@ describe("Agent-to-Agent X402 Payments", () => {
         mint: callerMintPda,
         creator: callerCreator.publicKey,
         platformTreasury: platformTreasury.publicKey,
+        nameReservation: nameReservationPda(program.programId, "Market Analyzer"),
+        symbolReservation: nameReservationPda(program.programId, "MKTAI"),
       })
       .signers([callerCreator])
       .rpc();
@@ -108,7 +115,7 @@ describe("Agent-to-Agent X402 Payments", () => {
         "AI agent providing market data",
         "Provide real-time market data and analytics",
         "GPT-4",
-        "Data"
+        ["data"]
       )
       .accounts({
         factory: factoryPda,
@@ -116,6 +123,8 @@ describe("Agent-to-Agent X402 Payments", () => {
         mint: targetMintPda,
         creator: targetCreator.publicKey,
         platformTreasury: platformTreasury.publicKey,
+        nameReservation: nameReservationPda(program.programId, "Data Provider"),
+        symbolReservation: nameReservationPda(program.programId, "DATAI"),
       })
       .signers([targetCreator])
       .rpc();
@@ -141,6 +150,7 @@ describe("Agent-to-Agent X402 Payments", () => {
         agent: targetAgentPda,
         x402Config: targetX402ConfigPda,
         creator: targetCreator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, targetAgentPda, targetCreator.publicKey),
         systemProgram: SystemProgram.programId,
       })
       .signers([targetCreator])
@@ -154,7 +164,8 @@ describe("Agent-to-Agent X402 Payments", () => {
     const x402Config = await program.account.x402Config.fetch(targetX402ConfigPda);
     const nonce = x402Config.nonce.add(new anchor.BN(1));
     const paymentAmount = new anchor.BN(0.05 * LAMPORTS_PER_SOL);
-    const serviceId = "get_market_data";
+    const serviceName = "get_market_data";
+    const serviceId = serviceIdHash(serviceName);
     const serviceParams = Buffer.from(JSON.stringify({
       symbol: "SOL/USD",
       timeframe: "1h",
@@ -178,7 +189,9 @@ describe("Agent-to-Agent X402 Payments", () => {
         paymentAmount,
         serviceId,
         nonce,
-        Array.from(serviceParams)
+        Array.from(serviceParams),
+        requestId,
+        new anchor.BN(0)
       )
       .accounts({
         callerAgent: callerAgentPda,
@@ -186,6 +199,7 @@ describe("Agent-to-Agent X402 Payments", () => {
         targetX402Config: targetX402ConfigPda,
         paymentRecord: paymentRecordPda,
         callerAuthority: callerCreator.publicKey,
+        callerOwnershipAccount: ownershipAccount(program.programId, callerAgentPda, callerCreator.publicKey),
         targetPaymentRecipient: targetCreator.publicKey,
         systemProgram: SystemProgram.programId,
       })
@@ -201,7 +215,7 @@ describe("Agent-to-Agent X402 Payments", () => {
     
     // Verify payment record
     expect(paymentRecord.amount.toString()).to.equal(paymentAmount.toString());
-    expect(paymentRecord.serviceId).to.equal(serviceId);
+    expect(paymentRecord.serviceId).to.deep.equal(serviceId);
     expect(paymentRecord.payer.toString()).to.equal(callerAgentPda.toString());
     expect(paymentRecord.agent.toString()).to.equal(targetAgentPda.toString());
     
@@ -213,7 +227,7 @@ describe("Agent-to-Agent X402 Payments", () => {
     console.log("✅ Agent-to-Agent payment successful!");
     console.log(`   Caller: ${callerAgentPda.toString().slice(0, 8)}...`);
     console.log(`   Target: ${targetAgentPda.toString().slice(0, 8)}...`);
-    console.log(`   Service: ${serviceId}`);
+    console.log(`   Service: ${serviceName}`);
     console.log(`   Amount: ${paymentAmount.toNumber() / LAMPORTS_PER_SOL} SOL`);
   });
   
@@ -243,9 +257,11 @@ describe("Agent-to-Agent X402 Payments", () => {
       await program.methods
         .callAgentService(
           paymentAmount,
-          service.id,
+          serviceIdHash(service.id),
           nonce,
-          Array.from(serviceParams)
+          Array.from(serviceParams),
+          requestId,
+          new anchor.BN(0)
         )
         .accounts({
           callerAgent: callerAgentPda,
@@ -253,6 +269,7 @@ describe("Agent-to-Agent X402 Payments", () => {
           targetX402Config: targetX402ConfigPda,
           paymentRecord: paymentRecordPda,
           callerAuthority: callerCreator.publicKey,
+          callerOwnershipAccount: ownershipAccount(program.programId, callerAgentPda, callerCreator.publicKey),
           targetPaymentRecipient: targetCreator.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -0,0 +1,237 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAssociatedTokenAccount } from "@solana/spl-token";
+import { expect } from "chai";
+import { airdrop, createTestAgent, ownershipAccount } from "./helpers";
+
+describe("Agent vaults", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts
+  const authority = provider.wallet as anchor.Wallet;
+  const creator = Keypair.generate();
+  const trader = Keypair.generate();
+  let platformTreasury: PublicKey;
+
+  let factoryPda: PublicKey;
+  let agentPda: PublicKey;
+  let mintPda: PublicKey;
+  let solVault: PublicKey;
+  let platformFeeVault: PublicKey;
+  let creatorFeeVault: PublicKey;
+  let stakingPoolPda: PublicKey;
+  let traderTokenAccount: PublicKey;
+  let vaultRent: number;
+
+  const BPS = 10_000;
+  const STAKING_FEE_BPS = 5_000;
+
+  const vaultPda = (seed: string) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from(seed), agentPda.toBuffer()],
+      program.programId
+    )[0];
+
+  const lamports = (address: PublicKey) => provider.connection.getBalance(address);
+
+  const fee = (amount: number, bps: number) => Math.floor((amount * bps) / BPS);
+
+  const buy = (solAmount: number) =>
+    program.methods
+      .buyTokens(new anchor.BN(solAmount), new anchor.BN(0))
+      .accounts({
+        agent: agentPda,
+        mint: mintPda,
+        recipientTokenAccount: traderTokenAccount,
+        buyer: trader.publicKey,
+        recipient: trader.publicKey,
+        creator: creator.publicKey,
+        platformTreasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        factory: factoryPda,
+        platformStake: null,
+        priceHistory: null,
+        trending: null,
+      })
+      .signers([trader])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, trader], 20);
+
+    ({ factoryPda, platformTreasury, agentPda, mintPda } = await createTestAgent(
+      program,
+      creator,
+      "Vault Agent",
+      "VAULT",
+      "Agent for vault accounting"
+    ));
+    [stakingPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("staking_pool"), agentPda.toBuffer()],
+      program.programId
+    );
+    solVault = vaultPda("sol_vault");
+    platformFeeVault = vaultPda("platform_fee_vault");
+    creatorFeeVault = vaultPda("creator_fee_vault");
+    vaultRent = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+    traderTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      trader,
+      mintPda,
+      trader.publicKey
+    );
+  });
+
+  it("Funds every vault with its rent at creation", async () => {
+    for (const vault of [solVault, platformFeeVault, creatorFeeVault]) {
+      expect(await lamports(vault)).to.equal(vaultRent);
+    }
+  });
+
+  it("Holds buy reserves in the SOL vault and pays the fees out", async () => {
+    const treasuryBefore = await lamports(platformTreasury);
+    const creatorBefore = await lamports(creator.publicKey);
+
+    await buy(LAMPORTS_PER_SOL);
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    const platformFee = fee(LAMPORTS_PER_SOL, agent.bondingCurve.platformFeeBps);
+    const creatorFee = fee(LAMPORTS_PER_SOL, agent.bondingCurve.creatorFeeBps);
+    expect(agent.bondingCurve.realSolReserves.toNumber()).to.equal(
+      LAMPORTS_PER_SOL - platformFee - creatorFee
+    );
+    expect(await lamports(solVault)).to.equal(
+      vaultRent + agent.bondingCurve.realSolReserves.toNumber()
+    );
+    expect((await lamports(platformTreasury)) - treasuryBefore).to.equal(platformFee);
+    expect((await lamports(creator.publicKey)) - creatorBefore).to.equal(creatorFee);
+    // Nothing is retained without insurance, public goods or staking shares
+    expect(await lamports(platformFeeVault)).to.equal(vaultRent);
+    expect(await lamports(creatorFeeVault)).to.equal(vaultRent);
+  });
+
+  it("Retains the staking share of creator fees in the creator fee vault", async () => {
+    await program.methods
+      .createStakingPool(STAKING_FEE_BPS, new anchor.BN(0))
+      .accounts({
+        agent: agentPda,
+        mint: mintPda,
+        stakingPool: stakingPoolPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+      })
+      .signers([creator])
+      .rpc();
+
+    const creatorBefore = await lamports(creator.publicKey);
+    await buy(LAMPORTS_PER_SOL);
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    const creatorFee = fee(LAMPORTS_PER_SOL, agent.bondingCurve.creatorFeeBps);
+    const toStaking = fee(creatorFee, STAKING_FEE_BPS);
+    expect(agent.pendingStakingRewards.toNumber()).to.equal(toStaking);
+    expect(await lamports(creatorFeeVault)).to.equal(vaultRent + toStaking);
+    expect((await lamports(creator.publicKey)) - creatorBefore).to.equal(creatorFee - toStaking);
+  });
+
+  it("Withdraws the retained share from the fee vault into the staking pool", async () => {
+    const pending = (await program.account.agent.fetch(agentPda) as any).pendingStakingRewards.toNumber();
+    const poolBefore = await lamports(stakingPoolPda);
+
+    await program.methods
+      .fundStakingPool(new anchor.BN(1))
+      .accounts({
+        agent: agentPda,
+        stakingPool: stakingPoolPda,
+        funder: creator.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    expect(agent.pendingStakingRewards.toNumber()).to.equal(0);
+    expect(await lamports(creatorFeeVault)).to.equal(vaultRent);
+    expect((await lamports(stakingPoolPda)) - poolBefore).to.equal(pending + 1);
+  });
+
+  it("Pays sells out of the SOL vault", async () => {
+    const balance = await provider.connection.getTokenAccountBalance(traderTokenAccount);
+    const tokenAmount = new anchor.BN(balance.value.amount).divn(2);
+    const vaultBefore = await lamports(solVault);
+    const treasuryBefore = await lamports(platformTreasury);
+    const traderBefore = await lamports(trader.publicKey);
+
+    await program.methods
+      .sellTokens(tokenAmount, new anchor.BN(0))
+      .accounts({
+        agent: agentPda,
+        mint: mintPda,
+        sellerTokenAccount: traderTokenAccount,
+        seller: trader.publicKey,
+        creator: creator.publicKey,
+        platformTreasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        factory: factoryPda,
+        platformStake: null,
+        priceHistory: null,
+        trending: null,
+      })
+      .signers([trader])
+      .rpc();
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    // The vault pays the gross proceeds: the seller's net and both fees
+    const grossSolOut = vaultBefore - (await lamports(solVault));
+    expect(grossSolOut).to.be.greaterThan(0);
+    expect(await lamports(solVault)).to.equal(
+      vaultRent + agent.bondingCurve.realSolReserves.toNumber()
+    );
+    expect((await lamports(platformTreasury)) - treasuryBefore).to.equal(
+      fee(grossSolOut, agent.bondingCurve.platformFeeBps)
+    );
+    expect((await lamports(trader.publicKey)) - traderBefore).to.equal(
+      grossSolOut -
+        fee(grossSolOut, agent.bondingCurve.platformFeeBps) -
+        fee(grossSolOut, agent.bondingCurve.creatorFeeBps)
+    );
+    expect(await lamports(creatorFeeVault)).to.equal(
+      vaultRent + agent.pendingStakingRewards.toNumber()
+    );
+  });
+
+  it("Keeps the reserves in the SOL vault until the curve can graduate", async () => {
+    const vaultBefore = await lamports(solVault);
+    try {
+      await program.methods
+        .graduateAgent()
+        .accounts({
+          agent: agentPda,
+          mint: mintPda,
+          authority: authority.publicKey,
+          dexProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          factory: factoryPda,
+          protocolLiquidity: null,
+          protocolLiquidityTokenAccount: null,
+        })
+        .rpc();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("CannotGraduate");
+    }
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    expect(agent.isGraduated).to.be.false;
+    expect(await lamports(solVault)).to.equal(vaultBefore);
+  });
+});
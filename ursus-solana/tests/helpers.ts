@@ -0,0 +1,217 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Connection, PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  NATIVE_MINT,
+  createAssociatedTokenAccount,
+  createMint,
+  createWrappedNativeAccount,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from "@solana/spl-token";
+import { createHash } from "crypto";
+
+// Fixtures shared by the suites that run against the one local factory
+
+export const METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+export const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+// Reservation of an agent name or symbol: ["name_reservation", sha256(lowercased)]
+export const nameReservationPda = (programId: PublicKey, name: string) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("name_reservation"), createHash("sha256").update(name.toLowerCase()).digest()],
+    programId
+  )[0];
+
+// Associated token account of `holder` for an agent's ownership NFT, which
+// creator-gated instructions take as `creatorOwnershipAccount`
+export const ownershipAccount = (programId: PublicKey, agent: PublicKey, holder: PublicKey) =>
+  getAssociatedTokenAddressSync(
+    PublicKey.findProgramAddressSync([Buffer.from("ownership_mint"), agent.toBuffer()], programId)[0],
+    holder
+  );
+
+// Metaplex metadata account of a mint
+export const metadataPda = (mint: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    METADATA_PROGRAM_ID
+  )[0];
+
+export const tokenBalance = async (connection: Connection, account: PublicKey) =>
+  Number((await getAccount(connection, account)).amount);
+
+export const airdrop = async (connection: Connection, wallets: Keypair[], sol: number) => {
+  for (const wallet of wallets) {
+    const sig = await connection.requestAirdrop(wallet.publicKey, sol * LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(sig, "confirmed");
+  }
+};
+
+// The factory is shared with the other suites; initialize it if needed
+export const ensureFactory = async (program: Program<any>) => {
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId
+  );
+
+  const existing: any = await program.account.agentFactory.fetchNullable(factoryPda);
+  if (existing) {
+    return { factoryPda, platformTreasury: existing.platformTreasury as PublicKey };
+  }
+
+  const platformTreasury = Keypair.generate().publicKey;
+  await program.methods
+    .initialize(new anchor.BN(0))
+    .accounts({
+      factory: factoryPda,
+      authority: program.provider.publicKey,
+      platformTreasury,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+  return { factoryPda, platformTreasury };
+};
+
+// Launch an agent at the factory's next index, with its token and ownership
+// NFT metadata; returns its PDAs
+export const createTestAgent = async (
+  program: Program<any>,
+  creator: Keypair,
+  name: string,
+  symbol: string,
+  description: string
+) => {
+  const { factoryPda, platformTreasury } = await ensureFactory(program);
+  const factory: any = await program.account.agentFactory.fetch(factoryPda);
+  const [agentPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("agent"), factory.totalAgents.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  );
+  const [x402ConfigPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("x402_config"), agentPda.toBuffer()],
+    program.programId
+  );
+  const [mintPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("mint"), agentPda.toBuffer()],
+    program.programId
+  );
+  const [ownershipMintPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("ownership_mint"), agentPda.toBuffer()],
+    program.programId
+  );
+
+  await program.methods
+    .createAgent(name, symbol, description, "", "gpt-4", ["test"])
+    .accounts({
+      factory: factoryPda,
+      agent: agentPda,
+      mint: mintPda,
+      mintMetadata: metadataPda(mintPda),
+      ownershipMint: ownershipMintPda,
+      ownershipMetadata: metadataPda(ownershipMintPda),
+      creator: creator.publicKey,
+      platformTreasury,
+      metadataProgram: METADATA_PROGRAM_ID,
+      nameReservation: nameReservationPda(program.programId, name),
+      symbolReservation: nameReservationPda(program.programId, symbol),
+    })
+    .signers([creator])
+    .rpc();
+
+  return { factoryPda, platformTreasury, agentPda, x402ConfigPda, mintPda, ownershipMintPda };
+};
+
+// Enable x402 for an agent; the service timeout doubles as the lease, review
+// period or session length of the features built on it
+export const configureX402 = (
+  program: Program<any>,
+  agentPda: PublicKey,
+  creator: Keypair,
+  minPayment: number,
+  maxPayment: number,
+  serviceTimeoutSecs: number
+) => {
+  const [x402ConfigPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("x402_config"), agentPda.toBuffer()],
+    program.programId
+  );
+  return program.methods
+    .configureX402(
+      true,
+      new anchor.BN(minPayment),
+      new anchor.BN(maxPayment),
+      new anchor.BN(serviceTimeoutSecs)
+    )
+    .accounts({
+      agent: agentPda,
+      x402Config: x402ConfigPda,
+      authority: creator.publicKey,
+      creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([creator])
+    .rpc();
+};
+
+// Let the agent's payments be disputed for `secs` after they settle
+export const setChargebackWindow = (
+  program: Program<any>,
+  agentPda: PublicKey,
+  creator: Keypair,
+  secs: number
+) => {
+  const [x402ConfigPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("x402_config"), agentPda.toBuffer()],
+    program.programId
+  );
+  return program.methods
+    .setChargebackWindow(new anchor.BN(secs))
+    .accounts({
+      agent: agentPda,
+      x402Config: x402ConfigPda,
+      creator: creator.publicKey,
+      creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+    })
+    .signers([creator])
+    .rpc();
+};
+
+// A 6-decimal stand-in for USDC: `funder` mints `amount` to itself; every
+// holder gets an empty account. Accounts are returned funder first.
+export const createUsdc = async (
+  connection: Connection,
+  funder: Keypair,
+  amount: number,
+  holders: Keypair[]
+) => {
+  const mint = await createMint(connection, funder, funder.publicKey, null, 6);
+  const accounts: PublicKey[] = [];
+  for (const wallet of [funder, ...holders]) {
+    accounts.push(await createAssociatedTokenAccount(connection, wallet, mint, wallet.publicKey));
+  }
+  await mintTo(connection, funder, mint, accounts[0], funder, amount);
+  return { mint, accounts };
+};
+
+// Wrapped SOL, which disputes and insurance value at par: `funder` wraps
+// `amount` lamports; every holder gets an empty account. Accounts are
+// returned funder first.
+export const createWsol = async (
+  connection: Connection,
+  funder: Keypair,
+  amount: number,
+  holders: Keypair[]
+) => {
+  const accounts = [
+    await createWrappedNativeAccount(connection, funder, funder.publicKey, amount),
+  ];
+  for (const wallet of holders) {
+    accounts.push(
+      await createAssociatedTokenAccount(connection, wallet, NATIVE_MINT, wallet.publicKey)
+    );
+  }
+  return { mint: NATIVE_MINT, accounts };
+};
@@ -0,0 +1,299 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createHash } from "crypto";
+import { expect } from "chai";
+import {
+  airdrop,
+  configureX402,
+  createTestAgent,
+  createUsdc,
+  sleep,
+  tokenBalance,
+  ownershipAccount,
+} from "./helpers";
+
+describe("Milestone jobs", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts; the factory authority is the default arbitration council
+  const authority = provider.wallet as anchor.Wallet;
+  const creator = Keypair.generate();
+  const payer = Keypair.generate();
+
+  let factoryPda: PublicKey;
+  let agentPda: PublicKey;
+  let x402ConfigPda: PublicKey;
+  let usdcMint: PublicKey;
+  let payerUsdc: PublicKey;
+  let creatorUsdc: PublicKey;
+  let jobPda: PublicKey;
+  let escrowPda: PublicKey;
+  let deadline: number;
+
+  const MILESTONES = [100_000, 200_000, 300_000, 400_000];
+  const REVIEW_PERIOD_SECS = 3;
+  const serviceId = Array.from(createHash("sha256").update("research_report").digest());
+  const deliverableHash = Array.from(createHash("sha256").update("deliverable").digest());
+  const evidenceHash = Array.from(createHash("sha256").update("evidence").digest());
+
+  // The validator's clock, which the deadline is checked against
+  const clusterTime = async () =>
+    (await provider.connection.getBlockTime(await provider.connection.getSlot()))!;
+
+  const fetchJob = async (): Promise<any> => program.account.job.fetch(jobPda);
+
+  const submitMilestone = (index: number) =>
+    program.methods
+      .submitMilestone(index, deliverableHash)
+      .accounts({
+        agent: agentPda,
+        job: jobPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+      })
+      .signers([creator])
+      .rpc();
+
+  // Released by the payer, or by the provider wallet as a third party
+  const releaseMilestone = (index: number, byPayer: boolean) =>
+    program.methods
+      .releaseMilestone(index)
+      .accounts({
+        x402Config: x402ConfigPda,
+        job: jobPda,
+        escrowTokenAccount: escrowPda,
+        recipientTokenAccount: creatorUsdc,
+        payer: payer.publicKey,
+        authority: byPayer ? payer.publicKey : authority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        revenueSplit: null,
+      })
+      .signers(byPayer ? [payer] : [])
+      .rpc();
+
+  const cancelJob = () =>
+    program.methods
+      .cancelJob()
+      .accounts({
+        x402Config: x402ConfigPda,
+        job: jobPda,
+        escrowTokenAccount: escrowPda,
+        payerTokenAccount: payerUsdc,
+        payer: payer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([payer])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, payer], 10);
+
+    ({ factoryPda, agentPda, x402ConfigPda } = await createTestAgent(
+      program,
+      creator,
+      "Milestone Agent",
+      "MLSTN",
+      "Agent hired for jobs"
+    ));
+    // The service timeout becomes the jobs' review period
+    await configureX402(program, agentPda, creator, 1, 0, REVIEW_PERIOD_SECS);
+
+    ({ mint: usdcMint, accounts: [payerUsdc, creatorUsdc] } = await createUsdc(
+      provider.connection,
+      payer,
+      10_000_000,
+      [creator]
+    ));
+  });
+
+  it("Locks every milestone in escrow", async () => {
+    const payerBefore = await tokenBalance(provider.connection, payerUsdc);
+    const nonce = new anchor.BN(1);
+    [jobPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("job"),
+        agentPda.toBuffer(),
+        payer.publicKey.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    [escrowPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("job_escrow"), jobPda.toBuffer()],
+      program.programId
+    );
+    deadline = (await clusterTime()) + 15;
+
+    await program.methods
+      .createJob(MILESTONES.map((amount) => new anchor.BN(amount)), serviceId, new anchor.BN(deadline), nonce)
+      .accounts({
+        agent: agentPda,
+        x402Config: x402ConfigPda,
+        job: jobPda,
+        escrowTokenAccount: escrowPda,
+        mint: usdcMint,
+        payer: payer.publicKey,
+        payerTokenAccount: payerUsdc,
+      })
+      .signers([payer])
+      .rpc();
+
+    const total = MILESTONES.reduce((sum, amount) => sum + amount, 0);
+    expect(await tokenBalance(provider.connection, escrowPda)).to.equal(total);
+    expect(await tokenBalance(provider.connection, payerUsdc)).to.equal(payerBefore - total);
+
+    const job = await fetchJob();
+    expect(job.milestones.map((m: any) => m.amount.toNumber())).to.deep.equal(MILESTONES);
+    expect(job.milestones.every((m: any) => "pending" in m.status)).to.be.true;
+    expect(job.reviewPeriodSecs.toNumber()).to.equal(REVIEW_PERIOD_SECS);
+  });
+
+  it("Releases a submitted milestone when the payer approves it", async () => {
+    try {
+      await releaseMilestone(0, true);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("InvalidMilestone");
+    }
+
+    await submitMilestone(0);
+    let job = await fetchJob();
+    expect(job.milestones[0].status).to.deep.equal({ submitted: {} });
+    expect(job.milestones[0].deliverableHash).to.deep.equal(deliverableHash);
+
+    // Others have to wait out the review period
+    try {
+      await releaseMilestone(0, false);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("ReviewPeriodActive");
+    }
+
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+    await releaseMilestone(0, true);
+
+    expect((await tokenBalance(provider.connection, creatorUsdc)) - creatorBefore).to.equal(MILESTONES[0]);
+    job = await fetchJob();
+    expect(job.milestones[0].status).to.deep.equal({ released: {} });
+    expect(job.released.toNumber()).to.equal(MILESTONES[0]);
+  });
+
+  it("Splits a disputed milestone as the council decides", async () => {
+    await submitMilestone(1);
+    await program.methods
+      .disputeMilestone(1, evidenceHash)
+      .accounts({ job: jobPda, payer: payer.publicKey })
+      .signers([payer])
+      .rpc();
+
+    let job = await fetchJob();
+    expect(job.milestones[1].status).to.deep.equal({ disputed: {} });
+    expect(job.evidenceHash).to.deep.equal(evidenceHash);
+
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+    const payerBefore = await tokenBalance(provider.connection, payerUsdc);
+
+    // Refund half to the payer
+    await program.methods
+      .voteMilestoneDispute(1, 5_000)
+      .accounts({
+        factory: factoryPda,
+        x402Config: x402ConfigPda,
+        job: jobPda,
+        escrowTokenAccount: escrowPda,
+        recipientTokenAccount: creatorUsdc,
+        payerTokenAccount: payerUsdc,
+        payer: payer.publicKey,
+        arbiter: authority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        revenueSplit: null,
+      })
+      .rpc();
+
+    const half = MILESTONES[1] / 2;
+    expect((await tokenBalance(provider.connection, creatorUsdc)) - creatorBefore).to.equal(half);
+    expect((await tokenBalance(provider.connection, payerUsdc)) - payerBefore).to.equal(half);
+    job = await fetchJob();
+    expect(job.milestones[1].status).to.deep.equal({ released: {} });
+    expect(job.milestones[1].refunded.toNumber()).to.equal(half);
+    expect(job.released.toNumber()).to.equal(MILESTONES[0] + half);
+    expect(job.refunded.toNumber()).to.equal(half);
+    expect(job.disputeVotes).to.have.length(0);
+  });
+
+  it("Keeps unsubmitted milestones locked until the deadline", async () => {
+    try {
+      await cancelJob();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("JobNotExpired");
+    }
+  });
+
+  it("Lets anyone release a milestone once its review period ends", async () => {
+    await submitMilestone(2);
+    await sleep((REVIEW_PERIOD_SECS + 1) * 1000);
+
+    // Too late to dispute it
+    try {
+      await program.methods
+        .disputeMilestone(2, evidenceHash)
+        .accounts({ job: jobPda, payer: payer.publicKey })
+        .signers([payer])
+        .rpc();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("ReviewPeriodEnded");
+    }
+
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+    await releaseMilestone(2, false);
+
+    expect((await tokenBalance(provider.connection, creatorUsdc)) - creatorBefore).to.equal(MILESTONES[2]);
+    const job = await fetchJob();
+    expect(job.milestones[2].status).to.deep.equal({ released: {} });
+  });
+
+  it("Refunds unsubmitted milestones after the deadline", async () => {
+    while ((await clusterTime()) <= deadline) {
+      await sleep(1000);
+    }
+
+    try {
+      await program.methods
+        .closeJob()
+        .accounts({ job: jobPda, payer: payer.publicKey })
+        .signers([payer])
+        .rpc();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("JobNotSettled");
+    }
+
+    const payerBefore = await tokenBalance(provider.connection, payerUsdc);
+    await cancelJob();
+
+    expect((await tokenBalance(provider.connection, payerUsdc)) - payerBefore).to.equal(MILESTONES[3]);
+    // The last milestone drained the escrow
+    expect(await provider.connection.getAccountInfo(escrowPda)).to.be.null;
+    const job = await fetchJob();
+    expect(job.milestones[3].status).to.deep.equal({ refunded: {} });
+    expect(job.refunded.toNumber()).to.equal(MILESTONES[1] / 2 + MILESTONES[3]);
+  });
+
+  it("Closes a settled job", async () => {
+    await program.methods
+      .closeJob()
+      .accounts({ job: jobPda, payer: payer.publicKey })
+      .signers([payer])
+      .rpc();
+
+    expect(await program.account.job.fetchNullable(jobPda)).to.be.null;
+  });
+});
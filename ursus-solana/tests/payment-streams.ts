@@ -0,0 +1,218 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  airdrop,
+  configureX402,
+  createTestAgent,
+  createUsdc,
+  sleep,
+  tokenBalance,
+} from "./helpers";
+
+describe("Payment streams", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts
+  const creator = Keypair.generate();
+  const payer = Keypair.generate();
+
+  let agentPda: PublicKey;
+  let x402ConfigPda: PublicKey;
+  let usdcMint: PublicKey;
+  let payerUsdc: PublicKey;
+  let creatorUsdc: PublicKey;
+  let streamPda: PublicKey;
+  let escrowPda: PublicKey;
+
+  const RATE_PER_SEC = 1_000;
+  const MAX_DURATION = 60;
+
+  // Start a stream at the next nonce; returns the stream and its escrow
+  const startStream = async (maxDuration: number) => {
+    const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
+    const nonce = x402Config.nonce.add(new anchor.BN(1));
+    const [stream] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_stream"),
+        agentPda.toBuffer(),
+        payer.publicKey.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [escrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stream_escrow"), stream.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .startStream(new anchor.BN(RATE_PER_SEC), new anchor.BN(maxDuration), nonce)
+      .accounts({
+        agent: agentPda,
+        x402Config: x402ConfigPda,
+        stream,
+        escrowTokenAccount: escrow,
+        mint: usdcMint,
+        payer: payer.publicKey,
+        payerTokenAccount: payerUsdc,
+      })
+      .signers([payer])
+      .rpc();
+
+    return { stream, escrow };
+  };
+
+  const withdrawStreamed = (stream: PublicKey, escrow: PublicKey) =>
+    program.methods
+      .withdrawStreamed()
+      .accounts({
+        x402Config: x402ConfigPda,
+        stream,
+        escrowTokenAccount: escrow,
+        recipientTokenAccount: creatorUsdc,
+        payer: payer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        revenueSplit: null,
+      })
+      .rpc();
+
+  const cancelStream = (stream: PublicKey, escrow: PublicKey) =>
+    program.methods
+      .cancelStream()
+      .accounts({
+        x402Config: x402ConfigPda,
+        stream,
+        escrowTokenAccount: escrow,
+        payerTokenAccount: payerUsdc,
+        payer: payer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([payer])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, payer], 10);
+
+    ({ agentPda, x402ConfigPda } = await createTestAgent(
+      program,
+      creator,
+      "Streaming Agent",
+      "STRM",
+      "Agent paid per second"
+    ));
+    await configureX402(program, agentPda, creator, 1, 0, 60);
+
+    ({ mint: usdcMint, accounts: [payerUsdc, creatorUsdc] } = await createUsdc(
+      provider.connection,
+      payer,
+      1_000_000,
+      [creator]
+    ));
+  });
+
+  it("Locks the whole deposit in escrow", async () => {
+    const payerBefore = await tokenBalance(provider.connection, payerUsdc);
+    ({ stream: streamPda, escrow: escrowPda } = await startStream(MAX_DURATION));
+
+    const deposit = RATE_PER_SEC * MAX_DURATION;
+    expect(await tokenBalance(provider.connection, escrowPda)).to.equal(deposit);
+    expect(await tokenBalance(provider.connection, payerUsdc)).to.equal(payerBefore - deposit);
+
+    const stream: any = await program.account.paymentStream.fetch(streamPda);
+    expect(stream.payer.toBase58()).to.equal(payer.publicKey.toBase58());
+    expect(stream.deposited.toNumber()).to.equal(deposit);
+    expect(stream.endTs.sub(stream.startTs).toNumber()).to.equal(MAX_DURATION);
+    expect(stream.withdrawn.toNumber()).to.equal(0);
+  });
+
+  it("Rejects streams beyond the maximum duration", async () => {
+    try {
+      await startStream(365 * 24 * 60 * 60 + 1);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("InvalidStreamParams");
+    }
+  });
+
+  it("Pays the provider what has streamed so far", async () => {
+    await sleep(2000);
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+
+    await withdrawStreamed(streamPda, escrowPda);
+
+    const stream: any = await program.account.paymentStream.fetch(streamPda);
+    const withdrawn = stream.withdrawn.toNumber();
+    expect(withdrawn).to.be.greaterThan(0);
+    expect(withdrawn % RATE_PER_SEC).to.equal(0);
+    expect(withdrawn).to.be.lessThan(stream.deposited.toNumber());
+    expect((await tokenBalance(provider.connection, creatorUsdc)) - creatorBefore).to.equal(withdrawn);
+    expect(await tokenBalance(provider.connection, escrowPda)).to.equal(stream.deposited.toNumber() - withdrawn);
+  });
+
+  it("Refunds the unstreamed remainder on cancel", async () => {
+    await sleep(2000);
+    const payerBefore = await tokenBalance(provider.connection, payerUsdc);
+
+    await cancelStream(streamPda, escrowPda);
+
+    const stream: any = await program.account.paymentStream.fetch(streamPda);
+    const refunded = stream.refunded.toNumber();
+    const streamed = stream.deposited.toNumber() - refunded;
+    expect(stream.cancelledAt.toNumber()).to.be.greaterThan(0);
+    expect(stream.endTs.toNumber()).to.equal(stream.cancelledAt.toNumber());
+    expect(streamed).to.equal(RATE_PER_SEC * stream.endTs.sub(stream.startTs).toNumber());
+    expect((await tokenBalance(provider.connection, payerUsdc)) - payerBefore).to.equal(refunded);
+    // What streamed before the cancel stays owed to the provider
+    expect(await tokenBalance(provider.connection, escrowPda)).to.equal(streamed - stream.withdrawn.toNumber());
+
+    try {
+      await cancelStream(streamPda, escrowPda);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("StreamCancelled");
+    }
+  });
+
+  it("Lets the provider withdraw the rest after a cancel", async () => {
+    const owed = await tokenBalance(provider.connection, escrowPda);
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+    await sleep(2000);
+
+    await withdrawStreamed(streamPda, escrowPda);
+
+    // Nothing streams after the cancel, and the drained escrow is closed
+    expect((await tokenBalance(provider.connection, creatorUsdc)) - creatorBefore).to.equal(owed);
+    expect(await provider.connection.getAccountInfo(escrowPda)).to.be.null;
+    const stream: any = await program.account.paymentStream.fetch(streamPda);
+    expect(stream.withdrawn.add(stream.refunded).toNumber()).to.equal(stream.deposited.toNumber());
+  });
+
+  it("Streams the whole deposit once the duration runs out", async () => {
+    const duration = 2;
+    const { stream, escrow } = await startStream(duration);
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+    await sleep((duration + 1) * 1000);
+
+    try {
+      await cancelStream(stream, escrow);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("StreamEnded");
+    }
+
+    await withdrawStreamed(stream, escrow);
+
+    expect((await tokenBalance(provider.connection, creatorUsdc)) - creatorBefore).to.equal(RATE_PER_SEC * duration);
+    expect(await provider.connection.getAccountInfo(escrow)).to.be.null;
+    const account: any = await program.account.paymentStream.fetch(stream);
+    expect(account.withdrawn.toNumber()).to.equal(RATE_PER_SEC * duration);
+    expect(account.refunded.toNumber()).to.equal(0);
+  });
+});
@@ -0,0 +1,215 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAssociatedTokenAccount } from "@solana/spl-token";
+import { expect } from "chai";
+import { ensureFactory, nameReservationPda, ownershipAccount } from "./helpers";
+
+describe("Presale", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts
+  const authority = provider.wallet as anchor.Wallet;
+  const creator = Keypair.generate();
+  const alice = Keypair.generate();
+  const bob = Keypair.generate();
+  let platformTreasury: PublicKey;
+
+  let factoryPda: PublicKey;
+  let agentPda: PublicKey;
+  let mintPda: PublicKey;
+  let presalePda: PublicKey;
+
+  const WINDOW_SECS = 4;
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  const commitmentPda = (committer: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("commitment"), presalePda.toBuffer(), committer.toBuffer()],
+      program.programId
+    )[0];
+
+  const commit = (committer: Keypair, lamports: number) =>
+    program.methods
+      .commitSol(new anchor.BN(lamports))
+      .accounts({
+        presale: presalePda,
+        commitment: commitmentPda(committer.publicKey),
+        committer: committer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([committer])
+      .rpc();
+
+  before(async () => {
+    for (const wallet of [creator, alice, bob]) {
+      const sig = await provider.connection.requestAirdrop(wallet.publicKey, 20 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig, "confirmed");
+    }
+
+    ({ factoryPda, platformTreasury } = await ensureFactory(program));
+
+    const factory: any = await program.account.agentFactory.fetch(factoryPda);
+    [agentPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("agent"), factory.totalAgents.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [mintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint"), agentPda.toBuffer()],
+      program.programId
+    );
+    [presalePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("presale"), agentPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .createAgent("Presale Agent", "PRE", "Agent launched by presale", "", "gpt-4", ["launch"])
+      .accounts({
+        factory: factoryPda,
+        agent: agentPda,
+        mint: mintPda,
+        creator: creator.publicKey,
+        platformTreasury,
+        nameReservation: nameReservationPda(program.programId, "Presale Agent"),
+        symbolReservation: nameReservationPda(program.programId, "PRE"),
+      })
+      .signers([creator])
+      .rpc();
+  });
+
+  it("Opens a presale and pauses trading", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createPresale(
+        new anchor.BN(now - 60),
+        new anchor.BN(now + WINDOW_SECS),
+        new anchor.BN(2 * LAMPORTS_PER_SOL),
+        new anchor.BN(4 * LAMPORTS_PER_SOL)
+      )
+      .accounts({
+        agent: agentPda,
+        presale: presalePda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    expect(agent.isPresaleActive).to.be.true;
+
+    const buyerTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      alice,
+      mintPda,
+      alice.publicKey
+    );
+    try {
+      await program.methods
+        .buyTokens(new anchor.BN(LAMPORTS_PER_SOL), new anchor.BN(0))
+        .accounts({
+          agent: agentPda,
+          mint: mintPda,
+          recipientTokenAccount: buyerTokenAccount,
+          buyer: alice.publicKey,
+          recipient: alice.publicKey,
+          creator: creator.publicKey,
+          platformTreasury,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([alice])
+        .rpc();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("PresaleActive");
+    }
+  });
+
+  it("Collects commitments beyond the hard cap", async () => {
+    await commit(alice, 3 * LAMPORTS_PER_SOL);
+    await commit(alice, 3 * LAMPORTS_PER_SOL);
+    await commit(bob, 2 * LAMPORTS_PER_SOL);
+
+    const presale: any = await program.account.presale.fetch(presalePda);
+    expect(presale.totalCommitted.toNumber()).to.equal(8 * LAMPORTS_PER_SOL);
+    expect(presale.committerCount.toNumber()).to.equal(2);
+  });
+
+  it("Finalizes at a uniform clearing price", async () => {
+    await sleep((WINDOW_SECS + 1) * 1000);
+
+    await program.methods
+      .finalizePresale()
+      .accounts({
+        factory: factoryPda,
+        presale: presalePda,
+        agent: agentPda,
+        creator: creator.publicKey,
+        platformTreasury,
+        cranker: authority.publicKey,
+      })
+      .rpc();
+
+    const presale: any = await program.account.presale.fetch(presalePda);
+    const agent: any = await program.account.agent.fetch(agentPda);
+    expect(presale.isSuccessful).to.be.true;
+    expect(presale.solSpent.toNumber()).to.equal(4 * LAMPORTS_PER_SOL);
+    expect(agent.isPresaleActive).to.be.false;
+    expect(agent.bondingCurve.realSolReserves.gt(new anchor.BN(0))).to.be.true;
+  });
+
+  it("Allocates tokens and refunds the excess pro rata", async () => {
+    const presale: any = await program.account.presale.fetch(presalePda);
+    // Alice's token account was created by the rejected buy above
+    await createAssociatedTokenAccount(provider.connection, bob, mintPda, bob.publicKey);
+
+    for (const committer of [alice, bob]) {
+      await program.methods
+        .claimPresale()
+        .accounts({
+          presale: presalePda,
+          commitment: commitmentPda(committer.publicKey),
+          agent: agentPda,
+          mint: mintPda,
+          committerTokenAccount: anchor.utils.token.associatedAddress({
+            mint: mintPda,
+            owner: committer.publicKey,
+          }),
+          committer: committer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([committer])
+        .rpc();
+    }
+
+    const balance = async (owner: PublicKey) =>
+      new anchor.BN(
+        (
+          await provider.connection.getTokenAccountBalance(
+            anchor.utils.token.associatedAddress({ mint: mintPda, owner })
+          )
+        ).value.amount
+      );
+
+    // Alice committed 6 of 8 SOL, so she gets 3/4 of the allocation
+    const allocated = presale.tokensAllocated;
+    expect((await balance(alice.publicKey)).toString()).to.equal(
+      allocated.muln(3).divn(4).toString()
+    );
+    expect((await balance(bob.publicKey)).toString()).to.equal(
+      allocated.divn(4).toString()
+    );
+
+    const closed = await program.account.presaleCommitment.fetchNullable(
+      commitmentPda(alice.publicKey)
+    );
+    expect(closed).to.be.null;
+  });
+});
@@ -0,0 +1,327 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createHash } from "crypto";
+import { expect } from "chai";
+import {
+  airdrop,
+  configureX402,
+  createTestAgent,
+  createWsol,
+  ownershipAccount,
+  setChargebackWindow,
+} from "./helpers";
+
+describe("Provider bonds and disputes", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts; the factory authority is the default arbitration council
+  const authority = provider.wallet as anchor.Wallet;
+  const creator = Keypair.generate();
+  const payer = Keypair.generate();
+
+  let factoryPda: PublicKey;
+  let agentPda: PublicKey;
+  let x402ConfigPda: PublicKey;
+  let providerBondPda: PublicKey;
+  let payerWsol: PublicKey;
+  let creatorWsol: PublicKey;
+  let paymentRecord: PublicKey;
+  let disputePda: PublicKey;
+  let dismissedRecord: PublicKey;
+
+  const PRICE = 1_000_000; // 0.001 wrapped SOL
+  const CHARGEBACK_WINDOW_SECS = 14 * 24 * 60 * 60;
+  const BOND = LAMPORTS_PER_SOL / 2;
+  const CLAIM = 800_000;
+  const AWARD = 600_000;
+  const serviceId = Array.from(createHash("sha256").update("market_analysis").digest());
+  const requestId = Array.from({ length: 16 }, (_, i) => i + 1);
+  const evidenceHash = Array.from(createHash("sha256").update("evidence").digest());
+
+  const lamports = (address: PublicKey) => provider.connection.getBalance(address);
+
+  const withdrawBond = (amount: number) =>
+    program.methods
+      .withdrawProviderBond(new anchor.BN(amount))
+      .accounts({
+        agent: agentPda,
+        providerBond: providerBondPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+      })
+      .signers([creator])
+      .rpc();
+
+  const disputePdaFor = (record: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("dispute"), record.toBuffer()],
+      program.programId
+    )[0];
+
+  // Pay the agent at the next nonce; returns the payment record
+  const payForService = async () => {
+    const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
+    const nonce = x402Config.nonce.add(new anchor.BN(1));
+    const [record] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_record"),
+        agentPda.toBuffer(),
+        payer.publicKey.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    await program.methods
+      .payForService(new anchor.BN(PRICE), serviceId, nonce, requestId, new anchor.BN(0))
+      .accounts({
+        agent: agentPda,
+        x402Config: x402ConfigPda,
+        paymentRecord: record,
+        payer: payer.publicKey,
+        payerTokenAccount: payerWsol,
+        recipientTokenAccount: creatorWsol,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        factory: null,
+        platformStake: null,
+        quote: null,
+        affiliate: null,
+        affiliateTokenAccount: null,
+        revenueSplit: null,
+        consumerStats: null,
+      })
+      .signers([payer])
+      .rpc();
+    return record;
+  };
+
+  const submitEvidence = (record: PublicKey, claimant: Keypair, claim = CLAIM) =>
+    program.methods
+      .submitEvidence(evidenceHash, new anchor.BN(claim))
+      .accounts({
+        factory: factoryPda,
+        paymentRecord: record,
+        providerBond: providerBondPda,
+        dispute: disputePdaFor(record),
+        claimant: claimant.publicKey,
+        payerAgent: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([claimant])
+      .rpc();
+
+  const vote = (slashAmount: number, record = paymentRecord, arbiter?: Keypair) =>
+    program.methods
+      .castArbiterVote(new anchor.BN(slashAmount))
+      .accounts({
+        factory: factoryPda,
+        dispute: disputePdaFor(record),
+        providerBond: providerBondPda,
+        claimant: payer.publicKey,
+        arbiter: arbiter ? arbiter.publicKey : authority.publicKey,
+      })
+      .signers(arbiter ? [arbiter] : [])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, payer], 10);
+
+    ({ factoryPda, agentPda, x402ConfigPda } = await createTestAgent(
+      program,
+      creator,
+      "Bonded Agent",
+      "BOND",
+      "Agent backed by a provider bond"
+    ));
+    [providerBondPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("provider_bond"), agentPda.toBuffer()],
+      program.programId
+    );
+    await configureX402(program, agentPda, creator, PRICE, 10 * PRICE, 60);
+    await setChargebackWindow(program, agentPda, creator, CHARGEBACK_WINDOW_SECS);
+
+    ({ accounts: [payerWsol, creatorWsol] } = await createWsol(
+      provider.connection,
+      payer,
+      100 * PRICE,
+      [creator]
+    ));
+
+    // A settled payment to dispute
+    paymentRecord = await payForService();
+    disputePda = disputePdaFor(paymentRecord);
+  });
+
+  it("Deposits the provider bond", async () => {
+    await program.methods
+      .depositProviderBond(new anchor.BN(BOND))
+      .accounts({
+        agent: agentPda,
+        providerBond: providerBondPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+
+    const bond: any = await program.account.providerBond.fetch(providerBondPda);
+    expect(bond.agent.toBase58()).to.equal(agentPda.toBase58());
+    expect(bond.amount.toNumber()).to.equal(BOND);
+    expect(bond.openDisputes).to.equal(0);
+  });
+
+  it("Only lets the payer dispute a payment", async () => {
+    try {
+      await submitEvidence(paymentRecord, creator);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("NotPaymentPayer");
+    }
+  });
+
+  it("Rejects a claim above the payment", async () => {
+    try {
+      await submitEvidence(paymentRecord, payer, PRICE + 1);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("ClaimExceedsPayment");
+    }
+  });
+
+  it("Opens a dispute with the payer's evidence and locks the bond", async () => {
+    await submitEvidence(paymentRecord, payer);
+
+    const dispute: any = await program.account.dispute.fetch(disputePda);
+    expect(dispute.paymentRecord.toBase58()).to.equal(paymentRecord.toBase58());
+    expect(dispute.agent.toBase58()).to.equal(agentPda.toBase58());
+    expect(dispute.claimant.toBase58()).to.equal(payer.publicKey.toBase58());
+    expect(dispute.evidenceHash).to.deep.equal(evidenceHash);
+    expect(dispute.claimedAmount.toNumber()).to.equal(CLAIM);
+    expect(dispute.status).to.deep.equal({ open: {} });
+
+    const bond: any = await program.account.providerBond.fetch(providerBondPda);
+    expect(bond.openDisputes).to.equal(1);
+
+    try {
+      await withdrawBond(BOND);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("BondLocked");
+    }
+  });
+
+  it("Rejects an award above the claim", async () => {
+    try {
+      await vote(CLAIM + 1);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("AwardExceedsClaim");
+    }
+
+    const dispute: any = await program.account.dispute.fetch(disputePda);
+    expect(dispute.status).to.deep.equal({ open: {} });
+    expect(dispute.votes).to.have.length(0);
+  });
+
+  it("Slashes the award from the bond to the claimant", async () => {
+    const bondLamportsBefore = await lamports(providerBondPda);
+    const claimantBefore = await lamports(payer.publicKey);
+
+    await vote(AWARD);
+
+    const dispute: any = await program.account.dispute.fetch(disputePda);
+    expect(dispute.status).to.deep.equal({ slashed: {} });
+    expect(dispute.awardedAmount.toNumber()).to.equal(AWARD);
+    expect(dispute.slashedAmount.toNumber()).to.equal(AWARD);
+    expect(dispute.resolvedAt.toNumber()).to.be.greaterThan(0);
+
+    const bond: any = await program.account.providerBond.fetch(providerBondPda);
+    expect(bond.amount.toNumber()).to.equal(BOND - AWARD);
+    expect(bond.totalSlashed.toNumber()).to.equal(AWARD);
+    expect(bond.openDisputes).to.equal(0);
+    expect(bondLamportsBefore - (await lamports(providerBondPda))).to.equal(AWARD);
+    expect((await lamports(payer.publicKey)) - claimantBefore).to.equal(AWARD);
+  });
+
+  it("Unlocks the rest of the bond once the dispute resolves", async () => {
+    const creatorBefore = await lamports(creator.publicKey);
+    const bondLamportsBefore = await lamports(providerBondPda);
+
+    await withdrawBond(BOND - AWARD);
+
+    const bond: any = await program.account.providerBond.fetch(providerBondPda);
+    expect(bond.amount.toNumber()).to.equal(0);
+    expect(bondLamportsBefore - (await lamports(providerBondPda))).to.equal(BOND - AWARD);
+    // The creator also pays the transaction fee
+    expect((await lamports(creator.publicKey)) - creatorBefore).to.be.greaterThan(BOND - AWARD - 10_000);
+  });
+
+  it("Only lets council members vote", async () => {
+    dismissedRecord = await payForService();
+    await submitEvidence(dismissedRecord, payer);
+
+    try {
+      await vote(AWARD, dismissedRecord, creator);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("NotArbiter");
+    }
+
+    const dispute: any = await program.account.dispute.fetch(disputePdaFor(dismissedRecord));
+    expect(dispute.status).to.deep.equal({ open: {} });
+    expect(dispute.votes).to.have.length(0);
+  });
+
+  it("Dismisses a dispute the council votes down", async () => {
+    const claimantBefore = await lamports(payer.publicKey);
+
+    await vote(0, dismissedRecord);
+
+    const dispute: any = await program.account.dispute.fetch(disputePdaFor(dismissedRecord));
+    expect(dispute.status).to.deep.equal({ dismissed: {} });
+    expect(dispute.awardedAmount.toNumber()).to.equal(0);
+    expect(dispute.slashedAmount.toNumber()).to.equal(0);
+    expect(dispute.votes).to.have.length(1);
+
+    const bond: any = await program.account.providerBond.fetch(providerBondPda);
+    expect(bond.openDisputes).to.equal(0);
+    expect(bond.totalSlashed.toNumber()).to.equal(AWARD);
+    expect(await lamports(payer.publicKey)).to.equal(claimantBefore);
+
+    try {
+      await vote(AWARD, dismissedRecord);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("DisputeNotOpen");
+    }
+  });
+
+  it("Settles payments final when the chargeback window is 0", async () => {
+    await setChargebackWindow(program, agentPda, creator, 0);
+    const record = await payForService();
+
+    const payment: any = await program.account.x402PaymentRecord.fetch(record);
+    expect(payment.disputeDeadline.toNumber()).to.equal(0);
+
+    try {
+      await submitEvidence(record, payer);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("ChargebackWindowClosed");
+    }
+
+    await program.methods
+      .finalizePayment()
+      .accounts({ paymentRecord: record, dispute: disputePdaFor(record) })
+      .rpc();
+    const finalized: any = await program.account.x402PaymentRecord.fetch(record);
+    expect(finalized.finalizedAt.toNumber()).to.be.greaterThan(0);
+  });
+});
@@ -0,0 +1,146 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAssociatedTokenAccount, transfer } from "@solana/spl-token";
+import { expect } from "chai";
+import { airdrop, createTestAgent, ownershipAccount, sleep, tokenBalance } from "./helpers";
+
+describe("Same-slot sell protection", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts; the accomplice sells tokens the trader moved to them
+  const creator = Keypair.generate();
+  const trader = Keypair.generate();
+  const accomplice = Keypair.generate();
+  let platformTreasury: PublicKey;
+
+  let factoryPda: PublicKey;
+  let agentPda: PublicKey;
+  let mintPda: PublicKey;
+  let traderTokenAccount: PublicKey;
+  let accompliceTokenAccount: PublicKey;
+
+  const BUY_AMOUNT = 100_000_000; // 0.1 SOL
+
+  const buy = () =>
+    program.methods
+      .buyTokens(new anchor.BN(BUY_AMOUNT), new anchor.BN(0))
+      .accounts({
+        agent: agentPda,
+        mint: mintPda,
+        recipientTokenAccount: traderTokenAccount,
+        buyer: trader.publicKey,
+        recipient: trader.publicKey,
+        creator: creator.publicKey,
+        platformTreasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        factory: factoryPda,
+        platformStake: null,
+        priceHistory: null,
+        trending: null,
+      })
+      .signers([trader]);
+
+  const sell = (amount: number) =>
+    program.methods
+      .sellTokens(new anchor.BN(amount), new anchor.BN(0))
+      .accounts({
+        agent: agentPda,
+        mint: mintPda,
+        sellerTokenAccount: accompliceTokenAccount,
+        seller: accomplice.publicKey,
+        creator: creator.publicKey,
+        platformTreasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        factory: factoryPda,
+        platformStake: null,
+        priceHistory: null,
+        trending: null,
+      })
+      .signers([accomplice]);
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, trader, accomplice], 10);
+    ({ factoryPda, platformTreasury, agentPda, mintPda } = await createTestAgent(
+      program,
+      creator,
+      "Guarded Agent",
+      "GUARD",
+      "Agent with same-slot sell protection"
+    ));
+
+    traderTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      trader,
+      mintPda,
+      trader.publicKey
+    );
+    accompliceTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      accomplice,
+      mintPda,
+      accomplice.publicKey
+    );
+
+    // Tokens bought earlier and moved to a wallet that never bought
+    await buy().rpc();
+    await transfer(
+      provider.connection,
+      trader,
+      traderTokenAccount,
+      accompliceTokenAccount,
+      trader,
+      await tokenBalance(provider.connection, traderTokenAccount)
+    );
+
+    await program.methods
+      .setSameSlotProtection(true)
+      .accounts({
+        agent: agentPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+      })
+      .signers([creator])
+      .rpc();
+  });
+
+  it("Rejects a sell bundled after a buy, even from another wallet", async () => {
+    const balance = await tokenBalance(provider.connection, accompliceTokenAccount);
+
+    try {
+      await buy()
+        .postInstructions([await sell(Math.floor(balance / 2)).instruction()])
+        .signers([trader, accomplice])
+        .rpc();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("SameSlotSell");
+    }
+    expect(await tokenBalance(provider.connection, accompliceTokenAccount)).to.equal(balance);
+  });
+
+  it("Records the slot of the latest buy on the agent", async () => {
+    const signature = await buy().rpc();
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    expect(agent.sameSlotProtection).to.be.true;
+    expect(agent.lastBuySlot.toNumber()).to.equal(tx!.slot);
+  });
+
+  it("Lets any wallet sell in a later slot", async () => {
+    await sleep(1000);
+    const balance = await tokenBalance(provider.connection, accompliceTokenAccount);
+    await sell(Math.floor(balance / 2)).rpc();
+    expect(await tokenBalance(provider.connection, accompliceTokenAccount)).to.be.below(balance);
+  });
+});
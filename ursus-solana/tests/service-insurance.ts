@@ -0,0 +1,310 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createHash } from "crypto";
+import { expect } from "chai";
+import {
+  airdrop,
+  configureX402,
+  createTestAgent,
+  createWsol,
+  sleep,
+  ownershipAccount,
+  setChargebackWindow,
+} from "./helpers";
+
+describe("Service insurance", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts; the factory authority is the default arbitration council
+  const authority = provider.wallet as anchor.Wallet;
+  const creator = Keypair.generate();
+  const payer = Keypair.generate();
+
+  let factoryPda: PublicKey;
+  let agentPda: PublicKey;
+  let x402ConfigPda: PublicKey;
+  let providerBondPda: PublicKey;
+  let insuranceFundPda: PublicKey;
+  let payerWsol: PublicKey;
+  let creatorWsol: PublicKey;
+  let paymentRecord: PublicKey;
+
+  const PRICE = 1_000_000; // 0.001 wrapped SOL
+  const CHARGEBACK_WINDOW_SECS = 14 * 24 * 60 * 60;
+  const BOND = 200_000;
+  const AWARD = 600_000;
+  const COVERAGE = 500_000;
+  const INSURANCE_PREMIUM_BPS = 500;
+  const serviceId = Array.from(createHash("sha256").update("market_analysis").digest());
+  const requestId = Array.from({ length: 16 }, (_, i) => i + 1);
+  const evidenceHash = Array.from(createHash("sha256").update("evidence").digest());
+
+  const recordPda = (seed: string, record: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from(seed), record.toBuffer()],
+      program.programId
+    )[0];
+
+  const lamports = (address: PublicKey) => provider.connection.getBalance(address);
+
+  // Payment at the next nonce, not yet sent
+  const payment = async () => {
+    const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
+    const nonce = x402Config.nonce.add(new anchor.BN(1));
+    const [record] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_record"),
+        agentPda.toBuffer(),
+        payer.publicKey.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const builder = program.methods
+      .payForService(new anchor.BN(PRICE), serviceId, nonce, requestId, new anchor.BN(0))
+      .accounts({
+        agent: agentPda,
+        x402Config: x402ConfigPda,
+        paymentRecord: record,
+        payer: payer.publicKey,
+        payerTokenAccount: payerWsol,
+        recipientTokenAccount: creatorWsol,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        factory: null,
+        platformStake: null,
+        quote: null,
+        affiliate: null,
+        affiliateTokenAccount: null,
+        revenueSplit: null,
+        consumerStats: null,
+      })
+      .signers([payer]);
+    return { record, builder };
+  };
+
+  const insure = (record: PublicKey, coverage = COVERAGE) =>
+    program.methods
+      .insurePayment(new anchor.BN(coverage))
+      .accounts({
+        factory: factoryPda,
+        paymentRecord: record,
+        insuranceFund: insuranceFundPda,
+        insurancePolicy: recordPda("insurance_policy", record),
+        holder: payer.publicKey,
+        payerAgent: null,
+        systemProgram: SystemProgram.programId,
+      });
+
+  const payClaim = () =>
+    program.methods
+      .payInsuredClaim()
+      .accounts({
+        insuranceFund: insuranceFundPda,
+        dispute: recordPda("dispute", paymentRecord),
+        insurancePolicy: recordPda("insurance_policy", paymentRecord),
+        claimant: payer.publicKey,
+      })
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, payer], 10);
+
+    ({ factoryPda, agentPda, x402ConfigPda } = await createTestAgent(
+      program,
+      creator,
+      "Insured Agent",
+      "INSRD",
+      "Agent with insured payments"
+    ));
+    [insuranceFundPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("insurance_fund")],
+      program.programId
+    );
+    [providerBondPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("provider_bond"), agentPda.toBuffer()],
+      program.programId
+    );
+
+    // The insurance fund is shared with the other suites too
+    if (!(await program.account.insuranceFund.fetchNullable(insuranceFundPda))) {
+      await program.methods
+        .initializeInsuranceFund()
+        .accounts({
+          factory: factoryPda,
+          insuranceFund: insuranceFundPda,
+          authority: authority.publicKey,
+          payer: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    await configureX402(program, agentPda, creator, PRICE, 10 * PRICE, 60);
+    await setChargebackWindow(program, agentPda, creator, CHARGEBACK_WINDOW_SECS);
+
+    // A bond too small to cover the award on its own
+    await program.methods
+      .depositProviderBond(new anchor.BN(BOND))
+      .accounts({
+        agent: agentPda,
+        providerBond: providerBondPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+
+    ({ accounts: [payerWsol, creatorWsol] } = await createWsol(
+      provider.connection,
+      payer,
+      100 * PRICE,
+      [creator]
+    ));
+  });
+
+  it("Takes deposits into the insurance fund", async () => {
+    const fundBefore: any = await program.account.insuranceFund.fetch(insuranceFundPda);
+    const lamportsBefore = await lamports(insuranceFundPda);
+
+    await program.methods
+      .fundInsurance(new anchor.BN(LAMPORTS_PER_SOL))
+      .accounts({
+        insuranceFund: insuranceFundPda,
+        funder: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const fund: any = await program.account.insuranceFund.fetch(insuranceFundPda);
+    expect(fund.totalDeposits.sub(fundBefore.totalDeposits).toNumber()).to.equal(LAMPORTS_PER_SOL);
+    expect((await lamports(insuranceFundPda)) - lamportsBefore).to.equal(LAMPORTS_PER_SOL);
+  });
+
+  it("Insures a payment bundled with it for the premium", async () => {
+    const fundBefore: any = await program.account.insuranceFund.fetch(insuranceFundPda);
+    const lamportsBefore = await lamports(insuranceFundPda);
+
+    const { record, builder } = await payment();
+    await builder.postInstructions([await insure(record).instruction()]).rpc();
+    paymentRecord = record;
+
+    const premium = Math.ceil((COVERAGE * INSURANCE_PREMIUM_BPS) / 10_000);
+    const policy: any = await program.account.insurancePolicy.fetch(
+      recordPda("insurance_policy", record)
+    );
+    expect(policy.paymentRecord.toBase58()).to.equal(record.toBase58());
+    expect(policy.holder.toBase58()).to.equal(payer.publicKey.toBase58());
+    expect(policy.coverage.toNumber()).to.equal(COVERAGE);
+    expect(policy.premium.toNumber()).to.equal(premium);
+    expect(policy.claimedAmount.toNumber()).to.equal(0);
+
+    const fund: any = await program.account.insuranceFund.fetch(insuranceFundPda);
+    expect(fund.totalPremiums.sub(fundBefore.totalPremiums).toNumber()).to.equal(premium);
+    expect((await lamports(insuranceFundPda)) - lamportsBefore).to.equal(premium);
+  });
+
+  it("Rejects coverage above the payment", async () => {
+    const { record, builder } = await payment();
+
+    try {
+      await builder.postInstructions([await insure(record, PRICE + 1).instruction()]).rpc();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("CoverageExceedsPayment");
+    }
+  });
+
+  it("Rejects insuring a payment after the fact", async () => {
+    const { record, builder } = await payment();
+    await builder.rpc();
+    await sleep(2000);
+
+    try {
+      await insure(record).signers([payer]).rpc();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("InsuranceWindowClosed");
+    }
+  });
+
+  it("Pays nothing until the dispute is upheld", async () => {
+    await program.methods
+      .submitEvidence(evidenceHash, new anchor.BN(AWARD))
+      .accounts({
+        factory: factoryPda,
+        paymentRecord,
+        providerBond: providerBondPda,
+        dispute: recordPda("dispute", paymentRecord),
+        claimant: payer.publicKey,
+        payerAgent: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([payer])
+      .rpc();
+
+    try {
+      await payClaim();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("NoInsuredShortfall");
+    }
+  });
+
+  it("Covers the part of the award the bond could not", async () => {
+    const claimantBefore = await lamports(payer.publicKey);
+
+    await program.methods
+      .castArbiterVote(new anchor.BN(AWARD))
+      .accounts({
+        factory: factoryPda,
+        dispute: recordPda("dispute", paymentRecord),
+        providerBond: providerBondPda,
+        claimant: payer.publicKey,
+        arbiter: authority.publicKey,
+      })
+      .rpc();
+
+    const dispute: any = await program.account.dispute.fetch(recordPda("dispute", paymentRecord));
+    expect(dispute.status).to.deep.equal({ slashed: {} });
+    expect(dispute.awardedAmount.toNumber()).to.equal(AWARD);
+    expect(dispute.slashedAmount.toNumber()).to.equal(BOND);
+    expect((await lamports(payer.publicKey)) - claimantBefore).to.equal(BOND);
+
+    const fundBefore: any = await program.account.insuranceFund.fetch(insuranceFundPda);
+    const lamportsBefore = await lamports(insuranceFundPda);
+
+    await payClaim();
+
+    // The shortfall fits within the coverage
+    const shortfall = AWARD - BOND;
+    expect(shortfall).to.be.lessThan(COVERAGE);
+    expect((await lamports(payer.publicKey)) - claimantBefore).to.equal(AWARD);
+    expect(lamportsBefore - (await lamports(insuranceFundPda))).to.equal(shortfall);
+
+    const policy: any = await program.account.insurancePolicy.fetch(
+      recordPda("insurance_policy", paymentRecord)
+    );
+    expect(policy.claimedAmount.toNumber()).to.equal(shortfall);
+
+    const fund: any = await program.account.insuranceFund.fetch(insuranceFundPda);
+    expect(fund.claimCount.sub(fundBefore.claimCount).toNumber()).to.equal(1);
+    expect(fund.totalClaimsPaid.sub(fundBefore.totalClaimsPaid).toNumber()).to.equal(shortfall);
+  });
+
+  it("Pays each policy once", async () => {
+    try {
+      await payClaim();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("NoInsuredShortfall");
+    }
+  });
+});
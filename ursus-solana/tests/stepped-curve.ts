@@ -0,0 +1,208 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID, createAssociatedTokenAccount } from "@solana/spl-token";
+import { expect } from "chai";
+import { ensureFactory, nameReservationPda } from "./helpers";
+
+describe("Stepped bonding curve", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts
+  const creator = Keypair.generate();
+  const buyer = Keypair.generate();
+  let platformTreasury: PublicKey;
+
+  let factoryPda: PublicKey;
+  let agentPda: PublicKey;
+  let mintPda: PublicKey;
+  let buyerTokenAccount: PublicKey;
+
+  const TOKEN = new anchor.BN(1_000_000_000); // 9 decimals
+  // Flat 200M token community phase at 28 lamports/token, then a 600M token
+  // discovery phase rising 200 nano-lamports per token sold
+  const TRANCHES = [
+    {
+      supply: new anchor.BN(200_000_000).mul(TOKEN),
+      startPrice: new anchor.BN(28_000_000_000),
+      slope: new anchor.BN(0),
+    },
+    {
+      supply: new anchor.BN(600_000_000).mul(TOKEN),
+      startPrice: new anchor.BN(28_000_000_000),
+      slope: new anchor.BN(200),
+    },
+  ];
+
+  const agentAddresses = async () => {
+    const factory: any = await program.account.agentFactory.fetch(factoryPda);
+    const [agent] = PublicKey.findProgramAddressSync(
+      [Buffer.from("agent"), factory.totalAgents.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [mint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint"), agent.toBuffer()],
+      program.programId
+    );
+    return { agent, mint };
+  };
+
+  before(async () => {
+    for (const wallet of [creator, buyer]) {
+      const sig = await provider.connection.requestAirdrop(wallet.publicKey, 10 * LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig, "confirmed");
+    }
+
+    ({ factoryPda, platformTreasury } = await ensureFactory(program));
+  });
+
+  it("Rejects tranches that don't cover the bonding curve supply", async () => {
+    const { agent, mint } = await agentAddresses();
+
+    try {
+      await program.methods
+        .createAgentWithCurve("Bad Curve", "BAD", "", "", "gpt-4", [], [TRANCHES[0]])
+        .accounts({
+          factory: factoryPda,
+          agent,
+          mint,
+          creator: creator.publicKey,
+          platformTreasury,
+          nameReservation: nameReservationPda(program.programId, "Bad Curve"),
+          symbolReservation: nameReservationPda(program.programId, "BAD"),
+        })
+        .signers([creator])
+        .rpc();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("InvalidCurveConfig");
+    }
+  });
+
+  it("Creates an agent with a stepped curve", async () => {
+    ({ agent: agentPda, mint: mintPda } = await agentAddresses());
+
+    await program.methods
+      .createAgentWithCurve(
+        "Stepped Agent",
+        "STEP",
+        "Agent launched on a two-phase curve",
+        "You are a helpful assistant",
+        "gpt-4",
+        ["launch"],
+        TRANCHES
+      )
+      .accounts({
+        factory: factoryPda,
+        agent: agentPda,
+        mint: mintPda,
+        creator: creator.publicKey,
+        platformTreasury,
+        nameReservation: nameReservationPda(program.programId, "Stepped Agent"),
+        symbolReservation: nameReservationPda(program.programId, "STEP"),
+      })
+      .signers([creator])
+      .rpc();
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    const stepped = agent.bondingCurve.curveType.stepped;
+    expect(stepped).to.not.be.undefined;
+    expect(stepped.trancheCount).to.equal(2);
+    expect(stepped.tranches[1].slope.toString()).to.equal("200");
+  });
+
+  it("Sells the community phase at a flat price", async () => {
+    buyerTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      buyer,
+      mintPda,
+      buyer.publicKey
+    );
+
+    const buy = async (lamports: number) => {
+      const before = await provider.connection.getTokenAccountBalance(buyerTokenAccount);
+      await program.methods
+        .buyTokens(new anchor.BN(lamports), new anchor.BN(0))
+        .accounts({
+          agent: agentPda,
+          mint: mintPda,
+          recipientTokenAccount: buyerTokenAccount,
+          buyer: buyer.publicKey,
+          recipient: buyer.publicKey,
+          creator: creator.publicKey,
+          platformTreasury,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      const after = await provider.connection.getTokenAccountBalance(buyerTokenAccount);
+      return new anchor.BN(after.value.amount).sub(new anchor.BN(before.value.amount));
+    };
+
+    // 0.98 SOL reaches the reserves after fees: 0.98e9 / 28 = 35M tokens
+    const first = await buy(LAMPORTS_PER_SOL);
+    const second = await buy(LAMPORTS_PER_SOL);
+    expect(first.toString()).to.equal(new anchor.BN(35_000_000).mul(TOKEN).toString());
+    expect(second.toString()).to.equal(first.toString());
+  });
+
+  it("Raises the price in the discovery phase", async () => {
+    const before = await provider.connection.getTokenAccountBalance(buyerTokenAccount);
+
+    // 4.9 SOL net: 3.64 SOL clears the remaining 130M flat tokens, the rest
+    // buys into the rising phase
+    await program.methods
+      .buyTokens(new anchor.BN(5 * LAMPORTS_PER_SOL), new anchor.BN(0))
+      .accounts({
+        agent: agentPda,
+        mint: mintPda,
+        recipientTokenAccount: buyerTokenAccount,
+        buyer: buyer.publicKey,
+        recipient: buyer.publicKey,
+        creator: creator.publicKey,
+        platformTreasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+
+    const after = await provider.connection.getTokenAccountBalance(buyerTokenAccount);
+    const received = new anchor.BN(after.value.amount).sub(new anchor.BN(before.value.amount));
+    const atFlatPrice = new anchor.BN(175_000_000).mul(TOKEN);
+    expect(received.gt(new anchor.BN(130_000_000).mul(TOKEN))).to.be.true;
+    expect(received.lt(atFlatPrice)).to.be.true;
+  });
+
+  it("Sells tokens back within the reserves", async () => {
+    const balance = await provider.connection.getTokenAccountBalance(buyerTokenAccount);
+    const agentBefore: any = await program.account.agent.fetch(agentPda);
+
+    await program.methods
+      .sellTokens(new anchor.BN(balance.value.amount), new anchor.BN(0))
+      .accounts({
+        agent: agentPda,
+        mint: mintPda,
+        sellerTokenAccount: buyerTokenAccount,
+        seller: buyer.publicKey,
+        creator: creator.publicKey,
+        platformTreasury,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+
+    const agent: any = await program.account.agent.fetch(agentPda);
+    expect(agent.bondingCurve.realTokenReserves.toString()).to.equal(
+      agent.bondingCurve.bondingCurveSupply.toString()
+    );
+    // Rounding always favours the curve, so selling everything leaves dust at most
+    expect(agent.bondingCurve.realSolReserves.lte(agentBefore.bondingCurve.realSolReserves)).to.be.true;
+  });
+});
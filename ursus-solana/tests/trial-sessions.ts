@@ -0,0 +1,190 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createHash } from "crypto";
+import { expect } from "chai";
+import {
+  airdrop,
+  configureX402,
+  createTestAgent,
+  createUsdc,
+  sleep,
+  tokenBalance,
+  ownershipAccount,
+} from "./helpers";
+
+describe("Trial sessions", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts
+  const creator = Keypair.generate();
+  const caller = Keypair.generate();
+
+  let agentPda: PublicKey;
+  let x402ConfigPda: PublicKey;
+  let sessionPda: PublicKey;
+  let escrowPda: PublicKey;
+  let usdcMint: PublicKey;
+  let callerUsdc: PublicKey;
+  let creatorUsdc: PublicKey;
+
+  const TRIAL_DEPOSIT = 50_000;
+  const SESSION_SECS = 3;
+  const serviceId = Array.from(createHash("sha256").update("market_analysis").digest());
+
+  const openTrial = () =>
+    program.methods
+      .openTrial(serviceId)
+      .accounts({
+        agent: agentPda,
+        x402Config: x402ConfigPda,
+        session: sessionPda,
+        escrowTokenAccount: escrowPda,
+        mint: usdcMint,
+        caller: caller.publicKey,
+        callerTokenAccount: callerUsdc,
+      })
+      .signers([caller])
+      .rpc();
+
+  const endTrial = () =>
+    program.methods
+      .endTrial()
+      .accounts({
+        x402Config: x402ConfigPda,
+        session: sessionPda,
+        escrowTokenAccount: escrowPda,
+        callerTokenAccount: callerUsdc,
+        caller: caller.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([caller])
+      .rpc();
+
+  // Permissionless; sent by the provider wallet
+  const forfeitTrial = () =>
+    program.methods
+      .forfeitTrial()
+      .accounts({
+        x402Config: x402ConfigPda,
+        session: sessionPda,
+        escrowTokenAccount: escrowPda,
+        recipientTokenAccount: creatorUsdc,
+        caller: caller.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, caller], 10);
+
+    ({ agentPda, x402ConfigPda } = await createTestAgent(
+      program,
+      creator,
+      "Trial Agent",
+      "TRIAL",
+      "Agent with a free tier"
+    ));
+    [sessionPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("trial_session"), agentPda.toBuffer(), caller.publicKey.toBuffer()],
+      program.programId
+    );
+    [escrowPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("trial_escrow"), sessionPda.toBuffer()],
+      program.programId
+    );
+    // The service timeout is the sessions' length
+    await configureX402(program, agentPda, creator, 1_000_000, 0, SESSION_SECS);
+
+    ({ mint: usdcMint, accounts: [callerUsdc, creatorUsdc] } = await createUsdc(
+      provider.connection,
+      caller,
+      1_000_000,
+      [creator]
+    ));
+  });
+
+  it("Rejects trials until the agent sets a deposit", async () => {
+    try {
+      await openTrial();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("TrialsNotOffered");
+    }
+
+    await program.methods
+      .setTrialDeposit(new anchor.BN(TRIAL_DEPOSIT))
+      .accounts({
+        agent: agentPda,
+        x402Config: x402ConfigPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+      })
+      .signers([creator])
+      .rpc();
+
+    const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
+    expect(x402Config.trialDeposit.toNumber()).to.equal(TRIAL_DEPOSIT);
+  });
+
+  it("Escrows the deposit for the session", async () => {
+    const callerBefore = await tokenBalance(provider.connection, callerUsdc);
+
+    await openTrial();
+
+    expect(await tokenBalance(provider.connection, escrowPda)).to.equal(TRIAL_DEPOSIT);
+    expect(await tokenBalance(provider.connection, callerUsdc)).to.equal(callerBefore - TRIAL_DEPOSIT);
+    const session: any = await program.account.trialSession.fetch(sessionPda);
+    expect(session.caller.toBase58()).to.equal(caller.publicKey.toBase58());
+    expect(session.deposit.toNumber()).to.equal(TRIAL_DEPOSIT);
+    expect(session.expiresAt.sub(session.openedAt).toNumber()).to.equal(SESSION_SECS);
+  });
+
+  it("Keeps the deposit from the provider while the session runs", async () => {
+    try {
+      await forfeitTrial();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("TrialSessionActive");
+    }
+    expect(await tokenBalance(provider.connection, escrowPda)).to.equal(TRIAL_DEPOSIT);
+  });
+
+  it("Refunds the deposit when the caller ends the session in time", async () => {
+    const callerBefore = await tokenBalance(provider.connection, callerUsdc);
+
+    await endTrial();
+
+    expect((await tokenBalance(provider.connection, callerUsdc)) - callerBefore).to.equal(TRIAL_DEPOSIT);
+    expect(await program.account.trialSession.fetchNullable(sessionPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(escrowPda)).to.be.null;
+  });
+
+  it("Forfeits an abandoned session's deposit to the provider", async () => {
+    await openTrial();
+    await sleep((SESSION_SECS + 1) * 1000);
+
+    try {
+      await endTrial();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("TrialSessionExpired");
+    }
+
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+    const callerLamportsBefore = await provider.connection.getBalance(caller.publicKey);
+
+    await forfeitTrial();
+
+    expect((await tokenBalance(provider.connection, creatorUsdc)) - creatorBefore).to.equal(TRIAL_DEPOSIT);
+    expect(await program.account.trialSession.fetchNullable(sessionPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(escrowPda)).to.be.null;
+    // The caller still gets the accounts' rent back
+    expect(await provider.connection.getBalance(caller.publicKey)).to.be.greaterThan(callerLamportsBefore);
+  });
+});
@@ -0,0 +1,277 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createHash } from "crypto";
+import { expect } from "chai";
+import {
+  airdrop,
+  configureX402,
+  createTestAgent,
+  createUsdc,
+  sleep,
+  tokenBalance,
+  ownershipAccount,
+} from "./helpers";
+
+describe("Work queue", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts
+  const creator = Keypair.generate();
+  const requester = Keypair.generate();
+  const workerA = Keypair.generate();
+  const workerB = Keypair.generate();
+
+  let agentPda: PublicKey;
+  let x402ConfigPda: PublicKey;
+  let queuePda: PublicKey;
+  let usdcMint: PublicKey;
+  let requesterUsdc: PublicKey;
+  let creatorUsdc: PublicKey;
+  let taskPda: PublicKey;
+
+  const MAX_PRICE = 500_000;
+  const LEASE_SECS = 3;
+  const paramsHash = Array.from(createHash("sha256").update("params").digest());
+  const resultHash = Array.from(createHash("sha256").update("result").digest());
+
+  const escrowPda = (task: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("task_escrow"), task.toBuffer()],
+      program.programId
+    )[0];
+
+  const setWorkers = (workers: PublicKey[]) =>
+    program.methods
+      .setQueueWorkers(workers)
+      .accounts({
+        agent: agentPda,
+        queue: queuePda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([creator])
+      .rpc();
+
+  // Enqueue a task under the queue's next id; returns the task
+  const enqueue = async () => {
+    const queue: any = await program.account.workQueue.fetch(queuePda);
+    const [task] = PublicKey.findProgramAddressSync(
+      [Buffer.from("work_task"), agentPda.toBuffer(), queue.nextTaskId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .enqueueTask(paramsHash, new anchor.BN(MAX_PRICE))
+      .accounts({
+        agent: agentPda,
+        x402Config: x402ConfigPda,
+        queue: queuePda,
+        task,
+        escrowTokenAccount: escrowPda(task),
+        mint: usdcMint,
+        requester: requester.publicKey,
+        requesterTokenAccount: requesterUsdc,
+      })
+      .signers([requester])
+      .rpc();
+    return task;
+  };
+
+  const claim = (worker: Keypair) =>
+    program.methods
+      .claimTask()
+      .accounts({
+        x402Config: x402ConfigPda,
+        queue: queuePda,
+        task: taskPda,
+        worker: worker.publicKey,
+      })
+      .signers([worker])
+      .rpc();
+
+  const complete = (worker: Keypair, price: number) =>
+    program.methods
+      .completeTask(new anchor.BN(price), resultHash)
+      .accounts({
+        x402Config: x402ConfigPda,
+        queue: queuePda,
+        task: taskPda,
+        escrowTokenAccount: escrowPda(taskPda),
+        recipientTokenAccount: creatorUsdc,
+        requesterTokenAccount: requesterUsdc,
+        requester: requester.publicKey,
+        worker: worker.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        revenueSplit: null,
+      })
+      .signers([worker])
+      .rpc();
+
+  const withdraw = () =>
+    program.methods
+      .withdrawTask()
+      .accounts({
+        x402Config: x402ConfigPda,
+        queue: queuePda,
+        task: taskPda,
+        escrowTokenAccount: escrowPda(taskPda),
+        requesterTokenAccount: requesterUsdc,
+        requester: requester.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([requester])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, requester], 10);
+
+    ({ agentPda, x402ConfigPda } = await createTestAgent(
+      program,
+      creator,
+      "Queue Agent",
+      "QUEUE",
+      "Agent served by workers"
+    ));
+    [queuePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("work_queue"), agentPda.toBuffer()],
+      program.programId
+    );
+    // The service timeout is the workers' claim lease
+    await configureX402(program, agentPda, creator, 1, 0, LEASE_SECS);
+
+    ({ mint: usdcMint, accounts: [requesterUsdc, creatorUsdc] } = await createUsdc(
+      provider.connection,
+      requester,
+      10_000_000,
+      [creator]
+    ));
+  });
+
+  it("Rejects tasks while no worker serves the queue", async () => {
+    await setWorkers([]);
+    try {
+      await enqueue();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("NoQueueWorkers");
+    }
+
+    await setWorkers([workerA.publicKey, workerB.publicKey]);
+    const queue: any = await program.account.workQueue.fetch(queuePda);
+    expect(queue.workers.map((w: PublicKey) => w.toBase58())).to.deep.equal([
+      workerA.publicKey.toBase58(),
+      workerB.publicKey.toBase58(),
+    ]);
+  });
+
+  it("Escrows the max price of an enqueued task", async () => {
+    const requesterBefore = await tokenBalance(provider.connection, requesterUsdc);
+    taskPda = await enqueue();
+
+    expect(await tokenBalance(provider.connection, escrowPda(taskPda))).to.equal(MAX_PRICE);
+    expect(await tokenBalance(provider.connection, requesterUsdc)).to.equal(requesterBefore - MAX_PRICE);
+
+    const task: any = await program.account.workTask.fetch(taskPda);
+    expect(task.taskId.toNumber()).to.equal(0);
+    expect(task.status).to.deep.equal({ open: {} });
+    expect(task.paramsHash).to.deep.equal(paramsHash);
+
+    const queue: any = await program.account.workQueue.fetch(queuePda);
+    expect(queue.nextTaskId.toNumber()).to.equal(1);
+    expect(queue.openTasks).to.equal(1);
+  });
+
+  it("Gives a task to one worker at a time", async () => {
+    try {
+      await claim(creator);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("NotQueueWorker");
+    }
+
+    await claim(workerA);
+    const task: any = await program.account.workTask.fetch(taskPda);
+    expect(task.status).to.deep.equal({ claimed: {} });
+    expect(task.worker.toBase58()).to.equal(workerA.publicKey.toBase58());
+
+    try {
+      await claim(workerB);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("TaskNotClaimable");
+    }
+
+    // Nor can the requester pull it from under the worker
+    try {
+      await withdraw();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("TaskNotClaimable");
+    }
+  });
+
+  it("Charges the price on completion and refunds the rest", async () => {
+    try {
+      await complete(workerB, MAX_PRICE);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("TaskNotClaimed");
+    }
+    try {
+      await complete(workerA, MAX_PRICE + 1);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("InvalidTaskPrice");
+    }
+
+    const price = 300_000;
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+    const requesterBefore = await tokenBalance(provider.connection, requesterUsdc);
+
+    await complete(workerA, price);
+
+    expect((await tokenBalance(provider.connection, creatorUsdc)) - creatorBefore).to.equal(price);
+    expect((await tokenBalance(provider.connection, requesterUsdc)) - requesterBefore).to.equal(MAX_PRICE - price);
+    expect(await program.account.workTask.fetchNullable(taskPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(escrowPda(taskPda))).to.be.null;
+    const queue: any = await program.account.workQueue.fetch(queuePda);
+    expect(queue.openTasks).to.equal(0);
+  });
+
+  it("Hands a stalled task to another worker once the lease ends", async () => {
+    taskPda = await enqueue();
+    await claim(workerA);
+    await sleep((LEASE_SECS + 1) * 1000);
+
+    await claim(workerB);
+
+    const task: any = await program.account.workTask.fetch(taskPda);
+    expect(task.worker.toBase58()).to.equal(workerB.publicKey.toBase58());
+    // The stalled worker lost its claim
+    try {
+      await complete(workerA, MAX_PRICE);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("TaskNotClaimed");
+    }
+  });
+
+  it("Refunds a task nobody is serving", async () => {
+    await sleep((LEASE_SECS + 1) * 1000);
+    const requesterBefore = await tokenBalance(provider.connection, requesterUsdc);
+
+    await withdraw();
+
+    expect((await tokenBalance(provider.connection, requesterUsdc)) - requesterBefore).to.equal(MAX_PRICE);
+    expect(await program.account.workTask.fetchNullable(taskPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(escrowPda(taskPda))).to.be.null;
+    const queue: any = await program.account.workQueue.fetch(queuePda);
+    expect(queue.openTasks).to.equal(0);
+  });
+});
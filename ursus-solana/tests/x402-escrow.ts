@@ -0,0 +1,247 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { createHash } from "crypto";
+import { expect } from "chai";
+import {
+  airdrop,
+  configureX402,
+  createTestAgent,
+  createUsdc,
+  sleep,
+  tokenBalance,
+  ownershipAccount,
+} from "./helpers";
+
+describe("X402 escrowed payments", () => {
+  // Configure the client to use the local cluster
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.AgentFactory as Program<any>;
+
+  // Test accounts
+  const creator = Keypair.generate();
+  const payer = Keypair.generate();
+  const verifier = Keypair.generate();
+
+  let agentPda: PublicKey;
+  let x402ConfigPda: PublicKey;
+  let usdcMint: PublicKey;
+  let payerUsdc: PublicKey;
+  let creatorUsdc: PublicKey;
+
+  const PRICE = 1_000_000; // 1 USDC
+  const SERVICE_TIMEOUT_SECS = 3;
+  const serviceId = Array.from(createHash("sha256").update("market_analysis").digest());
+  const requestId = Array.from({ length: 16 }, (_, i) => i + 1);
+  const attestationHash = Array.from(createHash("sha256").update("output").digest());
+
+  // Escrow a payment at the next nonce; returns its record and escrow
+  const payEscrowed = async () => {
+    const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
+    const nonce = x402Config.nonce.add(new anchor.BN(1));
+    const [paymentRecord] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment_record"),
+        agentPda.toBuffer(),
+        payer.publicKey.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [escrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_escrow"), paymentRecord.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .payForServiceEscrowed(new anchor.BN(PRICE), serviceId, nonce, requestId)
+      .accounts({
+        agent: agentPda,
+        x402Config: x402ConfigPda,
+        paymentRecord,
+        escrowTokenAccount: escrow,
+        mint: usdcMint,
+        payer: payer.publicKey,
+        payerTokenAccount: payerUsdc,
+        factory: null,
+        platformStake: null,
+        consumerStats: null,
+      })
+      .signers([payer])
+      .rpc();
+
+    return { paymentRecord, escrow };
+  };
+
+  const settle = (paymentRecord: PublicKey, escrow: PublicKey, passed: boolean, signer: Keypair) =>
+    program.methods
+      .settleAttestedPayment(passed, attestationHash)
+      .accounts({
+        x402Config: x402ConfigPda,
+        paymentRecord,
+        escrowTokenAccount: escrow,
+        recipientTokenAccount: creatorUsdc,
+        payerTokenAccount: payerUsdc,
+        payer: payer.publicKey,
+        verifier: signer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        revenueSplit: null,
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    await airdrop(provider.connection, [creator, payer], 10);
+
+    ({ agentPda, x402ConfigPda } = await createTestAgent(
+      program,
+      creator,
+      "Escrow Agent",
+      "ESCRW",
+      "Agent paid through escrow"
+    ));
+    await configureX402(program, agentPda, creator, PRICE, 10 * PRICE, SERVICE_TIMEOUT_SECS);
+
+    ({ mint: usdcMint, accounts: [payerUsdc, creatorUsdc] } = await createUsdc(
+      provider.connection,
+      payer,
+      100 * PRICE,
+      [creator]
+    ));
+  });
+
+  it("Rejects escrowed payments until a verifier is set", async () => {
+    try {
+      await payEscrowed();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("AttestationNotEnabled");
+    }
+
+    await program.methods
+      .setX402Verifier(verifier.publicKey)
+      .accounts({
+        agent: agentPda,
+        x402Config: x402ConfigPda,
+        creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+      })
+      .signers([creator])
+      .rpc();
+
+    const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
+    expect(x402Config.attestationVerifier.toBase58()).to.equal(verifier.publicKey.toBase58());
+  });
+
+  it("Holds the payment in escrow and releases it on a passed attestation", async () => {
+    const payerBefore = await tokenBalance(provider.connection, payerUsdc);
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+
+    const { paymentRecord, escrow } = await payEscrowed();
+    expect(await tokenBalance(provider.connection, escrow)).to.equal(PRICE);
+    expect(await tokenBalance(provider.connection, payerUsdc)).to.equal(payerBefore - PRICE);
+    let record: any = await program.account.x402PaymentRecord.fetch(paymentRecord);
+    expect(record.status).to.deep.equal({ pending: {} });
+
+    await settle(paymentRecord, escrow, true, verifier);
+
+    expect(await tokenBalance(provider.connection, creatorUsdc)).to.equal(creatorBefore + PRICE);
+    expect(await provider.connection.getAccountInfo(escrow)).to.be.null;
+    record = await program.account.x402PaymentRecord.fetch(paymentRecord);
+    expect(record.status).to.deep.equal({ settled: {} });
+    expect(record.attestationHash).to.deep.equal(attestationHash);
+    expect(record.refundedAmount.toNumber()).to.equal(0);
+  });
+
+  it("Refunds the payer on a failed attestation", async () => {
+    const payerBefore = await tokenBalance(provider.connection, payerUsdc);
+    const creatorBefore = await tokenBalance(provider.connection, creatorUsdc);
+
+    const { paymentRecord, escrow } = await payEscrowed();
+    await settle(paymentRecord, escrow, false, verifier);
+
+    expect(await tokenBalance(provider.connection, payerUsdc)).to.equal(payerBefore);
+    expect(await tokenBalance(provider.connection, creatorUsdc)).to.equal(creatorBefore);
+    const record: any = await program.account.x402PaymentRecord.fetch(paymentRecord);
+    expect(record.status).to.deep.equal({ failed: {} });
+    expect(record.refundedAmount.toNumber()).to.equal(PRICE);
+  });
+
+  it("Rejects attestations from anyone but the verifier", async () => {
+    const { paymentRecord, escrow } = await payEscrowed();
+    try {
+      await settle(paymentRecord, escrow, true, payer);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("NotAttestationVerifier");
+    }
+    expect(await tokenBalance(provider.connection, escrow)).to.equal(PRICE);
+  });
+
+  it("Refunds an unattested payment once the service timeout passes", async () => {
+    const payerBefore = await tokenBalance(provider.connection, payerUsdc);
+    const { paymentRecord, escrow } = await payEscrowed();
+
+    const refund = () =>
+      program.methods
+        .refundExpiredPayment()
+        .accounts({
+          x402Config: x402ConfigPda,
+          paymentRecord,
+          escrowTokenAccount: escrow,
+          payerTokenAccount: payerUsdc,
+          payer: payer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([payer])
+        .rpc();
+
+    try {
+      await refund();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("PaymentNotExpired");
+    }
+
+    await sleep((SERVICE_TIMEOUT_SECS + 1) * 1000);
+    await refund();
+
+    expect(await tokenBalance(provider.connection, payerUsdc)).to.equal(payerBefore);
+    expect(await provider.connection.getAccountInfo(escrow)).to.be.null;
+    const record: any = await program.account.x402PaymentRecord.fetch(paymentRecord);
+    expect(record.status).to.deep.equal({ failed: {} });
+    expect(record.refundedAmount.toNumber()).to.equal(PRICE);
+
+    // A refunded payment can no longer be attested
+    try {
+      await settle(paymentRecord, escrow, true, verifier);
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("PaymentNotPending");
+    }
+  });
+
+  it("Only lets the ownership NFT holder update the x402 settings", async () => {
+    try {
+      await program.methods
+        .updateX402(false, new anchor.BN(PRICE), new anchor.BN(10 * PRICE), new anchor.BN(SERVICE_TIMEOUT_SECS))
+        .accounts({
+          agent: agentPda,
+          x402Config: x402ConfigPda,
+          authority: payer.publicKey,
+          creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
+        })
+        .signers([payer])
+        .rpc();
+      expect.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error?.errorCode?.code).to.equal("NotOwnershipHolder");
+    }
+
+    const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
+    expect(x402Config.enabled).to.be.true;
+  });
+});
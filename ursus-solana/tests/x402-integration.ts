@@ -2,6 +2,8 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program, AnchorProvider } from "@coral-xyz/anchor";
 import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { expect } from "chai";
+import { createHash } from "crypto";
+import { nameReservationPda, ownershipAccount } from "./helpers";
 
 describe("X402 Payment Protocol Integration", () => {
   // Configure the client to use the local cluster
@@ -26,6 +28,9 @@ describe("X402 Payment Protocol Integration", () => {
   const MIN_PAYMENT = new anchor.BN(0.01 * LAMPORTS_PER_SOL);
   const MAX_PAYMENT = new anchor.BN(1 * LAMPORTS_PER_SOL);
   const SERVICE_TIMEOUT = new anchor.BN(300); // 5 minutes
+  const requestId = Array.from({ length: 16 }, (_, i) => i + 1);
+  const serviceIdHash = (name: string) =>
+    Array.from(createHash("sha256").update(name.trim().toLowerCase()).digest());
   
   before(async () => {
     // Airdrop SOL to test accounts
@@ -84,7 +89,7 @@ describe("X402 Payment Protocol Integration", () => {
         "AI agent for market analysis",
         "Analyze crypto market trends and provide insights",
         "GPT-4",
-        "Trading"
+        ["trading"]
       )
       .accounts({
         factory: factoryPda,
@@ -92,6 +97,8 @@ describe("X402 Payment Protocol Integration", () => {
         mint: mintPda,
         creator: creator.publicKey,
         platformTreasury: platformTreasury.publicKey,
+        nameReservation: nameReservationPda(program.programId, "Market Analyzer"),
+        symbolReservation: nameReservationPda(program.programId, "MKTAI"),
       })
       .signers([creator])
       .rpc();
@@ -118,6 +125,7 @@ describe("X402 Payment Protocol Integration", () => {
         agent: agentPda,
         x402Config: x402ConfigPda,
         creator: creator.publicKey,
+        creatorOwnershipAccount: ownershipAccount(program.programId, agentPda, creator.publicKey),
         systemProgram: SystemProgram.programId,
       })
       .signers([creator])
@@ -134,7 +142,7 @@ describe("X402 Payment Protocol Integration", () => {
     const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
     const nonce = x402Config.nonce.add(new anchor.BN(1));
     const paymentAmount = new anchor.BN(0.05 * LAMPORTS_PER_SOL);
-    const serviceId = "market_analysis";
+    const serviceId = serviceIdHash("market_analysis");
 
     [paymentRecordPda] = PublicKey.findProgramAddressSync(
       [
@@ -152,7 +160,9 @@ describe("X402 Payment Protocol Integration", () => {
       .payForService(
         paymentAmount,
         serviceId,
-        nonce
+        nonce,
+        requestId,
+        new anchor.BN(0)
       )
       .accounts({
         agent: agentPda,
@@ -174,7 +184,7 @@ describe("X402 Payment Protocol Integration", () => {
 
     // Verify payment record
     expect(paymentRecord.amount.toString()).to.equal(paymentAmount.toString());
-    expect(paymentRecord.serviceId).to.equal(serviceId);
+    expect(paymentRecord.serviceId).to.deep.equal(serviceId);
     expect(paymentRecord.payer.toString()).to.equal(payer.publicKey.toString());
 
     // Verify config was updated
@@ -187,7 +197,7 @@ describe("X402 Payment Protocol Integration", () => {
     const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
     const oldNonce = x402Config.nonce; // Using old nonce
     const paymentAmount = new anchor.BN(0.05 * LAMPORTS_PER_SOL);
-    const serviceId = "market_analysis";
+    const serviceId = serviceIdHash("market_analysis");
     
     [paymentRecordPda] = PublicKey.findProgramAddressSync(
       [
@@ -204,7 +214,9 @@ describe("X402 Payment Protocol Integration", () => {
         .payForService(
           paymentAmount,
           serviceId,
-          oldNonce
+          oldNonce,
+          requestId,
+          new anchor.BN(0)
         )
         .accounts({
           agent: agentPda,
@@ -227,7 +239,7 @@ describe("X402 Payment Protocol Integration", () => {
     const x402Config: any = await program.account.x402Config.fetch(x402ConfigPda);
     const nonce = x402Config.nonce.add(new anchor.BN(1));
     const paymentAmount = new anchor.BN(0.001 * LAMPORTS_PER_SOL); // Below minimum
-    const serviceId = "market_analysis";
+    const serviceId = serviceIdHash("market_analysis");
     
     [paymentRecordPda] = PublicKey.findProgramAddressSync(
       [
@@ -244,7 +256,9 @@ describe("X402 Payment Protocol Integration", () => {
         .payForService(
           paymentAmount,
           serviceId,
-          nonce
+          nonce,
+          requestId,
+          new anchor.BN(0)
         )
         .accounts({
           agent: agentPda,